@@ -11,13 +11,14 @@ use better_any::{Tid, TidAble};
 use move_binary_format::errors::{PartialVMError, PartialVMResult};
 use move_core_types::{
     account_address::AccountAddress,
-    effects::Op,
+    effects::{self, Op},
     gas_algebra::{InternalGas, InternalGasPerByte, NumBytes},
     language_storage::TypeTag,
     value::MoveTypeLayout,
     vm_status::StatusCode,
 };
 use move_vm_runtime::{
+    native_extensions::SavepointExtension,
     native_functions,
     native_functions::{NativeContext, NativeFunction, NativeFunctionTable},
 };
@@ -135,6 +136,13 @@ struct TableData {
     new_tables: BTreeMap<TableHandle, TableInfo>,
     removed_tables: BTreeSet<TableHandle>,
     tables: BTreeMap<TableHandle, Table>,
+    savepoints: Vec<TableSavepoint>,
+}
+
+/// The tables created and removed when a savepoint was created, restored when rolling back to it.
+struct TableSavepoint {
+    new_tables: BTreeMap<TableHandle, TableInfo>,
+    removed_tables: BTreeSet<TableHandle>,
 }
 
 /// A structure representing a single table.
@@ -143,6 +151,9 @@ struct Table {
     key_layout: MoveTypeLayout,
     value_layout: MoveTypeLayout,
     content: BTreeMap<Vec<u8>, GlobalValue>,
+    /// The changes flushed from `content` when creating savepoints, one for each savepoint and
+    /// the last one since the latest savepoint.
+    changes: Vec<BTreeMap<Vec<u8>, Op<Vec<u8>>>>,
 }
 
 /// The field index of the `handle` field in the `Table` Move struct.
@@ -169,34 +180,14 @@ impl<'a> NativeTableContext<'a> {
             new_tables,
             removed_tables,
             tables,
+            ..
         } = table_data.into_inner();
         let mut changes = BTreeMap::new();
-        for (handle, table) in tables {
-            let Table {
-                value_layout,
-                content,
-                ..
-            } = table;
+        for (handle, mut table) in tables {
+            table.flush()?;
             let mut entries = BTreeMap::new();
-            for (key, gv) in content {
-                let op = match gv.into_effect() {
-                    Some(op) => op,
-                    None => continue,
-                };
-
-                match op {
-                    Op::New(val) => {
-                        let bytes = serialize(&value_layout, &val)?;
-                        entries.insert(key, Op::New(bytes));
-                    }
-                    Op::Modify(val) => {
-                        let bytes = serialize(&value_layout, &val)?;
-                        entries.insert(key, Op::Modify(bytes));
-                    }
-                    Op::Delete => {
-                        entries.insert(key, Op::Delete);
-                    }
-                }
+            for table_changes in table.changes {
+                squash(&mut entries, table_changes)?;
             }
             if !entries.is_empty() {
                 changes.insert(handle, TableChange { entries });
@@ -210,6 +201,55 @@ impl<'a> NativeTableContext<'a> {
     }
 }
 
+impl<'a> SavepointExtension for NativeTableContext<'a> {
+    fn savepoint(&mut self) -> PartialVMResult<()> {
+        let table_data = self.table_data.get_mut();
+        for table in table_data.tables.values_mut() {
+            table.flush()?;
+            table.changes.push(BTreeMap::new());
+        }
+        table_data.savepoints.push(TableSavepoint {
+            new_tables: table_data.new_tables.clone(),
+            removed_tables: table_data.removed_tables.clone(),
+        });
+        Ok(())
+    }
+
+    fn rollback_to_savepoint(&mut self) -> PartialVMResult<()> {
+        let table_data = self.table_data.get_mut();
+        let savepoint = table_data
+            .savepoints
+            .pop()
+            .ok_or_else(|| partial_extension_error("no savepoint to roll back to"))?;
+        // the handles of the tables created since the savepoint are created again after it
+        for handle in table_data.new_tables.keys() {
+            if !savepoint.new_tables.contains_key(handle) {
+                table_data.tables.remove(handle);
+            }
+        }
+        for table in table_data.tables.values_mut() {
+            table.content.clear();
+            table.changes.pop();
+        }
+        table_data.new_tables = savepoint.new_tables;
+        table_data.removed_tables = savepoint.removed_tables;
+        Ok(())
+    }
+
+    fn commit_savepoint(&mut self) -> PartialVMResult<()> {
+        let table_data = self.table_data.get_mut();
+        table_data
+            .savepoints
+            .pop()
+            .ok_or_else(|| partial_extension_error("no savepoint to commit"))?;
+        for table in table_data.tables.values_mut() {
+            let changes = table.changes.pop().unwrap();
+            squash(table.changes.last_mut().unwrap(), changes)?;
+        }
+        Ok(())
+    }
+}
+
 impl TableData {
     /// Gets or creates a new table in the TableData. This initializes information about
     /// the table, like the type layout for keys and values.
@@ -229,6 +269,7 @@ impl TableData {
                     key_layout,
                     value_layout,
                     content: Default::default(),
+                    changes: vec![BTreeMap::new(); self.savepoints.len() + 1],
                 };
                 e.insert(table)
             }
@@ -238,6 +279,33 @@ impl TableData {
 }
 
 impl Table {
+    /// Moves the changes of the values loaded in `content` to the changes since the latest
+    /// savepoint.
+    fn flush(&mut self) -> PartialVMResult<()> {
+        let mut entries = BTreeMap::new();
+        for (key, gv) in std::mem::take(&mut self.content) {
+            let op = match gv.into_effect() {
+                Some(op) => op,
+                None => continue,
+            };
+
+            match op {
+                Op::New(val) => {
+                    let bytes = serialize(&self.value_layout, &val)?;
+                    entries.insert(key, Op::New(bytes));
+                }
+                Op::Modify(val) => {
+                    let bytes = serialize(&self.value_layout, &val)?;
+                    entries.insert(key, Op::Modify(bytes));
+                }
+                Op::Delete => {
+                    entries.insert(key, Op::Delete);
+                }
+            }
+        }
+        squash(self.changes.last_mut().unwrap(), entries)
+    }
+
     fn get_or_create_global_value(
         &mut self,
         context: &NativeTableContext,
@@ -245,6 +313,21 @@ impl Table {
     ) -> PartialVMResult<(&mut GlobalValue, Option<Option<NumBytes>>)> {
         Ok(match self.content.entry(key) {
             Entry::Vacant(entry) => {
+                // entries changed before a savepoint are not resolved again
+                let flushed = self
+                    .changes
+                    .iter()
+                    .rev()
+                    .find_map(|changes| changes.get(entry.key()));
+                if let Some(op) = flushed {
+                    let gv = match op {
+                        Op::New(val_bytes) | Op::Modify(val_bytes) => {
+                            GlobalValue::cached(deserialize(&self.value_layout, val_bytes)?)?
+                        }
+                        Op::Delete => GlobalValue::none(),
+                    };
+                    return Ok((entry.insert(gv), None));
+                }
                 let (gv, loaded) = match context
                     .resolver
                     .resolve_table_entry(&self.handle, entry.key())
@@ -704,6 +787,14 @@ fn deserialize(layout: &MoveTypeLayout, bytes: &[u8]) -> PartialVMResult<Value>
         .ok_or_else(|| partial_extension_error("cannot deserialize table key or value"))
 }
 
+fn squash(
+    entries: &mut BTreeMap<Vec<u8>, Op<Vec<u8>>>,
+    other: BTreeMap<Vec<u8>, Op<Vec<u8>>>,
+) -> PartialVMResult<()> {
+    effects::squash(entries, other)
+        .map_err(|err| partial_extension_error(format!("cannot squash table changes: {}", err)))
+}
+
 fn partial_extension_error(msg: impl ToString) -> PartialVMError {
     PartialVMError::new(StatusCode::VM_EXTENSION_ERROR).with_message(msg.to_string())
 }
@@ -21,6 +21,7 @@ use std::{
 use move_analyzer::{
     completion::on_completion_request,
     context::Context,
+    formatting::on_formatting_request,
     symbols,
     vfs::{on_text_document_sync_notification, VirtualFileSystem},
 };
@@ -107,6 +108,8 @@ fn main() {
         )),
         references_provider: Some(OneOf::Left(symbols::DEFS_AND_REFS_SUPPORT)),
         document_symbol_provider: Some(OneOf::Left(true)),
+        // The server formats whole documents, with the same formatter as `move fmt`.
+        document_formatting_provider: Some(OneOf::Left(true)),
         ..Default::default()
     })
     .expect("could not serialize server capabilities");
@@ -241,6 +244,9 @@ fn on_request(context: &Context, request: &Request) {
         lsp_types::request::DocumentSymbolRequest::METHOD => {
             symbols::on_document_symbol_request(context, request, &context.symbols.lock().unwrap());
         }
+        lsp_types::request::Formatting::METHOD => {
+            on_formatting_request(context, request);
+        }
         _ => eprintln!("handle request '{}' from client", request.method),
     }
 }
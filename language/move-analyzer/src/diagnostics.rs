@@ -3,8 +3,14 @@
 
 use crate::utils::get_loc;
 use codespan_reporting::{diagnostic::Severity, files::SimpleFiles};
-use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Range};
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag, Location, Range,
+};
 use move_command_line_common::files::FileHash;
+use move_compiler::diagnostics::{
+    codes::{Deprecation, DiagnosticCode},
+    Diagnostics,
+};
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 use std::collections::{BTreeMap, HashMap};
@@ -12,19 +18,16 @@ use url::Url;
 
 /// Converts diagnostics from the codespan format to the format understood by the language server.
 pub fn lsp_diagnostics(
-    diagnostics: &Vec<(
-        codespan_reporting::diagnostic::Severity,
-        &'static str,
-        (Loc, String),
-        Vec<(Loc, String)>,
-        Vec<String>,
-    )>,
+    diagnostics: Diagnostics,
     files: &SimpleFiles<Symbol, String>,
     file_id_mapping: &HashMap<FileHash, usize>,
     file_name_mapping: &BTreeMap<FileHash, Symbol>,
 ) -> BTreeMap<Symbol, Vec<Diagnostic>> {
     let mut lsp_diagnostics = BTreeMap::new();
-    for (s, _, (loc, msg), labels, _) in diagnostics {
+    let deprecation_info = Deprecation::Usage.into_info();
+    for diag in diagnostics.into_vec() {
+        let is_deprecation = *diag.info() == deprecation_info;
+        let (s, _, (loc, msg), labels, _) = diag.into_codespan_format();
         let fpath = file_name_mapping.get(&loc.file_hash()).unwrap();
         if let Some(start) = get_loc(&loc.file_hash(), loc.start(), files, file_id_mapping) {
             if let Some(end) = get_loc(&loc.file_hash(), loc.end(), files, file_id_mapping) {
@@ -57,17 +60,22 @@ pub fn lsp_diagnostics(
                             .collect(),
                     )
                 };
+                let tags_opt = if is_deprecation {
+                    Some(vec![DiagnosticTag::DEPRECATED])
+                } else {
+                    None
+                };
                 lsp_diagnostics
                     .entry(*fpath)
                     .or_insert_with(Vec::new)
                     .push(Diagnostic::new(
                         range,
-                        Some(severity(*s)),
+                        Some(severity(s)),
                         None,
                         None,
                         msg.to_string(),
                         related_info_opt,
-                        None,
+                        tags_opt,
                     ));
             }
         }
@@ -0,0 +1,81 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Document formatting, using the same formatter and per-package configuration as `move fmt`.
+
+use crate::{context::Context, symbols::SymbolicatorRunner};
+use lsp_server::Request;
+use lsp_types::{DocumentFormattingParams, Position, Range, TextEdit};
+use move_formatter::FormatConfig;
+use std::path::Path;
+
+/// Sends the given connection a response to a formatting request. The response replaces the
+/// whole document with its formatted text, or is empty if the document is already formatted or
+/// cannot be formatted.
+pub fn on_formatting_request(context: &Context, request: &Request) {
+    eprintln!("handling formatting request");
+    let parameters = serde_json::from_value::<DocumentFormattingParams>(request.params.clone())
+        .expect("could not deserialize formatting request");
+
+    let path = parameters.text_document.uri.to_file_path().unwrap();
+    let edits = match context.files.get(&path) {
+        Some(buffer) => formatting_edits(&path, buffer),
+        None => {
+            eprintln!(
+                "Could not read '{:?}' when handling formatting request",
+                path
+            );
+            vec![]
+        }
+    };
+
+    let result = serde_json::to_value(edits).expect("could not serialize formatting response");
+    let response = lsp_server::Response::new_ok(request.id.clone(), result);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send formatting response: {:?}", err);
+    }
+}
+
+fn formatting_edits(path: &Path, buffer: &str) -> Vec<TextEdit> {
+    // Files outside of a package are formatted with the default configuration
+    let config = match path.parent().and_then(SymbolicatorRunner::root_dir) {
+        Some(root) => match FormatConfig::load(&root) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("{:#}", err);
+                return vec![];
+            }
+        },
+        None => FormatConfig::default(),
+    };
+    let formatted = match move_formatter::format(buffer, &config) {
+        Ok(formatted) => formatted,
+        Err(_) => {
+            eprintln!("could not format '{:?}', which does not lex", path);
+            return vec![];
+        }
+    };
+    if formatted == buffer {
+        return vec![];
+    }
+
+    let last_line = buffer.rsplit('\n').next().unwrap_or_default();
+    let end = Position {
+        line: buffer.matches('\n').count() as u32,
+        character: last_line.encode_utf16().count() as u32,
+    };
+    vec![TextEdit {
+        range: Range {
+            start: Position {
+                line: 0,
+                character: 0,
+            },
+            end,
+        },
+        new_text: formatted,
+    }]
+}
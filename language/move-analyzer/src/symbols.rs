@@ -82,7 +82,7 @@ use move_compiler::{
     shared::Identifier,
     typing::ast::{
         BuiltinFunction_, Exp, ExpListItem, Function, FunctionBody_, LValue, LValueList, LValue_,
-        ModuleCall, ModuleDefinition, SequenceItem, SequenceItem_, UnannotatedExp_,
+        MatchPattern_, ModuleCall, ModuleDefinition, SequenceItem, SequenceItem_, UnannotatedExp_,
     },
     PASS_TYPING,
 };
@@ -342,6 +342,11 @@ fn type_to_ide_string(sp!(_, t): &Type) -> String {
                 )
             }
         },
+        Type_::Fun(args, result) => format!(
+            "|{}| {}",
+            type_list_to_ide_string(args),
+            type_to_ide_string(result)
+        ),
         Type_::Anything => "_".to_string(),
         Type_::Var(_) => "invalid type (var)".to_string(),
         Type_::UnresolvedError => "invalid type (unresolved)".to_string(),
@@ -685,7 +690,7 @@ impl Symbolicator {
         let mut ide_diagnostics = lsp_empty_diagnostics(&file_name_mapping);
         if let Some((compiler_diagnostics, failure)) = diagnostics {
             let lsp_diagnostics = lsp_diagnostics(
-                &compiler_diagnostics.into_codespan_format(),
+                compiler_diagnostics,
                 &files,
                 &file_id_mapping,
                 &file_name_mapping,
@@ -1398,16 +1403,32 @@ impl Symbolicator {
                 self.add_type_id_use_def(t, references, use_defs);
                 self.exp_symbols(exp, scope, references, use_defs);
             }
+            E::VarCall(v, args) => {
+                let arg_tys = match &args.ty.value {
+                    Type_::Unit => vec![],
+                    Type_::Apply(_, sp!(_, TypeName_::Multiple(_)), tys) => tys.clone(),
+                    _ => vec![args.ty.clone()],
+                };
+                let fun_ty = sp(v.loc(), Type_::Fun(arg_tys, Box::new(exp.ty.clone())));
+                self.add_local_use_def(&v.value(), &v.loc(), references, scope, use_defs, fun_ty);
+                self.exp_symbols(args, scope, references, use_defs);
+            }
+            E::Lambda(lvalues, _, body) => {
+                // a lambda is a new var scope
+                let mut new_scope = scope.clone();
+                self.lvalue_list_symbols(true, lvalues, &mut new_scope, references, use_defs);
+                self.exp_symbols(body, &mut new_scope, references, use_defs);
+            }
             E::IfElse(cond, t, f) => {
                 self.exp_symbols(cond, scope, references, use_defs);
                 self.exp_symbols(t, scope, references, use_defs);
                 self.exp_symbols(f, scope, references, use_defs);
             }
-            E::While(cond, body) => {
+            E::While(_, cond, body) => {
                 self.exp_symbols(cond, scope, references, use_defs);
                 self.exp_symbols(body, scope, references, use_defs);
             }
-            E::Loop { has_break: _, body } => {
+            E::Loop { body, .. } => {
                 self.exp_symbols(body, scope, references, use_defs);
             }
             E::Block(sequence) => {
@@ -1450,6 +1471,22 @@ impl Symbolicator {
             E::Pack(ident, name, tparams, fields) => {
                 self.pack_symbols(ident, name, tparams, fields, scope, references, use_defs);
             }
+            E::PackVariant(ident, name, _, tparams, fields) => {
+                self.pack_symbols(ident, name, tparams, fields, scope, references, use_defs);
+            }
+            E::Match(subject, arms) => {
+                self.exp_symbols(subject, scope, references, use_defs);
+                for sp!(_, (pattern, rhs)) in arms {
+                    // each arm is a new var scope
+                    let mut new_scope = scope.clone();
+                    if let MatchPattern_::Variant(_, _, _, _, fields) = &pattern.value {
+                        for (_, _, (_, (_, lval))) in fields {
+                            self.lvalue_symbols(true, lval, &mut new_scope, references, use_defs);
+                        }
+                    }
+                    self.exp_symbols(rhs, &mut new_scope, references, use_defs);
+                }
+            }
             E::ExpList(list_items) => {
                 for item in list_items {
                     let exp = match item {
@@ -78,6 +78,32 @@ pub trait ModuleAccess: Sync {
         &self.as_module().field_instantiations[idx.into_index()]
     }
 
+    fn struct_variant_handle_at(&self, idx: StructVariantHandleIndex) -> &StructVariantHandle {
+        let handle = &self.as_module().struct_variant_handles[idx.into_index()];
+        debug_assert!(handle.struct_index.into_index() < self.as_module().struct_defs.len()); // invariant
+        handle
+    }
+
+    fn struct_variant_instantiation_at(
+        &self,
+        idx: StructVariantInstantiationIndex,
+    ) -> &StructVariantInstantiation {
+        &self.as_module().struct_variant_instantiations[idx.into_index()]
+    }
+
+    fn variant_field_handle_at(&self, idx: VariantFieldHandleIndex) -> &VariantFieldHandle {
+        let handle = &self.as_module().variant_field_handles[idx.into_index()];
+        debug_assert!(handle.owner.into_index() < self.as_module().struct_defs.len()); // invariant
+        handle
+    }
+
+    fn variant_field_instantiation_at(
+        &self,
+        idx: VariantFieldInstantiationIndex,
+    ) -> &VariantFieldInstantiation {
+        &self.as_module().variant_field_instantiations[idx.into_index()]
+    }
+
     fn signature_at(&self, idx: SignatureIndex) -> &Signature {
         &self.as_module().signatures[idx.into_index()]
     }
@@ -136,6 +162,22 @@ pub trait ModuleAccess: Sync {
         &self.as_module().field_instantiations
     }
 
+    fn struct_variant_handles(&self) -> &[StructVariantHandle] {
+        &self.as_module().struct_variant_handles
+    }
+
+    fn struct_variant_instantiations(&self) -> &[StructVariantInstantiation] {
+        &self.as_module().struct_variant_instantiations
+    }
+
+    fn variant_field_handles(&self) -> &[VariantFieldHandle] {
+        &self.as_module().variant_field_handles
+    }
+
+    fn variant_field_instantiations(&self) -> &[VariantFieldInstantiation] {
+        &self.as_module().variant_field_instantiations
+    }
+
     fn signatures(&self) -> &[Signature] {
         &self.as_module().signatures
     }
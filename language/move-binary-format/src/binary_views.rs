@@ -13,7 +13,9 @@ use crate::{
         FunctionInstantiation, FunctionInstantiationIndex, IdentifierIndex, ModuleHandle,
         ModuleHandleIndex, Signature, SignatureIndex, SignatureToken, StructDefInstantiation,
         StructDefInstantiationIndex, StructDefinition, StructDefinitionIndex, StructHandle,
-        StructHandleIndex,
+        StructHandleIndex, StructVariantHandle, StructVariantHandleIndex,
+        StructVariantInstantiation, StructVariantInstantiationIndex, VariantFieldHandle,
+        VariantFieldHandleIndex, VariantFieldInstantiation, VariantFieldInstantiationIndex,
     },
     CompiledModule,
 };
@@ -211,6 +213,82 @@ impl<'a> BinaryIndexedView<'a> {
         }
     }
 
+    pub fn struct_variant_handles(&self) -> Option<&[StructVariantHandle]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.struct_variant_handles()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn struct_variant_handle_at(
+        &self,
+        idx: StructVariantHandleIndex,
+    ) -> PartialVMResult<&StructVariantHandle> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.struct_variant_handle_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
+    pub fn struct_variant_instantiations(&self) -> Option<&[StructVariantInstantiation]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.struct_variant_instantiations()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn struct_variant_instantiation_at(
+        &self,
+        idx: StructVariantInstantiationIndex,
+    ) -> PartialVMResult<&StructVariantInstantiation> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.struct_variant_instantiation_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
+    pub fn variant_field_handles(&self) -> Option<&[VariantFieldHandle]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.variant_field_handles()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn variant_field_handle_at(
+        &self,
+        idx: VariantFieldHandleIndex,
+    ) -> PartialVMResult<&VariantFieldHandle> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.variant_field_handle_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
+    pub fn variant_field_instantiations(&self) -> Option<&[VariantFieldInstantiation]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.variant_field_instantiations()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn variant_field_instantiation_at(
+        &self,
+        idx: VariantFieldInstantiationIndex,
+    ) -> PartialVMResult<&VariantFieldInstantiation> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.variant_field_instantiation_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
     pub fn struct_defs(&self) -> Option<&[StructDefinition]> {
         match self {
             BinaryIndexedView::Module(module) => Some(module.struct_defs()),
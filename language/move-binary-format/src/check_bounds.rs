@@ -12,7 +12,9 @@ use crate::{
         AbilitySet, Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript, Constant,
         FieldHandle, FieldInstantiation, FunctionDefinition, FunctionDefinitionIndex,
         FunctionHandle, FunctionInstantiation, LocalIndex, ModuleHandle, Signature, SignatureToken,
-        StructDefInstantiation, StructDefinition, StructFieldInformation, StructHandle, TableIndex,
+        StructDefInstantiation, StructDefinition, StructFieldInformation, StructHandle,
+        StructVariantHandle, StructVariantInstantiation, TableIndex, VariantFieldHandle,
+        VariantFieldInstantiation,
     },
     internals::ModuleIndex,
     IndexKind,
@@ -90,6 +92,10 @@ impl<'a> BoundsChecker<'a> {
         self.check_function_instantiations()?;
         self.check_field_instantiations()?;
         self.check_struct_defs()?;
+        self.check_struct_variant_handles()?;
+        self.check_struct_variant_instantiations()?;
+        self.check_variant_field_handles()?;
+        self.check_variant_field_instantiations()?;
         self.check_function_defs()
     }
 
@@ -170,6 +176,44 @@ impl<'a> BoundsChecker<'a> {
         Ok(())
     }
 
+    fn check_struct_variant_handles(&self) -> PartialVMResult<()> {
+        for variant_handle in self.view.struct_variant_handles().into_iter().flatten() {
+            self.check_struct_variant_handle(variant_handle)?
+        }
+        Ok(())
+    }
+
+    fn check_struct_variant_instantiations(&self) -> PartialVMResult<()> {
+        for variant_inst in self
+            .view
+            .struct_variant_instantiations()
+            .into_iter()
+            .flatten()
+        {
+            self.check_struct_variant_instantiation(variant_inst)?
+        }
+        Ok(())
+    }
+
+    fn check_variant_field_handles(&self) -> PartialVMResult<()> {
+        for field_handle in self.view.variant_field_handles().into_iter().flatten() {
+            self.check_variant_field_handle(field_handle)?
+        }
+        Ok(())
+    }
+
+    fn check_variant_field_instantiations(&self) -> PartialVMResult<()> {
+        for field_inst in self
+            .view
+            .variant_field_instantiations()
+            .into_iter()
+            .flatten()
+        {
+            self.check_variant_field_instantiation(field_inst)?
+        }
+        Ok(())
+    }
+
     fn check_function_defs(&mut self) -> PartialVMResult<()> {
         let view = self.view;
         for (function_def_idx, function_def) in
@@ -234,7 +278,7 @@ impl<'a> BoundsChecker<'a> {
             .and_then(|d| d.get(field_handle.owner.into_index()))
         {
             let fields_count = match &struct_def.field_information {
-                StructFieldInformation::Native => 0,
+                StructFieldInformation::Native | StructFieldInformation::DeclaredVariants(_) => 0,
                 StructFieldInformation::Declared(fields) => fields.len(),
             };
             if field_handle.field as usize >= fields_count {
@@ -249,6 +293,84 @@ impl<'a> BoundsChecker<'a> {
         Ok(())
     }
 
+    fn check_struct_variant_handle(
+        &self,
+        variant_handle: &StructVariantHandle,
+    ) -> PartialVMResult<()> {
+        check_bounds_impl_opt(&self.view.struct_defs(), variant_handle.struct_index)?;
+        // variant must be in bounds, struct def just checked above must exist
+        if let Some(struct_def) = &self
+            .view
+            .struct_defs()
+            .and_then(|d| d.get(variant_handle.struct_index.into_index()))
+        {
+            let variant_count = match &struct_def.field_information {
+                StructFieldInformation::DeclaredVariants(variants) => variants.len(),
+                StructFieldInformation::Native | StructFieldInformation::Declared(_) => 0,
+            };
+            if variant_handle.variant as usize >= variant_count {
+                return Err(bounds_error(
+                    StatusCode::INDEX_OUT_OF_BOUNDS,
+                    IndexKind::VariantDefinition,
+                    variant_handle.variant,
+                    variant_count,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_struct_variant_instantiation(
+        &self,
+        variant_inst: &StructVariantInstantiation,
+    ) -> PartialVMResult<()> {
+        check_bounds_impl_opt(&self.view.struct_variant_handles(), variant_inst.handle)?;
+        check_bounds_impl(self.view.signatures(), variant_inst.type_parameters)
+    }
+
+    fn check_variant_field_handle(&self, field_handle: &VariantFieldHandle) -> PartialVMResult<()> {
+        check_bounds_impl_opt(&self.view.struct_defs(), field_handle.owner)?;
+        // variant and field offset must be in bounds, struct def just checked above must exist
+        if let Some(struct_def) = &self
+            .view
+            .struct_defs()
+            .and_then(|d| d.get(field_handle.owner.into_index()))
+        {
+            let variants: &[_] = match &struct_def.field_information {
+                StructFieldInformation::DeclaredVariants(variants) => variants,
+                StructFieldInformation::Native | StructFieldInformation::Declared(_) => &[],
+            };
+            let variant = match variants.get(field_handle.variant as usize) {
+                Some(variant) => variant,
+                None => {
+                    return Err(bounds_error(
+                        StatusCode::INDEX_OUT_OF_BOUNDS,
+                        IndexKind::VariantDefinition,
+                        field_handle.variant,
+                        variants.len(),
+                    ))
+                }
+            };
+            if field_handle.field as usize >= variant.fields.len() {
+                return Err(bounds_error(
+                    StatusCode::INDEX_OUT_OF_BOUNDS,
+                    IndexKind::MemberCount,
+                    field_handle.field,
+                    variant.fields.len(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_variant_field_instantiation(
+        &self,
+        field_inst: &VariantFieldInstantiation,
+    ) -> PartialVMResult<()> {
+        check_bounds_impl_opt(&self.view.variant_field_handles(), field_inst.handle)?;
+        check_bounds_impl(self.view.signatures(), field_inst.type_parameters)
+    }
+
     fn check_struct_instantiation(
         &self,
         struct_instantiation: &StructDefInstantiation,
@@ -290,17 +412,30 @@ impl<'a> BoundsChecker<'a> {
     fn check_struct_def(&self, struct_def: &StructDefinition) -> PartialVMResult<()> {
         check_bounds_impl(self.view.struct_handles(), struct_def.struct_handle)?;
         // check signature (type) and type parameter for the field type
-        if let StructFieldInformation::Declared(fields) = &struct_def.field_information {
-            let type_param_count = self
-                .view
-                .struct_handles()
-                .get(struct_def.struct_handle.into_index())
-                .map_or(0, |sh| sh.type_parameters.len());
-            // field signatures are inlined
-            for field in fields {
-                check_bounds_impl(self.view.identifiers(), field.name)?;
-                self.check_type(&field.signature.0)?;
-                self.check_type_parameter(&field.signature.0, type_param_count)?;
+        let type_param_count = self
+            .view
+            .struct_handles()
+            .get(struct_def.struct_handle.into_index())
+            .map_or(0, |sh| sh.type_parameters.len());
+        match &struct_def.field_information {
+            StructFieldInformation::Native => (),
+            StructFieldInformation::Declared(fields) => {
+                // field signatures are inlined
+                for field in fields {
+                    check_bounds_impl(self.view.identifiers(), field.name)?;
+                    self.check_type(&field.signature.0)?;
+                    self.check_type_parameter(&field.signature.0, type_param_count)?;
+                }
+            }
+            StructFieldInformation::DeclaredVariants(variants) => {
+                for variant in variants {
+                    check_bounds_impl(self.view.identifiers(), variant.name)?;
+                    for field in &variant.fields {
+                        check_bounds_impl(self.view.identifiers(), field.name)?;
+                        self.check_type(&field.signature.0)?;
+                        self.check_type_parameter(&field.signature.0, type_param_count)?;
+                    }
+                }
             }
         }
         Ok(())
@@ -477,6 +612,64 @@ impl<'a> BoundsChecker<'a> {
                         }
                     }
                 }
+                PackVariant(idx) | UnpackVariant(idx) | TestVariant(idx) => self
+                    .check_code_unit_bounds_impl_opt(
+                        &self.view.struct_variant_handles(),
+                        *idx,
+                        bytecode_offset,
+                    )?,
+                PackVariantGeneric(idx) | UnpackVariantGeneric(idx) | TestVariantGeneric(idx) => {
+                    self.check_code_unit_bounds_impl_opt(
+                        &self.view.struct_variant_instantiations(),
+                        *idx,
+                        bytecode_offset,
+                    )?;
+                    // check type parameters in variant operations are bound to the function type parameters
+                    if let Some(variant_inst) = self
+                        .view
+                        .struct_variant_instantiations()
+                        .and_then(|s| s.get(idx.into_index()))
+                    {
+                        if let Some(sig) = self
+                            .view
+                            .signatures()
+                            .get(variant_inst.type_parameters.into_index())
+                        {
+                            for ty in &sig.0 {
+                                self.check_type_parameter(ty, type_param_count)?
+                            }
+                        }
+                    }
+                }
+                MutBorrowVariantField(idx) | ImmBorrowVariantField(idx) => self
+                    .check_code_unit_bounds_impl_opt(
+                        &self.view.variant_field_handles(),
+                        *idx,
+                        bytecode_offset,
+                    )?,
+                MutBorrowVariantFieldGeneric(idx) | ImmBorrowVariantFieldGeneric(idx) => {
+                    self.check_code_unit_bounds_impl_opt(
+                        &self.view.variant_field_instantiations(),
+                        *idx,
+                        bytecode_offset,
+                    )?;
+                    // check type parameters in borrow are bound to the function type parameters
+                    if let Some(field_inst) = self
+                        .view
+                        .variant_field_instantiations()
+                        .and_then(|f| f.get(idx.into_index()))
+                    {
+                        if let Some(sig) = self
+                            .view
+                            .signatures()
+                            .get(field_inst.type_parameters.into_index())
+                        {
+                            for ty in &sig.0 {
+                                self.check_type_parameter(ty, type_param_count)?
+                            }
+                        }
+                    }
+                }
                 // Instructions that refer to this code block.
                 BrTrue(offset) | BrFalse(offset) | Branch(offset) => {
                     let offset = *offset as usize;
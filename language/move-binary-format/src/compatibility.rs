@@ -112,6 +112,12 @@ impl Compatibility {
                 // (it's purely informational), but clients presumably do.
                 struct_layout = false
             }
+            if new_struct.variants != old_struct.variants {
+                // Variants changed. As with fields, previously published values may no longer
+                // be readable.
+                // TODO: appending new variants could in principle be allowed.
+                struct_layout = false
+            }
         }
 
         // The modules are considered as compatible function-wise when all the conditions are met:
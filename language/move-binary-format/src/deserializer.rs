@@ -239,6 +239,42 @@ fn load_struct_def_inst_index(
     )?))
 }
 
+fn load_struct_variant_handle_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<StructVariantHandleIndex> {
+    Ok(StructVariantHandleIndex(read_uleb_internal(
+        cursor,
+        STRUCT_VARIANT_HANDLE_INDEX_MAX,
+    )?))
+}
+
+fn load_struct_variant_inst_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<StructVariantInstantiationIndex> {
+    Ok(StructVariantInstantiationIndex(read_uleb_internal(
+        cursor,
+        STRUCT_VARIANT_INST_INDEX_MAX,
+    )?))
+}
+
+fn load_variant_field_handle_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<VariantFieldHandleIndex> {
+    Ok(VariantFieldHandleIndex(read_uleb_internal(
+        cursor,
+        VARIANT_FIELD_HANDLE_INDEX_MAX,
+    )?))
+}
+
+fn load_variant_field_inst_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<VariantFieldInstantiationIndex> {
+    Ok(VariantFieldInstantiationIndex(read_uleb_internal(
+        cursor,
+        VARIANT_FIELD_INST_INDEX_MAX,
+    )?))
+}
+
 fn load_constant_pool_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<ConstantPoolIndex> {
     Ok(ConstantPoolIndex(read_uleb_internal(
         cursor,
@@ -294,6 +330,14 @@ fn load_field_offset(cursor: &mut VersionedCursor) -> BinaryLoaderResult<u16> {
     read_uleb_internal(cursor, FIELD_OFFSET_MAX)
 }
 
+fn load_variant_count(cursor: &mut VersionedCursor) -> BinaryLoaderResult<u64> {
+    read_uleb_internal(cursor, VARIANT_COUNT_MAX)
+}
+
+fn load_variant_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<u16> {
+    read_uleb_internal(cursor, VARIANT_INDEX_MAX)
+}
+
 fn load_table_count(cursor: &mut VersionedCursor) -> BinaryLoaderResult<u8> {
     read_uleb_internal(cursor, TABLE_COUNT_MAX)
 }
@@ -605,6 +649,21 @@ fn build_common_tables(
             | TableType::STRUCT_DEF_INST
             | TableType::FIELD_HANDLE
             | TableType::FIELD_INST => continue,
+            TableType::STRUCT_VARIANT_HANDLES
+            | TableType::STRUCT_VARIANT_INST
+            | TableType::VARIANT_FIELD_HANDLES
+            | TableType::VARIANT_FIELD_INST => {
+                // variant tables do not exist before VERSION_7
+                if binary.version() < VERSION_7 {
+                    return Err(
+                        PartialVMError::new(StatusCode::MALFORMED).with_message(format!(
+                            "Struct variant tables not applicable in bytecode version {}",
+                            binary.version()
+                        )),
+                    );
+                }
+                continue;
+            }
             TableType::FRIEND_DECLS => {
                 // friend declarations do not exist before VERSION_2
                 if binary.version() < VERSION_2 {
@@ -645,6 +704,26 @@ fn build_module_tables(
             TableType::FRIEND_DECLS => {
                 load_module_handles(binary, table, &mut module.friend_decls)?;
             }
+            TableType::STRUCT_VARIANT_HANDLES => {
+                load_struct_variant_handles(binary, table, &mut module.struct_variant_handles)?;
+            }
+            TableType::STRUCT_VARIANT_INST => {
+                load_struct_variant_instantiations(
+                    binary,
+                    table,
+                    &mut module.struct_variant_instantiations,
+                )?;
+            }
+            TableType::VARIANT_FIELD_HANDLES => {
+                load_variant_field_handles(binary, table, &mut module.variant_field_handles)?;
+            }
+            TableType::VARIANT_FIELD_INST => {
+                load_variant_field_instantiations(
+                    binary,
+                    table,
+                    &mut module.variant_field_instantiations,
+                )?;
+            }
             TableType::MODULE_HANDLES
             | TableType::STRUCT_HANDLES
             | TableType::FUNCTION_HANDLES
@@ -685,7 +764,11 @@ fn build_script_tables(
             | TableType::FUNCTION_DEFS
             | TableType::FIELD_INST
             | TableType::FIELD_HANDLE
-            | TableType::FRIEND_DECLS => {
+            | TableType::FRIEND_DECLS
+            | TableType::STRUCT_VARIANT_HANDLES
+            | TableType::STRUCT_VARIANT_INST
+            | TableType::VARIANT_FIELD_HANDLES
+            | TableType::VARIANT_FIELD_INST => {
                 return Err(PartialVMError::new(StatusCode::MALFORMED)
                     .with_message("Bad table in Script".to_string()));
             }
@@ -1241,6 +1324,18 @@ fn load_struct_defs(
                 let fields = load_field_defs(&mut cursor)?;
                 StructFieldInformation::Declared(fields)
             }
+            SerializedNativeStructFlag::DECLARED_VARIANTS => {
+                if cursor.version() < VERSION_7 {
+                    return Err(
+                        PartialVMError::new(StatusCode::MALFORMED).with_message(format!(
+                            "Structs with variants not supported in bytecode version {}",
+                            cursor.version()
+                        )),
+                    );
+                }
+                let variants = load_variant_defs(&mut cursor)?;
+                StructFieldInformation::DeclaredVariants(variants)
+            }
         };
         struct_defs.push(StructDefinition {
             struct_handle,
@@ -1259,6 +1354,17 @@ fn load_field_defs(cursor: &mut VersionedCursor) -> BinaryLoaderResult<Vec<Field
     Ok(fields)
 }
 
+fn load_variant_defs(cursor: &mut VersionedCursor) -> BinaryLoaderResult<Vec<VariantDefinition>> {
+    let mut variants = Vec::new();
+    let variant_count = load_variant_count(cursor)?;
+    for _ in 0..variant_count {
+        let name = load_identifier_index(cursor)?;
+        let fields = load_field_defs(cursor)?;
+        variants.push(VariantDefinition { name, fields });
+    }
+    Ok(variants)
+}
+
 fn load_field_def(cursor: &mut VersionedCursor) -> BinaryLoaderResult<FieldDefinition> {
     let name = load_identifier_index(cursor)?;
     let signature = load_signature_token(cursor)?;
@@ -1328,6 +1434,84 @@ fn load_field_instantiations(
     Ok(())
 }
 
+fn load_struct_variant_handles(
+    binary: &VersionedBinary,
+    table: &Table,
+    variant_handles: &mut Vec<StructVariantHandle>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let struct_index = load_struct_def_index(&mut cursor)?;
+        let variant = load_variant_index(&mut cursor)?;
+        variant_handles.push(StructVariantHandle {
+            struct_index,
+            variant,
+        });
+    }
+    Ok(())
+}
+
+fn load_struct_variant_instantiations(
+    binary: &VersionedBinary,
+    table: &Table,
+    variant_insts: &mut Vec<StructVariantInstantiation>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let handle = load_struct_variant_handle_index(&mut cursor)?;
+        let type_parameters = load_signature_index(&mut cursor)?;
+        variant_insts.push(StructVariantInstantiation {
+            handle,
+            type_parameters,
+        });
+    }
+    Ok(())
+}
+
+fn load_variant_field_handles(
+    binary: &VersionedBinary,
+    table: &Table,
+    field_handles: &mut Vec<VariantFieldHandle>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let owner = load_struct_def_index(&mut cursor)?;
+        let variant = load_variant_index(&mut cursor)?;
+        let field = load_field_offset(&mut cursor)?;
+        field_handles.push(VariantFieldHandle {
+            owner,
+            variant,
+            field,
+        });
+    }
+    Ok(())
+}
+
+fn load_variant_field_instantiations(
+    binary: &VersionedBinary,
+    table: &Table,
+    field_insts: &mut Vec<VariantFieldInstantiation>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let handle = load_variant_field_handle_index(&mut cursor)?;
+        let type_parameters = load_signature_index(&mut cursor)?;
+        field_insts.push(VariantFieldInstantiation {
+            handle,
+            type_parameters,
+        });
+    }
+    Ok(())
+}
+
 /// Deserializes a `FunctionDefinition`.
 fn load_function_def(cursor: &mut VersionedCursor) -> BinaryLoaderResult<FunctionDefinition> {
     let function = load_function_handle_index(cursor)?;
@@ -1475,6 +1659,25 @@ fn load_code(cursor: &mut VersionedCursor, code: &mut Vec<Bytecode>) -> BinaryLo
                     )),
                 );
             }
+            Opcodes::PACK_VARIANT
+            | Opcodes::PACK_VARIANT_GENERIC
+            | Opcodes::UNPACK_VARIANT
+            | Opcodes::UNPACK_VARIANT_GENERIC
+            | Opcodes::TEST_VARIANT
+            | Opcodes::TEST_VARIANT_GENERIC
+            | Opcodes::MUT_BORROW_VARIANT_FIELD
+            | Opcodes::MUT_BORROW_VARIANT_FIELD_GENERIC
+            | Opcodes::IMM_BORROW_VARIANT_FIELD
+            | Opcodes::IMM_BORROW_VARIANT_FIELD_GENERIC
+                if (cursor.version() < VERSION_7) =>
+            {
+                return Err(
+                    PartialVMError::new(StatusCode::MALFORMED).with_message(format!(
+                        "Struct variant operations not supported in bytecode version {}",
+                        cursor.version()
+                    )),
+                );
+            }
             _ => (),
         };
 
@@ -1594,6 +1797,36 @@ fn load_code(cursor: &mut VersionedCursor, code: &mut Vec<Bytecode>) -> BinaryLo
             Opcodes::CAST_U16 => Bytecode::CastU16,
             Opcodes::CAST_U32 => Bytecode::CastU32,
             Opcodes::CAST_U256 => Bytecode::CastU256,
+            Opcodes::PACK_VARIANT => {
+                Bytecode::PackVariant(load_struct_variant_handle_index(cursor)?)
+            }
+            Opcodes::PACK_VARIANT_GENERIC => {
+                Bytecode::PackVariantGeneric(load_struct_variant_inst_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT => {
+                Bytecode::UnpackVariant(load_struct_variant_handle_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT_GENERIC => {
+                Bytecode::UnpackVariantGeneric(load_struct_variant_inst_index(cursor)?)
+            }
+            Opcodes::TEST_VARIANT => {
+                Bytecode::TestVariant(load_struct_variant_handle_index(cursor)?)
+            }
+            Opcodes::TEST_VARIANT_GENERIC => {
+                Bytecode::TestVariantGeneric(load_struct_variant_inst_index(cursor)?)
+            }
+            Opcodes::MUT_BORROW_VARIANT_FIELD => {
+                Bytecode::MutBorrowVariantField(load_variant_field_handle_index(cursor)?)
+            }
+            Opcodes::MUT_BORROW_VARIANT_FIELD_GENERIC => {
+                Bytecode::MutBorrowVariantFieldGeneric(load_variant_field_inst_index(cursor)?)
+            }
+            Opcodes::IMM_BORROW_VARIANT_FIELD => {
+                Bytecode::ImmBorrowVariantField(load_variant_field_handle_index(cursor)?)
+            }
+            Opcodes::IMM_BORROW_VARIANT_FIELD_GENERIC => {
+                Bytecode::ImmBorrowVariantFieldGeneric(load_variant_field_inst_index(cursor)?)
+            }
         };
         code.push(bytecode);
     }
@@ -1618,6 +1851,10 @@ impl TableType {
             0xE => Ok(TableType::FIELD_INST),
             0xF => Ok(TableType::FRIEND_DECLS),
             0x10 => Ok(TableType::METADATA),
+            0x11 => Ok(TableType::STRUCT_VARIANT_HANDLES),
+            0x12 => Ok(TableType::STRUCT_VARIANT_INST),
+            0x13 => Ok(TableType::VARIANT_FIELD_HANDLES),
+            0x14 => Ok(TableType::VARIANT_FIELD_INST),
             _ => Err(PartialVMError::new(StatusCode::UNKNOWN_TABLE_TYPE)),
         }
     }
@@ -1689,6 +1926,7 @@ impl SerializedNativeStructFlag {
         match value {
             0x1 => Ok(SerializedNativeStructFlag::NATIVE),
             0x2 => Ok(SerializedNativeStructFlag::DECLARED),
+            0x3 => Ok(SerializedNativeStructFlag::DECLARED_VARIANTS),
             _ => Err(PartialVMError::new(StatusCode::UNKNOWN_NATIVE_STRUCT_FLAG)),
         }
     }
@@ -1774,6 +2012,16 @@ impl Opcodes {
             0x4B => Ok(Opcodes::CAST_U16),
             0x4C => Ok(Opcodes::CAST_U32),
             0x4D => Ok(Opcodes::CAST_U256),
+            0x4E => Ok(Opcodes::PACK_VARIANT),
+            0x4F => Ok(Opcodes::PACK_VARIANT_GENERIC),
+            0x50 => Ok(Opcodes::UNPACK_VARIANT),
+            0x51 => Ok(Opcodes::UNPACK_VARIANT_GENERIC),
+            0x52 => Ok(Opcodes::TEST_VARIANT),
+            0x53 => Ok(Opcodes::TEST_VARIANT_GENERIC),
+            0x54 => Ok(Opcodes::MUT_BORROW_VARIANT_FIELD),
+            0x55 => Ok(Opcodes::MUT_BORROW_VARIANT_FIELD_GENERIC),
+            0x56 => Ok(Opcodes::IMM_BORROW_VARIANT_FIELD),
+            0x57 => Ok(Opcodes::IMM_BORROW_VARIANT_FIELD_GENERIC),
             _ => Err(PartialVMError::new(StatusCode::UNKNOWN_OPCODE)),
         }
     }
@@ -159,6 +159,26 @@ define_index! {
     kind: FunctionDefinition,
     doc: "Index into the `FunctionDefinition` table.",
 }
+define_index! {
+    name: StructVariantHandleIndex,
+    kind: StructVariantHandle,
+    doc: "Index into the `StructVariantHandle` table.",
+}
+define_index! {
+    name: StructVariantInstantiationIndex,
+    kind: StructVariantInstantiation,
+    doc: "Index into the `StructVariantInstantiation` table.",
+}
+define_index! {
+    name: VariantFieldHandleIndex,
+    kind: VariantFieldHandle,
+    doc: "Index into the `VariantFieldHandle` table.",
+}
+define_index! {
+    name: VariantFieldInstantiationIndex,
+    kind: VariantFieldInstantiation,
+    doc: "Index into the `VariantFieldInstantiation` table.",
+}
 
 /// Index of a local variable in a function.
 ///
@@ -166,6 +186,8 @@ define_index! {
 pub type LocalIndex = u8;
 /// Max number of fields in a `StructDefinition`.
 pub type MemberCount = u16;
+/// Index of a variant in a `StructDefinition` declared with variants (an enum).
+pub type VariantIndex = u16;
 /// Index into the code stream for a jump. The offset is relative to the beginning of
 /// the instruction stream.
 pub type CodeOffset = u16;
@@ -307,6 +329,27 @@ pub struct FieldHandle {
     pub field: MemberCount,
 }
 
+/// A variant of a struct declared with variants (owner type and variant index)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct StructVariantHandle {
+    pub struct_index: StructDefinitionIndex,
+    pub variant: VariantIndex,
+}
+
+/// A field access info for a variant (owner type, variant index and offset within the variant)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct VariantFieldHandle {
+    pub owner: StructDefinitionIndex,
+    pub variant: VariantIndex,
+    pub field: MemberCount,
+}
+
 // DEFINITIONS:
 // Definitions are the module code. So the set of types and functions in the module.
 
@@ -318,6 +361,7 @@ pub struct FieldHandle {
 pub enum StructFieldInformation {
     Native,
     Declared(Vec<FieldDefinition>),
+    DeclaredVariants(Vec<VariantDefinition>),
 }
 
 //
@@ -363,6 +407,26 @@ pub struct FieldInstantiation {
     pub type_parameters: SignatureIndex,
 }
 
+/// A complete or partial instantiation of a struct variant.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct StructVariantInstantiation {
+    pub handle: StructVariantHandleIndex,
+    pub type_parameters: SignatureIndex,
+}
+
+/// A complete or partial instantiation of a variant field (or the type of it).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct VariantFieldInstantiation {
+    pub handle: VariantFieldHandleIndex,
+    pub type_parameters: SignatureIndex,
+}
+
 /// A `StructDefinition` is a type definition. It either indicates it is native or defines all the
 /// user-specified fields declared on the type.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -376,6 +440,7 @@ pub struct StructDefinition {
     /// Contains either
     /// - Information indicating the struct is native and has no accessible fields
     /// - Information indicating the number of fields and the start `FieldDefinition`s
+    /// - Information indicating the variants of the struct and their `FieldDefinition`s
     pub field_information: StructFieldInformation,
 }
 
@@ -386,15 +451,64 @@ impl StructDefinition {
             StructFieldInformation::Native => Err(PartialVMError::new(StatusCode::LINKER_ERROR)
                 .with_message("Looking for field in native structure".to_string())),
             StructFieldInformation::Declared(fields) => Ok(fields.len() as u16),
+            StructFieldInformation::DeclaredVariants(_) => {
+                Err(PartialVMError::new(StatusCode::LINKER_ERROR)
+                    .with_message("Looking for field in structure with variants".to_string()))
+            }
         }
     }
 
     pub fn field(&self, offset: usize) -> Option<&FieldDefinition> {
         match &self.field_information {
-            StructFieldInformation::Native => None,
+            StructFieldInformation::Native | StructFieldInformation::DeclaredVariants(_) => None,
             StructFieldInformation::Declared(fields) => fields.get(offset),
         }
     }
+
+    /// Returns true if the struct is declared with variants.
+    pub fn has_variants(&self) -> bool {
+        matches!(
+            &self.field_information,
+            StructFieldInformation::DeclaredVariants(_)
+        )
+    }
+
+    pub fn variant(&self, variant: VariantIndex) -> Option<&VariantDefinition> {
+        match &self.field_information {
+            StructFieldInformation::Native | StructFieldInformation::Declared(_) => None,
+            StructFieldInformation::DeclaredVariants(variants) => variants.get(variant as usize),
+        }
+    }
+
+    pub fn variant_field(&self, variant: VariantIndex, offset: usize) -> Option<&FieldDefinition> {
+        self.variant(variant)
+            .and_then(|variant| variant.fields.get(offset))
+    }
+
+    /// Returns all field definitions of the struct: the declared fields of a plain struct, or
+    /// the fields of every variant, in declaration order. Native structs have no fields.
+    pub fn all_fields(&self) -> Box<dyn Iterator<Item = &FieldDefinition> + '_> {
+        match &self.field_information {
+            StructFieldInformation::Native => Box::new(std::iter::empty()),
+            StructFieldInformation::Declared(fields) => Box::new(fields.iter()),
+            StructFieldInformation::DeclaredVariants(variants) => {
+                Box::new(variants.iter().flat_map(|variant| variant.fields.iter()))
+            }
+        }
+    }
+}
+
+/// A `VariantDefinition` is the definition of one variant of a struct declared with variants:
+/// its name and the fields it carries.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct VariantDefinition {
+    /// The name of the variant.
+    pub name: IdentifierIndex,
+    /// The fields of the variant, in declaration order.
+    pub fields: Vec<FieldDefinition>,
 }
 
 /// A `FieldDefinition` is the definition of a field: its name and the field type.
@@ -1635,6 +1749,49 @@ pub enum Bytecode {
     ///
     /// ```..., integer_value -> ..., u256_value```
     CastU256,
+    /// Create an instance of the given variant of a struct declared with variants and push it
+    /// on the stack. The values of the fields of the variant, in the order they appear in the
+    /// variant declaration, must be pushed on the stack. All fields must be provided.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., field(1)_value, field(2)_value, ..., field(n)_value -> ..., instance_value```
+    PackVariant(StructVariantHandleIndex),
+    PackVariantGeneric(StructVariantInstantiationIndex),
+    /// Destroy an instance of the given variant and push the values bound to each field of the
+    /// variant on the stack. Aborts the execution if the instance is of a different variant.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., instance_value -> ..., field(1)_value, field(2)_value, ..., field(n)_value```
+    UnpackVariant(StructVariantHandleIndex),
+    UnpackVariantGeneric(StructVariantInstantiationIndex),
+    /// Consume a reference to an instance of a struct declared with variants and push `true` on
+    /// the stack if the instance is of the given variant, `false` otherwise.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., reference -> ..., bool_value```
+    TestVariant(StructVariantHandleIndex),
+    TestVariantGeneric(StructVariantInstantiationIndex),
+    /// Consume a mutable reference to an instance of a struct declared with variants and push a
+    /// mutable reference to the given field of the given variant. Aborts the execution if the
+    /// instance is of a different variant.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., reference -> ..., field_reference```
+    MutBorrowVariantField(VariantFieldHandleIndex),
+    MutBorrowVariantFieldGeneric(VariantFieldInstantiationIndex),
+    /// Consume a reference to an instance of a struct declared with variants and push an
+    /// immutable reference to the given field of the given variant. Aborts the execution if the
+    /// instance is of a different variant.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., reference -> ..., field_reference```
+    ImmBorrowVariantField(VariantFieldHandleIndex),
+    ImmBorrowVariantFieldGeneric(VariantFieldInstantiationIndex),
 }
 
 impl ::std::fmt::Debug for Bytecode {
@@ -1717,6 +1874,20 @@ impl ::std::fmt::Debug for Bytecode {
             Bytecode::VecPopBack(a) => write!(f, "VecPopBack({})", a),
             Bytecode::VecUnpack(a, n) => write!(f, "VecUnpack({}, {})", a, n),
             Bytecode::VecSwap(a) => write!(f, "VecSwap({})", a),
+            Bytecode::PackVariant(a) => write!(f, "PackVariant({})", a),
+            Bytecode::PackVariantGeneric(a) => write!(f, "PackVariantGeneric({})", a),
+            Bytecode::UnpackVariant(a) => write!(f, "UnpackVariant({})", a),
+            Bytecode::UnpackVariantGeneric(a) => write!(f, "UnpackVariantGeneric({})", a),
+            Bytecode::TestVariant(a) => write!(f, "TestVariant({})", a),
+            Bytecode::TestVariantGeneric(a) => write!(f, "TestVariantGeneric({})", a),
+            Bytecode::MutBorrowVariantField(a) => write!(f, "MutBorrowVariantField({:?})", a),
+            Bytecode::MutBorrowVariantFieldGeneric(a) => {
+                write!(f, "MutBorrowVariantFieldGeneric({:?})", a)
+            }
+            Bytecode::ImmBorrowVariantField(a) => write!(f, "ImmBorrowVariantField({:?})", a),
+            Bytecode::ImmBorrowVariantFieldGeneric(a) => {
+                write!(f, "ImmBorrowVariantFieldGeneric({:?})", a)
+            }
         }
     }
 }
@@ -1860,6 +2031,15 @@ pub struct CompiledModule {
     /// Field instantiations.
     pub field_instantiations: Vec<FieldInstantiation>,
 
+    /// Handles to variants of structs declared with variants.
+    pub struct_variant_handles: Vec<StructVariantHandle>,
+    /// Struct variant instantiations.
+    pub struct_variant_instantiations: Vec<StructVariantInstantiation>,
+    /// Handles to fields of variants.
+    pub variant_field_handles: Vec<VariantFieldHandle>,
+    /// Variant field instantiations.
+    pub variant_field_instantiations: Vec<VariantFieldInstantiation>,
+
     /// Locals signature pool. The signature for all locals of the functions defined in the module.
     pub signatures: SignaturePool,
 
@@ -1979,6 +2159,10 @@ impl Arbitrary for CompiledModule {
                         struct_def_instantiations: vec![],
                         function_instantiations: vec![],
                         field_instantiations: vec![],
+                        struct_variant_handles: vec![],
+                        struct_variant_instantiations: vec![],
+                        variant_field_handles: vec![],
+                        variant_field_instantiations: vec![],
                         signatures,
                         identifiers,
                         address_identifiers,
@@ -2003,6 +2187,7 @@ impl CompiledModule {
                 | IndexKind::FieldDefinition
                 | IndexKind::TypeParameter
                 | IndexKind::MemberCount
+                | IndexKind::VariantDefinition
         ));
         match kind {
             IndexKind::ModuleHandle => self.module_handles.len(),
@@ -2013,6 +2198,10 @@ impl CompiledModule {
             IndexKind::StructDefInstantiation => self.struct_def_instantiations.len(),
             IndexKind::FunctionInstantiation => self.function_instantiations.len(),
             IndexKind::FieldInstantiation => self.field_instantiations.len(),
+            IndexKind::StructVariantHandle => self.struct_variant_handles.len(),
+            IndexKind::StructVariantInstantiation => self.struct_variant_instantiations.len(),
+            IndexKind::VariantFieldHandle => self.variant_field_handles.len(),
+            IndexKind::VariantFieldInstantiation => self.variant_field_instantiations.len(),
             IndexKind::StructDefinition => self.struct_defs.len(),
             IndexKind::FunctionDefinition => self.function_defs.len(),
             IndexKind::Signature => self.signatures.len(),
@@ -2024,7 +2213,10 @@ impl CompiledModule {
             | other @ IndexKind::CodeDefinition
             | other @ IndexKind::FieldDefinition
             | other @ IndexKind::TypeParameter
-            | other @ IndexKind::MemberCount => unreachable!("invalid kind for count: {:?}", other),
+            | other @ IndexKind::MemberCount
+            | other @ IndexKind::VariantDefinition => {
+                unreachable!("invalid kind for count: {:?}", other)
+            }
         }
     }
 
@@ -2064,6 +2256,10 @@ pub fn empty_module() -> CompiledModule {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         signatures: vec![Signature(vec![])],
     }
 }
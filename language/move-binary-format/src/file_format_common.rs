@@ -52,6 +52,10 @@ pub const FIELD_HANDLE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const FIELD_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const STRUCT_DEF_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const CONSTANT_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const STRUCT_VARIANT_HANDLE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const STRUCT_VARIANT_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const VARIANT_FIELD_HANDLE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const VARIANT_FIELD_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 
 pub const BYTECODE_COUNT_MAX: u64 = 65535;
 pub const BYTECODE_INDEX_MAX: u64 = 65535;
@@ -72,6 +76,9 @@ pub const ACQUIRES_COUNT_MAX: u64 = 255;
 pub const FIELD_COUNT_MAX: u64 = 255;
 pub const FIELD_OFFSET_MAX: u64 = 255;
 
+pub const VARIANT_COUNT_MAX: u64 = 127;
+pub const VARIANT_INDEX_MAX: u64 = 127;
+
 pub const TYPE_PARAMETER_COUNT_MAX: u64 = 255;
 pub const TYPE_PARAMETER_INDEX_MAX: u64 = 65536;
 
@@ -101,6 +108,10 @@ pub enum TableType {
     FIELD_INST              = 0xE,
     FRIEND_DECLS            = 0xF,
     METADATA                = 0x10,
+    STRUCT_VARIANT_HANDLES  = 0x11,
+    STRUCT_VARIANT_INST     = 0x12,
+    VARIANT_FIELD_HANDLES   = 0x13,
+    VARIANT_FIELD_INST      = 0x14,
 }
 
 /// Constants for signature blob values.
@@ -133,6 +144,7 @@ pub enum SerializedType {
 pub enum SerializedNativeStructFlag {
     NATIVE                  = 0x1,
     DECLARED                = 0x2,
+    DECLARED_VARIANTS       = 0x3,
 }
 
 /// List of opcodes constants.
@@ -218,6 +230,16 @@ pub enum Opcodes {
     CAST_U16                    = 0x4B,
     CAST_U32                    = 0x4C,
     CAST_U256                   = 0x4D,
+    PACK_VARIANT                = 0x4E,
+    PACK_VARIANT_GENERIC        = 0x4F,
+    UNPACK_VARIANT              = 0x50,
+    UNPACK_VARIANT_GENERIC      = 0x51,
+    TEST_VARIANT                = 0x52,
+    TEST_VARIANT_GENERIC        = 0x53,
+    MUT_BORROW_VARIANT_FIELD    = 0x54,
+    MUT_BORROW_VARIANT_FIELD_GENERIC = 0x55,
+    IMM_BORROW_VARIANT_FIELD    = 0x56,
+    IMM_BORROW_VARIANT_FIELD_GENERIC = 0x57,
 }
 
 /// Upper limit on the binary size
@@ -405,8 +427,14 @@ pub const VERSION_5: u32 = 5;
 ///  + u16, u32, u256 integers and corresponding Ld, Cast bytecodes
 pub const VERSION_6: u32 = 6;
 
+/// Version 7: changes compared with version 6
+///  + structs declared with variants (enums)
+///  + variant handle and variant field handle tables, and their instantiations
+///  + bytecodes for packing, unpacking and testing variants, and borrowing variant fields
+pub const VERSION_7: u32 = 7;
+
 // Mark which version is the latest version
-pub const VERSION_MAX: u32 = VERSION_6;
+pub const VERSION_MAX: u32 = VERSION_7;
 
 // Mark which oldest version is supported.
 // TODO(#145): finish v4 compatibility; as of now, only metadata is implemented
@@ -625,6 +653,16 @@ pub fn instruction_key(instruction: &Bytecode) -> u8 {
         CastU16 => Opcodes::CAST_U16,
         CastU32 => Opcodes::CAST_U32,
         CastU256 => Opcodes::CAST_U256,
+        PackVariant(_) => Opcodes::PACK_VARIANT,
+        PackVariantGeneric(_) => Opcodes::PACK_VARIANT_GENERIC,
+        UnpackVariant(_) => Opcodes::UNPACK_VARIANT,
+        UnpackVariantGeneric(_) => Opcodes::UNPACK_VARIANT_GENERIC,
+        TestVariant(_) => Opcodes::TEST_VARIANT,
+        TestVariantGeneric(_) => Opcodes::TEST_VARIANT_GENERIC,
+        MutBorrowVariantField(_) => Opcodes::MUT_BORROW_VARIANT_FIELD,
+        MutBorrowVariantFieldGeneric(_) => Opcodes::MUT_BORROW_VARIANT_FIELD_GENERIC,
+        ImmBorrowVariantField(_) => Opcodes::IMM_BORROW_VARIANT_FIELD,
+        ImmBorrowVariantFieldGeneric(_) => Opcodes::IMM_BORROW_VARIANT_FIELD_GENERIC,
     };
     opcode as u8
 }
@@ -51,6 +51,11 @@ pub enum IndexKind {
     CodeDefinition,
     TypeParameter,
     MemberCount,
+    StructVariantHandle,
+    StructVariantInstantiation,
+    VariantFieldHandle,
+    VariantFieldInstantiation,
+    VariantDefinition,
 }
 
 impl IndexKind {
@@ -77,6 +82,11 @@ impl IndexKind {
             CodeDefinition,
             TypeParameter,
             MemberCount,
+            StructVariantHandle,
+            StructVariantInstantiation,
+            VariantFieldHandle,
+            VariantFieldInstantiation,
+            VariantDefinition,
         ]
     }
 }
@@ -105,6 +115,11 @@ impl fmt::Display for IndexKind {
             CodeDefinition => "code definition pool",
             TypeParameter => "type parameter",
             MemberCount => "field offset",
+            StructVariantHandle => "struct variant handle",
+            StructVariantInstantiation => "struct variant instantiation",
+            VariantFieldHandle => "variant field handle",
+            VariantFieldInstantiation => "variant field instantiation",
+            VariantDefinition => "variant definition",
         };
 
         f.write_str(desc)
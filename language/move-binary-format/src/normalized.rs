@@ -7,7 +7,7 @@ use crate::{
     file_format::{
         AbilitySet, CompiledModule, FieldDefinition, FunctionDefinition, SignatureToken,
         StructDefinition, StructFieldInformation, StructTypeParameter, TypeParameterIndex,
-        Visibility,
+        VariantDefinition, Visibility,
     },
 };
 use move_core_types::{
@@ -72,6 +72,15 @@ pub struct Field {
     pub type_: Type,
 }
 
+/// Normalized version of a `VariantDefinition`. As with fields, the `name` is included so that
+/// renaming or reordering variants is marked as incompatible. Not safe to compare without an
+/// enclosing `Struct`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Variant {
+    pub name: Identifier,
+    pub fields: Vec<Field>,
+}
+
 /// Normalized version of a `StructDefinition`. Not safe to compare without an associated
 /// `ModuleId` or `Module`.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -79,6 +88,7 @@ pub struct Struct {
     pub abilities: AbilitySet,
     pub type_parameters: Vec<StructTypeParameter>,
     pub fields: Vec<Field>,
+    pub variants: Vec<Variant>,
 }
 
 /// Normalized version of a `FunctionDefinition`. Not safe to compare without an associated
@@ -290,25 +300,40 @@ impl Field {
     }
 }
 
+impl Variant {
+    /// Create a `Variant` for `VariantDefinition` `v` in module `m`.
+    pub fn new(m: &CompiledModule, v: &VariantDefinition) -> Self {
+        Variant {
+            name: m.identifier_at(v.name).to_owned(),
+            fields: v.fields.iter().map(|f| Field::new(m, f)).collect(),
+        }
+    }
+}
+
 impl Struct {
     /// Create a `Struct` for `StructDefinition` `def` in module `m`. Panics if `def` is a
     /// a native struct definition.
     pub fn new(m: &CompiledModule, def: &StructDefinition) -> (Identifier, Self) {
         let handle = m.struct_handle_at(def.struct_handle);
-        let fields = match &def.field_information {
+        let (fields, variants) = match &def.field_information {
             StructFieldInformation::Native => {
                 // Pretend for compatibility checking no fields
-                vec![]
+                (vec![], vec![])
             }
             StructFieldInformation::Declared(fields) => {
-                fields.iter().map(|f| Field::new(m, f)).collect()
+                (fields.iter().map(|f| Field::new(m, f)).collect(), vec![])
             }
+            StructFieldInformation::DeclaredVariants(variants) => (
+                vec![],
+                variants.iter().map(|v| Variant::new(m, v)).collect(),
+            ),
         };
         let name = m.identifier_at(handle.name).to_owned();
         let s = Struct {
             abilities: handle.abilities,
             type_parameters: handle.type_parameters.clone(),
             fields,
+            variants,
         };
         (name, s)
     }
@@ -379,6 +379,11 @@ impl CompiledModuleStrategyGen {
                         function_instantiations,
                         field_instantiations,
 
+                        struct_variant_handles: vec![],
+                        struct_variant_instantiations: vec![],
+                        variant_field_handles: vec![],
+                        variant_field_instantiations: vec![],
+
                         struct_defs,
                         function_defs,
 
@@ -132,6 +132,34 @@ fn serialize_struct_def_inst_index(
     write_as_uleb128(binary, idx.0, STRUCT_DEF_INST_INDEX_MAX)
 }
 
+fn serialize_struct_variant_handle_index(
+    binary: &mut BinaryData,
+    idx: &StructVariantHandleIndex,
+) -> Result<()> {
+    write_as_uleb128(binary, idx.0, STRUCT_VARIANT_HANDLE_INDEX_MAX)
+}
+
+fn serialize_struct_variant_inst_index(
+    binary: &mut BinaryData,
+    idx: &StructVariantInstantiationIndex,
+) -> Result<()> {
+    write_as_uleb128(binary, idx.0, STRUCT_VARIANT_INST_INDEX_MAX)
+}
+
+fn serialize_variant_field_handle_index(
+    binary: &mut BinaryData,
+    idx: &VariantFieldHandleIndex,
+) -> Result<()> {
+    write_as_uleb128(binary, idx.0, VARIANT_FIELD_HANDLE_INDEX_MAX)
+}
+
+fn serialize_variant_field_inst_index(
+    binary: &mut BinaryData,
+    idx: &VariantFieldInstantiationIndex,
+) -> Result<()> {
+    write_as_uleb128(binary, idx.0, VARIANT_FIELD_INST_INDEX_MAX)
+}
+
 fn seiralize_table_offset(binary: &mut BinaryData, offset: u32) -> Result<()> {
     write_as_uleb128(binary, offset, TABLE_OFFSET_MAX)
 }
@@ -172,6 +200,14 @@ fn serialize_field_offset(binary: &mut BinaryData, offset: u16) -> Result<()> {
     write_as_uleb128(binary, offset, FIELD_OFFSET_MAX)
 }
 
+fn serialize_variant_count(binary: &mut BinaryData, len: usize) -> Result<()> {
+    write_as_uleb128(binary, len as u64, VARIANT_COUNT_MAX)
+}
+
+fn serialize_variant_index(binary: &mut BinaryData, idx: u16) -> Result<()> {
+    write_as_uleb128(binary, idx, VARIANT_INDEX_MAX)
+}
+
 fn serialize_acquires_count(binary: &mut BinaryData, len: usize) -> Result<()> {
     write_as_uleb128(binary, len as u64, ACQUIRES_COUNT_MAX)
 }
@@ -283,6 +319,10 @@ struct ModuleSerializer {
     field_handles: (u32, u32),
     field_instantiations: (u32, u32),
     friend_decls: (u32, u32),
+    struct_variant_handles: (u32, u32),
+    struct_variant_instantiations: (u32, u32),
+    variant_field_handles: (u32, u32),
+    variant_field_instantiations: (u32, u32),
 }
 
 /// Holds data to compute the header of a transaction script binary.
@@ -548,7 +588,11 @@ fn serialize_byte_blob(
 /// - `StructDefinition.handle` as a ULEB128 (index into the `ModuleHandle` table)
 /// - `StructDefinition.field_count` as a ULEB128 (number of fields defined in the type)
 /// - `StructDefinition.fields` as a ULEB128 (index into the `FieldDefinition` table)
+///
+/// For a struct declared with variants, the field count and fields are replaced by the
+/// variant count followed by each `VariantDefinition`.
 fn serialize_struct_definition(
+    major_version: u32,
     binary: &mut BinaryData,
     struct_definition: &StructDefinition,
 ) -> Result<()> {
@@ -559,9 +603,36 @@ fn serialize_struct_definition(
             binary.push(SerializedNativeStructFlag::DECLARED as u8)?;
             serialize_field_definitions(binary, fields)
         }
+        StructFieldInformation::DeclaredVariants(variants) => {
+            if major_version < VERSION_7 {
+                bail!(
+                    "Structs with variants not supported in bytecode version {}",
+                    major_version
+                )
+            }
+            binary.push(SerializedNativeStructFlag::DECLARED_VARIANTS as u8)?;
+            serialize_variant_count(binary, variants.len())?;
+            for variant in variants {
+                serialize_variant_definition(binary, variant)?;
+            }
+            Ok(())
+        }
     }
 }
 
+/// Serializes a `VariantDefinition`.
+///
+/// A `VariantDefinition` gets serialized as follows:
+/// - `VariantDefinition.name` as a ULEB128 (index into the `IdentifierPool` table)
+/// - `VariantDefinition.fields` as serialized by `serialize_field_definitions`
+fn serialize_variant_definition(
+    binary: &mut BinaryData,
+    variant_definition: &VariantDefinition,
+) -> Result<()> {
+    serialize_identifier_index(binary, &variant_definition.name)?;
+    serialize_field_definitions(binary, &variant_definition.fields)
+}
+
 fn serialize_struct_def_instantiation(
     binary: &mut BinaryData,
     struct_inst: &StructDefInstantiation,
@@ -609,6 +680,43 @@ fn serialize_field_instantiation(
     Ok(())
 }
 
+fn serialize_struct_variant_handle(
+    binary: &mut BinaryData,
+    variant_handle: &StructVariantHandle,
+) -> Result<()> {
+    serialize_struct_def_index(binary, &variant_handle.struct_index)?;
+    serialize_variant_index(binary, variant_handle.variant)?;
+    Ok(())
+}
+
+fn serialize_struct_variant_instantiation(
+    binary: &mut BinaryData,
+    variant_inst: &StructVariantInstantiation,
+) -> Result<()> {
+    serialize_struct_variant_handle_index(binary, &variant_inst.handle)?;
+    serialize_signature_index(binary, &variant_inst.type_parameters)?;
+    Ok(())
+}
+
+fn serialize_variant_field_handle(
+    binary: &mut BinaryData,
+    field_handle: &VariantFieldHandle,
+) -> Result<()> {
+    serialize_struct_def_index(binary, &field_handle.owner)?;
+    serialize_variant_index(binary, field_handle.variant)?;
+    serialize_field_offset(binary, field_handle.field)?;
+    Ok(())
+}
+
+fn serialize_variant_field_instantiation(
+    binary: &mut BinaryData,
+    field_inst: &VariantFieldInstantiation,
+) -> Result<()> {
+    serialize_variant_field_handle_index(binary, &field_inst.handle)?;
+    serialize_signature_index(binary, &field_inst.type_parameters)?;
+    Ok(())
+}
+
 /// Serializes a `Vec<StructDefinitionIndex>`.
 fn serialize_acquires(binary: &mut BinaryData, indices: &[StructDefinitionIndex]) -> Result<()> {
     serialize_acquires_count(binary, indices.len())?;
@@ -748,6 +856,23 @@ fn serialize_instruction_inner(
                 major_version
             ));
         }
+        Bytecode::PackVariant(_)
+        | Bytecode::PackVariantGeneric(_)
+        | Bytecode::UnpackVariant(_)
+        | Bytecode::UnpackVariantGeneric(_)
+        | Bytecode::TestVariant(_)
+        | Bytecode::TestVariantGeneric(_)
+        | Bytecode::MutBorrowVariantField(_)
+        | Bytecode::MutBorrowVariantFieldGeneric(_)
+        | Bytecode::ImmBorrowVariantField(_)
+        | Bytecode::ImmBorrowVariantFieldGeneric(_)
+            if (major_version < VERSION_7) =>
+        {
+            return Err(anyhow!(
+                "Struct variant operations not supported in bytecode version {}",
+                major_version
+            ));
+        }
         _ => (),
     };
 
@@ -960,6 +1085,46 @@ fn serialize_instruction_inner(
         Bytecode::CastU16 => binary.push(Opcodes::CAST_U16 as u8),
         Bytecode::CastU32 => binary.push(Opcodes::CAST_U32 as u8),
         Bytecode::CastU256 => binary.push(Opcodes::CAST_U256 as u8),
+        Bytecode::PackVariant(idx) => {
+            binary.push(Opcodes::PACK_VARIANT as u8)?;
+            serialize_struct_variant_handle_index(binary, idx)
+        }
+        Bytecode::PackVariantGeneric(idx) => {
+            binary.push(Opcodes::PACK_VARIANT_GENERIC as u8)?;
+            serialize_struct_variant_inst_index(binary, idx)
+        }
+        Bytecode::UnpackVariant(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT as u8)?;
+            serialize_struct_variant_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariantGeneric(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT_GENERIC as u8)?;
+            serialize_struct_variant_inst_index(binary, idx)
+        }
+        Bytecode::TestVariant(idx) => {
+            binary.push(Opcodes::TEST_VARIANT as u8)?;
+            serialize_struct_variant_handle_index(binary, idx)
+        }
+        Bytecode::TestVariantGeneric(idx) => {
+            binary.push(Opcodes::TEST_VARIANT_GENERIC as u8)?;
+            serialize_struct_variant_inst_index(binary, idx)
+        }
+        Bytecode::MutBorrowVariantField(field_idx) => {
+            binary.push(Opcodes::MUT_BORROW_VARIANT_FIELD as u8)?;
+            serialize_variant_field_handle_index(binary, field_idx)
+        }
+        Bytecode::MutBorrowVariantFieldGeneric(field_idx) => {
+            binary.push(Opcodes::MUT_BORROW_VARIANT_FIELD_GENERIC as u8)?;
+            serialize_variant_field_inst_index(binary, field_idx)
+        }
+        Bytecode::ImmBorrowVariantField(field_idx) => {
+            binary.push(Opcodes::IMM_BORROW_VARIANT_FIELD as u8)?;
+            serialize_variant_field_handle_index(binary, field_idx)
+        }
+        Bytecode::ImmBorrowVariantFieldGeneric(field_idx) => {
+            binary.push(Opcodes::IMM_BORROW_VARIANT_FIELD_GENERIC as u8)?;
+            serialize_variant_field_inst_index(binary, field_idx)
+        }
     };
     res?;
     Ok(())
@@ -1257,6 +1422,10 @@ impl ModuleSerializer {
             field_handles: (0, 0),
             field_instantiations: (0, 0),
             friend_decls: (0, 0),
+            struct_variant_handles: (0, 0),
+            struct_variant_instantiations: (0, 0),
+            variant_field_handles: (0, 0),
+            variant_field_instantiations: (0, 0),
         }
     }
 
@@ -1267,7 +1436,29 @@ impl ModuleSerializer {
         self.serialize_function_definitions(binary, &module.function_defs)?;
         self.serialize_field_handles(binary, &module.field_handles)?;
         self.serialize_field_instantiations(binary, &module.field_instantiations)?;
-        self.serialize_friend_declarations(binary, &module.friend_decls)
+        self.serialize_friend_declarations(binary, &module.friend_decls)?;
+        if self.common.major_version >= VERSION_7 {
+            self.serialize_struct_variant_handles(binary, &module.struct_variant_handles)?;
+            self.serialize_struct_variant_instantiations(
+                binary,
+                &module.struct_variant_instantiations,
+            )?;
+            self.serialize_variant_field_handles(binary, &module.variant_field_handles)?;
+            self.serialize_variant_field_instantiations(
+                binary,
+                &module.variant_field_instantiations,
+            )?;
+        } else if !module.struct_variant_handles.is_empty()
+            || !module.struct_variant_instantiations.is_empty()
+            || !module.variant_field_handles.is_empty()
+            || !module.variant_field_instantiations.is_empty()
+        {
+            bail!(
+                "Struct variant tables not supported in bytecode version {}",
+                self.common.major_version
+            )
+        }
+        Ok(())
     }
 
     fn serialize_table_indices(&mut self, binary: &mut BinaryData) -> Result<()> {
@@ -1308,6 +1499,30 @@ impl ModuleSerializer {
             self.friend_decls.0,
             self.friend_decls.1,
         )?;
+        serialize_table_index(
+            binary,
+            TableType::STRUCT_VARIANT_HANDLES,
+            self.struct_variant_handles.0,
+            self.struct_variant_handles.1,
+        )?;
+        serialize_table_index(
+            binary,
+            TableType::STRUCT_VARIANT_INST,
+            self.struct_variant_instantiations.0,
+            self.struct_variant_instantiations.1,
+        )?;
+        serialize_table_index(
+            binary,
+            TableType::VARIANT_FIELD_HANDLES,
+            self.variant_field_handles.0,
+            self.variant_field_handles.1,
+        )?;
+        serialize_table_index(
+            binary,
+            TableType::VARIANT_FIELD_INST,
+            self.variant_field_instantiations.0,
+            self.variant_field_instantiations.1,
+        )?;
         Ok(())
     }
 
@@ -1321,7 +1536,7 @@ impl ModuleSerializer {
             self.common.table_count = self.common.table_count.wrapping_add(1); // the count will bound to a small number
             self.struct_defs.0 = check_index_in_binary(binary.len())?;
             for struct_definition in struct_definitions {
-                serialize_struct_definition(binary, struct_definition)?;
+                serialize_struct_definition(self.common.major_version, binary, struct_definition)?;
             }
             self.struct_defs.1 = checked_calculate_table_size(binary, self.struct_defs.0)?;
         }
@@ -1440,6 +1655,74 @@ impl ModuleSerializer {
         Ok(())
     }
 
+    fn serialize_struct_variant_handles(
+        &mut self,
+        binary: &mut BinaryData,
+        struct_variant_handles: &[StructVariantHandle],
+    ) -> Result<()> {
+        if !struct_variant_handles.is_empty() {
+            self.common.table_count += 1;
+            self.struct_variant_handles.0 = check_index_in_binary(binary.len())?;
+            for variant_handle in struct_variant_handles {
+                serialize_struct_variant_handle(binary, variant_handle)?;
+            }
+            self.struct_variant_handles.1 =
+                checked_calculate_table_size(binary, self.struct_variant_handles.0)?;
+        }
+        Ok(())
+    }
+
+    fn serialize_struct_variant_instantiations(
+        &mut self,
+        binary: &mut BinaryData,
+        struct_variant_instantiations: &[StructVariantInstantiation],
+    ) -> Result<()> {
+        if !struct_variant_instantiations.is_empty() {
+            self.common.table_count += 1;
+            self.struct_variant_instantiations.0 = check_index_in_binary(binary.len())?;
+            for variant_inst in struct_variant_instantiations {
+                serialize_struct_variant_instantiation(binary, variant_inst)?;
+            }
+            self.struct_variant_instantiations.1 =
+                checked_calculate_table_size(binary, self.struct_variant_instantiations.0)?;
+        }
+        Ok(())
+    }
+
+    fn serialize_variant_field_handles(
+        &mut self,
+        binary: &mut BinaryData,
+        variant_field_handles: &[VariantFieldHandle],
+    ) -> Result<()> {
+        if !variant_field_handles.is_empty() {
+            self.common.table_count += 1;
+            self.variant_field_handles.0 = check_index_in_binary(binary.len())?;
+            for field_handle in variant_field_handles {
+                serialize_variant_field_handle(binary, field_handle)?;
+            }
+            self.variant_field_handles.1 =
+                checked_calculate_table_size(binary, self.variant_field_handles.0)?;
+        }
+        Ok(())
+    }
+
+    fn serialize_variant_field_instantiations(
+        &mut self,
+        binary: &mut BinaryData,
+        variant_field_instantiations: &[VariantFieldInstantiation],
+    ) -> Result<()> {
+        if !variant_field_instantiations.is_empty() {
+            self.common.table_count += 1;
+            self.variant_field_instantiations.0 = check_index_in_binary(binary.len())?;
+            for field_inst in variant_field_instantiations {
+                serialize_variant_field_instantiation(binary, field_inst)?;
+            }
+            self.variant_field_instantiations.1 =
+                checked_calculate_table_size(binary, self.variant_field_instantiations.0)?;
+        }
+        Ok(())
+    }
+
     fn serialize_friend_declarations(
         &mut self,
         binary: &mut BinaryData,
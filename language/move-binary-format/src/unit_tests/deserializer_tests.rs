@@ -3,10 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    file_format::{CompiledModule, CompiledScript},
+    file_format::{
+        basic_test_module, AbilitySet, Bytecode, CodeUnit, CompiledModule, CompiledScript,
+        FieldDefinition, FunctionDefinition, FunctionHandle, FunctionHandleIndex, IdentifierIndex,
+        ModuleHandleIndex, Signature, SignatureIndex, SignatureToken, StructDefinition,
+        StructDefinitionIndex, StructFieldInformation, StructHandle, StructHandleIndex,
+        StructVariantHandle, StructVariantHandleIndex, TypeSignature, VariantDefinition,
+        VariantFieldHandle, VariantFieldHandleIndex, Visibility,
+    },
     file_format_common::*,
 };
-use move_core_types::vm_status::StatusCode;
+use move_core_types::{identifier::Identifier, vm_status::StatusCode};
 
 fn malformed_simple_versioned_test(version: u32) {
     // bad uleb (more than allowed for table count)
@@ -257,3 +264,118 @@ fn deserialize_invalid_script_no_signature() {
         StatusCode::INDEX_OUT_OF_BOUNDS
     );
 }
+
+// Create a module with a struct declared with variants `E { A, B { x: u64 } }` and a function
+// using the variant instructions on it.
+fn module_with_variants() -> CompiledModule {
+    let mut m = basic_test_module();
+    let ident = |m: &mut CompiledModule, name: &str| {
+        m.identifiers.push(Identifier::new(name).unwrap());
+        IdentifierIndex((m.identifiers.len() - 1) as u16)
+    };
+
+    let struct_name = ident(&mut m, "E");
+    m.struct_handles.push(StructHandle {
+        module: ModuleHandleIndex(0),
+        name: struct_name,
+        abilities: AbilitySet::EMPTY,
+        type_parameters: vec![],
+    });
+    let variant_a = ident(&mut m, "A");
+    let variant_b = ident(&mut m, "B");
+    let field_x = ident(&mut m, "x");
+    m.struct_defs.push(StructDefinition {
+        struct_handle: StructHandleIndex((m.struct_handles.len() - 1) as u16),
+        field_information: StructFieldInformation::DeclaredVariants(vec![
+            VariantDefinition {
+                name: variant_a,
+                fields: vec![],
+            },
+            VariantDefinition {
+                name: variant_b,
+                fields: vec![FieldDefinition {
+                    name: field_x,
+                    signature: TypeSignature(SignatureToken::U64),
+                }],
+            },
+        ]),
+    });
+    let struct_index = StructDefinitionIndex((m.struct_defs.len() - 1) as u16);
+    m.struct_variant_handles.push(StructVariantHandle {
+        struct_index,
+        variant: 1,
+    });
+    m.variant_field_handles.push(VariantFieldHandle {
+        owner: struct_index,
+        variant: 1,
+        field: 0,
+    });
+
+    let fun_name = ident(&mut m, "bar");
+    m.signatures
+        .push(Signature(vec![SignatureToken::Struct(StructHandleIndex(
+            (m.struct_handles.len() - 1) as u16,
+        ))]));
+    m.function_handles.push(FunctionHandle {
+        module: ModuleHandleIndex(0),
+        name: fun_name,
+        parameters: SignatureIndex(0),
+        return_: SignatureIndex(0),
+        type_parameters: vec![],
+    });
+    m.function_defs.push(FunctionDefinition {
+        function: FunctionHandleIndex((m.function_handles.len() - 1) as u16),
+        visibility: Visibility::Private,
+        is_entry: false,
+        acquires_global_resources: vec![],
+        code: Some(CodeUnit {
+            locals: SignatureIndex((m.signatures.len() - 1) as u16),
+            code: vec![
+                Bytecode::LdU64(7),
+                Bytecode::PackVariant(StructVariantHandleIndex(0)),
+                Bytecode::StLoc(0),
+                Bytecode::ImmBorrowLoc(0),
+                Bytecode::TestVariant(StructVariantHandleIndex(0)),
+                Bytecode::Pop,
+                Bytecode::ImmBorrowLoc(0),
+                Bytecode::ImmBorrowVariantField(VariantFieldHandleIndex(0)),
+                Bytecode::Pop,
+                Bytecode::MoveLoc(0),
+                Bytecode::UnpackVariant(StructVariantHandleIndex(0)),
+                Bytecode::Pop,
+                Bytecode::Ret,
+            ],
+        }),
+    });
+    m
+}
+
+#[test]
+fn struct_variants_roundtrip() {
+    let module = module_with_variants();
+    let mut binary = vec![];
+    module.serialize(&mut binary).unwrap();
+    let deserialized = CompiledModule::deserialize(&binary).unwrap();
+    assert_eq!(deserialized, module);
+}
+
+#[test]
+fn struct_variants_not_supported_before_v7() {
+    let module = module_with_variants();
+    let mut binary = vec![];
+    assert!(module
+        .serialize_for_version(Some(VERSION_6), &mut binary)
+        .is_err());
+
+    // a v7 binary relabeled as v6 must be rejected
+    let mut binary = vec![];
+    module.serialize(&mut binary).unwrap();
+    binary[BinaryConstants::MOVE_MAGIC_SIZE..BinaryConstants::MOVE_MAGIC_SIZE + 4]
+        .copy_from_slice(&VERSION_6.to_le_bytes());
+    assert_eq!(
+        CompiledModule::deserialize(&binary)
+            .unwrap_err()
+            .major_status(),
+        StatusCode::MALFORMED
+    );
+}
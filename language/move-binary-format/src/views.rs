@@ -353,10 +353,15 @@ impl<'a, T: ModuleAccess> StructDefinitionView<'a, T> {
     pub fn is_native(&self) -> bool {
         match &self.struct_def.field_information {
             StructFieldInformation::Native => true,
-            StructFieldInformation::Declared { .. } => false,
+            StructFieldInformation::Declared { .. }
+            | StructFieldInformation::DeclaredVariants { .. } => false,
         }
     }
 
+    pub fn has_variants(&self) -> bool {
+        self.struct_def.has_variants()
+    }
+
     pub fn type_parameters(&self) -> &Vec<StructTypeParameter> {
         self.struct_handle_view.type_parameters()
     }
@@ -366,7 +371,7 @@ impl<'a, T: ModuleAccess> StructDefinitionView<'a, T> {
     ) -> Option<impl DoubleEndedIterator<Item = FieldDefinitionView<'a, T>> + Send> {
         let module = self.module;
         match &self.struct_def.field_information {
-            StructFieldInformation::Native => None,
+            StructFieldInformation::Native | StructFieldInformation::DeclaredVariants(_) => None,
             StructFieldInformation::Declared(fields) => Some(
                 fields
                     .iter()
@@ -375,11 +380,51 @@ impl<'a, T: ModuleAccess> StructDefinitionView<'a, T> {
         }
     }
 
+    pub fn variants(
+        &self,
+    ) -> Option<impl DoubleEndedIterator<Item = VariantDefinitionView<'a, T>> + Send> {
+        let module = self.module;
+        match &self.struct_def.field_information {
+            StructFieldInformation::Native | StructFieldInformation::Declared(_) => None,
+            StructFieldInformation::DeclaredVariants(variants) => Some(
+                variants
+                    .iter()
+                    .map(move |variant_def| VariantDefinitionView::new(module, variant_def)),
+            ),
+        }
+    }
+
     pub fn name(&self) -> &'a IdentStr {
         self.struct_handle_view.name()
     }
 }
 
+pub struct VariantDefinitionView<'a, T> {
+    module: &'a T,
+    variant_def: &'a VariantDefinition,
+}
+
+impl<'a, T: ModuleAccess> VariantDefinitionView<'a, T> {
+    pub fn new(module: &'a T, variant_def: &'a VariantDefinition) -> Self {
+        Self {
+            module,
+            variant_def,
+        }
+    }
+
+    pub fn name(&self) -> &'a IdentStr {
+        self.module.identifier_at(self.variant_def.name)
+    }
+
+    pub fn fields(&self) -> impl DoubleEndedIterator<Item = FieldDefinitionView<'a, T>> + Send {
+        let module = self.module;
+        self.variant_def
+            .fields
+            .iter()
+            .map(move |field_def| FieldDefinitionView::new(module, field_def))
+    }
+}
+
 pub struct FieldDefinitionView<'a, T> {
     module: &'a T,
     field_def: &'a FieldDefinition,
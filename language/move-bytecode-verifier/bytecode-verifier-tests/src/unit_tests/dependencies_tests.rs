@@ -80,6 +80,10 @@ fn mk_script_function_module() -> CompiledModule {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
     };
     move_bytecode_verifier::verify_module(&m).unwrap();
     m
@@ -172,6 +176,10 @@ fn mk_invoking_module(use_generic: bool, valid: bool) -> CompiledModule {
         friend_decls: vec![],
         struct_def_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
     };
     move_bytecode_verifier::verify_module(&m).unwrap();
     m
@@ -214,6 +214,10 @@ fn big_vec_unpacks() {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         signatures: vec![Signature(vec![]), Signature(vec![st])],
         identifiers: vec![
             Identifier::new("f").unwrap(),
@@ -93,6 +93,10 @@ fn no_verify_locals_good() {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         identifiers: vec![
             Identifier::new("Bad").unwrap(),
             Identifier::new("blah").unwrap(),
@@ -185,6 +189,10 @@ fn big_signature_test() {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         signatures: vec![Signature(vec![]), Signature(vec![st])],
         identifiers: vec![
             Identifier::new("f").unwrap(),
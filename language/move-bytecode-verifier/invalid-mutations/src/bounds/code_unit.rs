@@ -8,7 +8,8 @@ use move_binary_format::{
         Bytecode, CodeOffset, CompiledModule, ConstantPoolIndex, FieldHandleIndex,
         FieldInstantiationIndex, FunctionDefinitionIndex, FunctionHandleIndex,
         FunctionInstantiationIndex, LocalIndex, SignatureIndex, StructDefInstantiationIndex,
-        StructDefinitionIndex, TableIndex,
+        StructDefinitionIndex, StructVariantHandleIndex, StructVariantInstantiationIndex,
+        TableIndex, VariantFieldHandleIndex, VariantFieldInstantiationIndex,
     },
     internals::ModuleIndex,
     IndexKind,
@@ -198,6 +199,10 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
         let struct_inst_len = self.module.struct_def_instantiations.len();
         let function_inst_len = self.module.function_instantiations.len();
         let field_inst_len = self.module.field_instantiations.len();
+        let struct_variant_handle_len = self.module.struct_variant_handles.len();
+        let struct_variant_inst_len = self.module.struct_variant_instantiations.len();
+        let variant_field_handle_len = self.module.variant_field_handles.len();
+        let variant_field_inst_len = self.module.variant_field_instantiations.len();
         let signature_pool_len = self.module.signatures.len();
 
         mutations
@@ -377,6 +382,86 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
                         StructDefInstantiationIndex,
                         MoveToGeneric
                     ),
+                    PackVariant(_) => struct_bytecode!(
+                        struct_variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        StructVariantHandleIndex,
+                        PackVariant
+                    ),
+                    PackVariantGeneric(_) => struct_bytecode!(
+                        struct_variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        StructVariantInstantiationIndex,
+                        PackVariantGeneric
+                    ),
+                    UnpackVariant(_) => struct_bytecode!(
+                        struct_variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        StructVariantHandleIndex,
+                        UnpackVariant
+                    ),
+                    UnpackVariantGeneric(_) => struct_bytecode!(
+                        struct_variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        StructVariantInstantiationIndex,
+                        UnpackVariantGeneric
+                    ),
+                    TestVariant(_) => struct_bytecode!(
+                        struct_variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        StructVariantHandleIndex,
+                        TestVariant
+                    ),
+                    TestVariantGeneric(_) => struct_bytecode!(
+                        struct_variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        StructVariantInstantiationIndex,
+                        TestVariantGeneric
+                    ),
+                    ImmBorrowVariantField(_) => struct_bytecode!(
+                        variant_field_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantFieldHandleIndex,
+                        ImmBorrowVariantField
+                    ),
+                    ImmBorrowVariantFieldGeneric(_) => struct_bytecode!(
+                        variant_field_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantFieldInstantiationIndex,
+                        ImmBorrowVariantFieldGeneric
+                    ),
+                    MutBorrowVariantField(_) => struct_bytecode!(
+                        variant_field_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantFieldHandleIndex,
+                        MutBorrowVariantField
+                    ),
+                    MutBorrowVariantFieldGeneric(_) => struct_bytecode!(
+                        variant_field_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantFieldInstantiationIndex,
+                        MutBorrowVariantFieldGeneric
+                    ),
                     BrTrue(_) => {
                         code_bytecode!(code_len, current_fdef, bytecode_idx, offset, BrTrue)
                     }
@@ -520,6 +605,16 @@ fn is_interesting(bytecode: &Bytecode) -> bool {
         | MoveFromGeneric(_)
         | MoveTo(_)
         | MoveToGeneric(_)
+        | PackVariant(_)
+        | PackVariantGeneric(_)
+        | UnpackVariant(_)
+        | UnpackVariantGeneric(_)
+        | TestVariant(_)
+        | TestVariantGeneric(_)
+        | ImmBorrowVariantField(_)
+        | ImmBorrowVariantFieldGeneric(_)
+        | MutBorrowVariantField(_)
+        | MutBorrowVariantFieldGeneric(_)
         | BrTrue(_)
         | BrFalse(_)
         | Branch(_)
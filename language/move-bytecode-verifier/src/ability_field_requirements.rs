@@ -21,10 +21,9 @@ fn verify_module_impl(module: &CompiledModule) -> PartialVMResult<()> {
     let view = BinaryIndexedView::Module(module);
     for (idx, struct_def) in module.struct_defs().iter().enumerate() {
         let sh = module.struct_handle_at(struct_def.struct_handle);
-        let fields = match &struct_def.field_information {
-            StructFieldInformation::Native => continue,
-            StructFieldInformation::Declared(fields) => fields,
-        };
+        if let StructFieldInformation::Native = &struct_def.field_information {
+            continue;
+        }
         let required_abilities = sh
             .abilities
             .into_iter()
@@ -37,7 +36,7 @@ fn verify_module_impl(module: &CompiledModule) -> PartialVMResult<()> {
             .iter()
             .map(|_| AbilitySet::ALL)
             .collect::<Vec<_>>();
-        for field in fields {
+        for field in struct_def.all_fields() {
             let field_abilities = view.abilities(&field.signature.0, &type_parameter_abilities)?;
             if !required_abilities.is_subset(field_abilities) {
                 return Err(verification_error(
@@ -13,9 +13,9 @@ use move_binary_format::{
     access::{ModuleAccess, ScriptAccess},
     errors::{verification_error, Location, PartialVMResult, VMResult},
     file_format::{
-        CompiledModule, CompiledScript, Constant, FunctionHandle, FunctionHandleIndex,
-        FunctionInstantiation, ModuleHandle, Signature, StructFieldInformation, StructHandle,
-        StructHandleIndex, TableIndex,
+        CompiledModule, CompiledScript, Constant, FieldDefinition, FunctionHandle,
+        FunctionHandleIndex, FunctionInstantiation, ModuleHandle, Signature,
+        StructFieldInformation, StructHandle, StructHandleIndex, TableIndex,
     },
     IndexKind,
 };
@@ -47,6 +47,10 @@ impl<'a> DuplicationChecker<'a> {
         let checker = Self { module };
         checker.check_field_handles()?;
         checker.check_field_instantiations()?;
+        checker.check_struct_variant_handles()?;
+        checker.check_struct_variant_instantiations()?;
+        checker.check_variant_field_handles()?;
+        checker.check_variant_field_instantiations()?;
         checker.check_function_defintions()?;
         checker.check_struct_definitions()?;
         checker.check_struct_instantiations()
@@ -174,6 +178,50 @@ impl<'a> DuplicationChecker<'a> {
         }
     }
 
+    fn check_struct_variant_handles(&self) -> PartialVMResult<()> {
+        match Self::first_duplicate_element(self.module.struct_variant_handles()) {
+            Some(idx) => Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::StructVariantHandle,
+                idx,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_struct_variant_instantiations(&self) -> PartialVMResult<()> {
+        match Self::first_duplicate_element(self.module.struct_variant_instantiations()) {
+            Some(idx) => Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::StructVariantInstantiation,
+                idx,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_variant_field_handles(&self) -> PartialVMResult<()> {
+        match Self::first_duplicate_element(self.module.variant_field_handles()) {
+            Some(idx) => Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::VariantFieldHandle,
+                idx,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_variant_field_instantiations(&self) -> PartialVMResult<()> {
+        match Self::first_duplicate_element(self.module.variant_field_instantiations()) {
+            Some(idx) => Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::VariantFieldInstantiation,
+                idx,
+            )),
+            None => Ok(()),
+        }
+    }
+
     fn check_struct_instantiations(&self) -> PartialVMResult<()> {
         match Self::first_duplicate_element(self.module.struct_instantiations()) {
             Some(idx) => Err(verification_error(
@@ -209,23 +257,41 @@ impl<'a> DuplicationChecker<'a> {
         }
         // Field names in structs must be unique
         for (struct_idx, struct_def) in self.module.struct_defs().iter().enumerate() {
-            let fields = match &struct_def.field_information {
+            match &struct_def.field_information {
                 StructFieldInformation::Native => continue,
-                StructFieldInformation::Declared(fields) => fields,
-            };
-            if fields.is_empty() {
-                return Err(verification_error(
-                    StatusCode::ZERO_SIZED_STRUCT,
-                    IndexKind::StructDefinition,
-                    struct_idx as TableIndex,
-                ));
-            }
-            if let Some(idx) = Self::first_duplicate_element(fields.iter().map(|x| x.name)) {
-                return Err(verification_error(
-                    StatusCode::DUPLICATE_ELEMENT,
-                    IndexKind::FieldDefinition,
-                    idx,
-                ));
+                StructFieldInformation::Declared(fields) => {
+                    if fields.is_empty() {
+                        return Err(verification_error(
+                            StatusCode::ZERO_SIZED_STRUCT,
+                            IndexKind::StructDefinition,
+                            struct_idx as TableIndex,
+                        ));
+                    }
+                    Self::check_field_names(fields)?
+                }
+                StructFieldInformation::DeclaredVariants(variants) => {
+                    // A struct with variants must have at least one variant, but individual
+                    // variants may be empty
+                    if variants.is_empty() {
+                        return Err(verification_error(
+                            StatusCode::ZERO_SIZED_STRUCT,
+                            IndexKind::StructDefinition,
+                            struct_idx as TableIndex,
+                        ));
+                    }
+                    if let Some(idx) =
+                        Self::first_duplicate_element(variants.iter().map(|x| x.name))
+                    {
+                        return Err(verification_error(
+                            StatusCode::DUPLICATE_ELEMENT,
+                            IndexKind::VariantDefinition,
+                            idx,
+                        ));
+                    }
+                    for variant in variants {
+                        Self::check_field_names(&variant.fields)?
+                    }
+                }
             }
         }
         // Check that each struct definition is pointing to the self module
@@ -259,6 +325,17 @@ impl<'a> DuplicationChecker<'a> {
         Ok(())
     }
 
+    fn check_field_names(fields: &[FieldDefinition]) -> PartialVMResult<()> {
+        match Self::first_duplicate_element(fields.iter().map(|x| x.name)) {
+            Some(idx) => Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::FieldDefinition,
+                idx,
+            )),
+            None => Ok(()),
+        }
+    }
+
     fn check_function_defintions(&self) -> PartialVMResult<()> {
         // FunctionDefinition - contained FunctionHandle defines uniqueness
         if let Some(idx) =
@@ -12,7 +12,8 @@ use move_binary_format::{
     errors::{Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
         Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript, FieldHandleIndex,
-        FunctionDefinitionIndex, FunctionHandleIndex, StructDefinitionIndex, TableIndex,
+        FunctionDefinitionIndex, FunctionHandleIndex, StructDefinitionIndex,
+        StructVariantHandleIndex, TableIndex, VariantFieldHandleIndex,
     },
 };
 use move_core_types::vm_status::StatusCode;
@@ -84,19 +85,41 @@ impl<'a> InstructionConsistency<'a> {
                     self.check_function_op(offset, func_inst.handle, /* generic */ true)?;
                 }
                 Pack(idx) => {
+                    self.check_plain_struct_op(offset, *idx)?;
                     self.check_type_op(offset, *idx, /* generic */ false)?;
                 }
                 PackGeneric(idx) => {
                     let struct_inst = self.resolver.struct_instantiation_at(*idx)?;
+                    self.check_plain_struct_op(offset, struct_inst.def)?;
                     self.check_type_op(offset, struct_inst.def, /* generic */ true)?;
                 }
                 Unpack(idx) => {
+                    self.check_plain_struct_op(offset, *idx)?;
                     self.check_type_op(offset, *idx, /* generic */ false)?;
                 }
                 UnpackGeneric(idx) => {
                     let struct_inst = self.resolver.struct_instantiation_at(*idx)?;
+                    self.check_plain_struct_op(offset, struct_inst.def)?;
                     self.check_type_op(offset, struct_inst.def, /* generic */ true)?;
                 }
+                PackVariant(idx) | UnpackVariant(idx) | TestVariant(idx) => {
+                    self.check_variant_op(offset, *idx, /* generic */ false)?;
+                }
+                PackVariantGeneric(idx) | UnpackVariantGeneric(idx) | TestVariantGeneric(idx) => {
+                    let variant_inst = self.resolver.struct_variant_instantiation_at(*idx)?;
+                    self.check_variant_op(offset, variant_inst.handle, /* generic */ true)?;
+                }
+                MutBorrowVariantField(idx) | ImmBorrowVariantField(idx) => {
+                    self.check_variant_field_op(offset, *idx, /* generic */ false)?;
+                }
+                MutBorrowVariantFieldGeneric(idx) | ImmBorrowVariantFieldGeneric(idx) => {
+                    let field_inst = self.resolver.variant_field_instantiation_at(*idx)?;
+                    self.check_variant_field_op(
+                        offset,
+                        field_inst.handle,
+                        /* generic */ true,
+                    )?;
+                }
                 MutBorrowGlobal(idx) => {
                     self.check_type_op(offset, *idx, /* generic */ false)?;
                 }
@@ -170,6 +193,43 @@ impl<'a> InstructionConsistency<'a> {
         self.check_type_op(offset, field_handle.owner, generic)
     }
 
+    fn check_variant_op(
+        &self,
+        offset: usize,
+        variant_handle_index: StructVariantHandleIndex,
+        generic: bool,
+    ) -> PartialVMResult<()> {
+        let variant_handle = self
+            .resolver
+            .struct_variant_handle_at(variant_handle_index)?;
+        self.check_type_op(offset, variant_handle.struct_index, generic)
+    }
+
+    fn check_variant_field_op(
+        &self,
+        offset: usize,
+        field_handle_index: VariantFieldHandleIndex,
+        generic: bool,
+    ) -> PartialVMResult<()> {
+        let field_handle = self.resolver.variant_field_handle_at(field_handle_index)?;
+        self.check_type_op(offset, field_handle.owner, generic)
+    }
+
+    // Pack and Unpack must not be used on structs declared with variants; those are
+    // constructed and destructed with the variant instructions instead.
+    fn check_plain_struct_op(
+        &self,
+        offset: usize,
+        struct_def_index: StructDefinitionIndex,
+    ) -> PartialVMResult<()> {
+        let struct_def = self.resolver.struct_def_at(struct_def_index)?;
+        if struct_def.has_variants() {
+            return Err(PartialVMError::new(StatusCode::STRUCT_VARIANT_MISMATCH)
+                .at_code_offset(self.current_function(), offset as CodeOffset));
+        }
+        Ok(())
+    }
+
     fn current_function(&self) -> FunctionDefinitionIndex {
         self.current_function.unwrap_or(FunctionDefinitionIndex(0))
     }
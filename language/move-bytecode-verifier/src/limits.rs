@@ -164,6 +164,17 @@ impl<'a> LimitsVerifier<'a> {
                                 ));
                             }
                         }
+
+                        StructFieldInformation::DeclaredVariants(variants) => {
+                            if variants
+                                .iter()
+                                .any(|variant| variant.fields.len() > max_fields_in_struct)
+                            {
+                                return Err(PartialVMError::new(
+                                    StatusCode::MAX_FIELD_DEFINITIONS_REACHED,
+                                ));
+                            }
+                        }
                     }
                 }
             }
@@ -107,6 +107,16 @@ fn execute_inner(
         | Bytecode::PackGeneric(_)
         | Bytecode::Unpack(_)
         | Bytecode::UnpackGeneric(_)
+        | Bytecode::PackVariant(_)
+        | Bytecode::PackVariantGeneric(_)
+        | Bytecode::UnpackVariant(_)
+        | Bytecode::UnpackVariantGeneric(_)
+        | Bytecode::TestVariant(_)
+        | Bytecode::TestVariantGeneric(_)
+        | Bytecode::MutBorrowVariantField(_)
+        | Bytecode::MutBorrowVariantFieldGeneric(_)
+        | Bytecode::ImmBorrowVariantField(_)
+        | Bytecode::ImmBorrowVariantFieldGeneric(_)
         | Bytecode::ReadRef
         | Bytecode::WriteRef
         | Bytecode::CastU8
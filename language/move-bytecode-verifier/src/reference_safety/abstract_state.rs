@@ -9,7 +9,7 @@ use move_binary_format::{
     errors::{PartialVMError, PartialVMResult},
     file_format::{
         CodeOffset, FieldHandleIndex, FunctionDefinitionIndex, LocalIndex, Signature,
-        SignatureToken, StructDefinitionIndex,
+        SignatureToken, StructDefinitionIndex, VariantFieldHandleIndex,
     },
     safe_unwrap,
 };
@@ -51,11 +51,12 @@ impl AbstractValue {
 }
 
 /// Label is an element of a label on an edge in the borrow graph.
-#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 enum Label {
     Local(LocalIndex),
     Global(StructDefinitionIndex),
     Field(FieldHandleIndex),
+    VariantField(VariantFieldHandleIndex),
 }
 
 // Needed for debugging with the borrow graph
@@ -65,6 +66,7 @@ impl std::fmt::Display for Label {
             Label::Local(i) => write!(f, "local#{}", i),
             Label::Global(i) => write!(f, "resource@{}", i),
             Label::Field(i) => write!(f, "field#{}", i),
+            Label::VariantField(i) => write!(f, "variant_field#{}", i),
         }
     }
 }
@@ -147,9 +149,9 @@ impl AbstractState {
         self.borrow_graph.add_weak_borrow((), parent, child)
     }
 
-    fn add_field_borrow(&mut self, parent: RefID, field: FieldHandleIndex, child: RefID) {
+    fn add_field_borrow(&mut self, parent: RefID, field: Label, child: RefID) {
         self.borrow_graph
-            .add_strong_field_borrow((), parent, Label::Field(field), child)
+            .add_strong_field_borrow((), parent, field, child)
     }
 
     fn add_local_borrow(&mut self, local: LocalIndex, id: RefID) {
@@ -223,15 +225,15 @@ impl AbstractState {
     /// checks if `id` is freezable
     /// - Mutable references are freezable if there are no consistent mutable borrows
     /// - Immutable references are not freezable by the typing rules
-    fn is_freezable(&self, id: RefID, at_field_opt: Option<FieldHandleIndex>) -> bool {
+    fn is_freezable(&self, id: RefID, at_field_opt: Option<Label>) -> bool {
         assert!(self.borrow_graph.is_mutable(id));
-        !self.has_consistent_mutable_borrows(id, at_field_opt.map(Label::Field))
+        !self.has_consistent_mutable_borrows(id, at_field_opt)
     }
 
     /// checks if `id` is readable
     /// - Mutable references are readable if they are freezable
     /// - Immutable references are always readable
-    fn is_readable(&self, id: RefID, at_field_opt: Option<FieldHandleIndex>) -> bool {
+    fn is_readable(&self, id: RefID, at_field_opt: Option<Label>) -> bool {
         let is_mutable = self.borrow_graph.is_mutable(id);
         !is_mutable || self.is_freezable(id, at_field_opt)
     }
@@ -409,6 +411,26 @@ impl AbstractState {
         mut_: bool,
         id: RefID,
         field: FieldHandleIndex,
+    ) -> PartialVMResult<AbstractValue> {
+        self.borrow_field_impl(offset, mut_, id, Label::Field(field))
+    }
+
+    pub fn borrow_variant_field(
+        &mut self,
+        offset: CodeOffset,
+        mut_: bool,
+        id: RefID,
+        field: VariantFieldHandleIndex,
+    ) -> PartialVMResult<AbstractValue> {
+        self.borrow_field_impl(offset, mut_, id, Label::VariantField(field))
+    }
+
+    fn borrow_field_impl(
+        &mut self,
+        offset: CodeOffset,
+        mut_: bool,
+        id: RefID,
+        field: Label,
     ) -> PartialVMResult<AbstractValue> {
         // Any field borrows will be factored out, so don't check in the mutable case
         let is_mut_borrow_with_full_borrows = || mut_ && self.has_full_borrows(id);
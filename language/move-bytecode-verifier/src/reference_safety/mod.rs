@@ -17,7 +17,7 @@ use move_binary_format::{
     errors::{PartialVMError, PartialVMResult},
     file_format::{
         Bytecode, CodeOffset, FunctionDefinitionIndex, FunctionHandle, IdentifierIndex,
-        SignatureIndex, SignatureToken, StructDefinition, StructFieldInformation,
+        SignatureIndex, SignatureToken, StructDefinition, StructFieldInformation, VariantIndex,
     },
     safe_assert, safe_unwrap,
 };
@@ -93,16 +93,19 @@ fn call(
 
 fn num_fields(struct_def: &StructDefinition) -> usize {
     match &struct_def.field_information {
-        StructFieldInformation::Native => 0,
+        StructFieldInformation::Native | StructFieldInformation::DeclaredVariants(_) => 0,
         StructFieldInformation::Declared(fields) => fields.len(),
     }
 }
 
-fn pack(
-    verifier: &mut ReferenceSafetyAnalysis,
-    struct_def: &StructDefinition,
-) -> PartialVMResult<()> {
-    for _ in 0..num_fields(struct_def) {
+fn num_variant_fields(struct_def: &StructDefinition, variant: VariantIndex) -> usize {
+    struct_def
+        .variant(variant)
+        .map_or(0, |variant| variant.fields.len())
+}
+
+fn pack(verifier: &mut ReferenceSafetyAnalysis, num_fields: usize) -> PartialVMResult<()> {
+    for _ in 0..num_fields {
         safe_assert!(safe_unwrap!(verifier.stack.pop()).is_value())
     }
     // TODO maybe call state.value_for
@@ -110,13 +113,10 @@ fn pack(
     Ok(())
 }
 
-fn unpack(
-    verifier: &mut ReferenceSafetyAnalysis,
-    struct_def: &StructDefinition,
-) -> PartialVMResult<()> {
+fn unpack(verifier: &mut ReferenceSafetyAnalysis, num_fields: usize) -> PartialVMResult<()> {
     safe_assert!(safe_unwrap!(verifier.stack.pop()).is_value());
     // TODO maybe call state.value_for
-    for _ in 0..num_fields(struct_def) {
+    for _ in 0..num_fields {
         verifier.stack.push(AbstractValue::NonReference)
     }
     Ok(())
@@ -213,6 +213,33 @@ fn execute_inner(
             verifier.stack.push(value)
         }
 
+        Bytecode::MutBorrowVariantField(field_handle_index) => {
+            let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+            let value = state.borrow_variant_field(offset, true, id, *field_handle_index)?;
+            verifier.stack.push(value)
+        }
+        Bytecode::MutBorrowVariantFieldGeneric(field_inst_index) => {
+            let field_inst = verifier
+                .resolver
+                .variant_field_instantiation_at(*field_inst_index)?;
+            let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+            let value = state.borrow_variant_field(offset, true, id, field_inst.handle)?;
+            verifier.stack.push(value)
+        }
+        Bytecode::ImmBorrowVariantField(field_handle_index) => {
+            let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+            let value = state.borrow_variant_field(offset, false, id, *field_handle_index)?;
+            verifier.stack.push(value)
+        }
+        Bytecode::ImmBorrowVariantFieldGeneric(field_inst_index) => {
+            let field_inst = verifier
+                .resolver
+                .variant_field_instantiation_at(*field_inst_index)?;
+            let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+            let value = state.borrow_variant_field(offset, false, id, field_inst.handle)?;
+            verifier.stack.push(value)
+        }
+
         Bytecode::MutBorrowGlobal(idx) => {
             safe_assert!(safe_unwrap!(verifier.stack.pop()).is_value());
             let value = state.borrow_global(offset, true, *idx)?;
@@ -327,21 +354,74 @@ fn execute_inner(
 
         Bytecode::Pack(idx) => {
             let struct_def = verifier.resolver.struct_def_at(*idx)?;
-            pack(verifier, struct_def)?
+            pack(verifier, num_fields(struct_def))?
         }
         Bytecode::PackGeneric(idx) => {
             let struct_inst = verifier.resolver.struct_instantiation_at(*idx)?;
             let struct_def = verifier.resolver.struct_def_at(struct_inst.def)?;
-            pack(verifier, struct_def)?
+            pack(verifier, num_fields(struct_def))?
         }
         Bytecode::Unpack(idx) => {
             let struct_def = verifier.resolver.struct_def_at(*idx)?;
-            unpack(verifier, struct_def)?
+            unpack(verifier, num_fields(struct_def))?
         }
         Bytecode::UnpackGeneric(idx) => {
             let struct_inst = verifier.resolver.struct_instantiation_at(*idx)?;
             let struct_def = verifier.resolver.struct_def_at(struct_inst.def)?;
-            unpack(verifier, struct_def)?
+            unpack(verifier, num_fields(struct_def))?
+        }
+
+        Bytecode::PackVariant(idx) => {
+            let variant_handle = verifier.resolver.struct_variant_handle_at(*idx)?;
+            let struct_def = verifier
+                .resolver
+                .struct_def_at(variant_handle.struct_index)?;
+            pack(
+                verifier,
+                num_variant_fields(struct_def, variant_handle.variant),
+            )?
+        }
+        Bytecode::PackVariantGeneric(idx) => {
+            let variant_inst = verifier.resolver.struct_variant_instantiation_at(*idx)?;
+            let variant_handle = verifier
+                .resolver
+                .struct_variant_handle_at(variant_inst.handle)?;
+            let struct_def = verifier
+                .resolver
+                .struct_def_at(variant_handle.struct_index)?;
+            pack(
+                verifier,
+                num_variant_fields(struct_def, variant_handle.variant),
+            )?
+        }
+        Bytecode::UnpackVariant(idx) => {
+            let variant_handle = verifier.resolver.struct_variant_handle_at(*idx)?;
+            let struct_def = verifier
+                .resolver
+                .struct_def_at(variant_handle.struct_index)?;
+            unpack(
+                verifier,
+                num_variant_fields(struct_def, variant_handle.variant),
+            )?
+        }
+        Bytecode::UnpackVariantGeneric(idx) => {
+            let variant_inst = verifier.resolver.struct_variant_instantiation_at(*idx)?;
+            let variant_handle = verifier
+                .resolver
+                .struct_variant_handle_at(variant_inst.handle)?;
+            let struct_def = verifier
+                .resolver
+                .struct_def_at(variant_handle.struct_index)?;
+            unpack(
+                verifier,
+                num_variant_fields(struct_def, variant_handle.variant),
+            )?
+        }
+        Bytecode::TestVariant(_) | Bytecode::TestVariantGeneric(_) => {
+            let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+            let value = state.read_ref(offset, id)?;
+            safe_assert!(value.is_value());
+            verifier.stack.push(state.value_for(&SignatureToken::Bool))
         }
 
         Bytecode::VecPack(idx, num) => {
@@ -136,6 +136,10 @@ fn too_many_locals() {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         signatures: vec![Signature(sign_128)],
         identifiers: vec![Identifier::new("x").unwrap()],
         address_identifiers: vec![AccountAddress::ONE],
@@ -184,6 +188,10 @@ fn borrow_graph() {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         signatures: vec![Signature(vec![
             Reference(Box::new(U64)),
             Reference(Box::new(U64)),
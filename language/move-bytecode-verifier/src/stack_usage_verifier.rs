@@ -14,7 +14,10 @@ use move_binary_format::{
     binary_views::{BinaryIndexedView, FunctionView},
     control_flow_graph::{BlockId, ControlFlowGraph},
     errors::{PartialVMError, PartialVMResult},
-    file_format::{Bytecode, CodeUnit, FunctionDefinitionIndex, Signature, StructFieldInformation},
+    file_format::{
+        Bytecode, CodeUnit, FunctionDefinitionIndex, Signature, StructFieldInformation,
+        StructVariantHandleIndex,
+    },
 };
 use move_core_types::vm_status::StatusCode;
 
@@ -225,8 +228,10 @@ impl<'a> StackUsageVerifier<'a> {
             Bytecode::Pack(idx) => {
                 let struct_definition = self.resolver.struct_def_at(*idx)?;
                 let field_count = match &struct_definition.field_information {
-                    // 'Native' here is an error that will be caught by the bytecode verifier later
-                    StructFieldInformation::Native => 0,
+                    // 'Native' and 'DeclaredVariants' here are errors that will be caught by the
+                    // bytecode verifier later
+                    StructFieldInformation::Native
+                    | StructFieldInformation::DeclaredVariants(_) => 0,
                     StructFieldInformation::Declared(fields) => fields.len(),
                 };
                 (field_count as u64, 1)
@@ -235,8 +240,10 @@ impl<'a> StackUsageVerifier<'a> {
                 let struct_inst = self.resolver.struct_instantiation_at(*idx)?;
                 let struct_definition = self.resolver.struct_def_at(struct_inst.def)?;
                 let field_count = match &struct_definition.field_information {
-                    // 'Native' here is an error that will be caught by the bytecode verifier later
-                    StructFieldInformation::Native => 0,
+                    // 'Native' and 'DeclaredVariants' here are errors that will be caught by the
+                    // bytecode verifier later
+                    StructFieldInformation::Native
+                    | StructFieldInformation::DeclaredVariants(_) => 0,
                     StructFieldInformation::Declared(fields) => fields.len(),
                 };
                 (field_count as u64, 1)
@@ -246,8 +253,10 @@ impl<'a> StackUsageVerifier<'a> {
             Bytecode::Unpack(idx) => {
                 let struct_definition = self.resolver.struct_def_at(*idx)?;
                 let field_count = match &struct_definition.field_information {
-                    // 'Native' here is an error that will be caught by the bytecode verifier later
-                    StructFieldInformation::Native => 0,
+                    // 'Native' and 'DeclaredVariants' here are errors that will be caught by the
+                    // bytecode verifier later
+                    StructFieldInformation::Native
+                    | StructFieldInformation::DeclaredVariants(_) => 0,
                     StructFieldInformation::Declared(fields) => fields.len(),
                 };
                 (1, field_count as u64)
@@ -256,15 +265,56 @@ impl<'a> StackUsageVerifier<'a> {
                 let struct_inst = self.resolver.struct_instantiation_at(*idx)?;
                 let struct_definition = self.resolver.struct_def_at(struct_inst.def)?;
                 let field_count = match &struct_definition.field_information {
-                    // 'Native' here is an error that will be caught by the bytecode verifier later
-                    StructFieldInformation::Native => 0,
+                    // 'Native' and 'DeclaredVariants' here are errors that will be caught by the
+                    // bytecode verifier later
+                    StructFieldInformation::Native
+                    | StructFieldInformation::DeclaredVariants(_) => 0,
                     StructFieldInformation::Declared(fields) => fields.len(),
                 };
                 (1, field_count as u64)
             }
+
+            // PackVariant performs `num_fields` of the variant pops and one push
+            Bytecode::PackVariant(idx) => {
+                let field_count = self.variant_field_count(*idx)?;
+                (field_count as u64, 1)
+            }
+            Bytecode::PackVariantGeneric(idx) => {
+                let variant_inst = self.resolver.struct_variant_instantiation_at(*idx)?;
+                let field_count = self.variant_field_count(variant_inst.handle)?;
+                (field_count as u64, 1)
+            }
+
+            // UnpackVariant performs one pop and `num_fields` of the variant pushes
+            Bytecode::UnpackVariant(idx) => {
+                let field_count = self.variant_field_count(*idx)?;
+                (1, field_count as u64)
+            }
+            Bytecode::UnpackVariantGeneric(idx) => {
+                let variant_inst = self.resolver.struct_variant_instantiation_at(*idx)?;
+                let field_count = self.variant_field_count(variant_inst.handle)?;
+                (1, field_count as u64)
+            }
+
+            // Variant tests and variant field borrows pop and push once
+            Bytecode::TestVariant(_)
+            | Bytecode::TestVariantGeneric(_)
+            | Bytecode::MutBorrowVariantField(_)
+            | Bytecode::MutBorrowVariantFieldGeneric(_)
+            | Bytecode::ImmBorrowVariantField(_)
+            | Bytecode::ImmBorrowVariantFieldGeneric(_) => (1, 1),
         })
     }
 
+    fn variant_field_count(&self, idx: StructVariantHandleIndex) -> PartialVMResult<usize> {
+        let variant_handle = self.resolver.struct_variant_handle_at(idx)?;
+        let struct_definition = self.resolver.struct_def_at(variant_handle.struct_index)?;
+        // a missing variant here is an error that will be caught by the bytecode verifier later
+        Ok(struct_definition
+            .variant(variant_handle.variant)
+            .map_or(0, |variant| variant.fields.len()))
+    }
+
     fn current_function(&self) -> FunctionDefinitionIndex {
         self.current_function.unwrap_or(FunctionDefinitionIndex(0))
     }
@@ -13,6 +13,7 @@ use move_binary_format::{
         AbilitySet, Bytecode, CodeOffset, FieldHandleIndex, FunctionDefinitionIndex,
         FunctionHandle, LocalIndex, Signature, SignatureToken, SignatureToken as ST,
         StructDefinition, StructDefinitionIndex, StructFieldInformation, StructHandleIndex,
+        StructVariantHandleIndex, VariantFieldHandleIndex, VariantIndex,
     },
     safe_unwrap,
 };
@@ -122,7 +123,7 @@ fn borrow_field(
     }
 
     let field_def = match &struct_def.field_information {
-        StructFieldInformation::Native => {
+        StructFieldInformation::Native | StructFieldInformation::DeclaredVariants(_) => {
             return Err(verifier.error(StatusCode::BORROWFIELD_BAD_FIELD_ERROR, offset));
         }
         StructFieldInformation::Declared(fields) => {
@@ -141,6 +142,45 @@ fn borrow_field(
     Ok(())
 }
 
+// helper for both `ImmBorrowVariantField` and `MutBorrowVariantField`
+fn borrow_variant_field(
+    verifier: &mut TypeSafetyChecker,
+    offset: CodeOffset,
+    mut_: bool,
+    field_handle_index: VariantFieldHandleIndex,
+    type_args: &Signature,
+) -> PartialVMResult<()> {
+    // load operand and check mutability constraints
+    let operand = safe_unwrap!(verifier.stack.pop());
+    if mut_ && !operand.is_mutable_reference() {
+        return Err(verifier.error(StatusCode::BORROWFIELD_TYPE_MISMATCH_ERROR, offset));
+    }
+
+    // check the reference on the stack is the expected type
+    let field_handle = verifier
+        .resolver
+        .variant_field_handle_at(field_handle_index)?;
+    let struct_def = verifier.resolver.struct_def_at(field_handle.owner)?;
+    let expected_type = materialize_type(struct_def.struct_handle, type_args);
+    match operand {
+        ST::Reference(inner) | ST::MutableReference(inner) if expected_type == *inner => (),
+        _ => return Err(verifier.error(StatusCode::BORROWFIELD_TYPE_MISMATCH_ERROR, offset)),
+    }
+
+    let field_def =
+        match struct_def.variant_field(field_handle.variant, field_handle.field as usize) {
+            Some(field_def) => field_def,
+            None => return Err(verifier.error(StatusCode::BORROWFIELD_BAD_FIELD_ERROR, offset)),
+        };
+    let field_type = Box::new(instantiate(&field_def.signature.0, type_args));
+    verifier.stack.push(if mut_ {
+        ST::MutableReference(field_type)
+    } else {
+        ST::Reference(field_type)
+    });
+    Ok(())
+}
+
 // helper for both `ImmBorrowLoc` and `MutBorrowLoc`
 fn borrow_loc(
     verifier: &mut TypeSafetyChecker,
@@ -209,35 +249,42 @@ fn call(
     Ok(())
 }
 
+// `variant` is `None` for plain struct operations and names the variant for variant operations
 fn type_fields_signature(
     verifier: &mut TypeSafetyChecker,
     offset: CodeOffset,
     struct_def: &StructDefinition,
+    variant: Option<VariantIndex>,
     type_args: &Signature,
 ) -> PartialVMResult<Signature> {
-    match &struct_def.field_information {
-        StructFieldInformation::Native => {
+    let fields = match (&struct_def.field_information, variant) {
+        (StructFieldInformation::Declared(fields), None) => fields,
+        (StructFieldInformation::DeclaredVariants(variants), Some(variant))
+            if (variant as usize) < variants.len() =>
+        {
+            &variants[variant as usize].fields
+        }
+        _ => {
             // TODO: this is more of "unreachable"
-            Err(verifier.error(StatusCode::PACK_TYPE_MISMATCH_ERROR, offset))
-        }
-        StructFieldInformation::Declared(fields) => {
-            let mut field_sig = vec![];
-            for field_def in fields.iter() {
-                field_sig.push(instantiate(&field_def.signature.0, type_args));
-            }
-            Ok(Signature(field_sig))
+            return Err(verifier.error(StatusCode::PACK_TYPE_MISMATCH_ERROR, offset));
         }
+    };
+    let mut field_sig = vec![];
+    for field_def in fields.iter() {
+        field_sig.push(instantiate(&field_def.signature.0, type_args));
     }
+    Ok(Signature(field_sig))
 }
 
 fn pack(
     verifier: &mut TypeSafetyChecker,
     offset: CodeOffset,
     struct_def: &StructDefinition,
+    variant: Option<VariantIndex>,
     type_args: &Signature,
 ) -> PartialVMResult<()> {
     let struct_type = materialize_type(struct_def.struct_handle, type_args);
-    let field_sig = type_fields_signature(verifier, offset, struct_def, type_args)?;
+    let field_sig = type_fields_signature(verifier, offset, struct_def, variant, type_args)?;
     for sig in field_sig.0.iter().rev() {
         let arg = safe_unwrap!(verifier.stack.pop());
         if &arg != sig {
@@ -253,6 +300,7 @@ fn unpack(
     verifier: &mut TypeSafetyChecker,
     offset: CodeOffset,
     struct_def: &StructDefinition,
+    variant: Option<VariantIndex>,
     type_args: &Signature,
 ) -> PartialVMResult<()> {
     let struct_type = materialize_type(struct_def.struct_handle, type_args);
@@ -264,13 +312,31 @@ fn unpack(
         return Err(verifier.error(StatusCode::UNPACK_TYPE_MISMATCH_ERROR, offset));
     }
 
-    let field_sig = type_fields_signature(verifier, offset, struct_def, type_args)?;
+    let field_sig = type_fields_signature(verifier, offset, struct_def, variant, type_args)?;
     for sig in field_sig.0 {
         verifier.stack.push(sig)
     }
     Ok(())
 }
 
+fn test_variant(
+    verifier: &mut TypeSafetyChecker,
+    offset: CodeOffset,
+    struct_def: &StructDefinition,
+    type_args: &Signature,
+) -> PartialVMResult<()> {
+    let struct_type = materialize_type(struct_def.struct_handle, type_args);
+    let operand = safe_unwrap!(verifier.stack.pop());
+    match operand {
+        ST::Reference(inner) | ST::MutableReference(inner) if struct_type == *inner => (),
+        _ => {
+            return Err(verifier.error(StatusCode::TEST_VARIANT_TYPE_MISMATCH_ERROR, offset));
+        }
+    }
+    verifier.stack.push(ST::Bool);
+    Ok(())
+}
+
 fn exists(
     verifier: &mut TypeSafetyChecker,
     offset: CodeOffset,
@@ -345,6 +411,17 @@ fn move_to(
     }
 }
 
+fn variant_def<'a>(
+    verifier: &TypeSafetyChecker<'a>,
+    idx: StructVariantHandleIndex,
+) -> PartialVMResult<(&'a StructDefinition, VariantIndex)> {
+    let variant_handle = verifier.resolver.struct_variant_handle_at(idx)?;
+    let struct_def = verifier
+        .resolver
+        .struct_def_at(variant_handle.struct_index)?;
+    Ok((struct_def, variant_handle.variant))
+}
+
 fn borrow_vector_element(
     verifier: &mut TypeSafetyChecker,
     declared_element_type: &SignatureToken,
@@ -531,26 +608,118 @@ fn verify_instr(
 
         Bytecode::Pack(idx) => {
             let struct_definition = verifier.resolver.struct_def_at(*idx)?;
-            pack(verifier, offset, struct_definition, &Signature(vec![]))?
+            pack(
+                verifier,
+                offset,
+                struct_definition,
+                None,
+                &Signature(vec![]),
+            )?
         }
 
         Bytecode::PackGeneric(idx) => {
             let struct_inst = verifier.resolver.struct_instantiation_at(*idx)?;
             let struct_def = verifier.resolver.struct_def_at(struct_inst.def)?;
             let type_args = verifier.resolver.signature_at(struct_inst.type_parameters);
-            pack(verifier, offset, struct_def, type_args)?
+            pack(verifier, offset, struct_def, None, type_args)?
         }
 
         Bytecode::Unpack(idx) => {
             let struct_definition = verifier.resolver.struct_def_at(*idx)?;
-            unpack(verifier, offset, struct_definition, &Signature(vec![]))?
+            unpack(
+                verifier,
+                offset,
+                struct_definition,
+                None,
+                &Signature(vec![]),
+            )?
         }
 
         Bytecode::UnpackGeneric(idx) => {
             let struct_inst = verifier.resolver.struct_instantiation_at(*idx)?;
             let struct_def = verifier.resolver.struct_def_at(struct_inst.def)?;
             let type_args = verifier.resolver.signature_at(struct_inst.type_parameters);
-            unpack(verifier, offset, struct_def, type_args)?
+            unpack(verifier, offset, struct_def, None, type_args)?
+        }
+
+        Bytecode::PackVariant(idx) => {
+            let (struct_def, variant) = variant_def(verifier, *idx)?;
+            pack(
+                verifier,
+                offset,
+                struct_def,
+                Some(variant),
+                &Signature(vec![]),
+            )?
+        }
+
+        Bytecode::PackVariantGeneric(idx) => {
+            let variant_inst = verifier.resolver.struct_variant_instantiation_at(*idx)?;
+            let (struct_def, variant) = variant_def(verifier, variant_inst.handle)?;
+            let type_args = verifier.resolver.signature_at(variant_inst.type_parameters);
+            pack(verifier, offset, struct_def, Some(variant), type_args)?
+        }
+
+        Bytecode::UnpackVariant(idx) => {
+            let (struct_def, variant) = variant_def(verifier, *idx)?;
+            unpack(
+                verifier,
+                offset,
+                struct_def,
+                Some(variant),
+                &Signature(vec![]),
+            )?
+        }
+
+        Bytecode::UnpackVariantGeneric(idx) => {
+            let variant_inst = verifier.resolver.struct_variant_instantiation_at(*idx)?;
+            let (struct_def, variant) = variant_def(verifier, variant_inst.handle)?;
+            let type_args = verifier.resolver.signature_at(variant_inst.type_parameters);
+            unpack(verifier, offset, struct_def, Some(variant), type_args)?
+        }
+
+        Bytecode::TestVariant(idx) => {
+            let (struct_def, _) = variant_def(verifier, *idx)?;
+            test_variant(verifier, offset, struct_def, &Signature(vec![]))?
+        }
+
+        Bytecode::TestVariantGeneric(idx) => {
+            let variant_inst = verifier.resolver.struct_variant_instantiation_at(*idx)?;
+            let (struct_def, _) = variant_def(verifier, variant_inst.handle)?;
+            let type_args = verifier.resolver.signature_at(variant_inst.type_parameters);
+            test_variant(verifier, offset, struct_def, type_args)?
+        }
+
+        Bytecode::MutBorrowVariantField(field_handle_index) => borrow_variant_field(
+            verifier,
+            offset,
+            true,
+            *field_handle_index,
+            &Signature(vec![]),
+        )?,
+
+        Bytecode::MutBorrowVariantFieldGeneric(field_inst_index) => {
+            let field_inst = verifier
+                .resolver
+                .variant_field_instantiation_at(*field_inst_index)?;
+            let type_inst = verifier.resolver.signature_at(field_inst.type_parameters);
+            borrow_variant_field(verifier, offset, true, field_inst.handle, type_inst)?
+        }
+
+        Bytecode::ImmBorrowVariantField(field_handle_index) => borrow_variant_field(
+            verifier,
+            offset,
+            false,
+            *field_handle_index,
+            &Signature(vec![]),
+        )?,
+
+        Bytecode::ImmBorrowVariantFieldGeneric(field_inst_index) => {
+            let field_inst = verifier
+                .resolver
+                .variant_field_instantiation_at(*field_inst_index)?;
+            let type_inst = verifier.resolver.signature_at(field_inst.type_parameters);
+            borrow_variant_field(verifier, offset, false, field_inst.handle, type_inst)?
         }
 
         Bytecode::ReadRef => {
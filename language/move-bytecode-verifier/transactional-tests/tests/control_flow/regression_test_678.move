@@ -6,6 +6,7 @@ module 0x42::test_case {
         /* ... */
     }
 
+    #[allow(unused_function)]
     fun remove_tx(foo: &mut Foo) {
         let i = 0;
         while (i < 64) {
@@ -5,6 +5,7 @@ use crate::parser::{parse_address_number, NumberFormat};
 use anyhow::anyhow;
 use move_core_types::account_address::AccountAddress;
 use num_bigint::BigUint;
+use serde::{Serialize, Serializer};
 use std::{fmt, hash::Hash};
 
 // Parsed Address, either a name or a numerical address
@@ -104,6 +105,13 @@ impl fmt::Debug for NumericalAddress {
     }
 }
 
+/// Serialized as displayed, in the format it was written in
+impl Serialize for NumericalAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl fmt::UpperHex for NumericalAddress {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let encoded = hex::encode_upper(self.as_ref());
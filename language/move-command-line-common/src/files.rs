@@ -2,13 +2,13 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{anyhow, bail, *};
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, bail};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use sha2::Digest;
 use std::{collections::BTreeMap, convert::TryInto, path::Path};
 
 /// Result of sha256 hash of a file's contents.
-#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct FileHash(pub [u8; 32]);
 
 impl FileHash {
@@ -25,6 +25,37 @@ impl FileHash {
     }
 }
 
+/// Serialized as a hex string in human readable formats, e.g. JSON
+impl Serialize for FileHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            hex::encode(self.0).serialize(serializer)
+        } else {
+            serializer.serialize_newtype_struct("FileHash", &self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FileHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = <String>::deserialize(deserializer)?;
+            let bytes = hex::decode(s).map_err(D::Error::custom)?;
+            let bytes = bytes
+                .try_into()
+                .map_err(|_| D::Error::custom("Invalid file hash length"))?;
+            Ok(Self(bytes))
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename = "FileHash")]
+            struct Value([u8; 32]);
+
+            let value = Value::deserialize(deserializer)?;
+            Ok(Self(value.0))
+        }
+    }
+}
+
 impl std::fmt::Display for FileHash {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         hex::encode(self.0).fmt(f)
@@ -7,8 +7,14 @@
 use clap::*;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
-    command_line::{self as cli},
-    shared::{self, Flags, NumericalAddress},
+    command_line::{self as cli, compiler::Pass},
+    diagnostics::{unwrap_or_report_diagnostics_with_format, DiagnosticsFormat},
+    shared::{
+        self,
+        ast_dump::{AstDumpFormat, AstDumpPass},
+        Flags, NumericalAddress,
+    },
+    Compiler, PASS_CFGIR, PASS_EXPANSION, PASS_TYPING,
 };
 
 #[derive(Debug, Parser)]
@@ -62,6 +68,19 @@ pub struct Options {
     )]
     pub named_addresses: Vec<(String, NumericalAddress)>,
 
+    /// Print the AST of a compiler pass instead of compiling to bytecode
+    #[clap(name = "DUMP_AST", long = cli::DUMP_AST, arg_enum)]
+    pub dump_ast: Option<AstDumpPass>,
+
+    /// The format in which the AST is printed with `--dump-ast`
+    #[clap(
+        long = cli::FORMAT,
+        arg_enum,
+        default_value = "json",
+        requires = "DUMP_AST",
+    )]
+    pub format: AstDumpFormat,
+
     #[clap(flatten)]
     pub flags: Flags,
 }
@@ -74,16 +93,33 @@ pub fn main() -> anyhow::Result<()> {
         emit_source_map,
         flags,
         named_addresses,
+        dump_ast,
+        format,
     } = Options::parse();
 
     let interface_files_dir = format!("{}/generated_interface_files", out_dir);
     let named_addr_map = verify_and_create_named_address_mapping(named_addresses)?;
     let bytecode_version = flags.bytecode_version();
-    let (files, compiled_units) =
-        move_compiler::Compiler::from_files(source_files, dependencies, named_addr_map)
-            .set_interface_files_dir(interface_files_dir)
-            .set_flags(flags)
-            .build_and_report()?;
+    let diagnostics_format = flags.diagnostics_format();
+    let compiler = Compiler::from_files(source_files, dependencies, named_addr_map)
+        .set_interface_files_dir(interface_files_dir)
+        .set_flags(flags);
+    if let Some(pass) = dump_ast {
+        let dump = match pass {
+            AstDumpPass::Expansion => {
+                dump_pass_ast::<PASS_EXPANSION>(compiler, format, diagnostics_format)?
+            }
+            AstDumpPass::Typing => {
+                dump_pass_ast::<PASS_TYPING>(compiler, format, diagnostics_format)?
+            }
+            AstDumpPass::Cfgir => {
+                dump_pass_ast::<PASS_CFGIR>(compiler, format, diagnostics_format)?
+            }
+        };
+        println!("{}", dump);
+        return Ok(());
+    }
+    let (files, compiled_units) = compiler.build_and_report()?;
     move_compiler::output_compiled_units(
         bytecode_version,
         emit_source_map,
@@ -92,3 +128,14 @@ pub fn main() -> anyhow::Result<()> {
         &out_dir,
     )
 }
+
+fn dump_pass_ast<const P: Pass>(
+    compiler: Compiler,
+    format: AstDumpFormat,
+    diagnostics_format: DiagnosticsFormat,
+) -> anyhow::Result<String> {
+    let (files, res) = compiler.run::<P>()?;
+    let (_comments, stepped) =
+        unwrap_or_report_diagnostics_with_format(diagnostics_format, &files, res);
+    stepped.dump_ast(&files, format)
+}
@@ -8,11 +8,12 @@ use crate::{
         BaseType, Command, Command_, FunctionSignature, Label, SingleType, StructDefinition,
     },
     parser::ast::{ConstantName, FunctionName, StructName, Var, ENTRY_MODIFIER},
-    shared::{ast_debug::*, unique_map::UniqueMap},
+    shared::{ast_debug::*, ast_dump::serialize_map_as_seq, unique_map::UniqueMap},
 };
 use move_core_types::value::MoveValue;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 // HLIR + Unstructured Control Flow + CFG
@@ -21,7 +22,7 @@ use std::collections::{BTreeMap, BTreeSet, VecDeque};
 // Program
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub modules: UniqueMap<ModuleIdent, ModuleDefinition>,
     pub scripts: BTreeMap<Symbol, Script>,
@@ -31,7 +32,7 @@ pub struct Program {
 // Scripts
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Script {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -46,7 +47,7 @@ pub struct Script {
 // Modules
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDefinition {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -64,19 +65,21 @@ pub struct ModuleDefinition {
 // Constants
 //**************************************************************************************************
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize)]
 pub struct Constant {
     pub attributes: Attributes,
     pub loc: Loc,
     pub signature: BaseType,
     pub value: Option<MoveValue>,
+    // the message of the assertion the constant is the abort code of, if generated for one
+    pub assertion_message: Option<String>,
 }
 
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub enum FunctionBody_ {
     Native,
     Defined {
@@ -88,12 +91,13 @@ pub enum FunctionBody_ {
 }
 pub type FunctionBody = Spanned<FunctionBody_>;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
     pub signature: FunctionSignature,
+    #[serde(serialize_with = "serialize_map_as_seq")]
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
 }
@@ -106,7 +110,7 @@ pub type BasicBlocks = BTreeMap<Label, BasicBlock>;
 
 pub type BasicBlock = VecDeque<Command>;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
 pub enum LoopEnd {
     // If the generated loop end block was not used
     Unused,
@@ -114,13 +118,13 @@ pub enum LoopEnd {
     Target(Label),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct LoopInfo {
     pub is_loop_stmt: bool,
     pub loop_end: LoopEnd,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum BlockInfo {
     LoopHead(LoopInfo),
     Other,
@@ -167,7 +171,7 @@ fn remap_labels_block(remapping: &BTreeMap<Label, Label>, block: &mut BasicBlock
 fn remap_labels_cmd(remapping: &BTreeMap<Label, Label>, sp!(_, cmd_): &mut Command) {
     use Command_::*;
     match cmd_ {
-        Break | Continue => panic!("ICE break/continue not translated to jumps"),
+        Break(_) | Continue(_) => panic!("ICE break/continue not translated to jumps"),
         Mutate(_, _) | Assign(_, _) | IgnoreAndPop { .. } | Abort(_) | Return { .. } => (),
         Jump { target, .. } => *target = remapping[target],
         JumpIf {
@@ -273,6 +277,7 @@ impl AstDebug for (ConstantName, &Constant) {
                 loc: _loc,
                 signature,
                 value,
+                assertion_message,
             },
         ) = self;
         attributes.ast_debug(w);
@@ -284,6 +289,9 @@ impl AstDebug for (ConstantName, &Constant) {
             Some(v) => v.ast_debug(w),
         }
         w.write(";");
+        if let Some(message) = assertion_message {
+            w.write(&format!(" /* assertion message: {:?} */", message));
+        }
     }
 }
 
@@ -13,7 +13,7 @@ use crate::{
 };
 use move_ir_types::location::*;
 use state::{Value, *};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 //**************************************************************************************************
 // Entry and trait bindings
@@ -85,6 +85,7 @@ pub fn verify(
     acquires: &BTreeMap<StructName, Loc>,
     locals: &UniqueMap<Var, SingleType>,
     cfg: &super::cfg::BlockCFG,
+    inlined_calls: &[InlinedCall],
 ) -> BTreeMap<Label, BorrowState> {
     // check for existing errors
     let has_errors = compilation_env.has_errors();
@@ -93,10 +94,43 @@ pub fn verify(
     let mut safety = BorrowSafety::new(locals);
     initial_state.canonicalize_locals(&safety.local_numbers);
     let (final_state, ds) = safety.analyze_function(cfg, initial_state);
-    compilation_env.add_diags(ds);
+    compilation_env.add_diags(label_inlined_calls(inlined_calls, ds));
     final_state
 }
 
+// Errors in code expanded from an inline function point into the body of that function, so label
+// the call sites the code was expanded at
+fn label_inlined_calls(inlined_calls: &[InlinedCall], ds: Diagnostics) -> Diagnostics {
+    if inlined_calls.is_empty() {
+        return ds;
+    }
+    let contains = |outer: &Loc, inner: &Loc| {
+        outer.file_hash() == inner.file_hash()
+            && outer.start() <= inner.start()
+            && inner.end() <= outer.end()
+    };
+    ds.into_vec()
+        .into_iter()
+        .map(|mut diag| {
+            let mut seen = BTreeSet::new();
+            let mut locs = diag.labeled_locs().collect::<Vec<_>>();
+            while let Some(loc) = locs.pop() {
+                for call in inlined_calls {
+                    if contains(&call.body_loc, &loc) && seen.insert(call.loc) {
+                        let msg = format!(
+                            "In this call to inline function '{}::{}'",
+                            call.module, call.function
+                        );
+                        diag.add_secondary_label((call.loc, msg));
+                        locs.push(call.loc);
+                    }
+                }
+            }
+            diag
+        })
+        .collect()
+}
+
 //**************************************************************************************************
 // Command
 //**************************************************************************************************
@@ -135,7 +169,7 @@ fn command(context: &mut Context, sp!(loc, cmd_): &Command) {
             context.borrow_state.abort()
         }
         C::Jump { .. } => (),
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     }
 }
 
@@ -155,7 +189,7 @@ fn lvalue(context: &mut Context, sp!(loc, l_): &LValue, value: Value) {
             let diags = context.borrow_state.assign_local(*loc, v, value);
             context.add_diags(diags)
         }
-        L::Unpack(_, _, fields) => {
+        L::Unpack(_, _, fields) | L::UnpackVariant(_, _, _, fields) => {
             assert!(!value.is_ref());
             fields
                 .iter()
@@ -204,6 +238,18 @@ fn exp(context: &mut Context, parent_e: &Exp) -> Values {
             context.add_diags(diags);
             vec![value]
         }
+        E::BorrowVariantField(mut_, e, _, f) => {
+            let evalue = assert_single_value(exp(context, e));
+            let (diags, value) = context.borrow_state.borrow_field(*eloc, *mut_, evalue, f);
+            context.add_diags(diags);
+            vec![value]
+        }
+        E::TestVariant(e, _) => {
+            let evalue = assert_single_value(exp(context, e));
+            let (diags, _) = context.borrow_state.dereference(*eloc, evalue);
+            context.add_diags(diags);
+            svalue()
+        }
 
         E::Builtin(b, e) => {
             let evalues = exp(context, e);
@@ -281,7 +327,7 @@ fn exp(context: &mut Context, parent_e: &Exp) -> Values {
             assert!(!v2.is_ref());
             svalue()
         }
-        E::Pack(_, _, fields) => {
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
             fields.iter().for_each(|(_, _, e)| {
                 let arg = exp(context, e);
                 assert!(!assert_single_value(arg).is_ref());
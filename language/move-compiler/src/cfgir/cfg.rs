@@ -264,7 +264,7 @@ fn unreachable_loc(sp!(_, cmd_): &Command) -> Option<Loc> {
         | C::IgnoreAndPop { exp: e, .. }
         | C::JumpIf { cond: e, .. } => unreachable_loc_exp(e),
         C::Jump { .. } => None,
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     }
 }
 
@@ -287,11 +287,15 @@ fn unreachable_loc_exp(parent_e: &Exp) -> Option<Loc> {
         | E::Dereference(e)
         | E::UnaryExp(_, e)
         | E::Borrow(_, e, _)
+        | E::BorrowVariantField(_, e, _, _)
+        | E::TestVariant(e, _)
         | E::Cast(e, _) => unreachable_loc_exp(e),
 
         E::BinopExp(e1, _, e2) => unreachable_loc_exp(e1).or_else(|| unreachable_loc_exp(e2)),
 
-        E::Pack(_, _, fields) => fields.iter().find_map(|(_, _, e)| unreachable_loc_exp(e)),
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+            fields.iter().find_map(|(_, _, e)| unreachable_loc_exp(e))
+        }
 
         E::ExpList(es) => es.iter().find_map(unreachable_loc_item),
     }
@@ -333,7 +337,7 @@ fn determine_infinite_loop_starts(
         .collect::<Vec<_>>();
 
     // Fully populate infinite loop starts to be pruned later
-    // And for any block, determine the current loop
+    // And for any block, determine the enclosing loops
     let mut infinite_loop_starts = BTreeSet::new();
 
     let mut loop_stack: Vec<(Label, LoopEnd)> = vec![];
@@ -355,26 +359,23 @@ fn determine_infinite_loop_starts(
             }
         }
 
-        current_loop_info.push(loop_stack.last().cloned());
+        current_loop_info.push(loop_stack.clone());
     }
 
     // Given the loop info for any block, determine which loops are infinite
     // Each 'loop' based loop starts in the set, and is removed if it's break is used, or if a
     // return or abort is used
+    // A labeled break can exit several loops at once, removing each of them
     let mut prev_opt: Option<Label> = None;
-    let zipped =
-        block_info
-            .into_iter()
-            .zip(current_loop_info)
-            .filter_map(|(block_info, cur_loop_opt)| {
-                cur_loop_opt.map(|cur_loop| (block_info, cur_loop))
-            });
-    for ((lbl, _info), (cur_loop_start, cur_loop_end)) in zipped {
+    let zipped = block_info
+        .into_iter()
+        .zip(current_loop_info)
+        .filter(|(_block_info, cur_loops)| !cur_loops.is_empty());
+    for ((lbl, _info), cur_loops) in zipped {
         debug_assert!(prev_opt.map(|prev| prev.0 < lbl.0).unwrap_or(true));
         maybe_unmark_infinite_loop_starts(
             &mut infinite_loop_starts,
-            cur_loop_start,
-            cur_loop_end,
+            &cur_loops,
             &cfg.blocks()[lbl],
         );
         prev_opt = Some(*lbl);
@@ -385,31 +386,37 @@ fn determine_infinite_loop_starts(
 
 fn maybe_unmark_infinite_loop_starts(
     infinite_loop_starts: &mut BTreeSet<Label>,
-    cur_loop_start: Label,
-    cur_loop_end: LoopEnd,
+    cur_loops: &[(Label, LoopEnd)],
     block: &BasicBlock,
 ) {
     use Command_ as C;
+    // Removes the loop ending at the target, along with all loops nested inside of it
+    let mut unmark_exited = |target: Label| {
+        let exited = cur_loops
+            .iter()
+            .position(|(_, loop_end)| loop_end.equals(target));
+        if let Some(idx) = exited {
+            for (loop_start, _) in &cur_loops[idx..] {
+                infinite_loop_starts.remove(loop_start);
+            }
+        }
+    };
     // jumps/return/abort are only found at the end of the block
     match &block.back().unwrap().value {
-        C::Jump { target, .. } if cur_loop_end.equals(*target) => {
-            infinite_loop_starts.remove(&cur_loop_start);
-        }
+        C::Jump { target, .. } => unmark_exited(*target),
         C::JumpIf {
             if_true, if_false, ..
-        } if cur_loop_end.equals(*if_true) || cur_loop_end.equals(*if_false) => {
-            infinite_loop_starts.remove(&cur_loop_start);
+        } => {
+            unmark_exited(*if_true);
+            unmark_exited(*if_false);
         }
         C::Return { .. } | C::Abort(_) => {
-            infinite_loop_starts.remove(&cur_loop_start);
+            let (cur_loop_start, _) = cur_loops.last().unwrap();
+            infinite_loop_starts.remove(cur_loop_start);
         }
 
-        C::Jump { .. }
-        | C::JumpIf { .. }
-        | C::Assign(_, _)
-        | C::Mutate(_, _)
-        | C::IgnoreAndPop { .. } => (),
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Assign(_, _) | C::Mutate(_, _) | C::IgnoreAndPop { .. } => (),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     }
 }
 
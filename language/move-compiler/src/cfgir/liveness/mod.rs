@@ -98,7 +98,7 @@ fn command(state: &mut LivenessState, sp!(_, cmd_): &Command) {
         | C::JumpIf { cond: e, .. } => exp(state, e),
 
         C::Jump { .. } => (),
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     }
 }
 
@@ -113,7 +113,9 @@ fn lvalue(state: &mut LivenessState, sp!(_, l_): &LValue) {
         L::Var(v, _) => {
             state.0.remove(v);
         }
-        L::Unpack(_, _, fields) => fields.iter().for_each(|(_, l)| lvalue(state, l)),
+        L::Unpack(_, _, fields) | L::UnpackVariant(_, _, _, fields) => {
+            fields.iter().for_each(|(_, l)| lvalue(state, l))
+        }
     }
 }
 
@@ -137,6 +139,8 @@ fn exp(state: &mut LivenessState, parent_e: &Exp) {
         | E::Dereference(e)
         | E::UnaryExp(_, e)
         | E::Borrow(_, e, _)
+        | E::BorrowVariantField(_, e, _, _)
+        | E::TestVariant(e, _)
         | E::Cast(e, _) => exp(state, e),
 
         E::BinopExp(e1, _, e2) => {
@@ -144,7 +148,9 @@ fn exp(state: &mut LivenessState, parent_e: &Exp) {
             exp(state, e2)
         }
 
-        E::Pack(_, _, fields) => fields.iter().for_each(|(_, _, e)| exp(state, e)),
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+            fields.iter().for_each(|(_, _, e)| exp(state, e))
+        }
 
         E::ExpList(es) => es.iter().for_each(|item| exp_list_item(state, item)),
 
@@ -280,7 +286,7 @@ mod last_usage {
             | C::JumpIf { cond: e, .. } => exp(context, e),
 
             C::Jump { .. } => (),
-            C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+            C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
         }
     }
 
@@ -316,7 +322,9 @@ mod last_usage {
                     }
                 }
             }
-            L::Unpack(_, _, fields) => fields.iter_mut().for_each(|(_, l)| lvalue(context, l)),
+            L::Unpack(_, _, fields) | L::UnpackVariant(_, _, _, fields) => {
+                fields.iter_mut().for_each(|(_, l)| lvalue(context, l))
+            }
         }
     }
 
@@ -358,6 +366,8 @@ mod last_usage {
             | E::Dereference(e)
             | E::UnaryExp(_, e)
             | E::Borrow(_, e, _)
+            | E::BorrowVariantField(_, e, _, _)
+            | E::TestVariant(e, _)
             | E::Cast(e, _) => exp(context, e),
 
             E::BinopExp(e1, _, e2) => {
@@ -365,7 +375,7 @@ mod last_usage {
                 exp(context, e1)
             }
 
-            E::Pack(_, _, fields) => fields
+            E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => fields
                 .iter_mut()
                 .rev()
                 .for_each(|(_, _, e)| exp(context, e)),
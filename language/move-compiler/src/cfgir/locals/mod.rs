@@ -189,7 +189,7 @@ fn command(context: &mut Context, sp!(loc, cmd_): &Command) {
             context.extend_diags(diags)
         }
         C::Jump { .. } => (),
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     }
 }
 
@@ -239,7 +239,9 @@ fn lvalue(context: &mut Context, sp!(loc, l_): &LValue) {
             }
             context.set_state(*v, LocalState::Available(*loc))
         }
-        L::Unpack(_, _, fields) => fields.iter().for_each(|(_, l)| lvalue(context, l)),
+        L::Unpack(_, _, fields) | L::UnpackVariant(_, _, _, fields) => {
+            fields.iter().for_each(|(_, l)| lvalue(context, l))
+        }
     }
 }
 
@@ -266,6 +268,8 @@ fn exp(context: &mut Context, parent_e: &Exp) {
         | E::Dereference(e)
         | E::UnaryExp(_, e)
         | E::Borrow(_, e, _)
+        | E::BorrowVariantField(_, e, _, _)
+        | E::TestVariant(e, _)
         | E::Cast(e, _) => exp(context, e),
 
         E::BinopExp(e1, _, e2) => {
@@ -273,7 +277,9 @@ fn exp(context: &mut Context, parent_e: &Exp) {
             exp(context, e2)
         }
 
-        E::Pack(_, _, fields) => fields.iter().for_each(|(_, _, e)| exp(context, e)),
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+            fields.iter().for_each(|(_, _, e)| exp(context, e))
+        }
 
         E::ExpList(es) => es.iter().for_each(|item| exp_list_item(context, item)),
 
@@ -404,6 +410,7 @@ fn add_drop_ability_tip(context: &Context, diag: &mut Diagnostic, st: SingleType
                 T::Unit => AbilitySet::collection(ty_arg.loc),
                 T::Ref(_, _) => AbilitySet::references(ty_arg.loc),
                 T::UnresolvedError | T::Anything => AbilitySet::all(ty_arg.loc),
+                T::Fun(_, _) => AbilitySet::empty(),
                 T::Param(TParam { abilities, .. }) | T::Apply(Some(abilities), _, _) => {
                     abilities.clone()
                 }
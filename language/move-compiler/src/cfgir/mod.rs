@@ -32,6 +32,7 @@ pub fn refine_inference_and_verify(
     locals: &UniqueMap<Var, SingleType>,
     cfg: &mut BlockCFG,
     infinite_loop_starts: &BTreeSet<Label>,
+    inlined_calls: &[InlinedCall],
 ) {
     liveness::last_usage(compilation_env, locals, cfg, infinite_loop_starts);
     let locals_states = locals::verify(
@@ -44,5 +45,12 @@ pub fn refine_inference_and_verify(
     );
 
     liveness::release_dead_refs(&locals_states, locals, cfg, infinite_loop_starts);
-    borrows::verify(compilation_env, signature, acquires, locals, cfg);
+    borrows::verify(
+        compilation_env,
+        signature,
+        acquires,
+        locals,
+        cfg,
+        inlined_calls,
+    );
 }
@@ -67,7 +67,7 @@ fn optimize_cmd(sp!(_, cmd_): &mut Command) -> Option<bool> {
         }
 
         C::Jump { .. } => false,
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     })
 }
 
@@ -88,9 +88,14 @@ fn optimize_exp(e: &mut Exp) -> bool {
         | E::Unreachable => false,
 
         E::ModuleCall(mcall) => optimize_exp(&mut mcall.arguments),
-        E::Builtin(_, e) | E::Freeze(e) | E::Dereference(e) | E::Borrow(_, e, _) => optimize_exp(e),
-
-        E::Pack(_, _, fields) => fields
+        E::Builtin(_, e)
+        | E::Freeze(e)
+        | E::Dereference(e)
+        | E::Borrow(_, e, _)
+        | E::BorrowVariantField(_, e, _, _)
+        | E::TestVariant(e, _) => optimize_exp(e),
+
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => fields
             .iter_mut()
             .map(|(_, _, e)| optimize_exp(e))
             .any(|changed| changed),
@@ -410,6 +415,88 @@ const fn evalue_(loc: Loc, v: Value_) -> UnannotatedExp_ {
     E::Value(sp(loc, v))
 }
 
+//**************************************************************************************************
+// Unfoldable operations
+//**************************************************************************************************
+
+/// Returns the location of the innermost operation in `e` that could not be folded even though
+/// its operands are values, along with the reason it could not be folded, e.g. an overflow.
+pub fn unfoldable_operation(e: &Exp) -> Option<(Loc, String)> {
+    use UnannotatedExp_ as E;
+    let loc = e.exp.loc;
+    match &e.exp.value {
+        E::UnaryExp(_, e) | E::Vector(_, _, _, e) => unfoldable_operation(e),
+        E::ExpList(items) => items.iter().find_map(|item| match item {
+            ExpListItem::Single(e, _) | ExpListItem::Splat(_, e, _) => unfoldable_operation(e),
+        }),
+        E::BinopExp(e1, sp!(_, op_), e2) => {
+            if let Some(inner) = unfoldable_operation(e1).or_else(|| unfoldable_operation(e2)) {
+                return Some(inner);
+            }
+            let (v1, v2) = (foldable_exp(e1)?, foldable_exp(e2)?);
+            let ty = value_type_name(&v1);
+            let reason = match op_ {
+                BinOp_::Add => format!("The addition overflows the maximum value of '{}'", ty),
+                BinOp_::Mul => {
+                    format!("The multiplication overflows the maximum value of '{}'", ty)
+                }
+                BinOp_::Sub => "The subtraction underflows zero".to_owned(),
+                BinOp_::Div | BinOp_::Mod => "Division by zero".to_owned(),
+                BinOp_::Shl | BinOp_::Shr => format!(
+                    "The shift amount '{}' must be less than the number of bits of '{}'",
+                    value_string(&v2),
+                    ty
+                ),
+                _ => return None,
+            };
+            Some((loc, reason))
+        }
+        E::Cast(e, sp!(_, bt_)) => {
+            if let Some(inner) = unfoldable_operation(e) {
+                return Some(inner);
+            }
+            let v = foldable_exp(e)?;
+            let reason = format!(
+                "The value '{}' does not fit in the range of '{}'",
+                value_string(&v),
+                bt_
+            );
+            Some((loc, reason))
+        }
+        _ => None,
+    }
+}
+
+fn value_type_name(v: &Value_) -> &'static str {
+    use Value_ as V;
+    match v {
+        V::U8(_) => "u8",
+        V::U16(_) => "u16",
+        V::U32(_) => "u32",
+        V::U64(_) => "u64",
+        V::U128(_) => "u128",
+        V::U256(_) => "u256",
+        V::Address(_) => "address",
+        V::Bool(_) => "bool",
+        V::Vector(_, _) => "vector",
+    }
+}
+
+fn value_string(v: &Value_) -> String {
+    use Value_ as V;
+    match v {
+        V::U8(u) => u.to_string(),
+        V::U16(u) => u.to_string(),
+        V::U32(u) => u.to_string(),
+        V::U64(u) => u.to_string(),
+        V::U128(u) => u.to_string(),
+        V::U256(u) => u.to_string(),
+        V::Address(a) => format!("@{}", a),
+        V::Bool(b) => b.to_string(),
+        V::Vector(_, _) => "vector".to_owned(),
+    }
+}
+
 //**************************************************************************************************
 // Foldable Value
 //**************************************************************************************************
@@ -125,7 +125,7 @@ mod count {
             | C::JumpIf { cond: e, .. } => exp(context, e),
 
             C::Jump { .. } => (),
-            C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+            C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
         }
     }
 
@@ -139,7 +139,7 @@ mod count {
     fn lvalue(context: &mut Context, sp!(_, l_): &LValue, substitutable: bool) {
         use LValue_ as L;
         match l_ {
-            L::Ignore | L::Unpack(_, _, _) => (),
+            L::Ignore | L::Unpack(_, _, _) | L::UnpackVariant(_, _, _, _) => (),
             L::Var(v, _) => context.assign(v, substitutable),
         }
     }
@@ -163,6 +163,8 @@ mod count {
             | E::Dereference(e)
             | E::UnaryExp(_, e)
             | E::Borrow(_, e, _)
+            | E::BorrowVariantField(_, e, _, _)
+            | E::TestVariant(e, _)
             | E::Cast(e, _) => exp(context, e),
 
             E::BinopExp(e1, _, e2) => {
@@ -170,7 +172,9 @@ mod count {
                 exp(context, e2)
             }
 
-            E::Pack(_, _, fields) => fields.iter().for_each(|(_, _, e)| exp(context, e)),
+            E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+                fields.iter().for_each(|(_, _, e)| exp(context, e))
+            }
 
             E::ExpList(es) => es.iter().for_each(|item| exp_list_item(context, item)),
 
@@ -219,7 +223,9 @@ mod count {
             | E::Dereference(_)
             | E::ModuleCall(_)
             | E::Move { .. }
-            | E::Borrow(_, _, _) => false,
+            | E::Borrow(_, _, _)
+            | E::BorrowVariantField(_, _, _, _)
+            | E::TestVariant(_, _) => false,
 
             E::Unit { .. } | E::Value(_) | E::Constant(_) => true,
 
@@ -229,7 +235,9 @@ mod count {
                 can_subst_exp_binary(op) && can_subst_exp_single(e1) && can_subst_exp_single(e2)
             }
             E::ExpList(es) => es.iter().all(can_subst_exp_item),
-            E::Pack(_, _, fields) => fields.iter().all(|(_, _, e)| can_subst_exp_single(e)),
+            E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+                fields.iter().all(|(_, _, e)| can_subst_exp_single(e))
+            }
             E::Vector(_, _, _, eargs) => can_subst_exp_single(eargs),
 
             E::Unreachable => panic!("ICE should not analyze dead code"),
@@ -315,7 +323,7 @@ mod eliminate {
             | C::JumpIf { cond: e, .. } => exp(context, e),
 
             C::Jump { .. } => (),
-            C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+            C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
         }
     }
 
@@ -340,7 +348,9 @@ mod eliminate {
     fn lvalue(context: &mut Context, sp!(loc, l_): LValue) -> LRes {
         use LValue_ as L;
         match l_ {
-            l_ @ L::Ignore | l_ @ L::Unpack(_, _, _) => LRes::Same(sp(loc, l_)),
+            l_ @ L::Ignore | l_ @ L::Unpack(_, _, _) | l_ @ L::UnpackVariant(_, _, _, _) => {
+                LRes::Same(sp(loc, l_))
+            }
             L::Var(v, t) => {
                 let contained = context.ssa_temps.remove(&v);
                 if contained {
@@ -375,6 +385,8 @@ mod eliminate {
             | E::Dereference(e)
             | E::UnaryExp(_, e)
             | E::Borrow(_, e, _)
+            | E::BorrowVariantField(_, e, _, _)
+            | E::TestVariant(e, _)
             | E::Cast(e, _) => exp(context, e),
 
             E::BinopExp(e1, _, e2) => {
@@ -382,7 +394,9 @@ mod eliminate {
                 exp(context, e2)
             }
 
-            E::Pack(_, _, fields) => fields.iter_mut().for_each(|(_, _, e)| exp(context, e)),
+            E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+                fields.iter_mut().for_each(|(_, _, e)| exp(context, e))
+            }
 
             E::ExpList(es) => es.iter_mut().for_each(|item| exp_list_item(context, item)),
 
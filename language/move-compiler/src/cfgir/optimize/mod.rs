@@ -8,6 +8,8 @@ mod simplify_jumps;
 
 use crate::{cfgir::cfg::BlockCFG, hlir::ast::*, parser::ast::Var, shared::unique_map::UniqueMap};
 
+pub use constant_fold::unfoldable_operation;
+
 pub type Optimization = fn(&FunctionSignature, &UniqueMap<Var, SingleType>, &mut BlockCFG) -> bool;
 
 const OPTIMIZATIONS: &[Optimization] = &[
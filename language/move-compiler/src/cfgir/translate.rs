@@ -11,8 +11,8 @@ use crate::{
     diag,
     expansion::ast::{AbilitySet, ModuleIdent},
     hlir::ast::{self as H, Label, Value, Value_},
-    parser::ast::{ConstantName, FunctionName, StructName, Var},
-    shared::{unique_map::UniqueMap, CompilationEnv},
+    parser::ast::{BlockLabel, ConstantName, FunctionName, StructName, Var},
+    shared::{unique_map::UniqueMap, CompilationEnv, Identifier},
     FullyCompiledProgram,
 };
 use cfgir::ast::LoopInfo;
@@ -32,8 +32,8 @@ struct Context<'env> {
     env: &'env mut CompilationEnv,
     struct_declared_abilities: UniqueMap<ModuleIdent, UniqueMap<StructName, AbilitySet>>,
     start: Option<Label>,
-    loop_begin: Option<Label>,
-    loop_end: Option<Label>,
+    // The enclosing loops, innermost last, with their labels, and begin and end blocks
+    loop_stack: Vec<(Option<BlockLabel>, Label, Label)>,
     next_label: Option<Label>,
     label_count: usize,
     blocks: BasicBlocks,
@@ -67,8 +67,7 @@ impl<'env> Context<'env> {
             env,
             struct_declared_abilities,
             next_label: None,
-            loop_begin: None,
-            loop_end: None,
+            loop_stack: vec![],
             start: None,
             label_count: 0,
             blocks: BasicBlocks::new(),
@@ -103,8 +102,7 @@ impl<'env> Context<'env> {
         let block_info = mem::take(&mut self.block_info);
         self.loop_bounds = BTreeMap::new();
         self.label_count = 0;
-        self.loop_begin = None;
-        self.loop_end = None;
+        self.loop_stack = vec![];
 
         // Blocks will eventually be ordered and outputted to bytecode the label. But labels are
         // initially created depth first
@@ -251,6 +249,7 @@ fn constant(context: &mut Context, _name: ConstantName, c: H::Constant) -> G::Co
         loc,
         signature,
         value: (locals, block),
+        assertion_message,
     } = c;
 
     let final_value = constant_(context, loc, signature.clone(), locals, block);
@@ -261,6 +260,7 @@ fn constant(context: &mut Context, _name: ConstantName, c: H::Constant) -> G::Co
         loc,
         signature,
         value,
+        assertion_message,
     }
 }
 
@@ -300,6 +300,7 @@ fn constant_(
         &locals,
         &mut cfg,
         &fake_infinite_loop_starts,
+        &[],
     );
     assert!(
         num_previous_errors == context.env.count_diags(),
@@ -343,10 +344,15 @@ fn check_constant_value(context: &mut Context, e: &H::Exp) {
     use H::UnannotatedExp_ as E;
     match &e.exp.value {
         E::Value(_) => (),
-        _ => context.env.add_diag(diag!(
-            BytecodeGeneration::UnfoldableConstant,
-            (e.exp.loc, CANNOT_FOLD)
-        )),
+        _ => {
+            let (loc, msg) = match cfgir::optimize::unfoldable_operation(e) {
+                Some((loc, reason)) => (loc, format!("{}. {}", CANNOT_FOLD, reason)),
+                None => (e.exp.loc, CANNOT_FOLD.to_owned()),
+            };
+            context
+                .env
+                .add_diag(diag!(BytecodeGeneration::UnfoldableConstant, (loc, msg)))
+        }
     }
 }
 
@@ -390,8 +396,9 @@ fn function(context: &mut Context, _name: FunctionName, f: H::Function) -> G::Fu
         signature,
         acquires,
         body,
+        inlined_calls,
     } = f;
-    let body = function_body(context, &signature, &acquires, body);
+    let body = function_body(context, &signature, &acquires, &inlined_calls, body);
     G::Function {
         attributes,
         visibility,
@@ -406,6 +413,7 @@ fn function_body(
     context: &mut Context,
     signature: &H::FunctionSignature,
     acquires: &BTreeMap<StructName, Loc>,
+    inlined_calls: &[H::InlinedCall],
     sp!(loc, tb_): H::FunctionBody,
 ) -> G::FunctionBody {
     use G::FunctionBody_ as GB;
@@ -416,8 +424,7 @@ fn function_body(
     assert!(context.block_ordering.is_empty());
     assert!(context.block_info.is_empty());
     assert!(context.loop_bounds.is_empty());
-    assert!(context.loop_begin.is_none());
-    assert!(context.loop_end.is_none());
+    assert!(context.loop_stack.is_empty());
     let b_ = match tb_ {
         HB::Native => GB::Native,
         HB::Defined { locals, body } => {
@@ -436,6 +443,7 @@ fn function_body(
                 &locals,
                 &mut cfg,
                 &infinite_loop_starts,
+                inlined_calls,
             );
             // do not optimize if there are errors, warnings are okay
             if !context.env.has_errors() {
@@ -512,15 +520,19 @@ fn block_(context: &mut Context, cur_label: &mut Label, blocks: H::Block) -> Bas
     }
 
     macro_rules! loop_block {
-        (begin: $begin:expr, end: $end:expr, body: $body:expr, $block:expr) => {{
+        (
+            label: $label:expr,
+            begin: $begin:expr,
+            end: $end:expr,
+            body: $body:expr,
+            $block:expr
+        ) => {{
             let begin = $begin;
-            let old_begin = mem::replace(&mut context.loop_begin, Some(begin));
-            let old_end = mem::replace(&mut context.loop_end, Some($end));
+            context.loop_stack.push(($label, begin, $end));
             let old_next = mem::replace(&mut context.next_label, Some(begin));
             block(context, $body, $block);
             context.next_label = old_next;
-            context.loop_end = old_end;
-            context.loop_begin = old_begin;
+            context.loop_stack.pop();
         }};
     }
 
@@ -559,6 +571,7 @@ fn block_(context: &mut Context, cur_label: &mut Label, blocks: H::Block) -> Bas
                 context.next_label = old_next;
             }
             S::While {
+                label,
                 cond: (hcond_block, cond),
                 block: loop_block,
             } => {
@@ -598,11 +611,19 @@ fn block_(context: &mut Context, cur_label: &mut Label, blocks: H::Block) -> Bas
                 finish_block!(next_label: loop_end);
 
                 // Loop body
-                loop_block!(begin: loop_cond, end: loop_end, body: loop_body, loop_block)
+                loop_block!(
+                    label: label,
+                    begin: loop_cond,
+                    end: loop_end,
+                    body: loop_body,
+                    loop_block
+                )
             }
 
             S::Loop {
-                block: loop_block, ..
+                label,
+                block: loop_block,
+                ..
             } => {
                 let loop_body = context.new_label();
                 let loop_end = context.new_label();
@@ -628,7 +649,13 @@ fn block_(context: &mut Context, cur_label: &mut Label, blocks: H::Block) -> Bas
                 finish_block!(next_label: loop_end);
 
                 // Loop body
-                loop_block!(begin: loop_body, end: loop_end, body: loop_body, loop_block)
+                loop_block!(
+                    label: label,
+                    begin: loop_body,
+                    end: loop_end,
+                    body: loop_body,
+                    loop_block
+                )
             }
         }
     }
@@ -644,15 +671,17 @@ fn command(context: &Context, sp!(_, hc_): &mut H::Command) {
         | C::Abort(_)
         | C::Return { .. }
         | C::IgnoreAndPop { .. } => {}
-        C::Continue => {
+        C::Continue(label) => {
+            let (begin, _end) = target_loop(context, label);
             *hc_ = C::Jump {
-                target: context.loop_begin.unwrap(),
+                target: begin,
                 from_user: true,
             }
         }
-        C::Break => {
+        C::Break(label) => {
+            let (_begin, end) = target_loop(context, label);
             *hc_ = C::Jump {
-                target: context.loop_end.unwrap(),
+                target: end,
                 from_user: true,
             }
         }
@@ -661,3 +690,16 @@ fn command(context: &Context, sp!(_, hc_): &mut H::Command) {
         }
     }
 }
+
+// The begin and end blocks of the loop targeted by a 'break' or 'continue'. Unlabeled jumps
+// target the innermost loop
+fn target_loop(context: &Context, label: &Option<BlockLabel>) -> (Label, Label) {
+    let mut loops = context.loop_stack.iter().rev();
+    let target = match label {
+        None => loops.next(),
+        Some(label) => loops
+            .find(|(loop_label, _, _)| matches!(loop_label, Some(l) if l.value() == label.value())),
+    };
+    let (_, begin, end) = target.expect("ICE break/continue target should have been resolved");
+    (*begin, *end)
+}
@@ -8,9 +8,10 @@ use crate::{
     compiled_unit,
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::{codes::Severity, *},
-    expansion, hlir, interface_generator, naming, parser,
+    expansion, hlir, interface_generator, linters, naming, parser,
     parser::{comments::*, *},
     shared::{
+        ast_dump::{AstDumpFormat, PassAst},
         CompilationEnv, Flags, IndexedPackagePath, NamedAddressMap, NamedAddressMaps,
         NumericalAddress, PackagePaths,
     },
@@ -230,8 +231,9 @@ impl<'a> Compiler<'a> {
     }
 
     pub fn check_and_report(self) -> anyhow::Result<FilesSourceText> {
+        let format = self.flags.diagnostics_format();
         let (files, res) = self.check()?;
-        unwrap_or_report_diagnostics(&files, res);
+        unwrap_or_report_diagnostics_with_format(format, &files, res);
         Ok(files)
     }
 
@@ -242,16 +244,21 @@ impl<'a> Compiler<'a> {
         Result<(Vec<AnnotatedCompiledUnit>, Diagnostics), Diagnostics>,
     )> {
         let (files, res) = self.run::<PASS_COMPILATION>()?;
-        Ok((
-            files,
-            res.map(|(_comments, stepped)| stepped.into_compiled_units()),
-        ))
+        let res = res.map(|(_comments, stepped)| {
+            let (mut units, warnings) = stepped.into_compiled_units();
+            for unit in &mut units {
+                unit.build_line_table(&files);
+            }
+            (units, warnings)
+        });
+        Ok((files, res))
     }
 
     pub fn build_and_report(self) -> anyhow::Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)> {
+        let format = self.flags.diagnostics_format();
         let (files, units_res) = self.build()?;
-        let (units, warnings) = unwrap_or_report_diagnostics(&files, units_res);
-        report_warnings(&files, warnings);
+        let (units, warnings) = unwrap_or_report_diagnostics_with_format(format, &files, units_res);
+        report_warnings_with_format(format, &files, warnings);
         Ok((files, units))
     }
 }
@@ -292,6 +299,21 @@ impl<'a, const P: Pass> SteppedCompiler<'a, P> {
     pub fn compilation_env(&mut self) -> &mut CompilationEnv {
         &mut self.compilation_env
     }
+
+    /// Dumps the AST of the pass in `format`, for the expansion, typing and CFGIR passes
+    pub fn dump_ast(
+        &self,
+        files: &FilesSourceText,
+        format: AstDumpFormat,
+    ) -> anyhow::Result<String> {
+        let ast = match &self.program {
+            Some(PassResult::Expansion(eprog)) => PassAst::Expansion(eprog),
+            Some(PassResult::Typing(tprog)) => PassAst::Typing(tprog),
+            Some(PassResult::CFGIR(cprog)) => PassAst::Cfgir(cprog),
+            _ => anyhow::bail!("The AST of pass {} cannot be dumped", P),
+        };
+        ast.dump(files, format)
+    }
 }
 
 macro_rules! ast_stepped_compilers {
@@ -365,17 +387,20 @@ macro_rules! ast_stepped_compilers {
                 }
 
                 pub fn check_and_report(self, files: &FilesSourceText)  {
+                    let format = self.compilation_env.flags().diagnostics_format();
                     let errors_result = self.check();
-                    unwrap_or_report_diagnostics(&files, errors_result);
+                    unwrap_or_report_diagnostics_with_format(format, &files, errors_result);
                 }
 
                 pub fn build_and_report(
                     self,
                     files: &FilesSourceText,
                 ) -> Vec<AnnotatedCompiledUnit> {
+                    let format = self.compilation_env.flags().diagnostics_format();
                     let units_result = self.build();
-                    let (units, warnings) = unwrap_or_report_diagnostics(&files, units_result);
-                    report_warnings(&files, warnings);
+                    let (units, warnings) =
+                        unwrap_or_report_diagnostics_with_format(format, &files, units_result);
+                    report_warnings_with_format(format, &files, warnings);
                     units
                 }
             }
@@ -787,6 +812,9 @@ fn run(
             )
         }
         PassResult::Typing(tprog) => {
+            if compilation_env.flags().is_linting() && !compilation_env.has_errors() {
+                linters::program(compilation_env, &tprog);
+            }
             let hprog = hlir::translate::program(compilation_env, pre_compiled_lib, tprog);
             compilation_env.check_diags_at_or_above_severity(Severity::Bug)?;
             run(
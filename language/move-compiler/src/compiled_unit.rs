@@ -4,7 +4,7 @@
 
 use crate::{
     diag,
-    diagnostics::Diagnostics,
+    diagnostics::{Diagnostics, FilesSourceText},
     expansion::ast::{Attributes, ModuleIdent, ModuleIdent_, SpecId},
     hlir::ast as H,
     parser::ast::{FunctionName, ModuleName, Var},
@@ -13,8 +13,8 @@ use crate::{
 use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
 use move_core_types::{
-    account_address::AccountAddress, identifier::Identifier as MoveCoreIdentifier,
-    language_storage::ModuleId,
+    account_address::AccountAddress, errmap::ErrorMapping,
+    identifier::Identifier as MoveCoreIdentifier, language_storage::ModuleId,
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
@@ -140,6 +140,23 @@ impl AnnotatedCompiledUnit {
         }
     }
 
+    /// Builds the line table of the unit's source map from the source files in `files`
+    pub fn build_line_table(&mut self, files: &FilesSourceText) {
+        let source_map = match self {
+            Self::Module(AnnotatedCompiledModule { named_module, .. }) => {
+                &mut named_module.source_map
+            }
+            Self::Script(AnnotatedCompiledScript { named_script, .. }) => {
+                &mut named_script.source_map
+            }
+        };
+        source_map.build_line_table(|file_hash| {
+            files
+                .get(&file_hash)
+                .map(|(path, contents)| (path.as_str(), contents.as_str()))
+        })
+    }
+
     pub fn into_compiled_unit(self) -> CompiledUnit {
         match self {
             Self::Module(AnnotatedCompiledModule {
@@ -190,6 +207,23 @@ impl CompiledUnit {
         }
     }
 
+    /// Returns the descriptions of the errors raised by the assertions with messages of the unit.
+    /// It is empty for scripts, as errors are described by module
+    pub fn error_mapping(&self) -> ErrorMapping {
+        let mut error_mapping = ErrorMapping::default();
+        if let Self::Module(NamedCompiledModule {
+            module, source_map, ..
+        }) = self
+        {
+            for (abort_code, description) in source_map.error_descriptions() {
+                error_mapping
+                    .add_module_error(module.self_id(), abort_code, description)
+                    .expect("ICE abort codes of assertions are unique in a module")
+            }
+        }
+        error_mapping
+    }
+
     pub fn serialize(&self, bytecode_version: Option<u32>) -> Vec<u8> {
         let mut serialized = Vec::<u8>::new();
         match self {
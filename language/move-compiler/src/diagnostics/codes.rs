@@ -92,6 +92,22 @@ macro_rules! codes {
             }
         )*
 
+        impl DiagnosticInfo {
+            /// The info of the code `code` of the category `category`, if there is such a code
+            pub fn from_category_and_code(category: u8, code: u8) -> Option<Self> {
+                $(
+                    if category == Category::$cat as u8 {
+                        $(
+                            if code == $cat::$code as u8 {
+                                return Some($cat::$code.into_info());
+                            }
+                        )*
+                        return None;
+                    }
+                )*
+                None
+            }
+        }
     };
 }
 
@@ -114,6 +130,7 @@ codes!(
         SpecContextRestricted:
             { msg: "syntax item restricted to spec contexts", severity: BlockingError },
         InvalidSpecBlockMember: { msg: "invalid spec block member", severity: NonblockingError },
+        InvalidForLoop: { msg: "invalid 'for' loop", severity: NonblockingError },
     ],
     // errors for any rules around declaration items
     Declarations: [
@@ -136,6 +153,8 @@ codes!(
         InvalidNonPhantomUse:
             { msg: "invalid non-phantom type parameter usage", severity: Warning },
         InvalidAttribute: { msg: "invalid attribute", severity: NonblockingError },
+        InvalidUseFun: { msg: "invalid 'use fun' declaration", severity: NonblockingError },
+        ShadowedLabel: { msg: "invalid shadowing of a loop label", severity: NonblockingError },
     ],
     // errors name resolution, mostly expansion/translate and naming/translate
     NameResolution: [
@@ -151,6 +170,11 @@ codes!(
         UnboundField: { msg: "unbound field", severity: BlockingError },
         ReservedName: { msg: "invalid use of reserved name", severity: BlockingError },
         UnboundMacro: { msg: "unbound macro", severity: BlockingError },
+        UnboundVariant: { msg: "unbound variant", severity: BlockingError },
+        UnboundMethod: { msg: "unbound method", severity: BlockingError },
+        UnboundLabel: { msg: "unbound label", severity: BlockingError },
+        PositionalMismatch:
+            { msg: "mismatched positional and named fields", severity: BlockingError },
     ],
     // errors for typing rules. mostly typing/translate
     TypeSafety: [
@@ -182,6 +206,13 @@ codes!(
                 (NOTE: this may become an error in the future)",
             severity: Warning
         },
+        NonExhaustiveMatch: { msg: "non-exhaustive match", severity: BlockingError },
+        InvalidFunctionType: { msg: "invalid use of function type", severity: BlockingError },
+        InvalidLambda: { msg: "invalid use of lambda", severity: BlockingError },
+        CyclicInline: { msg: "cyclic inline function calls", severity: BlockingError },
+        InvalidReturn: { msg: "invalid 'return'", severity: BlockingError },
+        InvalidIndex: { msg: "invalid index", severity: BlockingError },
+        CyclicConstant: { msg: "cyclic constant definitions", severity: BlockingError },
     ],
     // errors for ability rules. mostly typing/translate
     AbilitySafety: [
@@ -218,6 +249,10 @@ codes!(
         DeadCode: { msg: "dead or unreachable code", severity: Warning },
         StructTypeParam: { msg: "unused struct type parameter", severity: Warning },
         Attribute: { msg: "unused attribute", severity: Warning },
+        Function: { msg: "unused function", severity: Warning },
+        Constant: { msg: "unused constant", severity: Warning },
+        Struct: { msg: "unused struct", severity: Warning },
+        Friend: { msg: "unused friend declaration", severity: Warning },
     ],
     Attributes: [
         Duplicate: { msg: "invalid duplicate attribute", severity: NonblockingError },
@@ -227,7 +262,8 @@ codes!(
         InvalidTest: { msg: "unable to generate test", severity: NonblockingError },
         InvalidBytecodeInst:
             { msg: "unknown bytecode instruction function", severity: NonblockingError },
-        ValueWarning: { msg: "potential issue with attribute value", severity: Warning }
+        ValueWarning: { msg: "potential issue with attribute value", severity: Warning },
+        InvalidSyntaxMethod: { msg: "invalid syntax method", severity: NonblockingError },
     ],
     Tests: [
         TestFailed: { msg: "test failure", severity: BlockingError },
@@ -238,7 +274,20 @@ codes!(
     ],
     Derivation: [
         DeriveFailed: { msg: "attribute derivation failed", severity: BlockingError }
-    ]
+    ],
+    // warnings from the linters, only run with '--lint'
+    Linter: [
+        SelfAssignment: { msg: "self assignment", severity: Warning },
+        RedundantCopy: { msg: "redundant copy", severity: Warning },
+        WhileTrue: { msg: "'while (true)' instead of 'loop'", severity: Warning },
+        ConstantComparison: { msg: "comparison of constants", severity: Warning },
+        NeedlessMutRef: { msg: "needless mutable reference", severity: Warning },
+        ShiftOverflow: { msg: "shift always overflows", severity: Warning },
+    ],
+    // warnings for uses of items marked '#[deprecated]'. naming/translate
+    Deprecation: [
+        Usage: { msg: "use of deprecated item", severity: Warning },
+    ],
 );
 
 //**************************************************************************************************
@@ -271,6 +320,14 @@ impl DiagnosticInfo {
     pub fn severity(&self) -> Severity {
         self.severity
     }
+
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    pub fn code(&self) -> u8 {
+        self.code
+    }
 }
 
 impl Severity {
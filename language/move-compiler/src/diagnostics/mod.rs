@@ -3,11 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod codes;
+mod structured;
 
 use crate::{
     command_line::COLOR_MODE_ENV_VAR,
     diagnostics::codes::{DiagnosticCode, DiagnosticInfo, Severity},
 };
+use clap::ArgEnum;
 use codespan_reporting::{
     self as csr,
     files::SimpleFiles,
@@ -20,8 +22,10 @@ use codespan_reporting::{
 use move_command_line_common::{env::read_env_var, files::FileHash};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    io::Write,
     iter::FromIterator,
     ops::Range,
 };
@@ -51,44 +55,118 @@ pub struct Diagnostics {
     severity_count: BTreeMap<Severity, usize>,
 }
 
+/// A diagnostic in a form that can be serialized, so that it can be reported again without
+/// recompiling the code it is about
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct SavedDiagnostic {
+    category: u8,
+    code: u8,
+    primary_label: (Loc, String),
+    secondary_labels: Vec<(Loc, String)>,
+    notes: Vec<String>,
+}
+
+/// The format in which diagnostics are reported
+#[derive(
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    ArgEnum,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticsFormat {
+    /// Rendered for humans, with the source code of each label
+    #[default]
+    Human,
+    /// A JSON object per diagnostic, one per line
+    Json,
+    /// A SARIF 2.1.0 log
+    Sarif,
+}
+
 //**************************************************************************************************
 // Reporting
 //**************************************************************************************************
 
 pub fn report_diagnostics(files: &FilesSourceText, diags: Diagnostics) -> ! {
+    report_diagnostics_with_format(DiagnosticsFormat::Human, files, diags)
+}
+
+pub fn report_diagnostics_with_format(
+    format: DiagnosticsFormat,
+    files: &FilesSourceText,
+    diags: Diagnostics,
+) -> ! {
     let should_exit = true;
-    report_diagnostics_impl(files, diags, should_exit);
+    report_diagnostics_impl(format, files, diags, should_exit);
     std::process::exit(1)
 }
 
 pub fn report_warnings(files: &FilesSourceText, warnings: Diagnostics) {
+    report_warnings_with_format(DiagnosticsFormat::Human, files, warnings)
+}
+
+pub fn report_warnings_with_format(
+    format: DiagnosticsFormat,
+    files: &FilesSourceText,
+    warnings: Diagnostics,
+) {
     if warnings.is_empty() {
         return;
     }
     debug_assert!(warnings.max_severity().unwrap() == Severity::Warning);
-    report_diagnostics_impl(files, warnings, false)
+    report_diagnostics_impl(format, files, warnings, false)
 }
 
-fn report_diagnostics_impl(files: &FilesSourceText, diags: Diagnostics, should_exit: bool) {
-    let color_choice = match read_env_var(COLOR_MODE_ENV_VAR).as_str() {
-        "NONE" => ColorChoice::Never,
-        "ANSI" => ColorChoice::AlwaysAnsi,
-        "ALWAYS" => ColorChoice::Always,
-        _ => ColorChoice::Auto,
-    };
-    let mut writer = StandardStream::stderr(color_choice);
-    output_diagnostics(&mut writer, files, diags);
+fn report_diagnostics_impl(
+    format: DiagnosticsFormat,
+    files: &FilesSourceText,
+    diags: Diagnostics,
+    should_exit: bool,
+) {
+    match format {
+        DiagnosticsFormat::Human => {
+            let color_choice = match read_env_var(COLOR_MODE_ENV_VAR).as_str() {
+                "NONE" => ColorChoice::Never,
+                "ANSI" => ColorChoice::AlwaysAnsi,
+                "ALWAYS" => ColorChoice::Always,
+                _ => ColorChoice::Auto,
+            };
+            let mut writer = StandardStream::stderr(color_choice);
+            output_diagnostics(&mut writer, files, diags);
+        }
+        DiagnosticsFormat::Json | DiagnosticsFormat::Sarif => {
+            let buf = structured::render(format, files, diags);
+            std::io::stderr().write_all(&buf).unwrap()
+        }
+    }
     if should_exit {
         std::process::exit(1);
     }
 }
 
 pub fn unwrap_or_report_diagnostics<T>(files: &FilesSourceText, res: Result<T, Diagnostics>) -> T {
+    unwrap_or_report_diagnostics_with_format(DiagnosticsFormat::Human, files, res)
+}
+
+pub fn unwrap_or_report_diagnostics_with_format<T>(
+    format: DiagnosticsFormat,
+    files: &FilesSourceText,
+    res: Result<T, Diagnostics>,
+) -> T {
     match res {
         Ok(t) => t,
         Err(diags) => {
             assert!(!diags.is_empty());
-            report_diagnostics(files, diags)
+            report_diagnostics_with_format(format, files, diags)
         }
     }
 }
@@ -99,6 +177,21 @@ pub fn report_diagnostics_to_buffer(files: &FilesSourceText, diags: Diagnostics)
     writer.into_inner()
 }
 
+/// Renders the diagnostics in the given format. Human readable diagnostics are rendered without
+/// color
+pub fn report_diagnostics_to_buffer_with_format(
+    format: DiagnosticsFormat,
+    files: &FilesSourceText,
+    diags: Diagnostics,
+) -> Vec<u8> {
+    match format {
+        DiagnosticsFormat::Human => report_diagnostics_to_buffer(files, diags),
+        DiagnosticsFormat::Json | DiagnosticsFormat::Sarif => {
+            structured::render(format, files, diags)
+        }
+    }
+}
+
 pub fn report_diagnostics_to_color_buffer(files: &FilesSourceText, diags: Diagnostics) -> Vec<u8> {
     let mut writer = Buffer::ansi();
     output_diagnostics(&mut writer, files, diags);
@@ -123,19 +216,9 @@ fn render_diagnostics(
     writer: &mut dyn WriteColor,
     files: &SimpleFiles<Symbol, &str>,
     file_mapping: &FileMapping,
-    mut diags: Diagnostics,
+    diags: Diagnostics,
 ) {
-    diags.diagnostics.sort_by(|e1, e2| {
-        let loc1: &Loc = &e1.primary_label.0;
-        let loc2: &Loc = &e2.primary_label.0;
-        loc1.cmp(loc2)
-    });
-    let mut seen: HashSet<Diagnostic> = HashSet::new();
-    for diag in diags.diagnostics {
-        if seen.contains(&diag) {
-            continue;
-        }
-        seen.insert(diag.clone());
+    for diag in diags.into_sorted_unique() {
         let rendered = render_diagnostic(file_mapping, diag);
         emit(writer, &Config::default(), files, &rendered).unwrap()
     }
@@ -232,6 +315,20 @@ impl Diagnostics {
         self.diagnostics
     }
 
+    /// The diagnostics sorted by their primary location, without duplicates
+    fn into_sorted_unique(mut self) -> Vec<Diagnostic> {
+        self.diagnostics.sort_by(|e1, e2| {
+            let loc1: &Loc = &e1.primary_label.0;
+            let loc2: &Loc = &e2.primary_label.0;
+            loc1.cmp(loc2)
+        });
+        let mut seen: HashSet<Diagnostic> = HashSet::new();
+        self.diagnostics
+            .into_iter()
+            .filter(|diag| seen.insert(diag.clone()))
+            .collect()
+    }
+
     pub fn into_codespan_format(
         self,
     ) -> Vec<(
@@ -241,24 +338,10 @@ impl Diagnostics {
         Vec<(Loc, String)>,
         Vec<String>,
     )> {
-        let mut v = vec![];
-        for diag in self.into_vec() {
-            let Diagnostic {
-                info,
-                primary_label,
-                secondary_labels,
-                notes,
-            } = diag;
-            let csr_diag = (
-                info.severity().into_codespan_severity(),
-                info.message(),
-                primary_label,
-                secondary_labels,
-                notes,
-            );
-            v.push(csr_diag)
-        }
-        v
+        self.into_vec()
+            .into_iter()
+            .map(Diagnostic::into_codespan_format)
+            .collect()
     }
 }
 
@@ -280,6 +363,53 @@ impl Diagnostic {
         }
     }
 
+    pub fn into_codespan_format(
+        self,
+    ) -> (
+        codespan_reporting::diagnostic::Severity,
+        &'static str,
+        (Loc, String),
+        Vec<(Loc, String)>,
+        Vec<String>,
+    ) {
+        let Diagnostic {
+            info,
+            primary_label,
+            secondary_labels,
+            notes,
+        } = self;
+        (
+            info.severity().into_codespan_severity(),
+            info.message(),
+            primary_label,
+            secondary_labels,
+            notes,
+        )
+    }
+
+    pub fn info(&self) -> &DiagnosticInfo {
+        &self.info
+    }
+
+    pub fn primary_loc(&self) -> Loc {
+        self.primary_label.0
+    }
+
+    /// The locations of all labels, starting with the primary label
+    pub fn labeled_locs(&self) -> impl Iterator<Item = Loc> + '_ {
+        std::iter::once(self.primary_label.0).chain(self.secondary_labels.iter().map(|(l, _)| *l))
+    }
+
+    pub fn save(&self) -> SavedDiagnostic {
+        SavedDiagnostic {
+            category: self.info.category() as u8,
+            code: self.info.code(),
+            primary_label: self.primary_label.clone(),
+            secondary_labels: self.secondary_labels.clone(),
+            notes: self.notes.clone(),
+        }
+    }
+
     pub fn set_code(mut self, code: impl DiagnosticCode) -> Self {
         self.info = code.into_info();
         self
@@ -316,6 +446,30 @@ impl Diagnostic {
     }
 }
 
+impl SavedDiagnostic {
+    /// The diagnostic that was saved, unless its code no longer exists
+    pub fn restore(self) -> Option<Diagnostic> {
+        let SavedDiagnostic {
+            category,
+            code,
+            primary_label,
+            secondary_labels,
+            notes,
+        } = self;
+        Some(Diagnostic {
+            info: DiagnosticInfo::from_category_and_code(category, code)?,
+            primary_label,
+            secondary_labels,
+            notes,
+        })
+    }
+
+    /// The locations of all labels, starting with the primary label
+    pub fn labeled_locs(&self) -> impl Iterator<Item = Loc> + '_ {
+        std::iter::once(self.primary_label.0).chain(self.secondary_labels.iter().map(|(l, _)| *l))
+    }
+}
+
 #[macro_export]
 macro_rules! diag {
     ($code: expr, $primary: expr $(,)?) => {{
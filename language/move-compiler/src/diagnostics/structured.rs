@@ -0,0 +1,233 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine readable renderings of diagnostics, for tools such as CI annotators and editors. Every
+//! diagnostic keeps its code, severity, all of its labels and its notes. Positions are 1-based
+//! lines and columns, where the end position is exclusive.
+
+use super::{
+    codes::{DiagnosticInfo, Severity},
+    Diagnostic, Diagnostics, DiagnosticsFormat, FileMapping, FilesSourceText,
+};
+use codespan_reporting::files::{Files, SimpleFiles};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "move-compiler";
+
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    code: String,
+    category: String,
+    severity: JsonSeverity,
+    message: &'static str,
+    primary_label: JsonLabel,
+    secondary_labels: Vec<JsonLabel>,
+    notes: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum JsonSeverity {
+    Warning,
+    Error,
+    Bug,
+}
+
+#[derive(Serialize)]
+struct JsonLabel {
+    file: String,
+    start: JsonPosition,
+    end: JsonPosition,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
+/// Renders the diagnostics in a structured format, i.e. JSON or SARIF
+pub(super) fn render(
+    format: DiagnosticsFormat,
+    sources: &FilesSourceText,
+    diags: Diagnostics,
+) -> Vec<u8> {
+    let mut files = SimpleFiles::new();
+    let mut file_mapping = HashMap::new();
+    for (fhash, (fname, source)) in sources {
+        let id = files.add(*fname, source.as_str());
+        file_mapping.insert(*fhash, id);
+    }
+    let diags = diags
+        .into_sorted_unique()
+        .into_iter()
+        .map(|diag| json_diagnostic(&files, &file_mapping, diag))
+        .collect::<Vec<_>>();
+    match format {
+        DiagnosticsFormat::Human => {
+            unreachable!("ICE human readable diagnostics are not structured")
+        }
+        DiagnosticsFormat::Json => {
+            let mut buf = vec![];
+            for diag in diags {
+                serde_json::to_writer(&mut buf, &diag).unwrap();
+                buf.push(b'\n');
+            }
+            buf
+        }
+        DiagnosticsFormat::Sarif => {
+            let mut buf = serde_json::to_vec(&sarif_log(diags)).unwrap();
+            buf.push(b'\n');
+            buf
+        }
+    }
+}
+
+//**************************************************************************************************
+// JSON
+//**************************************************************************************************
+
+fn json_diagnostic(
+    files: &SimpleFiles<Symbol, &str>,
+    file_mapping: &FileMapping,
+    diag: Diagnostic,
+) -> JsonDiagnostic {
+    let Diagnostic {
+        info,
+        primary_label,
+        secondary_labels,
+        notes,
+    } = diag;
+    let category = format!("{:?}", info.category());
+    let severity = json_severity(&info);
+    let (code, message) = info.render();
+    JsonDiagnostic {
+        code,
+        category,
+        severity,
+        message,
+        primary_label: json_label(files, file_mapping, primary_label),
+        secondary_labels: secondary_labels
+            .into_iter()
+            .map(|label| json_label(files, file_mapping, label))
+            .collect(),
+        notes,
+    }
+}
+
+fn json_label(
+    files: &SimpleFiles<Symbol, &str>,
+    file_mapping: &FileMapping,
+    (loc, message): (Loc, String),
+) -> JsonLabel {
+    let id = *file_mapping.get(&loc.file_hash()).unwrap();
+    let position = |byte_index: u32| {
+        let byte_index = byte_index as usize;
+        let line_index = files.line_index(id, byte_index).unwrap();
+        JsonPosition {
+            line: files.line_number(id, line_index).unwrap(),
+            column: files.column_number(id, line_index, byte_index).unwrap(),
+        }
+    };
+    JsonLabel {
+        file: files.name(id).unwrap().to_string(),
+        start: position(loc.start()),
+        end: position(loc.end()),
+        message,
+    }
+}
+
+fn json_severity(info: &DiagnosticInfo) -> JsonSeverity {
+    match info.severity() {
+        Severity::Warning => JsonSeverity::Warning,
+        Severity::NonblockingError | Severity::BlockingError => JsonSeverity::Error,
+        Severity::Bug => JsonSeverity::Bug,
+    }
+}
+
+//**************************************************************************************************
+// SARIF
+//**************************************************************************************************
+
+fn sarif_log(diags: Vec<JsonDiagnostic>) -> serde_json::Value {
+    let rules = diags
+        .iter()
+        .map(|diag| {
+            let rule = json!({
+                "id": diag.code,
+                "name": diag.category,
+                "shortDescription": { "text": diag.message },
+            });
+            (diag.code.clone(), rule)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let results = diags.into_iter().map(sarif_result).collect::<Vec<_>>();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "rules": rules.into_values().collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(diag: JsonDiagnostic) -> serde_json::Value {
+    let JsonDiagnostic {
+        code,
+        category: _,
+        severity,
+        message,
+        primary_label,
+        secondary_labels,
+        notes,
+    } = diag;
+    let level = match severity {
+        JsonSeverity::Warning => "warning",
+        JsonSeverity::Error | JsonSeverity::Bug => "error",
+    };
+    let related_locations = secondary_labels
+        .into_iter()
+        .enumerate()
+        .map(|(idx, label)| {
+            let mut location = sarif_location(&label);
+            location["id"] = json!(idx);
+            location["message"] = json!({ "text": label.message });
+            location
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "ruleId": code,
+        "level": level,
+        "message": { "text": format!("{}: {}", message, primary_label.message) },
+        "locations": [sarif_location(&primary_label)],
+        "relatedLocations": related_locations,
+        "properties": { "severity": severity, "notes": notes },
+    })
+}
+
+fn sarif_location(label: &JsonLabel) -> serde_json::Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": label.file },
+            "region": {
+                "startLine": label.start.line,
+                "startColumn": label.start.column,
+                "endLine": label.end.line,
+                "endColumn": label.end.column,
+            },
+        },
+    })
+}
@@ -4,16 +4,19 @@
 
 use crate::{
     parser::ast::{
-        self as P, Ability, Ability_, BinOp, ConstantName, Field, FunctionName, ModuleName,
-        QuantKind, SpecApplyPattern, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        self as P, Ability, Ability_, BinOp, BlockLabel, ConstantName, Field, FunctionName,
+        ModuleName, QuantKind, SpecApplyPattern, StructName, UnaryOp, Var, VariantName,
+        ENTRY_MODIFIER, INLINE_MODIFIER,
     },
     shared::{
         ast_debug::*, known_attributes::KnownAttribute, unique_map::UniqueMap,
         unique_set::UniqueSet, *,
     },
 };
+use move_core_types::account_address::AccountAddress;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     fmt,
@@ -24,7 +27,7 @@ use std::{
 // Program
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     // Map of declared named addresses, and their values if specified
     pub modules: UniqueMap<ModuleIdent, ModuleDefinition>,
@@ -35,7 +38,7 @@ pub struct Program {
 // Attributes
 //**************************************************************************************************
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AttributeValue_ {
     Value(Value),
     Module(ModuleIdent),
@@ -43,7 +46,7 @@ pub enum AttributeValue_ {
 }
 pub type AttributeValue = Spanned<AttributeValue_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Attribute_ {
     Name(Name),
     Assigned(Name, Box<AttributeValue>),
@@ -61,7 +64,7 @@ impl Attribute_ {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum AttributeName_ {
     Unknown(Symbol),
     Known(KnownAttribute),
@@ -74,7 +77,7 @@ pub type Attributes = UniqueMap<AttributeName, Attribute>;
 // Scripts
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Script {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -92,19 +95,19 @@ pub struct Script {
 // Modules
 //**************************************************************************************************
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize)]
 pub enum Address {
     Numerical(Option<Name>, Spanned<NumericalAddress>),
     NamedUnassigned(Name),
 }
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct ModuleIdent_ {
     pub address: Address,
     pub module: ModuleName,
 }
 pub type ModuleIdent = Spanned<ModuleIdent_>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDefinition {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -116,10 +119,13 @@ pub struct ModuleDefinition {
     pub dependency_order: usize,
     pub immediate_neighbors: UniqueMap<ModuleIdent, Neighbor>,
     pub used_addresses: BTreeSet<Address>,
+    /// The module members referenced in the specs of the module, set in the uses pass
+    pub spec_uses: BTreeSet<(ModuleIdent, Name)>,
     pub friends: UniqueMap<ModuleIdent, Friend>,
     pub structs: UniqueMap<StructName, StructDefinition>,
     pub functions: UniqueMap<FunctionName, Function>,
     pub constants: UniqueMap<ConstantName, Constant>,
+    pub use_funs: Vec<UseFun>,
     pub specs: Vec<SpecBlock>,
 }
 
@@ -127,13 +133,26 @@ pub struct ModuleDefinition {
 // Friend
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Friend {
     pub attributes: Attributes,
     pub loc: Loc,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+//**************************************************************************************************
+// Use Fun
+//**************************************************************************************************
+
+// use fun function as ty.method
+#[derive(Debug, Clone, Serialize)]
+pub struct UseFun {
+    pub attributes: Attributes,
+    pub function: ModuleAccess,
+    pub ty: ModuleAccess,
+    pub method: Name,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize)]
 pub enum Neighbor {
     Dependency,
     Friend,
@@ -145,14 +164,21 @@ pub enum Neighbor {
 
 pub type Fields<T> = UniqueMap<Field, (usize, T)>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Returns true if the fields are those of a positional struct, i.e. they are all named by their
+/// index
+pub fn is_positional<T>(fields: &Fields<T>) -> bool {
+    !fields.is_empty() && fields.key_cloned_iter().all(|(f, _)| f.is_positional())
+}
+pub type Variants<T> = UniqueMap<VariantName, (usize, Fields<T>)>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct StructTypeParameter {
     pub is_phantom: bool,
     pub name: Name,
     pub constraints: AbilitySet,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct StructDefinition {
     pub attributes: Attributes,
     pub loc: Loc,
@@ -161,46 +187,49 @@ pub struct StructDefinition {
     pub fields: StructFields,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum StructFields {
     Defined(Fields<Type>),
     Native(Loc),
+    Variants(Variants<Type>),
 }
 
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize)]
 pub enum Visibility {
     Public(Loc),
     Friend(Loc),
+    Package(Loc),
     Internal,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize)]
 pub struct FunctionSignature {
     pub type_parameters: Vec<(Name, AbilitySet)>,
     pub parameters: Vec<(Var, Type)>,
     pub return_type: Type,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize)]
 pub enum FunctionBody_ {
     Defined(Sequence),
     Native,
 }
 pub type FunctionBody = Spanned<FunctionBody_>;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize)]
 pub struct SpecId(usize);
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize)]
 pub struct Function {
     pub attributes: Attributes,
     pub loc: Loc,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: bool,
     pub signature: FunctionSignature,
     pub acquires: Vec<ModuleAccess>,
     pub body: FunctionBody,
@@ -211,19 +240,21 @@ pub struct Function {
 // Constants
 //**************************************************************************************************
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize)]
 pub struct Constant {
     pub attributes: Attributes,
     pub loc: Loc,
     pub signature: Type,
     pub value: Exp,
+    // the message of the assertion the constant is the abort code of, if generated for one
+    pub assertion_message: Option<String>,
 }
 
 //**************************************************************************************************
 // Specification Blocks
 //**************************************************************************************************
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SpecBlock_ {
     pub attributes: Attributes,
     pub target: SpecBlockTarget,
@@ -231,7 +262,7 @@ pub struct SpecBlock_ {
 }
 pub type SpecBlock = Spanned<SpecBlock_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum SpecBlockTarget_ {
     Code,
     Module,
@@ -241,7 +272,7 @@ pub enum SpecBlockTarget_ {
 
 pub type SpecBlockTarget = Spanned<SpecBlockTarget_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum SpecBlockMember_ {
     Condition {
@@ -287,7 +318,7 @@ pub enum SpecBlockMember_ {
 }
 pub type SpecBlockMember = Spanned<SpecBlockMember_>;
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub enum SpecConditionKind_ {
     Assert,
     Assume,
@@ -305,14 +336,14 @@ pub enum SpecConditionKind_ {
 }
 pub type SpecConditionKind = Spanned<SpecConditionKind_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PragmaProperty_ {
     pub name: Name,
     pub value: Option<PragmaValue>,
 }
 pub type PragmaProperty = Spanned<PragmaProperty_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PragmaValue {
     Literal(Value),
     Ident(ModuleAccess),
@@ -322,17 +353,17 @@ pub enum PragmaValue {
 // Types
 //**************************************************************************************************
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct AbilitySet(UniqueSet<Ability>);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ModuleAccess_ {
     Name(Name),
     ModuleAccess(ModuleIdent, Name),
 }
 pub type ModuleAccess = Spanned<ModuleAccess_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Type_ {
     Unit,
@@ -348,7 +379,7 @@ pub type Type = Spanned<Type_>;
 // Expressions
 //**************************************************************************************************
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum LValue_ {
     Var(ModuleAccess, Option<Vec<Type>>),
     Unpack(ModuleAccess, Option<Vec<Type>>, Fields<LValue>),
@@ -357,20 +388,32 @@ pub type LValue = Spanned<LValue_>;
 pub type LValueList_ = Vec<LValue>;
 pub type LValueList = Spanned<LValueList_>;
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum MatchPattern_ {
+    Wildcard,
+    Variant(ModuleAccess, VariantName, Option<Vec<Type>>, Fields<LValue>),
+}
+pub type MatchPattern = Spanned<MatchPattern_>;
+
+pub type MatchArm_ = (MatchPattern, Exp);
+pub type MatchArm = Spanned<MatchArm_>;
+
 pub type LValueWithRange_ = (LValue, Exp);
 pub type LValueWithRange = Spanned<LValueWithRange_>;
 pub type LValueWithRangeList_ = Vec<LValueWithRange>;
 pub type LValueWithRangeList = Spanned<LValueWithRangeList_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum ExpDotted_ {
     Exp(Exp),
     Dot(Box<ExpDotted>, Name),
+    Index(Box<ExpDotted>, Box<Exp>),
 }
 pub type ExpDotted = Spanned<ExpDotted_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Value_ {
     // 0x<hex representation up to 64 digits with padding 0s>
     Address(Address),
@@ -395,7 +438,7 @@ pub enum Value_ {
 }
 pub type Value = Spanned<Value_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Exp_ {
     Value(Value),
@@ -409,12 +452,15 @@ pub enum Exp_ {
         Option<Vec<Type>>,
         Spanned<Vec<Exp>>,
     ),
+    MethodCall(Box<ExpDotted>, Name, Option<Vec<Type>>, Spanned<Vec<Exp>>),
     Pack(ModuleAccess, Option<Vec<Type>>, Fields<Exp>),
+    PackVariant(ModuleAccess, VariantName, Option<Vec<Type>>, Fields<Exp>),
     Vector(Loc, Option<Vec<Type>>, Spanned<Vec<Exp>>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
-    While(Box<Exp>, Box<Exp>),
-    Loop(Box<Exp>),
+    While(Option<BlockLabel>, Box<Exp>, Box<Exp>),
+    Loop(Option<BlockLabel>, Box<Exp>),
+    Match(Box<Exp>, Vec<MatchArm>),
     Block(Sequence),
     Lambda(LValueList, Box<Exp>), // spec only
     Quant(
@@ -431,8 +477,8 @@ pub enum Exp_ {
 
     Return(Box<Exp>),
     Abort(Box<Exp>),
-    Break,
-    Continue,
+    Break(Option<BlockLabel>),
+    Continue(Option<BlockLabel>),
 
     Dereference(Box<Exp>),
     UnaryExp(UnaryOp, Box<Exp>),
@@ -457,7 +503,7 @@ pub enum Exp_ {
 pub type Exp = Spanned<Exp_>;
 
 pub type Sequence = VecDeque<SequenceItem>;
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum SequenceItem_ {
     Seq(Exp),
     Declare(LValueList, Option<Type>),
@@ -573,6 +619,19 @@ impl ModuleIdent_ {
     pub fn new(address: Address, module: ModuleName) -> Self {
         Self { address, module }
     }
+
+    /// The standard library vector module, `0x1::vector`
+    pub fn vector(loc: Loc) -> ModuleIdent {
+        let address = Address::Numerical(
+            None,
+            sp(
+                loc,
+                NumericalAddress::new(AccountAddress::ONE.into_bytes(), NumberFormat::Hex),
+            ),
+        );
+        let module = ModuleName(sp(loc, Symbol::from("vector")));
+        sp(loc, ModuleIdent_ { address, module })
+    }
 }
 
 impl SpecId {
@@ -681,11 +740,14 @@ impl AbilitySet {
 impl Visibility {
     pub const PUBLIC: &'static str = P::Visibility::PUBLIC;
     pub const FRIEND: &'static str = P::Visibility::FRIEND;
+    pub const PACKAGE: &'static str = P::Visibility::PACKAGE;
     pub const INTERNAL: &'static str = P::Visibility::INTERNAL;
 
     pub fn loc(&self) -> Option<Loc> {
         match self {
-            Visibility::Public(loc) | Visibility::Friend(loc) => Some(*loc),
+            Visibility::Public(loc) | Visibility::Friend(loc) | Visibility::Package(loc) => {
+                Some(*loc)
+            }
             Visibility::Internal => None,
         }
     }
@@ -797,6 +859,7 @@ impl fmt::Display for Visibility {
             match &self {
                 Visibility::Public(_) => Visibility::PUBLIC,
                 Visibility::Friend(_) => Visibility::FRIEND,
+                Visibility::Package(_) => Visibility::PACKAGE,
                 Visibility::Internal => Visibility::INTERNAL,
             }
         )
@@ -946,10 +1009,12 @@ impl AstDebug for ModuleDefinition {
             dependency_order,
             immediate_neighbors,
             used_addresses,
+            spec_uses,
             friends,
             structs,
             functions,
             constants,
+            use_funs,
             specs,
         } = self;
         if let Some(n) = package_name {
@@ -970,10 +1035,18 @@ impl AstDebug for ModuleDefinition {
             w.write(&format!("uses address {};", addr));
             w.new_line()
         }
+        for (mident, n) in spec_uses {
+            w.write(&format!("spec uses {}::{};", mident, n));
+            w.new_line()
+        }
         for (mident, _loc) in friends.key_cloned_iter() {
             w.write(&format!("friend {};", mident));
             w.new_line();
         }
+        for use_fun in use_funs {
+            use_fun.ast_debug(w);
+            w.new_line();
+        }
         for sdef in structs.key_cloned_iter() {
             sdef.ast_debug(w);
             w.new_line();
@@ -993,6 +1066,19 @@ impl AstDebug for ModuleDefinition {
     }
 }
 
+impl AstDebug for UseFun {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        let UseFun {
+            attributes,
+            function,
+            ty,
+            method,
+        } = self;
+        attributes.ast_debug(w);
+        w.write(&format!("use fun {} as {}.{};", function, ty, method));
+    }
+}
+
 pub fn ability_modifiers_ast_debug(w: &mut AstWriter, abilities: &AbilitySet) {
     if !abilities.is_empty() {
         w.write(" has ");
@@ -1022,18 +1108,35 @@ impl AstDebug for (StructName, &StructDefinition) {
             w.write("native ");
         }
 
-        w.write(&format!("struct {}", name));
+        match fields {
+            StructFields::Variants(_) => w.write(&format!("enum {}", name)),
+            _ => w.write(&format!("struct {}", name)),
+        }
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Defined(fields) => w.block(|w| {
                 w.list(fields, ",", |w, (_, f, idx_st)| {
                     let (idx, st) = idx_st;
                     w.write(&format!("{}#{}: ", idx, f));
                     st.ast_debug(w);
                     true
                 });
-            })
+            }),
+            StructFields::Variants(variants) => w.block(|w| {
+                w.list(variants, ",", |w, (_, v, (vidx, fields))| {
+                    w.write(&format!("{}#{}", vidx, v));
+                    w.write("{");
+                    w.comma(fields, |w, (_, f, idx_st)| {
+                        let (idx, st) = idx_st;
+                        w.write(&format!("{}#{}: ", idx, f));
+                        st.ast_debug(w);
+                    });
+                    w.write("}");
+                    true
+                });
+            }),
+            StructFields::Native(_) => (),
         }
     }
 }
@@ -1233,6 +1336,7 @@ impl AstDebug for (FunctionName, &Function) {
                 loc: _loc,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -1244,6 +1348,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if *inline {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -1294,6 +1401,7 @@ impl AstDebug for (ConstantName, &Constant) {
                 loc: _loc,
                 signature,
                 value,
+                assertion_message,
             },
         ) = self;
         attributes.ast_debug(w);
@@ -1302,6 +1410,9 @@ impl AstDebug for (ConstantName, &Constant) {
         w.write(" = ");
         value.ast_debug(w);
         w.write(";");
+        if let Some(message) = assertion_message {
+            w.write(&format!(" /* assertion message: {:?} */", message));
+        }
     }
 }
 
@@ -1487,6 +1598,18 @@ impl AstDebug for Exp_ {
                 w.comma(rhs, |w, e| e.ast_debug(w));
                 w.write(")");
             }
+            E::MethodCall(ed, n, tys_opt, sp!(_, rhs)) => {
+                ed.ast_debug(w);
+                w.write(&format!(".{}", n));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Pack(ma, tys_opt, fields) => {
                 ma.ast_debug(w);
                 if let Some(ss) = tys_opt {
@@ -1502,6 +1625,22 @@ impl AstDebug for Exp_ {
                 });
                 w.write("}");
             }
+            E::PackVariant(ma, v, tys_opt, fields) => {
+                ma.ast_debug(w);
+                w.write(&format!("::{}", v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_e)| {
+                    let (idx, e) = idx_e;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
             E::Vector(_loc, tys_opt, sp!(_, elems)) => {
                 w.write("vector");
                 if let Some(ss) = tys_opt {
@@ -1521,16 +1660,30 @@ impl AstDebug for Exp_ {
                 w.write(" else ");
                 f.ast_debug(w);
             }
-            E::While(b, e) => {
+            E::While(label, b, e) => {
+                label.ast_debug(w);
                 w.write("while (");
                 b.ast_debug(w);
                 w.write(")");
                 e.ast_debug(w);
             }
-            E::Loop(e) => {
+            E::Loop(label, e) => {
+                label.ast_debug(w);
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, rhs))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        rhs.ast_debug(w);
+                    })
+                });
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
             E::Lambda(sp!(_, bs), e) => {
                 w.write("fun ");
@@ -1581,8 +1734,18 @@ impl AstDebug for Exp_ {
                 w.write("abort ");
                 e.ast_debug(w);
             }
-            E::Break => w.write("break"),
-            E::Continue => w.write("continue"),
+            E::Break(label) => {
+                w.write("break");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
+            E::Continue(label) => {
+                w.write("continue");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
             E::Dereference(e) => {
                 w.write("*");
                 e.ast_debug(w)
@@ -1649,6 +1812,12 @@ impl AstDebug for ExpDotted_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", n))
             }
+            D::Index(e, i) => {
+                e.ast_debug(w);
+                w.write("[");
+                i.ast_debug(w);
+                w.write("]")
+            }
         }
     }
 }
@@ -1666,6 +1835,30 @@ impl AstDebug for Vec<LValue> {
     }
 }
 
+impl AstDebug for MatchPattern_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            MatchPattern_::Wildcard => w.write("_"),
+            MatchPattern_::Variant(ma, v, tys_opt, fields) => {
+                ma.ast_debug(w);
+                w.write(&format!("::{}", v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_b)| {
+                    let (idx, b) = idx_b;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    b.ast_debug(w);
+                });
+                w.write("}");
+            }
+        }
+    }
+}
+
 impl AstDebug for LValue_ {
     fn ast_debug(&self, w: &mut AstWriter) {
         use LValue_ as L;
@@ -30,6 +30,7 @@ pub fn verify(
         module_neighbors,
         neighbors_by_node,
         addresses_by_node,
+        spec_uses_by_module,
         ..
     } = context;
     let graph = dependency_graph(&module_neighbors);
@@ -69,6 +70,9 @@ pub fn verify(
             }
         }
     }
+    for (mident, spec_uses) in spec_uses_by_module {
+        modules.get_mut(&mident).unwrap().spec_uses = spec_uses;
+    }
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
@@ -95,8 +99,13 @@ struct Context<'a> {
     neighbors_by_node: BTreeMap<NodeIdent, UniqueMap<ModuleIdent, E::Neighbor>>,
     // All addresses used by a node
     addresses_by_node: BTreeMap<NodeIdent, BTreeSet<Address>>,
+    // The module members referenced in the specs of a module. Those of scripts are not tracked, as
+    // they are only needed to find the module members that are never used
+    spec_uses_by_module: BTreeMap<ModuleIdent, BTreeSet<(ModuleIdent, Name)>>,
     // The module or script we are currently exploring
     current_node: Option<NodeIdent>,
+    // Whether we are currently exploring a spec block
+    in_spec: bool,
 }
 
 impl<'a> Context<'a> {
@@ -106,7 +115,9 @@ impl<'a> Context<'a> {
             module_neighbors: BTreeMap::new(),
             neighbors_by_node: BTreeMap::new(),
             addresses_by_node: BTreeMap::new(),
+            spec_uses_by_module: BTreeMap::new(),
             current_node: None,
+            in_spec: false,
         }
     }
 
@@ -169,6 +180,15 @@ impl<'a> Context<'a> {
         self.add_neighbor(mident, DepType::Friend, loc);
     }
 
+    fn add_spec_usage(&mut self, mident: ModuleIdent, name: Name) {
+        if let Some(NodeIdent::Module(current_mident)) = &self.current_node {
+            self.spec_uses_by_module
+                .entry(*current_mident)
+                .or_default()
+                .insert((mident, name));
+        }
+    }
+
     fn add_address_usage(&mut self, address: Address) {
         self.addresses_by_node
             .entry(self.current_node.clone().unwrap())
@@ -280,6 +300,10 @@ fn module(context: &mut Context, mident: ModuleIdent, mdef: &E::ModuleDefinition
     mdef.functions
         .iter()
         .for_each(|(_, _, fdef)| function(context, fdef));
+    mdef.use_funs.iter().for_each(|use_fun| {
+        module_access(context, &use_fun.function);
+        module_access(context, &use_fun.ty)
+    });
     mdef.specs
         .iter()
         .for_each(|sblock| spec_block(context, sblock));
@@ -337,8 +361,14 @@ fn function_acquires(context: &mut Context, acqs: &[E::ModuleAccess]) {
 //**************************************************************************************************
 
 fn struct_def(context: &mut Context, sdef: &E::StructDefinition) {
-    if let E::StructFields::Defined(fields) = &sdef.fields {
-        fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt));
+    match &sdef.fields {
+        E::StructFields::Defined(fields) => {
+            fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt))
+        }
+        E::StructFields::Variants(variants) => variants.iter().for_each(|(_, _, (_, fields))| {
+            fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt))
+        }),
+        E::StructFields::Native(_) => (),
     }
 }
 
@@ -347,8 +377,11 @@ fn struct_def(context: &mut Context, sdef: &E::StructDefinition) {
 //**************************************************************************************************
 
 fn module_access(context: &mut Context, sp!(loc, ma_): &E::ModuleAccess) {
-    if let E::ModuleAccess_::ModuleAccess(m, _) = ma_ {
-        context.add_usage(*m, *loc)
+    if let E::ModuleAccess_::ModuleAccess(m, n) = ma_ {
+        context.add_usage(*m, *loc);
+        if context.in_spec {
+            context.add_spec_usage(*m, *n)
+        }
     }
 }
 
@@ -424,14 +457,14 @@ fn lvalue(context: &mut Context, sp!(_loc, a_): &E::LValue) {
 }
 
 fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
-    use crate::expansion::ast::{Exp_ as E, Value_ as V};
+    use crate::expansion::ast::{Exp_ as E, MatchPattern_, Value_ as V};
     match e_ {
         E::Value(sp!(_, V::Address(a))) => context.add_address_usage(*a),
 
         E::Unit { .. }
         | E::UnresolvedError
-        | E::Break
-        | E::Continue
+        | E::Break(_)
+        | E::Continue(_)
         | E::Spec(_, _)
         | E::Value(_)
         | E::Move(_)
@@ -446,7 +479,12 @@ fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
             types_opt(context, tys_opt);
             args_.iter().for_each(|e| exp(context, e))
         }
-        E::Pack(ma, tys_opt, fields) => {
+        E::MethodCall(edotted, _, tys_opt, sp!(_, args_)) => {
+            exp_dotted(context, edotted);
+            types_opt(context, tys_opt);
+            args_.iter().for_each(|e| exp(context, e))
+        }
+        E::Pack(ma, tys_opt, fields) | E::PackVariant(ma, _, tys_opt, fields) => {
             module_access(context, ma);
             types_opt(context, tys_opt);
             fields.iter().for_each(|(_, _, (_, e))| exp(context, e))
@@ -462,11 +500,22 @@ fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
             exp(context, ef)
         }
 
-        E::BinopExp(e1, _, e2) | E::Mutate(e1, e2) | E::While(e1, e2) | E::Index(e1, e2) => {
+        E::BinopExp(e1, _, e2) | E::Mutate(e1, e2) | E::While(_, e1, e2) | E::Index(e1, e2) => {
             exp(context, e1);
             exp(context, e2)
         }
         E::Block(seq) => sequence(context, seq),
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (pat, earm)) in arms {
+                if let MatchPattern_::Variant(ma, _, tys_opt, fields) = &pat.value {
+                    module_access(context, ma);
+                    types_opt(context, tys_opt);
+                    lvalues(context, fields.iter().map(|(_, _, (_, b))| b));
+                }
+                exp(context, earm)
+            }
+        }
         E::Assign(al, e) => {
             lvalues(context, &al.value);
             exp(context, e)
@@ -476,7 +525,7 @@ fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
             exp(context, e);
         }
 
-        E::Loop(e)
+        E::Loop(_, e)
         | E::Return(e)
         | E::Abort(e)
         | E::Dereference(e)
@@ -512,6 +561,10 @@ fn exp_dotted(context: &mut Context, sp!(_, ed_): &E::ExpDotted) {
     match ed_ {
         D::Exp(e) => exp(context, e),
         D::Dot(edotted, _) => exp_dotted(context, edotted),
+        D::Index(edotted, e) => {
+            exp_dotted(context, edotted);
+            exp(context, e)
+        }
     }
 }
 
@@ -520,9 +573,11 @@ fn exp_dotted(context: &mut Context, sp!(_, ed_): &E::ExpDotted) {
 //**************************************************************************************************
 
 fn spec_block(context: &mut Context, sp!(_, sb_): &E::SpecBlock) {
+    context.in_spec = true;
     sb_.members
         .iter()
-        .for_each(|sbm| spec_block_member(context, sbm))
+        .for_each(|sbm| spec_block_member(context, sbm));
+    context.in_spec = false;
 }
 
 fn spec_block_member(context: &mut Context, sp!(_, sbm_): &E::SpecBlockMember) {
@@ -4,14 +4,16 @@
 
 use crate::{
     diag,
-    diagnostics::Diagnostic,
+    diagnostics::{codes::TypeSafety, Diagnostic},
     expansion::{
         aliases::{AliasMap, AliasSet},
         ast::{self as E, Address, Fields, ModuleIdent, ModuleIdent_, SpecId},
         byte_string, hex_string,
     },
+    linters,
     parser::ast::{
         self as P, Ability, ConstantName, Field, FunctionName, ModuleName, StructName, Var,
+        VariantName,
     },
     shared::{known_attributes::AttributePosition, unique_map::UniqueMap, *},
     FullyCompiledProgram,
@@ -39,6 +41,12 @@ struct Context<'env, 'map> {
     is_source_definition: bool,
     in_spec_context: bool,
     exp_specs: BTreeMap<SpecId, E::SpecBlock>,
+    // The module whose assertions are expanded, `None` in scripts
+    assertion_module: Option<ModuleIdent>,
+    // The names of the constants of the current module or script, declared or generated
+    constant_names: BTreeSet<Symbol>,
+    // The constants generated for the assertions with messages of the current module or script
+    assertion_constants: Vec<(ConstantName, E::Constant)>,
     env: &'env mut CompilationEnv,
 }
 impl<'env, 'map> Context<'env, 'map> {
@@ -55,6 +63,9 @@ impl<'env, 'map> Context<'env, 'map> {
             is_source_definition: false,
             in_spec_context: false,
             exp_specs: BTreeMap::new(),
+            assertion_module: None,
+            constant_names: BTreeSet::new(),
+            assertion_constants: vec![],
         }
     }
 
@@ -87,6 +98,25 @@ impl<'env, 'map> Context<'env, 'map> {
     pub fn extract_exp_specs(&mut self) -> BTreeMap<SpecId, E::SpecBlock> {
         std::mem::take(&mut self.exp_specs)
     }
+
+    /// Sets the module, or the script if `None`, whose assertions are expanded, with the names of
+    /// the constants it declares
+    pub fn enter_assertion_scope(
+        &mut self,
+        assertion_module: Option<ModuleIdent>,
+        constant_names: BTreeSet<Symbol>,
+    ) {
+        self.assertion_module = assertion_module;
+        self.constant_names = constant_names;
+    }
+
+    /// Returns the constants generated for the assertions with messages of the current module or
+    /// script, and resets the assertion scope
+    pub fn extract_assertion_constants(&mut self) -> Vec<(ConstantName, E::Constant)> {
+        self.assertion_module = None;
+        self.constant_names = BTreeSet::new();
+        std::mem::take(&mut self.assertion_constants)
+    }
 }
 
 //**************************************************************************************************
@@ -400,6 +430,7 @@ fn module_(
         members,
     } = mdef;
     let attributes = flatten_attributes(context, AttributePosition::Module, attributes);
+    linters::allow_warnings(context.env, loc, &attributes);
     assert!(context.address.is_none());
     assert!(address.is_none());
     set_sender_address(context, &name, module_address);
@@ -434,13 +465,23 @@ fn module_(
     let mut functions = UniqueMap::new();
     let mut constants = UniqueMap::new();
     let mut structs = UniqueMap::new();
+    let mut use_funs = vec![];
     let mut specs = vec![];
+    let constant_names = members
+        .iter()
+        .filter_map(|member| match member {
+            P::ModuleMember::Constant(c) => Some(c.name.value()),
+            _ => None,
+        })
+        .collect();
+    context.enter_assertion_scope(Some(current_module), constant_names);
     for member in members {
         match member {
-            P::ModuleMember::Use(_) => unreachable!(),
+            P::ModuleMember::Use(u) => use_fun(context, &mut use_funs, u),
             P::ModuleMember::Friend(f) => friend(context, &mut friends, f),
             P::ModuleMember::Function(mut f) => {
-                if !context.is_source_definition {
+                // the bodies of inline functions are needed to expand calls to them
+                if !context.is_source_definition && !f.inline {
                     f.body.value = P::FunctionBody_::Native
                 }
                 function(context, &mut functions, f)
@@ -450,6 +491,7 @@ fn module_(
             P::ModuleMember::Spec(s) => specs.push(spec(context, s)),
         }
     }
+    add_assertion_constants(context, &mut constants);
     context.set_to_outer_scope(old_aliases);
 
     let def = E::ModuleDefinition {
@@ -460,10 +502,12 @@ fn module_(
         dependency_order: 0,
         immediate_neighbors: UniqueMap::new(),
         used_addresses: BTreeSet::new(),
+        spec_uses: BTreeSet::new(),
         friends,
         structs,
         constants,
         functions,
+        use_funs,
         specs,
     };
     (current_module, def)
@@ -491,6 +535,7 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
     } = pscript;
 
     let attributes = flatten_attributes(context, AttributePosition::Script, attributes);
+    linters::allow_warnings(context.env, loc, &attributes);
     let new_scope = uses(context, puses);
     let old_aliases = context.aliases.add_and_shadow_all(new_scope);
     assert!(
@@ -498,6 +543,8 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
         "ICE there should be no aliases entering a script"
     );
 
+    let constant_names = pconstants.iter().map(|c| c.name.value()).collect();
+    context.enter_assertion_scope(None, constant_names);
     let mut constants = UniqueMap::new();
     for c in pconstants {
         // TODO remove after Self rework
@@ -509,7 +556,7 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
     check_valid_module_member_name(context, ModuleMemberKind::Function, pfunction.name.0);
     let (function_name, function) = function_(context, pfunction);
     match &function.visibility {
-        E::Visibility::Public(loc) | E::Visibility::Friend(loc) => {
+        E::Visibility::Public(loc) | E::Visibility::Friend(loc) | E::Visibility::Package(loc) => {
             let msg = format!(
                 "Invalid '{}' visibility modifier. \
                 Script functions are not callable from other Move functions.",
@@ -521,6 +568,15 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
         }
         E::Visibility::Internal => (),
     }
+    if function.inline {
+        context.env.add_diag(diag!(
+            Declarations::InvalidScript,
+            (
+                function.loc,
+                "Invalid 'inline' function. 'script' functions cannot be inlined"
+            )
+        ));
+    }
     match &function.body {
         sp!(_, E::FunctionBody_::Defined(_)) => (),
         sp!(loc, E::FunctionBody_::Native) => {
@@ -534,6 +590,7 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
         }
     }
     let specs = specs(context, pspecs);
+    add_assertion_constants(context, &mut constants);
     context.set_to_outer_scope(old_aliases);
 
     E::Script {
@@ -837,6 +894,11 @@ fn aliases_from_member(
     }
 
     match member {
+        // 'use fun' declarations do not produce aliases, they are resolved with the other members
+        u @ P::ModuleMember::Use(P::UseDecl {
+            use_: P::Use::Fun { .. },
+            ..
+        }) => Some(u),
         P::ModuleMember::Use(u) => {
             use_(context, acc, u);
             None
@@ -986,6 +1048,36 @@ fn use_(context: &mut Context, acc: &mut AliasMapBuilder, u: P::UseDecl) {
                 }
             }
         }
+        P::Use::Fun { method, .. } => {
+            let msg = "Invalid 'use fun'. 'use fun' declarations are only supported at the \
+                       module level";
+            context
+                .env
+                .add_diag(diag!(Declarations::InvalidUseFun, (method.loc, msg)));
+        }
+    }
+}
+
+fn use_fun(context: &mut Context, use_funs: &mut Vec<E::UseFun>, u: P::UseDecl) {
+    let P::UseDecl { use_, attributes } = u;
+    let attributes = flatten_attributes(context, AttributePosition::Use, attributes);
+    let (pfunction, pty, method) = match use_ {
+        P::Use::Fun {
+            function,
+            ty,
+            method,
+        } => (function, ty, method),
+        _ => panic!("ICE only 'use fun' declarations remain as module members"),
+    };
+    let function_opt = name_access_chain(context, Access::ApplyPositional, pfunction);
+    let ty_opt = name_access_chain(context, Access::Type, pty);
+    if let (Some(function), Some(ty)) = (function_opt, ty_opt) {
+        use_funs.push(E::UseFun {
+            attributes,
+            function,
+            ty,
+            method,
+        })
     }
 }
 
@@ -1055,6 +1147,7 @@ fn struct_def_(
         fields: pfields,
     } = pstruct;
     let attributes = flatten_attributes(context, AttributePosition::Struct, attributes);
+    linters::allow_warnings(context.env, loc, &attributes);
     let type_parameters = struct_type_parameters(context, pty_params);
     let old_aliases = context
         .aliases
@@ -1080,7 +1173,60 @@ fn struct_fields(
     let pfields_vec = match pfields {
         P::StructFields::Native(loc) => return E::StructFields::Native(loc),
         P::StructFields::Defined(v) => v,
+        P::StructFields::Positional(tys) => {
+            if tys.is_empty() {
+                let msg = format!(
+                    "Invalid struct declaration. The positional struct '{}' must have at least \
+                     one field",
+                    sname
+                );
+                context
+                    .env
+                    .add_diag(diag!(Declarations::InvalidStruct, (sname.loc(), msg)));
+            }
+            tys.into_iter()
+                .enumerate()
+                .map(|(idx, ty)| (Field::positional(ty.loc, idx), ty))
+                .collect()
+        }
+        P::StructFields::Variants(pvariants) => {
+            if pvariants.is_empty() {
+                let msg = format!(
+                    "Invalid enum declaration. The enum '{}' must have at least one variant",
+                    sname
+                );
+                context
+                    .env
+                    .add_diag(diag!(Declarations::InvalidStruct, (sname.loc(), msg)));
+            }
+            let mut variant_map = UniqueMap::new();
+            for (idx, (variant, pfields_vec)) in pvariants.into_iter().enumerate() {
+                let field_map = struct_field_map(context, sname, pfields_vec);
+                if let Err((variant, old_loc)) = variant_map.add(variant, (idx, field_map)) {
+                    context.env.add_diag(diag!(
+                        Declarations::DuplicateItem,
+                        (
+                            variant.loc(),
+                            format!(
+                                "Duplicate definition for variant '{}' in enum '{}'",
+                                variant, sname
+                            ),
+                        ),
+                        (old_loc, "Variant previously defined here"),
+                    ));
+                }
+            }
+            return E::StructFields::Variants(variant_map);
+        }
     };
+    E::StructFields::Defined(struct_field_map(context, sname, pfields_vec))
+}
+
+fn struct_field_map(
+    context: &mut Context,
+    sname: &StructName,
+    pfields_vec: Vec<(Field, P::Type)>,
+) -> Fields<E::Type> {
     let mut field_map = UniqueMap::new();
     for (idx, (field, pt)) in pfields_vec.into_iter().enumerate() {
         let t = type_(context, pt);
@@ -1098,7 +1244,7 @@ fn struct_fields(
             ));
         }
     }
-    E::StructFields::Defined(field_map)
+    field_map
 }
 
 //**************************************************************************************************
@@ -1167,6 +1313,7 @@ fn constant_(context: &mut Context, pconstant: P::Constant) -> (ConstantName, E:
         value: pvalue,
     } = pconstant;
     let attributes = flatten_attributes(context, AttributePosition::Constant, pattributes);
+    linters::allow_warnings(context.env, loc, &attributes);
     let signature = type_(context, psignature);
     let value = exp_(context, pvalue);
     let _specs = context.extract_exp_specs();
@@ -1175,6 +1322,7 @@ fn constant_(context: &mut Context, pconstant: P::Constant) -> (ConstantName, E:
         loc,
         signature,
         value,
+        assertion_message: None,
     };
     (name, constant)
 }
@@ -1201,12 +1349,14 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         name,
         visibility: pvisibility,
         entry,
+        inline,
         signature: psignature,
         body: pbody,
         acquires,
     } = pfunction;
     assert!(context.exp_specs.is_empty());
     let attributes = flatten_attributes(context, AttributePosition::Function, pattributes);
+    linters::allow_warnings(context.env, loc, &attributes);
     let visibility = visibility(context, pvisibility);
     let (old_aliases, signature) = function_signature(context, psignature);
     let acquires = acquires
@@ -1220,6 +1370,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         loc,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -1237,6 +1388,7 @@ fn visibility(context: &mut Context, pvisibility: P::Visibility) -> E::Visibilit
             E::Visibility::Public(loc)
         }
         P::Visibility::Friend(loc) => E::Visibility::Friend(loc),
+        P::Visibility::Package(loc) => E::Visibility::Package(loc),
         P::Visibility::Internal => E::Visibility::Internal,
     }
 }
@@ -1588,17 +1740,9 @@ fn type_(context: &mut Context, sp!(loc, pt_): P::Type) -> E::Type {
         }
         PT::Ref(mut_, inner) => ET::Ref(mut_, Box::new(type_(context, *inner))),
         PT::Fun(args, result) => {
-            if context.in_spec_context {
-                let args = types(context, args);
-                let result = type_(context, *result);
-                ET::Fun(args, Box::new(result))
-            } else {
-                context.env.add_diag(diag!(
-                    Syntax::SpecContextRestricted,
-                    (loc, "`|_|_` function type only allowed in specifications")
-                ));
-                ET::UnresolvedError
-            }
+            let args = types(context, args);
+            let result = type_(context, *result);
+            ET::Fun(args, Box::new(result))
         }
     };
     sp(loc, t_)
@@ -1668,6 +1812,43 @@ fn name_access_chain(
     Some(sp(loc, tn_))
 }
 
+// Resolves a name access chain that might refer to the variant of an enum, i.e.
+// `Enum::Variant` where `Enum` is in scope or `m::Enum::Variant` where `m` is a module alias.
+// Returns the enum and the variant name, or just the module access if the chain does not refer
+// to a variant.
+fn variant_access_chain(
+    context: &mut Context,
+    access: Access,
+    chain: P::NameAccessChain,
+) -> Option<(E::ModuleAccess, Option<VariantName>)> {
+    use E::ModuleAccess_ as EN;
+    use P::{LeadingNameAccess_ as LN, NameAccessChain_ as PN};
+
+    match &chain.value {
+        PN::Two(sp!(_, LN::Name(n1)), n2) if context.aliases.module_alias_get(n1).is_none() => {
+            if let Some((mident, mem)) = context.aliases.member_alias_get(n1) {
+                let en = sp(n1.loc, EN::ModuleAccess(mident, mem));
+                return Some((en, Some(VariantName(*n2))));
+            }
+        }
+        PN::Three(sp!(ident_loc, (sp!(_, LN::Name(n1)), n2)), n3)
+            if !context
+                .named_address_mapping
+                .as_ref()
+                .map(|m| m.contains_key(&n1.value))
+                .unwrap_or(false) =>
+        {
+            if let Some(mident) = context.aliases.module_alias_get(n1) {
+                let en = sp(*ident_loc, EN::ModuleAccess(mident, *n2));
+                return Some((en, Some(VariantName(*n3))));
+            }
+        }
+        _ => (),
+    }
+    let en = name_access_chain(context, access, chain)?;
+    Some((en, None))
+}
+
 fn name_access_chain_to_module_ident(
     context: &mut Context,
     sp!(loc, pn_): P::NameAccessChain,
@@ -1826,16 +2007,24 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
             EE::UnresolvedError
         }
         PE::Name(pn, ptys_opt) => {
-            let en_opt = name_access_chain(context, Access::Term, pn);
+            let en_opt = variant_access_chain(context, Access::Term, pn);
             let tys_opt = optional_types(context, ptys_opt);
             match en_opt {
-                Some(en) => EE::Name(en, tys_opt),
+                Some((en, None)) => EE::Name(en, tys_opt),
+                Some((en, Some(variant))) => {
+                    EE::PackVariant(en, variant, tys_opt, UniqueMap::new())
+                }
                 None => {
                     assert!(context.env.has_errors());
                     EE::UnresolvedError
                 }
             }
         }
+        PE::Call(sp!(_, P::NameAccessChain_::One(n)), true, None, sp!(rloc, prs))
+            if !context.in_spec_context && is_assertion_macro(&n, &prs) =>
+        {
+            assertion_macro(context, loc, n, sp(rloc, prs))
+        }
         PE::Call(pn, is_macro, ptys_opt, sp!(rloc, prs)) => {
             let tys_opt = optional_types(context, ptys_opt);
             let ers = sp(rloc, exps(context, prs));
@@ -1849,7 +2038,7 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
             }
         }
         PE::Pack(pn, ptys_opt, pfields) => {
-            let en_opt = name_access_chain(context, Access::ApplyNamed, pn);
+            let en_opt = variant_access_chain(context, Access::ApplyNamed, pn);
             let tys_opt = optional_types(context, ptys_opt);
             let efields_vec = pfields
                 .into_iter()
@@ -1857,7 +2046,8 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
                 .collect();
             let efields = fields(context, loc, "construction", "argument", efields_vec);
             match en_opt {
-                Some(en) => EE::Pack(en, tys_opt, efields),
+                Some((en, None)) => EE::Pack(en, tys_opt, efields),
+                Some((en, Some(variant))) => EE::PackVariant(en, variant, tys_opt, efields),
                 None => {
                     assert!(context.env.has_errors());
                     EE::UnresolvedError
@@ -1878,25 +2068,32 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
             };
             EE::IfElse(eb, et, ef)
         }
-        PE::While(pb, ploop) => EE::While(exp(context, *pb), exp(context, *ploop)),
-        PE::Loop(ploop) => EE::Loop(exp(context, *ploop)),
+        PE::While(label, pb, ploop) => EE::While(label, exp(context, *pb), exp(context, *ploop)),
+        PE::For(label, pv, piter, ploop) => for_loop(context, label, pv, *piter, *ploop),
+        PE::Loop(label, ploop) => EE::Loop(label, exp(context, *ploop)),
+        PE::Match(pe, parms) => {
+            let e = exp(context, *pe);
+            let arms = parms
+                .into_iter()
+                .map(|arm| match_arm(context, arm))
+                .collect::<Option<Vec<_>>>();
+            match arms {
+                Some(arms) => EE::Match(e, arms),
+                None => {
+                    assert!(context.env.has_errors());
+                    EE::UnresolvedError
+                }
+            }
+        }
         PE::Block(seq) => EE::Block(sequence(context, loc, seq)),
         PE::Lambda(pbs, pe) => {
-            if !context.in_spec_context {
-                context.env.add_diag(diag!(
-                    Syntax::SpecContextRestricted,
-                    (loc, "lambda expression only allowed in specifications"),
-                ));
-                EE::UnresolvedError
-            } else {
-                let bs_opt = bind_list(context, pbs);
-                let e = exp_(context, *pe);
-                match bs_opt {
-                    Some(bs) => EE::Lambda(bs, Box::new(e)),
-                    None => {
-                        assert!(context.env.has_errors());
-                        EE::UnresolvedError
-                    }
+            let bs_opt = bind_list(context, pbs);
+            let e = exp_(context, *pe);
+            match bs_opt {
+                Some(bs) => EE::Lambda(bs, Box::new(e)),
+                None => {
+                    assert!(context.env.has_errors());
+                    EE::UnresolvedError
                 }
             }
         }
@@ -1942,6 +2139,7 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
                 Some(LValue::FieldMutate(edotted)) => EE::FieldMutate(edotted, er),
             }
         }
+        PE::AssignOp(plhs, op, prhs) => assign_op(context, loc, *plhs, op, *prhs),
         PE::Return(pe_opt) => {
             let ev = match pe_opt {
                 None => Box::new(sp(loc, EE::Unit { trailing: false })),
@@ -1950,8 +2148,8 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
             EE::Return(ev)
         }
         PE::Abort(pe) => EE::Abort(exp(context, *pe)),
-        PE::Break => EE::Break,
-        PE::Continue => EE::Continue,
+        PE::Break(label) => EE::Break(label),
+        PE::Continue(label) => EE::Continue(label),
         PE::Dereference(pe) => EE::Dereference(exp(context, *pe)),
         PE::UnaryExp(op, pe) => EE::UnaryExp(op, exp(context, *pe)),
         PE::BinopExp(pl, op, pr) => {
@@ -1976,18 +2174,34 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
                 EE::UnresolvedError
             }
         },
+        PE::DotCall(_, method, _, _) if context.in_spec_context => {
+            let msg = "method calls are not supported in specifications";
+            context
+                .env
+                .add_diag(diag!(Syntax::SpecContextRestricted, (method.loc, msg)));
+            EE::UnresolvedError
+        }
+        PE::DotCall(plhs, method, ptys_opt, sp!(rloc, prs)) => {
+            let edotted_opt = exp_dotted(context, *plhs);
+            let tys_opt = optional_types(context, ptys_opt);
+            let ers = sp(rloc, exps(context, prs));
+            match edotted_opt {
+                Some(edotted) => EE::MethodCall(Box::new(edotted), method, tys_opt, ers),
+                None => {
+                    assert!(context.env.has_errors());
+                    EE::UnresolvedError
+                }
+            }
+        }
         PE::Cast(e, ty) => EE::Cast(exp(context, *e), type_(context, ty)),
-        PE::Index(e, i) => {
-            if context.in_spec_context {
-                EE::Index(exp(context, *e), exp(context, *i))
-            } else {
-                let msg = "`_[_]` index operator only allowed in specifications";
-                context
-                    .env
-                    .add_diag(diag!(Syntax::SpecContextRestricted, (loc, msg)));
+        PE::Index(e, i) if context.in_spec_context => EE::Index(exp(context, *e), exp(context, *i)),
+        pdotted_ @ PE::Index(_, _) => match exp_dotted(context, sp(loc, pdotted_)) {
+            Some(edotted) => EE::ExpDotted(Box::new(edotted)),
+            None => {
+                assert!(context.env.has_errors());
                 EE::UnresolvedError
             }
-        }
+        },
         PE::Annotate(e, ty) => EE::Annotate(exp(context, *e), type_(context, ty)),
         PE::Spec(_) if context.in_spec_context => {
             context.env.add_diag(diag!(
@@ -2000,11 +2214,224 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
             let (spec_id, unbound_names) = context.bind_exp_spec(spec_block);
             EE::Spec(spec_id, unbound_names)
         }
-        PE::UnresolvedError => panic!("ICE error should have been thrown"),
+        PE::UnresolvedError => EE::UnresolvedError,
     };
     sp(loc, e_)
 }
 
+// Desugars a 'for' loop into the existing 'while' form, so that 'break' and 'continue' as well as
+// the borrow and locals checks apply unchanged. The loop counter is advanced before the body is
+// run, which keeps 'continue' from skipping the increment.
+//
+//     for (x in a..b) body
+//         ~> { let ($for_idx, $for_end) = (a, b);
+//              while ($for_idx < $for_end) { let x = $for_idx; $for_idx = $for_idx + 1; body } }
+//
+//     for (x in &v) body
+//         ~> { let $for_vec = &v; let $for_idx = 0; let $for_end = 0x1::vector::length($for_vec);
+//              while ($for_idx < $for_end) {
+//                  let x = 0x1::vector::borrow($for_vec, $for_idx); $for_idx = $for_idx + 1; body
+//              } }
+//
+// with 'borrow_mut' being used for '&mut v'. The hidden locals cannot be written in source. A
+// label on the 'for' loop is kept on the 'while' loop.
+fn for_loop(
+    context: &mut Context,
+    label: Option<P::BlockLabel>,
+    pv: Var,
+    piter: P::Exp,
+    ploop: P::Exp,
+) -> E::Exp_ {
+    use E::{Exp_ as EE, SequenceItem_ as ES};
+    use P::Exp_ as PE;
+
+    fn vector_call(loc: Loc, name: &str, args: Vec<E::Exp>) -> E::Exp {
+        let mident = ModuleIdent_::vector(loc);
+        let access = sp(
+            loc,
+            E::ModuleAccess_::ModuleAccess(mident, sp(loc, Symbol::from(name))),
+        );
+        sp(loc, EE::Call(access, false, None, sp(loc, args)))
+    }
+
+    check_valid_local_name(context, &pv);
+    let iter_loc = piter.loc;
+    let idx = sp(iter_loc, Symbol::from("$for_idx"));
+    let end = sp(iter_loc, Symbol::from("$for_end"));
+    let vec = sp(iter_loc, Symbol::from("$for_vec"));
+    let mut items = VecDeque::new();
+    let elem = match piter.value {
+        PE::BinopExp(plo, sp!(_, P::BinOp_::Range), phi) => {
+            let lo = exp_(context, *plo);
+            let hi = exp_(context, *phi);
+            let bounds = sp(iter_loc, EE::ExpList(vec![lo, hi]));
+            items.push_back(sp(
+                iter_loc,
+                ES::Bind(hidden_lvalues(iter_loc, vec![idx, end]), bounds),
+            ));
+            hidden_local(idx)
+        }
+        PE::Borrow(mut_, pvec) => {
+            let ev = sp(iter_loc, EE::Borrow(mut_, exp(context, *pvec)));
+            let zero = sp(
+                iter_loc,
+                EE::Value(sp(iter_loc, E::Value_::InferredNum(0u64.into()))),
+            );
+            let len = vector_call(iter_loc, "length", vec![hidden_local(vec)]);
+            items.push_back(hidden_bind(vec, ev));
+            items.push_back(hidden_bind(idx, zero));
+            items.push_back(hidden_bind(end, len));
+            let borrow = if mut_ { "borrow_mut" } else { "borrow" };
+            vector_call(iter_loc, borrow, vec![hidden_local(vec), hidden_local(idx)])
+        }
+        _ => {
+            context.env.add_diag(diag!(
+                Syntax::InvalidForLoop,
+                (
+                    iter_loc,
+                    "Invalid 'for' loop. Expected a range 'a..b' or a vector reference '&v' or \
+                     '&mut v'"
+                )
+            ));
+            return EE::UnresolvedError;
+        }
+    };
+
+    let loop_loc = ploop.loc;
+    let body = exp_(context, ploop);
+    let one = sp(
+        iter_loc,
+        EE::Value(sp(iter_loc, E::Value_::InferredNum(1u64.into()))),
+    );
+    let lt = sp(iter_loc, P::BinOp_::Lt);
+    let add = sp(iter_loc, P::BinOp_::Add);
+    let cond = sp(
+        iter_loc,
+        EE::BinopExp(Box::new(hidden_local(idx)), lt, Box::new(hidden_local(end))),
+    );
+    let next = sp(
+        iter_loc,
+        EE::BinopExp(Box::new(hidden_local(idx)), add, Box::new(one)),
+    );
+    let incr = sp(
+        iter_loc,
+        EE::Assign(hidden_lvalues(iter_loc, vec![idx]), Box::new(next)),
+    );
+    let loop_items = VecDeque::from([
+        sp(
+            pv.loc(),
+            ES::Bind(hidden_lvalues(pv.loc(), vec![pv.0]), elem),
+        ),
+        sp(iter_loc, ES::Seq(incr)),
+        sp(loop_loc, ES::Seq(body)),
+    ]);
+    let eloop = sp(loop_loc, EE::Block(loop_items));
+    items.push_back(sp(
+        loop_loc,
+        ES::Seq(sp(
+            loop_loc,
+            EE::While(label, Box::new(cond), Box::new(eloop)),
+        )),
+    ));
+    EE::Block(items)
+}
+
+// Lowers a compound assignment so that the place on its left is evaluated only once. As for
+// primitive types in Rust, the right-hand side is evaluated first, which keeps it from conflicting
+// with the mutable borrow of the place.
+//
+//     x op= e      ~> x = x op e
+//     *r op= e     ~> { let $rhs = e; let $lhs = r; *$lhs = *$lhs op $rhs }
+//     e.f op= e'   ~> { let $rhs = e'; let $lhs = &mut e.f; *$lhs = *$lhs op $rhs }
+//
+// Whether the place is mutable is left to the typing pass.
+fn assign_op(context: &mut Context, loc: Loc, plhs: P::Exp, op: P::BinOp, prhs: P::Exp) -> E::Exp_ {
+    use E::{Exp_ as EE, SequenceItem_ as ES};
+
+    let lhs_loc = plhs.loc;
+    let lhs_opt = lvalues(context, plhs);
+    let rhs = exp_(context, prhs);
+    let place = match lhs_opt {
+        None => {
+            assert!(context.env.has_errors());
+            return EE::UnresolvedError;
+        }
+        Some(LValue::Assigns(sp!(_, lvalues))) => match &lvalues[..] {
+            [sp!(_, E::LValue_::Var(sp!(_, E::ModuleAccess_::Name(n)), None))] => {
+                let value = sp(
+                    loc,
+                    EE::BinopExp(Box::new(hidden_local(*n)), op, Box::new(rhs)),
+                );
+                return EE::Assign(sp(lhs_loc, lvalues), Box::new(value));
+            }
+            _ => {
+                context.env.add_diag(diag!(
+                    Syntax::InvalidLValue,
+                    (
+                        lhs_loc,
+                        format!(
+                            "Invalid '{}=' assignment. Expected a local, a dereference '*e', a \
+                             field 'e.f', or an index 'e[i]'",
+                            op
+                        )
+                    )
+                ));
+                return EE::UnresolvedError;
+            }
+        },
+        Some(LValue::Mutate(er)) => *er,
+        Some(LValue::FieldMutate(edotted)) => sp(
+            lhs_loc,
+            EE::Borrow(true, Box::new(sp(lhs_loc, EE::ExpDotted(edotted)))),
+        ),
+    };
+    let lhs = sp(lhs_loc, Symbol::from("$lhs"));
+    let rhs_var = sp(rhs.loc, Symbol::from("$rhs"));
+    let current = sp(lhs_loc, EE::Dereference(Box::new(hidden_local(lhs))));
+    let value = sp(
+        loc,
+        EE::BinopExp(Box::new(current), op, Box::new(hidden_local(rhs_var))),
+    );
+    let mutate = sp(
+        loc,
+        EE::Mutate(Box::new(hidden_local(lhs)), Box::new(value)),
+    );
+    let items = VecDeque::from([
+        hidden_bind(rhs_var, rhs),
+        hidden_bind(lhs, place),
+        sp(loc, ES::Seq(mutate)),
+    ]);
+    EE::Block(items)
+}
+
+// A use of a local introduced by desugaring
+fn hidden_local(n: Name) -> E::Exp {
+    sp(
+        n.loc,
+        E::Exp_::Name(sp(n.loc, E::ModuleAccess_::Name(n)), None),
+    )
+}
+
+fn hidden_lvalues(loc: Loc, names: Vec<Name>) -> E::LValueList {
+    let lvalues = names
+        .into_iter()
+        .map(|n| {
+            sp(
+                n.loc,
+                E::LValue_::Var(sp(n.loc, E::ModuleAccess_::Name(n)), None),
+            )
+        })
+        .collect();
+    sp(loc, lvalues)
+}
+
+fn hidden_bind(n: Name, e: E::Exp) -> E::SequenceItem {
+    sp(
+        n.loc,
+        E::SequenceItem_::Bind(hidden_lvalues(n.loc, vec![n]), e),
+    )
+}
+
 fn exp_dotted(context: &mut Context, sp!(loc, pdotted_): P::Exp) -> Option<E::ExpDotted> {
     use E::ExpDotted_ as EE;
     use P::Exp_ as PE;
@@ -2013,6 +2440,10 @@ fn exp_dotted(context: &mut Context, sp!(loc, pdotted_): P::Exp) -> Option<E::Ex
             let lhs = exp_dotted(context, *plhs)?;
             EE::Dot(Box::new(lhs), field)
         }
+        PE::Index(plhs, pindex) if !context.in_spec_context => {
+            let lhs = exp_dotted(context, *plhs)?;
+            EE::Index(Box::new(lhs), exp(context, *pindex))
+        }
         pe_ => EE::Exp(exp_(context, sp(loc, pe_))),
     };
     Some(sp(loc, edotted_))
@@ -2113,6 +2544,148 @@ fn num_too_big_error(loc: Loc, type_description: &'static str) -> Diagnostic {
     )
 }
 
+//**************************************************************************************************
+// Assertions
+//**************************************************************************************************
+
+const ASSERT_MACRO: &str = "assert";
+const ASSERT_EQ_MACRO: &str = "assert_eq";
+const ASSERT_NE_MACRO: &str = "assert_ne";
+
+/// The abort code of the first assertion with a message of a module or script. The abort codes of
+/// assertions with messages are above it, so that they do not collide with user-defined codes
+pub const ASSERTION_ABORT_CODE_BASE: u64 = 1 << 63;
+
+const ASSERTION_CONSTANT_PREFIX: &str = "EASSERTION_";
+
+// Is the call `n!(args)` expanded by `assertion_macro`: `assert!(cond, b"message")`, or
+// `assert_eq!`/`assert_ne!` with any arguments
+fn is_assertion_macro(n: &Name, args: &[P::Exp]) -> bool {
+    match n.value.as_str() {
+        ASSERT_MACRO => args.len() == 2 && is_assertion_message(&args[1]),
+        ASSERT_EQ_MACRO | ASSERT_NE_MACRO => true,
+        _ => false,
+    }
+}
+
+fn is_assertion_message(e: &P::Exp) -> bool {
+    matches!(&e.value, P::Exp_::Value(sp!(_, P::Value_::ByteString(_))))
+}
+
+// Expands an assertion with a message to `assert!(cond, code)`, where `code` is a constant
+// generated for the message. `assert_eq!(left, right[, message_or_code])` and
+// `assert_ne!(left, right[, message_or_code])` assert `left == right` and `left != right`, with
+// a default message if there is none
+fn assertion_macro(
+    context: &mut Context,
+    loc: Loc,
+    n: Name,
+    sp!(rloc, pargs): Spanned<Vec<P::Exp>>,
+) -> E::Exp_ {
+    use E::Exp_ as EE;
+
+    let assert = sp(
+        n.loc,
+        E::ModuleAccess_::Name(sp(n.loc, ASSERT_MACRO.into())),
+    );
+    let op = match n.value.as_str() {
+        ASSERT_EQ_MACRO => P::BinOp_::Eq,
+        ASSERT_NE_MACRO => P::BinOp_::Neq,
+        _ => {
+            let mut pargs = pargs.into_iter();
+            let cond = exp_(context, pargs.next().unwrap());
+            let code = assertion_code(context, loc, pargs.next().unwrap());
+            return EE::Call(assert, true, None, sp(rloc, vec![cond, code]));
+        }
+    };
+    let arity = pargs.len();
+    if !(2..=3).contains(&arity) {
+        let code = if arity < 2 {
+            TypeSafety::TooFewArguments
+        } else {
+            TypeSafety::TooManyArguments
+        };
+        let msg = format!(
+            "Invalid call of '{}!'. The call expected 2 or 3 argument(s) but got {}",
+            n, arity
+        );
+        context.env.add_diag(diag!(code, (rloc, msg)));
+        return EE::UnresolvedError;
+    }
+    let mut pargs = pargs.into_iter();
+    let left = exp(context, pargs.next().unwrap());
+    let right = exp(context, pargs.next().unwrap());
+    let cond = sp(loc, EE::BinopExp(left, sp(n.loc, op), right));
+    let code = match pargs.next() {
+        Some(pcode) => assertion_code(context, loc, pcode),
+        None => {
+            let message = format!("assertion `left {} right` failed", op);
+            assertion_constant(context, loc, message)
+        }
+    };
+    EE::Call(assert, true, None, sp(rloc, vec![cond, code]))
+}
+
+// The abort code of an assertion: a constant generated for the message if `pcode` is a byte
+// string, or `pcode` itself otherwise
+fn assertion_code(context: &mut Context, loc: Loc, pcode: P::Exp) -> E::Exp {
+    match pcode.value {
+        P::Exp_::Value(sp!(vloc, P::Value_::ByteString(s))) => {
+            match byte_string::decode(vloc, &s) {
+                Ok(bytes) => {
+                    let message = String::from_utf8_lossy(&bytes).into_owned();
+                    assertion_constant(context, loc, message)
+                }
+                Err(e) => {
+                    context.env.add_diags(e);
+                    sp(vloc, E::Exp_::UnresolvedError)
+                }
+            }
+        }
+        _ => exp_(context, pcode),
+    }
+}
+
+// Generates the constant declaring the abort code of the assertion at `loc`, and returns a
+// reference to it
+fn assertion_constant(context: &mut Context, loc: Loc, message: String) -> E::Exp {
+    let index = context.assertion_constants.len();
+    let name = (index..)
+        .map(|i| Symbol::from(format!("{}{}", ASSERTION_CONSTANT_PREFIX, i)))
+        .find(|name| !context.constant_names.contains(name))
+        .unwrap();
+    context.constant_names.insert(name);
+    let code = ASSERTION_ABORT_CODE_BASE + index as u64;
+    let u64_type = sp(loc, E::ModuleAccess_::Name(sp(loc, Symbol::from("u64"))));
+    let constant = E::Constant {
+        attributes: UniqueMap::new(),
+        loc,
+        signature: sp(loc, E::Type_::Apply(u64_type, vec![])),
+        value: sp(loc, E::Exp_::Value(sp(loc, E::Value_::U64(code)))),
+        assertion_message: Some(message),
+    };
+    let name = sp(loc, name);
+    context
+        .assertion_constants
+        .push((ConstantName(name), constant));
+    let access = match context.assertion_module {
+        Some(mident) => E::ModuleAccess_::ModuleAccess(mident, name),
+        None => E::ModuleAccess_::Name(name),
+    };
+    sp(loc, E::Exp_::Name(sp(loc, access), None))
+}
+
+fn add_assertion_constants(
+    context: &mut Context,
+    constants: &mut UniqueMap<ConstantName, E::Constant>,
+) {
+    for (name, constant) in context.extract_assertion_constants() {
+        constants
+            .add(name, constant)
+            .expect("ICE generated constant names are unique")
+    }
+}
+
 //**************************************************************************************************
 // Fields
 //**************************************************************************************************
@@ -2141,6 +2714,50 @@ fn fields<T>(
     fmap
 }
 
+fn match_arm(context: &mut Context, sp!(loc, (ppat, pe)): P::MatchArm) -> Option<E::MatchArm> {
+    let pat = match_pattern(context, ppat);
+    let e = exp_(context, pe);
+    Some(sp(loc, (pat?, e)))
+}
+
+fn match_pattern(
+    context: &mut Context,
+    sp!(loc, ppat_): P::MatchPattern,
+) -> Option<E::MatchPattern> {
+    use E::MatchPattern_ as EM;
+    use P::MatchPattern_ as PM;
+    let pat_ = match ppat_ {
+        PM::Wildcard => EM::Wildcard,
+        PM::Variant(pn, ptys_opt, pfields) => {
+            let (en, variant_opt) = variant_access_chain(context, Access::ApplyNamed, *pn)?;
+            let tys_opt = optional_types(context, ptys_opt);
+            let vfields: Option<Vec<(Field, E::LValue)>> = pfields
+                .into_iter()
+                .map(|(f, pb)| Some((f, bind(context, pb)?)))
+                .collect();
+            let fields = fields(context, loc, "match pattern", "binding", vfields?);
+            match variant_opt {
+                Some(variant) => EM::Variant(en, variant, tys_opt, fields),
+                None => {
+                    context.env.add_diag(diag!(
+                        NameResolution::UnboundVariant,
+                        (
+                            en.loc,
+                            format!(
+                                "Invalid match pattern. Expected an enum variant of the form \
+                                 'Enum::Variant', found '{}'",
+                                en
+                            )
+                        )
+                    ));
+                    return None;
+                }
+            }
+        }
+    };
+    Some(sp(loc, pat_))
+}
+
 //**************************************************************************************************
 // LValues
 //**************************************************************************************************
@@ -2173,8 +2790,32 @@ fn bind(context: &mut Context, sp!(loc, pb_): P::Bind) -> Option<E::LValue> {
             check_valid_local_name(context, &v);
             EL::Var(sp(loc, E::ModuleAccess_::Name(v.0)), None)
         }
+        PB::PositionalUnpack(ptn, ptys_opt, pbinds) => {
+            let pfields = pbinds
+                .into_iter()
+                .enumerate()
+                .map(|(idx, pb)| (Field::positional(pb.loc, idx), pb))
+                .collect();
+            return bind(context, sp(loc, PB::Unpack(ptn, ptys_opt, pfields)));
+        }
         PB::Unpack(ptn, ptys_opt, pfields) => {
-            let tn = name_access_chain(context, Access::ApplyNamed, *ptn)?;
+            let tn = match variant_access_chain(context, Access::ApplyNamed, *ptn)? {
+                (tn, None) => tn,
+                (tn, Some(variant)) => {
+                    context.env.add_diag(diag!(
+                        Syntax::InvalidLValue,
+                        (
+                            loc,
+                            format!(
+                                "Invalid binding. The variant '{}::{}' cannot be unpacked \
+                                 here, use a 'match' expression instead",
+                                tn, variant
+                            )
+                        )
+                    ));
+                    return None;
+                }
+            };
             let tys_opt = optional_types(context, ptys_opt);
             let vfields: Option<Vec<(Field, E::LValue)>> = pfields
                 .into_iter()
@@ -2211,6 +2852,10 @@ fn lvalues(context: &mut Context, sp!(loc, e_): P::Exp) -> Option<LValue> {
             let dotted = exp_dotted(context, sp(loc, pdotted_))?;
             L::FieldMutate(Box::new(dotted))
         }
+        pdotted_ @ PE::Index(_, _) if !context.in_spec_context => {
+            let dotted = exp_dotted(context, sp(loc, pdotted_))?;
+            L::FieldMutate(Box::new(dotted))
+        }
         _ => L::Assigns(sp(loc, vec![assign(context, sp(loc, e_))?])),
     };
     Some(al)
@@ -2280,6 +2925,17 @@ fn assign(context: &mut Context, sp!(loc, e_): P::Exp) -> Option<E::LValue> {
             let efields = assign_unpack_fields(context, loc, pfields)?;
             EL::Unpack(en, tys_opt, efields)
         }
+        PE::Call(pn, false, ptys_opt, sp!(_, pargs)) => {
+            let en = name_access_chain(context, Access::ApplyPositional, pn)?;
+            let tys_opt = optional_types(context, ptys_opt);
+            let pfields = pargs
+                .into_iter()
+                .enumerate()
+                .map(|(idx, e)| (Field::positional(e.loc, idx), e))
+                .collect();
+            let efields = assign_unpack_fields(context, loc, pfields)?;
+            EL::Unpack(en, tys_opt, efields)
+        }
         _ => {
             context.env.add_diag(diag!(
                 Syntax::InvalidLValue,
@@ -2352,8 +3008,8 @@ fn unbound_names_exp(unbound: &mut BTreeSet<Name>, sp!(_, e_): &E::Exp) {
     use E::Exp_ as EE;
     match e_ {
         EE::Value(_)
-        | EE::Break
-        | EE::Continue
+        | EE::Break(_)
+        | EE::Continue(_)
         | EE::UnresolvedError
         | EE::Name(sp!(_, E::ModuleAccess_::ModuleAccess(..)), _)
         | EE::Unit { .. } => (),
@@ -2366,17 +3022,36 @@ fn unbound_names_exp(unbound: &mut BTreeSet<Name>, sp!(_, e_): &E::Exp) {
         EE::Call(_, _, _, sp!(_, es_)) | EE::Vector(_, _, sp!(_, es_)) => {
             unbound_names_exps(unbound, es_)
         }
-        EE::Pack(_, _, es) => unbound_names_exps(unbound, es.iter().map(|(_, _, (_, e))| e)),
+        EE::MethodCall(ed, _, _, sp!(_, es_)) => {
+            unbound_names_exps(unbound, es_);
+            unbound_names_dotted(unbound, ed)
+        }
+        EE::Pack(_, _, es) | EE::PackVariant(_, _, _, es) => {
+            unbound_names_exps(unbound, es.iter().map(|(_, _, (_, e))| e))
+        }
         EE::IfElse(econd, et, ef) => {
             unbound_names_exp(unbound, ef);
             unbound_names_exp(unbound, et);
             unbound_names_exp(unbound, econd)
         }
-        EE::While(econd, eloop) => {
+        EE::While(_, econd, eloop) => {
             unbound_names_exp(unbound, eloop);
             unbound_names_exp(unbound, econd)
         }
-        EE::Loop(eloop) => unbound_names_exp(unbound, eloop),
+        EE::Loop(_, eloop) => unbound_names_exp(unbound, eloop),
+        EE::Match(esubject, arms) => {
+            for sp!(_, (pat, earm)) in arms {
+                let mut arm_unbound = BTreeSet::new();
+                unbound_names_exp(&mut arm_unbound, earm);
+                if let E::MatchPattern_::Variant(_, _, _, efields) = &pat.value {
+                    efields
+                        .iter()
+                        .for_each(|(_, _, (_, l))| unbound_names_bind(&mut arm_unbound, l));
+                }
+                unbound.extend(arm_unbound);
+            }
+            unbound_names_exp(unbound, esubject)
+        }
 
         EE::Block(seq) => unbound_names_sequence(unbound, seq),
         EE::Lambda(ls, er) => {
@@ -2506,6 +3181,10 @@ fn unbound_names_dotted(unbound: &mut BTreeSet<Name>, sp!(_, edot_): &E::ExpDott
     match edot_ {
         ED::Exp(e) => unbound_names_exp(unbound, e),
         ED::Dot(d, _) => unbound_names_dotted(unbound, d),
+        ED::Index(d, i) => {
+            unbound_names_exp(unbound, i);
+            unbound_names_dotted(unbound, d)
+        }
     }
 }
 
@@ -9,12 +9,16 @@ use crate::{
     },
     naming::ast::{BuiltinTypeName, BuiltinTypeName_, StructTypeParameter, TParam},
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        BinOp, BlockLabel, ConstantName, Field, FunctionName, StructName, UnaryOp, Var,
+        VariantName, ENTRY_MODIFIER,
+    },
+    shared::{
+        ast_debug::*, ast_dump::serialize_map_as_seq, unique_map::UniqueMap, NumericalAddress,
     },
-    shared::{ast_debug::*, unique_map::UniqueMap, NumericalAddress},
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 // High Level IR
@@ -23,7 +27,7 @@ use std::collections::{BTreeMap, BTreeSet, VecDeque};
 // Program
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub modules: UniqueMap<ModuleIdent, ModuleDefinition>,
     pub scripts: BTreeMap<Symbol, Script>,
@@ -33,7 +37,7 @@ pub struct Program {
 // Scripts
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Script {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -48,7 +52,7 @@ pub struct Script {
 // Modules
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDefinition {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -66,7 +70,7 @@ pub struct ModuleDefinition {
 // Structs
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct StructDefinition {
     pub attributes: Attributes,
     pub abilities: AbilitySet,
@@ -74,9 +78,10 @@ pub struct StructDefinition {
     pub fields: StructFields,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum StructFields {
     Defined(Vec<(Field, BaseType)>),
+    Variants(Vec<(VariantName, Vec<(Field, BaseType)>)>),
     Native(Loc),
 }
 
@@ -84,26 +89,28 @@ pub enum StructFields {
 // Constants
 //**************************************************************************************************
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Constant {
     pub attributes: Attributes,
     pub loc: Loc,
     pub signature: BaseType,
     pub value: (UniqueMap<Var, SingleType>, Block),
+    // the message of the assertion the constant is the abort code of, if generated for one
+    pub assertion_message: Option<String>,
 }
 
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize)]
 pub struct FunctionSignature {
     pub type_parameters: Vec<TParam>,
     pub parameters: Vec<(Var, SingleType)>,
     pub return_type: Type,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub enum FunctionBody_ {
     Native,
     Defined {
@@ -113,21 +120,33 @@ pub enum FunctionBody_ {
 }
 pub type FunctionBody = Spanned<FunctionBody_>;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
     pub signature: FunctionSignature,
+    #[serde(serialize_with = "serialize_map_as_seq")]
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
+    pub inlined_calls: Vec<InlinedCall>,
+}
+
+/// A call to an inline function that was expanded into the body of the calling function
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InlinedCall {
+    pub loc: Loc,
+    /// The location of the body of the inline function
+    pub body_loc: Loc,
+    pub module: ModuleIdent,
+    pub function: FunctionName,
 }
 
 //**************************************************************************************************
 // Types
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum TypeName_ {
     Builtin(BuiltinTypeName),
@@ -135,7 +154,7 @@ pub enum TypeName_ {
 }
 pub type TypeName = Spanned<TypeName_>;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum BaseType_ {
     Param(TParam),
@@ -145,14 +164,14 @@ pub enum BaseType_ {
 }
 pub type BaseType = Spanned<BaseType_>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum SingleType_ {
     Base(BaseType),
     Ref(bool, BaseType),
 }
 pub type SingleType = Spanned<SingleType_>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Type_ {
     Unit,
@@ -165,7 +184,7 @@ pub type Type = Spanned<Type_>;
 // Statements
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Statement_ {
     Command(Command),
@@ -175,10 +194,12 @@ pub enum Statement_ {
         else_block: Block,
     },
     While {
+        label: Option<BlockLabel>,
         cond: (Block, Box<Exp>),
         block: Block,
     },
     Loop {
+        label: Option<BlockLabel>,
         block: Block,
         has_break: bool,
     },
@@ -191,14 +212,14 @@ pub type BasicBlocks = BTreeMap<Label, BasicBlock>;
 
 pub type BasicBlock = VecDeque<Command>;
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, PartialOrd, Ord, Serialize)]
 pub struct Label(pub usize);
 
 //**************************************************************************************************
 // Commands
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Command_ {
     Assign(Vec<LValue>, Box<Exp>),
@@ -208,8 +229,8 @@ pub enum Command_ {
         from_user: bool,
         exp: Exp,
     },
-    Break,
-    Continue,
+    Break(Option<BlockLabel>),
+    Continue(Option<BlockLabel>),
     IgnoreAndPop {
         pop_num: usize,
         exp: Exp,
@@ -226,11 +247,12 @@ pub enum Command_ {
 }
 pub type Command = Spanned<Command_>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum LValue_ {
     Ignore,
     Var(Var, Box<SingleType>),
     Unpack(StructName, Vec<BaseType>, Vec<(Field, LValue)>),
+    UnpackVariant(StructName, VariantName, Vec<BaseType>, Vec<(Field, LValue)>),
 }
 pub type LValue = Spanned<LValue_>;
 
@@ -238,23 +260,24 @@ pub type LValue = Spanned<LValue_>;
 // Expressions
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum UnitCase {
     Trailing,
     Implicit,
     FromUser,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct ModuleCall {
     pub module: ModuleIdent,
     pub name: FunctionName,
     pub type_arguments: Vec<BaseType>,
     pub arguments: Box<Exp>,
+    #[serde(serialize_with = "serialize_map_as_seq")]
     pub acquires: BTreeMap<StructName, Loc>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum BuiltinFunction_ {
     MoveTo(BaseType),
     MoveFrom(BaseType),
@@ -263,7 +286,7 @@ pub enum BuiltinFunction_ {
 }
 pub type BuiltinFunction = Spanned<BuiltinFunction_>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum Value_ {
     // @<address>
     Address(NumericalAddress),
@@ -287,7 +310,7 @@ pub enum Value_ {
 }
 pub type Value = Spanned<Value_>;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum MoveOpAnnotation {
     // 'move' annotated by the user
     FromUser,
@@ -297,7 +320,7 @@ pub enum MoveOpAnnotation {
     InferredNoCopy,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum UnannotatedExp_ {
     Unit {
         case: UnitCase,
@@ -323,21 +346,33 @@ pub enum UnannotatedExp_ {
     BinopExp(Box<Exp>, BinOp, Box<Exp>),
 
     Pack(StructName, Vec<BaseType>, Vec<(Field, BaseType, Exp)>),
+    PackVariant(
+        StructName,
+        VariantName,
+        Vec<BaseType>,
+        Vec<(Field, BaseType, Exp)>,
+    ),
     ExpList(Vec<ExpListItem>),
 
     Borrow(bool, Box<Exp>, Field),
+    BorrowVariantField(bool, Box<Exp>, VariantName, Field),
     BorrowLocal(bool, Var),
+    // Tests if the enum behind the reference holds the given variant
+    TestVariant(Box<Exp>, VariantName),
 
     Cast(Box<Exp>, BuiltinTypeName),
 
     Unreachable,
 
-    Spec(SpecId, BTreeMap<Var, SingleType>),
+    Spec(
+        SpecId,
+        #[serde(serialize_with = "serialize_map_as_seq")] BTreeMap<Var, SingleType>,
+    ),
 
     UnresolvedError,
 }
 pub type UnannotatedExp = Spanned<UnannotatedExp_>;
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Exp {
     pub ty: Type,
     pub exp: UnannotatedExp,
@@ -346,7 +381,7 @@ pub fn exp(ty: Type, exp: UnannotatedExp) -> Exp {
     Exp { ty, exp }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum ExpListItem {
     Single(Exp, Box<SingleType>),
     Splat(Loc, Exp, Vec<SingleType>),
@@ -368,7 +403,7 @@ impl Command_ {
     pub fn is_terminal(&self) -> bool {
         use Command_::*;
         match self {
-            Break | Continue => panic!("ICE break/continue not translated to jumps"),
+            Break(_) | Continue(_) => panic!("ICE break/continue not translated to jumps"),
             Assign(_, _) | Mutate(_, _) | IgnoreAndPop { .. } => false,
             Abort(_) | Return { .. } | Jump { .. } | JumpIf { .. } => true,
         }
@@ -377,7 +412,7 @@ impl Command_ {
     pub fn is_exit(&self) -> bool {
         use Command_::*;
         match self {
-            Break | Continue => panic!("ICE break/continue not translated to jumps"),
+            Break(_) | Continue(_) => panic!("ICE break/continue not translated to jumps"),
             Assign(_, _) | Mutate(_, _) | IgnoreAndPop { .. } | Jump { .. } | JumpIf { .. } => {
                 false
             }
@@ -388,7 +423,7 @@ impl Command_ {
     pub fn is_unit(&self) -> bool {
         use Command_::*;
         match self {
-            Break | Continue => panic!("ICE break/continue not translated to jumps"),
+            Break(_) | Continue(_) => panic!("ICE break/continue not translated to jumps"),
             Assign(ls, e) => ls.is_empty() && e.is_unit(),
             IgnoreAndPop { exp: e, .. } => e.is_unit(),
 
@@ -401,7 +436,7 @@ impl Command_ {
 
         let mut successors = BTreeSet::new();
         match self {
-            Break | Continue => panic!("ICE break/continue not translated to jumps"),
+            Break(_) | Continue(_) => panic!("ICE break/continue not translated to jumps"),
             Mutate(_, _) | Assign(_, _) | IgnoreAndPop { .. } => {
                 panic!("ICE Should not be last command in block")
             }
@@ -730,17 +765,33 @@ impl AstDebug for (StructName, &StructDefinition) {
             w.write("native ");
         }
 
-        w.write(&format!("struct {}", name));
+        match fields {
+            StructFields::Variants(_) => w.write(&format!("enum {}", name)),
+            _ => w.write(&format!("struct {}", name)),
+        }
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Defined(fields) => w.block(|w| {
                 w.list(fields, ";", |w, (f, bt)| {
                     w.write(&format!("{}: ", f));
                     bt.ast_debug(w);
                     true
                 })
-            })
+            }),
+            StructFields::Variants(variants) => w.block(|w| {
+                w.list(variants, ";", |w, (v, fields)| {
+                    w.write(&format!("{}", v));
+                    w.write("{");
+                    w.comma(fields, |w, (f, bt)| {
+                        w.write(&format!("{}: ", f));
+                        bt.ast_debug(w);
+                    });
+                    w.write("}");
+                    true
+                })
+            }),
+            StructFields::Native(_) => (),
         }
     }
 }
@@ -756,6 +807,7 @@ impl AstDebug for (FunctionName, &Function) {
                 signature,
                 acquires,
                 body,
+                inlined_calls: _,
             },
         ) = self;
         attributes.ast_debug(w);
@@ -830,6 +882,7 @@ impl AstDebug for (ConstantName, &Constant) {
                 loc: _loc,
                 signature,
                 value,
+                assertion_message,
             },
         ) = self;
         attributes.ast_debug(w);
@@ -838,6 +891,9 @@ impl AstDebug for (ConstantName, &Constant) {
         w.write(" = ");
         w.block(|w| value.ast_debug(w));
         w.write(";");
+        if let Some(message) = assertion_message {
+            w.write(&format!(" /* assertion message: {:?} */", message));
+        }
     }
 }
 
@@ -958,13 +1014,19 @@ impl AstDebug for Statement_ {
                 w.write(" else ");
                 w.block(|w| else_block.ast_debug(w));
             }
-            S::While { cond, block } => {
+            S::While { label, cond, block } => {
+                label.ast_debug(w);
                 w.write("while (");
                 cond.ast_debug(w);
                 w.write(")");
                 w.block(|w| block.ast_debug(w))
             }
-            S::Loop { block, has_break } => {
+            S::Loop {
+                label,
+                block,
+                has_break,
+            } => {
+                label.ast_debug(w);
                 w.write("loop");
                 if *has_break {
                     w.write("#has_break");
@@ -1003,8 +1065,18 @@ impl AstDebug for Command_ {
                 w.write("return ");
                 e.ast_debug(w);
             }
-            C::Break => w.write("break"),
-            C::Continue => w.write("continue"),
+            C::Break(label) => {
+                w.write("break");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
+            C::Continue(label) => {
+                w.write("continue");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
             C::IgnoreAndPop { pop_num, exp } => {
                 w.write("pop ");
                 w.comma(0..*pop_num, |w, _| w.write("_"));
@@ -1125,6 +1197,19 @@ impl AstDebug for UnannotatedExp_ {
                 });
                 w.write("}");
             }
+            E::PackVariant(s, v, tys, fields) => {
+                w.write(&format!("{}::{}", s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (f, bt, e)| {
+                    w.annotate(|w| w.write(&format!("{}", f)), bt);
+                    w.write(": ");
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
 
             E::ExpList(es) => {
                 w.write("(");
@@ -1156,6 +1241,21 @@ impl AstDebug for UnannotatedExp_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", f));
             }
+            E::BorrowVariantField(mut_, e, v, f) => {
+                w.write("&");
+                if *mut_ {
+                    w.write("mut ");
+                }
+                e.ast_debug(w);
+                w.write(&format!(".({}).{}", v, f));
+            }
+            E::TestVariant(e, v) => {
+                w.write("test_variant<");
+                w.write(&format!("{}", v));
+                w.write(">(");
+                e.ast_debug(w);
+                w.write(")");
+            }
             E::BorrowLocal(mut_, v) => {
                 w.write("&");
                 if *mut_ {
@@ -1275,6 +1375,18 @@ impl AstDebug for LValue_ {
                 });
                 w.write("}");
             }
+            L::UnpackVariant(s, v, tys, fields) => {
+                w.write(&format!("{}::{}", s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (f, l)| {
+                    w.write(&format!("{}: ", f));
+                    l.ast_debug(w)
+                });
+                w.write("}");
+            }
         }
     }
 }
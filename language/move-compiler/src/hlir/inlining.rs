@@ -0,0 +1,752 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Expands calls to inline functions at their call sites, before the translation to HLIR.
+//! The body of the inline function replaces the call: its locals are renamed apart from the
+//! locals of the caller, its type parameters are instantiated, and each call of a function typed
+//! parameter is replaced by the body of the lambda given for that parameter. The result is
+//! ordinary code, so inline functions themselves are never compiled.
+
+use crate::{
+    diag,
+    diagnostics::Diagnostic,
+    expansion::ast::{AbilitySet, ModuleIdent, Visibility},
+    hlir::ast as H,
+    naming::ast::{self as N, BuiltinTypeName_, Type, TypeName_, Type_},
+    parser::ast::{ConstantName, FunctionName, StructName, Var},
+    shared::*,
+    typing::{
+        ast as T,
+        core::{make_tparam_subst, TParamSubst},
+        translate::add_package_friends,
+    },
+    FullyCompiledProgram,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, BTreeSet};
+
+//**************************************************************************************************
+// Context
+//**************************************************************************************************
+
+pub type InlinedCalls = BTreeMap<(Option<ModuleIdent>, FunctionName), Vec<H::InlinedCall>>;
+
+const INLINED_NAME_DELIM: &str = "#i";
+
+struct InlineFunction {
+    signature: N::FunctionSignature,
+    body: Spanned<T::Sequence>,
+}
+
+struct Context<'env> {
+    env: &'env mut CompilationEnv,
+    inline_functions: BTreeMap<(ModuleIdent, FunctionName), InlineFunction>,
+    visibilities: BTreeMap<(ModuleIdent, FunctionName), Visibility>,
+    friends: BTreeMap<ModuleIdent, BTreeSet<ModuleIdent>>,
+    packages: BTreeMap<ModuleIdent, Option<Symbol>>,
+    // the modules calling 'public(package)' functions of each module through inlined bodies
+    package_friends: BTreeMap<ModuleIdent, BTreeMap<ModuleIdent, Loc>>,
+    // declared abilities and phantom-ness of the type parameters of each struct
+    structs: BTreeMap<(ModuleIdent, StructName), (AbilitySet, Vec<bool>)>,
+    constants: BTreeMap<(ModuleIdent, ConstantName), T::Exp>,
+    // the module the code is inlined into, `None` for scripts
+    current_module: Option<ModuleIdent>,
+    // the calls currently being expanded, outermost first
+    call_stack: Vec<(Loc, ModuleIdent, FunctionName)>,
+    inlined_calls: Vec<H::InlinedCall>,
+    counter: usize,
+}
+
+impl<'env> Context<'env> {
+    fn new(
+        env: &'env mut CompilationEnv,
+        pre_compiled_lib: Option<&FullyCompiledProgram>,
+        prog: &T::Program,
+    ) -> Self {
+        let all_modules = prog
+            .modules
+            .key_cloned_iter()
+            .chain(pre_compiled_lib.iter().flat_map(|pre_compiled| {
+                pre_compiled
+                    .typing
+                    .modules
+                    .key_cloned_iter()
+                    .filter(|(mident, _m)| !prog.modules.contains_key(mident))
+            }));
+        let mut inline_functions = BTreeMap::new();
+        let mut visibilities = BTreeMap::new();
+        let mut friends = BTreeMap::new();
+        let mut packages = BTreeMap::new();
+        let mut structs = BTreeMap::new();
+        let mut constants = BTreeMap::new();
+        for (mident, mdef) in all_modules {
+            friends.insert(
+                mident,
+                mdef.friends.key_cloned_iter().map(|(m, _)| m).collect(),
+            );
+            packages.insert(mident, mdef.package_name);
+            for (sname, sdef) in mdef.structs.key_cloned_iter() {
+                let phantoms = sdef.type_parameters.iter().map(|p| p.is_phantom).collect();
+                structs.insert((mident, sname), (sdef.abilities.clone(), phantoms));
+            }
+            for (cname, cdef) in mdef.constants.key_cloned_iter() {
+                constants.insert((mident, cname), cdef.value.clone());
+            }
+            for (fname, fdef) in mdef.functions.key_cloned_iter() {
+                visibilities.insert((mident, fname), fdef.visibility.clone());
+                if let (true, sp!(loc, T::FunctionBody_::Defined(seq))) = (fdef.inline, &fdef.body)
+                {
+                    let f = InlineFunction {
+                        signature: fdef.signature.clone(),
+                        body: sp(*loc, seq.clone()),
+                    };
+                    inline_functions.insert((mident, fname), f);
+                }
+            }
+        }
+        Context {
+            env,
+            inline_functions,
+            visibilities,
+            friends,
+            packages,
+            package_friends: BTreeMap::new(),
+            structs,
+            constants,
+            current_module: None,
+            call_stack: vec![],
+            inlined_calls: vec![],
+            counter: 0,
+        }
+    }
+
+    fn is_inline(&self, call: &T::ModuleCall) -> bool {
+        self.inline_functions
+            .contains_key(&(call.module, call.name))
+    }
+
+    fn counter_next(&mut self) -> usize {
+        self.counter += 1;
+        self.counter
+    }
+
+    fn is_current_module(&self, m: &ModuleIdent) -> bool {
+        self.current_module.as_ref() == Some(m)
+    }
+
+    // Reports an operation in an inlined body that is not allowed in the module the body is
+    // inlined into
+    fn add_inlining_diag(&mut self, loc: Loc, msg: String) {
+        let (call_loc, m, f) = self.call_stack.first().unwrap();
+        let into = match &self.current_module {
+            Some(current) => format!("module '{}'", current),
+            None => "a script".to_string(),
+        };
+        let mut diag: Diagnostic = diag!(
+            TypeSafety::Visibility,
+            (
+                *call_loc,
+                format!("Invalid call to inline function '{}::{}'", m, f)
+            ),
+            (
+                loc,
+                format!("Inlining this function into {} would {}", into, msg)
+            ),
+        );
+        if self.call_stack.len() > 1 {
+            let (inner_loc, inner_m, inner_f) = self.call_stack.last().unwrap();
+            diag.add_secondary_label((
+                *inner_loc,
+                format!(
+                    "From this call to inline function '{}::{}'",
+                    inner_m, inner_f
+                ),
+            ));
+        }
+        self.env.add_diag(diag)
+    }
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn program(
+    compilation_env: &mut CompilationEnv,
+    pre_compiled_lib: Option<&FullyCompiledProgram>,
+    prog: &mut T::Program,
+) -> InlinedCalls {
+    let mut inlined_calls = InlinedCalls::new();
+    // Inlining relies on well typed bodies
+    if compilation_env.has_errors() {
+        return inlined_calls;
+    }
+    let mut context = Context::new(compilation_env, pre_compiled_lib, prog);
+    if context.inline_functions.is_empty() {
+        return inlined_calls;
+    }
+    for (mloc, mident_, mdef) in prog.modules.iter_mut() {
+        let mident = sp(mloc, *mident_);
+        context.current_module = Some(mident);
+        for (floc, fname_, fdef) in mdef.functions.iter_mut() {
+            if fdef.inline {
+                continue;
+            }
+            let fname = FunctionName(sp(floc, *fname_));
+            let calls = function(&mut context, fdef);
+            if !calls.is_empty() {
+                inlined_calls.insert((Some(mident), fname), calls);
+            }
+        }
+    }
+    for script in prog.scripts.values_mut() {
+        context.current_module = None;
+        let calls = function(&mut context, &mut script.function);
+        if !calls.is_empty() {
+            inlined_calls.insert((None, script.function_name), calls);
+        }
+    }
+    add_package_friends(context.package_friends, &mut prog.modules);
+    inlined_calls
+}
+
+fn function(context: &mut Context, fdef: &mut T::Function) -> Vec<H::InlinedCall> {
+    if let T::FunctionBody_::Defined(seq) = &mut fdef.body.value {
+        sequence(context, seq)
+    }
+    std::mem::take(&mut context.inlined_calls)
+}
+
+//**************************************************************************************************
+// Expansion of calls
+//**************************************************************************************************
+
+fn sequence(context: &mut Context, seq: &mut T::Sequence) {
+    for sp!(_, item_) in seq {
+        match item_ {
+            T::SequenceItem_::Seq(e) | T::SequenceItem_::Bind(_, _, e) => exp(context, e),
+            T::SequenceItem_::Declare(_) => (),
+        }
+    }
+}
+
+fn exp(context: &mut Context, e: &mut T::Exp) {
+    use T::UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Use(_)
+        | E::Constant(_, _)
+        | E::Break(_)
+        | E::Continue(_)
+        | E::BorrowLocal(_, _)
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => exp(context, &mut call.arguments),
+        E::Builtin(_, e)
+        | E::VarCall(_, e)
+        | E::Vector(_, _, _, e)
+        | E::Loop { body: e, .. }
+        | E::Lambda(_, _, e)
+        | E::Assign(_, _, e)
+        | E::Return(e)
+        | E::Abort(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::TempBorrow(_, e)
+        | E::Cast(e, _)
+        | E::Annotate(e, _) => exp(context, e),
+        E::IfElse(eb, et, ef) => {
+            exp(context, eb);
+            exp(context, et);
+            exp(context, ef);
+        }
+        E::While(_, e1, e2) | E::Mutate(e1, e2) | E::BinopExp(e1, _, _, e2) => {
+            exp(context, e1);
+            exp(context, e2);
+        }
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (_, earm)) in arms {
+                exp(context, earm)
+            }
+        }
+        E::Block(seq) => sequence(context, seq),
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
+            for (_, _, (_, (_, fe))) in fields.iter_mut() {
+                exp(context, fe)
+            }
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        exp(context, e)
+                    }
+                }
+            }
+        }
+    }
+    if matches!(&e.exp.value, E::ModuleCall(call) if context.is_inline(call)) {
+        let loc = e.exp.loc;
+        match std::mem::replace(&mut e.exp.value, E::UnresolvedError) {
+            E::ModuleCall(call) => *e = inline_call(context, loc, e.ty.clone(), *call),
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn inline_call(context: &mut Context, loc: Loc, ty: Type, call: T::ModuleCall) -> T::Exp {
+    use T::UnannotatedExp_ as E;
+    let T::ModuleCall {
+        module,
+        name,
+        type_arguments,
+        arguments,
+        ..
+    } = call;
+    let suffix = context.counter_next();
+    let callee = &context.inline_functions[&(module, name)];
+    let tparam_subst = make_tparam_subst(&callee.signature.type_parameters, type_arguments);
+    let parameters = callee.signature.parameters.clone();
+    let arity = parameters.len();
+    let sp!(body_loc, mut body) = callee.body.clone();
+
+    // Lambdas are substituted for their parameters, all other arguments are bound to the
+    // (renamed) parameters before the body
+    let mut lambdas = BTreeMap::new();
+    let mut bind_lvalues = vec![];
+    let mut bind_tys = vec![];
+    let mut bind_args = vec![];
+    for ((param, param_ty), arg) in parameters
+        .into_iter()
+        .zip(call_arguments(*arguments, arity))
+    {
+        let param_ty = type_(context, &tparam_subst, param_ty);
+        if let Type_::Fun(_, _) = &param_ty.value {
+            lambdas.insert(param, arg);
+            continue;
+        }
+        let var = rename(suffix, param);
+        let lvalue = sp(var.loc(), T::LValue_::Var(var, Box::new(param_ty.clone())));
+        bind_lvalues.push(lvalue);
+        bind_tys.push(Some(param_ty));
+        bind_args.push(arg);
+    }
+
+    context.call_stack.push((loc, module, name));
+    let mut inliner = Inliner {
+        context,
+        tparam_subst,
+        lambdas,
+        suffix,
+        callee_module: module,
+    };
+    inliner.sequence(&mut body);
+
+    if !bind_lvalues.is_empty() {
+        let args = if bind_args.len() == 1 {
+            bind_args.pop().unwrap()
+        } else {
+            let tys = bind_args.iter().map(|arg| arg.ty.clone()).collect();
+            let items = bind_args.into_iter().map(T::single_item).collect();
+            let ty = type_(context, &TParamSubst::new(), Type_::multiple(loc, tys));
+            T::exp(ty, sp(loc, E::ExpList(items)))
+        };
+        let bind = T::SequenceItem_::Bind(sp(loc, bind_lvalues), bind_tys, Box::new(args));
+        body.push_front(sp(loc, bind));
+    }
+
+    context.inlined_calls.push(H::InlinedCall {
+        loc,
+        body_loc,
+        module,
+        function: name,
+    });
+    let mut inlined = T::exp(ty, sp(loc, E::Block(body)));
+    // expand the inline calls in the body of the inline function
+    exp(context, &mut inlined);
+    context.call_stack.pop();
+    inlined
+}
+
+fn call_arguments(arguments: T::Exp, arity: usize) -> Vec<T::Exp> {
+    use T::UnannotatedExp_ as E;
+    match (arity, arguments) {
+        (0, _) => vec![],
+        (1, arg) => vec![arg],
+        (
+            _,
+            T::Exp {
+                exp: sp!(_, E::ExpList(items)),
+                ..
+            },
+        ) => items
+            .into_iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) => e,
+                T::ExpListItem::Splat(_, _, _) => panic!("ICE unexpected splat in call arguments"),
+            })
+            .collect(),
+        _ => panic!("ICE arity mismatch in call arguments"),
+    }
+}
+
+fn rename(suffix: usize, Var(sp!(loc, v_)): Var) -> Var {
+    Var(sp(
+        loc,
+        format!("{}{}{}", v_, INLINED_NAME_DELIM, suffix).into(),
+    ))
+}
+
+//**************************************************************************************************
+// Types
+//**************************************************************************************************
+
+// Substitutes the type parameters, recomputing the abilities of the types that depend on them
+fn type_(context: &Context, subst: &TParamSubst, sp!(loc, ty_): Type) -> Type {
+    use Type_::*;
+    let ty_ = match ty_ {
+        Param(tp) => return subst.get(&tp.id).cloned().unwrap_or(sp(loc, Param(tp))),
+        Ref(mut_, inner) => Ref(mut_, Box::new(type_(context, subst, *inner))),
+        Apply(abilities_opt, n, ty_args) => {
+            let ty_args: Vec<_> = ty_args
+                .into_iter()
+                .map(|t| type_(context, subst, t))
+                .collect();
+            let abilities_opt = match &n.value {
+                TypeName_::Builtin(sp!(bloc, BuiltinTypeName_::Vector)) => {
+                    let declared = BuiltinTypeName_::Vector.declared_abilities(*bloc);
+                    Some(instantiated_abilities(declared, ty_args.iter()))
+                }
+                TypeName_::ModuleType(m, s) => {
+                    let (declared, phantoms) = &context.structs[&(*m, *s)];
+                    let non_phantom_args = ty_args
+                        .iter()
+                        .zip(phantoms)
+                        .filter(|(_, is_phantom)| !**is_phantom)
+                        .map(|(t, _)| t);
+                    Some(instantiated_abilities(declared.clone(), non_phantom_args))
+                }
+                TypeName_::Multiple(_) => Some(instantiated_abilities(
+                    AbilitySet::collection(loc),
+                    ty_args.iter(),
+                )),
+                TypeName_::Builtin(_) => abilities_opt,
+            };
+            Apply(abilities_opt, n, ty_args)
+        }
+        Fun(args, result) => Fun(
+            args.into_iter().map(|t| type_(context, subst, t)).collect(),
+            Box::new(type_(context, subst, *result)),
+        ),
+        x @ (Unit | Var(_) | Anything | UnresolvedError) => x,
+    };
+    sp(loc, ty_)
+}
+
+fn instantiated_abilities<'a>(
+    declared: AbilitySet,
+    ty_args: impl Iterator<Item = &'a Type>,
+) -> AbilitySet {
+    let ty_args_abilities = ty_args.map(abilities).collect::<Vec<_>>();
+    AbilitySet::from_abilities(declared.into_iter().filter(|ab| {
+        let requirement = ab.value.requires();
+        ty_args_abilities
+            .iter()
+            .all(|ty_arg_abilities| ty_arg_abilities.has_ability_(requirement))
+    }))
+    .unwrap()
+}
+
+fn abilities(sp!(loc, ty_): &Type) -> AbilitySet {
+    use Type_::*;
+    match ty_ {
+        Unit => AbilitySet::collection(*loc),
+        Ref(_, _) => AbilitySet::references(*loc),
+        Param(tp) => tp.abilities.clone(),
+        Apply(Some(abilities), _, _) => abilities.clone(),
+        Fun(_, _) => AbilitySet::empty(),
+        Apply(None, _, _) | Var(_) => panic!("ICE type not expanded"),
+        Anything | UnresolvedError => AbilitySet::all(*loc),
+    }
+}
+
+fn struct_name(ty: &Type) -> Option<(ModuleIdent, StructName)> {
+    match &ty.value {
+        Type_::Ref(_, inner) => struct_name(inner),
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, s)), _) => Some((*m, *s)),
+        _ => None,
+    }
+}
+
+//**************************************************************************************************
+// Inlined bodies
+//**************************************************************************************************
+
+struct Inliner<'a, 'env> {
+    context: &'a mut Context<'env>,
+    tparam_subst: TParamSubst,
+    lambdas: BTreeMap<Var, T::Exp>,
+    suffix: usize,
+    callee_module: ModuleIdent,
+}
+
+impl<'a, 'env> Inliner<'a, 'env> {
+    fn var(&self, v: &mut Var) {
+        *v = rename(self.suffix, *v)
+    }
+
+    fn type_(&self, ty: &mut Type) {
+        *ty = type_(self.context, &self.tparam_subst, ty.clone())
+    }
+
+    fn types<'t>(&self, tys: impl IntoIterator<Item = &'t mut Type>) {
+        tys.into_iter().for_each(|ty| self.type_(ty))
+    }
+
+    fn check_struct_op(&mut self, loc: Loc, m: &ModuleIdent, s: &StructName, op: &str) {
+        if m != &self.callee_module || self.context.is_current_module(m) {
+            return;
+        }
+        let msg = format!("{} '{}::{}' outside of its module", op, m, s);
+        self.context.add_inlining_diag(loc, msg)
+    }
+
+    fn check_global_op(&mut self, loc: Loc, ty: &Type) {
+        if let Some((m, s)) = struct_name(ty) {
+            self.check_struct_op(loc, &m, &s, "access global storage for")
+        }
+    }
+
+    fn check_call(&mut self, loc: Loc, m: &ModuleIdent, f: &FunctionName) {
+        if self.context.is_current_module(m) {
+            return;
+        }
+        let visible = match &self.context.visibilities[&(*m, *f)] {
+            Visibility::Public(_) => true,
+            Visibility::Internal => false,
+            Visibility::Friend(_) => match &self.context.current_module {
+                Some(current) => self.context.friends[m].contains(current),
+                None => false,
+            },
+            Visibility::Package(_) => match self.context.current_module {
+                Some(current)
+                    if current.value.address == m.value.address
+                        && self.context.packages[&current] == self.context.packages[m] =>
+                {
+                    let (call_loc, _, _) = self.context.call_stack.first().unwrap();
+                    let call_loc = *call_loc;
+                    self.context
+                        .package_friends
+                        .entry(*m)
+                        .or_default()
+                        .entry(current)
+                        .or_insert(call_loc);
+                    true
+                }
+                _ => false,
+            },
+        };
+        if !visible {
+            let msg = format!("call '{}::{}', which is not visible there", m, f);
+            self.context.add_inlining_diag(loc, msg)
+        }
+    }
+
+    fn sequence(&mut self, seq: &mut T::Sequence) {
+        for sp!(_, item_) in seq {
+            match item_ {
+                T::SequenceItem_::Seq(e) => self.exp(e),
+                T::SequenceItem_::Declare(lvalues) => self.lvalues(lvalues),
+                T::SequenceItem_::Bind(lvalues, tys, e) => {
+                    self.lvalues(lvalues);
+                    self.types(tys.iter_mut().flatten());
+                    self.exp(e)
+                }
+            }
+        }
+    }
+
+    fn lvalues(&mut self, sp!(_, lvalues): &mut T::LValueList) {
+        lvalues.iter_mut().for_each(|l| self.lvalue(l))
+    }
+
+    fn lvalue(&mut self, sp!(loc, l_): &mut T::LValue) {
+        use T::LValue_ as L;
+        match l_ {
+            L::Ignore => (),
+            L::Var(v, ty) => {
+                self.var(v);
+                self.type_(ty)
+            }
+            L::Unpack(m, s, tys, fields) | L::BorrowUnpack(_, m, s, tys, fields) => {
+                self.check_struct_op(*loc, m, s, "unpack");
+                self.types(tys.iter_mut());
+                for (_, _, (_, (ty, l))) in fields.iter_mut() {
+                    self.type_(ty);
+                    self.lvalue(l)
+                }
+            }
+        }
+    }
+
+    fn exp(&mut self, e: &mut T::Exp) {
+        use T::UnannotatedExp_ as E;
+        self.type_(&mut e.ty);
+        let loc = e.exp.loc;
+        match &mut e.exp.value {
+            E::Unit { .. } | E::Value(_) | E::Break(_) | E::Continue(_) | E::UnresolvedError => (),
+            E::Move { var, .. } if self.lambdas.contains_key(var) => {
+                *e = self.lambdas[var].clone();
+            }
+            E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) | E::BorrowLocal(_, var) => {
+                self.var(var)
+            }
+            E::Constant(Some(m), c) if !self.context.is_current_module(m) => {
+                // Constants are only accessible in their own module, so use their value instead
+                let mut value = self.context.constants[&(*m, *c)].clone();
+                value.exp.loc = loc;
+                self.exp(&mut value);
+                *e = value;
+            }
+            E::Constant(_, _) => (),
+            // Specification blocks are not carried over into the caller
+            E::Spec(_, _) => e.exp.value = E::Unit { trailing: false },
+
+            E::ModuleCall(call) => {
+                self.check_call(loc, &call.module, &call.name);
+                self.types(call.type_arguments.iter_mut());
+                self.types(call.parameter_types.iter_mut());
+                self.exp(&mut call.arguments)
+            }
+            E::VarCall(var, args) => {
+                self.exp(args);
+                let lambda = match self.lambdas.get(var) {
+                    Some(lambda) => lambda.clone(),
+                    None => panic!("ICE unbound function parameter '{}'", var),
+                };
+                let (lvalues, lvalue_tys, body) = match lambda.exp.value {
+                    E::Lambda(lvalues, lvalue_tys, body) => (lvalues, lvalue_tys, body),
+                    _ => panic!("ICE expected a lambda for '{}'", var),
+                };
+                let args = std::mem::replace(
+                    args,
+                    Box::new(T::exp(
+                        sp(loc, Type_::UnresolvedError),
+                        sp(loc, E::UnresolvedError),
+                    )),
+                );
+                let mut seq = T::Sequence::new();
+                if !lvalues.value.is_empty() {
+                    let bind = T::SequenceItem_::Bind(lvalues, lvalue_tys, args);
+                    seq.push_back(sp(loc, bind));
+                }
+                seq.push_back(sp(loc, T::SequenceItem_::Seq(body)));
+                e.exp.value = E::Block(seq);
+            }
+            E::Builtin(b, args) => {
+                use T::BuiltinFunction_ as B;
+                match &mut b.value {
+                    B::MoveTo(ty) | B::MoveFrom(ty) | B::BorrowGlobal(_, ty) | B::Exists(ty) => {
+                        self.type_(ty);
+                        let ty = ty.clone();
+                        self.check_global_op(loc, &ty)
+                    }
+                    B::Freeze(ty) => self.type_(ty),
+                    B::Assert(_) => (),
+                }
+                self.exp(args)
+            }
+            E::Vector(_, _, ty, args) => {
+                self.type_(ty);
+                self.exp(args)
+            }
+            E::IfElse(eb, et, ef) => {
+                self.exp(eb);
+                self.exp(et);
+                self.exp(ef);
+            }
+            E::While(_, e1, e2) | E::Mutate(e1, e2) => {
+                self.exp(e1);
+                self.exp(e2);
+            }
+            E::BinopExp(e1, _, ty, e2) => {
+                self.exp(e1);
+                self.type_(ty);
+                self.exp(e2);
+            }
+            E::Loop { body: e, .. }
+            | E::Return(e)
+            | E::Abort(e)
+            | E::Dereference(e)
+            | E::UnaryExp(_, e)
+            | E::TempBorrow(_, e) => self.exp(e),
+            E::Borrow(_, e, _) => {
+                self.exp(e);
+                if let Some((m, s)) = struct_name(&e.ty) {
+                    self.check_struct_op(loc, &m, &s, "borrow a field of")
+                }
+            }
+            E::Cast(e, ty) | E::Annotate(e, ty) => {
+                self.exp(e);
+                self.type_(ty)
+            }
+            E::Match(esubject, arms) => {
+                self.exp(esubject);
+                for sp!(_, (sp!(ploc, pat_), earm)) in arms {
+                    if let T::MatchPattern_::Variant(m, s, _, tys, fields) = pat_ {
+                        self.check_struct_op(*ploc, m, s, "match on");
+                        self.types(tys.iter_mut());
+                        for (_, _, (_, (ty, l))) in fields.iter_mut() {
+                            self.type_(ty);
+                            self.lvalue(l)
+                        }
+                    }
+                    self.exp(earm)
+                }
+            }
+            E::Block(seq) => self.sequence(seq),
+            E::Lambda(lvalues, tys, body) | E::Assign(lvalues, tys, body) => {
+                self.lvalues(lvalues);
+                self.types(tys.iter_mut().flatten());
+                self.exp(body)
+            }
+            E::Pack(m, s, tys, fields) => {
+                self.check_struct_op(loc, m, s, "pack");
+                self.types(tys.iter_mut());
+                for (_, _, (_, (ty, fe))) in fields.iter_mut() {
+                    self.type_(ty);
+                    self.exp(fe)
+                }
+            }
+            E::PackVariant(m, s, _, tys, fields) => {
+                self.check_struct_op(loc, m, s, "pack");
+                self.types(tys.iter_mut());
+                for (_, _, (_, (ty, fe))) in fields.iter_mut() {
+                    self.type_(ty);
+                    self.exp(fe)
+                }
+            }
+            E::ExpList(items) => {
+                for item in items {
+                    match item {
+                        T::ExpListItem::Single(e, ty) => {
+                            self.exp(e);
+                            self.type_(ty)
+                        }
+                        T::ExpListItem::Splat(_, e, tys) => {
+                            self.exp(e);
+                            self.types(tys.iter_mut())
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,4 +3,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ast;
+mod inlining;
 pub(crate) mod translate;
@@ -5,9 +5,12 @@
 use crate::{
     diag,
     expansion::ast::{self as E, AbilitySet, Fields, ModuleIdent},
-    hlir::ast::{self as H, Block, MoveOpAnnotation},
+    hlir::{
+        ast::{self as H, Block, MoveOpAnnotation},
+        inlining::{self, InlinedCalls},
+    },
     naming::ast as N,
-    parser::ast::{BinOp_, ConstantName, Field, FunctionName, StructName, Var},
+    parser::ast::{BinOp_, ConstantName, Field, FunctionName, StructName, Var, VariantName},
     shared::{unique_map::UniqueMap, *},
     typing::ast as T,
     FullyCompiledProgram,
@@ -60,14 +63,18 @@ pub fn display_var(s: Symbol) -> DisplayVar {
 // Context
 //**************************************************************************************************
 
+type FieldIndices = UniqueMap<Field, usize>;
+
 struct Context<'env> {
     env: &'env mut CompilationEnv,
-    structs: UniqueMap<ModuleIdent, UniqueMap<StructName, UniqueMap<Field, usize>>>,
+    structs: UniqueMap<ModuleIdent, UniqueMap<StructName, FieldIndices>>,
+    enums: UniqueMap<ModuleIdent, UniqueMap<StructName, UniqueMap<VariantName, FieldIndices>>>,
     function_locals: UniqueMap<Var, H::SingleType>,
     local_scope: UniqueMap<Var, Var>,
     used_locals: BTreeSet<Var>,
     signature: Option<H::FunctionSignature>,
     tmp_counter: usize,
+    inlined_calls: InlinedCalls,
 }
 
 impl<'env> Context<'env> {
@@ -75,45 +82,65 @@ impl<'env> Context<'env> {
         env: &'env mut CompilationEnv,
         pre_compiled_lib_opt: Option<&FullyCompiledProgram>,
         prog: &T::Program,
+        inlined_calls: InlinedCalls,
     ) -> Self {
+        fn field_indices(field_map: &Fields<N::Type>) -> FieldIndices {
+            let mut fields = UniqueMap::new();
+            for (field, (idx, _)) in field_map.key_cloned_iter() {
+                fields.add(field, *idx).unwrap();
+            }
+            fields
+        }
+
         fn add_struct_fields(
-            structs: &mut UniqueMap<ModuleIdent, UniqueMap<StructName, UniqueMap<Field, usize>>>,
+            structs: &mut UniqueMap<ModuleIdent, UniqueMap<StructName, FieldIndices>>,
+            enums: &mut UniqueMap<
+                ModuleIdent,
+                UniqueMap<StructName, UniqueMap<VariantName, FieldIndices>>,
+            >,
             mident: ModuleIdent,
             struct_defs: &UniqueMap<StructName, N::StructDefinition>,
         ) {
             let mut cur_structs = UniqueMap::new();
+            let mut cur_enums = UniqueMap::new();
             for (sname, sdef) in struct_defs.key_cloned_iter() {
-                let mut fields = UniqueMap::new();
-                let field_map = match &sdef.fields {
+                match &sdef.fields {
                     N::StructFields::Native(_) => continue,
-                    N::StructFields::Defined(m) => m,
-                };
-                for (field, (idx, _)) in field_map.key_cloned_iter() {
-                    fields.add(field, *idx).unwrap();
+                    N::StructFields::Defined(m) => {
+                        cur_structs.add(sname, field_indices(m)).unwrap();
+                    }
+                    N::StructFields::Variants(vm) => {
+                        let variants = vm.ref_map(|_, (_, m)| field_indices(m));
+                        cur_enums.add(sname, variants).unwrap();
+                    }
                 }
-                cur_structs.add(sname, fields).unwrap();
             }
             structs.remove(&mident);
             structs.add(mident, cur_structs).unwrap();
+            enums.remove(&mident);
+            enums.add(mident, cur_enums).unwrap();
         }
 
         let mut structs = UniqueMap::new();
+        let mut enums = UniqueMap::new();
         if let Some(pre_compiled_lib) = pre_compiled_lib_opt {
             for (mident, mdef) in pre_compiled_lib.typing.modules.key_cloned_iter() {
-                add_struct_fields(&mut structs, mident, &mdef.structs)
+                add_struct_fields(&mut structs, &mut enums, mident, &mdef.structs)
             }
         }
         for (mident, mdef) in prog.modules.key_cloned_iter() {
-            add_struct_fields(&mut structs, mident, &mdef.structs)
+            add_struct_fields(&mut structs, &mut enums, mident, &mdef.structs)
         }
         Context {
             env,
             structs,
+            enums,
             function_locals: UniqueMap::new(),
             local_scope: UniqueMap::new(),
             used_locals: BTreeSet::new(),
             signature: None,
             tmp_counter: 0,
+            inlined_calls,
         }
     }
 
@@ -155,11 +182,7 @@ impl<'env> Context<'env> {
         remapped
     }
 
-    pub fn fields(
-        &self,
-        module: &ModuleIdent,
-        struct_name: &StructName,
-    ) -> Option<&UniqueMap<Field, usize>> {
+    pub fn fields(&self, module: &ModuleIdent, struct_name: &StructName) -> Option<&FieldIndices> {
         let fields = self
             .structs
             .get(module)
@@ -170,6 +193,22 @@ impl<'env> Context<'env> {
         fields
     }
 
+    pub fn variant_fields(
+        &self,
+        module: &ModuleIdent,
+        enum_name: &StructName,
+        variant: &VariantName,
+    ) -> Option<&FieldIndices> {
+        let fields = self
+            .enums
+            .get(module)
+            .and_then(|enums| enums.get(enum_name))
+            .and_then(|variants| variants.get(variant));
+        // same as for `fields`, there should be errors if the variant is not found
+        assert!(fields.is_some() || self.env.has_errors());
+        fields
+    }
+
     fn counter_next(&mut self) -> usize {
         self.tmp_counter += 1;
         self.tmp_counter
@@ -183,9 +222,10 @@ impl<'env> Context<'env> {
 pub fn program(
     compilation_env: &mut CompilationEnv,
     pre_compiled_lib: Option<&FullyCompiledProgram>,
-    prog: T::Program,
+    mut prog: T::Program,
 ) -> H::Program {
-    let mut context = Context::new(compilation_env, pre_compiled_lib, &prog);
+    let inlined_calls = inlining::program(compilation_env, pre_compiled_lib, &mut prog);
+    let mut context = Context::new(compilation_env, pre_compiled_lib, &prog, inlined_calls);
     let T::Program {
         modules: tmodules,
         scripts: tscripts,
@@ -225,7 +265,9 @@ fn module(
     let structs = tstructs.map(|name, s| struct_def(context, name, s));
 
     let constants = tconstants.map(|name, c| constant(context, name, c));
-    let functions = tfunctions.map(|name, f| function(context, name, f));
+    // inline functions have been expanded at their call sites
+    let functions = tfunctions
+        .filter_map(|name, f| (!f.inline).then(|| function(context, Some(module_ident), name, f)));
     (
         module_ident,
         H::ModuleDefinition {
@@ -261,7 +303,7 @@ fn script(context: &mut Context, tscript: T::Script) -> H::Script {
         function: tfunction,
     } = tscript;
     let constants = tconstants.map(|name, c| constant(context, name, c));
-    let function = function(context, function_name, tfunction);
+    let function = function(context, None, function_name, tfunction);
     H::Script {
         package_name,
         attributes,
@@ -276,19 +318,29 @@ fn script(context: &mut Context, tscript: T::Script) -> H::Script {
 // Functions
 //**************************************************************************************************
 
-fn function(context: &mut Context, _name: FunctionName, f: T::Function) -> H::Function {
+fn function(
+    context: &mut Context,
+    module: Option<ModuleIdent>,
+    name: FunctionName,
+    f: T::Function,
+) -> H::Function {
     assert!(context.has_empty_locals());
     assert!(context.tmp_counter == 0);
     let T::Function {
         attributes,
         visibility,
         entry,
+        inline: _,
         signature,
         acquires,
         body,
     } = f;
     let signature = function_signature(context, signature);
     let body = function_body(context, &signature, body);
+    let inlined_calls = context
+        .inlined_calls
+        .remove(&(module, name))
+        .unwrap_or_default();
     H::Function {
         attributes,
         visibility,
@@ -296,6 +348,7 @@ fn function(context: &mut Context, _name: FunctionName, f: T::Function) -> H::Fu
         signature,
         acquires,
         body,
+        inlined_calls,
     }
 }
 
@@ -380,6 +433,7 @@ fn constant(context: &mut Context, _name: ConstantName, cdef: T::Constant) -> H:
         loc,
         signature: tsignature,
         value: tvalue,
+        assertion_message,
     } = cdef;
     let signature = base_type(context, tsignature);
     let eloc = tvalue.exp.loc;
@@ -399,6 +453,7 @@ fn constant(context: &mut Context, _name: ConstantName, cdef: T::Constant) -> H:
         loc,
         signature,
         value: (locals, body),
+        assertion_message,
     }
 }
 
@@ -424,16 +479,27 @@ fn struct_def(
 }
 
 fn struct_fields(context: &mut Context, tfields: N::StructFields) -> H::StructFields {
-    let tfields_map = match tfields {
-        N::StructFields::Native(loc) => return H::StructFields::Native(loc),
-        N::StructFields::Defined(m) => m,
-    };
+    match tfields {
+        N::StructFields::Native(loc) => H::StructFields::Native(loc),
+        N::StructFields::Defined(m) => H::StructFields::Defined(field_decls(context, m)),
+        N::StructFields::Variants(vm) => {
+            let mut indexed_variants = vm
+                .into_iter()
+                .map(|(v, (idx, m))| (idx, (v, field_decls(context, m))))
+                .collect::<Vec<_>>();
+            indexed_variants.sort_by(|(idx1, _), (idx2, _)| idx1.cmp(idx2));
+            H::StructFields::Variants(indexed_variants.into_iter().map(|(_, v)| v).collect())
+        }
+    }
+}
+
+fn field_decls(context: &mut Context, tfields_map: Fields<N::Type>) -> Vec<(Field, H::BaseType)> {
     let mut indexed_fields = tfields_map
         .into_iter()
         .map(|(f, (idx, t))| (idx, (f, base_type(context, t))))
         .collect::<Vec<_>>();
     indexed_fields.sort_by(|(idx1, _), (idx2, _)| idx1.cmp(idx2));
-    H::StructFields::Defined(indexed_fields.into_iter().map(|(_, f_ty)| f_ty).collect())
+    indexed_fields.into_iter().map(|(_, f_ty)| f_ty).collect()
 }
 
 //**************************************************************************************************
@@ -481,6 +547,11 @@ fn base_type(context: &Context, sp!(loc, nb_): N::Type) -> H::BaseType {
         NT::Param(tp) => HB::Param(tp),
         NT::UnresolvedError => HB::UnresolvedError,
         NT::Anything => HB::Unreachable,
+        // only remains if inlining was skipped due to errors
+        NT::Fun(_, _) => {
+            assert!(context.env.has_errors());
+            HB::UnresolvedError
+        }
         NT::Ref(_, _) | NT::Unit => {
             panic!(
                 "ICE type constraints failed {}:{}-{}",
@@ -606,7 +677,7 @@ fn statement(context: &mut Context, result: &mut Block, e: T::Exp) {
                 else_block,
             }
         }
-        TE::While(tb, loop_body) => {
+        TE::While(label, tb, loop_body) => {
             let mut cond_block = Block::new();
             let cond_exp = exp(context, &mut cond_block, None, *tb);
 
@@ -615,17 +686,20 @@ fn statement(context: &mut Context, result: &mut Block, e: T::Exp) {
             ignore_and_pop(&mut loop_block, el);
 
             S::While {
+                label,
                 cond: (cond_block, cond_exp),
                 block: loop_block,
             }
         }
         TE::Loop {
+            label,
             body: loop_body,
             has_break,
         } => {
             let loop_block = statement_loop_body(context, *loop_body);
 
             S::Loop {
+                label,
                 block: loop_block,
                 has_break,
             }
@@ -716,7 +790,7 @@ fn assign(
             let bs = base_types(context, tbs);
 
             let mut fields = vec![];
-            for (decl_idx, f, bt, tfa) in assign_fields(context, &m, &s, tfields) {
+            for (decl_idx, f, bt, tfa) in assign_fields(context, context.fields(&m, &s), tfields) {
                 assert!(fields.len() == decl_idx);
                 let st = &H::SingleType_::base(bt);
                 let (fa, mut fafter) = assign(context, tfa, st);
@@ -734,7 +808,7 @@ fn assign(
                 };
                 H::exp(H::Type_::single(rvalue_ty.clone()), sp(loc, copy_tmp_))
             };
-            let fields = assign_fields(context, &m, &s, tfields)
+            let fields = assign_fields(context, context.fields(&m, &s), tfields)
                 .into_iter()
                 .enumerate();
             for (idx, (decl_idx, f, bt, tfa)) in fields {
@@ -753,11 +827,9 @@ fn assign(
 
 fn assign_fields(
     context: &Context,
-    m: &ModuleIdent,
-    s: &StructName,
+    decl_fields: Option<&FieldIndices>,
     tfields: Fields<(N::Type, T::LValue)>,
 ) -> Vec<(usize, Field, H::BaseType, T::LValue)> {
-    let decl_fields = context.fields(m, s);
     let mut count = 0;
     let mut decl_field = |f: &Field| -> usize {
         match decl_fields {
@@ -778,6 +850,164 @@ fn assign_fields(
     tfields_vec
 }
 
+//**************************************************************************************************
+// Match
+//**************************************************************************************************
+
+// A match is lowered to a chain of if-else statements, one per arm, testing the variant of the
+// subject. As the arms are exhaustive, the last arm is not tested.
+fn match_exp(
+    context: &mut Context,
+    result: &mut Block,
+    ty: &H::Type,
+    eloc: Loc,
+    tsubject: T::Exp,
+    tarms: Vec<T::MatchArm>,
+) -> H::UnannotatedExp_ {
+    use H::{Command_ as C, Statement_ as S, UnannotatedExp_ as E};
+
+    let subject = exp_(context, result, None, tsubject);
+    if matches!(&subject.exp.value, E::Unreachable) {
+        return E::Unreachable;
+    }
+    if tarms.is_empty() {
+        assert!(context.env.has_errors());
+        return E::UnresolvedError;
+    }
+    let subject_ty = match &subject.ty.value {
+        H::Type_::Single(st) => st.clone(),
+        _ => panic!("ICE typing failed for match subject"),
+    };
+    let sloc = subject.exp.loc;
+    let tmp = context.new_temp(sloc, subject_ty.clone());
+    let tmp_lvalue = sp(sloc, H::LValue_::Var(tmp, Box::new(subject_ty.clone())));
+    let assign = sp(sloc, C::Assign(vec![tmp_lvalue], Box::new(subject)));
+    result.push_back(sp(sloc, S::Command(assign)));
+
+    let mut arms = vec![];
+    for sp!(aloc, (pat, trhs)) in tarms {
+        let old_scope = context.local_scope.clone();
+        let mut arm_block = Block::new();
+        let variant_opt = match_pattern(context, &mut arm_block, tmp, &subject_ty, pat);
+        let erhs = exp_(context, &mut arm_block, Some(ty), trhs);
+        context.local_scope = old_scope;
+        arms.push((aloc, variant_opt, arm_block, erhs));
+    }
+
+    // Each nested if-else binds its own result, mirroring the lowering of 'else if' chains
+    let (_, _, mut chain, mut chain_exp) = arms.pop().unwrap();
+    for (aloc, variant_opt, mut if_block, et) in arms.into_iter().rev() {
+        let variant = match variant_opt {
+            Some(variant) => variant,
+            None => panic!("ICE wildcard pattern must be in the last match arm"),
+        };
+        let cond = Box::new(test_variant(tmp, &subject_ty, variant, aloc));
+        let mut else_block = chain;
+        let ef = chain_exp;
+        let e_ = match (&et.exp.value, &ef.exp.value) {
+            (E::Unreachable, E::Unreachable) => E::Unreachable,
+            _ => {
+                let tmps = make_temps(context, eloc, ty.clone());
+                let tres = bind_exp_(&mut if_block, aloc, tmps.clone(), et);
+                let fres = bind_exp_(&mut else_block, aloc, tmps, ef);
+                match (tres, fres) {
+                    (E::Unreachable, E::Unreachable) => unreachable!(),
+                    (E::Unreachable, res) | (res, E::Unreachable) | (res, _) => res,
+                }
+            }
+        };
+        let if_else = S::IfElse {
+            cond,
+            if_block,
+            else_block,
+        };
+        chain = Block::new();
+        chain.push_back(sp(aloc, if_else));
+        chain_exp = H::exp(ty.clone(), sp(eloc, e_));
+    }
+    result.append(&mut chain);
+    chain_exp.exp.value
+}
+
+// Binds the fields of the pattern from the subject stored in `tmp`.
+// Returns the variant of the pattern, or None for a wildcard
+fn match_pattern(
+    context: &mut Context,
+    result: &mut Block,
+    tmp: Var,
+    subject_ty: &H::SingleType,
+    sp!(ploc, pat_): T::MatchPattern,
+) -> Option<VariantName> {
+    use H::{Command_ as C, Statement_ as S, UnannotatedExp_ as E};
+    let (m, s, v, tbs, tfields) = match pat_ {
+        T::MatchPattern_::Wildcard => return None,
+        T::MatchPattern_::Variant(m, s, v, tbs, tfields) => (m, s, v, tbs, tfields),
+    };
+    for (_, _, (_, (_, tfa))) in &tfields {
+        declare_bind(context, tfa)
+    }
+    let fields = assign_fields(context, context.variant_fields(&m, &s, &v), tfields);
+    match &subject_ty.value {
+        H::SingleType_::Base(_) => {
+            let bs = base_types(context, tbs);
+            let mut lfields = vec![];
+            let mut after = Block::new();
+            for (decl_idx, f, bt, tfa) in fields {
+                assert!(lfields.len() == decl_idx);
+                let st = &H::SingleType_::base(bt);
+                let (fa, mut fafter) = assign(context, tfa, st);
+                after.append(&mut fafter);
+                lfields.push((f, fa))
+            }
+            let lvalue = sp(ploc, H::LValue_::UnpackVariant(s, v, bs, lfields));
+            let move_tmp = H::exp(H::Type_::single(subject_ty.clone()), sp(ploc, use_tmp(tmp)));
+            let assign = sp(ploc, C::Assign(vec![lvalue], Box::new(move_tmp)));
+            result.push_back(sp(ploc, S::Command(assign)));
+            result.append(&mut after);
+        }
+        H::SingleType_::Ref(mut_, _) => {
+            for (idx, (decl_idx, f, bt, tfa)) in fields.into_iter().enumerate() {
+                assert!(idx == decl_idx);
+                let floc = tfa.loc;
+                let copy_tmp_ = E::Copy {
+                    from_user: false,
+                    var: tmp,
+                };
+                let copy_tmp = H::exp(H::Type_::single(subject_ty.clone()), sp(floc, copy_tmp_));
+                let borrow_ = E::BorrowVariantField(*mut_, Box::new(copy_tmp), v, f);
+                let borrow_ty = H::Type_::single(sp(floc, H::SingleType_::Ref(*mut_, bt)));
+                let borrow = H::exp(borrow_ty, sp(floc, borrow_));
+                assign_command(context, result, floc, sp(floc, vec![tfa]), borrow);
+            }
+        }
+    }
+    Some(v)
+}
+
+fn test_variant(tmp: Var, subject_ty: &H::SingleType, v: VariantName, loc: Loc) -> H::Exp {
+    use H::UnannotatedExp_ as E;
+    let subject_ref = match &subject_ty.value {
+        H::SingleType_::Base(bt) => {
+            let ref_ty = sp(loc, H::SingleType_::Ref(false, bt.clone()));
+            H::exp(
+                H::Type_::single(ref_ty),
+                sp(loc, E::BorrowLocal(false, tmp)),
+            )
+        }
+        H::SingleType_::Ref(_, _) => {
+            let copy_tmp_ = E::Copy {
+                from_user: false,
+                var: tmp,
+            };
+            H::exp(H::Type_::single(subject_ty.clone()), sp(loc, copy_tmp_))
+        }
+    };
+    H::exp(
+        H::Type_::bool(loc),
+        sp(loc, E::TestVariant(Box::new(subject_ref), v)),
+    )
+}
+
 //**************************************************************************************************
 // Commands
 //**************************************************************************************************
@@ -1062,7 +1292,7 @@ fn exp_impl(
 
     let res = match e_ {
         // Statement-like expressions
-        TE::While(tb, loop_body) => {
+        TE::While(label, tb, loop_body) => {
             let mut cond_block = Block::new();
             let cond_exp = exp(context, &mut cond_block, None, *tb);
 
@@ -1071,6 +1301,7 @@ fn exp_impl(
             ignore_and_pop(&mut loop_block, el);
 
             let s_ = S::While {
+                label,
                 cond: (cond_block, cond_exp),
                 block: loop_block,
             };
@@ -1080,12 +1311,14 @@ fn exp_impl(
             }
         }
         TE::Loop {
+            label,
             has_break,
             body: loop_body,
         } => {
             let loop_block = statement_loop_body(context, *loop_body);
 
             let s_ = S::Loop {
+                label,
                 block: loop_block,
                 has_break,
             };
@@ -1099,6 +1332,7 @@ fn exp_impl(
             }
         }
         TE::Block(seq) => return block(context, result, eloc, None, seq),
+        TE::Match(tsubject, tarms) => match_exp(context, result, &ty, eloc, *tsubject, tarms),
 
         // Command-like expressions
         TE::Return(te) => {
@@ -1120,13 +1354,13 @@ fn exp_impl(
             result.push_back(sp(eloc, S::Command(c)));
             HE::Unreachable
         }
-        TE::Break => {
-            let c = sp(eloc, C::Break);
+        TE::Break(label) => {
+            let c = sp(eloc, C::Break(label));
             result.push_back(sp(eloc, S::Command(c)));
             HE::Unreachable
         }
-        TE::Continue => {
-            let c = sp(eloc, C::Continue);
+        TE::Continue(label) => {
+            let c = sp(eloc, C::Continue(label));
             result.push_back(sp(eloc, S::Command(c)));
             HE::Unreachable
         }
@@ -1216,78 +1450,16 @@ fn exp_impl(
 
         TE::Pack(m, s, tbs, tfields) => {
             let bs = base_types(context, tbs);
-
-            let decl_fields = context.fields(&m, &s);
-            let mut count = 0;
-            let mut decl_field = |f: &Field| -> usize {
-                match decl_fields {
-                    Some(field_map) => *field_map.get(f).unwrap(),
-                    None => {
-                        // none can occur with errors in typing
-                        let i = count;
-                        count += 1;
-                        i
-                    }
-                }
-            };
-
-            let mut texp_fields: Vec<(usize, Field, usize, N::Type, T::Exp)> = tfields
-                .into_iter()
-                .map(|(f, (exp_idx, (bt, tf)))| (decl_field(&f), f, exp_idx, bt, tf))
-                .collect();
-            texp_fields.sort_by(|(_, _, eidx1, _, _), (_, _, eidx2, _, _)| eidx1.cmp(eidx2));
-
-            let bind_all_fields = texp_fields
-                .iter()
-                .any(|(decl_idx, _, exp_idx, _, _)| decl_idx != exp_idx);
-            let fields = if !bind_all_fields {
-                let mut fs = vec![];
-                let tes = texp_fields
-                    .into_iter()
-                    .map(|(_, f, _, bt, te)| {
-                        let bt = base_type(context, bt);
-                        fs.push((f, bt.clone()));
-                        let t = H::Type_::base(bt);
-                        (te, Some(t))
-                    })
-                    .collect();
-                let es = exp_evaluation_order(context, result, tes);
-                assert!(
-                    fs.len() == es.len(),
-                    "ICE exp_evaluation_order changed arity"
-                );
-                es.into_iter()
-                    .zip(fs)
-                    .map(|(e, (f, bt))| (f, bt, e))
-                    .collect()
-            } else {
-                let num_fields = decl_fields.as_ref().map(|m| m.len()).unwrap_or(0);
-                let mut fields = (0..num_fields).map(|_| None).collect::<Vec<_>>();
-                for (decl_idx, f, _exp_idx, bt, tf) in texp_fields {
-                    // Might have too many arguments, there will be an error from typing
-                    if decl_idx >= fields.len() {
-                        debug_assert!(context.env.has_errors());
-                        break;
-                    }
-                    let bt = base_type(context, bt);
-                    let t = H::Type_::base(bt.clone());
-                    let ef = exp_(context, result, Some(&t), tf);
-                    assert!(fields.get(decl_idx).unwrap().is_none());
-                    let move_tmp = bind_exp(context, result, ef);
-                    fields[decl_idx] = Some((f, bt, move_tmp))
-                }
-                // Might have too few arguments, there will be an error from typing if so
-                fields
-                    .into_iter()
-                    .filter_map(|o| {
-                        // if o is None, context should have errors
-                        debug_assert!(o.is_some() || context.env.has_errors());
-                        o
-                    })
-                    .collect()
-            };
+            let decl_fields = context.fields(&m, &s).cloned();
+            let fields = pack_fields(context, result, decl_fields, tfields);
             HE::Pack(s, bs, fields)
         }
+        TE::PackVariant(m, s, v, tbs, tfields) => {
+            let bs = base_types(context, tbs);
+            let decl_fields = context.variant_fields(&m, &s, &v).cloned();
+            let fields = pack_fields(context, result, decl_fields, tfields);
+            HE::PackVariant(s, v, bs, fields)
+        }
         TE::ExpList(titems) => {
             assert!(!titems.is_empty());
             let mut tmp_items = vec![];
@@ -1369,12 +1541,93 @@ fn exp_impl(
             assert!(context.env.has_errors());
             HE::UnresolvedError
         }
+        // only remain if inlining was skipped due to errors
+        TE::VarCall(_, _) | TE::Lambda(_, _, _) => {
+            assert!(context.env.has_errors());
+            HE::UnresolvedError
+        }
 
         TE::IfElse(..) | TE::BinopExp(..) => unreachable!(),
     };
     H::exp(ty, sp(eloc, res))
 }
 
+fn pack_fields(
+    context: &mut Context,
+    result: &mut Block,
+    decl_fields: Option<FieldIndices>,
+    tfields: Fields<(N::Type, T::Exp)>,
+) -> Vec<(Field, H::BaseType, H::Exp)> {
+    let mut count = 0;
+    let mut decl_field = |f: &Field| -> usize {
+        match &decl_fields {
+            Some(field_map) => *field_map.get(f).unwrap(),
+            None => {
+                // none can occur with errors in typing
+                let i = count;
+                count += 1;
+                i
+            }
+        }
+    };
+
+    let mut texp_fields: Vec<(usize, Field, usize, N::Type, T::Exp)> = tfields
+        .into_iter()
+        .map(|(f, (exp_idx, (bt, tf)))| (decl_field(&f), f, exp_idx, bt, tf))
+        .collect();
+    texp_fields.sort_by(|(_, _, eidx1, _, _), (_, _, eidx2, _, _)| eidx1.cmp(eidx2));
+
+    let bind_all_fields = texp_fields
+        .iter()
+        .any(|(decl_idx, _, exp_idx, _, _)| decl_idx != exp_idx);
+    if !bind_all_fields {
+        let mut fs = vec![];
+        let tes = texp_fields
+            .into_iter()
+            .map(|(_, f, _, bt, te)| {
+                let bt = base_type(context, bt);
+                fs.push((f, bt.clone()));
+                let t = H::Type_::base(bt);
+                (te, Some(t))
+            })
+            .collect();
+        let es = exp_evaluation_order(context, result, tes);
+        assert!(
+            fs.len() == es.len(),
+            "ICE exp_evaluation_order changed arity"
+        );
+        es.into_iter()
+            .zip(fs)
+            .map(|(e, (f, bt))| (f, bt, e))
+            .collect()
+    } else {
+        let num_fields = decl_fields.as_ref().map(|m| m.len()).unwrap_or(0);
+        let mut fields = (0..num_fields).map(|_| None).collect::<Vec<_>>();
+        for (decl_idx, f, _exp_idx, bt, tf) in texp_fields {
+            // Might have too many arguments, there will be an error from typing
+            if decl_idx >= fields.len() {
+                debug_assert!(context.env.has_errors());
+                break;
+            }
+            let bt = base_type(context, bt);
+            let t = H::Type_::base(bt.clone());
+            let ef = exp_(context, result, Some(&t), tf);
+            assert!(fields.get(decl_idx).unwrap().is_none());
+            let move_tmp = bind_exp(context, result, ef);
+            fields[decl_idx] = Some((f, bt, move_tmp))
+        }
+        // Might have too few arguments, there will be an error from typing if so
+        fields
+            .into_iter()
+            .filter_map(|o| {
+                // if o is None, context should have errors
+                debug_assert!(o.is_some() || context.env.has_errors());
+                o
+            })
+            .collect()
+    }
+}
+
 fn exp_evaluation_order(
     context: &mut Context,
     result: &mut Block,
@@ -1710,6 +1963,8 @@ fn bind_for_short_circuit(e: &T::Exp) -> bool {
         | TE::Constant(_, _)
         | TE::Move { .. }
         | TE::Copy { .. }
+        | TE::VarCall(_, _)
+        | TE::Lambda(_, _, _)
         | TE::UnresolvedError => false,
 
         // TODO might want to case ModuleCall for fake natives
@@ -1718,11 +1973,12 @@ fn bind_for_short_circuit(e: &T::Exp) -> bool {
         TE::Block(seq) => bind_for_short_circuit_sequence(seq),
         TE::Annotate(el, _) => bind_for_short_circuit(el),
 
-        TE::Break
-        | TE::Continue
+        TE::Break(_)
+        | TE::Continue(_)
         | TE::IfElse(_, _, _)
-        | TE::While(_, _)
+        | TE::While(_, _, _)
         | TE::Loop { .. }
+        | TE::Match(_, _)
         | TE::Return(_)
         | TE::Abort(_)
         | TE::Builtin(_, _)
@@ -1737,6 +1993,7 @@ fn bind_for_short_circuit(e: &T::Exp) -> bool {
         | TE::Assign(_, _, _)
         | TE::Mutate(_, _)
         | TE::Pack(_, _, _, _)
+        | TE::PackVariant(_, _, _, _, _)
         | TE::Vector(_, _, _, _)
         | TE::BorrowLocal(_, _)
         | TE::ExpList(_)
@@ -1821,8 +2078,8 @@ fn check_trailing_unit(context: &mut Context, block: &mut Block) {
     fn divergent_block(block: &Block) -> bool {
         matches!(
             block.back(),
-            Some(hcmd!(_, C::Break))
-                | Some(hcmd!(_, C::Continue))
+            Some(hcmd!(_, C::Break(_)))
+                | Some(hcmd!(_, C::Continue(_)))
                 | Some(hcmd!(_, C::Abort(_)))
                 | Some(hcmd!(_, C::Return { .. }))
                 | Some(hignored!(_, E::Unreachable))
@@ -1882,10 +2139,10 @@ fn check_trailing_unit(context: &mut Context, block: &mut Block) {
         {
             invalid_trailing_unit!(context, *loc, *uloc)
         }
-        (hcmd!(loc, C::Break), trailing!(uloc))
-        | (hcmd!(loc, C::Break), trailing_returned!(uloc))
-        | (hcmd!(loc, C::Continue), trailing!(uloc))
-        | (hcmd!(loc, C::Continue), trailing_returned!(uloc))
+        (hcmd!(loc, C::Break(_)), trailing!(uloc))
+        | (hcmd!(loc, C::Break(_)), trailing_returned!(uloc))
+        | (hcmd!(loc, C::Continue(_)), trailing!(uloc))
+        | (hcmd!(loc, C::Continue(_)), trailing_returned!(uloc))
         | (hcmd!(loc, C::Abort(_)), trailing!(uloc))
         | (hcmd!(loc, C::Abort(_)), trailing_returned!(uloc))
         | (hcmd!(loc, C::Return { .. }), trailing!(uloc))
@@ -1913,6 +2170,7 @@ fn check_trailing_unit_statement(context: &mut Context, sp!(_, s_): &mut H::Stat
         S::While {
             cond: (cond_block, _),
             block,
+            ..
         } => {
             check_trailing_unit(context, cond_block);
             check_trailing_unit(context, block)
@@ -1990,6 +2248,7 @@ fn remove_unused_bindings_statement(unused: &BTreeSet<Var>, sp!(_, s_): &mut H::
         S::While {
             cond: (cond_block, _),
             block,
+            ..
         } => {
             remove_unused_bindings(unused, cond_block);
             remove_unused_bindings(unused, block)
@@ -2016,7 +2275,7 @@ fn remove_unused_bindings_lvalue(unused: &BTreeSet<Var>, sp!(_, l_): &mut H::LVa
     match l_ {
         HL::Var(v, _) if unused.contains(v) => *l_ = HL::Ignore,
         HL::Var(_, _) | HL::Ignore => (),
-        HL::Unpack(_, _, fields) => fields
+        HL::Unpack(_, _, fields) | HL::UnpackVariant(_, _, _, fields) => fields
             .iter_mut()
             .for_each(|(_, l)| remove_unused_bindings_lvalue(unused, l)),
     }
@@ -7,9 +7,9 @@ use anyhow::{anyhow, Result};
 use move_binary_format::{
     access::ModuleAccess,
     file_format::{
-        Ability, AbilitySet, CompiledModule, FunctionDefinition, ModuleHandle, SignatureToken,
-        StructDefinition, StructFieldInformation, StructHandleIndex, StructTypeParameter,
-        TypeParameterIndex, Visibility,
+        Ability, AbilitySet, CompiledModule, FieldDefinition, FunctionDefinition, ModuleHandle,
+        SignatureToken, StructDefinition, StructFieldInformation, StructHandleIndex,
+        StructTypeParameter, TypeParameterIndex, Visibility,
     },
 };
 use move_core_types::language_storage::ModuleId;
@@ -185,35 +185,57 @@ fn write_struct_def(ctx: &mut Context, sdef: &StructDefinition) -> String {
     push_line!(
         out,
         format!(
-            "    struct {}{}{} {{",
+            "    {} {}{}{} {{",
+            if sdef.has_variants() {
+                "enum"
+            } else {
+                "struct"
+            },
             ctx.module.identifier_at(shandle.name),
             write_struct_type_parameters(&shandle.type_parameters),
             write_ability_modifiers(shandle.abilities),
         )
     );
 
-    let fields = match &sdef.field_information {
-        StructFieldInformation::Native => {
-            push!(out, "    }");
-            return out;
+    match &sdef.field_information {
+        StructFieldInformation::Native => (),
+        StructFieldInformation::Declared(fields) => {
+            for field in fields {
+                push_line!(out, format!("        {},", write_field(ctx, field)))
+            }
+        }
+        StructFieldInformation::DeclaredVariants(variants) => {
+            for variant in variants {
+                let fields = variant
+                    .fields
+                    .iter()
+                    .map(|field| write_field(ctx, field))
+                    .collect::<Vec<_>>();
+                let name = ctx.module.identifier_at(variant.name);
+                if fields.is_empty() {
+                    push_line!(out, format!("        {},", name))
+                } else {
+                    push_line!(
+                        out,
+                        format!("        {} {{ {} }},", name, fields.join(", "))
+                    )
+                }
+            }
         }
-        StructFieldInformation::Declared(fields) => fields,
-    };
-    for field in fields {
-        push_line!(
-            out,
-            format!(
-                "        {}: {},",
-                ctx.module.identifier_at(field.name),
-                write_signature_token(ctx, &field.signature.0),
-            )
-        )
     }
 
     push!(out, "    }");
     out
 }
 
+fn write_field(ctx: &mut Context, field: &FieldDefinition) -> String {
+    format!(
+        "{}: {}",
+        ctx.module.identifier_at(field.name),
+        write_signature_token(ctx, &field.signature.0),
+    )
+}
+
 fn write_function_def(ctx: &mut Context, fdef: &FunctionDefinition) -> String {
     let fhandle = ctx.module.function_handle_at(fdef.function);
     let parameters = &ctx.module.signature_at(fhandle.parameters).0;
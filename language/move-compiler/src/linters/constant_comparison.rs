@@ -0,0 +1,44 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports comparisons between two constant values, e.g. `1 < 2` or `MAX == 0`, whose result is
+//! always the same
+
+use super::TypingLint;
+use crate::{
+    diag,
+    parser::ast::BinOp_,
+    shared::CompilationEnv,
+    typing::ast::{self as T, UnannotatedExp_ as TE},
+};
+
+pub const NAME: &str = "constant_comparison";
+
+pub struct ConstantComparison;
+
+impl TypingLint for ConstantComparison {
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp) {
+        if let TE::BinopExp(lhs, sp!(_, op), _, rhs) = &e.exp.value {
+            let is_comparison = matches!(
+                op,
+                BinOp_::Eq | BinOp_::Neq | BinOp_::Lt | BinOp_::Gt | BinOp_::Le | BinOp_::Ge
+            );
+            if is_comparison && is_constant(lhs) && is_constant(rhs) {
+                let msg = format!(
+                    "Both operands of '{}' are constants, so the comparison always has the same \
+                     result",
+                    op
+                );
+                env.add_diag(diag!(Linter::ConstantComparison, (e.exp.loc, msg)))
+            }
+        }
+    }
+}
+
+fn is_constant(e: &T::Exp) -> bool {
+    match &e.exp.value {
+        TE::Value(_) | TE::Constant(_, _) => true,
+        TE::Annotate(inner, _) => is_constant(inner),
+        _ => false,
+    }
+}
@@ -0,0 +1,262 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints run over the typed AST when compiling with `--lint`. They report code that is valid,
+//! but most likely not what was intended. Any named warning, including those of the lints, can be
+//! allowed within an item with `#[allow(<name>)]`, or `#[allow(lint(<name>))]` for the lints.
+
+mod constant_comparison;
+mod needless_mut_ref;
+mod redundant_copy;
+mod self_assignment;
+mod shift_overflow;
+mod while_true;
+
+use crate::{
+    diag,
+    diagnostics::codes::*,
+    expansion::ast::{self as E, AttributeName_},
+    shared::{
+        known_attributes::{KnownAttribute, LintAttribute},
+        CompilationEnv, Name,
+    },
+    typing::ast as T,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use once_cell::sync::Lazy;
+use std::collections::BTreeSet;
+
+/// A lint over the expressions of the typed AST
+trait TypingLint {
+    /// Checks a single expression. Sub-expressions are visited separately
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp);
+}
+
+fn lints() -> Vec<Box<dyn TypingLint>> {
+    vec![
+        Box::new(self_assignment::SelfAssignment),
+        Box::new(redundant_copy::RedundantCopy),
+        Box::new(while_true::WhileTrue),
+        Box::new(constant_comparison::ConstantComparison),
+        Box::new(needless_mut_ref::NeedlessMutRef),
+        Box::new(shift_overflow::ShiftOverflow),
+    ]
+}
+
+/// The names of the lints, as used in `#[allow(lint(<name>))]`, and their warnings
+static LINT_WARNINGS: Lazy<Vec<(&'static str, DiagnosticInfo)>> = Lazy::new(|| {
+    vec![
+        (self_assignment::NAME, Linter::SelfAssignment.into_info()),
+        (redundant_copy::NAME, Linter::RedundantCopy.into_info()),
+        (while_true::NAME, Linter::WhileTrue.into_info()),
+        (
+            constant_comparison::NAME,
+            Linter::ConstantComparison.into_info(),
+        ),
+        (needless_mut_ref::NAME, Linter::NeedlessMutRef.into_info()),
+        (shift_overflow::NAME, Linter::ShiftOverflow.into_info()),
+    ]
+});
+
+/// The names of the other warnings that can be allowed
+static OTHER_WARNINGS: Lazy<Vec<(&'static str, DiagnosticInfo)>> = Lazy::new(|| {
+    vec![
+        ("unused_alias", UnusedItem::Alias.into_info()),
+        ("unused_variable", UnusedItem::Variable.into_info()),
+        ("unused_assignment", UnusedItem::Assignment.into_info()),
+        ("unused_trailing_semi", UnusedItem::TrailingSemi.into_info()),
+        ("dead_code", UnusedItem::DeadCode.into_info()),
+        (
+            "unused_type_parameter",
+            UnusedItem::StructTypeParam.into_info(),
+        ),
+        ("unused_attribute", UnusedItem::Attribute.into_info()),
+        ("unused_function", UnusedItem::Function.into_info()),
+        ("unused_constant", UnusedItem::Constant.into_info()),
+        ("unused_struct", UnusedItem::Struct.into_info()),
+        ("unused_friend", UnusedItem::Friend.into_info()),
+        ("deprecated_usage", Deprecation::Usage.into_info()),
+    ]
+});
+
+/// Returns the name of the warning, if it can be allowed
+pub fn warning_name(info: &DiagnosticInfo) -> Option<&'static str> {
+    LINT_WARNINGS
+        .iter()
+        .chain(OTHER_WARNINGS.iter())
+        .find(|(_, warning)| warning == info)
+        .map(|(name, _)| *name)
+}
+
+//**************************************************************************************************
+// Allowed warnings
+//**************************************************************************************************
+
+const ALLOW_ATTR: AttributeName_ =
+    AttributeName_::Known(KnownAttribute::Lint(LintAttribute::Allow));
+
+/// Allows the warnings listed in the `#[allow(..)]` attribute of the item at `loc`
+pub fn allow_warnings(env: &mut CompilationEnv, loc: Loc, attributes: &E::Attributes) {
+    let attr = match attributes.get_(&ALLOW_ATTR) {
+        None => return,
+        Some(attr) => attr,
+    };
+    let mut names = BTreeSet::new();
+    match &attr.value {
+        E::Attribute_::Parameterized(_, inner) => {
+            for (_, _, sp!(inner_loc, inner_attr)) in inner {
+                match inner_attr {
+                    E::Attribute_::Name(n) => allow_name(env, &mut names, n, false),
+                    E::Attribute_::Parameterized(n, lints)
+                        if n.value.as_str() == LintAttribute::LINT =>
+                    {
+                        for (_, _, lint_attr) in lints {
+                            match &lint_attr.value {
+                                E::Attribute_::Name(n) => allow_name(env, &mut names, n, true),
+                                _ => invalid_allow(env, lint_attr.loc),
+                            }
+                        }
+                    }
+                    _ => invalid_allow(env, *inner_loc),
+                }
+            }
+        }
+        E::Attribute_::Name(_) | E::Attribute_::Assigned(_, _) => invalid_allow(env, attr.loc),
+    }
+    env.add_allowed_warnings(loc, names)
+}
+
+fn allow_name(env: &mut CompilationEnv, names: &mut BTreeSet<Symbol>, n: &Name, lint: bool) {
+    let known = |warnings: &[(&str, DiagnosticInfo)]| {
+        warnings.iter().any(|(name, _)| *name == n.value.as_str())
+    };
+    let is_known = known(&LINT_WARNINGS) || (!lint && known(&OTHER_WARNINGS));
+    if is_known {
+        names.insert(n.value);
+    } else {
+        let msg = if lint {
+            format!("Unknown lint '{}'", n)
+        } else {
+            format!("Unknown warning '{}'", n)
+        };
+        env.add_diag(diag!(Attributes::ValueWarning, (n.loc, msg)))
+    }
+}
+
+fn invalid_allow(env: &mut CompilationEnv, loc: Loc) {
+    let msg = format!(
+        "Invalid '{}' attribute. Expected a list of warning names, e.g. '{}(<name>)' or \
+         '{}({}(<name>))'",
+        LintAttribute::ALLOW,
+        LintAttribute::ALLOW,
+        LintAttribute::ALLOW,
+        LintAttribute::LINT,
+    );
+    env.add_diag(diag!(Attributes::InvalidValue, (loc, msg)))
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+/// Runs the lints over the modules and scripts compiled from source
+pub fn program(env: &mut CompilationEnv, prog: &T::Program) {
+    let lints = lints();
+    for (_, _, mdef) in prog.modules.iter().filter(|(_, _, m)| m.is_source_module) {
+        for (_, _, cdef) in &mdef.constants {
+            exp(&lints, env, &cdef.value)
+        }
+        for (_, _, fdef) in &mdef.functions {
+            function(&lints, env, fdef)
+        }
+    }
+    for script in prog.scripts.values() {
+        for (_, _, cdef) in &script.constants {
+            exp(&lints, env, &cdef.value)
+        }
+        function(&lints, env, &script.function)
+    }
+}
+
+fn function(lints: &[Box<dyn TypingLint>], env: &mut CompilationEnv, fdef: &T::Function) {
+    match &fdef.body.value {
+        T::FunctionBody_::Native => (),
+        T::FunctionBody_::Defined(seq) => sequence(lints, env, seq),
+    }
+}
+
+fn sequence(lints: &[Box<dyn TypingLint>], env: &mut CompilationEnv, seq: &T::Sequence) {
+    for sp!(_, item) in seq {
+        match item {
+            T::SequenceItem_::Seq(e) | T::SequenceItem_::Bind(_, _, e) => exp(lints, env, e),
+            T::SequenceItem_::Declare(_) => (),
+        }
+    }
+}
+
+fn exp(lints: &[Box<dyn TypingLint>], env: &mut CompilationEnv, e: &T::Exp) {
+    use T::UnannotatedExp_ as TE;
+    for lint in lints {
+        lint.visit_exp(env, e)
+    }
+    match &e.exp.value {
+        TE::Unit { .. }
+        | TE::Value(_)
+        | TE::Move { .. }
+        | TE::Copy { .. }
+        | TE::Use(_)
+        | TE::Constant(_, _)
+        | TE::BorrowLocal(_, _)
+        | TE::Break(_)
+        | TE::Continue(_)
+        | TE::Spec(_, _)
+        | TE::UnresolvedError => (),
+
+        TE::ModuleCall(call) => exp(lints, env, &call.arguments),
+        TE::Builtin(_, e)
+        | TE::VarCall(_, e)
+        | TE::Vector(_, _, _, e)
+        | TE::Loop { body: e, .. }
+        | TE::Lambda(_, _, e)
+        | TE::Assign(_, _, e)
+        | TE::Return(e)
+        | TE::Abort(e)
+        | TE::Dereference(e)
+        | TE::UnaryExp(_, e)
+        | TE::Borrow(_, e, _)
+        | TE::TempBorrow(_, e)
+        | TE::Cast(e, _)
+        | TE::Annotate(e, _) => exp(lints, env, e),
+        TE::IfElse(cond, if_true, if_false) => {
+            exp(lints, env, cond);
+            exp(lints, env, if_true);
+            exp(lints, env, if_false)
+        }
+        TE::While(_, e1, e2) | TE::Mutate(e1, e2) | TE::BinopExp(e1, _, _, e2) => {
+            exp(lints, env, e1);
+            exp(lints, env, e2)
+        }
+        TE::Match(subject, arms) => {
+            exp(lints, env, subject);
+            for sp!(_, (_, arm)) in arms {
+                exp(lints, env, arm)
+            }
+        }
+        TE::Block(seq) => sequence(lints, env, seq),
+        TE::Pack(_, _, _, fields) | TE::PackVariant(_, _, _, _, fields) => {
+            for (_, _, (_, (_, e))) in fields {
+                exp(lints, env, e)
+            }
+        }
+        TE::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        exp(lints, env, e)
+                    }
+                }
+            }
+        }
+    }
+}
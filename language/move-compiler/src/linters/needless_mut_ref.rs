@@ -0,0 +1,65 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports a `&mut` borrow that is immediately frozen to an immutable reference, e.g. when
+//! passing `&mut x` to a `&u64` parameter, where a `&` borrow would do
+
+use super::TypingLint;
+use crate::{
+    diag,
+    naming::ast::Type_,
+    shared::CompilationEnv,
+    typing::ast::{self as T, BuiltinFunction_, UnannotatedExp_ as TE},
+};
+
+pub const NAME: &str = "needless_mut_ref";
+
+pub struct NeedlessMutRef;
+
+impl TypingLint for NeedlessMutRef {
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp) {
+        match &e.exp.value {
+            TE::Builtin(bf, borrow) if matches!(&bf.value, BuiltinFunction_::Freeze(_)) => {
+                check_borrow(env, borrow)
+            }
+            TE::ModuleCall(call) => {
+                let arguments = match arguments(&call.arguments) {
+                    Some(arguments) => arguments,
+                    None => return,
+                };
+                for (param_ty, arg) in call.parameter_types.iter().zip(arguments) {
+                    if matches!(&param_ty.value, Type_::Ref(false, _)) {
+                        check_borrow(env, arg)
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+// The arguments of a call, if they can be matched up with the parameters
+fn arguments(e: &T::Exp) -> Option<Vec<&T::Exp>> {
+    match &e.exp.value {
+        TE::Unit { .. } => Some(vec![]),
+        TE::ExpList(items) => items
+            .iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) => Some(e),
+                T::ExpListItem::Splat(_, _, _) => None,
+            })
+            .collect(),
+        _ => Some(vec![e]),
+    }
+}
+
+fn check_borrow(env: &mut CompilationEnv, borrow: &T::Exp) {
+    let is_mut_borrow = matches!(
+        &borrow.exp.value,
+        TE::Borrow(true, _, _) | TE::BorrowLocal(true, _) | TE::TempBorrow(true, _)
+    );
+    if is_mut_borrow {
+        let msg = "This mutable reference is only used immutably. Use '&' instead of '&mut'";
+        env.add_diag(diag!(Linter::NeedlessMutRef, (borrow.exp.loc, msg)))
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports an explicit `copy` that is only borrowed or dereferenced, e.g. `&copy x` or
+//! `(copy x).f`, where the local itself could be used instead
+
+use super::TypingLint;
+use crate::{
+    diag,
+    shared::CompilationEnv,
+    typing::ast::{self as T, UnannotatedExp_ as TE},
+};
+
+pub const NAME: &str = "redundant_copy";
+
+pub struct RedundantCopy;
+
+impl TypingLint for RedundantCopy {
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp) {
+        let copied = match &e.exp.value {
+            TE::TempBorrow(_, inner) | TE::Dereference(inner) => inner,
+            _ => return,
+        };
+        if let TE::Copy {
+            from_user: true,
+            var,
+        } = &copied.exp.value
+        {
+            let msg = format!(
+                "This 'copy' is redundant, as the copy of '{}' is only used to read from it",
+                var
+            );
+            env.add_diag(diag!(Linter::RedundantCopy, (copied.exp.loc, msg)))
+        }
+    }
+}
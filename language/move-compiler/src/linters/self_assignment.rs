@@ -0,0 +1,56 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports assignments of a local or a field to itself, e.g. `x = x` or `s.f = s.f`, which have
+//! no effect
+
+use super::TypingLint;
+use crate::{
+    diag,
+    parser::ast::Var,
+    shared::CompilationEnv,
+    typing::ast::{self as T, UnannotatedExp_ as TE},
+};
+
+pub const NAME: &str = "self_assignment";
+
+pub struct SelfAssignment;
+
+impl TypingLint for SelfAssignment {
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp) {
+        let is_self_assignment = match &e.exp.value {
+            TE::Assign(sp!(_, lvalues), _, rhs) => match &lvalues[..] {
+                [sp!(_, T::LValue_::Var(v, _))] => matches!(local(rhs), Some(r) if r == v),
+                _ => false,
+            },
+            TE::Mutate(lhs, rhs) => match &rhs.exp.value {
+                TE::Dereference(place) => same_place(lhs, place),
+                _ => false,
+            },
+            _ => false,
+        };
+        if is_self_assignment {
+            let msg = "This assigns a value to itself, which has no effect";
+            env.add_diag(diag!(Linter::SelfAssignment, (e.exp.loc, msg)))
+        }
+    }
+}
+
+fn local(e: &T::Exp) -> Option<&Var> {
+    match &e.exp.value {
+        TE::Move { var, .. } | TE::Copy { var, .. } | TE::Use(var) => Some(var),
+        _ => None,
+    }
+}
+
+// Returns true if both references point to the same place, i.e. the same field path from the
+// same local
+fn same_place(e1: &T::Exp, e2: &T::Exp) -> bool {
+    match (&e1.exp.value, &e2.exp.value) {
+        (TE::Borrow(_, inner1, f1), TE::Borrow(_, inner2, f2)) => {
+            f1 == f2 && same_place(inner1, inner2)
+        }
+        (TE::BorrowLocal(_, v1), TE::BorrowLocal(_, v2)) => v1 == v2,
+        _ => matches!((local(e1), local(e2)), (Some(v1), Some(v2)) if v1 == v2),
+    }
+}
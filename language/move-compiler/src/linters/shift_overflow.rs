@@ -0,0 +1,59 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports shifts by a constant number of bits that is at least the bit width of the shifted
+//! value, e.g. `x << 64` for a `u64`, which always abort
+
+use super::TypingLint;
+use crate::{
+    diag,
+    expansion::ast::Value_,
+    naming::ast::{BuiltinTypeName_, Type, TypeName_, Type_},
+    parser::ast::BinOp_,
+    shared::CompilationEnv,
+    typing::ast::{self as T, UnannotatedExp_ as TE},
+};
+
+pub const NAME: &str = "shift_overflow";
+
+pub struct ShiftOverflow;
+
+impl TypingLint for ShiftOverflow {
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp) {
+        let (lhs, rhs) = match &e.exp.value {
+            TE::BinopExp(lhs, sp!(_, BinOp_::Shl | BinOp_::Shr), _, rhs) => (lhs, rhs),
+            _ => return,
+        };
+        let shift = match &rhs.exp.value {
+            TE::Value(sp!(_, Value_::U8(n))) => *n as u16,
+            _ => return,
+        };
+        let bits = match bit_width(&lhs.ty) {
+            Some(bits) => bits,
+            None => return,
+        };
+        if shift >= bits {
+            let msg = format!(
+                "Shifting a {}-bit value by {} bits always aborts with an arithmetic error",
+                bits, shift
+            );
+            env.add_diag(diag!(Linter::ShiftOverflow, (e.exp.loc, msg)))
+        }
+    }
+}
+
+fn bit_width(sp!(_, ty_): &Type) -> Option<u16> {
+    use BuiltinTypeName_ as B;
+    match ty_ {
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, b))), _) => match b {
+            B::U8 => Some(8),
+            B::U16 => Some(16),
+            B::U32 => Some(32),
+            B::U64 => Some(64),
+            B::U128 => Some(128),
+            B::U256 => Some(256),
+            B::Address | B::Signer | B::Vector | B::Bool => None,
+        },
+        _ => None,
+    }
+}
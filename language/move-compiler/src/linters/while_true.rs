@@ -0,0 +1,27 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `while (true)` loops, which should be written with `loop`
+
+use super::TypingLint;
+use crate::{
+    diag,
+    expansion::ast::Value_,
+    shared::CompilationEnv,
+    typing::ast::{self as T, UnannotatedExp_ as TE},
+};
+
+pub const NAME: &str = "while_true";
+
+pub struct WhileTrue;
+
+impl TypingLint for WhileTrue {
+    fn visit_exp(&self, env: &mut CompilationEnv, e: &T::Exp) {
+        if let TE::While(_, cond, _) = &e.exp.value {
+            if let TE::Value(sp!(_, Value_::Bool(true))) = &cond.exp.value {
+                let msg = "'while (true)' can be replaced with 'loop'";
+                env.add_diag(diag!(Linter::WhileTrue, (cond.exp.loc, msg)))
+            }
+        }
+    }
+}
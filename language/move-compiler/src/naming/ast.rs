@@ -5,16 +5,18 @@
 use crate::{
     expansion::ast::{
         ability_constraints_ast_debug, ability_modifiers_ast_debug, AbilitySet, Attributes, Fields,
-        Friend, ModuleIdent, SpecId, Value, Value_, Visibility,
+        Friend, ModuleIdent, SpecId, Value, Value_, Variants, Visibility,
     },
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        BinOp, BlockLabel, ConstantName, Field, FunctionName, StructName, UnaryOp, Var,
+        VariantName, ENTRY_MODIFIER, INLINE_MODIFIER,
     },
-    shared::{ast_debug::*, unique_map::UniqueMap, *},
+    shared::{ast_debug::*, ast_dump::serialize_map_as_seq, unique_map::UniqueMap, *},
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     fmt,
@@ -24,7 +26,7 @@ use std::{
 // Program
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub modules: UniqueMap<ModuleIdent, ModuleDefinition>,
     pub scripts: BTreeMap<Symbol, Script>,
@@ -34,7 +36,7 @@ pub struct Program {
 // Scripts
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Script {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -49,7 +51,7 @@ pub struct Script {
 // Modules
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDefinition {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -58,17 +60,34 @@ pub struct ModuleDefinition {
     /// `dependency_order` is the topological order/rank in the dependency graph.
     /// `dependency_order` is initialized at `0` and set in the uses pass
     pub dependency_order: usize,
+    /// The module members referenced in the specs of the module
+    pub spec_uses: BTreeSet<(ModuleIdent, Name)>,
     pub friends: UniqueMap<ModuleIdent, Friend>,
     pub structs: UniqueMap<StructName, StructDefinition>,
     pub constants: UniqueMap<ConstantName, Constant>,
     pub functions: UniqueMap<FunctionName, Function>,
+    pub use_funs: UseFuns,
+}
+
+//**************************************************************************************************
+// Use Funs
+//**************************************************************************************************
+
+// The 'use fun' aliases of a module, keyed by the receiver type and the method name
+pub type UseFuns = BTreeMap<(TypeName_, Symbol), UseFun>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct UseFun {
+    pub loc: Loc,
+    pub module: ModuleIdent,
+    pub function: FunctionName,
 }
 
 //**************************************************************************************************
 // Structs
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct StructDefinition {
     pub attributes: Attributes,
     pub abilities: AbilitySet,
@@ -76,15 +95,16 @@ pub struct StructDefinition {
     pub fields: StructFields,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct StructTypeParameter {
     pub param: TParam,
     pub is_phantom: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum StructFields {
     Defined(Fields<Type>),
+    Variants(Variants<Type>),
     Native(Loc),
 }
 
@@ -92,26 +112,28 @@ pub enum StructFields {
 // Functions
 //**************************************************************************************************
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize)]
 pub struct FunctionSignature {
     pub type_parameters: Vec<TParam>,
     pub parameters: Vec<(Var, Type)>,
     pub return_type: Type,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub enum FunctionBody_ {
     Defined(Sequence),
     Native,
 }
 pub type FunctionBody = Spanned<FunctionBody_>;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: bool,
     pub signature: FunctionSignature,
+    #[serde(serialize_with = "serialize_map_as_seq")]
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
 }
@@ -120,19 +142,21 @@ pub struct Function {
 // Constants
 //**************************************************************************************************
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Constant {
     pub attributes: Attributes,
     pub loc: Loc,
     pub signature: Type,
     pub value: Exp,
+    // the message of the assertion the constant is the abort code of, if generated for one
+    pub assertion_message: Option<String>,
 }
 
 //**************************************************************************************************
 // Types
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Clone, PartialOrd, Eq, Ord)]
+#[derive(Debug, PartialEq, Clone, PartialOrd, Eq, Ord, Serialize)]
 pub enum BuiltinTypeName_ {
     // address
     Address,
@@ -157,7 +181,7 @@ pub enum BuiltinTypeName_ {
 }
 pub type BuiltinTypeName = Spanned<BuiltinTypeName_>;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum TypeName_ {
     // exp-list/tuple type
@@ -167,26 +191,27 @@ pub enum TypeName_ {
 }
 pub type TypeName = Spanned<TypeName_>;
 
-#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize)]
 pub struct TParamID(pub u64);
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct TParam {
     pub id: TParamID,
     pub user_specified_name: Name,
     pub abilities: AbilitySet,
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize)]
 pub struct TVar(u64);
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Type_ {
     Unit,
     Ref(bool, Box<Type>),
     Param(TParam),
     Apply(Option<AbilitySet>, TypeName, Vec<Type>),
+    Fun(Vec<Type>, Box<Type>),
     Var(TVar),
     Anything,
     UnresolvedError,
@@ -197,7 +222,7 @@ pub type Type = Spanned<Type_>;
 // Expressions
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum LValue_ {
     Ignore,
@@ -208,14 +233,32 @@ pub type LValue = Spanned<LValue_>;
 pub type LValueList_ = Vec<LValue>;
 pub type LValueList = Spanned<LValueList_>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum MatchPattern_ {
+    Wildcard,
+    Variant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Option<Vec<Type>>,
+        Fields<LValue>,
+    ),
+}
+pub type MatchPattern = Spanned<MatchPattern_>;
+
+pub type MatchArm_ = (MatchPattern, Exp);
+pub type MatchArm = Spanned<MatchArm_>;
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum ExpDotted_ {
     Exp(Box<Exp>),
     Dot(Box<ExpDotted>, Field),
+    Index(Box<ExpDotted>, Box<Exp>),
 }
 pub type ExpDotted = Spanned<ExpDotted_>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum BuiltinFunction_ {
     MoveTo(Option<Type>),
@@ -227,7 +270,7 @@ pub enum BuiltinFunction_ {
 }
 pub type BuiltinFunction = Spanned<BuiltinFunction_>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Exp_ {
     Value(Value),
@@ -242,13 +285,17 @@ pub enum Exp_ {
         Option<Vec<Type>>,
         Spanned<Vec<Exp>>,
     ),
+    MethodCall(ExpDotted, Name, Option<Vec<Type>>, Spanned<Vec<Exp>>),
     Builtin(BuiltinFunction, Spanned<Vec<Exp>>),
+    VarCall(Var, Spanned<Vec<Exp>>),
     Vector(Loc, Option<Type>, Spanned<Vec<Exp>>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
-    While(Box<Exp>, Box<Exp>),
-    Loop(Box<Exp>),
+    While(Option<BlockLabel>, Box<Exp>, Box<Exp>),
+    Loop(Option<BlockLabel>, Box<Exp>),
+    Match(Box<Exp>, Vec<MatchArm>),
     Block(Sequence),
+    Lambda(LValueList, Box<Exp>),
 
     Assign(LValueList, Box<Exp>),
     FieldMutate(ExpDotted, Box<Exp>),
@@ -256,14 +303,21 @@ pub enum Exp_ {
 
     Return(Box<Exp>),
     Abort(Box<Exp>),
-    Break,
-    Continue,
+    Break(Option<BlockLabel>),
+    Continue(Option<BlockLabel>),
 
     Dereference(Box<Exp>),
     UnaryExp(UnaryOp, Box<Exp>),
     BinopExp(Box<Exp>, BinOp, Box<Exp>),
 
     Pack(ModuleIdent, StructName, Option<Vec<Type>>, Fields<Exp>),
+    PackVariant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Option<Vec<Type>>,
+        Fields<Exp>,
+    ),
     ExpList(Vec<Exp>),
     Unit {
         trailing: bool,
@@ -282,7 +336,7 @@ pub enum Exp_ {
 pub type Exp = Spanned<Exp_>;
 
 pub type Sequence = VecDeque<SequenceItem>;
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum SequenceItem_ {
     Seq(Exp),
     Declare(LValueList, Option<Type>),
@@ -661,10 +715,12 @@ impl AstDebug for ModuleDefinition {
             attributes,
             is_source_module,
             dependency_order,
+            spec_uses,
             friends,
             structs,
             constants,
             functions,
+            use_funs,
         } = self;
         if let Some(n) = package_name {
             w.writeln(&format!("{}", n))
@@ -676,10 +732,27 @@ impl AstDebug for ModuleDefinition {
             w.writeln("source module")
         }
         w.writeln(&format!("dependency order #{}", dependency_order));
+        for (mident, n) in spec_uses {
+            w.write(&format!("spec uses {}::{};", mident, n));
+            w.new_line()
+        }
         for (mident, _loc) in friends.key_cloned_iter() {
             w.write(&format!("friend {};", mident));
             w.new_line();
         }
+        for (
+            (tn, method),
+            UseFun {
+                module, function, ..
+            },
+        ) in use_funs
+        {
+            w.write(&format!(
+                "use fun {}::{} as {}.{};",
+                module, function, tn, method
+            ));
+            w.new_line();
+        }
         for sdef in structs.key_cloned_iter() {
             sdef.ast_debug(w);
             w.new_line();
@@ -710,18 +783,35 @@ impl AstDebug for (StructName, &StructDefinition) {
         if let StructFields::Native(_) = fields {
             w.write("native ");
         }
-        w.write(&format!("struct {}", name));
+        match fields {
+            StructFields::Variants(_) => w.write(&format!("enum {}", name)),
+            _ => w.write(&format!("struct {}", name)),
+        }
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Defined(fields) => w.block(|w| {
                 w.list(fields, ",", |w, (_, f, idx_st)| {
                     let (idx, st) = idx_st;
                     w.write(&format!("{}#{}: ", idx, f));
                     st.ast_debug(w);
                     true
                 })
-            })
+            }),
+            StructFields::Variants(variants) => w.block(|w| {
+                w.list(variants, ",", |w, (_, v, (vidx, fields))| {
+                    w.write(&format!("{}#{}", vidx, v));
+                    w.write("{");
+                    w.comma(fields, |w, (_, f, idx_st)| {
+                        let (idx, st) = idx_st;
+                        w.write(&format!("{}#{}: ", idx, f));
+                        st.ast_debug(w);
+                    });
+                    w.write("}");
+                    true
+                })
+            }),
+            StructFields::Native(_) => (),
         }
     }
 }
@@ -734,6 +824,7 @@ impl AstDebug for (FunctionName, &Function) {
                 attributes,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -744,6 +835,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if *inline {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -808,6 +902,7 @@ impl AstDebug for (ConstantName, &Constant) {
                 loc: _loc,
                 signature,
                 value,
+                assertion_message,
             },
         ) = self;
         attributes.ast_debug(w);
@@ -816,6 +911,9 @@ impl AstDebug for (ConstantName, &Constant) {
         w.write(" = ");
         value.ast_debug(w);
         w.write(";");
+        if let Some(message) = assertion_message {
+            w.write(&format!(" /* assertion message: {:?} */", message));
+        }
     }
 }
 
@@ -904,6 +1002,12 @@ impl AstDebug for Type_ {
                     }),
                 }
             }
+            Type_::Fun(args, result) => {
+                w.write("|");
+                w.comma(args, |w, ty| ty.ast_debug(w));
+                w.write("|");
+                result.ast_debug(w);
+            }
             Type_::Var(tv) => w.write(&format!("#{}", tv.0)),
             Type_::Anything => w.write("_"),
             Type_::UnresolvedError => w.write("_|_"),
@@ -970,12 +1074,29 @@ impl AstDebug for Exp_ {
                 w.comma(rhs, |w, e| e.ast_debug(w));
                 w.write(")");
             }
+            E::MethodCall(ed, n, tys_opt, sp!(_, rhs)) => {
+                ed.ast_debug(w);
+                w.write(&format!(".{}", n));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Builtin(bf, sp!(_, rhs)) => {
                 bf.ast_debug(w);
                 w.write("(");
                 w.comma(rhs, |w, e| e.ast_debug(w));
                 w.write(")");
             }
+            E::VarCall(v, sp!(_, rhs)) => {
+                w.write(&format!("{}(", v));
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Vector(_loc, ty_opt, sp!(_, elems)) => {
                 w.write("vector");
                 if let Some(ty) = ty_opt {
@@ -1002,6 +1123,21 @@ impl AstDebug for Exp_ {
                 });
                 w.write("}");
             }
+            E::PackVariant(m, s, v, tys_opt, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_e)| {
+                    let (idx, e) = idx_e;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
             E::IfElse(b, t, f) => {
                 w.write("if (");
                 b.ast_debug(w);
@@ -1010,17 +1146,37 @@ impl AstDebug for Exp_ {
                 w.write(" else ");
                 f.ast_debug(w);
             }
-            E::While(b, e) => {
+            E::While(label, b, e) => {
+                label.ast_debug(w);
                 w.write("while (");
                 b.ast_debug(w);
                 w.write(")");
                 e.ast_debug(w);
             }
-            E::Loop(e) => {
+            E::Loop(label, e) => {
+                label.ast_debug(w);
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, rhs))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        rhs.ast_debug(w);
+                    })
+                });
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
+            E::Lambda(sp!(_, bs), e) => {
+                w.write("|");
+                bs.ast_debug(w);
+                w.write("| ");
+                e.ast_debug(w);
+            }
             E::ExpList(es) => {
                 w.write("(");
                 w.comma(es, |w, e| e.ast_debug(w));
@@ -1052,8 +1208,18 @@ impl AstDebug for Exp_ {
                 w.write("abort ");
                 e.ast_debug(w);
             }
-            E::Break => w.write("break"),
-            E::Continue => w.write("continue"),
+            E::Break(label) => {
+                w.write("break");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
+            E::Continue(label) => {
+                w.write("continue");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
             E::Dereference(e) => {
                 w.write("*");
                 e.ast_debug(w)
@@ -1138,6 +1304,12 @@ impl AstDebug for ExpDotted_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", n))
             }
+            D::Index(e, i) => {
+                e.ast_debug(w);
+                w.write("[");
+                i.ast_debug(w);
+                w.write("]")
+            }
         }
     }
 }
@@ -1179,3 +1351,26 @@ impl AstDebug for LValue_ {
         }
     }
 }
+
+impl AstDebug for MatchPattern_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            MatchPattern_::Wildcard => w.write("_"),
+            MatchPattern_::Variant(m, s, v, tys_opt, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_b)| {
+                    let (idx, b) = idx_b;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    b.ast_debug(w);
+                });
+                w.write("}");
+            }
+        }
+    }
+}
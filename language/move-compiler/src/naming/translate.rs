@@ -10,13 +10,17 @@ use crate::{
         translate::is_valid_struct_constant_or_schema_name as is_constant_name,
     },
     naming::ast as N,
-    parser::ast::{Ability_, ConstantName, Field, FunctionName, StructName, Var},
-    shared::{unique_map::UniqueMap, *},
+    parser::ast::{Ability_, BlockLabel, ConstantName, Field, FunctionName, StructName, Var},
+    shared::{
+        known_attributes::{DeprecationAttribute, KnownAttribute},
+        unique_map::UniqueMap,
+        *,
+    },
     FullyCompiledProgram,
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use super::fake_natives;
 
@@ -42,14 +46,52 @@ impl ResolvedType {
     }
 }
 
+/// A struct, function or constant marked '#[deprecated]'
+#[derive(Debug, Clone)]
+pub(crate) struct DeprecatedMember {
+    kind: &'static str,
+    // the location of the attribute
+    loc: Loc,
+    note: Option<String>,
+}
+
+impl DeprecatedMember {
+    // Warns about the use at `loc` of the deprecated member `n` of `m`
+    pub(crate) fn report_use(
+        &self,
+        env: &mut CompilationEnv,
+        loc: Loc,
+        m: &ModuleIdent,
+        n: impl std::fmt::Display,
+    ) {
+        let mut msg = format!("Use of deprecated {} '{}::{}'", self.kind, m, n);
+        if let Some(note) = &self.note {
+            msg = format!("{}. {}", msg, note);
+        }
+        env.add_diag(diag!(
+            Deprecation::Usage,
+            (loc, msg),
+            (self.loc, "Marked as deprecated here"),
+        ))
+    }
+}
+
 struct Context<'env> {
     env: &'env mut CompilationEnv,
     current_module: Option<ModuleIdent>,
     scoped_types: BTreeMap<ModuleIdent, BTreeMap<Symbol, (Loc, ModuleIdent, AbilitySet, usize)>>,
     unscoped_types: BTreeMap<Symbol, ResolvedType>,
     scoped_functions: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
+    /// Structs declared with positional fields, e.g. `struct S(u64)`
+    positional_structs: BTreeSet<(ModuleIdent, Symbol)>,
     unscoped_constants: BTreeMap<Symbol, Loc>,
     scoped_constants: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
+    /// The deprecated members of all modules. Module members share a single namespace
+    deprecated: BTreeMap<(ModuleIdent, Symbol), DeprecatedMember>,
+    /// Parameters of function type of the inline function currently being translated
+    fun_params: BTreeSet<Symbol>,
+    /// Labels of the loops surrounding the current expression, innermost last
+    loop_labels: Vec<BlockLabel>,
 }
 
 impl<'env> Context<'env> {
@@ -85,6 +127,17 @@ impl<'env> Context<'env> {
                 (mident, mems)
             })
             .collect();
+        let positional_structs = all_modules()
+            .flat_map(|(mident, mdef)| {
+                mdef.structs
+                    .key_cloned_iter()
+                    .filter(|(_, sdef)| {
+                        matches!(&sdef.fields, E::StructFields::Defined(fields) if E::is_positional(fields))
+                    })
+                    .map(|(s, _)| (mident, s.value()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
         let scoped_functions = all_modules()
             .map(|(mident, mdef)| {
                 let mems = mdef
@@ -105,6 +158,34 @@ impl<'env> Context<'env> {
                 (mident, mems)
             })
             .collect();
+        let mut deprecated = BTreeMap::new();
+        for (mident, mdef) in all_modules() {
+            let members = mdef
+                .structs
+                .iter()
+                .map(|(_, n, sdef)| ("struct", *n, &sdef.attributes))
+                .chain(
+                    mdef.functions
+                        .iter()
+                        .map(|(_, n, fdef)| ("function", *n, &fdef.attributes)),
+                )
+                .chain(
+                    mdef.constants
+                        .iter()
+                        .map(|(_, n, cdef)| ("constant", *n, &cdef.attributes)),
+                );
+            // the attributes of dependencies are not validated, their diagnostics are not reported
+            let mut env = if mdef.is_source_module {
+                Some(&mut *compilation_env)
+            } else {
+                None
+            };
+            for (kind, n, attributes) in members {
+                if let Some(deprecation) = deprecation(env.as_deref_mut(), kind, attributes) {
+                    deprecated.insert((mident, n), deprecation);
+                }
+            }
+        }
         let unscoped_types = N::BuiltinTypeName_::all_names()
             .iter()
             .map(|s| (*s, RT::BuiltinType))
@@ -114,9 +195,13 @@ impl<'env> Context<'env> {
             current_module: None,
             scoped_types,
             scoped_functions,
+            positional_structs,
             scoped_constants,
+            deprecated,
             unscoped_types,
             unscoped_constants: BTreeMap::new(),
+            fun_params: BTreeSet::new(),
+            loop_labels: vec![],
         }
     }
 
@@ -161,7 +246,9 @@ impl<'env> Context<'env> {
                 None
             }
             Some((decl_loc, _, abilities, arity)) => {
-                Some((*decl_loc, StructName(*n), abilities.clone(), *arity))
+                let resolved = (*decl_loc, StructName(*n), abilities.clone(), *arity);
+                self.check_deprecated(loc, m, n);
+                Some(resolved)
             }
         }
     }
@@ -192,7 +279,10 @@ impl<'env> Context<'env> {
                     .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
                 None
             }
-            Some(_) => Some(FunctionName(*n)),
+            Some(_) => {
+                self.check_deprecated(loc, m, n);
+                Some(FunctionName(*n))
+            }
         }
     }
 
@@ -222,10 +312,26 @@ impl<'env> Context<'env> {
                     .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
                 None
             }
-            Some(_) => Some(ConstantName(n)),
+            Some(_) => {
+                self.check_deprecated(loc, m, &n);
+                Some(ConstantName(n))
+            }
         }
     }
 
+    // Warns about the use at `loc` of the member `n` of `m` if it is deprecated, unless it is used
+    // within its own module
+    fn check_deprecated(&mut self, loc: Loc, m: &ModuleIdent, n: &Name) {
+        if self.current_module.as_ref() == Some(m) {
+            return;
+        }
+        let deprecation = match self.deprecated.get(&(*m, n.value)) {
+            None => return,
+            Some(deprecation) => deprecation,
+        };
+        deprecation.report_use(self.env, loc, m, n)
+    }
+
     fn resolve_unscoped_type(&mut self, n: &Name) -> Option<ResolvedType> {
         match self.unscoped_types.get(&n.value) {
             None => {
@@ -279,6 +385,46 @@ impl<'env> Context<'env> {
         }
     }
 
+    /// Returns true if `m::n` is a struct that can be constructed with a call-like
+    /// `n(e1, ..., en)`, i.e. there is no function of that name
+    fn is_struct_constructor(&self, m: &ModuleIdent, n: &Name) -> bool {
+        let is_struct =
+            matches!(self.scoped_types.get(m), Some(types) if types.contains_key(&n.value));
+        let is_function =
+            matches!(self.scoped_functions.get(m), Some(funs) if funs.contains_key(&n.value));
+        is_struct && !is_function
+    }
+
+    /// Checks that the struct is constructed or deconstructed with the same kind of fields, named
+    /// or positional, that it was declared with
+    fn check_positional_usage(
+        &mut self,
+        loc: Loc,
+        verb: &str,
+        m: &ModuleIdent,
+        sn: &StructName,
+        positional: bool,
+    ) -> bool {
+        let declared_positional = self.positional_structs.contains(&(*m, sn.value()));
+        if positional == declared_positional {
+            return true;
+        }
+        let msg = if declared_positional {
+            format!(
+                "Invalid {}. '{}::{}' is a positional struct, expected '{}(...)'",
+                verb, m, sn, sn
+            )
+        } else {
+            format!(
+                "Invalid {}. '{}::{}' has named fields, expected '{} {{ ... }}'",
+                verb, m, sn, sn
+            )
+        };
+        self.env
+            .add_diag(diag!(NameResolution::PositionalMismatch, (loc, msg)));
+        false
+    }
+
     fn resolve_constant(
         &mut self,
         sp!(loc, ma_): E::ModuleAccess,
@@ -324,6 +470,106 @@ impl<'env> Context<'env> {
         self.unscoped_types = types;
         self.unscoped_constants = constants;
     }
+
+    // Makes the label of a loop available in its body. A label cannot shadow the label of an
+    // enclosing loop
+    fn enter_loop(&mut self, label: Option<BlockLabel>) {
+        let label = match label {
+            None => return,
+            Some(label) => label,
+        };
+        if let Some(prev) = self
+            .loop_labels
+            .iter()
+            .find(|prev| prev.value() == label.value())
+        {
+            let msg = format!(
+                "Invalid loop label {}. It shadows the label of an enclosing loop",
+                label
+            );
+            self.env.add_diag(diag!(
+                Declarations::ShadowedLabel,
+                (label.loc(), msg),
+                (prev.loc(), "Enclosing loop labeled here"),
+            ))
+        }
+        self.loop_labels.push(label)
+    }
+
+    fn exit_loop(&mut self, label: Option<BlockLabel>) {
+        if label.is_some() {
+            self.loop_labels.pop();
+        }
+    }
+
+    // Checks that the label of a 'break' or 'continue' is the label of an enclosing loop
+    fn resolve_loop_label(&mut self, verb: &str, label: &BlockLabel) -> bool {
+        let resolved = self
+            .loop_labels
+            .iter()
+            .any(|enclosing| enclosing.value() == label.value());
+        if !resolved {
+            let msg = format!(
+                "Invalid '{}'. Unbound label {}. It must be the label of an enclosing loop",
+                verb, label
+            );
+            self.env
+                .add_diag(diag!(NameResolution::UnboundLabel, (label.loc(), msg)))
+        }
+        resolved
+    }
+}
+
+const DEPRECATED_ATTR: E::AttributeName_ = E::AttributeName_::Known(KnownAttribute::Deprecation(
+    DeprecationAttribute::Deprecated,
+));
+
+// Returns the deprecation of a module member from its attributes, as either '#[deprecated]' or
+// '#[deprecated(note = b"<note>")]'. Invalid attributes are reported to `env`, if any
+pub(crate) fn deprecation(
+    env: Option<&mut CompilationEnv>,
+    kind: &'static str,
+    attributes: &E::Attributes,
+) -> Option<DeprecatedMember> {
+    let attr = attributes.get_(&DEPRECATED_ATTR)?;
+    let mut note = None;
+    let mut invalid_loc = None;
+    match &attr.value {
+        E::Attribute_::Name(_) => (),
+        E::Attribute_::Parameterized(_, inner) => {
+            for (_, _, sp!(inner_loc, inner_attr)) in inner {
+                match inner_attr {
+                    E::Attribute_::Assigned(n, v)
+                        if n.value.as_str() == DeprecationAttribute::NOTE =>
+                    {
+                        match &v.value {
+                            E::AttributeValue_::Value(sp!(_, E::Value_::Bytearray(bytes))) => {
+                                note = Some(String::from_utf8_lossy(bytes).into_owned())
+                            }
+                            _ => invalid_loc = Some(v.loc),
+                        }
+                    }
+                    _ => invalid_loc = Some(*inner_loc),
+                }
+            }
+        }
+        E::Attribute_::Assigned(_, _) => invalid_loc = Some(attr.loc),
+    }
+    if let (Some(env), Some(loc)) = (env, invalid_loc) {
+        let msg = format!(
+            "Invalid '{}' attribute. Expected no arguments or a byte string note, e.g. \
+             '{}({} = b\"<note>\")'",
+            DeprecationAttribute::DEPRECATED,
+            DeprecationAttribute::DEPRECATED,
+            DeprecationAttribute::NOTE,
+        );
+        env.add_diag(diag!(Attributes::InvalidValue, (loc, msg)))
+    }
+    Some(DeprecatedMember {
+        kind,
+        loc: attr.loc,
+        note,
+    })
 }
 
 //**************************************************************************************************
@@ -366,13 +612,16 @@ fn module(
         dependency_order,
         immediate_neighbors: _,
         used_addresses: _,
+        spec_uses,
         friends: efriends,
         structs: estructs,
         functions: efunctions,
         constants: econstants,
+        use_funs: euse_funs,
         specs: _specs,
     } = mdef;
     let friends = efriends.filter_map(|mident, f| friend(context, mident, f));
+    let use_funs = use_funs(context, euse_funs);
     let unscoped = context.save_unscoped();
     let structs = estructs.map(|name, s| {
         context.restore_unscoped(unscoped.clone());
@@ -392,10 +641,12 @@ fn module(
         attributes,
         is_source_module,
         dependency_order,
+        spec_uses,
         friends,
         structs,
         constants,
         functions,
+        use_funs,
     }
 }
 
@@ -443,6 +694,80 @@ fn script(context: &mut Context, escript: E::Script) -> N::Script {
     }
 }
 
+//**************************************************************************************************
+// Use Funs
+//**************************************************************************************************
+
+fn use_funs(context: &mut Context, euse_funs: Vec<E::UseFun>) -> N::UseFuns {
+    use ResolvedType as RT;
+    use E::ModuleAccess_ as EA;
+    let mut use_funs = N::UseFuns::new();
+    for euse_fun in euse_funs {
+        let E::UseFun {
+            attributes: _,
+            function: sp!(floc, function_),
+            ty: sp!(tloc, ty_),
+            method,
+        } = euse_fun;
+        let target_opt = match function_ {
+            EA::Name(n) => {
+                context.env.add_diag(diag!(
+                    NameResolution::UnboundUnscopedName,
+                    (n.loc, format!("Unbound function '{}' in current scope", n)),
+                ));
+                None
+            }
+            EA::ModuleAccess(m, n) => context
+                .resolve_module_function(floc, &m, &n)
+                .map(|f| (m, f)),
+        };
+        let tn_opt = match ty_ {
+            EA::Name(n) => match context.resolve_unscoped_type(&n) {
+                None => None,
+                Some(RT::BuiltinType) => {
+                    let bn_ = N::BuiltinTypeName_::resolve(&n.value).unwrap();
+                    Some(N::TypeName_::Builtin(sp(tloc, bn_)))
+                }
+                Some(RT::TParam(..)) => {
+                    panic!("ICE type parameters are not in scope for 'use fun'")
+                }
+            },
+            EA::ModuleAccess(m, n) => context
+                .resolve_module_type(tloc, &m, &n)
+                .map(|_| N::TypeName_::ModuleType(m, StructName(n))),
+        };
+        let ((module, function), tn) = match (target_opt, tn_opt) {
+            (Some(target), Some(tn)) => (target, tn),
+            _ => {
+                assert!(context.env.has_errors());
+                continue;
+            }
+        };
+        let use_fun = N::UseFun {
+            loc: method.loc,
+            module,
+            function,
+        };
+        match use_funs.get(&(tn.clone(), method.value)) {
+            Some(prev) => {
+                let msg = format!(
+                    "Duplicate 'use fun' for method '{}' on type '{}'",
+                    method, tn
+                );
+                context.env.add_diag(diag!(
+                    Declarations::DuplicateItem,
+                    (method.loc, msg),
+                    (prev.loc, "Previously declared here"),
+                ));
+            }
+            None => {
+                use_funs.insert((tn, method.value), use_fun);
+            }
+        }
+    }
+    use_funs
+}
+
 //**************************************************************************************************
 // Friends
 //**************************************************************************************************
@@ -490,18 +815,21 @@ fn function(
         loc: _,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
         specs: _,
     } = ef;
-    let signature = function_signature(context, signature);
+    let signature = function_signature(context, inline, signature);
     let acquires = function_acquires(context, acquires);
     let body = function_body(context, body);
+    context.fun_params.clear();
     let f = N::Function {
         attributes,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -510,12 +838,24 @@ fn function(
     f
 }
 
-fn function_signature(context: &mut Context, sig: E::FunctionSignature) -> N::FunctionSignature {
+fn function_signature(
+    context: &mut Context,
+    inline: bool,
+    sig: E::FunctionSignature,
+) -> N::FunctionSignature {
     let type_parameters = fun_type_parameters(context, sig.type_parameters);
     let parameters = sig
         .parameters
         .into_iter()
-        .map(|(v, ty)| (v, type_(context, ty)))
+        .map(|(v, ty)| match ty {
+            sp!(loc, E::Type_::Fun(args, result)) if inline => {
+                context.fun_params.insert(v.value());
+                let args = types(context, args);
+                let result = type_(context, *result);
+                (v, sp(loc, N::Type_::Fun(args, Box::new(result))))
+            }
+            ty => (v, type_(context, ty)),
+        })
         .collect();
     let return_type = type_(context, sig.return_type);
     N::FunctionSignature {
@@ -656,6 +996,9 @@ fn struct_fields(context: &mut Context, efields: E::StructFields) -> N::StructFi
         E::StructFields::Defined(em) => {
             N::StructFields::Defined(em.map(|_f, (idx, t)| (idx, type_(context, t))))
         }
+        E::StructFields::Variants(ev) => N::StructFields::Variants(
+            ev.map(|_v, (vidx, em)| (vidx, em.map(|_f, (idx, t)| (idx, type_(context, t))))),
+        ),
     }
 }
 
@@ -669,6 +1012,7 @@ fn constant(context: &mut Context, _name: ConstantName, econstant: E::Constant)
         loc,
         signature: esignature,
         value: evalue,
+        assertion_message,
     } = econstant;
     let signature = type_(context, esignature);
     let value = exp_(context, evalue);
@@ -677,6 +1021,7 @@ fn constant(context: &mut Context, _name: ConstantName, econstant: E::Constant)
         loc,
         signature,
         value,
+        assertion_message,
     }
 }
 
@@ -794,7 +1139,16 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
                 }
             }
         }
-        ET::Fun(_, _) => panic!("ICE only allowed in spec context"),
+        ET::Fun(_, _) => {
+            context.env.add_diag(diag!(
+                TypeSafety::InvalidFunctionType,
+                (
+                    loc,
+                    "Function types are only allowed as parameters of inline functions"
+                ),
+            ));
+            NT::UnresolvedError
+        }
     };
     sp(loc, ty_)
 }
@@ -906,9 +1260,48 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
         EE::IfElse(eb, et, ef) => {
             NE::IfElse(exp(context, *eb), exp(context, *et), exp(context, *ef))
         }
-        EE::While(eb, el) => NE::While(exp(context, *eb), exp(context, *el)),
-        EE::Loop(el) => NE::Loop(exp(context, *el)),
+        EE::While(label, eb, el) => {
+            let nb = exp(context, *eb);
+            context.enter_loop(label);
+            let nl = exp(context, *el);
+            context.exit_loop(label);
+            NE::While(label, nb, nl)
+        }
+        EE::Loop(label, el) => {
+            context.enter_loop(label);
+            let nl = exp(context, *el);
+            context.exit_loop(label);
+            NE::Loop(label, nl)
+        }
+        EE::Match(esubject, earms) => {
+            let nsubject = exp(context, *esubject);
+            let narms = earms
+                .into_iter()
+                .map(|earm| match_arm(context, earm))
+                .collect::<Option<Vec<_>>>();
+            match narms {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some(narms) => NE::Match(nsubject, narms),
+            }
+        }
         EE::Block(seq) => NE::Block(sequence(context, seq)),
+        EE::Lambda(elvs, e) => {
+            let nlvs_opt = bind_list(context, elvs);
+            // The body of a lambda cannot 'break' or 'continue' the loops surrounding it
+            let loop_labels = std::mem::take(&mut context.loop_labels);
+            let ne = exp(context, *e);
+            context.loop_labels = loop_labels;
+            match nlvs_opt {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some(nlvs) => NE::Lambda(nlvs, ne),
+            }
+        }
 
         EE::Assign(a, e) => {
             let na_opt = assign_list(context, a);
@@ -940,8 +1333,14 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
 
         EE::Return(es) => NE::Return(exp(context, *es)),
         EE::Abort(es) => NE::Abort(exp(context, *es)),
-        EE::Break => NE::Break,
-        EE::Continue => NE::Continue,
+        EE::Break(Some(label)) if !context.resolve_loop_label("break", &label) => {
+            NE::UnresolvedError
+        }
+        EE::Break(label) => NE::Break(label),
+        EE::Continue(Some(label)) if !context.resolve_loop_label("continue", &label) => {
+            NE::UnresolvedError
+        }
+        EE::Continue(label) => NE::Continue(label),
 
         EE::Dereference(e) => NE::Dereference(exp(context, *e)),
         EE::UnaryExp(uop, e) => NE::UnaryExp(uop, exp(context, *e)),
@@ -953,6 +1352,11 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                     assert!(context.env.has_errors());
                     NE::UnresolvedError
                 }
+                Some((m, sn, _))
+                    if !context.check_positional_usage(eloc, "construction", &m, &sn, false) =>
+                {
+                    NE::UnresolvedError
+                }
                 Some((m, sn, tys_opt)) => NE::Pack(
                     m,
                     sn,
@@ -961,6 +1365,21 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                 ),
             }
         }
+        EE::PackVariant(tn, v, etys_opt, efields) => {
+            match context.resolve_struct_name(eloc, "construction", tn, etys_opt) {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some((m, sn, tys_opt)) => NE::PackVariant(
+                    m,
+                    sn,
+                    v,
+                    tys_opt,
+                    efields.map(|_, (idx, e)| (idx, exp_(context, e))),
+                ),
+            }
+        }
         EE::ExpList(es) => {
             assert!(es.len() > 1);
             NE::ExpList(exps(context, es))
@@ -1009,6 +1428,33 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                 }
             }
         }
+        EE::Call(sp!(mloc, E::ModuleAccess_::ModuleAccess(m, n)), false, tys_opt, rhs)
+            if context.is_struct_constructor(&m, &n) =>
+        {
+            let tn = sp(mloc, E::ModuleAccess_::ModuleAccess(m, n));
+            match context.resolve_struct_name(eloc, "construction", tn, tys_opt) {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some((m, sn, _))
+                    if !context.check_positional_usage(eloc, "construction", &m, &sn, true) =>
+                {
+                    NE::UnresolvedError
+                }
+                Some((m, sn, tys_opt)) => {
+                    let nfields = UniqueMap::maybe_from_iter(
+                        call_args(context, rhs)
+                            .value
+                            .into_iter()
+                            .enumerate()
+                            .map(|(idx, e)| (Field::positional(e.loc, idx), (idx, e))),
+                    )
+                    .expect("ICE positional fields are unique");
+                    NE::Pack(m, sn, tys_opt, nfields)
+                }
+            }
+        }
         EE::Call(sp!(mloc, ma_), false, tys_opt, rhs) => {
             use E::ModuleAccess_ as EA;
             let ty_args = tys_opt.map(|tys| types(context, tys));
@@ -1024,6 +1470,19 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                     }
                 }
 
+                EA::Name(n) if context.fun_params.contains(&n.value) => {
+                    if ty_args.is_some() {
+                        context.env.add_diag(diag!(
+                            NameResolution::TooManyTypeArguments,
+                            (
+                                mloc,
+                                "Invalid call of function parameter. Function parameters cannot \
+                                 take type arguments"
+                            ),
+                        ));
+                    }
+                    NE::VarCall(Var(n), nes)
+                }
                 EA::Name(n) => {
                     context.env.add_diag(diag!(
                         NameResolution::UnboundUnscopedName,
@@ -1040,6 +1499,18 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                 },
             }
         }
+        EE::MethodCall(edot, method, tys_opt, rhs) => {
+            let ndot_opt = dotted(context, *edot);
+            let ty_args = tys_opt.map(|tys| types(context, tys));
+            let nes = call_args(context, rhs);
+            match ndot_opt {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some(d) => NE::MethodCall(d, method, ty_args, nes),
+            }
+        }
         EE::Vector(vec_loc, tys_opt, rhs) => {
             let ty_args = tys_opt.map(|tys| types(context, tys));
             let nes = call_args(context, rhs);
@@ -1068,13 +1539,36 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
             NE::UnresolvedError
         }
         // `Name` matches name variants only allowed in specs (we handle the allowed ones above)
-        EE::Index(..) | EE::Lambda(..) | EE::Quant(..) | EE::Name(_, Some(_)) => {
+        EE::Index(..) | EE::Quant(..) | EE::Name(_, Some(_)) => {
             panic!("ICE unexpected specification construct")
         }
     };
     sp(eloc, ne_)
 }
 
+fn match_arm(context: &mut Context, sp!(loc, (epat, e)): E::MatchArm) -> Option<N::MatchArm> {
+    let sp!(ploc, epat_) = epat;
+    let npat_ = match epat_ {
+        E::MatchPattern_::Wildcard => N::MatchPattern_::Wildcard,
+        E::MatchPattern_::Variant(tn, v, etys_opt, efields) => {
+            let (m, sn, tys_opt) = context.resolve_struct_name(ploc, "match", tn, etys_opt)?;
+            let nfields =
+                UniqueMap::maybe_from_opt_iter(efields.into_iter().map(|(k, (idx, inner))| {
+                    Some((k, (idx, lvalue(context, LValueCase::Bind, inner)?)))
+                }))?;
+            N::MatchPattern_::Variant(
+                m,
+                sn,
+                v,
+                tys_opt,
+                nfields.expect("ICE fields were already unique"),
+            )
+        }
+    };
+    let ne = exp_(context, e);
+    Some(sp(loc, (sp(ploc, npat_), ne)))
+}
+
 fn access_constant(context: &mut Context, ma: E::ModuleAccess) -> N::Exp_ {
     match context.resolve_constant(ma) {
         None => {
@@ -1096,6 +1590,10 @@ fn dotted(context: &mut Context, edot: E::ExpDotted) -> Option<N::ExpDotted> {
             }
         }
         E::ExpDotted_::Dot(d, f) => N::ExpDotted_::Dot(Box::new(dotted(context, *d)?), Field(f)),
+        E::ExpDotted_::Index(d, i) => {
+            let nd = dotted(context, *d)?;
+            N::ExpDotted_::Index(Box::new(nd), exp(context, *i))
+        }
     };
     Some(sp(loc, nedot_))
 }
@@ -1125,6 +1623,9 @@ fn lvalue(context: &mut Context, case: LValueCase, sp!(loc, l_): E::LValue) -> O
                 C::Assign => "deconstructing assignment",
             };
             let (m, sn, tys_opt) = context.resolve_struct_name(loc, msg, tn, etys_opt)?;
+            if !context.check_positional_usage(loc, msg, &m, &sn, E::is_positional(&efields)) {
+                return None;
+            }
             let nfields = UniqueMap::maybe_from_opt_iter(
                 efields
                     .into_iter()
@@ -9,11 +9,12 @@ use crate::shared::{
 use move_command_line_common::files::FileHash;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{fmt, hash::Hash};
 
 macro_rules! new_name {
     ($n:ident) => {
-        #[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+        #[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Serialize)]
         pub struct $n(pub Name);
 
         impl TName for $n {
@@ -54,21 +55,21 @@ macro_rules! new_name {
 // Program
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub named_address_maps: NamedAddressMaps,
     pub source_definitions: Vec<PackageDefinition>,
     pub lib_definitions: Vec<PackageDefinition>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PackageDefinition {
     pub package: Option<Symbol>,
     pub named_address_map: NamedAddressMapIndex,
     pub def: Definition,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Definition {
     Module(ModuleDefinition),
@@ -76,7 +77,7 @@ pub enum Definition {
     Script(Script),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AddressDefinition {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
@@ -84,7 +85,7 @@ pub struct AddressDefinition {
     pub modules: Vec<ModuleDefinition>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Script {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
@@ -94,13 +95,19 @@ pub struct Script {
     pub specs: Vec<SpecBlock>,
 }
 
-#[derive(Debug, PartialEq, Clone, Eq)]
+#[derive(Debug, PartialEq, Clone, Eq, Serialize)]
 pub enum Use {
     Module(ModuleIdent, Option<ModuleName>),
     Members(ModuleIdent, Vec<(Name, Option<Name>)>),
+    // use fun function as ty.method
+    Fun {
+        function: NameAccessChain,
+        ty: NameAccessChain,
+        method: Name,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UseDecl {
     pub attributes: Vec<Attributes>,
     pub use_: Use,
@@ -110,14 +117,14 @@ pub struct UseDecl {
 // Attributes
 //**************************************************************************************************
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum AttributeValue_ {
     Value(Value),
     ModuleAccess(NameAccessChain),
 }
 pub type AttributeValue = Spanned<AttributeValue_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Attribute_ {
     Name(Name),
     Assigned(Name, Box<AttributeValue>),
@@ -143,7 +150,7 @@ impl Attribute_ {
 
 new_name!(ModuleName);
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 /// Specifies a name at the beginning of an access chain. Could be
 /// - A module name
 /// - A named address
@@ -154,14 +161,14 @@ pub enum LeadingNameAccess_ {
 }
 pub type LeadingNameAccess = Spanned<LeadingNameAccess_>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct ModuleIdent_ {
     pub address: LeadingNameAccess,
     pub module: ModuleName,
 }
 pub type ModuleIdent = Spanned<ModuleIdent_>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDefinition {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
@@ -171,7 +178,7 @@ pub struct ModuleDefinition {
     pub members: Vec<ModuleMember>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum ModuleMember {
     Function(Function),
     Struct(StructDefinition),
@@ -185,7 +192,7 @@ pub enum ModuleMember {
 // Friends
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FriendDecl {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
@@ -198,17 +205,44 @@ pub struct FriendDecl {
 
 new_name!(Field);
 new_name!(StructName);
+new_name!(VariantName);
+
+impl Field {
+    /// The field at index `idx` of a positional struct. Positional fields are named by their
+    /// index, which cannot be written as a field name in source
+    pub fn positional(loc: Loc, idx: usize) -> Field {
+        Field(sp(loc, format!("{}", idx).into()))
+    }
+
+    pub fn is_positional(&self) -> bool {
+        is_positional_field_name(self.0.value.as_str())
+    }
+}
+
+fn is_positional_field_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The name of a field in the bytecode. The index of a positional field is not a valid
+/// identifier, so positional fields are named `pos0`, `pos1`, ...
+pub fn bytecode_field_name(name: Symbol) -> Symbol {
+    if is_positional_field_name(name.as_str()) {
+        format!("pos{}", name).into()
+    } else {
+        name
+    }
+}
 
 pub type ResourceLoc = Option<Loc>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct StructTypeParameter {
     pub is_phantom: bool,
     pub name: Name,
     pub constraints: Vec<Ability>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct StructDefinition {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
@@ -218,10 +252,14 @@ pub struct StructDefinition {
     pub fields: StructFields,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum StructFields {
     Defined(Vec<(Field, Type)>),
+    // struct S(t1, ..., tn)
+    Positional(Vec<Type>),
     Native(Loc),
+    // enum E { V1 { f1: t1, ... }, V2, ... }
+    Variants(Vec<(VariantName, Vec<(Field, Type)>)>),
 }
 
 //**************************************************************************************************
@@ -232,30 +270,32 @@ new_name!(FunctionName);
 
 pub const NATIVE_MODIFIER: &str = "native";
 pub const ENTRY_MODIFIER: &str = "entry";
+pub const INLINE_MODIFIER: &str = "inline";
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize)]
 pub struct FunctionSignature {
     pub type_parameters: Vec<(Name, Vec<Ability>)>,
     pub parameters: Vec<(Var, Type)>,
     pub return_type: Type,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize)]
 pub enum Visibility {
     Public(Loc),
     Script(Loc),
     Friend(Loc),
+    Package(Loc),
     Internal,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize)]
 pub enum FunctionBody_ {
     Defined(Sequence),
     Native,
 }
 pub type FunctionBody = Spanned<FunctionBody_>;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 // (public?) foo<T1(: copyable?), ..., TN(: copyable?)>(x1: t1, ..., xn: tn): t1 * ... * tn {
 //    body
 //  }
@@ -265,6 +305,7 @@ pub struct Function {
     pub loc: Loc,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: bool,
     pub signature: FunctionSignature,
     pub acquires: Vec<NameAccessChain>,
     pub name: FunctionName,
@@ -277,7 +318,7 @@ pub struct Function {
 
 new_name!(ConstantName);
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Constant {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
@@ -292,7 +333,7 @@ pub struct Constant {
 
 // Specification block:
 //    SpecBlock = "spec" <SpecBlockTarget> "{" SpecBlockMember* "}"
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SpecBlock_ {
     pub attributes: Vec<Attributes>,
     pub target: SpecBlockTarget,
@@ -302,7 +343,7 @@ pub struct SpecBlock_ {
 
 pub type SpecBlock = Spanned<SpecBlock_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum SpecBlockTarget_ {
     Code,
     Module,
@@ -312,13 +353,13 @@ pub enum SpecBlockTarget_ {
 
 pub type SpecBlockTarget = Spanned<SpecBlockTarget_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PragmaProperty_ {
     pub name: Name,
     pub value: Option<PragmaValue>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PragmaValue {
     Literal(Value),
     Ident(NameAccessChain),
@@ -326,7 +367,7 @@ pub enum PragmaValue {
 
 pub type PragmaProperty = Spanned<PragmaProperty_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SpecApplyPattern_ {
     pub visibility: Option<Visibility>,
     pub name_pattern: Vec<SpecApplyFragment>,
@@ -335,7 +376,7 @@ pub struct SpecApplyPattern_ {
 
 pub type SpecApplyPattern = Spanned<SpecApplyPattern_>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum SpecApplyFragment_ {
     Wildcard,
     NamePart(Name),
@@ -343,7 +384,7 @@ pub enum SpecApplyFragment_ {
 
 pub type SpecApplyFragment = Spanned<SpecApplyFragment_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum SpecBlockMember_ {
     Condition {
@@ -391,7 +432,7 @@ pub enum SpecBlockMember_ {
 pub type SpecBlockMember = Spanned<SpecBlockMember_>;
 
 // Specification condition kind.
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub enum SpecConditionKind_ {
     Assert,
     Assume,
@@ -415,7 +456,7 @@ pub type SpecConditionKind = Spanned<SpecConditionKind_>;
 
 // A ModuleAccess references a local or global name or something from a module,
 // either a struct type or a function.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum NameAccessChain_ {
     // <Name>
     One(Name),
@@ -426,7 +467,7 @@ pub enum NameAccessChain_ {
 }
 pub type NameAccessChain = Spanned<NameAccessChain_>;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Serialize)]
 pub enum Ability_ {
     Copy,
     Drop,
@@ -435,7 +476,7 @@ pub enum Ability_ {
 }
 pub type Ability = Spanned<Ability_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Type_ {
     // N
     // N<t1, ... , tn>
@@ -458,23 +499,42 @@ pub type Type = Spanned<Type_>;
 //**************************************************************************************************
 
 new_name!(Var);
+new_name!(BlockLabel);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Bind_ {
     // x
     Var(Var),
     // T { f1: b1, ... fn: bn }
     // T<t1, ... , tn> { f1: b1, ... fn: bn }
     Unpack(Box<NameAccessChain>, Option<Vec<Type>>, Vec<(Field, Bind)>),
+    // T(b1, ... bn)
+    // T<t1, ... , tn>(b1, ... bn)
+    PositionalUnpack(Box<NameAccessChain>, Option<Vec<Type>>, Vec<Bind>),
 }
 pub type Bind = Spanned<Bind_>;
 // b1, ..., bn
 pub type BindList = Spanned<Vec<Bind>>;
 
+// A pattern in a match arm
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum MatchPattern_ {
+    // _
+    Wildcard,
+    // tn::V
+    // tn::V { f1: b1, ... fn: bn }
+    // tn::V<t1, ... , tn> { f1: b1, ... fn: bn }
+    Variant(Box<NameAccessChain>, Option<Vec<Type>>, Vec<(Field, Bind)>),
+}
+pub type MatchPattern = Spanned<MatchPattern_>;
+
+// pattern => e
+pub type MatchArm = Spanned<(MatchPattern, Exp)>;
+
 pub type BindWithRange = Spanned<(Bind, Exp)>;
 pub type BindWithRangeList = Spanned<Vec<BindWithRange>>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Value_ {
     // @<num>
     Address(LeadingNameAccess),
@@ -489,14 +549,14 @@ pub enum Value_ {
 }
 pub type Value = Spanned<Value_>;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize)]
 pub enum UnaryOp_ {
     // !
     Not,
 }
 pub type UnaryOp = Spanned<UnaryOp_>;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize)]
 pub enum BinOp_ {
     // Int ops
     // +
@@ -548,7 +608,7 @@ pub enum BinOp_ {
 }
 pub type BinOp = Spanned<BinOp_>;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize)]
 pub enum QuantKind_ {
     Forall,
     Exists,
@@ -557,7 +617,7 @@ pub enum QuantKind_ {
 }
 pub type QuantKind = Spanned<QuantKind_>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Exp_ {
     Value(Value),
@@ -585,15 +645,19 @@ pub enum Exp_ {
 
     // if (eb) et else ef
     IfElse(Box<Exp>, Box<Exp>, Option<Box<Exp>>),
-    // while (eb) eloop
-    While(Box<Exp>, Box<Exp>),
-    // loop eloop
-    Loop(Box<Exp>),
+    // 'l: while (eb) eloop
+    While(Option<BlockLabel>, Box<Exp>, Box<Exp>),
+    // 'l: for (x in eiter) eloop
+    For(Option<BlockLabel>, Var, Box<Exp>, Box<Exp>),
+    // 'l: loop eloop
+    Loop(Option<BlockLabel>, Box<Exp>),
+    // match (e) { arm1, ..., armn }
+    Match(Box<Exp>, Vec<MatchArm>),
 
     // { seq }
     Block(Sequence),
-    // fun (x1, ..., xn) e
-    Lambda(BindList, Box<Exp>), // spec only
+    // |x1, ..., xn| e
+    Lambda(BindList, Box<Exp>),
     // forall/exists x1 : e1, ..., xn [{ t1, .., tk } *] [where cond]: en.
     Quant(
         QuantKind,
@@ -609,15 +673,17 @@ pub enum Exp_ {
 
     // a = e
     Assign(Box<Exp>, Box<Exp>),
+    // a op= e
+    AssignOp(Box<Exp>, BinOp, Box<Exp>),
 
     // return e
     Return(Option<Box<Exp>>),
     // abort e
     Abort(Box<Exp>),
-    // break
-    Break,
-    // continue
-    Continue,
+    // break 'l
+    Break(Option<BlockLabel>),
+    // continue 'l
+    Continue(Option<BlockLabel>),
 
     // *e
     Dereference(Box<Exp>),
@@ -632,8 +698,10 @@ pub enum Exp_ {
 
     // e.f
     Dot(Box<Exp>, Name),
+    // e.f<t1, ... tn>(earg,*)
+    DotCall(Box<Exp>, Name, Option<Vec<Type>>, Spanned<Vec<Exp>>),
     // e[e']
-    Index(Box<Exp>, Box<Exp>), // spec only
+    Index(Box<Exp>, Box<Exp>),
 
     // (e as t)
     Cast(Box<Exp>, Type),
@@ -658,7 +726,7 @@ pub type Sequence = (
     Option<Loc>,
     Box<Option<Exp>>,
 );
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum SequenceItem_ {
     // e;
@@ -885,13 +953,15 @@ impl Visibility {
     pub const PUBLIC: &'static str = "public";
     pub const SCRIPT: &'static str = "public(script)";
     pub const FRIEND: &'static str = "public(friend)";
+    pub const PACKAGE: &'static str = "public(package)";
     pub const INTERNAL: &'static str = "";
 
     pub fn loc(&self) -> Option<Loc> {
         match self {
-            Visibility::Public(loc) | Visibility::Script(loc) | Visibility::Friend(loc) => {
-                Some(*loc)
-            }
+            Visibility::Public(loc)
+            | Visibility::Script(loc)
+            | Visibility::Friend(loc)
+            | Visibility::Package(loc) => Some(*loc),
             Visibility::Internal => None,
         }
     }
@@ -947,6 +1017,7 @@ impl fmt::Display for Visibility {
                 Visibility::Public(_) => Visibility::PUBLIC,
                 Visibility::Script(_) => Visibility::SCRIPT,
                 Visibility::Friend(_) => Visibility::FRIEND,
+                Visibility::Package(_) => Visibility::PACKAGE,
                 Visibility::Internal => Visibility::INTERNAL,
             }
         )
@@ -1194,6 +1265,11 @@ impl AstDebug for Use {
                     })
                 })
             }
+            Use::Fun {
+                function,
+                ty,
+                method,
+            } => w.write(&format!("use fun {} as {}.{}", function, ty, method)),
         }
         w.write(";")
     }
@@ -1232,15 +1308,37 @@ impl AstDebug for StructDefinition {
             w.write("native ");
         }
 
-        w.write(&format!("struct {}", name));
+        match fields {
+            StructFields::Variants(_) => w.write(&format!("enum {}", name)),
+            _ => w.write(&format!("struct {}", name)),
+        }
         type_parameters.ast_debug(w);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Defined(fields) => w.block(|w| {
                 w.semicolon(fields, |w, (f, st)| {
                     w.write(&format!("{}: ", f));
                     st.ast_debug(w);
                 });
-            })
+            }),
+            StructFields::Positional(tys) => {
+                w.write("(");
+                w.comma(tys, |w, st| st.ast_debug(w));
+                w.write(")");
+            }
+            StructFields::Variants(variants) => w.block(|w| {
+                w.comma(variants, |w, (v, fields)| {
+                    w.write(&format!("{}", v));
+                    if !fields.is_empty() {
+                        w.write(" { ");
+                        w.comma(fields, |w, (f, st)| {
+                            w.write(&format!("{}: ", f));
+                            st.ast_debug(w);
+                        });
+                        w.write(" }");
+                    }
+                });
+            }),
+            StructFields::Native(_) => (),
         }
     }
 }
@@ -1462,6 +1560,7 @@ impl AstDebug for Function {
             loc: _loc,
             visibility,
             entry,
+            inline,
             signature,
             acquires,
             name,
@@ -1472,6 +1571,9 @@ impl AstDebug for Function {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if *inline {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -1751,16 +1853,37 @@ impl AstDebug for Exp_ {
                     f.ast_debug(w);
                 }
             }
-            E::While(b, e) => {
+            E::While(label, b, e) => {
+                label.ast_debug(w);
                 w.write("while (");
                 b.ast_debug(w);
                 w.write(")");
                 e.ast_debug(w);
             }
-            E::Loop(e) => {
+            E::For(label, v, i, e) => {
+                label.ast_debug(w);
+                w.write(&format!("for ({} in ", v));
+                i.ast_debug(w);
+                w.write(")");
+                e.ast_debug(w);
+            }
+            E::Loop(label, e) => {
+                label.ast_debug(w);
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, rhs))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        rhs.ast_debug(w);
+                    })
+                });
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
             E::Lambda(sp!(_, bs), e) => {
                 w.write("fun ");
@@ -1790,6 +1913,11 @@ impl AstDebug for Exp_ {
                 w.write(" = ");
                 rhs.ast_debug(w);
             }
+            E::AssignOp(lvalue, op, rhs) => {
+                lvalue.ast_debug(w);
+                w.write(&format!(" {}= ", op));
+                rhs.ast_debug(w);
+            }
             E::Return(e) => {
                 w.write("return");
                 if let Some(v) = e {
@@ -1801,8 +1929,18 @@ impl AstDebug for Exp_ {
                 w.write("abort ");
                 e.ast_debug(w);
             }
-            E::Break => w.write("break"),
-            E::Continue => w.write("continue"),
+            E::Break(label) => {
+                w.write("break");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
+            E::Continue(label) => {
+                w.write("continue");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
             E::Dereference(e) => {
                 w.write("*");
                 e.ast_debug(w)
@@ -1830,6 +1968,18 @@ impl AstDebug for Exp_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", n));
             }
+            E::DotCall(e, n, tys_opt, sp!(_, rhs)) => {
+                e.ast_debug(w);
+                w.write(&format!(".{}", n));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Cast(e, ty) => {
                 w.write("(");
                 e.ast_debug(w);
@@ -1860,6 +2010,14 @@ impl AstDebug for Exp_ {
     }
 }
 
+impl AstDebug for Option<BlockLabel> {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        if let Some(label) = self {
+            w.write(&format!("{}: ", label))
+        }
+    }
+}
+
 impl AstDebug for BinOp_ {
     fn ast_debug(&self, w: &mut AstWriter) {
         w.write(&format!("{}", self));
@@ -1940,6 +2098,28 @@ impl AstDebug for Vec<Vec<Exp>> {
     }
 }
 
+impl AstDebug for MatchPattern_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            MatchPattern_::Wildcard => w.write("_"),
+            MatchPattern_::Variant(ma, tys_opt, fields) => {
+                ma.ast_debug(w);
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (f, b)| {
+                    w.write(&format!("{}: ", f));
+                    b.ast_debug(w);
+                });
+                w.write("}");
+            }
+        }
+    }
+}
+
 impl AstDebug for Bind_ {
     fn ast_debug(&self, w: &mut AstWriter) {
         use Bind_ as B;
@@ -1959,6 +2139,17 @@ impl AstDebug for Bind_ {
                 });
                 w.write("}");
             }
+            B::PositionalUnpack(ma, tys_opt, binds) => {
+                ma.ast_debug(w);
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(binds, |w, b| b.ast_debug(w));
+                w.write(")");
+            }
         }
     }
 }
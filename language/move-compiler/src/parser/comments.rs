@@ -35,3 +35,56 @@ pub fn verify_string(file_hash: FileHash, string: &str) -> Result<(), Diagnostic
         }
     }
 }
+
+/// The two forms a comment can take in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    /// A `// ...` comment, including `///` documentation comments. Its span does not include
+    /// the terminating newline.
+    Line,
+    /// A `/* ... */` comment, which can be nested, including `/** ... */` documentation comments.
+    Block,
+}
+
+/// Returns the spans (relative to `text`) and kinds of the comments in `text`, which is expected
+/// to be the whitespace and comments the lexer skips between two tokens. Scanning stops at the
+/// first character that is neither, or at an unterminated block comment.
+pub fn find_comments(text: &str) -> Vec<(usize, usize, CommentKind)> {
+    let bytes = text.as_bytes();
+    let mut comments = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+        } else if bytes[i..].starts_with(b"//") {
+            let end = text[i..].find('\n').map_or(text.len(), |len| i + len);
+            let end = i + text[i..end].trim_end_matches('\r').len();
+            comments.push((i, end, CommentKind::Line));
+            i = end;
+        } else if bytes[i..].starts_with(b"/*") {
+            let start = i;
+            let mut depth = 0;
+            while i < bytes.len() {
+                if bytes[i..].starts_with(b"/*") {
+                    depth += 1;
+                    i += 2;
+                } else if bytes[i..].starts_with(b"*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            if depth != 0 {
+                break;
+            }
+            comments.push((start, i, CommentKind::Block));
+        } else {
+            break;
+        }
+    }
+    comments
+}
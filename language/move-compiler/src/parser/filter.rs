@@ -2,7 +2,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use move_ir_types::location::sp;
+use move_ir_types::location::{sp, Loc};
+use move_symbol_pool::Symbol;
+use std::collections::BTreeSet;
 
 use crate::parser::ast as P;
 
@@ -17,6 +19,11 @@ pub trait FilterContext {
         false
     }
 
+    /// Called when a module compiled from source, or some of its members, are filtered out, with
+    /// the location of the module name and the names of the module members the filtered code
+    /// refers to
+    fn filtered_source_uses(&mut self, _module_name_loc: Loc, _names: BTreeSet<Symbol>) {}
+
     fn filter_map_address(
         &mut self,
         address_def: P::AddressDefinition,
@@ -268,8 +275,15 @@ fn filter_module<T: FilterContext>(
     module_def: P::ModuleDefinition,
     is_source_def: bool,
 ) -> Option<P::ModuleDefinition> {
-    let module_def = context.filter_map_module(module_def, is_source_def)?;
-
+    let module_name_loc = module_def.name.0.loc;
+    if is_source_def && context.should_remove_by_attributes(&module_def.attributes, is_source_def) {
+        let mut names = BTreeSet::new();
+        module_def
+            .members
+            .iter()
+            .for_each(|member| uses::member(&mut names, member));
+        context.filtered_source_uses(module_name_loc, names)
+    }
     let P::ModuleDefinition {
         attributes,
         loc,
@@ -277,11 +291,11 @@ fn filter_module<T: FilterContext>(
         name,
         is_spec_module,
         members,
-    } = module_def;
+    } = context.filter_map_module(module_def, is_source_def)?;
 
     let new_members: Vec<_> = members
         .into_iter()
-        .filter_map(|member| filter_module_member(context, member, is_source_def))
+        .filter_map(|member| filter_module_member(context, member, is_source_def, module_name_loc))
         .collect();
 
     Some(P::ModuleDefinition {
@@ -298,9 +312,17 @@ fn filter_module_member<T: FilterContext>(
     context: &mut T,
     module_member: P::ModuleMember,
     is_source_def: bool,
+    module_name_loc: Loc,
 ) -> Option<P::ModuleMember> {
     use P::ModuleMember as PM;
 
+    if is_source_def
+        && context.should_remove_by_attributes(member_attributes(&module_member), is_source_def)
+    {
+        let mut names = BTreeSet::new();
+        uses::member(&mut names, &module_member);
+        context.filtered_source_uses(module_name_loc, names)
+    }
     match module_member {
         PM::Function(func_def) => context
             .filter_map_function(func_def, is_source_def)
@@ -320,3 +342,348 @@ fn filter_module_member<T: FilterContext>(
             .map(PM::Constant),
     }
 }
+
+fn member_attributes(module_member: &P::ModuleMember) -> &[P::Attributes] {
+    use P::ModuleMember as PM;
+
+    match module_member {
+        PM::Function(func_def) => &func_def.attributes,
+        PM::Struct(struct_def) => &struct_def.attributes,
+        PM::Spec(spec) => &spec.value.attributes,
+        PM::Use(use_decl) => &use_decl.attributes,
+        PM::Friend(friend_decl) => &friend_decl.attributes,
+        PM::Constant(constant) => &constant.attributes,
+    }
+}
+
+//**************************************************************************************************
+// Uses in filtered code
+//**************************************************************************************************
+
+// Collects the names of the module members filtered code may refer to, i.e. the last name of
+// every name access chain, the members imported by 'use' declarations and the methods called.
+// Names are not resolved, so a local that shadows a member counts as a use of the member.
+mod uses {
+    use super::P;
+    use move_symbol_pool::Symbol;
+    use std::collections::BTreeSet;
+
+    type Names = BTreeSet<Symbol>;
+
+    pub(super) fn member(names: &mut Names, module_member: &P::ModuleMember) {
+        use P::ModuleMember as PM;
+
+        match module_member {
+            PM::Function(f) => function(names, f),
+            PM::Struct(s) => struct_def(names, s),
+            PM::Spec(spec) => spec_block(names, &spec.value),
+            PM::Use(u) => use_decl(names, u),
+            PM::Friend(f) => attributes(names, &f.attributes),
+            PM::Constant(c) => {
+                attributes(names, &c.attributes);
+                type_(names, &c.signature);
+                exp(names, &c.value)
+            }
+        }
+    }
+
+    fn name_access_chain(names: &mut Names, sp!(_, chain_): &P::NameAccessChain) {
+        let n = match chain_ {
+            P::NameAccessChain_::One(n)
+            | P::NameAccessChain_::Two(_, n)
+            | P::NameAccessChain_::Three(_, n) => n,
+        };
+        names.insert(n.value);
+    }
+
+    fn attributes(names: &mut Names, attributes: &[P::Attributes]) {
+        attributes
+            .iter()
+            .for_each(|attrs| attribute_list(names, &attrs.value))
+    }
+
+    fn attribute_list(names: &mut Names, attrs: &[P::Attribute]) {
+        for sp!(_, attr) in attrs {
+            match attr {
+                P::Attribute_::Name(_) => (),
+                P::Attribute_::Assigned(_, value) => {
+                    if let P::AttributeValue_::ModuleAccess(chain) = &value.value {
+                        name_access_chain(names, chain)
+                    }
+                }
+                P::Attribute_::Parameterized(_, inner) => attribute_list(names, &inner.value),
+            }
+        }
+    }
+
+    fn use_decl(names: &mut Names, use_decl: &P::UseDecl) {
+        attributes(names, &use_decl.attributes);
+        match &use_decl.use_ {
+            P::Use::Module(_, _) => (),
+            P::Use::Members(_, members) => names.extend(members.iter().map(|(n, _)| n.value)),
+            P::Use::Fun { function, ty, .. } => {
+                name_access_chain(names, function);
+                name_access_chain(names, ty)
+            }
+        }
+    }
+
+    fn struct_def(names: &mut Names, sdef: &P::StructDefinition) {
+        attributes(names, &sdef.attributes);
+        match &sdef.fields {
+            P::StructFields::Defined(fields) => fields.iter().for_each(|(_, t)| type_(names, t)),
+            P::StructFields::Positional(tys) => types(names, tys),
+            P::StructFields::Native(_) => (),
+            P::StructFields::Variants(variants) => variants
+                .iter()
+                .flat_map(|(_, fields)| fields)
+                .for_each(|(_, t)| type_(names, t)),
+        }
+    }
+
+    fn function(names: &mut Names, fdef: &P::Function) {
+        attributes(names, &fdef.attributes);
+        signature(names, &fdef.signature);
+        fdef.acquires
+            .iter()
+            .for_each(|chain| name_access_chain(names, chain));
+        function_body(names, &fdef.body)
+    }
+
+    fn signature(names: &mut Names, signature: &P::FunctionSignature) {
+        signature
+            .parameters
+            .iter()
+            .for_each(|(_, t)| type_(names, t));
+        type_(names, &signature.return_type)
+    }
+
+    fn function_body(names: &mut Names, body: &P::FunctionBody) {
+        match &body.value {
+            P::FunctionBody_::Defined(seq) => sequence(names, seq),
+            P::FunctionBody_::Native => (),
+        }
+    }
+
+    fn spec_block(names: &mut Names, spec: &P::SpecBlock_) {
+        attributes(names, &spec.attributes);
+        if let P::SpecBlockTarget_::Member(n, signature_opt) = &spec.target.value {
+            names.insert(n.value);
+            if let Some(s) = signature_opt {
+                signature(names, s)
+            }
+        }
+        spec.uses.iter().for_each(|u| use_decl(names, u));
+        for sp!(_, member) in &spec.members {
+            match member {
+                P::SpecBlockMember_::Condition {
+                    properties,
+                    exp: e,
+                    additional_exps,
+                    ..
+                } => {
+                    pragma_properties(names, properties);
+                    exp(names, e);
+                    exps(names, additional_exps)
+                }
+                P::SpecBlockMember_::Function {
+                    signature: s, body, ..
+                } => {
+                    signature(names, s);
+                    function_body(names, body)
+                }
+                P::SpecBlockMember_::Variable { type_: t, init, .. } => {
+                    type_(names, t);
+                    init.iter().for_each(|e| exp(names, e))
+                }
+                P::SpecBlockMember_::Let { def, .. } => exp(names, def),
+                P::SpecBlockMember_::Update { lhs, rhs } => {
+                    exp(names, lhs);
+                    exp(names, rhs)
+                }
+                P::SpecBlockMember_::Include { properties, exp: e } => {
+                    pragma_properties(names, properties);
+                    exp(names, e)
+                }
+                P::SpecBlockMember_::Apply { exp: e, .. } => exp(names, e),
+                P::SpecBlockMember_::Pragma { properties } => pragma_properties(names, properties),
+            }
+        }
+    }
+
+    fn pragma_properties(names: &mut Names, properties: &[P::PragmaProperty]) {
+        for sp!(_, property) in properties {
+            if let Some(P::PragmaValue::Ident(chain)) = &property.value {
+                name_access_chain(names, chain)
+            }
+        }
+    }
+
+    fn type_(names: &mut Names, sp!(_, t_): &P::Type) {
+        match t_ {
+            P::Type_::Apply(chain, tys) => {
+                name_access_chain(names, chain);
+                types(names, tys)
+            }
+            P::Type_::Ref(_, t) => type_(names, t),
+            P::Type_::Fun(tys, t) => {
+                types(names, tys);
+                type_(names, t)
+            }
+            P::Type_::Unit => (),
+            P::Type_::Multiple(tys) => types(names, tys),
+        }
+    }
+
+    fn types(names: &mut Names, tys: &[P::Type]) {
+        tys.iter().for_each(|t| type_(names, t))
+    }
+
+    fn type_args(names: &mut Names, tys_opt: &Option<Vec<P::Type>>) {
+        if let Some(tys) = tys_opt {
+            types(names, tys)
+        }
+    }
+
+    fn sequence(names: &mut Names, (uses, items, _, e_opt): &P::Sequence) {
+        uses.iter().for_each(|u| use_decl(names, u));
+        for sp!(_, item) in items {
+            match item {
+                P::SequenceItem_::Seq(e) => exp(names, e),
+                P::SequenceItem_::Declare(binds, t_opt) => {
+                    bind_list(names, binds);
+                    t_opt.iter().for_each(|t| type_(names, t))
+                }
+                P::SequenceItem_::Bind(binds, t_opt, e) => {
+                    bind_list(names, binds);
+                    t_opt.iter().for_each(|t| type_(names, t));
+                    exp(names, e)
+                }
+            }
+        }
+        if let Some(e) = &**e_opt {
+            exp(names, e)
+        }
+    }
+
+    fn bind_list(names: &mut Names, binds: &P::BindList) {
+        binds.value.iter().for_each(|b| bind(names, b))
+    }
+
+    fn bind(names: &mut Names, sp!(_, b_): &P::Bind) {
+        match b_ {
+            P::Bind_::Var(_) => (),
+            P::Bind_::Unpack(chain, tys_opt, fields) => {
+                name_access_chain(names, chain);
+                type_args(names, tys_opt);
+                fields.iter().for_each(|(_, b)| bind(names, b))
+            }
+            P::Bind_::PositionalUnpack(chain, tys_opt, binds) => {
+                name_access_chain(names, chain);
+                type_args(names, tys_opt);
+                binds.iter().for_each(|b| bind(names, b))
+            }
+        }
+    }
+
+    fn exps(names: &mut Names, es: &[P::Exp]) {
+        es.iter().for_each(|e| exp(names, e))
+    }
+
+    fn exp(names: &mut Names, sp!(_, e_): &P::Exp) {
+        use P::Exp_ as E;
+
+        match e_ {
+            E::Value(_)
+            | E::Move(_)
+            | E::Copy(_)
+            | E::Unit
+            | E::Break(_)
+            | E::Continue(_)
+            | E::UnresolvedError => (),
+            E::Name(chain, tys_opt) => {
+                name_access_chain(names, chain);
+                type_args(names, tys_opt)
+            }
+            E::Call(chain, _, tys_opt, args) => {
+                name_access_chain(names, chain);
+                type_args(names, tys_opt);
+                exps(names, &args.value)
+            }
+            E::Pack(chain, tys_opt, fields) => {
+                name_access_chain(names, chain);
+                type_args(names, tys_opt);
+                fields.iter().for_each(|(_, e)| exp(names, e))
+            }
+            E::Vector(_, tys_opt, args) => {
+                type_args(names, tys_opt);
+                exps(names, &args.value)
+            }
+            E::IfElse(econd, etrue, efalse_opt) => {
+                exp(names, econd);
+                exp(names, etrue);
+                efalse_opt.iter().for_each(|e| exp(names, e))
+            }
+            E::While(_, econd, ebody) => {
+                exp(names, econd);
+                exp(names, ebody)
+            }
+            E::For(_, _, eiter, ebody) => {
+                exp(names, eiter);
+                exp(names, ebody)
+            }
+            E::Loop(_, ebody) => exp(names, ebody),
+            E::Match(esubject, arms) => {
+                exp(names, esubject);
+                for sp!(_, (sp!(_, pattern), e)) in arms {
+                    if let P::MatchPattern_::Variant(chain, tys_opt, fields) = pattern {
+                        name_access_chain(names, chain);
+                        type_args(names, tys_opt);
+                        fields.iter().for_each(|(_, b)| bind(names, b))
+                    }
+                    exp(names, e)
+                }
+            }
+            E::Block(seq) => sequence(names, seq),
+            E::Lambda(binds, e) => {
+                bind_list(names, binds);
+                exp(names, e)
+            }
+            E::Quant(_, ranges, triggers, cond_opt, e) => {
+                for sp!(_, (b, range)) in &ranges.value {
+                    bind(names, b);
+                    exp(names, range)
+                }
+                triggers.iter().for_each(|es| exps(names, es));
+                cond_opt.iter().for_each(|cond| exp(names, cond));
+                exp(names, e)
+            }
+            E::ExpList(es) => exps(names, es),
+            E::Assign(lhs, rhs) | E::AssignOp(lhs, _, rhs) | E::BinopExp(lhs, _, rhs) => {
+                exp(names, lhs);
+                exp(names, rhs)
+            }
+            E::Index(e, idx) => {
+                exp(names, e);
+                exp(names, idx)
+            }
+            E::Return(e_opt) => e_opt.iter().for_each(|e| exp(names, e)),
+            E::Abort(e)
+            | E::Dereference(e)
+            | E::UnaryExp(_, e)
+            | E::Borrow(_, e)
+            | E::Dot(e, _) => exp(names, e),
+            E::DotCall(e, method, tys_opt, args) => {
+                exp(names, e);
+                names.insert(method.value);
+                type_args(names, tys_opt);
+                exps(names, &args.value)
+            }
+            E::Cast(e, t) | E::Annotate(e, t) => {
+                exp(names, e);
+                type_(names, t)
+            }
+            E::Spec(spec) => spec_block(names, &spec.value),
+        }
+    }
+}
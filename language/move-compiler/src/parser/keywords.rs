@@ -12,6 +12,7 @@ pub const KEYWORDS: &[&str] = &[
     "copy",
     "else",
     "false",
+    "for",
     "friend",
     "fun",
     "has",
@@ -47,6 +48,7 @@ pub const CONTEXTUAL_KEYWORDS: &[&str] = &[
     "forall",
     "global",
     "include",
+    "inline",
     "internal",
     "local",
     "min",
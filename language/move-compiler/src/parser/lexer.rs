@@ -43,6 +43,7 @@ pub enum Tok {
     Equal,
     EqualEqual,
     EqualEqualGreater,
+    EqualGreater,
     LessEqualEqualGreater,
     Greater,
     GreaterEqual,
@@ -80,6 +81,20 @@ pub enum Tok {
     Friend,
     NumSign,
     AtSign,
+    Enum,
+    Match,
+    For,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    AmpEqual,
+    PipeEqual,
+    CaretEqual,
+    LessLessEqual,
+    GreaterGreaterEqual,
+    BlockLabel,
 }
 
 impl fmt::Display for Tok {
@@ -117,6 +132,7 @@ impl fmt::Display for Tok {
             Equal => "=",
             EqualEqual => "==",
             EqualEqualGreater => "==>",
+            EqualGreater => "=>",
             LessEqualEqualGreater => "<==>",
             Greater => ">",
             GreaterEqual => ">=",
@@ -154,6 +170,20 @@ impl fmt::Display for Tok {
             Friend => "friend",
             NumSign => "#",
             AtSign => "@",
+            Enum => "enum",
+            Match => "match",
+            For => "for",
+            PlusEqual => "+=",
+            MinusEqual => "-=",
+            StarEqual => "*=",
+            SlashEqual => "/=",
+            PercentEqual => "%=",
+            AmpEqual => "&=",
+            PipeEqual => "|=",
+            CaretEqual => "^=",
+            LessLessEqual => "<<=",
+            GreaterGreaterEqual => ">>=",
+            BlockLabel => "[BlockLabel]",
         };
         fmt::Display::fmt(s, formatter)
     }
@@ -168,6 +198,7 @@ pub struct Lexer<'input> {
     cur_start: usize,
     cur_end: usize,
     token: Tok,
+    brace_depth: usize,
 }
 
 impl<'input> Lexer<'input> {
@@ -181,6 +212,7 @@ impl<'input> Lexer<'input> {
             cur_start: 0,
             cur_end: 0,
             token: Tok::EOF,
+            brace_depth: 0,
         }
     }
 
@@ -350,7 +382,13 @@ impl<'input> Lexer<'input> {
     // comments. The documentation comments are not stored in the AST, but can be retrieved by
     // using the start position of an item as an index into `matched_doc_comments`.
     pub fn match_doc_comments(&mut self) {
-        let start = self.previous_end_loc() as u32;
+        self.match_doc_comments_since(self.previous_end_loc())
+    }
+
+    // Matches the doc comments from `start` to the position of the current token, e.g. the doc
+    // comments written before the attributes of an item
+    pub fn match_doc_comments_since(&mut self, start: usize) {
+        let start = start as u32;
         let end = self.cur_start as u32;
         let mut matched = vec![];
         let merged = self
@@ -388,11 +426,22 @@ impl<'input> Lexer<'input> {
         std::mem::take(&mut self.matched_doc_comments)
     }
 
+    // The number of '{' tokens advanced past that have not yet been closed by a '}'. Used to find
+    // the end of the enclosing block when recovering from a syntax error.
+    pub fn brace_depth(&self) -> usize {
+        self.brace_depth
+    }
+
     pub fn advance(&mut self) -> Result<(), Box<Diagnostic>> {
         self.prev_end = self.cur_end;
         let text = self.trim_whitespace_and_comments(self.cur_end)?;
         self.cur_start = self.text.len() - text.len();
         let (token, len) = find_token(self.file_hash, text, self.cur_start)?;
+        match self.token {
+            Tok::LBrace => self.brace_depth += 1,
+            Tok::RBrace => self.brace_depth = self.brace_depth.saturating_sub(1),
+            _ => (),
+        }
         self.cur_end = self.cur_start + len;
         self.token = token;
         Ok(())
@@ -461,6 +510,8 @@ fn find_token(
                 (Tok::AmpMut, 5)
             } else if text.starts_with("&&") {
                 (Tok::AmpAmp, 2)
+            } else if text.starts_with("&=") {
+                (Tok::AmpEqual, 2)
             } else {
                 (Tok::Amp, 1)
             }
@@ -468,6 +519,8 @@ fn find_token(
         '|' => {
             if text.starts_with("||") {
                 (Tok::PipePipe, 2)
+            } else if text.starts_with("|=") {
+                (Tok::PipeEqual, 2)
             } else {
                 (Tok::Pipe, 1)
             }
@@ -477,6 +530,8 @@ fn find_token(
                 (Tok::EqualEqualGreater, 3)
             } else if text.starts_with("==") {
                 (Tok::EqualEqual, 2)
+            } else if text.starts_with("=>") {
+                (Tok::EqualGreater, 2)
             } else {
                 (Tok::Equal, 1)
             }
@@ -493,6 +548,8 @@ fn find_token(
                 (Tok::LessEqualEqualGreater, 4)
             } else if text.starts_with("<=") {
                 (Tok::LessEqual, 2)
+            } else if text.starts_with("<<=") {
+                (Tok::LessLessEqual, 3)
             } else if text.starts_with("<<") {
                 (Tok::LessLess, 2)
             } else {
@@ -502,6 +559,8 @@ fn find_token(
         '>' => {
             if text.starts_with(">=") {
                 (Tok::GreaterEqual, 2)
+            } else if text.starts_with(">>=") {
+                (Tok::GreaterGreaterEqual, 3)
             } else if text.starts_with(">>") {
                 (Tok::GreaterGreater, 2)
             } else {
@@ -515,15 +574,39 @@ fn find_token(
                 (Tok::Colon, 1)
             }
         }
-        '%' => (Tok::Percent, 1),
+        '%' => {
+            if text.starts_with("%=") {
+                (Tok::PercentEqual, 2)
+            } else {
+                (Tok::Percent, 1)
+            }
+        }
         '(' => (Tok::LParen, 1),
         ')' => (Tok::RParen, 1),
         '[' => (Tok::LBracket, 1),
         ']' => (Tok::RBracket, 1),
-        '*' => (Tok::Star, 1),
-        '+' => (Tok::Plus, 1),
+        '*' => {
+            if text.starts_with("*=") {
+                (Tok::StarEqual, 2)
+            } else {
+                (Tok::Star, 1)
+            }
+        }
+        '+' => {
+            if text.starts_with("+=") {
+                (Tok::PlusEqual, 2)
+            } else {
+                (Tok::Plus, 1)
+            }
+        }
         ',' => (Tok::Comma, 1),
-        '-' => (Tok::Minus, 1),
+        '-' => {
+            if text.starts_with("-=") {
+                (Tok::MinusEqual, 2)
+            } else {
+                (Tok::Minus, 1)
+            }
+        }
         '.' => {
             if text.starts_with("..") {
                 (Tok::PeriodPeriod, 2)
@@ -531,13 +614,28 @@ fn find_token(
                 (Tok::Period, 1)
             }
         }
-        '/' => (Tok::Slash, 1),
+        '/' => {
+            if text.starts_with("/=") {
+                (Tok::SlashEqual, 2)
+            } else {
+                (Tok::Slash, 1)
+            }
+        }
         ';' => (Tok::Semicolon, 1),
-        '^' => (Tok::Caret, 1),
+        '^' => {
+            if text.starts_with("^=") {
+                (Tok::CaretEqual, 2)
+            } else {
+                (Tok::Caret, 1)
+            }
+        }
         '{' => (Tok::LBrace, 1),
         '}' => (Tok::RBrace, 1),
         '#' => (Tok::NumSign, 1),
         '@' => (Tok::AtSign, 1),
+        '\'' if matches!(text[1..].chars().next(), Some('a'..='z' | 'A'..='Z' | '_')) => {
+            (Tok::BlockLabel, 1 + get_name_len(&text[1..]))
+        }
         _ => {
             let loc = make_loc(file_hash, start_offset, start_offset);
             return Err(Box::new(diag!(
@@ -620,13 +718,16 @@ fn get_name_token(name: &str) -> Tok {
         "continue" => Tok::Continue,
         "copy" => Tok::Copy,
         "else" => Tok::Else,
+        "enum" => Tok::Enum,
         "false" => Tok::False,
+        "for" => Tok::For,
         "fun" => Tok::Fun,
         "friend" => Tok::Friend,
         "if" => Tok::If,
         "invariant" => Tok::Invariant,
         "let" => Tok::Let,
         "loop" => Tok::Loop,
+        "match" => Tok::Match,
         "module" => Tok::Module,
         "move" => Tok::Move,
         "native" => Tok::Native,
@@ -74,6 +74,107 @@ fn add_type_args_ambiguity_label(loc: Loc, mut diag: Box<Diagnostic>) -> Box<Dia
     diag
 }
 
+//**************************************************************************************************
+// Error Recovery
+//**************************************************************************************************
+
+// After a syntax error, the parser skips ahead to the next item or statement boundary, so that it
+// can report further errors and keep the rest of the file. Skipping fails only if the lexer
+// cannot get past an invalid token, in which case the original error is returned instead.
+
+// Returns true if the current token can start a definition at the top level of a file
+fn at_start_of_definition(context: &mut Context) -> bool {
+    match context.tokens.peek() {
+        Tok::EOF => true,
+        Tok::Module => matches!(
+            context.tokens.lookahead(),
+            Ok(Tok::Identifier | Tok::NumValue)
+        ),
+        Tok::Script => context.tokens.lookahead() == Ok(Tok::LBrace),
+        Tok::Identifier => {
+            context.tokens.content() == "address"
+                && matches!(
+                    context.tokens.lookahead(),
+                    Ok(Tok::Identifier | Tok::NumValue)
+                )
+        }
+        _ => false,
+    }
+}
+
+// Returns true if the current token can start a module member, other than a 'use' or a 'spec',
+// which can also appear within function bodies
+fn at_start_of_module_member(context: &mut Context) -> bool {
+    matches!(
+        context.tokens.peek(),
+        Tok::Fun
+            | Tok::Struct
+            | Tok::Enum
+            | Tok::Const
+            | Tok::Friend
+            | Tok::Public
+            | Tok::Native
+            | Tok::NumSign
+    )
+}
+
+// Makes sure that recovery consumes at least one token, if the item starting at `start_loc` failed
+// to parse before consuming any
+fn skip_past(context: &mut Context, start_loc: usize) -> Result<(), Box<Diagnostic>> {
+    if context.tokens.start_loc() == start_loc && context.tokens.peek() != Tok::EOF {
+        context.tokens.advance()?;
+    }
+    Ok(())
+}
+
+// Skips to the start of the next definition in the file
+fn skip_to_next_definition(context: &mut Context, start_loc: usize) -> Result<(), Box<Diagnostic>> {
+    skip_past(context, start_loc)?;
+    while !at_start_of_definition(context) {
+        context.tokens.advance()?;
+    }
+    Ok(())
+}
+
+// Skips to the start of the next module member or to the end of the module, or to the start of
+// the next definition if the module is not closed
+fn skip_to_next_module_member(
+    context: &mut Context,
+    start_loc: usize,
+    module_depth: usize,
+) -> Result<(), Box<Diagnostic>> {
+    skip_past(context, start_loc)?;
+    while !at_start_of_module_member(context) && !at_start_of_definition(context) {
+        if context.tokens.peek() == Tok::RBrace && context.tokens.brace_depth() <= module_depth {
+            break;
+        }
+        context.tokens.advance()?;
+    }
+    Ok(())
+}
+
+// Skips past the next ';' of the sequence, or to its closing '}'. Stops early at a function or
+// struct declaration, as the sequence is then most likely missing its '}'. Returns false in that
+// case
+fn skip_to_next_sequence_item(
+    context: &mut Context,
+    seq_depth: usize,
+) -> Result<bool, Box<Diagnostic>> {
+    loop {
+        let at_seq_depth = context.tokens.brace_depth() <= seq_depth;
+        match context.tokens.peek() {
+            Tok::EOF => return Ok(false),
+            Tok::Fun | Tok::Struct | Tok::Enum => return Ok(false),
+            Tok::RBrace if at_seq_depth => return Ok(true),
+            Tok::Semicolon if at_seq_depth => {
+                context.tokens.advance()?;
+                return Ok(true);
+            }
+            _ => context.tokens.advance()?,
+        }
+    }
+}
+
 //**************************************************************************************************
 // Miscellaneous Utilities
 //**************************************************************************************************
@@ -170,9 +271,14 @@ fn consume_optional_token_with_loc(
 // While parsing a list and expecting a ">" token to mark the end, replace
 // a ">>" token with the expected ">". This handles the situation where there
 // are nested type parameters that result in two adjacent ">" tokens, e.g.,
-// "A<B<C>>".
+// "A<B<C>>". The same applies to ">>=" and ">=" tokens, which are split when
+// a type is directly followed by an assignment, e.g., "let x: A<B<C>>= e".
 fn adjust_token(tokens: &mut Lexer, end_token: Tok) {
-    if tokens.peek() == Tok::GreaterGreater && end_token == Tok::Greater {
+    if matches!(
+        tokens.peek(),
+        Tok::GreaterGreater | Tok::GreaterGreaterEqual | Tok::GreaterEqual
+    ) && end_token == Tok::Greater
+    {
         tokens.replace_token(Tok::Greater, 1);
     }
 }
@@ -338,6 +444,34 @@ fn parse_var(context: &mut Context) -> Result<Var, Box<Diagnostic>> {
     Ok(Var(parse_identifier(context)?))
 }
 
+// Parse a loop label:
+//      BlockLabel = "'" <Identifier>
+fn parse_block_label(context: &mut Context) -> Result<BlockLabel, Box<Diagnostic>> {
+    if context.tokens.peek() != Tok::BlockLabel {
+        return Err(unexpected_token_error(context.tokens, "a label"));
+    }
+    let start_loc = context.tokens.start_loc();
+    let id = context.tokens.content().into();
+    context.tokens.advance()?;
+    let end_loc = context.tokens.previous_end_loc();
+    Ok(BlockLabel(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        id,
+    )))
+}
+
+// Parse an optional loop label for 'break' or 'continue':
+//      BlockLabelOpt = <BlockLabel>?
+fn parse_block_label_opt(context: &mut Context) -> Result<Option<BlockLabel>, Box<Diagnostic>> {
+    if context.tokens.peek() == Tok::BlockLabel {
+        Ok(Some(parse_block_label(context)?))
+    } else {
+        Ok(None)
+    }
+}
+
 // Parse a field name:
 //      Field = <Identifier>
 fn parse_field(context: &mut Context) -> Result<Field, Box<Diagnostic>> {
@@ -428,6 +562,7 @@ struct Modifiers {
     visibility: Option<Visibility>,
     entry: Option<Loc>,
     native: Option<Loc>,
+    inline: Option<Loc>,
 }
 
 impl Modifiers {
@@ -436,14 +571,15 @@ impl Modifiers {
             visibility: None,
             entry: None,
             native: None,
+            inline: None,
         }
     }
 }
 
-// Parse module member modifiers: visiblility and native.
+// Parse module member modifiers: visiblility, native, entry, and inline.
 // The modifiers are also used for script-functions
 //      ModuleMemberModifiers = <ModuleMemberModifier>*
-//      ModuleMemberModifier = <Visibility> | "native"
+//      ModuleMemberModifier = <Visibility> | "native" | "entry" | "inline"
 // ModuleMemberModifiers checks for uniqueness, meaning each individual ModuleMemberModifier can
 // appear only once
 fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box<Diagnostic>> {
@@ -491,6 +627,20 @@ fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box
                 }
                 mods.entry = Some(loc)
             }
+            Tok::Identifier if context.tokens.content() == INLINE_MODIFIER => {
+                let loc = current_token_loc(context.tokens);
+                context.tokens.advance()?;
+                if let Some(prev_loc) = mods.inline {
+                    let msg = format!("Duplicate '{}' modifier", INLINE_MODIFIER);
+                    let prev_msg = format!("'{}' modifier previously given here", INLINE_MODIFIER);
+                    context.env.add_diag(diag!(
+                        Declarations::DuplicateItem,
+                        (loc, msg),
+                        (prev_loc, prev_msg)
+                    ))
+                }
+                mods.inline = Some(loc)
+            }
             _ => break,
         }
     }
@@ -498,17 +648,19 @@ fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box
 }
 
 // Parse a function visibility modifier:
-//      Visibility = "public" ( "(" "script" | "friend" ")" )?
+//      Visibility = "public" ( "(" "script" | "friend" | "package" ")" )?
 fn parse_visibility(context: &mut Context) -> Result<Visibility, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     consume_token(context.tokens, Tok::Public)?;
     let sub_public_vis = if match_token(context.tokens, Tok::LParen)? {
         let sub_token = context.tokens.peek();
+        // 'package' is not a keyword, so it is only special inside of 'public(...)'
+        let is_package = sub_token == Tok::Identifier && context.tokens.content() == "package";
         context.tokens.advance()?;
         if sub_token != Tok::RParen {
             consume_token(context.tokens, Tok::RParen)?;
         }
-        Some(sub_token)
+        Some((sub_token, is_package))
     } else {
         None
     };
@@ -517,13 +669,15 @@ fn parse_visibility(context: &mut Context) -> Result<Visibility, Box<Diagnostic>
     let loc = make_loc(context.tokens.file_hash(), start_loc, end_loc);
     Ok(match sub_public_vis {
         None => Visibility::Public(loc),
-        Some(Tok::Script) => Visibility::Script(loc),
-        Some(Tok::Friend) => Visibility::Friend(loc),
+        Some((Tok::Script, _)) => Visibility::Script(loc),
+        Some((Tok::Friend, _)) => Visibility::Friend(loc),
+        Some((_, true)) => Visibility::Package(loc),
         _ => {
             let msg = format!(
-                "Invalid visibility modifier. Consider removing it or using '{}' or '{}'",
+                "Invalid visibility modifier. Consider removing it or using '{}', '{}', or '{}'",
                 Visibility::PUBLIC,
-                Visibility::FRIEND
+                Visibility::FRIEND,
+                Visibility::PACKAGE
             );
             return Err(Box::new(diag!(Syntax::UnexpectedToken, (loc, msg))));
         }
@@ -644,11 +798,15 @@ fn parse_bind_field(context: &mut Context) -> Result<(Field, Bind), Box<Diagnost
 //      Bind =
 //          <Var>
 //          | <NameAccessChain> <OptionalTypeArgs> "{" Comma<BindField> "}"
+//          | <NameAccessChain> <OptionalTypeArgs> "(" Comma<Bind> ")"
 fn parse_bind(context: &mut Context) -> Result<Bind, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     if context.tokens.peek() == Tok::Identifier {
         let next_tok = context.tokens.lookahead()?;
-        if next_tok != Tok::LBrace && next_tok != Tok::Less && next_tok != Tok::ColonColon {
+        if !matches!(
+            next_tok,
+            Tok::LBrace | Tok::LParen | Tok::Less | Tok::ColonColon
+        ) {
             let v = Bind_::Var(parse_var(context)?);
             let end_loc = context.tokens.previous_end_loc();
             return Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, v));
@@ -659,15 +817,26 @@ fn parse_bind(context: &mut Context) -> Result<Bind, Box<Diagnostic>> {
     // it is possible that the user intention was to use a variable name.
     let ty = parse_name_access_chain(context, || "a variable or struct name")?;
     let ty_args = parse_optional_type_args(context)?;
-    let args = parse_comma_list(
-        context,
-        Tok::LBrace,
-        Tok::RBrace,
-        parse_bind_field,
-        "a field binding",
-    )?;
+    let unpack = if context.tokens.peek() == Tok::LParen {
+        let binds = parse_comma_list(
+            context,
+            Tok::LParen,
+            Tok::RParen,
+            parse_bind,
+            "a variable or structure binding",
+        )?;
+        Bind_::PositionalUnpack(Box::new(ty), ty_args, binds)
+    } else {
+        let args = parse_comma_list(
+            context,
+            Tok::LBrace,
+            Tok::RBrace,
+            parse_bind_field,
+            "a field binding",
+        )?;
+        Bind_::Unpack(Box::new(ty), ty_args, args)
+    };
     let end_loc = context.tokens.previous_end_loc();
-    let unpack = Bind_::Unpack(Box::new(ty), ty_args, args);
     Ok(spanned(
         context.tokens.file_hash(),
         start_loc,
@@ -703,8 +872,18 @@ fn parse_bind_list(context: &mut Context) -> Result<BindList, Box<Diagnostic>> {
 // Parse a list of bindings for lambda.
 //      LambdaBindList =
 //          "|" Comma<Bind> "|"
+//          | "||"
 fn parse_lambda_bind_list(context: &mut Context) -> Result<BindList, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
+    if match_token(context.tokens, Tok::PipePipe)? {
+        let end_loc = context.tokens.previous_end_loc();
+        return Ok(spanned(
+            context.tokens.file_hash(),
+            start_loc,
+            end_loc,
+            vec![],
+        ));
+    }
     let b = parse_comma_list(
         context,
         Tok::Pipe,
@@ -843,11 +1022,45 @@ fn parse_sequence(context: &mut Context) -> Result<Sequence, Box<Diagnostic>> {
         uses.push(parse_use_decl(vec![], context)?);
     }
 
+    let seq_depth = context.tokens.brace_depth();
     let mut seq: Vec<SequenceItem> = vec![];
     let mut last_semicolon_loc = None;
     let mut eopt = None;
     while context.tokens.peek() != Tok::RBrace {
-        let item = parse_sequence_item(context)?;
+        let start_loc = context.tokens.start_loc();
+        let item = match parse_sequence_item(context) {
+            Ok(item) => item,
+            Err(diag) => {
+                let error_loc = context.tokens.start_loc();
+                let in_sequence = match skip_to_next_sequence_item(context, seq_depth) {
+                    Ok(in_sequence) => in_sequence,
+                    Err(_) => return Err(diag),
+                };
+                if !in_sequence {
+                    // The sequence is not closed. Report it once, if the error was not already
+                    // found at the point where recovery stopped
+                    if context.tokens.start_loc() == error_loc {
+                        return Err(diag);
+                    }
+                    context.env.add_diag(*diag);
+                    return Err(unexpected_token_error(context.tokens, "'}'"));
+                }
+                context.env.add_diag(*diag);
+                // Keep an error in place of the invalid item
+                let loc = make_loc(
+                    context.tokens.file_hash(),
+                    start_loc,
+                    context.tokens.previous_end_loc(),
+                );
+                let error = sp(loc, Exp_::UnresolvedError);
+                if context.tokens.peek() == Tok::RBrace {
+                    eopt = Some(error);
+                    break;
+                }
+                seq.push(sp(loc, SequenceItem_::Seq(Box::new(error))));
+                continue;
+            }
+        };
         if context.tokens.peek() == Tok::RBrace {
             // If the sequence ends with an expression that is not
             // followed by a semicolon, split out that expression
@@ -859,13 +1072,22 @@ fn parse_sequence(context: &mut Context) -> Result<Sequence, Box<Diagnostic>> {
                         value: e.value,
                     });
                 }
-                _ => return Err(unexpected_token_error(context.tokens, "';'")),
+                _ => {
+                    context
+                        .env
+                        .add_diag(*unexpected_token_error(context.tokens, "';'"));
+                    seq.push(item);
+                }
             }
             break;
         }
+        let semicolon_loc = current_token_loc(context.tokens);
+        match consume_token(context.tokens, Tok::Semicolon) {
+            Ok(()) => last_semicolon_loc = Some(semicolon_loc),
+            // Keep the item, and continue with the next one from here
+            Err(diag) => context.env.add_diag(*diag),
+        }
         seq.push(item);
-        last_semicolon_loc = Some(current_token_loc(context.tokens));
-        consume_token(context.tokens, Tok::Semicolon)?;
     }
     context.tokens.advance()?; // consume the RBrace
     Ok((uses, seq, last_semicolon_loc, Box::new(eopt)))
@@ -877,8 +1099,8 @@ fn parse_sequence(context: &mut Context) -> Result<Sequence, Box<Diagnostic>> {
 
 // Parse an expression term:
 //      Term =
-//          "break"
-//          | "continue"
+//          "break" <BlockLabelOpt>
+//          | "continue" <BlockLabelOpt>
 //          | "vector" ('<' Comma<Type> ">")? "[" Comma<Exp> "]"
 //          | <Value>
 //          | "(" Comma<Exp> ")"
@@ -888,14 +1110,17 @@ fn parse_sequence(context: &mut Context) -> Result<Sequence, Box<Diagnostic>> {
 //          | "if" "(" <Exp> ")" <Exp> "else" "{" <Exp> "}"
 //          | "if" "(" <Exp> ")" "{" <Exp> "}"
 //          | "if" "(" <Exp> ")" <Exp> ("else" <Exp>)?
-//          | "while" "(" <Exp> ")" "{" <Exp> "}"
-//          | "while" "(" <Exp> ")" <Exp> (SpecBlock)?
-//          | "loop" <Exp>
-//          | "loop" "{" <Exp> "}"
+//          | (<BlockLabel> ":")? "while" "(" <Exp> ")" "{" <Exp> "}"
+//          | (<BlockLabel> ":")? "while" "(" <Exp> ")" <Exp> (SpecBlock)?
+//          | (<BlockLabel> ":")? "for" "(" <Var> "in" <Exp> ")" "{" <Exp> "}"
+//          | (<BlockLabel> ":")? "for" "(" <Var> "in" <Exp> ")" <Exp>
+//          | (<BlockLabel> ":")? "loop" <Exp>
+//          | (<BlockLabel> ":")? "loop" "{" <Exp> "}"
 //          | "return" "{" <Exp> "}"
 //          | "return" <Exp>?
 //          | "abort" "{" <Exp> "}"
 //          | "abort" <Exp>
+//          | <MatchExp>
 fn parse_term(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
     const VECTOR_IDENT: &str = "vector";
 
@@ -911,19 +1136,22 @@ fn parse_term(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
         }
         Tok::Break => {
             context.tokens.advance()?;
+            let label = parse_block_label_opt(context)?;
             if at_start_of_exp(context) {
                 let mut diag = unexpected_token_error(context.tokens, "the end of an expression");
                 diag.add_note("'break' with a value is not yet supported");
                 return Err(diag);
             }
-            Exp_::Break
+            Exp_::Break(label)
         }
 
         Tok::Continue => {
             context.tokens.advance()?;
-            Exp_::Continue
+            Exp_::Continue(parse_block_label_opt(context)?)
         }
 
+        Tok::Match => parse_match_exp(context)?,
+
         Tok::Identifier
             if context.tokens.content() == VECTOR_IDENT
                 && matches!(context.tokens.lookahead(), Ok(Tok::Less | Tok::LBracket)) =>
@@ -1039,7 +1267,7 @@ fn parse_term(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
 fn is_control_exp(tok: Tok) -> bool {
     matches!(
         tok,
-        Tok::If | Tok::While | Tok::Loop | Tok::Return | Tok::Abort
+        Tok::If | Tok::While | Tok::For | Tok::Loop | Tok::Return | Tok::Abort | Tok::BlockLabel
     )
 }
 
@@ -1069,6 +1297,19 @@ fn parse_control_exp(context: &mut Context) -> Result<(Exp, bool), Box<Diagnosti
         }
     }
     let start_loc = context.tokens.start_loc();
+    let label = if context.tokens.peek() == Tok::BlockLabel {
+        let label = parse_block_label(context)?;
+        consume_token(context.tokens, Tok::Colon)?;
+        if !matches!(context.tokens.peek(), Tok::While | Tok::For | Tok::Loop) {
+            return Err(unexpected_token_error(
+                context.tokens,
+                "'while', 'for', or 'loop' after a label",
+            ));
+        }
+        Some(label)
+    } else {
+        None
+    };
     let (exp_, ends_in_block) = match context.tokens.peek() {
         Tok::If => {
             context.tokens.advance()?;
@@ -1120,12 +1361,28 @@ fn parse_control_exp(context: &mut Context) -> Result<(Exp, bool), Box<Diagnosti
             } else {
                 (econd, ends_in_block)
             };
-            (Exp_::While(Box::new(econd), Box::new(eloop)), ends_in_block)
+            (
+                Exp_::While(label, Box::new(econd), Box::new(eloop)),
+                ends_in_block,
+            )
+        }
+        Tok::For => {
+            context.tokens.advance()?;
+            consume_token(context.tokens, Tok::LParen)?;
+            let var = parse_var(context)?;
+            consume_identifier(context.tokens, "in")?;
+            let eiter = parse_exp(context)?;
+            consume_token(context.tokens, Tok::RParen)?;
+            let (eloop, ends_in_block) = parse_exp_or_sequence(context)?;
+            (
+                Exp_::For(label, var, Box::new(eiter), Box::new(eloop)),
+                ends_in_block,
+            )
         }
         Tok::Loop => {
             context.tokens.advance()?;
             let (eloop, ends_in_block) = parse_exp_or_sequence(context)?;
-            (Exp_::Loop(Box::new(eloop)), ends_in_block)
+            (Exp_::Loop(label, Box::new(eloop)), ends_in_block)
         }
         Tok::Return => {
             context.tokens.advance()?;
@@ -1149,6 +1406,89 @@ fn parse_control_exp(context: &mut Context) -> Result<(Exp, bool), Box<Diagnosti
     Ok((exp, ends_in_block))
 }
 
+// Parse a match expression:
+//      MatchExp = "match" "(" <Exp> ")" "{" (<MatchArm> ","?)* "}"
+//
+// The comma after an arm can only be omitted if the arm's expression is a block
+// or if it is the last arm.
+fn parse_match_exp(context: &mut Context) -> Result<Exp_, Box<Diagnostic>> {
+    consume_token(context.tokens, Tok::Match)?;
+    consume_token(context.tokens, Tok::LParen)?;
+    let e = parse_exp(context)?;
+    consume_token(context.tokens, Tok::RParen)?;
+    consume_token(context.tokens, Tok::LBrace)?;
+    let mut arms = vec![];
+    while context.tokens.peek() != Tok::RBrace {
+        let arm = parse_match_arm(context)?;
+        let ends_in_block = matches!(arm.value.1.value, Exp_::Block(_));
+        arms.push(arm);
+        if context.tokens.peek() == Tok::RBrace {
+            break;
+        }
+        if !match_token(context.tokens, Tok::Comma)? && !ends_in_block {
+            return Err(unexpected_token_error(
+                context.tokens,
+                &format!("'{}' or '{}'", Tok::Comma, Tok::RBrace),
+            ));
+        }
+    }
+    consume_token(context.tokens, Tok::RBrace)?;
+    Ok(Exp_::Match(Box::new(e), arms))
+}
+
+// Parse an arm of a match expression:
+//      MatchArm = <MatchPattern> "=>" <Exp>
+fn parse_match_arm(context: &mut Context) -> Result<MatchArm, Box<Diagnostic>> {
+    let start_loc = context.tokens.start_loc();
+    let pattern = parse_match_pattern(context)?;
+    consume_token(context.tokens, Tok::EqualGreater)?;
+    let rhs = parse_exp(context)?;
+    let end_loc = context.tokens.previous_end_loc();
+    Ok(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        (pattern, rhs),
+    ))
+}
+
+// Parse a pattern of a match arm:
+//      MatchPattern =
+//          "_"
+//          | <NameAccessChain> <OptionalTypeArgs> ("{" Comma<BindField> "}")?
+fn parse_match_pattern(context: &mut Context) -> Result<MatchPattern, Box<Diagnostic>> {
+    let start_loc = context.tokens.start_loc();
+    let pattern = if context.tokens.peek() == Tok::Identifier
+        && context.tokens.content() == "_"
+        && context.tokens.lookahead()? == Tok::EqualGreater
+    {
+        context.tokens.advance()?;
+        MatchPattern_::Wildcard
+    } else {
+        let n = parse_name_access_chain(context, || "a variant pattern or '_'")?;
+        let ty_args = parse_optional_type_args(context)?;
+        let fields = if context.tokens.peek() == Tok::LBrace {
+            parse_comma_list(
+                context,
+                Tok::LBrace,
+                Tok::RBrace,
+                parse_bind_field,
+                "a field binding",
+            )?
+        } else {
+            vec![]
+        };
+        MatchPattern_::Variant(Box::new(n), ty_args, fields)
+    };
+    let end_loc = context.tokens.previous_end_loc();
+    Ok(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        pattern,
+    ))
+}
+
 // Parse a pack, call, or other reference to a name:
 //      NameExp =
 //          <NameAccessChain> <OptionalTypeArgs> "{" Comma<ExpField> "}"
@@ -1261,19 +1601,23 @@ fn at_start_of_exp(context: &mut Context) -> bool {
             | Tok::Loop
             | Tok::Return
             | Tok::While
+            | Tok::For
+            | Tok::Match
+            | Tok::BlockLabel
     )
 }
 
 // Parse an expression:
 //      Exp =
-//            <LambdaBindList> <Exp>        spec only
+//            <LambdaBindList> <Exp>
 //          | <Quantifier>                  spec only
 //          | <BinOpExp>
 //          | <UnaryExp> "=" <Exp>
+//          | <UnaryExp> <AssignOp> <Exp>
 fn parse_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     let exp = match context.tokens.peek() {
-        Tok::Pipe => {
+        Tok::Pipe | Tok::PipePipe => {
             let bindings = parse_lambda_bind_list(context)?;
             let body = Box::new(parse_exp(context)?);
             Exp_::Lambda(bindings, body)
@@ -1283,18 +1627,49 @@ fn parse_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
             // This could be either an assignment or a binary operator
             // expression.
             let lhs = parse_unary_exp(context)?;
-            if context.tokens.peek() != Tok::Equal {
+            if let Some(op) = parse_assign_op(context)? {
+                let rhs = Box::new(parse_exp(context)?);
+                Exp_::AssignOp(Box::new(lhs), op, rhs)
+            } else if context.tokens.peek() != Tok::Equal {
                 return parse_binop_exp(context, lhs, /* min_prec */ 1);
+            } else {
+                context.tokens.advance()?; // consume the "="
+                let rhs = Box::new(parse_exp(context)?);
+                Exp_::Assign(Box::new(lhs), rhs)
             }
-            context.tokens.advance()?; // consume the "="
-            let rhs = Box::new(parse_exp(context)?);
-            Exp_::Assign(Box::new(lhs), rhs)
         }
     };
     let end_loc = context.tokens.previous_end_loc();
     Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, exp))
 }
 
+// Parse a compound assignment operator, returning the binary operator it applies:
+//      AssignOp = "+=" | "-=" | "*=" | "/=" | "%=" | "&=" | "|=" | "^=" | "<<=" | ">>="
+fn parse_assign_op(context: &mut Context) -> Result<Option<BinOp>, Box<Diagnostic>> {
+    let op = match context.tokens.peek() {
+        Tok::PlusEqual => BinOp_::Add,
+        Tok::MinusEqual => BinOp_::Sub,
+        Tok::StarEqual => BinOp_::Mul,
+        Tok::SlashEqual => BinOp_::Div,
+        Tok::PercentEqual => BinOp_::Mod,
+        Tok::AmpEqual => BinOp_::BitAnd,
+        Tok::PipeEqual => BinOp_::BitOr,
+        Tok::CaretEqual => BinOp_::Xor,
+        Tok::LessLessEqual => BinOp_::Shl,
+        Tok::GreaterGreaterEqual => BinOp_::Shr,
+        _ => return Ok(None),
+    };
+    let start_loc = context.tokens.start_loc();
+    context.tokens.advance()?;
+    let end_loc = context.tokens.previous_end_loc();
+    Ok(Some(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        op,
+    )))
+}
+
 // Get the precedence of a binary operator. The minimum precedence value
 // is 1, and larger values have higher precedence. For tokens that are not
 // binary operators, this returns a value of zero so that they will be
@@ -1463,17 +1838,45 @@ fn parse_unary_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
 // Parse an expression term optionally followed by a chain of dot or index accesses:
 //      DotOrIndexChain =
 //          <DotOrIndexChain> "." <Identifier>
-//          | <DotOrIndexChain> "[" <Exp> "]"                      spec only
+//          | <DotOrIndexChain> "." <Number>
+//          | <DotOrIndexChain> "." <Identifier> <OptionalTypeArgs> "(" Comma<Exp> ")"
+//          | <DotOrIndexChain> "[" <Exp> "]"
 //          | <Term>
 fn parse_dot_or_index_chain(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     let mut lhs = parse_term(context)?;
     loop {
         let exp = match context.tokens.peek() {
+            Tok::Period
+                if matches!(
+                    context.tokens.lookahead()?,
+                    Tok::NumValue | Tok::NumTypedValue
+                ) =>
+            {
+                context.tokens.advance()?;
+                Exp_::Dot(Box::new(lhs), parse_positional_field(context)?)
+            }
             Tok::Period => {
                 context.tokens.advance()?;
                 let n = parse_identifier(context)?;
-                Exp_::Dot(Box::new(lhs), n)
+                // As with names, a '<' directly after the identifier starts the type arguments
+                // of a method call.
+                let mut tys = None;
+                let tys_start_loc = context.tokens.start_loc();
+                if context.tokens.peek() == Tok::Less && n.loc.end() as usize == tys_start_loc {
+                    let loc = make_loc(context.tokens.file_hash(), tys_start_loc, tys_start_loc);
+                    tys = parse_optional_type_args(context)
+                        .map_err(|diag| add_type_args_ambiguity_label(loc, diag))?;
+                    if context.tokens.peek() != Tok::LParen {
+                        return Err(unexpected_token_error(context.tokens, "'('"));
+                    }
+                }
+                if context.tokens.peek() == Tok::LParen {
+                    let args = parse_call_args(context)?;
+                    Exp_::DotCall(Box::new(lhs), n, tys, args)
+                } else {
+                    Exp_::Dot(Box::new(lhs), n)
+                }
             }
             Tok::LBracket => {
                 context.tokens.advance()?;
@@ -1490,6 +1893,24 @@ fn parse_dot_or_index_chain(context: &mut Context) -> Result<Exp, Box<Diagnostic
     Ok(lhs)
 }
 
+// Parse the index of a positional field, e.g. the `0` in `s.0`:
+//      PositionalField = <Number>
+fn parse_positional_field(context: &mut Context) -> Result<Name, Box<Diagnostic>> {
+    if !context.tokens.content().bytes().all(|b| b.is_ascii_digit()) {
+        return Err(unexpected_token_error(
+            context.tokens,
+            "a field name or the index of a positional field",
+        ));
+    }
+    let loc = current_token_loc(context.tokens);
+    let idx = context.tokens.content().parse::<usize>().map_err(|_| {
+        let msg = "Invalid field access. Positional field index is too large";
+        Box::new(diag!(Syntax::UnexpectedToken, (loc, msg)))
+    })?;
+    context.tokens.advance()?;
+    Ok(Field::positional(loc, idx).0)
+}
+
 // Lookahead to determine whether this is a quantifier. This matches
 //
 //      ( "exists" | "forall" | "choose" | "min" )
@@ -1671,7 +2092,8 @@ fn make_builtin_call(loc: Loc, name: Symbol, type_args: Option<Vec<Type>>, args:
 //          <NameAccessChain> ('<' Comma<Type> ">")?
 //          | "&" <Type>
 //          | "&mut" <Type>
-//          | "|" Comma<Type> "|" Type   (spec only)
+//          | "|" Comma<Type> "|" <Type>?
+//          | "||" <Type>?
 //          | "(" Comma<Type> ")"
 fn parse_type(context: &mut Context) -> Result<Type, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
@@ -1694,9 +2116,20 @@ fn parse_type(context: &mut Context) -> Result<Type, Box<Diagnostic>> {
             let t = parse_type(context)?;
             Type_::Ref(true, Box::new(t))
         }
-        Tok::Pipe => {
-            let args = parse_comma_list(context, Tok::Pipe, Tok::Pipe, parse_type, "a type")?;
-            let result = parse_type(context)?;
+        Tok::Pipe | Tok::PipePipe => {
+            let args = if match_token(context.tokens, Tok::PipePipe)? {
+                vec![]
+            } else {
+                parse_comma_list(context, Tok::Pipe, Tok::Pipe, parse_type, "a type")?
+            };
+            // The result type can be omitted if it is '()'
+            let result = match context.tokens.peek() {
+                Tok::Comma | Tok::RParen | Tok::Greater | Tok::RBrace | Tok::Semicolon => {
+                    let loc = current_token_loc(context.tokens);
+                    sp(loc, Type_::Unit)
+                }
+                _ => parse_type(context)?,
+            };
             return Ok(spanned(
                 context.tokens.file_hash(),
                 start_loc,
@@ -1880,6 +2313,7 @@ fn parse_function_decl(
         visibility,
         mut entry,
         native,
+        inline,
     } = modifiers;
 
     if let Some(Visibility::Script(vloc)) = visibility {
@@ -1897,6 +2331,25 @@ fn parse_function_decl(
             entry = Some(vloc)
         }
     }
+    if let Some(inline_loc) = inline {
+        let invalid_modifier = match (native, entry) {
+            (Some(loc), _) => Some((loc, NATIVE_MODIFIER)),
+            (None, Some(loc)) => Some((loc, ENTRY_MODIFIER)),
+            (None, None) => None,
+        };
+        if let Some((loc, modifier)) = invalid_modifier {
+            let msg = format!(
+                "Invalid function declaration. '{}' functions cannot be '{}'",
+                INLINE_MODIFIER, modifier
+            );
+            let inline_msg = format!("Function declared '{}' here", INLINE_MODIFIER);
+            context.env.add_diag(diag!(
+                Syntax::InvalidModifier,
+                (loc, msg),
+                (inline_loc, inline_msg)
+            ));
+        }
+    }
 
     // "fun" <FunctionDefName>
     consume_token(context.tokens, Tok::Fun)?;
@@ -1970,6 +2423,7 @@ fn parse_function_decl(
         loc,
         visibility: visibility.unwrap_or(Visibility::Internal),
         entry,
+        inline: inline.is_some(),
         signature,
         acquires,
         name,
@@ -1990,10 +2444,13 @@ fn parse_parameter(context: &mut Context) -> Result<(Var, Type), Box<Diagnostic>
 // Structs
 //**************************************************************************************************
 
-// Parse a struct definition:
+// Parse a struct or enum definition:
 //      StructDecl =
 //          "struct" <StructDefName> ("has" <Ability> (, <Ability>)+)?
 //          ("{" Comma<FieldAnnot> "}" | ";")
+//          | "struct" <StructDefName> "(" Comma<Type> ")" ("has" <Ability> (, <Ability>)+)? ";"
+//          | "enum" <StructDefName> ("has" <Ability> (, <Ability>)+)?
+//          "{" Comma<VariantDecl> "}"
 //      StructDefName =
 //          <Identifier> <OptionalTypeParameters>
 fn parse_struct_decl(
@@ -2006,6 +2463,7 @@ fn parse_struct_decl(
         visibility,
         entry,
         native,
+        inline,
     } = modifiers;
     if let Some(vis) = visibility {
         let msg = format!(
@@ -2026,13 +2484,45 @@ fn parse_struct_decl(
             .env
             .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
     }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid struct declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
 
-    consume_token(context.tokens, Tok::Struct)?;
+    let is_enum = match context.tokens.peek() {
+        Tok::Enum => {
+            context.tokens.advance()?;
+            true
+        }
+        _ => {
+            consume_token(context.tokens, Tok::Struct)?;
+            false
+        }
+    };
 
     // <StructDefName>
     let name = StructName(parse_identifier(context)?);
     let type_parameters = parse_struct_type_parameters(context)?;
 
+    // The fields of a positional struct come before its abilities, e.g. `struct S(u64) has copy;`
+    let positional_fields = if native.is_none() && !is_enum && context.tokens.peek() == Tok::LParen
+    {
+        Some(parse_comma_list(
+            context,
+            Tok::LParen,
+            Tok::RParen,
+            parse_type,
+            "a type",
+        )?)
+    } else {
+        None
+    };
+
     let abilities = if context.tokens.peek() == Tok::Identifier && context.tokens.content() == "has"
     {
         context.tokens.advance()?;
@@ -2060,12 +2550,32 @@ fn parse_struct_decl(
         vec![]
     };
 
-    let fields = match native {
-        Some(loc) => {
+    let fields = match (native, positional_fields) {
+        (Some(loc), _) if is_enum => {
+            return Err(Box::new(diag!(
+                Syntax::InvalidModifier,
+                (loc, "Invalid enum declaration. Enums cannot be 'native'")
+            )));
+        }
+        (Some(loc), _) => {
             consume_token(context.tokens, Tok::Semicolon)?;
             StructFields::Native(loc)
         }
-        _ => {
+        (None, _) if is_enum => {
+            let list = parse_comma_list(
+                context,
+                Tok::LBrace,
+                Tok::RBrace,
+                parse_variant_decl,
+                "a variant",
+            )?;
+            StructFields::Variants(list)
+        }
+        (None, Some(tys)) => {
+            consume_token(context.tokens, Tok::Semicolon)?;
+            StructFields::Positional(tys)
+        }
+        (None, None) => {
             let list = parse_comma_list(
                 context,
                 Tok::LBrace,
@@ -2092,6 +2602,27 @@ fn parse_struct_decl(
     })
 }
 
+// Parse a variant of an enum, with an optional list of fields:
+//      VariantDecl = <DocComments> <Identifier> ("{" Comma<FieldAnnot> "}")?
+fn parse_variant_decl(
+    context: &mut Context,
+) -> Result<(VariantName, Vec<(Field, Type)>), Box<Diagnostic>> {
+    context.tokens.match_doc_comments();
+    let name = VariantName(parse_identifier(context)?);
+    let fields = if context.tokens.peek() == Tok::LBrace {
+        parse_comma_list(
+            context,
+            Tok::LBrace,
+            Tok::RBrace,
+            parse_field_annot,
+            "a field",
+        )?
+    } else {
+        vec![]
+    };
+    Ok((name, fields))
+}
+
 // Parse a field annotated with a type:
 //      FieldAnnot = <DocComments> <Field> ":" <Type>
 fn parse_field_annot(context: &mut Context) -> Result<(Field, Type), Box<Diagnostic>> {
@@ -2118,6 +2649,7 @@ fn parse_constant_decl(
         visibility,
         entry,
         native,
+        inline,
     } = modifiers;
     if let Some(vis) = visibility {
         let msg = "Invalid constant declaration. Constants cannot have visibility modifiers as \
@@ -2141,6 +2673,15 @@ fn parse_constant_decl(
             .env
             .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
     }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid constant declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
     consume_token(context.tokens, Tok::Const)?;
     let name = ConstantName(parse_identifier(context)?);
     consume_token(context.tokens, Tok::Colon)?;
@@ -2204,11 +2745,16 @@ fn parse_address_block(
         Tok::LBrace => {
             context.tokens.advance()?;
             let mut modules = vec![];
-            while context.tokens.peek() != Tok::RBrace {
+            // A module that is not closed ends at the start of the next definition
+            while context.tokens.peek() != Tok::RBrace
+                && (context.tokens.peek() == Tok::Module || !at_start_of_definition(context))
+            {
                 let attributes = parse_attributes(context)?;
                 modules.push(parse_module(attributes, context)?);
             }
-            consume_token(context.tokens, Tok::RBrace)?;
+            if let Err(diag) = consume_token(context.tokens, Tok::RBrace) {
+                context.env.add_diag(*diag);
+            }
             modules
         }
         _ => return Err(unexpected_token_error(context.tokens, "'{'")),
@@ -2257,12 +2803,28 @@ fn parse_friend_decl(
 //      UseDecl =
 //          "use" <ModuleIdent> <UseAlias> ";" |
 //          "use" <ModuleIdent> :: <UseMember> ";" |
-//          "use" <ModuleIdent> :: "{" Comma<UseMember> "}" ";"
+//          "use" <ModuleIdent> :: "{" Comma<UseMember> "}" ";" |
+//          "use" "fun" <NameAccessChain> "as" <NameAccessChain> "." <Identifier> ";"
 fn parse_use_decl(
     attributes: Vec<Attributes>,
     context: &mut Context,
 ) -> Result<UseDecl, Box<Diagnostic>> {
     consume_token(context.tokens, Tok::Use)?;
+    if context.tokens.peek() == Tok::Fun {
+        context.tokens.advance()?;
+        let function = parse_name_access_chain(context, || "a function name")?;
+        consume_token(context.tokens, Tok::As)?;
+        let ty = parse_name_access_chain(context, || "a type name")?;
+        consume_token(context.tokens, Tok::Period)?;
+        let method = parse_identifier(context)?;
+        consume_token(context.tokens, Tok::Semicolon)?;
+        let use_ = Use::Fun {
+            function,
+            ty,
+            method,
+        };
+        return Ok(UseDecl { attributes, use_ });
+    }
     let ident = parse_module_ident(context)?;
     let alias_opt = parse_use_alias(context)?;
     let use_ = match (&alias_opt, context.tokens.peek()) {
@@ -2308,12 +2870,7 @@ fn parse_use_alias(context: &mut Context) -> Result<Option<Name>, Box<Diagnostic
 // Parse a module:
 //      Module =
 //          <DocComments> ( "spec" | "module") (<LeadingNameAccess>::)?<ModuleName> "{"
-//              ( <Attributes>
-//                  ( <UseDecl> | <FriendDecl> | <SpecBlock> |
-//                    <DocComments> <ModuleMemberModifiers>
-//                        (<ConstantDecl> | <StructDecl> | <FunctionDecl>) )
-//                  )
-//              )*
+//              <ModuleMember>*
 //          "}"
 fn parse_module(
     attributes: Vec<Attributes>,
@@ -2342,85 +2899,30 @@ fn parse_module(
     };
     consume_token(context.tokens, Tok::LBrace)?;
 
+    let module_depth = context.tokens.brace_depth();
     let mut members = vec![];
+    let mut closed = true;
     while context.tokens.peek() != Tok::RBrace {
-        members.push({
-            let attributes = parse_attributes(context)?;
-            match context.tokens.peek() {
-                // Top-level specification constructs
-                Tok::Invariant => {
-                    context.tokens.match_doc_comments();
-                    ModuleMember::Spec(singleton_module_spec_block(
-                        context,
-                        context.tokens.start_loc(),
-                        attributes,
-                        parse_invariant,
-                    )?)
-                }
-                Tok::Spec => {
-                    match context.tokens.lookahead() {
-                        Ok(Tok::Fun) | Ok(Tok::Native) => {
-                            context.tokens.match_doc_comments();
-                            let start_loc = context.tokens.start_loc();
-                            context.tokens.advance()?;
-                            // Add an extra check for better error message
-                            // if old syntax is used
-                            if context.tokens.lookahead2() == Ok((Tok::Identifier, Tok::LBrace)) {
-                                return Err(unexpected_token_error(
-                                    context.tokens,
-                                    "only 'spec', drop the 'fun' keyword",
-                                ));
-                            }
-                            ModuleMember::Spec(singleton_module_spec_block(
-                                context,
-                                start_loc,
-                                attributes,
-                                parse_spec_function,
-                            )?)
-                        }
-                        _ => {
-                            // Regular spec block
-                            ModuleMember::Spec(parse_spec_block(attributes, context)?)
-                        }
-                    }
+        let member_start_loc = context.tokens.start_loc();
+        match parse_module_member(context) {
+            Ok(member) => members.push(member),
+            Err(diag) => {
+                if skip_to_next_module_member(context, member_start_loc, module_depth).is_err() {
+                    return Err(diag);
                 }
-                // Regular move constructs
-                Tok::Use => ModuleMember::Use(parse_use_decl(attributes, context)?),
-                Tok::Friend => ModuleMember::Friend(parse_friend_decl(attributes, context)?),
-                _ => {
-                    context.tokens.match_doc_comments();
-                    let start_loc = context.tokens.start_loc();
-                    let modifiers = parse_module_member_modifiers(context)?;
-                    match context.tokens.peek() {
-                        Tok::Const => ModuleMember::Constant(parse_constant_decl(
-                            attributes, start_loc, modifiers, context,
-                        )?),
-                        Tok::Fun => ModuleMember::Function(parse_function_decl(
-                            attributes, start_loc, modifiers, context,
-                        )?),
-                        Tok::Struct => ModuleMember::Struct(parse_struct_decl(
-                            attributes, start_loc, modifiers, context,
-                        )?),
-                        _ => {
-                            return Err(unexpected_token_error(
-                                context.tokens,
-                                &format!(
-                                    "a module member: '{}', '{}', '{}', '{}', '{}', or '{}'",
-                                    Tok::Spec,
-                                    Tok::Use,
-                                    Tok::Friend,
-                                    Tok::Const,
-                                    Tok::Fun,
-                                    Tok::Struct
-                                ),
-                            ))
-                        }
-                    }
+                context.env.add_diag(*diag);
+                if at_start_of_definition(context) {
+                    // The module is not closed, so keep the members parsed so far and let the
+                    // file continue with the next definition
+                    closed = false;
+                    break;
                 }
             }
-        })
+        }
+    }
+    if closed {
+        consume_token(context.tokens, Tok::RBrace)?;
     }
-    consume_token(context.tokens, Tok::RBrace)?;
     let loc = make_loc(
         context.tokens.file_hash(),
         start_loc,
@@ -2438,6 +2940,95 @@ fn parse_module(
     Ok(def)
 }
 
+// Parse a module member:
+//      ModuleMember =
+//          <Attributes>
+//              ( <UseDecl> | <FriendDecl> | <SpecBlock> |
+//                <DocComments> <ModuleMemberModifiers>
+//                    (<ConstantDecl> | <StructDecl> | <FunctionDecl>) )
+// The doc comments of a constant, struct or function can also be written before its attributes
+fn parse_module_member(context: &mut Context) -> Result<ModuleMember, Box<Diagnostic>> {
+    let doc_start = context.tokens.previous_end_loc();
+    let attributes = parse_attributes(context)?;
+    let member = match context.tokens.peek() {
+        // Top-level specification constructs
+        Tok::Invariant => {
+            context.tokens.match_doc_comments();
+            ModuleMember::Spec(singleton_module_spec_block(
+                context,
+                context.tokens.start_loc(),
+                attributes,
+                parse_invariant,
+            )?)
+        }
+        Tok::Spec => {
+            match context.tokens.lookahead() {
+                Ok(Tok::Fun) | Ok(Tok::Native) => {
+                    context.tokens.match_doc_comments();
+                    let start_loc = context.tokens.start_loc();
+                    context.tokens.advance()?;
+                    // Add an extra check for better error message
+                    // if old syntax is used
+                    if context.tokens.lookahead2() == Ok((Tok::Identifier, Tok::LBrace)) {
+                        let diag = unexpected_token_error(
+                            context.tokens,
+                            "only 'spec', drop the 'fun' keyword",
+                        );
+                        // Skip the 'fun', so that recovery does not parse a function from here
+                        context.tokens.advance()?;
+                        return Err(diag);
+                    }
+                    ModuleMember::Spec(singleton_module_spec_block(
+                        context,
+                        start_loc,
+                        attributes,
+                        parse_spec_function,
+                    )?)
+                }
+                _ => {
+                    // Regular spec block
+                    ModuleMember::Spec(parse_spec_block(attributes, context)?)
+                }
+            }
+        }
+        // Regular move constructs
+        Tok::Use => ModuleMember::Use(parse_use_decl(attributes, context)?),
+        Tok::Friend => ModuleMember::Friend(parse_friend_decl(attributes, context)?),
+        _ => {
+            context.tokens.match_doc_comments_since(doc_start);
+            let start_loc = context.tokens.start_loc();
+            let modifiers = parse_module_member_modifiers(context)?;
+            match context.tokens.peek() {
+                Tok::Const => ModuleMember::Constant(parse_constant_decl(
+                    attributes, start_loc, modifiers, context,
+                )?),
+                Tok::Fun => ModuleMember::Function(parse_function_decl(
+                    attributes, start_loc, modifiers, context,
+                )?),
+                Tok::Struct | Tok::Enum => ModuleMember::Struct(parse_struct_decl(
+                    attributes, start_loc, modifiers, context,
+                )?),
+                _ => {
+                    return Err(unexpected_token_error(
+                        context.tokens,
+                        &format!(
+                            "a module member: '{}', '{}', '{}', '{}', '{}', '{}', or '{}'",
+                            Tok::Spec,
+                            Tok::Use,
+                            Tok::Friend,
+                            Tok::Const,
+                            Tok::Fun,
+                            Tok::Struct,
+                            Tok::Enum
+                        ),
+                    ))
+                }
+            }
+        }
+    };
+    Ok(member)
+}
+
 //**************************************************************************************************
 // Scripts
 //**************************************************************************************************
@@ -2535,10 +3126,11 @@ fn parse_spec_block(
     let target_start_loc = context.tokens.start_loc();
     let target_ = match context.tokens.peek() {
         Tok::Fun => {
-            return Err(unexpected_token_error(
-                context.tokens,
-                "only 'spec', drop the 'fun' keyword",
-            ));
+            let diag =
+                unexpected_token_error(context.tokens, "only 'spec', drop the 'fun' keyword");
+            // Skip the 'fun', so that recovery does not parse a function from here
+            context.tokens.advance()?;
+            return Err(diag);
         }
         Tok::Struct => {
             return Err(unexpected_token_error(
@@ -3207,19 +3799,34 @@ fn singleton_module_spec_block(
 fn parse_file(context: &mut Context) -> Result<Vec<Definition>, Box<Diagnostic>> {
     let mut defs = vec![];
     while context.tokens.peek() != Tok::EOF {
-        let attributes = parse_attributes(context)?;
-        defs.push(match context.tokens.peek() {
-            Tok::Spec | Tok::Module => Definition::Module(parse_module(attributes, context)?),
-            Tok::Script => Definition::Script(parse_script(attributes, context)?),
-            _ => Definition::Address(parse_address_block(attributes, context)?),
-        })
+        let start_loc = context.tokens.start_loc();
+        match parse_definition(context) {
+            Ok(def) => defs.push(def),
+            Err(diag) => {
+                if skip_to_next_definition(context, start_loc).is_err() {
+                    return Err(diag);
+                }
+                context.env.add_diag(*diag);
+            }
+        }
     }
     Ok(defs)
 }
 
+fn parse_definition(context: &mut Context) -> Result<Definition, Box<Diagnostic>> {
+    let attributes = parse_attributes(context)?;
+    Ok(match context.tokens.peek() {
+        Tok::Spec | Tok::Module => Definition::Module(parse_module(attributes, context)?),
+        Tok::Script => Definition::Script(parse_script(attributes, context)?),
+        _ => Definition::Address(parse_address_block(attributes, context)?),
+    })
+}
+
 /// Parse the `input` string as a file of Move source code and return the
 /// result as either a pair of FileDefinition and doc comments or some Diagnostics. The `file` name
-/// is used to identify source locations in error messages.
+/// is used to identify source locations in error messages. Syntax errors that the parser can
+/// recover from are added to `env`, and the definitions are returned with the invalid parts
+/// skipped or replaced by error nodes.
 pub fn parse_file_string(
     env: &mut CompilationEnv,
     file_hash: FileHash,
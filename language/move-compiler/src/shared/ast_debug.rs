@@ -40,6 +40,12 @@ pub fn print<T: AstDebug>(t: &T) {
     print!("{}", writer);
 }
 
+pub fn display<T: AstDebug>(t: &T) -> String {
+    let mut writer = AstWriter::normal();
+    t.ast_debug(&mut writer);
+    writer.to_string()
+}
+
 pub fn print_verbose<T: AstDebug>(t: &T) {
     let mut writer = AstWriter::verbose();
     t.ast_debug(&mut writer);
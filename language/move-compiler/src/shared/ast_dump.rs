@@ -0,0 +1,156 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serialized dumps of the ASTs of the compiler passes, for tools analyzing Move programs without
+//! depending on the internals of the compiler. A JSON dump is an object with the version of the
+//! dump format, the pass, the source files, and the program of the pass. Every location in the
+//! program refers to one of the files by its hash, with byte offsets into its source.
+
+use crate::{
+    cfgir::ast as G, diagnostics::FilesSourceText, expansion::ast as E, shared::ast_debug::*,
+    typing::ast as T,
+};
+use clap::ArgEnum;
+use move_command_line_common::files::FileHash;
+use move_symbol_pool::Symbol;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// The version of the format of JSON dumps, bumped on changes that are not additions
+pub const AST_DUMP_VERSION: u64 = 1;
+
+/// The passes whose AST can be dumped
+#[derive(PartialEq, Eq, Clone, Copy, Debug, ArgEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AstDumpPass {
+    Expansion,
+    Typing,
+    Cfgir,
+}
+
+/// The format in which an AST is dumped
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, ArgEnum)]
+pub enum AstDumpFormat {
+    /// A JSON object with the program and its source files
+    #[default]
+    Json,
+    /// The pretty-printed program, as in the debug output of the compiler
+    Debug,
+}
+
+/// The AST of a pass
+pub enum PassAst<'a> {
+    Expansion(&'a E::Program),
+    Typing(&'a T::Program),
+    Cfgir(&'a G::Program),
+}
+
+#[derive(Serialize)]
+struct JsonDump<'a, P: Serialize> {
+    version: u64,
+    pass: AstDumpPass,
+    files: Vec<JsonFile>,
+    program: &'a P,
+}
+
+#[derive(Serialize)]
+struct JsonFile {
+    file_hash: FileHash,
+    path: Symbol,
+}
+
+impl<'a> PassAst<'a> {
+    pub fn pass(&self) -> AstDumpPass {
+        match self {
+            PassAst::Expansion(_) => AstDumpPass::Expansion,
+            PassAst::Typing(_) => AstDumpPass::Typing,
+            PassAst::Cfgir(_) => AstDumpPass::Cfgir,
+        }
+    }
+
+    /// Dumps the AST in `format`, with the source files of its locations
+    pub fn dump(&self, files: &FilesSourceText, format: AstDumpFormat) -> anyhow::Result<String> {
+        match self {
+            PassAst::Expansion(prog) => dump(self.pass(), *prog, files, format),
+            PassAst::Typing(prog) => dump(self.pass(), *prog, files, format),
+            PassAst::Cfgir(prog) => dump(self.pass(), *prog, files, format),
+        }
+    }
+}
+
+fn dump<P: Serialize + AstDebug>(
+    pass: AstDumpPass,
+    program: &P,
+    files: &FilesSourceText,
+    format: AstDumpFormat,
+) -> anyhow::Result<String> {
+    match format {
+        AstDumpFormat::Json => {
+            let mut files = files
+                .iter()
+                .map(|(file_hash, (path, _))| JsonFile {
+                    file_hash: *file_hash,
+                    path: *path,
+                })
+                .collect::<Vec<_>>();
+            files.sort_by(|f1, f2| f1.path.as_str().cmp(f2.path.as_str()));
+            let dump = JsonDump {
+                version: AST_DUMP_VERSION,
+                pass,
+                files,
+                program,
+            };
+            Ok(serde_json::to_string(&dump)?)
+        }
+        AstDumpFormat::Debug => Ok(display(program)),
+    }
+}
+
+/// Serializes a map as a sequence of key-value pairs, for maps whose keys are not strings
+pub fn serialize_map_as_seq<K: Serialize, V: Serialize, S: Serializer>(
+    map: &BTreeMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command_line::compiler::Pass, shared::Flags, Compiler, PASS_CFGIR, PASS_EXPANSION,
+        PASS_TYPING,
+    };
+
+    fn dump_stdlib<const P: Pass>() -> serde_json::Value {
+        let (files, res) = Compiler::from_files(
+            move_stdlib::move_stdlib_files(),
+            vec![],
+            move_stdlib::move_stdlib_named_addresses(),
+        )
+        .set_flags(Flags::empty())
+        .run::<P>()
+        .unwrap();
+        let (_comments, stepped) = res.unwrap();
+        let dump = stepped.dump_ast(&files, AstDumpFormat::Json).unwrap();
+        serde_json::from_str(&dump).unwrap()
+    }
+
+    #[test]
+    fn dump_stdlib_passes() {
+        for (pass, dump) in [
+            ("expansion", dump_stdlib::<PASS_EXPANSION>()),
+            ("typing", dump_stdlib::<PASS_TYPING>()),
+            ("cfgir", dump_stdlib::<PASS_CFGIR>()),
+        ] {
+            assert_eq!(dump["version"], AST_DUMP_VERSION);
+            assert_eq!(dump["pass"], pass);
+            let files = dump["files"].as_array().unwrap();
+            assert_eq!(files.len(), move_stdlib::move_stdlib_files().len());
+            // locations refer to the files by their hash
+            let file_hash = files[0]["file_hash"].as_str().unwrap();
+            assert_eq!(file_hash.len(), 64);
+            assert!(dump["program"].to_string().contains(file_hash));
+        }
+    }
+}
@@ -4,21 +4,24 @@
 
 use crate::{
     command_line as cli,
-    diagnostics::{codes::Severity, Diagnostic, Diagnostics},
+    diagnostics::{codes::Severity, Diagnostic, Diagnostics, DiagnosticsFormat},
+    linters,
     naming::ast::ModuleDefinition,
 };
 use clap::*;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
+use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt,
     hash::Hash,
     sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
 };
 
 pub mod ast_debug;
+pub mod ast_dump;
 pub mod remembering_unique_map;
 pub mod unique_map;
 pub mod unique_set;
@@ -132,10 +135,10 @@ pub fn shortest_cycle<'a, T: Ord + Hash>(
 
 pub type NamedAddressMap = BTreeMap<Symbol, NumericalAddress>;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
 pub struct NamedAddressMapIndex(usize);
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct NamedAddressMaps(Vec<NamedAddressMap>);
 
 impl NamedAddressMaps {
@@ -174,6 +177,11 @@ pub type AttributeDeriver = dyn Fn(&mut CompilationEnv, &mut ModuleDefinition);
 pub struct CompilationEnv {
     flags: Flags,
     diags: Diagnostics,
+    /// Warnings allowed with `#[allow(..)]`, by the location of the annotated item
+    allowed_warnings: Vec<(Loc, BTreeSet<Symbol>)>,
+    /// The names of the module members referred to by the source code that was filtered out, by
+    /// the location of the name of its module
+    filtered_uses: BTreeMap<Loc, BTreeSet<Symbol>>,
     // TODO(tzakian): Remove the global counter and use this counter instead
     // pub counter: u64,
 }
@@ -183,15 +191,74 @@ impl CompilationEnv {
         Self {
             flags,
             diags: Diagnostics::new(),
+            allowed_warnings: vec![],
+            filtered_uses: BTreeMap::new(),
         }
     }
 
     pub fn add_diag(&mut self, diag: Diagnostic) {
-        self.diags.add(diag)
+        if !self.is_allowed(&diag) {
+            self.diags.add(diag)
+        }
     }
 
     pub fn add_diags(&mut self, diags: Diagnostics) {
-        self.diags.extend(diags)
+        for diag in diags.into_vec() {
+            self.add_diag(diag)
+        }
+    }
+
+    /// Allows the named warnings for any diagnostic reported within `loc`
+    pub fn add_allowed_warnings(&mut self, loc: Loc, names: BTreeSet<Symbol>) {
+        if !names.is_empty() {
+            self.allowed_warnings.push((loc, names))
+        }
+    }
+
+    /// Records the names of the module members referred to by source code of the module named at
+    /// `module_name_loc` that was filtered out, e.g. test code when not compiling for tests
+    pub fn add_filtered_uses(&mut self, module_name_loc: Loc, names: BTreeSet<Symbol>) {
+        self.filtered_uses
+            .entry(module_name_loc)
+            .or_default()
+            .extend(names)
+    }
+
+    /// Returns true if filtered out code of the module named at `module_name_loc` refers to `name`
+    pub fn is_used_in_filtered_module_code(&self, module_name_loc: Loc, name: Symbol) -> bool {
+        self.filtered_uses
+            .get(&module_name_loc)
+            .map_or(false, |names| names.contains(&name))
+    }
+
+    /// The names of the module members referred to by the source code that was filtered out, by
+    /// the location of the name of its module
+    pub fn filtered_uses(&self) -> &BTreeMap<Loc, BTreeSet<Symbol>> {
+        &self.filtered_uses
+    }
+
+    /// Returns true if any filtered out code refers to `name`
+    pub fn is_used_in_filtered_code(&self, name: Symbol) -> bool {
+        self.filtered_uses
+            .values()
+            .any(|names| names.contains(&name))
+    }
+
+    fn is_allowed(&self, diag: &Diagnostic) -> bool {
+        if diag.info().severity() != Severity::Warning {
+            return false;
+        }
+        let name = match linters::warning_name(diag.info()) {
+            None => return false,
+            Some(name) => name,
+        };
+        let loc = diag.primary_loc();
+        self.allowed_warnings.iter().any(|(allowed_loc, names)| {
+            allowed_loc.file_hash() == loc.file_hash()
+                && allowed_loc.start() <= loc.start()
+                && loc.end() <= allowed_loc.end()
+                && names.contains(&Symbol::from(name))
+        })
     }
 
     pub fn has_warnings_or_errors(&self) -> bool {
@@ -312,6 +379,14 @@ pub struct Flags {
     )]
     shadow: bool,
 
+    /// Run the linters over the compiled sources, reporting their findings as warnings
+    #[clap(long = cli::LINT)]
+    lint: bool,
+
+    /// The format in which diagnostics are reported
+    #[clap(long = cli::DIAGNOSTICS_FORMAT, arg_enum, default_value = "human")]
+    diagnostics_format: DiagnosticsFormat,
+
     /// Internal flag used by the model builder to maintain functions which would be otherwise
     /// included only in tests, without creating the unit test code regular tests do.
     #[clap(skip)]
@@ -326,6 +401,8 @@ impl Flags {
             shadow: false,
             flavor: "".to_string(),
             bytecode_version: None,
+            lint: false,
+            diagnostics_format: DiagnosticsFormat::Human,
             keep_testing_functions: false,
         }
     }
@@ -337,6 +414,8 @@ impl Flags {
             shadow: false,
             flavor: "".to_string(),
             bytecode_version: None,
+            lint: false,
+            diagnostics_format: DiagnosticsFormat::Human,
             keep_testing_functions: false,
         }
     }
@@ -348,6 +427,8 @@ impl Flags {
             shadow: true, // allows overlapping between sources and deps
             flavor: "".to_string(),
             bytecode_version: None,
+            lint: false,
+            diagnostics_format: DiagnosticsFormat::Human,
             keep_testing_functions: false,
         }
     }
@@ -373,6 +454,20 @@ impl Flags {
         }
     }
 
+    pub fn set_lint(self, value: bool) -> Self {
+        Self {
+            lint: value,
+            ..self
+        }
+    }
+
+    pub fn set_diagnostics_format(self, value: DiagnosticsFormat) -> Self {
+        Self {
+            diagnostics_format: value,
+            ..self
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self == &Self::empty()
     }
@@ -400,6 +495,14 @@ impl Flags {
     pub fn bytecode_version(&self) -> Option<u32> {
         self.bytecode_version
     }
+
+    pub fn is_linting(&self) -> bool {
+        self.lint
+    }
+
+    pub fn diagnostics_format(&self) -> DiagnosticsFormat {
+        self.diagnostics_format
+    }
 }
 
 //**************************************************************************************************
@@ -408,6 +511,7 @@ impl Flags {
 
 pub mod known_attributes {
     use once_cell::sync::Lazy;
+    use serde::Serialize;
     use std::{collections::BTreeSet, fmt};
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -423,14 +527,17 @@ pub mod known_attributes {
         Spec,
     }
 
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
     pub enum KnownAttribute {
         Testing(TestingAttribute),
         Verification(VerificationAttribute),
         Native(NativeAttribute),
+        Syntax(SyntaxAttribute),
+        Lint(LintAttribute),
+        Deprecation(DeprecationAttribute),
     }
 
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
     pub enum TestingAttribute {
         // Can be called by other testing code, and included in compilation in test mode
         TestOnly,
@@ -440,18 +547,36 @@ pub mod known_attributes {
         ExpectedFailure,
     }
 
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
     pub enum VerificationAttribute {
         // The associated AST node will be included in the compilation in prove mode
         VerifyOnly,
     }
 
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
     pub enum NativeAttribute {
         // It is a fake native function that actually compiles to a bytecode instruction
         BytecodeInstruction,
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+    pub enum SyntaxAttribute {
+        // The function implements a piece of syntax for its type, e.g. 'syntax(index)'
+        Syntax,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+    pub enum LintAttribute {
+        // Allows the listed warnings within the item, e.g. 'allow(unused_variable)'
+        Allow,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+    pub enum DeprecationAttribute {
+        // Uses of the item from other modules are warned about, e.g. 'deprecated(note = b"..")'
+        Deprecated,
+    }
+
     impl fmt::Display for AttributePosition {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -482,6 +607,11 @@ pub mod known_attributes {
                 NativeAttribute::BYTECODE_INSTRUCTION => {
                     Self::Native(NativeAttribute::BytecodeInstruction)
                 }
+                SyntaxAttribute::SYNTAX => Self::Syntax(SyntaxAttribute::Syntax),
+                LintAttribute::ALLOW => Self::Lint(LintAttribute::Allow),
+                DeprecationAttribute::DEPRECATED => {
+                    Self::Deprecation(DeprecationAttribute::Deprecated)
+                }
                 _ => return None,
             })
         }
@@ -491,6 +621,9 @@ pub mod known_attributes {
                 Self::Testing(a) => a.name(),
                 Self::Verification(a) => a.name(),
                 Self::Native(a) => a.name(),
+                Self::Syntax(a) => a.name(),
+                Self::Lint(a) => a.name(),
+                Self::Deprecation(a) => a.name(),
             }
         }
 
@@ -499,6 +632,9 @@ pub mod known_attributes {
                 Self::Testing(a) => a.expected_positions(),
                 Self::Verification(a) => a.expected_positions(),
                 Self::Native(a) => a.expected_positions(),
+                Self::Syntax(a) => a.expected_positions(),
+                Self::Lint(a) => a.expected_positions(),
+                Self::Deprecation(a) => a.expected_positions(),
             }
         }
     }
@@ -603,4 +739,76 @@ pub mod known_attributes {
             }
         }
     }
+    impl SyntaxAttribute {
+        pub const SYNTAX: &'static str = "syntax";
+        pub const INDEX: &'static str = "index";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                SyntaxAttribute::Syntax => Self::SYNTAX,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static SYNTAX_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+                Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
+            match self {
+                SyntaxAttribute::Syntax => &SYNTAX_POSITIONS,
+            }
+        }
+    }
+
+    impl LintAttribute {
+        pub const ALLOW: &'static str = "allow";
+        // Groups the names of lints, e.g. 'allow(lint(self_assignment))'
+        pub const LINT: &'static str = "lint";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                LintAttribute::Allow => Self::ALLOW,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static ALLOW_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::Module,
+                    AttributePosition::Script,
+                    AttributePosition::Constant,
+                    AttributePosition::Struct,
+                    AttributePosition::Function,
+                ])
+                .collect()
+            });
+            match self {
+                LintAttribute::Allow => &ALLOW_POSITIONS,
+            }
+        }
+    }
+
+    impl DeprecationAttribute {
+        pub const DEPRECATED: &'static str = "deprecated";
+        // The explanation shown along with the warnings, e.g. 'deprecated(note = b"use g")'
+        pub const NOTE: &'static str = "note";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                DeprecationAttribute::Deprecated => Self::DEPRECATED,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static DEPRECATED_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::Constant,
+                    AttributePosition::Struct,
+                    AttributePosition::Function,
+                ])
+                .collect()
+            });
+            match self {
+                DeprecationAttribute::Deprecated => &DEPRECATED_POSITIONS,
+            }
+        }
+    }
 }
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use serde::{Serialize, Serializer};
 use std::{collections::BTreeMap, fmt::Debug, iter::IntoIterator};
 
 //**************************************************************************************************
@@ -238,6 +239,17 @@ where
     }
 }
 
+//**************************************************************************************************
+// Serialize
+//**************************************************************************************************
+
+/// Serialized as a sequence of key-value pairs, as keys are not necessarily strings
+impl<K: TName + Serialize, V: Serialize> Serialize for UniqueMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.key_cloned_iter())
+    }
+}
+
 //**************************************************************************************************
 // IntoIter
 //**************************************************************************************************
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{unique_map::UniqueMap, *};
+use serde::{Serialize, Serializer};
 use std::{cmp::Ordering, fmt::Debug, iter::IntoIterator};
 
 /// Unique set wrapper around `UniqueMap` where the value of the map is not needed
@@ -150,6 +151,16 @@ where
     }
 }
 
+//**************************************************************************************************
+// Serialize
+//**************************************************************************************************
+
+impl<T: TName + Serialize> Serialize for UniqueSet<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.cloned_iter())
+    }
+}
+
 //**************************************************************************************************
 // IntoIter
 //**************************************************************************************************
@@ -19,15 +19,15 @@ use crate::{
         fake_natives,
     },
     parser::ast::{
-        Ability, Ability_, BinOp, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp,
-        UnaryOp_, Var,
+        bytecode_field_name, Ability, Ability_, BinOp, BinOp_, ConstantName, Field, FunctionName,
+        StructName, UnaryOp, UnaryOp_, Var, VariantName,
     },
     shared::{unique_map::UniqueMap, *},
     FullyCompiledProgram,
 };
 use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
-use move_core_types::account_address::AccountAddress as MoveAddress;
+use move_core_types::{account_address::AccountAddress as MoveAddress, value::MoveValue};
 use move_ir_types::{ast as IR, location::*};
 use move_symbol_pool::Symbol;
 use std::{
@@ -186,6 +186,7 @@ fn module(
         .into_iter()
         .map(|(s, sdef)| struct_def(&mut context, &ident, s, sdef))
         .collect();
+    let assertions = assertions(&mdef.constants);
     let constants = mdef
         .constants
         .into_iter()
@@ -252,17 +253,18 @@ fn module(
         synthetics: vec![],
     };
     let deps: Vec<&F::CompiledModule> = vec![];
-    let (module, source_map) = match move_ir_to_bytecode::compiler::compile_module(ir_module, deps)
-    {
-        Ok(res) => res,
-        Err(e) => {
-            compilation_env.add_diag(diag!(
-                Bug::BytecodeGeneration,
-                (ident_loc, format!("IR ERROR: {}", e))
-            ));
-            return None;
-        }
-    };
+    let (module, mut source_map) =
+        match move_ir_to_bytecode::compiler::compile_module(ir_module, deps) {
+            Ok(res) => res,
+            Err(e) => {
+                compilation_env.add_diag(diag!(
+                    Bug::BytecodeGeneration,
+                    (ident_loc, format!("IR ERROR: {}", e))
+                ));
+                return None;
+            }
+        };
+    add_assertion_mappings(&mut source_map, assertions);
     let function_infos = module_function_infos(&module, &source_map, &collected_function_infos);
     let module = NamedCompiledModule {
         package_name: mdef.package_name,
@@ -300,6 +302,7 @@ fn script(
     let loc = name.loc();
     let mut context = Context::new(compilation_env, None);
 
+    let assertions = assertions(&constants);
     let constants = constants
         .into_iter()
         .map(|(n, c)| constant(&mut context, None, n, c))
@@ -320,17 +323,18 @@ fn script(
         main,
     };
     let deps: Vec<&F::CompiledModule> = vec![];
-    let (script, source_map) = match move_ir_to_bytecode::compiler::compile_script(ir_script, deps)
-    {
-        Ok(res) => res,
-        Err(e) => {
-            compilation_env.add_diag(diag!(
-                Bug::BytecodeGeneration,
-                (loc, format!("IR ERROR: {}", e))
-            ));
-            return None;
-        }
-    };
+    let (script, mut source_map) =
+        match move_ir_to_bytecode::compiler::compile_script(ir_script, deps) {
+            Ok(res) => res,
+            Err(e) => {
+                compilation_env.add_diag(diag!(
+                    Bug::BytecodeGeneration,
+                    (loc, format!("IR ERROR: {}", e))
+                ));
+                return None;
+            }
+        };
+    add_assertion_mappings(&mut source_map, assertions);
     let function_info = script_function_info(&source_map, info);
     let script = NamedCompiledScript {
         package_name,
@@ -518,6 +522,19 @@ fn struct_fields(
                 .collect();
             IRF::Move { fields }
         }
+        HF::Variants(variants) => {
+            let variants = variants
+                .into_iter()
+                .map(|(v, field_vec)| {
+                    let fields = field_vec
+                        .into_iter()
+                        .map(|(f, ty)| (field(f), base_type(context, ty)))
+                        .collect();
+                    (variant(v), fields)
+                })
+                .collect();
+            IRF::Variants { variants }
+        }
     }
 }
 
@@ -541,6 +558,33 @@ fn constant(
     }
 }
 
+// The abort codes, names, messages and locations of the assertions with messages, whose abort
+// codes are declared by the constants generated for them
+fn assertions(
+    constants: &UniqueMap<ConstantName, G::Constant>,
+) -> Vec<(u64, IR::ConstantName, String, Loc)> {
+    constants
+        .key_cloned_iter()
+        .filter_map(|(n, c)| match (&c.assertion_message, &c.value) {
+            (Some(message), Some(MoveValue::U64(code))) => {
+                Some((*code, IR::ConstantName(n.0.value), message.clone(), c.loc))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn add_assertion_mappings(
+    source_map: &mut SourceMap,
+    assertions: Vec<(u64, IR::ConstantName, String, Loc)>,
+) {
+    for (code, name, message, loc) in assertions {
+        source_map
+            .add_assertion_mapping(code, name, message, loc)
+            .expect("ICE the abort codes of assertions are unique")
+    }
+}
+
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
@@ -605,7 +649,9 @@ fn function(
 fn visibility(v: Visibility) -> IR::FunctionVisibility {
     match v {
         Visibility::Public(_) => IR::FunctionVisibility::Public,
-        Visibility::Friend(_) => IR::FunctionVisibility::Friend,
+        // 'public(package)' functions are lowered to 'friend' functions. The other modules of
+        // the package that call them are added as friends during typing and inlining
+        Visibility::Friend(_) | Visibility::Package(_) => IR::FunctionVisibility::Friend,
         Visibility::Internal => IR::FunctionVisibility::Internal,
     }
 }
@@ -730,7 +776,11 @@ fn var(v: Var) -> IR::Var {
 }
 
 fn field(f: Field) -> IR::Field {
-    sp(f.0.loc, IR::Field_(f.0.value))
+    sp(f.0.loc, IR::Field_(bytecode_field_name(f.0.value)))
+}
+
+fn variant(v: VariantName) -> IR::VariantName {
+    IR::VariantName(v.value())
 }
 
 fn struct_definition_name(
@@ -907,7 +957,7 @@ fn command(context: &mut Context, code: &mut IR::BytecodeBlock, sp!(loc, cmd_):
             code.push(sp(loc, B::BrFalse(label(if_false))));
             code.push(sp(loc, B::Branch(label(if_true))));
         }
-        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        C::Break(_) | C::Continue(_) => panic!("ICE break/continue not translated to jumps"),
     }
 }
 
@@ -948,6 +998,16 @@ fn lvalue(context: &mut Context, code: &mut IR::BytecodeBlock, sp!(loc, l_): H::
 
             lvalues_(context, code, field_ls.into_iter().map(|(_, l)| l));
         }
+
+        L::UnpackVariant(s, v, tys, field_ls) => {
+            let n = context.struct_definition_name(context.current_module().unwrap(), s);
+            code.push(sp(
+                loc,
+                B::UnpackVariant(n, variant(v), base_types(context, tys)),
+            ));
+
+            lvalues_(context, code, field_ls.into_iter().map(|(_, l)| l));
+        }
     }
 }
 
@@ -1058,6 +1118,23 @@ fn exp_(context: &mut Context, code: &mut IR::BytecodeBlock, e: H::Exp) {
             code.push(sp(loc, B::Pack(n, base_types(context, tys))))
         }
 
+        E::PackVariant(s, v, tys, field_args) => {
+            for (_, _, earg) in field_args {
+                exp_(context, code, earg);
+            }
+            let n = context.struct_definition_name(context.current_module().unwrap(), s);
+            code.push(sp(
+                loc,
+                B::PackVariant(n, variant(v), base_types(context, tys)),
+            ))
+        }
+
+        E::TestVariant(er, v) => {
+            let (n, tys) = struct_definition_name(context, er.ty.clone());
+            exp(context, code, er);
+            code.push(sp(loc, B::TestVariant(n, variant(v), tys)));
+        }
+
         E::Vector(_, n, bt, args) => {
             let ty = base_type(context, *bt);
             exp(context, code, args);
@@ -1084,6 +1161,17 @@ fn exp_(context: &mut Context, code: &mut IR::BytecodeBlock, e: H::Exp) {
             code.push(sp(loc, instr));
         }
 
+        E::BorrowVariantField(mut_, el, v, f) => {
+            let (n, tys) = struct_definition_name(context, el.ty.clone());
+            exp(context, code, el);
+            let instr = if mut_ {
+                B::MutBorrowVariantField(n, variant(v), tys, field(f))
+            } else {
+                B::ImmBorrowVariantField(n, variant(v), tys, field(f))
+            };
+            code.push(sp(loc, instr));
+        }
+
         E::BorrowLocal(mut_, v) => {
             let instr = if mut_ {
                 B::MutBorrowLoc(var(v))
@@ -6,12 +6,14 @@ use crate::{
     expansion::ast::{Attributes, Fields, Friend, ModuleIdent, SpecId, Value, Visibility},
     naming::ast::{FunctionSignature, StructDefinition, Type, TypeName_, Type_},
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        BinOp, BlockLabel, ConstantName, Field, FunctionName, StructName, UnaryOp, Var,
+        VariantName, ENTRY_MODIFIER, INLINE_MODIFIER,
     },
-    shared::{ast_debug::*, unique_map::UniqueMap},
+    shared::{ast_debug::*, ast_dump::serialize_map_as_seq, unique_map::UniqueMap},
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, VecDeque},
     fmt,
@@ -21,7 +23,7 @@ use std::{
 // Program
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub modules: UniqueMap<ModuleIdent, ModuleDefinition>,
     pub scripts: BTreeMap<Symbol, Script>,
@@ -31,7 +33,7 @@ pub struct Program {
 // Scripts
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Script {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -46,7 +48,7 @@ pub struct Script {
 // Modules
 //**************************************************************************************************
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModuleDefinition {
     // package name metadata from compiler arguments, not used for any language rules
     pub package_name: Option<Symbol>,
@@ -64,19 +66,21 @@ pub struct ModuleDefinition {
 // Functions
 //**************************************************************************************************
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub enum FunctionBody_ {
     Defined(Sequence),
     Native,
 }
 pub type FunctionBody = Spanned<FunctionBody_>;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: bool,
     pub signature: FunctionSignature,
+    #[serde(serialize_with = "serialize_map_as_seq")]
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
 }
@@ -85,19 +89,21 @@ pub struct Function {
 // Constants
 //**************************************************************************************************
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
 pub struct Constant {
     pub attributes: Attributes,
     pub loc: Loc,
     pub signature: Type,
     pub value: Exp,
+    // the message of the assertion the constant is the abort code of, if generated for one
+    pub assertion_message: Option<String>,
 }
 
 //**************************************************************************************************
 // Expressions
 //**************************************************************************************************
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum LValue_ {
     Ignore,
@@ -115,17 +121,35 @@ pub type LValue = Spanned<LValue_>;
 pub type LValueList_ = Vec<LValue>;
 pub type LValueList = Spanned<LValueList_>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum MatchPattern_ {
+    Wildcard,
+    Variant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Vec<Type>,
+        Fields<(Type, LValue)>,
+    ),
+}
+pub type MatchPattern = Spanned<MatchPattern_>;
+
+pub type MatchArm_ = (MatchPattern, Exp);
+pub type MatchArm = Spanned<MatchArm_>;
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct ModuleCall {
     pub module: ModuleIdent,
     pub name: FunctionName,
     pub type_arguments: Vec<Type>,
     pub arguments: Box<Exp>,
     pub parameter_types: Vec<Type>,
+    #[serde(serialize_with = "serialize_map_as_seq")]
     pub acquires: BTreeMap<StructName, Loc>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum BuiltinFunction_ {
     MoveTo(Type),
@@ -137,35 +161,60 @@ pub enum BuiltinFunction_ {
 }
 pub type BuiltinFunction = Spanned<BuiltinFunction_>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum UnannotatedExp_ {
-    Unit { trailing: bool },
+    Unit {
+        trailing: bool,
+    },
     Value(Value),
-    Move { from_user: bool, var: Var },
-    Copy { from_user: bool, var: Var },
+    Move {
+        from_user: bool,
+        var: Var,
+    },
+    Copy {
+        from_user: bool,
+        var: Var,
+    },
     Use(Var),
     Constant(Option<ModuleIdent>, ConstantName),
 
     ModuleCall(Box<ModuleCall>),
     Builtin(Box<BuiltinFunction>, Box<Exp>),
+    // Call of a function typed parameter of an inline function
+    VarCall(Var, Box<Exp>),
     Vector(Loc, usize, Box<Type>, Box<Exp>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
-    While(Box<Exp>, Box<Exp>),
-    Loop { has_break: bool, body: Box<Exp> },
+    While(Option<BlockLabel>, Box<Exp>, Box<Exp>),
+    Loop {
+        label: Option<BlockLabel>,
+        has_break: bool,
+        body: Box<Exp>,
+    },
+    // The variant patterns bind references to the fields if the subject is a reference
+    Match(Box<Exp>, Vec<MatchArm>),
     Block(Sequence),
+    // Only valid as an argument to an inline function. Removed when inlining
+    Lambda(LValueList, Vec<Option<Type>>, Box<Exp>),
     Assign(LValueList, Vec<Option<Type>>, Box<Exp>),
     Mutate(Box<Exp>, Box<Exp>),
     Return(Box<Exp>),
     Abort(Box<Exp>),
-    Break,
-    Continue,
+    Break(Option<BlockLabel>),
+    Continue(Option<BlockLabel>),
 
     Dereference(Box<Exp>),
     UnaryExp(UnaryOp, Box<Exp>),
     BinopExp(Box<Exp>, BinOp, Box<Type>, Box<Exp>),
 
     Pack(ModuleIdent, StructName, Vec<Type>, Fields<(Type, Exp)>),
+    PackVariant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Vec<Type>,
+        Fields<(Type, Exp)>,
+    ),
     ExpList(Vec<ExpListItem>),
 
     Borrow(bool, Box<Exp>, Field),
@@ -175,12 +224,15 @@ pub enum UnannotatedExp_ {
     Cast(Box<Exp>, Box<Type>),
     Annotate(Box<Exp>, Box<Type>),
 
-    Spec(SpecId, BTreeMap<Var, Type>),
+    Spec(
+        SpecId,
+        #[serde(serialize_with = "serialize_map_as_seq")] BTreeMap<Var, Type>,
+    ),
 
     UnresolvedError,
 }
 pub type UnannotatedExp = Spanned<UnannotatedExp_>;
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Exp {
     pub ty: Type,
     pub exp: UnannotatedExp,
@@ -190,7 +242,7 @@ pub fn exp(ty: Type, exp: UnannotatedExp) -> Exp {
 }
 
 pub type Sequence = VecDeque<SequenceItem>;
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum SequenceItem_ {
     Seq(Box<Exp>),
     Declare(LValueList),
@@ -198,7 +250,7 @@ pub enum SequenceItem_ {
 }
 pub type SequenceItem = Spanned<SequenceItem_>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum ExpListItem {
     Single(Exp, Box<Type>),
     Splat(Loc, Exp, Vec<Type>),
@@ -341,6 +393,7 @@ impl AstDebug for (FunctionName, &Function) {
                 attributes,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -351,6 +404,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if *inline {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -377,6 +433,7 @@ impl AstDebug for (ConstantName, &Constant) {
                 loc: _loc,
                 signature,
                 value,
+                assertion_message,
             },
         ) = self;
         attributes.ast_debug(w);
@@ -385,6 +442,9 @@ impl AstDebug for (ConstantName, &Constant) {
         w.write(" = ");
         value.ast_debug(w);
         w.write(";");
+        if let Some(message) = assertion_message {
+            w.write(&format!(" /* assertion message: {:?} */", message));
+        }
     }
 }
 
@@ -453,6 +513,11 @@ impl AstDebug for UnannotatedExp_ {
                 rhs.ast_debug(w);
                 w.write(")");
             }
+            E::VarCall(v, rhs) => {
+                w.write(&format!("{}(", v));
+                rhs.ast_debug(w);
+                w.write(")");
+            }
             E::Vector(_loc, usize, ty, elems) => {
                 w.write(format!("vector#{}", usize));
                 w.write("<");
@@ -477,6 +542,21 @@ impl AstDebug for UnannotatedExp_ {
                 });
                 w.write("}");
             }
+            E::PackVariant(m, s, v, tys, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_bt_e)| {
+                    let (idx, (bt, e)) = idx_bt_e;
+                    w.write(&format!("({}#{}:", idx, f));
+                    bt.ast_debug(w);
+                    w.write("): ");
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
             E::IfElse(b, t, f) => {
                 w.write("if (");
                 b.ast_debug(w);
@@ -485,13 +565,19 @@ impl AstDebug for UnannotatedExp_ {
                 w.write(" else ");
                 f.ast_debug(w);
             }
-            E::While(b, e) => {
+            E::While(label, b, e) => {
+                label.ast_debug(w);
                 w.write("while (");
                 b.ast_debug(w);
                 w.write(")");
                 e.ast_debug(w);
             }
-            E::Loop { has_break, body } => {
+            E::Loop {
+                label,
+                has_break,
+                body,
+            } => {
+                label.ast_debug(w);
                 w.write("loop");
                 if *has_break {
                     w.write("#with_break");
@@ -499,7 +585,27 @@ impl AstDebug for UnannotatedExp_ {
                 w.write(" ");
                 body.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, rhs))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        rhs.ast_debug(w);
+                    })
+                });
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
+            E::Lambda(sp!(_, lvalues), expected_types, body) => {
+                w.write("|");
+                lvalues.ast_debug(w);
+                w.write(": (");
+                expected_types.ast_debug(w);
+                w.write(")| ");
+                body.ast_debug(w);
+            }
             E::ExpList(es) => {
                 w.write("(");
                 w.comma(es, |w, e| e.ast_debug(w));
@@ -529,8 +635,18 @@ impl AstDebug for UnannotatedExp_ {
                 w.write("abort ");
                 e.ast_debug(w);
             }
-            E::Break => w.write("break"),
-            E::Continue => w.write("continue"),
+            E::Break(label) => {
+                w.write("break");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
+            E::Continue(label) => {
+                w.write("continue");
+                if let Some(label) = label {
+                    w.write(&format!(" {}", label))
+                }
+            }
             E::Dereference(e) => {
                 w.write("*");
                 e.ast_debug(w)
@@ -740,3 +856,25 @@ impl AstDebug for LValue_ {
         }
     }
 }
+
+impl AstDebug for MatchPattern_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            MatchPattern_::Wildcard => w.write("_"),
+            MatchPattern_::Variant(m, s, v, tys, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_bt_a)| {
+                    let (idx, (bt, a)) = idx_bt_a;
+                    w.annotate(|w| w.write(&format!("{}#{}", idx, f)), bt);
+                    w.write(": ");
+                    a.ast_debug(w);
+                });
+                w.write("}");
+            }
+        }
+    }
+}
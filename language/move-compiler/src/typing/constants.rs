@@ -0,0 +1,226 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolves references between constants. As the value of a constant is computed at compile time,
+//! it can refer to other constants, of the same module or of other modules. Each such reference
+//! is replaced by the value of the constant it refers to, after checking that no constant is
+//! defined in terms of itself. The resulting values are folded when the constants are compiled.
+
+use crate::{
+    diag,
+    expansion::ast::ModuleIdent,
+    parser::ast::ConstantName,
+    shared::{unique_map::UniqueMap, *},
+    typing::ast as T,
+    FullyCompiledProgram,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use petgraph::{algo::tarjan_scc as petgraph_scc, graphmap::DiGraphMap};
+use std::collections::BTreeMap;
+
+// A constant of a module, or of the script being resolved if the module is `None`
+type ConstantKey = (Option<ModuleIdent>, ConstantName);
+
+struct Context<'env> {
+    env: &'env mut CompilationEnv,
+    // the values of the constants whose references are resolved
+    resolved: BTreeMap<ConstantKey, T::Exp>,
+    // the values of the constants whose references are not resolved yet
+    unresolved: BTreeMap<ConstantKey, T::Exp>,
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn program(
+    compilation_env: &mut CompilationEnv,
+    pre_compiled_lib: Option<&FullyCompiledProgram>,
+    modules: &mut UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    scripts: &mut BTreeMap<Symbol, T::Script>,
+) {
+    // the constants of the pre-compiled library were resolved when it was compiled
+    let resolved = pre_compiled_lib
+        .iter()
+        .flat_map(|pre_compiled| pre_compiled.typing.modules.key_cloned_iter())
+        .filter(|(mident, _)| !modules.contains_key(mident))
+        .flat_map(|(mident, mdef)| {
+            mdef.constants
+                .key_cloned_iter()
+                .map(move |(cname, cdef)| ((Some(mident), cname), cdef.value.clone()))
+        })
+        .collect();
+    let unresolved = modules
+        .key_cloned_iter()
+        .flat_map(|(mident, mdef)| {
+            mdef.constants
+                .key_cloned_iter()
+                .map(move |(cname, cdef)| ((Some(mident), cname), cdef.value.clone()))
+        })
+        .collect();
+    let mut context = Context {
+        env: compilation_env,
+        resolved,
+        unresolved,
+    };
+    resolve_all(&mut context);
+    for (mloc, mident_, mdef) in modules.iter_mut() {
+        let mident = sp(mloc, *mident_);
+        for (cloc, cname_, cdef) in mdef.constants.iter_mut() {
+            let cname = ConstantName(sp(cloc, *cname_));
+            cdef.value = context.resolved[&(Some(mident), cname)].clone();
+        }
+    }
+
+    for script in scripts.values_mut() {
+        context.unresolved = script
+            .constants
+            .key_cloned_iter()
+            .map(|(cname, cdef)| ((None, cname), cdef.value.clone()))
+            .collect();
+        resolve_all(&mut context);
+        for (cloc, cname_, cdef) in script.constants.iter_mut() {
+            let cname = ConstantName(sp(cloc, *cname_));
+            cdef.value = context.resolved.remove(&(None, cname)).unwrap();
+        }
+    }
+}
+
+// Resolves the references of all unresolved constants, unless some of them form a cycle, in which
+// case they are left as they are
+fn resolve_all(context: &mut Context) {
+    let mut references: BTreeMap<ConstantKey, BTreeMap<ConstantKey, Loc>> = BTreeMap::new();
+    for (key, value) in &mut context.unresolved {
+        let neighbors = references.entry(*key).or_default();
+        constant_references(value, &mut |e| {
+            if let (T::UnannotatedExp_::Constant(m, c), loc) = (&e.exp.value, e.exp.loc) {
+                neighbors.entry((*m, *c)).or_insert(loc);
+            }
+        });
+    }
+    if has_cycles(context.env, &references) {
+        let unresolved = std::mem::take(&mut context.unresolved);
+        context.resolved.extend(unresolved);
+        return;
+    }
+    let keys = context.unresolved.keys().copied().collect::<Vec<_>>();
+    for key in keys {
+        resolve(context, key)
+    }
+}
+
+// Resolves the references of the constant `key`, after those of the constants it refers to
+fn resolve(context: &mut Context, key: ConstantKey) {
+    let mut value = match context.unresolved.remove(&key) {
+        Some(value) => value,
+        None => return,
+    };
+    constant_references(&mut value, &mut |e| {
+        let (m, c) = match &e.exp.value {
+            T::UnannotatedExp_::Constant(m, c) => (*m, *c),
+            _ => unreachable!(),
+        };
+        resolve(context, (m, c));
+        // The value keeps its locations, so that a failure to fold it is reported once, in the
+        // constant declaring it. It is missing if the constant could not be typed
+        if let Some(referenced) = context.resolved.get(&(m, c)) {
+            *e = referenced.clone();
+        }
+    });
+    context.resolved.insert(key, value);
+}
+
+//**************************************************************************************************
+// Cycles
+//**************************************************************************************************
+
+fn has_cycles(
+    env: &mut CompilationEnv,
+    references: &BTreeMap<ConstantKey, BTreeMap<ConstantKey, Loc>>,
+) -> bool {
+    let edges = references
+        .iter()
+        .flat_map(|(user, used)| used.keys().map(move |used| (user, used)));
+    let graph: DiGraphMap<&ConstantKey, ()> = DiGraphMap::from_edges(edges);
+    let mut has_cycles = false;
+    for scc in petgraph_scc(&graph) {
+        if scc.len() == 1 && !graph.contains_edge(scc[0], scc[0]) {
+            continue;
+        }
+        has_cycles = true;
+        let cycle = shortest_cycle(&graph, scc[0]);
+        let cycle_strings = cycle
+            .iter()
+            .map(|key| format!("'{}'", display_constant(key)))
+            .collect::<Vec<_>>()
+            .join(" uses ");
+        let (user, used) = if cycle.len() == 1 {
+            (cycle[0], cycle[0])
+        } else {
+            (cycle[cycle.len() - 2], cycle[cycle.len() - 1])
+        };
+        let msg = format!(
+            "Invalid reference to constant '{}' in the value of constant '{}'",
+            display_constant(used),
+            display_constant(user)
+        );
+        let cycle_msg = format!(
+            "Constants cannot be defined in terms of themselves. This reference creates a cycle: \
+             {}",
+            cycle_strings
+        );
+        // the location of the declaration of the constant
+        let (declared, _) = references.get_key_value(used).unwrap();
+        env.add_diag(diag!(
+            TypeSafety::CyclicConstant,
+            (references[user][used], msg),
+            (declared.1.loc(), cycle_msg),
+        ))
+    }
+    has_cycles
+}
+
+fn display_constant((m, c): &ConstantKey) -> String {
+    match m {
+        Some(m) => format!("{}::{}", m, c),
+        None => c.to_string(),
+    }
+}
+
+//**************************************************************************************************
+// References
+//**************************************************************************************************
+
+// Calls `f` on each reference to a constant in `e`. Only the expressions supported in constants
+// are visited
+fn constant_references(e: &mut T::Exp, f: &mut impl FnMut(&mut T::Exp)) {
+    use T::UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::Constant(_, _) => f(e),
+        E::Block(seq) => {
+            for sp!(_, item_) in seq {
+                if let T::SequenceItem_::Seq(e) = item_ {
+                    constant_references(e, f)
+                }
+            }
+        }
+        E::UnaryExp(_, e) | E::Cast(e, _) | E::Annotate(e, _) | E::Vector(_, _, _, e) => {
+            constant_references(e, f)
+        }
+        E::BinopExp(e1, _, _, e2) => {
+            constant_references(e1, f);
+            constant_references(e2, f)
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        constant_references(e, f)
+                    }
+                }
+            }
+        }
+        _ => (),
+    }
+}
@@ -2,19 +2,26 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use super::index_syntax::{self, IndexFunctions};
 use crate::{
     diag,
     diagnostics::{codes::NameResolution, Diagnostic},
     expansion::ast::{AbilitySet, ModuleIdent, Visibility},
-    naming::ast::{
-        self as N, BuiltinTypeName_, FunctionSignature, StructDefinition, StructTypeParameter,
-        TParam, TParamID, TVar, Type, TypeName, TypeName_, Type_,
+    naming::{
+        ast::{
+            self as N, BuiltinTypeName_, FunctionSignature, StructDefinition, StructTypeParameter,
+            TParam, TParamID, TVar, Type, TypeName, TypeName_, Type_,
+        },
+        translate::{deprecation, DeprecatedMember},
+    },
+    parser::ast::{
+        Ability_, BlockLabel, ConstantName, Field, FunctionName, StructName, Var, VariantName,
     },
-    parser::ast::{Ability_, ConstantName, Field, FunctionName, StructName, Var},
     shared::{unique_map::UniqueMap, *},
     FullyCompiledProgram,
 };
 use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 //**************************************************************************************************
@@ -40,8 +47,10 @@ pub type TParamSubst = HashMap<TParamID, Type>;
 pub struct FunctionInfo {
     pub defined_loc: Loc,
     pub visibility: Visibility,
+    pub inline: bool,
     pub signature: FunctionSignature,
     pub acquires: BTreeMap<StructName, Loc>,
+    pub deprecation: Option<DeprecatedMember>,
 }
 
 pub struct ConstantInfo {
@@ -50,19 +59,15 @@ pub struct ConstantInfo {
 }
 
 pub struct ModuleInfo {
+    pub package: Option<Symbol>,
     pub friends: UniqueMap<ModuleIdent, Loc>,
     pub structs: UniqueMap<StructName, StructDefinition>,
     pub functions: UniqueMap<FunctionName, FunctionInfo>,
     pub constants: UniqueMap<ConstantName, ConstantInfo>,
 }
 
-pub struct LoopInfo(LoopInfo_);
-
-enum LoopInfo_ {
-    NotInLoop,
-    BreakTypeUnknown,
-    BreakType(Box<Type>),
-}
+/// The enclosing loops, innermost last, with their labels and break types, if any
+pub struct LoopInfo(Vec<(Option<BlockLabel>, Option<Type>)>);
 
 pub struct Context<'env> {
     pub modules: UniqueMap<ModuleIdent, ModuleInfo>,
@@ -70,7 +75,15 @@ pub struct Context<'env> {
 
     pub current_module: Option<ModuleIdent>,
     pub current_function: Option<FunctionName>,
+    pub current_function_inline: bool,
+    /// Whether the value of a constant is being typed. Such values can refer to the constants of
+    /// other modules, as they are computed at compile time
+    pub in_constant: bool,
     pub current_script_constants: Option<UniqueMap<ConstantName, ConstantInfo>>,
+    /// The 'use fun' aliases of the current module
+    pub use_funs: N::UseFuns,
+    /// The '#[syntax(index)]' functions of all modules, by the type they index
+    index_functions: BTreeMap<TypeName_, IndexFunctions>,
     pub return_type: Option<Type>,
     locals: UniqueMap<Var, Type>,
 
@@ -78,6 +91,13 @@ pub struct Context<'env> {
     pub constraints: Constraints,
 
     loop_info: LoopInfo,
+    in_lambda: bool,
+
+    /// Calls between inline functions of the current module, used to detect recursive inlining
+    pub inline_calls: BTreeMap<FunctionName, BTreeMap<FunctionName, Loc>>,
+    /// For each module, the other modules of its package that call its 'public(package)'
+    /// functions. These become 'friend' declarations in the compiled module
+    pub package_friends: BTreeMap<ModuleIdent, BTreeMap<ModuleIdent, Loc>>,
 }
 
 impl<'env> Context<'env> {
@@ -101,14 +121,17 @@ impl<'env> Context<'env> {
             let functions = mdef.functions.ref_map(|fname, fdef| FunctionInfo {
                 defined_loc: fname.loc(),
                 visibility: fdef.visibility.clone(),
+                inline: fdef.inline,
                 signature: fdef.signature.clone(),
                 acquires: fdef.acquires.clone(),
+                deprecation: deprecation(None, "function", &fdef.attributes),
             });
             let constants = mdef.constants.ref_map(|cname, cdef| ConstantInfo {
                 defined_loc: cname.loc(),
                 signature: cdef.signature.clone(),
             });
             let minfo = ModuleInfo {
+                package: mdef.package_name,
                 friends: mdef.friends.ref_map(|_, friend| friend.loc),
                 structs,
                 functions,
@@ -117,15 +140,23 @@ impl<'env> Context<'env> {
             (mident, minfo)
         }))
         .unwrap();
+        let index_functions = index_syntax::index_functions(env, pre_compiled_lib, prog);
         Context {
             subst: Subst::empty(),
             current_module: None,
             current_function: None,
+            current_function_inline: false,
+            in_constant: false,
             current_script_constants: None,
+            use_funs: N::UseFuns::new(),
+            index_functions,
             return_type: None,
             constraints: vec![],
             locals: UniqueMap::new(),
-            loop_info: LoopInfo(LoopInfo_::NotInLoop),
+            loop_info: LoopInfo(vec![]),
+            in_lambda: false,
+            inline_calls: BTreeMap::new(),
+            package_friends: BTreeMap::new(),
             modules,
             env,
         }
@@ -133,7 +164,7 @@ impl<'env> Context<'env> {
 
     pub fn reset_for_module_item(&mut self) {
         assert!(
-            matches!(&self.loop_info, LoopInfo(LoopInfo_::NotInLoop)),
+            self.loop_info.0.is_empty(),
             "ICE loop_info should be reset after the loop"
         );
         self.return_type = None;
@@ -141,6 +172,8 @@ impl<'env> Context<'env> {
         self.subst = Subst::empty();
         self.constraints = Constraints::new();
         self.current_function = None;
+        self.current_function_inline = false;
+        self.in_constant = false;
     }
 
     pub fn bind_script_constants(&mut self, constants: &UniqueMap<ConstantName, N::Constant>) {
@@ -237,6 +270,11 @@ impl<'env> Context<'env> {
         }
     }
 
+    /// Returns true if the local is a function typed parameter of an inline function
+    pub fn is_function_local(&self, var: &Var) -> bool {
+        matches!(self.locals.get(var), Some(sp!(_, Type_::Fun(_, _))))
+    }
+
     pub fn save_locals_scope(&self) -> UniqueMap<Var, Type> {
         self.locals.clone()
     }
@@ -275,6 +313,17 @@ impl<'env> Context<'env> {
         self.is_current_module(m) && matches!(&self.current_function, Some(curf) if curf == f)
     }
 
+    // Warns about a call of the deprecated function `m::f` from another module. Calls written
+    // as 'm::f(...)' are checked in naming, this is for the calls resolved during typing
+    pub fn check_deprecated_call(&mut self, loc: Loc, m: &ModuleIdent, f: &FunctionName) {
+        if self.is_current_module(m) {
+            return;
+        }
+        if let Some(deprecation) = self.function_info(m, f).deprecation.clone() {
+            deprecation.report_use(self.env, loc, m, f)
+        }
+    }
+
     fn current_module_is_a_friend_of(&self, m: &ModuleIdent) -> bool {
         match &self.current_module {
             None => false,
@@ -285,6 +334,18 @@ impl<'env> Context<'env> {
         }
     }
 
+    // 'public(package)' functions are compiled to 'public(friend)' functions, so they can only be
+    // called from modules of the same package that are also at the same address
+    fn current_module_shares_package_with(&self, m: &ModuleIdent) -> bool {
+        match &self.current_module {
+            None => false,
+            Some(current_mident) => {
+                current_mident.value.address == m.value.address
+                    && self.module_info(current_mident).package == self.module_info(m).package
+            }
+        }
+    }
+
     fn module_info(&self, m: &ModuleIdent) -> &ModuleInfo {
         self.modules
             .get(m)
@@ -315,6 +376,26 @@ impl<'env> Context<'env> {
         &self.struct_definition(m, n).type_parameters
     }
 
+    pub fn is_inline_function(&self, m: &ModuleIdent, n: &FunctionName) -> bool {
+        self.function_info(m, n).inline
+    }
+
+    /// The parameters of 'm::n', or None if there is no such function. Unlike other lookups, the
+    /// function is not known to exist, as method calls are only resolved during typing
+    pub fn function_parameters_opt(
+        &self,
+        m: &ModuleIdent,
+        n: &FunctionName,
+    ) -> Option<&Vec<(Var, Type)>> {
+        let finfo = self.modules.get(m)?.functions.get(n)?;
+        Some(&finfo.signature.parameters)
+    }
+
+    /// The '#[syntax(index)]' functions declared for the type 'tn', if any
+    pub fn index_functions(&self, tn: &TypeName_) -> Option<&IndexFunctions> {
+        self.index_functions.get(tn)
+    }
+
     fn function_info(&self, m: &ModuleIdent, n: &FunctionName) -> &FunctionInfo {
         self.module_info(m)
             .functions
@@ -331,39 +412,60 @@ impl<'env> Context<'env> {
     }
 
     pub fn in_loop(&self) -> bool {
-        match &self.loop_info.0 {
-            LoopInfo_::NotInLoop => false,
-            LoopInfo_::BreakTypeUnknown | LoopInfo_::BreakType(_) => true,
-        }
+        !self.loop_info.0.is_empty()
+    }
+
+    // The loop targeted by a 'break' or 'continue' with the given label, or the innermost loop if
+    // there is no label
+    fn target_loop(&mut self, label: &Option<BlockLabel>) -> Option<&mut Option<Type>> {
+        let mut loops = self.loop_info.0.iter_mut().rev();
+        let (_, break_type) = match label {
+            None => loops.next()?,
+            Some(label) => loops.find(
+                |(loop_label, _)| matches!(loop_label, Some(l) if l.value() == label.value()),
+            )?,
+        };
+        Some(break_type)
     }
 
-    pub fn get_break_type(&self) -> Option<&Type> {
-        match &self.loop_info.0 {
-            LoopInfo_::NotInLoop | LoopInfo_::BreakTypeUnknown => None,
-            LoopInfo_::BreakType(t) => Some(t),
-        }
+    pub fn get_break_type(&mut self, label: &Option<BlockLabel>) -> Option<&Type> {
+        self.target_loop(label)?.as_ref()
     }
 
-    pub fn set_break_type(&mut self, t: Type) {
-        match &self.loop_info.0 {
-            LoopInfo_::NotInLoop => (),
-            LoopInfo_::BreakTypeUnknown | LoopInfo_::BreakType(_) => {
-                self.loop_info.0 = LoopInfo_::BreakType(Box::new(t))
-            }
+    pub fn set_break_type(&mut self, label: &Option<BlockLabel>, t: Type) {
+        if let Some(break_type) = self.target_loop(label) {
+            *break_type = Some(t)
         }
     }
 
-    pub fn enter_loop(&mut self) -> LoopInfo {
-        std::mem::replace(&mut self.loop_info, LoopInfo(LoopInfo_::BreakTypeUnknown))
+    pub fn enter_loop(&mut self, label: Option<BlockLabel>) {
+        self.loop_info.0.push((label, None))
     }
 
-    // Reset loop info and return the loop's break type, if it has one
-    pub fn exit_loop(&mut self, old_info: LoopInfo) -> Option<Type> {
-        match std::mem::replace(&mut self.loop_info, old_info).0 {
-            LoopInfo_::NotInLoop => panic!("ICE exit_loop called while not in a loop"),
-            LoopInfo_::BreakTypeUnknown => None,
-            LoopInfo_::BreakType(t) => Some(*t),
-        }
+    // Exit the innermost loop and return its break type, if it has one
+    pub fn exit_loop(&mut self) -> Option<Type> {
+        let (_, break_type) = self
+            .loop_info
+            .0
+            .pop()
+            .expect("ICE exit_loop called while not in a loop");
+        break_type
+    }
+
+    pub fn in_lambda(&self) -> bool {
+        self.in_lambda
+    }
+
+    // A lambda body cannot 'break' or 'continue' the loops surrounding it
+    pub fn enter_lambda(&mut self) -> (LoopInfo, bool) {
+        let old_loop_info = std::mem::replace(&mut self.loop_info, LoopInfo(vec![]));
+        let old_in_lambda = std::mem::replace(&mut self.in_lambda, true);
+        (old_loop_info, old_in_lambda)
+    }
+
+    pub fn exit_lambda(&mut self, (old_loop_info, old_in_lambda): (LoopInfo, bool)) {
+        self.loop_info = old_loop_info;
+        self.in_lambda = old_in_lambda;
     }
 }
 
@@ -487,6 +589,11 @@ fn error_format_impl_(b_: &Type_, subst: &Subst, nested: bool) -> String {
             };
             format!("{}{}", n, tys_str)
         }
+        Fun(args, result) => format!(
+            "|{}|{}",
+            format_comma(args.iter().map(|t| error_format_nested(t, subst))),
+            error_format_nested(result, subst)
+        ),
         Param(tp) => tp.user_specified_name.value.to_string(),
         Ref(mut_, ty) => format!(
             "&{}{}",
@@ -511,6 +618,7 @@ pub fn infer_abilities(context: &Context, subst: &Subst, ty: Type) -> AbilitySet
     match unfold_type(subst, ty).value {
         T::Unit => AbilitySet::collection(loc),
         T::Ref(_, _) => AbilitySet::references(loc),
+        T::Fun(_, _) => AbilitySet::empty(),
         T::Var(_) => unreachable!("ICE unfold_type failed, which is impossible"),
         T::UnresolvedError | T::Anything => AbilitySet::all(loc),
         T::Param(TParam { abilities, .. }) | T::Apply(Some(abilities), _, _) => abilities,
@@ -553,6 +661,7 @@ fn debug_abilities_info(context: &Context, ty: &Type) -> (Option<Loc>, AbilitySe
     let loc = ty.loc;
     match &ty.value {
         T::Unit | T::Ref(_, _) => (None, AbilitySet::references(loc), vec![]),
+        T::Fun(_, _) => (None, AbilitySet::empty(), vec![]),
         T::Var(_) => panic!("ICE call unfold_type before debug_abilities_info"),
         T::UnresolvedError | T::Anything => (None, AbilitySet::all(loc), vec![]),
         T::Param(TParam {
@@ -660,6 +769,31 @@ pub fn make_field_types(
                 (*idx, subst_tparams(tparam_subst, field_ty.clone()))
             }))
         }
+        N::StructFields::Variants(vm) => N::StructFields::Variants(vm.ref_map(|_, (vidx, m)| {
+            let m = m.ref_map(|_, (idx, field_ty)| {
+                (*idx, subst_tparams(tparam_subst, field_ty.clone()))
+            });
+            (*vidx, m)
+        })),
+    }
+}
+
+// Returns the variants of the enum in declaration order, or None if it is not an enum
+pub fn enum_variants(
+    context: &mut Context,
+    m: &ModuleIdent,
+    n: &StructName,
+) -> Option<Vec<VariantName>> {
+    match &context.struct_definition(m, n).fields {
+        N::StructFields::Variants(vm) => {
+            let mut variants = vm
+                .key_cloned_iter()
+                .map(|(v, (idx, _))| (*idx, v))
+                .collect::<Vec<_>>();
+            variants.sort_by_key(|(idx, _)| *idx);
+            Some(variants.into_iter().map(|(_, v)| v).collect())
+        }
+        N::StructFields::Defined(_) | N::StructFields::Native(_) => None,
     }
 }
 
@@ -684,6 +818,17 @@ pub fn make_field_type(
             ));
             return context.error_type(loc);
         }
+        N::StructFields::Variants(_) => {
+            let msg = format!(
+                "Invalid access of field '{}' on enum '{}::{}'. The fields of an enum variant can \
+                 only be accessed with a 'match' expression",
+                field, m, n
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundField, (loc, msg)));
+            return context.error_type(loc);
+        }
         N::StructFields::Defined(m) => m,
     };
     match fields_map.get(field).cloned() {
@@ -726,7 +871,7 @@ pub fn make_constant_type(
         } = context.constant_info(m, c);
         (*defined_loc, signature.clone())
     };
-    if !in_current_module {
+    if !in_current_module && !context.in_constant {
         let msg = match m {
             None => format!("Invalid access of '{}'", c),
             Some(mident) => format!("Invalid access of '{}::{}'", mident, c),
@@ -759,6 +904,89 @@ pub fn make_function_type(
     Vec<(Var, Type)>,
     BTreeMap<StructName, Loc>,
     Type,
+) {
+    let in_current_module = match &context.current_module {
+        Some(current) => m == current,
+        None => false,
+    };
+    let (defined_loc, ty_args, params, acquires, return_ty) =
+        instantiate_function_type(context, loc, m, f, ty_args_opt);
+    match context.function_info(m, f).visibility.clone() {
+        Visibility::Internal if in_current_module => (),
+        Visibility::Internal => {
+            let internal_msg = format!(
+                "This function is internal to its module. Only '{}', '{}', and '{}' functions \
+                 can be called outside of their module",
+                Visibility::PUBLIC,
+                Visibility::FRIEND,
+                Visibility::PACKAGE
+            );
+            context.env.add_diag(diag!(
+                TypeSafety::Visibility,
+                (loc, format!("Invalid call to '{}::{}'", m, f)),
+                (defined_loc, internal_msg),
+            ));
+        }
+        Visibility::Friend(_) if in_current_module || context.current_module_is_a_friend_of(m) => {}
+        Visibility::Friend(vis_loc) => {
+            let internal_msg = format!(
+                "This function can only be called from a 'friend' of module '{}'",
+                m
+            );
+            context.env.add_diag(diag!(
+                TypeSafety::Visibility,
+                (loc, format!("Invalid call to '{}::{}'", m, f)),
+                (vis_loc, internal_msg),
+            ));
+        }
+        Visibility::Package(_) if in_current_module => (),
+        Visibility::Package(_) if context.current_module_shares_package_with(m) => {
+            let current = context.current_module.unwrap();
+            context
+                .package_friends
+                .entry(*m)
+                .or_default()
+                .entry(current)
+                .or_insert(loc);
+        }
+        Visibility::Package(vis_loc) => {
+            let internal_msg = match context.module_info(m).package {
+                Some(package) => format!(
+                    "This function can only be called from modules at address '{}' in package \
+                     '{}'",
+                    m.value.address, package
+                ),
+                None => format!(
+                    "This function can only be called from modules at address '{}' in the same \
+                     package",
+                    m.value.address
+                ),
+            };
+            context.env.add_diag(diag!(
+                TypeSafety::Visibility,
+                (loc, format!("Invalid call to '{}::{}'", m, f)),
+                (vis_loc, internal_msg),
+            ));
+        }
+        Visibility::Public(_) => (),
+    };
+    (defined_loc, ty_args, params, acquires, return_ty)
+}
+
+/// Instantiates the signature of 'm::f' like `make_function_type`, but without checking that
+/// the function is visible from the current module
+pub fn instantiate_function_type(
+    context: &mut Context,
+    loc: Loc,
+    m: &ModuleIdent,
+    f: &FunctionName,
+    ty_args_opt: Option<Vec<Type>>,
+) -> (
+    Loc,
+    Vec<Type>,
+    Vec<(Var, Type)>,
+    BTreeMap<StructName, Loc>,
+    Type,
 ) {
     let in_current_module = match &context.current_module {
         Some(current) => m == current,
@@ -803,37 +1031,7 @@ pub fn make_function_type(
     } else {
         BTreeMap::new()
     };
-    let defined_loc = finfo.defined_loc;
-    match finfo.visibility {
-        Visibility::Internal if in_current_module => (),
-        Visibility::Internal => {
-            let internal_msg = format!(
-                "This function is internal to its module. Only '{}' and '{}' functions can \
-                 be called outside of their module",
-                Visibility::PUBLIC,
-                Visibility::FRIEND
-            );
-            context.env.add_diag(diag!(
-                TypeSafety::Visibility,
-                (loc, format!("Invalid call to '{}::{}'", m, f)),
-                (defined_loc, internal_msg),
-            ));
-        }
-        Visibility::Friend(_) if in_current_module || context.current_module_is_a_friend_of(m) => {}
-        Visibility::Friend(vis_loc) => {
-            let internal_msg = format!(
-                "This function can only be called from a 'friend' of module '{}'",
-                m
-            );
-            context.env.add_diag(diag!(
-                TypeSafety::Visibility,
-                (loc, format!("Invalid call to '{}::{}'", m, f)),
-                (vis_loc, internal_msg),
-            ));
-        }
-        Visibility::Public(_) => (),
-    };
-    (defined_loc, ty_args, params, acquires, return_ty)
+    (finfo.defined_loc, ty_args, params, acquires, return_ty)
 }
 
 //**************************************************************************************************
@@ -1049,7 +1247,7 @@ fn solve_base_type_constraint(context: &mut Context, loc: Loc, msg: String, ty:
                 (tyloc, tmsg)
             ))
         }
-        UnresolvedError | Anything | Param(_) | Apply(_, _, _) => (),
+        UnresolvedError | Anything | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
     }
 }
 
@@ -1070,7 +1268,7 @@ fn solve_single_type_constraint(context: &mut Context, loc: Loc, msg: String, ty
                 (tyloc, tmsg)
             ))
         }
-        UnresolvedError | Anything | Ref(_, _) | Param(_) | Apply(_, _, _) => (),
+        UnresolvedError | Anything | Ref(_, _) | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
     }
 }
 
@@ -1144,6 +1342,11 @@ pub fn subst_tparams(subst: &TParamSubst, sp!(loc, t_): Type) -> Type {
                 .collect();
             sp(loc, Apply(k, n, ftys))
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| subst_tparams(subst, t)).collect();
+            let result = subst_tparams(subst, *result);
+            sp(loc, Fun(args, Box::new(result)))
+        }
     }
 }
 
@@ -1156,6 +1359,11 @@ pub fn ready_tvars(subst: &Subst, sp!(loc, t_): Type) -> Type {
             let tys = tys.into_iter().map(|t| ready_tvars(subst, t)).collect();
             sp(loc, Apply(k, n, tys))
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| ready_tvars(subst, t)).collect();
+            let result = ready_tvars(subst, *result);
+            sp(loc, Fun(args, Box::new(result)))
+        }
         Var(i) => {
             let last_var = forward_tvar(subst, i);
             match subst.get(last_var) {
@@ -1185,6 +1393,10 @@ pub fn instantiate(context: &mut Context, sp!(loc, t_): Type) -> Type {
         Apply(abilities_opt, n, ty_args) => {
             instantiate_apply(context, loc, abilities_opt, n, ty_args)
         }
+        Fun(args, result) => Fun(
+            args.into_iter().map(|t| instantiate(context, t)).collect(),
+            Box::new(instantiate(context, *result)),
+        ),
         x @ Param(_) => x,
         Var(_) => panic!("ICE instantiate type variable"),
     };
@@ -1413,6 +1625,14 @@ fn join_impl(
             let (subst, tys) = join_impl_types(subst, case, tys1, tys2)?;
             Ok((subst, sp(*loc, Apply(k2.clone(), n2.clone(), tys))))
         }
+        (sp!(_, Fun(args1, result1)), sp!(loc, Fun(args2, result2)))
+            if args1.len() == args2.len() =>
+        {
+            // arguments are contravariant, the result is covariant
+            let (subst, args) = join_impl_types(subst, case, args2, args1)?;
+            let (subst, result) = join_impl(subst, case, result1, result2)?;
+            Ok((subst, sp(*loc, Fun(args, Box::new(result)))))
+        }
         (sp!(loc1, Var(id1)), sp!(loc2, Var(id2))) => {
             if *id1 == *id2 {
                 Ok((subst, sp(*loc2, Var(*id2))))
@@ -1559,6 +1779,10 @@ fn join_bind_tvar(subst: &mut Subst, loc: Loc, tvar: TVar, ty: Type) -> Result<b
                 .iter()
                 .rev()
                 .for_each(|inner| used_tvars(used, inner)),
+            T::Fun(args, result) => {
+                used_tvars(used, result);
+                args.iter().rev().for_each(|inner| used_tvars(used, inner))
+            }
             T::Unit | T::Param(_) | T::Anything | T::UnresolvedError => (),
         }
     }
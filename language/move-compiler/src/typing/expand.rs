@@ -52,6 +52,10 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
     match &mut ty.value {
         Anything | UnresolvedError | Param(_) | Unit => (),
         Ref(_, b) => type_(context, b),
+        Fun(args, result) => {
+            types(context, args);
+            type_(context, result);
+        }
         Var(tvar) => {
             let ty_tvar = sp(ty.loc, Var(*tvar));
             let replacement = core::unfold_type(&context.subst, ty_tvar);
@@ -112,7 +116,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
     use T::UnannotatedExp_ as E;
     match &e.exp.value {
         // dont expand the type for return, abort, break, or continue
-        E::Break | E::Continue | E::Return(_) | E::Abort(_) => {
+        E::Break(_) | E::Continue(_) | E::Return(_) | E::Abort(_) => {
             let t = e.ty.clone();
             match core::unfold_type(&context.subst, t) {
                 sp!(_, Type_::Anything) => (),
@@ -225,8 +229,8 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
         | E::Move { .. }
         | E::Copy { .. }
         | E::BorrowLocal(_, _)
-        | E::Break
-        | E::Continue
+        | E::Break(_)
+        | E::Continue(_)
         | E::UnresolvedError => (),
 
         E::ModuleCall(call) => module_call(context, call),
@@ -234,6 +238,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
             builtin_function(context, b);
             exp(context, args);
         }
+        E::VarCall(_, args) => exp(context, args),
         E::Vector(_vec_loc, _n, ty_arg, args) => {
             type_(context, ty_arg);
             exp(context, args);
@@ -244,12 +249,30 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
             exp(context, et);
             exp(context, ef);
         }
-        E::While(eb, eloop) => {
+        E::While(_, eb, eloop) => {
             exp(context, eb);
             exp(context, eloop);
         }
         E::Loop { body: eloop, .. } => exp(context, eloop),
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (pat, earm)) in arms {
+                if let T::MatchPattern_::Variant(_, _, _, bts, fields) = &mut pat.value {
+                    types(context, bts);
+                    for (_, _, (_, (bt, innerb))) in fields.iter_mut() {
+                        type_(context, bt);
+                        lvalue(context, innerb)
+                    }
+                }
+                exp(context, earm)
+            }
+        }
         E::Block(seq) => sequence(context, seq),
+        E::Lambda(binds, tys, body) => {
+            lvalues(context, binds);
+            expected_types(context, tys);
+            exp(context, body);
+        }
         E::Assign(assigns, tys, er) => {
             lvalues(context, assigns);
             expected_types(context, tys);
@@ -272,7 +295,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
             type_(context, operand_ty);
         }
 
-        E::Pack(_, _, bs, fields) => {
+        E::Pack(_, _, bs, fields) | E::PackVariant(_, _, _, bs, fields) => {
             types(context, bs);
             for (_, _, (_, (bt, fe))) in fields.iter_mut() {
                 type_(context, bt);
@@ -92,8 +92,8 @@ fn exp(
         | E::Move { .. }
         | E::Copy { .. }
         | E::BorrowLocal(_, _)
-        | E::Break
-        | E::Continue
+        | E::Break(_)
+        | E::Continue(_)
         | E::Spec(_, _)
         | E::UnresolvedError => (),
 
@@ -115,20 +115,28 @@ fn exp(
             builtin_function(context, annotated_acquires, seen, &e.exp.loc, b);
             exp(context, annotated_acquires, seen, args);
         }
-        E::Vector(_vec_loc, _n, _targ, args) => exp(context, annotated_acquires, seen, args),
+        E::Vector(_, _, _, args) | E::VarCall(_, args) => {
+            exp(context, annotated_acquires, seen, args)
+        }
 
         E::IfElse(eb, et, ef) => {
             exp(context, annotated_acquires, seen, eb);
             exp(context, annotated_acquires, seen, et);
             exp(context, annotated_acquires, seen, ef);
         }
-        E::While(eb, eloop) => {
+        E::While(_, eb, eloop) => {
             exp(context, annotated_acquires, seen, eb);
             exp(context, annotated_acquires, seen, eloop);
         }
         E::Loop { body: eloop, .. } => exp(context, annotated_acquires, seen, eloop),
+        E::Match(esubject, arms) => {
+            exp(context, annotated_acquires, seen, esubject);
+            for sp!(_, (_, earm)) in arms {
+                exp(context, annotated_acquires, seen, earm)
+            }
+        }
         E::Block(seq) => sequence(context, annotated_acquires, seen, seq),
-        E::Assign(_, _, er) => {
+        E::Assign(_, _, er) | E::Lambda(_, _, er) => {
             exp(context, annotated_acquires, seen, er);
         }
 
@@ -143,7 +151,7 @@ fn exp(
             exp(context, annotated_acquires, seen, er)
         }
 
-        E::Pack(_, _, _, fields) => {
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
             for (_, _, (_, (_, fe))) in fields {
                 exp(context, annotated_acquires, seen, fe)
             }
@@ -268,7 +276,7 @@ where
         T::Anything | T::UnresolvedError => {
             return None;
         }
-        T::Ref(_, _) | T::Unit => {
+        T::Ref(_, _) | T::Unit | T::Fun(_, _) => {
             // Key ability is checked by constraints, and these types do not have Key
             assert!(context.env.has_errors());
             return None;
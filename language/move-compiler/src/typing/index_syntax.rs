@@ -0,0 +1,168 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module collects the functions that implement the index syntax `e[i]` for a type. These
+//! functions are marked with `#[syntax(index)]`, take a reference to a type declared in the same
+//! module followed by the index, and return a reference of the same mutability.
+
+use crate::{
+    diag,
+    diagnostics::Diagnostic,
+    expansion::ast::{self as E, AttributeName_, ModuleIdent},
+    naming::ast::{self as N, TypeName_, Type_},
+    parser::ast::FunctionName,
+    shared::{
+        known_attributes::{KnownAttribute, SyntaxAttribute},
+        CompilationEnv, Identifier,
+    },
+    FullyCompiledProgram,
+};
+use std::collections::BTreeMap;
+
+const SYNTAX_ATTR: AttributeName_ =
+    AttributeName_::Known(KnownAttribute::Syntax(SyntaxAttribute::Syntax));
+
+/// The functions used for `&e[i]` and `&mut e[i]` respectively
+#[derive(Debug, Clone, Default)]
+pub struct IndexFunctions {
+    pub borrow: Option<(ModuleIdent, FunctionName)>,
+    pub borrow_mut: Option<(ModuleIdent, FunctionName)>,
+}
+
+/// Collects the index functions of all modules. Invalid declarations are only reported for the
+/// modules being compiled
+pub fn index_functions(
+    env: &mut CompilationEnv,
+    pre_compiled_lib: Option<&FullyCompiledProgram>,
+    prog: &N::Program,
+) -> BTreeMap<TypeName_, IndexFunctions> {
+    let pre_compiled_modules = pre_compiled_lib.iter().flat_map(|pre_compiled| {
+        pre_compiled
+            .naming
+            .modules
+            .key_cloned_iter()
+            .filter(|(mident, _m)| !prog.modules.contains_key(mident))
+            .map(|(mident, mdef)| (mident, mdef, false))
+    });
+    let all_modules = prog
+        .modules
+        .key_cloned_iter()
+        .map(|(mident, mdef)| (mident, mdef, true))
+        .chain(pre_compiled_modules);
+
+    let mut index_functions = BTreeMap::<TypeName_, IndexFunctions>::new();
+    for (mident, mdef, report) in all_modules {
+        for (fname, fdef) in mdef.functions.key_cloned_iter() {
+            let diag = match index_function(&mident, &fname, fdef) {
+                None => continue,
+                Some(Err(diag)) => diag,
+                Some(Ok((tn, mut_))) => {
+                    let entry = index_functions.entry(tn.clone()).or_default();
+                    let slot = if mut_ {
+                        &mut entry.borrow_mut
+                    } else {
+                        &mut entry.borrow
+                    };
+                    match slot {
+                        None => {
+                            *slot = Some((mident, fname));
+                            continue;
+                        }
+                        Some((_, prev)) => {
+                            let msg = format!(
+                                "Duplicate '{}' index function for type '{}'",
+                                if mut_ { "&mut" } else { "&" },
+                                tn
+                            );
+                            diag!(
+                                Attributes::InvalidSyntaxMethod,
+                                (fname.loc(), msg),
+                                (prev.loc(), "Previously declared here"),
+                            )
+                        }
+                    }
+                }
+            };
+            if report {
+                env.add_diag(diag)
+            }
+        }
+    }
+    index_functions
+}
+
+// Returns the indexed type and the mutability of the function, if it is marked as an index
+// function
+fn index_function(
+    mident: &ModuleIdent,
+    fname: &FunctionName,
+    fdef: &N::Function,
+) -> Option<Result<(TypeName_, bool), Diagnostic>> {
+    let attr = fdef.attributes.get_(&SYNTAX_ATTR)?;
+    let is_index = match &attr.value {
+        E::Attribute_::Parameterized(_, inner) => {
+            inner.len() == 1
+                && inner.iter().all(|(_, _, inner_attr)| {
+                    matches!(
+                        &inner_attr.value,
+                        E::Attribute_::Name(n) if n.value.as_str() == SyntaxAttribute::INDEX
+                    )
+                })
+        }
+        _ => false,
+    };
+    if !is_index {
+        let msg = format!(
+            "Invalid '{}' attribute. Expected '{}({})'",
+            SyntaxAttribute::SYNTAX,
+            SyntaxAttribute::SYNTAX,
+            SyntaxAttribute::INDEX
+        );
+        return Some(Err(diag!(Attributes::InvalidSyntaxMethod, (attr.loc, msg))));
+    }
+
+    let invalid = |msg: String| {
+        let attr_msg = format!("Invalid index function '{}::{}'", mident, fname);
+        Some(Err(diag!(
+            Attributes::InvalidSyntaxMethod,
+            (attr.loc, attr_msg),
+            (fname.loc(), msg),
+        )))
+    };
+    let signature = &fdef.signature;
+    if signature.parameters.len() != 2 {
+        return invalid(
+            "Index functions must take exactly two parameters: a reference to the indexed value \
+             and the index"
+                .to_owned(),
+        );
+    }
+    let (mut_, subject) = match &signature.parameters[0].1.value {
+        Type_::Ref(mut_, inner) => (*mut_, inner),
+        _ => {
+            return invalid(
+                "The first parameter of an index function must be a reference".to_owned(),
+            )
+        }
+    };
+    let tn = match &subject.value {
+        Type_::Apply(_, sp!(_, tn @ TypeName_::ModuleType(m, _)), _) if m == mident => tn.clone(),
+        _ => {
+            return invalid(format!(
+                "The first parameter of an index function must be a reference to a type \
+                 declared in module '{}'",
+                mident
+            ))
+        }
+    };
+    match &signature.return_type.value {
+        Type_::Ref(ret_mut, _) if *ret_mut == mut_ => Some(Ok((tn, mut_))),
+        _ => {
+            let ref_ = if mut_ { "&mut" } else { "&" };
+            invalid(format!(
+                "The index function takes a '{}' reference, so it must return a '{}' reference",
+                ref_, ref_
+            ))
+        }
+    }
+}
@@ -94,6 +94,15 @@ impl<'a> Context<'a> {
                 tys.iter()
                     .for_each(|t| Self::add_tparam_edges(acc, tparam, info.clone(), t))
             }
+            Fun(args, result) => {
+                let info = EdgeInfo {
+                    edge: Edge::Nested,
+                    ..info
+                };
+                args.iter()
+                    .chain(std::iter::once(&**result))
+                    .for_each(|t| Self::add_tparam_edges(acc, tparam, info.clone(), t))
+            }
             Param(tp) => {
                 let tp_neighbors = acc.entry(tp.clone()).or_insert_with(BTreeMap::new);
                 match tp_neighbors.get(tparam) {
@@ -219,8 +228,8 @@ fn exp(context: &mut Context, e: &T::Exp) {
         | E::Move { .. }
         | E::Copy { .. }
         | E::BorrowLocal(_, _)
-        | E::Break
-        | E::Continue
+        | E::Break(_)
+        | E::Continue(_)
         | E::Spec(_, _)
         | E::UnresolvedError => (),
 
@@ -234,15 +243,22 @@ fn exp(context: &mut Context, e: &T::Exp) {
             exp(context, et);
             exp(context, ef);
         }
-        E::While(eb, eloop) => {
+        E::While(_, eb, eloop) => {
             exp(context, eb);
             exp(context, eloop);
         }
         E::Loop { body: eloop, .. } => exp(context, eloop),
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (_, earm)) in arms {
+                exp(context, earm)
+            }
+        }
         E::Block(seq) => sequence(context, seq),
-        E::Assign(_, _, er) => exp(context, er),
+        E::Assign(_, _, er) | E::Lambda(_, _, er) => exp(context, er),
 
         E::Builtin(_, er)
+        | E::VarCall(_, er)
         | E::Vector(_, _, _, er)
         | E::Return(er)
         | E::Abort(er)
@@ -255,7 +271,7 @@ fn exp(context: &mut Context, e: &T::Exp) {
             exp(context, er)
         }
 
-        E::Pack(_, _, _, fields) => {
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
             for (_, _, (_, (_, fe))) in fields.iter() {
                 exp(context, fe)
             }
@@ -3,9 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ast;
+mod constants;
 pub(crate) mod core;
 mod expand;
 mod globals;
+mod index_syntax;
 mod infinite_instantiations;
 mod recursive_structs;
 pub(crate) mod translate;
+mod unused_items;
+
+pub use unused_items::program_uses;
@@ -89,6 +89,9 @@ fn struct_def(context: &mut Context, sname: StructName, sdef: &N::StructDefiniti
         N::StructFields::Defined(fields) => {
             fields.iter().for_each(|(_, _, (_, ty))| type_(context, ty))
         }
+        N::StructFields::Variants(variants) => variants.iter().for_each(|(_, _, (_, fields))| {
+            fields.iter().for_each(|(_, _, (_, ty))| type_(context, ty))
+        }),
     };
     context.current_struct = None;
 }
@@ -105,6 +108,10 @@ fn type_(context: &mut Context, sp!(loc, ty_): &N::Type) {
             }
             tys.iter().for_each(|t| type_(context, t))
         }
+        Fun(args, result) => {
+            args.iter().for_each(|t| type_(context, t));
+            type_(context, result)
+        }
     }
 }
 
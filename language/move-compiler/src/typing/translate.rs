@@ -3,21 +3,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
+    constants,
     core::{self, Context, Subst},
-    expand, globals, infinite_instantiations, recursive_structs,
+    expand, globals,
+    index_syntax::IndexFunctions,
+    infinite_instantiations, recursive_structs, unused_items,
 };
 use crate::{
     diag,
     diagnostics::{codes::*, Diagnostic},
-    expansion::ast::{Fields, ModuleIdent, Value_},
-    naming::ast::{self as N, TParam, TParamID, Type, TypeName_, Type_},
-    parser::ast::{Ability_, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp_, Var},
-    shared::{unique_map::UniqueMap, *},
+    expansion::ast::{self as E, Fields, ModuleIdent, ModuleIdent_, Value_},
+    naming::ast::{self as N, BuiltinTypeName_, TParam, TParamID, Type, TypeName_, Type_},
+    parser::ast::{
+        Ability_, BinOp_, BlockLabel, ConstantName, Field, FunctionName, StructName, UnaryOp_, Var,
+        VariantName,
+    },
+    shared::{known_attributes::SyntaxAttribute, unique_map::UniqueMap, *},
     typing::ast as T,
     FullyCompiledProgram,
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use petgraph::{algo::tarjan_scc as petgraph_scc, graphmap::DiGraphMap};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 //**************************************************************************************************
@@ -31,18 +38,51 @@ pub fn program(
 ) -> T::Program {
     let mut context = Context::new(compilation_env, pre_compiled_lib, &prog);
     let N::Program {
-        modules: nmodules,
+        modules: mut nmodules,
         scripts: nscripts,
     } = prog;
-    let modules = modules(&mut context, nmodules);
-    let scripts = scripts(&mut context, nscripts);
+    let spec_uses = nmodules
+        .iter_mut()
+        .map(|(loc, mident_, mdef)| (sp(loc, *mident_), std::mem::take(&mut mdef.spec_uses)))
+        .collect();
+    let mut modules = modules(&mut context, nmodules);
+    let mut scripts = scripts(&mut context, nscripts);
+    // the generated friend declarations of 'public(package)' functions are always used
+    unused_items::program(context.env, &modules, &scripts, &spec_uses);
+    let package_friends = std::mem::take(&mut context.package_friends);
+    add_package_friends(package_friends, &mut modules);
 
     assert!(context.constraints.is_empty());
     recursive_structs::modules(context.env, &modules);
     infinite_instantiations::modules(context.env, &modules);
+    constants::program(context.env, pre_compiled_lib, &mut modules, &mut scripts);
     T::Program { modules, scripts }
 }
 
+/// Declares the callers of each module's 'public(package)' functions as its friends, as these
+/// functions are compiled to 'public(friend)' functions
+pub fn add_package_friends(
+    package_friends: BTreeMap<ModuleIdent, BTreeMap<ModuleIdent, Loc>>,
+    modules: &mut UniqueMap<ModuleIdent, T::ModuleDefinition>,
+) {
+    for (mident, callers) in package_friends {
+        let mdef = match modules.get_mut(&mident) {
+            Some(mdef) => mdef,
+            // modules from the pre-compiled library are not recompiled
+            None => continue,
+        };
+        for (caller, loc) in callers {
+            if !mdef.friends.contains_key(&caller) {
+                let friend = E::Friend {
+                    attributes: UniqueMap::new(),
+                    loc,
+                };
+                mdef.friends.add(caller, friend).unwrap();
+            }
+        }
+    }
+}
+
 fn modules(
     context: &mut Context,
     modules: UniqueMap<ModuleIdent, N::ModuleDefinition>,
@@ -62,17 +102,21 @@ fn module(
         attributes,
         is_source_module,
         dependency_order,
+        spec_uses: _,
         friends,
         mut structs,
         functions: nfunctions,
         constants: nconstants,
+        use_funs,
     } = mdef;
+    context.use_funs = use_funs;
     structs
         .iter_mut()
         .for_each(|(_, _, s)| struct_def(context, s));
     let constants = nconstants.map(|name, c| constant(context, name, c));
     let functions = nfunctions.map(|name, f| function(context, name, f, false));
     assert!(context.constraints.is_empty());
+    check_inline_cycles(context);
     T::ModuleDefinition {
         package_name,
         attributes,
@@ -98,6 +142,7 @@ fn scripts(
 fn script(context: &mut Context, nscript: N::Script) -> T::Script {
     assert!(context.current_script_constants.is_none());
     context.current_module = None;
+    context.use_funs = N::UseFuns::new();
     let N::Script {
         package_name,
         attributes,
@@ -135,6 +180,7 @@ fn function(
         attributes,
         visibility,
         entry,
+        inline,
         mut signature,
         body: n_body,
         acquires,
@@ -142,6 +188,7 @@ fn function(
     assert!(context.constraints.is_empty());
     context.reset_for_module_item();
     context.current_function = Some(name);
+    context.current_function_inline = inline;
     function_signature(context, &signature);
     if is_script {
         let mk_msg = || {
@@ -168,12 +215,53 @@ fn function(
         attributes,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
     }
 }
 
+// Inline functions are expanded at their call sites, so calls among the inline functions of a
+// module cannot form a cycle
+fn check_inline_cycles(context: &mut Context) {
+    let inline_calls = std::mem::take(&mut context.inline_calls);
+    let edges = inline_calls
+        .iter()
+        .flat_map(|(caller, callees)| callees.keys().map(move |callee| (caller, callee)));
+    let graph: DiGraphMap<&FunctionName, ()> = DiGraphMap::from_edges(edges);
+    for scc in petgraph_scc(&graph) {
+        if scc.len() == 1 && !graph.contains_edge(scc[0], scc[0]) {
+            continue;
+        }
+        let cycle = shortest_cycle(&graph, scc[0]);
+        let cycle_strings = cycle
+            .iter()
+            .map(|f| format!("'{}'", f))
+            .collect::<Vec<_>>()
+            .join(" calls ");
+        let (caller, callee) = if cycle.len() == 1 {
+            (cycle[0], cycle[0])
+        } else {
+            (cycle[cycle.len() - 2], cycle[cycle.len() - 1])
+        };
+        let call_loc = inline_calls[caller][callee];
+        let msg = format!(
+            "Invalid call to inline function '{}' in inline function '{}'",
+            callee, caller
+        );
+        let cycle_msg = format!(
+            "Inline functions cannot be recursive. This call creates a cycle: {}",
+            cycle_strings
+        );
+        context.env.add_diag(diag!(
+            TypeSafety::CyclicInline,
+            (call_loc, msg),
+            (callee.loc(), cycle_msg),
+        ))
+    }
+}
+
 fn function_signature(context: &mut Context, sig: &N::FunctionSignature) {
     assert!(context.constraints.is_empty());
 
@@ -242,6 +330,7 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
         loc,
         signature,
         value: nvalue,
+        assertion_message,
     } = nconstant;
 
     // Don't need to add base type constraint, as it is checked in `check_valid_constant::signature`
@@ -254,6 +343,7 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
         &signature,
     );
     context.return_type = Some(signature.clone());
+    context.in_constant = true;
 
     let mut value = exp_(context, nvalue);
 
@@ -276,6 +366,7 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
         loc,
         signature,
         value,
+        assertion_message,
     }
 }
 
@@ -359,12 +450,14 @@ mod check_valid_constant {
             //*****************************************
             // Error cases handled elsewhere
             //*****************************************
-            E::Use(_) | E::Continue | E::Break | E::UnresolvedError => return,
+            E::Use(_) | E::Continue(_) | E::Break(_) | E::UnresolvedError => return,
 
             //*****************************************
             // Valid cases
             //*****************************************
-            E::Unit { .. } | E::Value(_) | E::Move { .. } | E::Copy { .. } => return,
+            E::Unit { .. } | E::Value(_) | E::Move { .. } | E::Copy { .. } | E::Constant(_, _) => {
+                return
+            }
             E::Block(seq) => {
                 sequence(context, seq);
                 return;
@@ -405,13 +498,18 @@ mod check_valid_constant {
                 s = format!("'{}' is", b);
                 &s
             }
+            E::VarCall(_, args) => {
+                exp(context, args);
+                "Function calls are"
+            }
+            E::Lambda(_, _, _) => "Lambdas are",
             E::IfElse(eb, et, ef) => {
                 exp(context, eb);
                 exp(context, et);
                 exp(context, ef);
                 "'if' expressions are"
             }
-            E::While(eb, eloop) => {
+            E::While(_, eb, eloop) => {
                 exp(context, eb);
                 exp(context, eloop);
                 "'while' expressions are"
@@ -420,6 +518,13 @@ mod check_valid_constant {
                 exp(context, eloop);
                 "'loop' expressions are"
             }
+            E::Match(esubject, arms) => {
+                exp(context, esubject);
+                for sp!(_, (_, earm)) in arms {
+                    exp(context, earm)
+                }
+                "'match' expressions are"
+            }
             E::Assign(_assigns, _tys, er) => {
                 exp(context, er);
                 "Assignments are"
@@ -447,7 +552,12 @@ mod check_valid_constant {
                 }
                 "Structs are"
             }
-            E::Constant(_, _) => "Other constants are",
+            E::PackVariant(_, _, _, _, fields) => {
+                for (_, _, (_, (_, fe))) in fields {
+                    exp(context, fe)
+                }
+                "Enums are"
+            }
         };
         context.env.add_diag(diag!(
             TypeSafety::UnsupportedConstant,
@@ -508,15 +618,20 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
     assert!(context.constraints.is_empty());
     context.reset_for_module_item();
 
-    let field_map = match &mut s.fields {
+    // the fields of all variants are checked in the same way as the fields of a struct
+    let mut field_types: Vec<&mut Type> = match &mut s.fields {
         N::StructFields::Native(_) => return,
-        N::StructFields::Defined(m) => m,
+        N::StructFields::Defined(m) => m.iter_mut().map(|(_, _, idx_ty)| &mut idx_ty.1).collect(),
+        N::StructFields::Variants(vm) => vm
+            .iter_mut()
+            .flat_map(|(_, _, (_, m))| m.iter_mut().map(|(_, _, idx_ty)| &mut idx_ty.1))
+            .collect(),
     };
 
     // instantiate types and check constraints
-    for (_field_loc, _field, idx_ty) in field_map.iter() {
-        let loc = idx_ty.1.loc;
-        let inst_ty = core::instantiate(context, idx_ty.1.clone());
+    for field_ty in field_types.iter() {
+        let loc = field_ty.loc;
+        let inst_ty = core::instantiate(context, (*field_ty).clone());
         context.add_base_type_constraint(loc, "Invalid field type", inst_ty.clone());
     }
     core::solve_constraints(context);
@@ -530,9 +645,9 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
             .iter()
             .map(|tp| sp(tp.param.user_specified_name.loc, Type_::Anything)),
     );
-    for (_field_loc, _field, idx_ty) in field_map.iter() {
-        let loc = idx_ty.1.loc;
-        let subst_ty = core::subst_tparams(tparam_subst, idx_ty.1.clone());
+    for field_ty in field_types.iter() {
+        let loc = field_ty.loc;
+        let subst_ty = core::subst_tparams(tparam_subst, (*field_ty).clone());
         for declared_ability in declared_abilities {
             let required = declared_ability.value.requires();
             let msg = format!(
@@ -545,21 +660,20 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
     }
     core::solve_constraints(context);
 
-    for (_field_loc, _field_, idx_ty) in field_map.iter_mut() {
-        expand::type_(context, &mut idx_ty.1);
+    for field_ty in field_types.iter_mut() {
+        expand::type_(context, field_ty);
     }
 
-    check_type_params_usage(context, &s.type_parameters, field_map);
+    let field_types: Vec<&Type> = field_types.into_iter().map(|ty| &*ty).collect();
+    check_type_params_usage(context, &s.type_parameters, &field_types);
 }
 
 fn check_type_params_usage(
     context: &mut Context,
     type_parameters: &[N::StructTypeParameter],
-    field_map: &Fields<Type>,
+    field_types: &[&Type],
 ) {
-    let has_unresolved = field_map
-        .iter()
-        .any(|(_, _, ty)| has_unresolved_error_type(&ty.1));
+    let has_unresolved = field_types.iter().any(|ty| has_unresolved_error_type(ty));
 
     if has_unresolved {
         return;
@@ -574,10 +688,10 @@ fn check_type_params_usage(
         .filter(|ty_param| ty_param.is_phantom)
         .map(|param| param.param.id)
         .collect();
-    for (_, _, idx_ty) in field_map.iter() {
+    for field_ty in field_types {
         visit_type_params(
             context,
-            &idx_ty.1,
+            field_ty,
             ParamPos::FIELD,
             &mut |context, loc, param, pos| {
                 let param_is_phantom = phantom_params.contains(&param.id);
@@ -673,6 +787,13 @@ fn visit_type_params(
                 }
             }
         },
+        // Function types cannot appear in structs, but we still report them as a non-phantom
+        // position for full information.
+        Type_::Fun(args, result) => {
+            for ty in args.iter().chain(std::iter::once(&**result)) {
+                visit_type_params(context, ty, ParamPos::NonPhantom(NonPhantomPos::TypeArg), f)
+            }
+        }
         Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => {}
         Type_::Unit => {}
     }
@@ -733,6 +854,9 @@ fn has_unresolved_error_type(ty: &Type) -> bool {
         Type_::UnresolvedError => true,
         Type_::Ref(_, ty) => has_unresolved_error_type(ty),
         Type_::Apply(_, _, ty_args) => ty_args.iter().any(has_unresolved_error_type),
+        Type_::Fun(args, result) => {
+            args.iter().any(has_unresolved_error_type) || has_unresolved_error_type(result)
+        }
         Type_::Param(_) | Type_::Var(_) | Type_::Anything | Type_::Unit => false,
     }
 }
@@ -1209,6 +1333,17 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             (ty, TE::Constant(m, c))
         }
 
+        NE::Move(var) | NE::Copy(var) | NE::Use(var) if context.is_function_local(&var) => {
+            let msg = format!(
+                "Invalid usage of function parameter '{}'. Function parameters can only be called \
+                 or passed as arguments to inline functions",
+                var
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidFunctionType, (eloc, msg)));
+            (context.error_type(eloc), TE::UnresolvedError)
+        }
         NE::Move(var) => {
             let ty = context.get_local(eloc, "move", &var);
             let from_user = true;
@@ -1234,13 +1369,19 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
         }
 
         NE::ModuleCall(m, f, ty_args_opt, sp!(argloc, nargs_)) => {
-            let args = exp_vec(context, nargs_);
-            module_call(context, eloc, m, f, ty_args_opt, argloc, args)
+            module_call(context, eloc, m, f, ty_args_opt, argloc, None, nargs_)
+        }
+        NE::MethodCall(ndotted, method, ty_args_opt, sp!(argloc, nargs_)) => {
+            method_call(context, eloc, ndotted, method, ty_args_opt, argloc, nargs_)
         }
         NE::Builtin(b, sp!(argloc, nargs_)) => {
             let args = exp_vec(context, nargs_);
             builtin_call(context, eloc, b, argloc, args)
         }
+        NE::VarCall(var, sp!(argloc, nargs_)) => {
+            let args = exp_vec(context, nargs_);
+            var_call(context, eloc, var, argloc, args)
+        }
         NE::Vector(vec_loc, ty_opt, sp!(argloc, nargs_)) => {
             let args_ = exp_vec(context, nargs_);
             vector_pack(context, eloc, vec_loc, ty_opt, argloc, args_)
@@ -1267,7 +1408,7 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             );
             (ty, TE::IfElse(eb, et, ef))
         }
-        NE::While(nb, nloop) => {
+        NE::While(label, nb, nloop) => {
             let eb = exp(context, nb);
             let bloc = eb.exp.loc;
             subtype(
@@ -1277,18 +1418,31 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
                 eb.ty.clone(),
                 Type_::bool(bloc),
             );
-            let (_has_break, ty, body) = loop_body(context, eloc, false, nloop);
-            (sp(eloc, ty.value), TE::While(eb, body))
-        }
-        NE::Loop(nloop) => {
-            let (has_break, ty, body) = loop_body(context, eloc, true, nloop);
-            let eloop = TE::Loop { has_break, body };
+            let (_has_break, ty, body) = loop_body(context, eloc, label, false, nloop);
+            (sp(eloc, ty.value), TE::While(label, eb, body))
+        }
+        NE::Loop(label, nloop) => {
+            let (has_break, ty, body) = loop_body(context, eloc, label, true, nloop);
+            let eloop = TE::Loop {
+                label,
+                has_break,
+                body,
+            };
             (sp(eloc, ty.value), eloop)
         }
+        NE::Match(nsubject, narms) => match_exp(context, eloc, nsubject, narms),
         NE::Block(nseq) => {
             let seq = sequence(context, nseq);
             (sequence_type(&seq).clone(), TE::Block(seq))
         }
+        NE::Lambda(_, _) => {
+            let msg = "Invalid lambda. Lambdas can only be passed directly as arguments to \
+                       inline functions";
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidLambda, (eloc, msg)));
+            (context.error_type(eloc), TE::UnresolvedError)
+        }
 
         NE::Assign(na, nr) => {
             let er = exp(context, nr);
@@ -1314,6 +1468,16 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
         }
 
         NE::Return(nret) => {
+            if context.in_lambda() || context.current_function_inline {
+                let msg = if context.in_lambda() {
+                    "Invalid usage of 'return'. 'return' cannot be used inside a lambda"
+                } else {
+                    "Invalid usage of 'return'. 'return' cannot be used inside an inline function"
+                };
+                context
+                    .env
+                    .add_diag(diag!(TypeSafety::InvalidReturn, (eloc, msg)))
+            }
             let eret = exp(context, nret);
             let ret_ty = context.return_type.clone().unwrap();
             subtype(context, eloc, || "Invalid return", eret.ty.clone(), ret_ty);
@@ -1325,7 +1489,7 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             subtype(context, eloc, || "Invalid abort", ecode.ty.clone(), code_ty);
             (sp(eloc, Type_::Anything), TE::Abort(ecode))
         }
-        NE::Break => {
+        NE::Break(label) => {
             if !context.in_loop() {
                 let msg = "Invalid usage of 'break'. 'break' can only be used inside a loop body";
                 context
@@ -1333,17 +1497,17 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
                     .add_diag(diag!(TypeSafety::InvalidLoopControl, (eloc, msg)))
             }
             let current_break_ty = sp(eloc, Type_::Unit);
-            let break_ty = match context.get_break_type() {
+            let break_ty = match context.get_break_type(&label) {
                 None => current_break_ty,
                 Some(t) => {
                     let t = t.clone();
                     join(context, eloc, || "Invalid break.", t, current_break_ty)
                 }
             };
-            context.set_break_type(break_ty);
-            (sp(eloc, Type_::Anything), TE::Break)
+            context.set_break_type(&label, break_ty);
+            (sp(eloc, Type_::Anything), TE::Break(label))
         }
-        NE::Continue => {
+        NE::Continue(label) => {
             if !context.in_loop() {
                 let msg =
                     "Invalid usage of 'continue'. 'continue' can only be used inside a loop body";
@@ -1351,7 +1515,7 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
                     .env
                     .add_diag(diag!(TypeSafety::InvalidLoopControl, (eloc, msg)))
             }
-            (sp(eloc, Type_::Anything), TE::Continue)
+            (sp(eloc, Type_::Anything), TE::Continue(label))
         }
 
         NE::Dereference(nref) => {
@@ -1441,6 +1605,47 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             }
             (bt, TE::Pack(m, n, targs, tfields))
         }
+        NE::PackVariant(m, n, v, ty_args_opt, nfields) => {
+            let (bt, targs) = core::make_struct_type(context, eloc, &m, &n, ty_args_opt);
+            let typed_nfields = add_variant_field_types(
+                context,
+                eloc,
+                "argument",
+                &m,
+                &n,
+                &v,
+                targs.clone(),
+                nfields,
+            );
+
+            let tfields = typed_nfields.map(|f, (idx, (fty, narg))| {
+                let arg = exp_(context, narg);
+                subtype(
+                    context,
+                    arg.exp.loc,
+                    || {
+                        format!(
+                            "Invalid argument for field '{}' for '{}::{}::{}'",
+                            f, &m, &n, &v
+                        )
+                    },
+                    arg.ty.clone(),
+                    fty.clone(),
+                );
+                (idx, (fty, arg))
+            });
+            if !context.is_current_module(&m) {
+                let msg = format!(
+                    "Invalid instantiation of '{}::{}::{}'.\nAll enums can only be constructed in \
+                     the module in which they are declared",
+                    &m, &n, &v,
+                );
+                context
+                    .env
+                    .add_diag(diag!(TypeSafety::Visibility, (eloc, msg)));
+            }
+            (bt, TE::PackVariant(m, n, v, targs, tfields))
+        }
 
         NE::Borrow(mut_, sp!(_, N::ExpDotted_::Exp(ner))) => {
             let er = exp_(context, *ner);
@@ -1512,12 +1717,13 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
 fn loop_body(
     context: &mut Context,
     eloc: Loc,
+    label: Option<BlockLabel>,
     is_loop: bool,
     nloop: Box<N::Exp>,
 ) -> (bool, Type, Box<T::Exp>) {
-    let old_loop_info = context.enter_loop();
+    context.enter_loop(label);
     let eloop = exp(context, nloop);
-    let break_type_opt = context.exit_loop(old_loop_info);
+    let break_type_opt = context.exit_loop();
 
     let lloc = eloop.exp.loc;
     subtype(
@@ -1763,6 +1969,182 @@ fn lvalue(
     sp(loc, tl_)
 }
 
+//**************************************************************************************************
+// Match
+//**************************************************************************************************
+
+fn match_exp(
+    context: &mut Context,
+    eloc: Loc,
+    nsubject: Box<N::Exp>,
+    narms: Vec<N::MatchArm>,
+) -> (Type, T::UnannotatedExp_) {
+    let esubject = exp(context, nsubject);
+    // If the subject is a reference, the arms bind references to the fields of the variant
+    let (ref_mut, subject_ty) = match core::unfold_type(&context.subst, esubject.ty.clone()).value {
+        Type_::Ref(mut_, inner) => (Some(mut_), *inner),
+        _ => (None, esubject.ty.clone()),
+    };
+    let mut result_ty: Option<Type> = None;
+    let mut tarms = vec![];
+    for sp!(aloc, (sp!(ploc, npat_), nrhs)) in narms {
+        let old_locals = context.save_locals_scope();
+        let mut seen_locals = UniqueMap::new();
+        let tpat_ = match npat_ {
+            N::MatchPattern_::Wildcard => {
+                if ref_mut.is_none() {
+                    context.add_ability_constraint(
+                        ploc,
+                        Some(format!(
+                            "Cannot ignore values without the '{}' ability. The value must be used",
+                            Ability_::Drop
+                        )),
+                        subject_ty.clone(),
+                        Ability_::Drop,
+                    );
+                }
+                T::MatchPattern_::Wildcard
+            }
+            N::MatchPattern_::Variant(m, n, v, ty_args_opt, nfields) => {
+                let (bt, targs) = core::make_struct_type(context, ploc, &m, &n, ty_args_opt);
+                subtype(
+                    context,
+                    ploc,
+                    || "Invalid match pattern",
+                    bt,
+                    subject_ty.clone(),
+                );
+                let typed_fields = add_variant_field_types(
+                    context,
+                    ploc,
+                    "binding",
+                    &m,
+                    &n,
+                    &v,
+                    targs.clone(),
+                    nfields,
+                );
+                let tfields = typed_fields.map(|f, (idx, (fty, nl))| {
+                    let nl_ty = match ref_mut {
+                        None => fty.clone(),
+                        Some(mut_) => sp(f.loc(), Type_::Ref(mut_, Box::new(fty.clone()))),
+                    };
+                    let tl = lvalue(context, LValueCase::Bind, &mut seen_locals, nl, nl_ty);
+                    (idx, (fty, tl))
+                });
+                if !context.is_current_module(&m) {
+                    let msg = format!(
+                        "Invalid match of '{}::{}::{}'.\nAll enums can only be matched in the \
+                         module in which they are declared",
+                        &m, &n, &v,
+                    );
+                    context
+                        .env
+                        .add_diag(diag!(TypeSafety::Visibility, (ploc, msg)));
+                }
+                T::MatchPattern_::Variant(m, n, v, targs, tfields)
+            }
+        };
+        let erhs = exp_(context, nrhs);
+        context.close_locals_scope(old_locals, seen_locals);
+        let arm_ty = match result_ty {
+            None => erhs.ty.clone(),
+            Some(ty) => join(
+                context,
+                erhs.exp.loc,
+                || "Incompatible match arms",
+                ty,
+                erhs.ty.clone(),
+            ),
+        };
+        result_ty = Some(arm_ty);
+        tarms.push(sp(aloc, (sp(ploc, tpat_), erhs)));
+    }
+    let tarms = check_match_arms(context, eloc, tarms);
+    let ty = match result_ty {
+        Some(ty) => sp(eloc, ty.value),
+        None => context.error_type(eloc),
+    };
+    (ty, T::UnannotatedExp_::Match(esubject, tarms))
+}
+
+// Removes unreachable arms, reporting them as dead code, and checks that the remaining arms cover
+// all variants of the enum
+fn check_match_arms(context: &mut Context, eloc: Loc, tarms: Vec<T::MatchArm>) -> Vec<T::MatchArm> {
+    let mut enum_name = None;
+    let mut covered: BTreeMap<Symbol, Loc> = BTreeMap::new();
+    let mut wildcard_loc = None;
+    let mut reachable = vec![];
+    for tarm in tarms {
+        let ploc = tarm.value.0.loc;
+        if let Some(wloc) = wildcard_loc {
+            context.env.add_diag(diag!(
+                UnusedItem::DeadCode,
+                (ploc, "Unreachable match arm"),
+                (
+                    wloc,
+                    "All remaining cases are covered by this wildcard pattern"
+                ),
+            ));
+            continue;
+        }
+        match &tarm.value.0.value {
+            T::MatchPattern_::Wildcard => wildcard_loc = Some(ploc),
+            T::MatchPattern_::Variant(m, n, v, _, _) => {
+                if enum_name.is_none() {
+                    enum_name = Some((*m, *n));
+                }
+                if let Some(prev_loc) = covered.get(&v.value()) {
+                    let msg = format!(
+                        "Unreachable match arm. The variant '{}' is already covered",
+                        v
+                    );
+                    context.env.add_diag(diag!(
+                        UnusedItem::DeadCode,
+                        (ploc, msg),
+                        (*prev_loc, "Previously covered here"),
+                    ));
+                    continue;
+                }
+                covered.insert(v.value(), ploc);
+            }
+        }
+        reachable.push(tarm)
+    }
+    if wildcard_loc.is_some() {
+        return reachable;
+    }
+    let variants = match enum_name {
+        None => {
+            context.env.add_diag(diag!(
+                TypeSafety::NonExhaustiveMatch,
+                (eloc, "Invalid 'match'. Expected at least one match arm")
+            ));
+            return reachable;
+        }
+        Some((m, n)) => match core::enum_variants(context, &m, &n) {
+            // An error has already been reported for the patterns
+            None => return reachable,
+            Some(variants) => variants,
+        },
+    };
+    let missing = variants
+        .iter()
+        .filter(|v| !covered.contains_key(&v.value()))
+        .map(|v| format!("'{}'", v))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let msg = format!(
+            "Non-exhaustive 'match'. Missing arms for the variants: {}",
+            missing.join(", ")
+        );
+        context
+            .env
+            .add_diag(diag!(TypeSafety::NonExhaustiveMatch, (eloc, msg)));
+    }
+    reachable
+}
+
 fn check_mutation(context: &mut Context, loc: Loc, given_ref: Type, rvalue_ty: &Type) -> Type {
     let inner = core::make_tvar(context, loc);
     let ref_ty = sp(loc, Type_::Ref(true, Box::new(inner.clone())));
@@ -1858,7 +2240,7 @@ fn add_field_types<T>(
     fields: Fields<T>,
 ) -> Fields<(Type, T)> {
     let maybe_fields_ty = core::make_field_types(context, loc, m, n, targs);
-    let mut fields_ty = match maybe_fields_ty {
+    let fields_ty = match maybe_fields_ty {
         N::StructFields::Defined(m) => m,
         N::StructFields::Native(nloc) => {
             let msg = format!(
@@ -1873,10 +2255,69 @@ fn add_field_types<T>(
             ));
             return fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)));
         }
+        N::StructFields::Variants(_) => {
+            let msg = format!(
+                "Invalid {} usage for enum '{}::{}'. Enums can only be constructed/deconstructed \
+                 through one of their variants, e.g. '{}::<Variant>'",
+                verb, m, n, n
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundVariant, (loc, msg)));
+            return fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)));
+        }
+    };
+    match_field_types(
+        context,
+        loc,
+        verb,
+        &format!("{}::{}", m, n),
+        fields_ty,
+        fields,
+    )
+}
+
+fn add_variant_field_types<T>(
+    context: &mut Context,
+    loc: Loc,
+    verb: &str,
+    m: &ModuleIdent,
+    n: &StructName,
+    v: &VariantName,
+    targs: Vec<Type>,
+    fields: Fields<T>,
+) -> Fields<(Type, T)> {
+    let maybe_fields_ty = core::make_field_types(context, loc, m, n, targs);
+    let msg = match maybe_fields_ty {
+        N::StructFields::Variants(mut vm) => match vm.remove(v) {
+            Some((_, fields_ty)) => {
+                let name = format!("{}::{}::{}", m, n, v);
+                return match_field_types(context, loc, verb, &name, fields_ty, fields);
+            }
+            None => format!("Unbound variant '{}' in '{}::{}'", v, m, n),
+        },
+        N::StructFields::Defined(_) | N::StructFields::Native(_) => format!(
+            "Invalid {} usage of variant '{}'. '{}::{}' is not an enum",
+            verb, v, m, n
+        ),
     };
+    context
+        .env
+        .add_diag(diag!(NameResolution::UnboundVariant, (loc, msg)));
+    fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)))
+}
+
+fn match_field_types<T>(
+    context: &mut Context,
+    loc: Loc,
+    verb: &str,
+    name: &str,
+    mut fields_ty: Fields<Type>,
+    fields: Fields<T>,
+) -> Fields<(Type, T)> {
     for (_, f_, _) in &fields_ty {
         if fields.get_(f_).is_none() {
-            let msg = format!("Missing {} for field '{}' in '{}::{}'", verb, f_, m, n);
+            let msg = format!("Missing {} for field '{}' in '{}'", verb, f_, name);
             context
                 .env
                 .add_diag(diag!(TypeSafety::TooFewArguments, (loc, msg)))
@@ -1887,7 +2328,7 @@ fn add_field_types<T>(
             None => {
                 context.env.add_diag(diag!(
                     NameResolution::UnboundField,
-                    (loc, format!("Unbound field '{}' in '{}::{}'", &f, m, n))
+                    (loc, format!("Unbound field '{}' in '{}'", &f, name))
                 ));
                 context.error_type(f.loc())
             }
@@ -1901,6 +2342,8 @@ enum ExpDotted_ {
     Exp(Box<T::Exp>),
     TmpBorrow(Box<T::Exp>, Box<Type>),
     Dot(Box<ExpDotted>, Field, Box<Type>),
+    // The indexed value, the index functions, the index, and the element type
+    Index(Box<ExpDotted>, IndexFunctions, Box<T::Exp>, Box<Type>),
 }
 type ExpDotted = Spanned<ExpDotted_>;
 
@@ -1936,10 +2379,113 @@ fn exp_dotted(
                 field_ty,
             )
         }
+        NE::Index(nlhs, nindex) => {
+            let (lhs, inner) = exp_dotted(context, "index", *nlhs);
+            let index = exp(context, nindex);
+            match resolve_index_functions(context, dloc, &inner) {
+                None => {
+                    assert!(context.env.has_errors());
+                    let ty = context.error_type(dloc);
+                    let e = T::exp(ty.clone(), sp(dloc, T::UnannotatedExp_::UnresolvedError));
+                    (ExpDotted_::Exp(Box::new(e)), ty)
+                }
+                Some(index_fns) => {
+                    let elem_ty = index_element_type(context, dloc, &index_fns, inner);
+                    (
+                        ExpDotted_::Index(
+                            Box::new(lhs),
+                            index_fns,
+                            index,
+                            Box::new(elem_ty.clone()),
+                        ),
+                        elem_ty,
+                    )
+                }
+            }
+        }
     };
     (sp(dloc, edot_), ty)
 }
 
+// Finds the functions implementing 'e[i]' for the type of 'e': 'vector::borrow' and
+// 'vector::borrow_mut' for vectors, otherwise the '#[syntax(index)]' functions of the type
+fn resolve_index_functions(
+    context: &mut Context,
+    loc: Loc,
+    indexed_ty: &Type,
+) -> Option<IndexFunctions> {
+    use TypeName_ as TN;
+    use Type_ as Ty;
+    let unfolded = core::unfold_type(&context.subst, indexed_ty.clone());
+    let index_fns = match &unfolded.value {
+        Ty::UnresolvedError => return None,
+        Ty::Var(_) | Ty::Anything => {
+            let msg = "Unable to index. Could not infer the type of the indexed value. Try \
+                       annotating its type";
+            context
+                .env
+                .add_diag(diag!(TypeSafety::UninferredType, (loc, msg)));
+            return None;
+        }
+        Ty::Apply(_, sp!(_, TN::Builtin(sp!(_, BuiltinTypeName_::Vector))), _) => {
+            let m = ModuleIdent_::vector(loc);
+            let function = |name: &str| {
+                let f = FunctionName(sp(loc, Symbol::from(name)));
+                context.function_parameters_opt(&m, &f).map(|_| (m, f))
+            };
+            Some(IndexFunctions {
+                borrow: function("borrow"),
+                borrow_mut: function("borrow_mut"),
+            })
+        }
+        Ty::Apply(_, sp!(_, tn), _) => context.index_functions(tn).cloned(),
+        _ => None,
+    };
+    match index_fns {
+        Some(index_fns) if index_fns.borrow.is_some() || index_fns.borrow_mut.is_some() => {
+            Some(index_fns)
+        }
+        _ => {
+            let msg = format!(
+                "Invalid index. No index functions are available for type {}. Index functions \
+                 are declared with '#[{}({})]'",
+                core::error_format(&unfolded, &context.subst),
+                SyntaxAttribute::SYNTAX,
+                SyntaxAttribute::INDEX,
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidIndex, (loc, msg)));
+            None
+        }
+    }
+}
+
+// The element type of 'e[i]', taken from the signature of one of the index functions. The
+// function actually called is only known once it is known if the element is borrowed mutably
+fn index_element_type(
+    context: &mut Context,
+    loc: Loc,
+    index_fns: &IndexFunctions,
+    indexed_ty: Type,
+) -> Type {
+    let (m, f) = index_fns
+        .borrow
+        .as_ref()
+        .or(index_fns.borrow_mut.as_ref())
+        .unwrap();
+    let (_, _, params, _, ret_ty) = core::instantiate_function_type(context, loc, m, f, None);
+    let subject_ty = params.first().map(|(_, ty)| ty.value.clone());
+    match (subject_ty, ret_ty.value) {
+        (Some(Type_::Ref(_, subject_ty)), Type_::Ref(_, elem_ty)) => {
+            let msg = || format!("Invalid index of '{}::{}'", m, f);
+            subtype(context, loc, msg, indexed_ty, *subject_ty);
+            *elem_ty
+        }
+        _ => context.error_type(loc),
+    }
+}
+
 fn exp_dotted_to_borrow(
     context: &mut Context,
     loc: Loc,
@@ -1990,6 +2536,54 @@ fn exp_dotted_to_borrow(
             let ty = sp(loc, Ref(mut_, field_ty));
             T::exp(ty, sp(dloc, e_))
         }
+        ExpDotted_::Index(lhs, index_fns, index, elem_ty) => {
+            let lhs_borrow = exp_dotted_to_borrow(context, dloc, mut_, *lhs);
+            let sp!(tyloc, unfolded_) = core::unfold_type(&context.subst, lhs_borrow.ty.clone());
+            let (lhs_mut, indexed_ty) = match unfolded_ {
+                Ref(lhs_mut, indexed_ty) => (lhs_mut, indexed_ty),
+                _ => panic!(
+                    "ICE expected a ref from exp_dotted borrow, otherwise should have gotten a \
+                     TmpBorrow"
+                ),
+            };
+            // lhs is immutable and current borrow is mutable
+            if !lhs_mut && mut_ {
+                context.env.add_diag(diag!(
+                    ReferenceSafety::RefTrans,
+                    (loc, "Invalid mutable borrow from an immutable reference"),
+                    (tyloc, "Immutable because of this position"),
+                ));
+                return T::exp(context.error_type(loc), sp(dloc, TE::UnresolvedError));
+            }
+            let target = if mut_ {
+                index_fns.borrow_mut
+            } else {
+                index_fns.borrow
+            };
+            let (m, f) = match target {
+                Some(target) => target,
+                None => {
+                    let msg = format!(
+                        "Invalid {} index. No index function taking a '{}' reference is \
+                         available for type {}",
+                        if mut_ { "mutable" } else { "immutable" },
+                        if mut_ { "&mut" } else { "&" },
+                        core::error_format(&indexed_ty, &context.subst),
+                    );
+                    context
+                        .env
+                        .add_diag(diag!(TypeSafety::InvalidIndex, (loc, msg)));
+                    return T::exp(context.error_type(loc), sp(dloc, TE::UnresolvedError));
+                }
+            };
+            context.check_deprecated_call(dloc, &m, &f);
+            let args = vec![Some(lhs_borrow), Some(*index)];
+            let (ret_ty, e_) = module_call_impl(context, dloc, m, f, None, dloc, args, vec![]);
+            let ty = sp(loc, Ref(mut_, elem_ty));
+            let msg = || format!("Invalid index of '{}::{}'", m, f);
+            subtype(context, loc, msg, ret_ty, ty.clone());
+            T::exp(ty, sp(dloc, e_))
+        }
     }
 }
 
@@ -2004,22 +2598,21 @@ fn exp_dotted_to_owned_value(
         // TODO investigate this nonsense
         sp!(_, ExpDotted_::Exp(lhs)) => *lhs,
         edot => {
-            let name = match &edot {
+            let msg = match &edot {
                 sp!(_, ExpDotted_::Exp(_)) => panic!("ICE covered above"),
                 sp!(_, ExpDotted_::TmpBorrow(_, _)) => panic!("ICE why is this here?"),
-                sp!(_, ExpDotted_::Dot(_, name, _)) => *name,
-            };
-            let eborrow = exp_dotted_to_borrow(context, eloc, false, edot);
-            context.add_ability_constraint(
-                eloc,
-                Some(format!(
+                sp!(_, ExpDotted_::Dot(_, name, _)) => format!(
                     "Invalid implicit copy of field '{}' without the '{}' ability",
                     name,
                     Ability_::COPY,
-                )),
-                inner_ty.clone(),
-                Ability_::Copy,
-            );
+                ),
+                sp!(_, ExpDotted_::Index(_, _, _, _)) => format!(
+                    "Invalid implicit copy of index result without the '{}' ability",
+                    Ability_::COPY,
+                ),
+            };
+            let eborrow = exp_dotted_to_borrow(context, eloc, false, edot);
+            context.add_ability_constraint(eloc, Some(msg), inner_ty.clone(), Ability_::Copy);
             T::exp(inner_ty, sp(eloc, TE::Dereference(Box::new(eborrow))))
         }
     }
@@ -2039,6 +2632,17 @@ impl crate::shared::ast_debug::AstDebug for ExpDotted_ {
                 w.write(".");
                 w.annotate(|w| w.write(&format!("{}", n)), ty)
             }
+            D::Index(e, _, i, ty) => {
+                e.ast_debug(w);
+                w.annotate(
+                    |w| {
+                        w.write("[");
+                        i.ast_debug(w);
+                        w.write("]")
+                    },
+                    ty,
+                )
+            }
         }
     }
 }
@@ -2047,6 +2651,145 @@ impl crate::shared::ast_debug::AstDebug for ExpDotted_ {
 // Calls
 //**************************************************************************************************
 
+fn method_call(
+    context: &mut Context,
+    loc: Loc,
+    ndotted: N::ExpDotted,
+    method: Name,
+    ty_args_opt: Option<Vec<Type>>,
+    argloc: Loc,
+    nargs: Vec<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    use Type_::*;
+    let (edotted, inner_ty) = exp_dotted(context, "method call", ndotted);
+    let (m, f) = match resolve_method(context, loc, &inner_ty, method) {
+        None => {
+            assert!(context.env.has_errors());
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+        }
+        Some(target) => target,
+    };
+    context.check_deprecated_call(method.loc, &m, &f);
+    // The receiver is borrowed or copied as needed by the first parameter of the function
+    let first_param = context
+        .function_parameters_opt(&m, &f)
+        .and_then(|params| params.first())
+        .map(|(_, ty)| ty.value.clone());
+    let receiver = match first_param {
+        Some(Ref(mut_, _)) => exp_dotted_to_borrow(context, edotted.loc, mut_, edotted),
+        _ => exp_dotted_to_receiver_value(context, edotted.loc, edotted, inner_ty),
+    };
+    module_call(
+        context,
+        loc,
+        m,
+        f,
+        ty_args_opt,
+        argloc,
+        Some(receiver),
+        nargs,
+    )
+}
+
+// Finds the function called by 'e.method(...)' from the type of 'e': a 'use fun' alias in the
+// current module, otherwise the function 'method' in the module that defines the type
+fn resolve_method(
+    context: &mut Context,
+    loc: Loc,
+    receiver_ty: &Type,
+    method: Name,
+) -> Option<(ModuleIdent, FunctionName)> {
+    use TypeName_ as TN;
+    use Type_ as Ty;
+    let unfolded = core::unfold_type(&context.subst, receiver_ty.clone());
+    let tn = match &unfolded.value {
+        Ty::UnresolvedError => return None,
+        Ty::Apply(_, sp!(_, tn), _) => tn.clone(),
+        Ty::Var(_) | Ty::Anything => {
+            let msg = format!(
+                "Unable to call method '{}'. Could not infer the type of the receiver. Try \
+                 annotating its type",
+                method
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::UninferredType, (loc, msg)));
+            return None;
+        }
+        _ => {
+            let msg = format!(
+                "Invalid method call. No methods are available for type {}",
+                core::error_format(&unfolded, &context.subst)
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundMethod, (method.loc, msg)));
+            return None;
+        }
+    };
+    if let Some(use_fun) = context.use_funs.get(&(tn.clone(), method.value)) {
+        let f = FunctionName(sp(method.loc, use_fun.function.value()));
+        return Some((use_fun.module, f));
+    }
+    let m = match &tn {
+        TN::ModuleType(m, _) => *m,
+        TN::Builtin(sp!(_, BuiltinTypeName_::Vector)) => ModuleIdent_::vector(loc),
+        _ => {
+            let msg = format!(
+                "Invalid method call. No function '{}' found for type {}. Try declaring one \
+                 with 'use fun'",
+                method,
+                core::error_format(&unfolded, &context.subst)
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundMethod, (method.loc, msg)));
+            return None;
+        }
+    };
+    let f = FunctionName(method);
+    if context.function_parameters_opt(&m, &f).is_none() {
+        let msg = format!(
+            "Invalid method call. No function '{}' found in module '{}', which defines type {}",
+            method,
+            m,
+            core::error_format(&unfolded, &context.subst)
+        );
+        context
+            .env
+            .add_diag(diag!(NameResolution::UnboundMethod, (method.loc, msg)));
+        return None;
+    }
+    Some((m, f))
+}
+
+// Passes the receiver of a method call by value, copying it if it is behind a reference
+fn exp_dotted_to_receiver_value(
+    context: &mut Context,
+    loc: Loc,
+    edot: ExpDotted,
+    inner_ty: Type,
+) -> T::Exp {
+    use T::UnannotatedExp_ as TE;
+    match edot {
+        sp!(_, ExpDotted_::TmpBorrow(e, _)) => *e,
+        sp!(_, ExpDotted_::Exp(e)) => {
+            context.add_ability_constraint(
+                loc,
+                Some(format!(
+                    "Invalid implicit copy of method receiver without the '{}' ability",
+                    Ability_::COPY,
+                )),
+                inner_ty.clone(),
+                Ability_::Copy,
+            );
+            T::exp(inner_ty, sp(loc, TE::Dereference(e)))
+        }
+        edot => exp_dotted_to_owned_value(context, loc, edot, inner_ty),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn module_call(
     context: &mut Context,
     loc: Loc,
@@ -2054,10 +2797,83 @@ fn module_call(
     f: FunctionName,
     ty_args_opt: Option<Vec<Type>>,
     argloc: Loc,
-    args: Vec<T::Exp>,
+    receiver: Option<T::Exp>,
+    nargs: Vec<N::Exp>,
 ) -> (Type, T::UnannotatedExp_) {
+    // Lambdas are typed after the function type is known, so that the types of their parameters
+    // can be taken from the function signature
+    let mut args = receiver.into_iter().map(Some).collect::<Vec<_>>();
+    let mut lambdas = vec![];
+    let is_inline = context.is_inline_function(&m, &f);
+    for sp!(eloc, ne_) in nargs {
+        match ne_ {
+            N::Exp_::Lambda(nbind, nbody) => {
+                lambdas.push((args.len(), eloc, nbind, nbody));
+                args.push(None)
+            }
+            // function parameters can be passed on to other inline functions
+            N::Exp_::Move(var) | N::Exp_::Use(var)
+                if is_inline && context.is_function_local(&var) =>
+            {
+                let ty = context.get_local(eloc, "variable usage", &var);
+                args.push(Some(T::exp(ty, sp(eloc, T::UnannotatedExp_::Use(var)))))
+            }
+            ne_ => args.push(Some(exp_(context, sp(eloc, ne_)))),
+        }
+    }
+    module_call_impl(context, loc, m, f, ty_args_opt, argloc, args, lambdas)
+}
+
+// Types a call of 'm::f' whose arguments are already typed, except for the lambdas which are
+// typed here with their expected types. Each lambda leaves a 'None' hole in 'args'
+#[allow(clippy::too_many_arguments)]
+fn module_call_impl(
+    context: &mut Context,
+    loc: Loc,
+    m: ModuleIdent,
+    f: FunctionName,
+    ty_args_opt: Option<Vec<Type>>,
+    argloc: Loc,
+    mut args: Vec<Option<T::Exp>>,
+    lambdas: Vec<(usize, Loc, N::LValueList, Box<N::Exp>)>,
+) -> (Type, T::UnannotatedExp_) {
+    let is_inline = context.is_inline_function(&m, &f);
     let (_, ty_args, parameters, acquires, ret_ty) =
         core::make_function_type(context, loc, &m, &f, ty_args_opt);
+    let lambda_idxs = lambdas
+        .iter()
+        .map(|(idx, _, _, _)| *idx)
+        .collect::<BTreeSet<_>>();
+    // Check the other arguments first, so the types of the lambda parameters can be inferred
+    // from them
+    let checked_early = !lambdas.is_empty() && args.len() == parameters.len();
+    if checked_early {
+        for (arg, (param, param_ty)) in args.iter().zip(&parameters) {
+            if let Some(arg) = arg {
+                let msg = || {
+                    format!(
+                        "Invalid call of '{}::{}'. Invalid argument for parameter '{}'",
+                        &m, &f, param
+                    )
+                };
+                subtype(context, loc, msg, arg.ty.clone(), param_ty.clone());
+            }
+        }
+    }
+    for (idx, eloc, nbind, nbody) in lambdas {
+        let expected_ty = parameters.get(idx).map(|(_, ty)| ty.clone());
+        args[idx] = Some(lambda(context, eloc, nbind, nbody, expected_ty));
+    }
+    let args = args.into_iter().map(|arg| arg.unwrap()).collect();
+    if is_inline && context.current_function_inline && context.is_current_module(&m) {
+        let caller = context.current_function.unwrap();
+        context
+            .inline_calls
+            .entry(caller)
+            .or_default()
+            .entry(f)
+            .or_insert(loc);
+    }
     let (arguments, arg_tys) = call_args(
         context,
         loc,
@@ -2067,7 +2883,12 @@ fn module_call(
         args,
     );
     assert!(arg_tys.len() == parameters.len());
-    for (arg_ty, (param, param_ty)) in arg_tys.into_iter().zip(parameters.clone()) {
+    for (idx, (arg_ty, (param, param_ty))) in
+        arg_tys.into_iter().zip(parameters.clone()).enumerate()
+    {
+        if checked_early && !lambda_idxs.contains(&idx) {
+            continue;
+        }
         let msg = || {
             format!(
                 "Invalid call of '{}::{}'. Invalid argument for parameter '{}'",
@@ -2088,6 +2909,75 @@ fn module_call(
     (ret_ty, T::UnannotatedExp_::ModuleCall(Box::new(call)))
 }
 
+fn lambda(
+    context: &mut Context,
+    loc: Loc,
+    nbind: N::LValueList,
+    nbody: Box<N::Exp>,
+    expected_ty_opt: Option<Type>,
+) -> T::Exp {
+    use T::UnannotatedExp_ as TE;
+    let expected_ty_opt = expected_ty_opt.map(|ty| core::unfold_type(&context.subst, ty));
+    let (param_tys, result_ty) = match expected_ty_opt {
+        Some(sp!(_, Type_::Fun(param_tys, result_ty))) => (param_tys, *result_ty),
+        _ => {
+            let msg = "Invalid lambda. Lambdas can only be passed as arguments for the function \
+                       typed parameters of inline functions";
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidLambda, (loc, msg)));
+            return T::exp(context.error_type(loc), sp(loc, TE::UnresolvedError));
+        }
+    };
+    let old_locals = context.save_locals_scope();
+    let param_ty = Type_::multiple(nbind.loc, param_tys.clone());
+    let (declared, bind) = bind_list(context, nbind, Some(param_ty));
+    let lvalue_tys = lvalues_expected_types(context, &bind);
+    let old_lambda_info = context.enter_lambda();
+    let body = exp(context, nbody);
+    context.exit_lambda(old_lambda_info);
+    context.close_locals_scope(old_locals, declared);
+    let bloc = body.exp.loc;
+    subtype(
+        context,
+        bloc,
+        || "Invalid lambda body",
+        body.ty.clone(),
+        result_ty.clone(),
+    );
+    let ty = sp(loc, Type_::Fun(param_tys, Box::new(result_ty)));
+    T::exp(ty, sp(loc, TE::Lambda(bind, lvalue_tys, body)))
+}
+
+fn var_call(
+    context: &mut Context,
+    loc: Loc,
+    var: Var,
+    argloc: Loc,
+    args: Vec<T::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    let var_ty = context.get_local(loc, "call", &var);
+    let arity = args.len();
+    let (arguments, arg_tys) = call_args(
+        context,
+        loc,
+        || format!("Invalid call of '{}'", &var),
+        arity,
+        argloc,
+        args,
+    );
+    let ret_ty = core::make_tvar(context, loc);
+    let fun_ty = sp(loc, Type_::Fun(arg_tys, Box::new(ret_ty.clone())));
+    subtype(
+        context,
+        loc,
+        || format!("Invalid call of '{}'", &var),
+        var_ty,
+        fun_ty,
+    );
+    (ret_ty, T::UnannotatedExp_::VarCall(var, arguments))
+}
+
 fn builtin_call(
     context: &mut Context,
     loc: Loc,
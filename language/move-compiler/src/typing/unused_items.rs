@@ -0,0 +1,507 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports the module members that are never used: the private functions that are never called,
+//! the constants and structs that are never referred to, and the friend declarations of modules
+//! that do not call any 'public(friend)' function. References in specs count as uses. When test
+//! or verification code is filtered out of the compilation, the members it refers to are not
+//! reported.
+
+use crate::{
+    diag,
+    expansion::ast::{
+        self as E, AttributeName_, AttributeValue_, Attribute_, ModuleAccess_, ModuleIdent,
+        Visibility,
+    },
+    naming::ast::{self as N, TypeName_},
+    parser::ast::FunctionName,
+    shared::{
+        known_attributes::{KnownAttribute, SyntaxAttribute, TestingAttribute},
+        unique_map::UniqueMap,
+        *,
+    },
+    typing::ast as T,
+    unit_test::filter_test_members::UNIT_TEST_POISON_FUN_NAME,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, BTreeSet};
+
+const TEST_ATTR: AttributeName_ =
+    AttributeName_::Known(KnownAttribute::Testing(TestingAttribute::Test));
+const SYNTAX_ATTR: AttributeName_ =
+    AttributeName_::Known(KnownAttribute::Syntax(SyntaxAttribute::Syntax));
+
+type FunctionKey = (ModuleIdent, FunctionName);
+
+struct Context {
+    // The module members referred to, by module and name
+    used: BTreeSet<(ModuleIdent, Symbol)>,
+    // The functions called by the code of each module
+    module_calls: BTreeMap<ModuleIdent, BTreeSet<FunctionKey>>,
+    // The functions called by each inline function, as its calls are made by its callers once it
+    // is inlined
+    inline_calls: BTreeMap<FunctionKey, BTreeSet<FunctionKey>>,
+    // The module and function, if any, whose code is visited
+    current_module: Option<ModuleIdent>,
+    current_function: Option<(FunctionName, bool)>,
+}
+
+impl Context {
+    fn new() -> Self {
+        Context {
+            used: BTreeSet::new(),
+            module_calls: BTreeMap::new(),
+            inline_calls: BTreeMap::new(),
+            current_module: None,
+            current_function: None,
+        }
+    }
+
+    fn add_use(&mut self, m: ModuleIdent, n: Symbol) {
+        self.used.insert((m, n));
+    }
+
+    fn add_call(&mut self, m: ModuleIdent, f: FunctionName) {
+        let current_module = match self.current_module {
+            Some(current_module) => current_module,
+            None => return,
+        };
+        if let Some((current_function, inline)) = self.current_function {
+            // a recursive call does not make the function used
+            if current_module == m && current_function == f {
+                return;
+            }
+            if inline {
+                self.inline_calls
+                    .entry((current_module, current_function))
+                    .or_default()
+                    .insert((m, f));
+            }
+        }
+        self.add_use(m, f.value());
+        self.module_calls
+            .entry(current_module)
+            .or_default()
+            .insert((m, f));
+    }
+
+    // The functions called by the code of `mident`, including the calls of the inline functions
+    // it calls
+    fn calls_after_inlining(&self, mident: &ModuleIdent) -> BTreeSet<FunctionKey> {
+        let mut calls = BTreeSet::new();
+        let mut to_visit = self
+            .module_calls
+            .get(mident)
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(call) = to_visit.pop() {
+            if calls.insert(call) {
+                to_visit.extend(self.inline_calls.get(&call).into_iter().flatten().copied())
+            }
+        }
+        calls
+    }
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn program(
+    env: &mut CompilationEnv,
+    modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    scripts: &BTreeMap<Symbol, T::Script>,
+    spec_uses: &BTreeMap<ModuleIdent, BTreeSet<(ModuleIdent, Name)>>,
+) {
+    let mut context = Context::new();
+    for (mident, mdef) in modules.key_cloned_iter() {
+        context.current_module = Some(mident);
+        module(&mut context, mdef);
+    }
+    context.current_module = None;
+    for script_def in scripts.values() {
+        script(&mut context, script_def)
+    }
+    for (m, n) in spec_uses.values().flatten() {
+        context.add_use(*m, n.value)
+    }
+
+    for (mident, mdef) in modules.key_cloned_iter() {
+        if mdef.is_source_module {
+            report_unused(env, &context, modules, spec_uses, mident, mdef)
+        }
+    }
+}
+
+/// The module members referred to by the code of each module and script of `program`, by the
+/// location of the name of the module or of the script, as counted when reporting the members that
+/// are never used. The references in specs are found before typing, see `spec_uses` in the
+/// expansion AST
+pub fn program_uses(program: &T::Program) -> Vec<(Loc, BTreeSet<(ModuleIdent, Symbol)>)> {
+    let mut uses = vec![];
+    for (mident, mdef) in program.modules.key_cloned_iter() {
+        let mut context = Context::new();
+        context.current_module = Some(mident);
+        module(&mut context, mdef);
+        uses.push((mident.loc, context.used))
+    }
+    for script_def in program.scripts.values() {
+        let mut context = Context::new();
+        script(&mut context, script_def);
+        uses.push((script_def.loc, context.used))
+    }
+    uses
+}
+
+fn report_unused(
+    env: &mut CompilationEnv,
+    context: &Context,
+    modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    spec_uses: &BTreeMap<ModuleIdent, BTreeSet<(ModuleIdent, Name)>>,
+    mident: ModuleIdent,
+    mdef: &T::ModuleDefinition,
+) {
+    let is_used =
+        |n: Symbol| context.used.contains(&(mident, n)) || env.is_used_in_filtered_code(n);
+    // private functions can only be called from their module
+    let is_called = |f: Symbol| {
+        context.used.contains(&(mident, f)) || env.is_used_in_filtered_module_code(mident.loc, f)
+    };
+    let mut diags = vec![];
+    for (floc, f, fdef) in &mdef.functions {
+        if matches!(fdef.visibility, Visibility::Internal)
+            && fdef.entry.is_none()
+            && !matches!(fdef.body.value, T::FunctionBody_::Native)
+            && !fdef.attributes.contains_key_(&TEST_ATTR)
+            && !fdef.attributes.contains_key_(&SYNTAX_ATTR)
+            && f.as_str() != UNIT_TEST_POISON_FUN_NAME
+            && !is_called(*f)
+        {
+            let msg = format!(
+                "The non-'public', non-'entry' function '{}' is never called. Consider removing \
+                 it.",
+                f
+            );
+            diags.push(diag!(UnusedItem::Function, (floc, msg)))
+        }
+    }
+    for (cloc, c, _) in &mdef.constants {
+        if !is_used(*c) {
+            let msg = format!("The constant '{}' is never used. Consider removing it.", c);
+            diags.push(diag!(UnusedItem::Constant, (cloc, msg)))
+        }
+    }
+    for (sloc, s, sdef) in &mdef.structs {
+        if !matches!(sdef.fields, N::StructFields::Native(_)) && !is_used(*s) {
+            let msg = format!("The struct '{}' is never used. Consider removing it.", s);
+            diags.push(diag!(UnusedItem::Struct, (sloc, msg)))
+        }
+    }
+    for (friend, fdecl) in mdef.friends.key_cloned_iter() {
+        // the friend might be compiled separately
+        let friend_loc = match modules.get_loc(&friend) {
+            Some(friend_loc) => *friend_loc,
+            None => continue,
+        };
+        let is_friend_function = |f: &FunctionName| {
+            matches!(
+                mdef.functions.get(f).map(|fdef| &fdef.visibility),
+                Some(Visibility::Friend(_) | Visibility::Package(_))
+            )
+        };
+        let calls_friend_function = context
+            .calls_after_inlining(&friend)
+            .iter()
+            .filter(|(m, _)| m == &mident)
+            .any(|(_, f)| is_friend_function(f));
+        let called_from_filtered_code = mdef.functions.key_cloned_iter().any(|(f, _)| {
+            is_friend_function(&f) && env.is_used_in_filtered_module_code(friend_loc, f.value())
+        });
+        let used_in_specs = spec_uses
+            .get(&friend)
+            .into_iter()
+            .flatten()
+            .any(|(m, _)| m == &mident);
+        if !calls_friend_function && !used_in_specs && !called_from_filtered_code {
+            let msg = format!(
+                "Module '{}' is declared as a friend, but it does not call any 'public(friend)' \
+                 function of this module. Consider removing the friend declaration.",
+                friend
+            );
+            diags.push(diag!(UnusedItem::Friend, (fdecl.loc, msg)))
+        }
+    }
+    for diag in diags {
+        env.add_diag(diag)
+    }
+}
+
+//**************************************************************************************************
+// Modules
+//**************************************************************************************************
+
+fn module(context: &mut Context, mdef: &T::ModuleDefinition) {
+    attributes(context, &mdef.attributes);
+    for (_, _, sdef) in &mdef.structs {
+        attributes(context, &sdef.attributes);
+        match &sdef.fields {
+            N::StructFields::Defined(fields) => {
+                fields.iter().for_each(|(_, _, (_, ty))| type_(context, ty))
+            }
+            N::StructFields::Variants(variants) => {
+                variants.iter().for_each(|(_, _, (_, fields))| {
+                    fields.iter().for_each(|(_, _, (_, ty))| type_(context, ty))
+                })
+            }
+            N::StructFields::Native(_) => (),
+        }
+    }
+    for (_, _, cdef) in &mdef.constants {
+        constant(context, cdef)
+    }
+    for (f, fdef) in mdef.functions.key_cloned_iter() {
+        context.current_function = Some((f, fdef.inline));
+        function(context, fdef);
+        context.current_function = None;
+    }
+}
+
+fn attributes(context: &mut Context, attributes: &E::Attributes) {
+    for (_, _, attr) in attributes {
+        attribute(context, attr)
+    }
+}
+
+fn attribute(context: &mut Context, sp!(_, attr): &E::Attribute) {
+    match attr {
+        Attribute_::Name(_) => (),
+        Attribute_::Assigned(_, value) => match &value.value {
+            AttributeValue_::ModuleAccess(sp!(_, ModuleAccess_::ModuleAccess(m, n))) => {
+                context.add_use(*m, n.value)
+            }
+            AttributeValue_::ModuleAccess(sp!(_, ModuleAccess_::Name(n))) => {
+                if let Some(current_module) = context.current_module {
+                    context.add_use(current_module, n.value)
+                }
+            }
+            AttributeValue_::Value(_) | AttributeValue_::Module(_) => (),
+        },
+        Attribute_::Parameterized(_, inner) => attributes(context, inner),
+    }
+}
+
+fn constant(context: &mut Context, cdef: &T::Constant) {
+    attributes(context, &cdef.attributes);
+    type_(context, &cdef.signature);
+    exp(context, &cdef.value)
+}
+
+fn function(context: &mut Context, fdef: &T::Function) {
+    attributes(context, &fdef.attributes);
+    let T::Function {
+        signature,
+        acquires,
+        body,
+        ..
+    } = fdef;
+    for (_, ty) in &signature.parameters {
+        type_(context, ty)
+    }
+    type_(context, &signature.return_type);
+    if let Some(current_module) = context.current_module {
+        for s in acquires.keys() {
+            context.add_use(current_module, s.value())
+        }
+    }
+    match &body.value {
+        T::FunctionBody_::Native => (),
+        T::FunctionBody_::Defined(seq) => sequence(context, seq),
+    }
+}
+
+//**************************************************************************************************
+// Scripts
+//**************************************************************************************************
+
+fn script(context: &mut Context, script: &T::Script) {
+    attributes(context, &script.attributes);
+    for (_, _, cdef) in &script.constants {
+        constant(context, cdef)
+    }
+    function(context, &script.function)
+}
+
+//**************************************************************************************************
+// Types
+//**************************************************************************************************
+
+fn types<'a>(context: &mut Context, tys: impl IntoIterator<Item = &'a N::Type>) {
+    tys.into_iter().for_each(|ty| type_(context, ty))
+}
+
+fn type_(context: &mut Context, sp!(_, ty_): &N::Type) {
+    use N::Type_ as Ty;
+    match ty_ {
+        Ty::Apply(_, sp!(_, tn), tys) => {
+            if let TypeName_::ModuleType(m, s) = tn {
+                context.add_use(*m, s.value())
+            }
+            types(context, tys)
+        }
+        Ty::Ref(_, ty) => type_(context, ty),
+        Ty::Fun(tys, ty) => {
+            types(context, tys);
+            type_(context, ty)
+        }
+        Ty::Unit | Ty::Param(_) | Ty::Var(_) | Ty::Anything | Ty::UnresolvedError => (),
+    }
+}
+
+//**************************************************************************************************
+// Expressions
+//**************************************************************************************************
+
+fn sequence(context: &mut Context, seq: &T::Sequence) {
+    for sp!(_, item) in seq {
+        match item {
+            T::SequenceItem_::Seq(e) => exp(context, e),
+            T::SequenceItem_::Declare(ls) => lvalues(context, ls),
+            T::SequenceItem_::Bind(ls, tys, e) => {
+                lvalues(context, ls);
+                types(context, tys.iter().flatten());
+                exp(context, e)
+            }
+        }
+    }
+}
+
+fn lvalues(context: &mut Context, sp!(_, ls): &T::LValueList) {
+    ls.iter().for_each(|l| lvalue(context, l))
+}
+
+fn lvalue(context: &mut Context, sp!(_, l): &T::LValue) {
+    match l {
+        T::LValue_::Ignore => (),
+        T::LValue_::Var(_, ty) => type_(context, ty),
+        T::LValue_::Unpack(m, s, tys, fields) | T::LValue_::BorrowUnpack(_, m, s, tys, fields) => {
+            context.add_use(*m, s.value());
+            types(context, tys);
+            for (_, _, (_, (ty, l))) in fields {
+                type_(context, ty);
+                lvalue(context, l)
+            }
+        }
+    }
+}
+
+fn exp(context: &mut Context, e: &T::Exp) {
+    use T::UnannotatedExp_ as TE;
+    type_(context, &e.ty);
+    match &e.exp.value {
+        TE::Unit { .. }
+        | TE::Value(_)
+        | TE::Move { .. }
+        | TE::Copy { .. }
+        | TE::Use(_)
+        | TE::BorrowLocal(_, _)
+        | TE::Break(_)
+        | TE::Continue(_)
+        | TE::UnresolvedError => (),
+        TE::Spec(_, used_locals) => types(context, used_locals.values()),
+        TE::Constant(m, c) => {
+            if let Some(m) = m.or(context.current_module) {
+                context.add_use(m, c.value())
+            }
+        }
+
+        TE::ModuleCall(call) => {
+            context.add_call(call.module, call.name);
+            types(context, &call.type_arguments);
+            types(context, &call.parameter_types);
+            exp(context, &call.arguments)
+        }
+        TE::Builtin(b, e) => {
+            use T::BuiltinFunction_ as B;
+            match &b.value {
+                B::MoveTo(ty)
+                | B::MoveFrom(ty)
+                | B::BorrowGlobal(_, ty)
+                | B::Exists(ty)
+                | B::Freeze(ty) => type_(context, ty),
+                B::Assert(_) => (),
+            }
+            exp(context, e)
+        }
+        TE::Vector(_, _, ty, e) | TE::Cast(e, ty) | TE::Annotate(e, ty) => {
+            type_(context, ty);
+            exp(context, e)
+        }
+        TE::VarCall(_, e)
+        | TE::Loop { body: e, .. }
+        | TE::Return(e)
+        | TE::Abort(e)
+        | TE::Dereference(e)
+        | TE::UnaryExp(_, e)
+        | TE::Borrow(_, e, _)
+        | TE::TempBorrow(_, e) => exp(context, e),
+        TE::Lambda(ls, tys, e) | TE::Assign(ls, tys, e) => {
+            lvalues(context, ls);
+            types(context, tys.iter().flatten());
+            exp(context, e)
+        }
+        TE::IfElse(cond, if_true, if_false) => {
+            exp(context, cond);
+            exp(context, if_true);
+            exp(context, if_false)
+        }
+        TE::While(_, e1, e2) | TE::Mutate(e1, e2) => {
+            exp(context, e1);
+            exp(context, e2)
+        }
+        TE::BinopExp(e1, _, ty, e2) => {
+            exp(context, e1);
+            type_(context, ty);
+            exp(context, e2)
+        }
+        TE::Match(subject, arms) => {
+            exp(context, subject);
+            for sp!(_, (pattern, arm)) in arms {
+                if let T::MatchPattern_::Variant(m, s, _, tys, fields) = &pattern.value {
+                    context.add_use(*m, s.value());
+                    types(context, tys);
+                    for (_, _, (_, (ty, l))) in fields {
+                        type_(context, ty);
+                        lvalue(context, l)
+                    }
+                }
+                exp(context, arm)
+            }
+        }
+        TE::Block(seq) => sequence(context, seq),
+        TE::Pack(m, s, tys, fields) | TE::PackVariant(m, s, _, tys, fields) => {
+            context.add_use(*m, s.value());
+            types(context, tys);
+            for (_, _, (_, (ty, e))) in fields {
+                type_(context, ty);
+                exp(context, e)
+            }
+        }
+        TE::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, ty) => {
+                        type_(context, ty);
+                        exp(context, e)
+                    }
+                    T::ExpListItem::Splat(_, e, tys) => {
+                        types(context, tys);
+                        exp(context, e)
+                    }
+                }
+            }
+        }
+    }
+}
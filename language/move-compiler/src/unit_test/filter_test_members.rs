@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_ir_types::location::{sp, Loc};
+use move_symbol_pool::Symbol;
+use std::collections::BTreeSet;
 
 use crate::{
     diag,
@@ -35,6 +37,10 @@ impl FilterContext for Context<'_> {
         should_remove_node(self.env, attrs, is_source_def)
     }
 
+    fn filtered_source_uses(&mut self, module_name_loc: Loc, names: BTreeSet<Symbol>) {
+        self.env.add_filtered_uses(module_name_loc, names)
+    }
+
     fn filter_map_module(
         &mut self,
         mut module_def: P::ModuleDefinition,
@@ -100,6 +106,8 @@ impl FilterContext for Context<'_> {
 //***************************************************************************
 
 const UNIT_TEST_MODULE_NAME: &str = "unit_test";
+/// The name of the private function added to each module compiled in test mode
+pub const UNIT_TEST_POISON_FUN_NAME: &str = "unit_test_poison";
 const STDLIB_ADDRESS_NAME: &str = "std";
 
 // This filters out all test, and test-only annotated module member from `prog` if the `test` flag
@@ -199,9 +207,10 @@ fn create_test_poison(mloc: Loc) -> P::ModuleMember {
         loc: mloc,
         visibility: P::Visibility::Internal,
         entry: None,
+        inline: false,
         acquires: vec![],
         signature,
-        name: P::FunctionName(sp(mloc, "unit_test_poison".into())),
+        name: P::FunctionName(sp(mloc, UNIT_TEST_POISON_FUN_NAME.into())),
         body: sp(
             mloc,
             P::FunctionBody_::Defined((
@@ -241,7 +250,11 @@ fn test_attributes(attrs: &P::Attributes) -> Vec<(Loc, known_attributes::Testing
         .filter_map(
             |attr| match KnownAttribute::resolve(attr.value.attribute_name().value)? {
                 KnownAttribute::Testing(test_attr) => Some((attr.loc, test_attr)),
-                KnownAttribute::Verification(_) | KnownAttribute::Native(_) => None,
+                KnownAttribute::Verification(_)
+                | KnownAttribute::Native(_)
+                | KnownAttribute::Syntax(_)
+                | KnownAttribute::Lint(_)
+                | KnownAttribute::Deprecation(_) => None,
             },
         )
         .collect()
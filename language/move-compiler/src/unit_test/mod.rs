@@ -86,7 +86,8 @@ impl TestPlan {
 
         let module_info = units
             .into_iter()
-            .filter_map(|unit| {
+            .filter_map(|mut unit| {
+                unit.build_line_table(&files);
                 if let AnnotatedCompiledUnit::Module(annot_module) = unit {
                     Some((
                         annot_module.named_module.module.self_id(),
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_ir_types::location::Loc;
+use move_symbol_pool::Symbol;
+use std::collections::BTreeSet;
 
 use crate::{
     parser::{
@@ -32,6 +34,10 @@ impl FilterContext for Context<'_> {
     ) -> bool {
         should_remove_node(self.env, attrs)
     }
+
+    fn filtered_source_uses(&mut self, module_name_loc: Loc, names: BTreeSet<Symbol>) {
+        self.env.add_filtered_uses(module_name_loc, names)
+    }
 }
 
 //***************************************************************************
@@ -67,7 +73,11 @@ fn verification_attributes(
         .filter_map(
             |attr| match KnownAttribute::resolve(attr.value.attribute_name().value)? {
                 KnownAttribute::Verification(verify_attr) => Some((attr.loc, verify_attr)),
-                KnownAttribute::Testing(_) | KnownAttribute::Native(_) => None,
+                KnownAttribute::Testing(_)
+                | KnownAttribute::Native(_)
+                | KnownAttribute::Syntax(_)
+                | KnownAttribute::Lint(_)
+                | KnownAttribute::Deprecation(_) => None,
             },
         )
         .collect()
@@ -0,0 +1,9 @@
+module 0x42::M {
+    use std::vector;
+
+    fun t(v: vector<u64>) {
+        for (x in &mut v) {
+            vector::push_back(&mut v, *x);
+        };
+    }
+}
@@ -0,0 +1,24 @@
+module 0x8675309::M {
+    struct S has drop {
+        values: vector<u64>,
+    }
+
+    fun mut_borrow_while_borrowed(v: &mut vector<u64>) {
+        let x = &v[0];
+        let y = &mut v[1];
+        *y = *x;
+    }
+
+    fun borrow_field_while_index_borrowed(s: &mut S) {
+        let x = &mut s.values[0];
+        let values = &s.values;
+        *x = values[1];
+    }
+
+    fun local_moved_while_borrowed(v: vector<u64>): vector<u64> {
+        let x = &mut v[0];
+        let w = v;
+        *x = 1;
+        w
+    }
+}
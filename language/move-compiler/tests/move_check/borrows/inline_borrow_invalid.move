@@ -0,0 +1,21 @@
+module 0x42::m {
+    struct S { f: u64 }
+
+    inline fun update(s: &mut S, g: |&mut u64|) {
+        let r = &s.f;
+        g(&mut s.f);
+        let _ = *r;
+    }
+
+    inline fun reset(s: &mut S) {
+        update(s, |f| *f = 0)
+    }
+
+    fun t0(s: &mut S) {
+        update(s, |f| *f = *f + 1);
+    }
+
+    fun t1(s: &mut S) {
+        reset(s);
+    }
+}
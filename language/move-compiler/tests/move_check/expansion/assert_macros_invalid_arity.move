@@ -0,0 +1,7 @@
+module 0x42::M {
+    public fun t(x: u64) {
+        assert_eq!(x);
+        assert_ne!();
+        assert_eq!(x, 1, b"message", 0);
+    }
+}
@@ -0,0 +1,12 @@
+module 0x42::M {
+    struct S has drop { f: u64 }
+
+    fun foo(): u64 { 0 }
+
+    fun t(x: u64, s: S) {
+        (x, x) += 1;
+        foo() += 1;
+        S { f: _ } += s;
+        () |= x;
+    }
+}
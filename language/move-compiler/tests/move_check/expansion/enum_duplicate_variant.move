@@ -0,0 +1,7 @@
+module 0x42::m {
+    enum E {
+        A,
+        B { x: u64 },
+        A { y: bool },
+    }
+}
@@ -0,0 +1,3 @@
+module 0x42::m {
+    enum E {}
+}
@@ -0,0 +1,10 @@
+module 0x42::m {
+    enum E has drop {
+        A { x: u64 },
+    }
+
+    fun f(e: E): u64 {
+        let E::A { x } = e;
+        x
+    }
+}
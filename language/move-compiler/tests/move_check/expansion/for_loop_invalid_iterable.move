@@ -0,0 +1,7 @@
+module 0x42::M {
+    fun t(_v: vector<u64>, _n: u64) {
+        for (x in _v) { x; };
+        for (i in _n) { i; };
+        for (i in vector[1, 2]) { i; };
+    }
+}
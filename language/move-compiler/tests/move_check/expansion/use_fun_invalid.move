@@ -0,0 +1,15 @@
+module 0x42::M {
+    struct S has drop {}
+
+    use fun missing as S.missing;
+    use fun 0x42::N::f as S.f;
+
+    fun f(s: &S) {
+        s;
+    }
+
+    fun t(s: S) {
+        use fun f as S.g;
+        s.g();
+    }
+}
@@ -0,0 +1,21 @@
+address 0x42 {
+module M {
+    const MAX: u64 = 18446744073709551615;
+    const ONE: u64 = 1;
+    const ZERO: u64 = ONE - 1;
+
+    const OVERFLOW: u64 = MAX + ONE;
+    const UNDERFLOW: u64 = ZERO - ONE;
+    const MUL: u64 = MAX * 2;
+    const DIV: u64 = MAX / ZERO;
+    const MOD: u64 = MAX % ZERO;
+    const SHIFT: u64 = ONE << (MAX as u8);
+    const CAST: u8 = (MAX as u8);
+    const BITS: u8 = 64;
+    const SHL: u64 = ONE << BITS;
+
+    // the overflow is only reported in the constant where it occurs
+    const INHERITED: u64 = OVERFLOW / 2;
+    const VECTOR: vector<u64> = vector[ONE, MAX + 1];
+}
+}
@@ -0,0 +1,13 @@
+module 0x42::m {
+    #[allow(lint(not_a_lint, unused_variable), not_a_warning)]
+    fun t() {}
+
+    #[allow = 0]
+    fun u() {}
+
+    #[allow(lint = 0)]
+    fun v() {}
+
+    #[allow(lint(self_assignment = 0))]
+    fun w() {}
+}
@@ -0,0 +1,20 @@
+#[allow(lint(while_true))]
+module 0x42::m {
+    fun t(x: u64) {
+        while (true) {
+            if (x == 0) break;
+            x = x - 1;
+        };
+    }
+
+    #[allow(lint(self_assignment, shift_overflow))]
+    fun u(x: u64): u64 {
+        x = x;
+        x << 64
+    }
+
+    fun v(x: u64): u64 {
+        x = x;
+        x
+    }
+}
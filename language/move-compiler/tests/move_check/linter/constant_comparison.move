@@ -0,0 +1,7 @@
+module 0x42::m {
+    const ZERO: u64 = 0;
+
+    fun t(x: u64): bool {
+        1 < 2 || ZERO == (0: u64) || x == ZERO
+    }
+}
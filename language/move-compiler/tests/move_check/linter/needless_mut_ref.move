@@ -0,0 +1,16 @@
+module 0x42::m {
+    struct S { f: u64 }
+
+    fun read(r: &u64): u64 {
+        *r
+    }
+
+    fun read_both(r1: &u64, r2: &mut u64): u64 {
+        *r1 + *r2
+    }
+
+    fun t(s: &mut S, x: u64, y: u64): u64 {
+        let r = freeze(&mut x);
+        read(&mut s.f) + read(r) + read(&s.f) + read_both(&mut y, &mut x)
+    }
+}
@@ -0,0 +1,8 @@
+module 0x42::m {
+    struct S has copy, drop { f: u64 }
+
+    fun t(s: S, r: &u64): u64 {
+        let f = (&copy s).f;
+        f + *copy r
+    }
+}
@@ -0,0 +1,10 @@
+module 0x42::m {
+    struct S has drop { f: u64 }
+
+    fun t(x: u64, s: S, r: &mut u64): u64 {
+        x = x;
+        s.f = s.f;
+        *r = *r;
+        x + s.f
+    }
+}
@@ -0,0 +1,8 @@
+module 0x42::m {
+    fun t(x: u8, y: u64, z: u256): u256 {
+        let a = x << 8;
+        let b = y >> 64;
+        let c = y << 63;
+        (a as u256) + (b as u256) + (c as u256) + (z << 255)
+    }
+}
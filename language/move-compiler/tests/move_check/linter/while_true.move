@@ -0,0 +1,12 @@
+module 0x42::m {
+    fun t(x: u64) {
+        while (true) {
+            if (x == 0) break;
+            x = x - 1;
+        };
+        loop {
+            if (x == 10) break;
+            x = x + 1;
+        }
+    }
+}
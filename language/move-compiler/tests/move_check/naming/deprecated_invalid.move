@@ -0,0 +1,13 @@
+module 0x42::m {
+    #[deprecated = b"reason"]
+    fun f() {}
+
+    #[deprecated(note = 0)]
+    fun g() {}
+
+    #[deprecated(reason = b"reason")]
+    fun h() {}
+
+    #[deprecated]
+    use 0x42::m as n;
+}
@@ -0,0 +1,36 @@
+module 0x42::old {
+    #[deprecated(note = b"Use 'new' instead")]
+    struct S has drop { f: u64 }
+
+    #[deprecated]
+    const LIMIT: u64 = 10;
+
+    #[deprecated(note = b"Use 'new::g' instead")]
+    public fun f(): u64 { LIMIT }
+
+    public fun make(): S { S { f: f() } }
+}
+
+module 0x42::user {
+    use 0x42::old::{Self, S};
+
+    const DERIVED: u64 = 0x42::old::LIMIT + 1;
+
+    fun uses(_s: S): u64 {
+        let _ = old::make();
+        old::f() + DERIVED
+    }
+
+    #[allow(deprecated_usage)]
+    fun allowed(): u64 {
+        old::f()
+    }
+}
+
+script {
+    use 0x42::old;
+
+    fun main() {
+        old::f();
+    }
+}
@@ -0,0 +1,13 @@
+module 0x42::m {
+    fun shadowed() {
+        'a: loop {
+            'a: while (true) { break 'a };
+            break 'a
+        }
+    }
+
+    fun siblings() {
+        'a: loop { break 'a };
+        'a: loop { break 'a };
+    }
+}
@@ -0,0 +1,17 @@
+module 0x42::m {
+    fun unbound() {
+        'a: loop { break 'b };
+        while (true) { continue 'a };
+    }
+
+    fun lambda_boundary() {
+        'outer: loop {
+            apply(|x| { if (x == 0) break 'outer });
+            break
+        }
+    }
+
+    inline fun apply(f: |u64|) {
+        f(0)
+    }
+}
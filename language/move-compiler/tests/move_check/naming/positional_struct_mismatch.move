@@ -0,0 +1,22 @@
+module 0x42::m {
+    struct P(u64) has drop;
+    struct N has drop { f: u64 }
+
+    fun pack() {
+        P { f: 0 };
+        N(0);
+    }
+
+    fun unpack(p: P, n: N) {
+        let P { f } = p;
+        let N(x) = n;
+        f; x;
+    }
+
+    fun assign(p: P, n: N) {
+        let f; let x;
+        P { f } = p;
+        N(x) = n;
+        f; x;
+    }
+}
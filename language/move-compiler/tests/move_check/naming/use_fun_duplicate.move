@@ -0,0 +1,10 @@
+module 0x42::M {
+    struct S has drop {}
+
+    use fun f as S.m;
+    use fun g as S.m;
+    use fun f as T.m;
+
+    fun f(_s: &S) {}
+    fun g(_s: &S) {}
+}
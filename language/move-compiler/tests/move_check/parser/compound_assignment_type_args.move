@@ -0,0 +1,13 @@
+module 0x42::M {
+    fun t() {
+        let v: vector<vector<u8>>= vector[];
+        let w: vector<u8>= vector[];
+        v;
+        w;
+    }
+
+    fun ops(x: u64) {
+        x+=1;x-=1;x*=1;x/=1;x%=1;x&=1;x|=1;x^=1;x<<=1;x>>=1;
+        x;
+    }
+}
@@ -0,0 +1,14 @@
+// doc comments of module members can be written before or after their attributes
+module 0x42::M {
+    /// This is C.
+    #[allow(unused_constant)]
+    const C: u64 = 0;
+
+    #[allow(unused_struct)]
+    /// This is S.
+    struct S {}
+
+    /// This is f.
+    #[allow(unused_function)]
+    fun f() { }
+}
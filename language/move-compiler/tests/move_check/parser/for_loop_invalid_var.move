@@ -0,0 +1,5 @@
+module 0x42::M {
+    fun t(n: u64) {
+        for ((i, j) in 0..n) { i; }
+    }
+}
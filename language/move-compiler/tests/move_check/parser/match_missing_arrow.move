@@ -0,0 +1,11 @@
+module 0x42::m {
+    enum E has drop {
+        A,
+    }
+
+    fun f(e: E): u64 {
+        match (e) {
+            E::A 0,
+        }
+    }
+}
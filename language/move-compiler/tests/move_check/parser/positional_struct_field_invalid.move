@@ -0,0 +1,7 @@
+module 0x42::m {
+    struct S(u64) has drop;
+
+    fun t(s: S): u64 {
+        s.0u64
+    }
+}
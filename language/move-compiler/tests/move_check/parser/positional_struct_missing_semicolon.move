@@ -0,0 +1,3 @@
+module 0x42::m {
+    struct S(u64) has copy, drop { f: u64 }
+}
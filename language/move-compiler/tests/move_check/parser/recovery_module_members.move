@@ -0,0 +1,23 @@
+module 0x42::m {
+    struct S { f: u64, }
+
+    // an invalid struct does not hide the errors in the members after it
+    struct T { f u64 }
+
+    fun bad_signature(x: ): u64 { x }
+
+    const C: u64 = ;
+
+    // members after the errors are still checked
+    fun t(s: S): u64 {
+        s.g
+    }
+}
+
+module 0x42::n {
+    fun bad(
+}
+
+module 0x42::k {
+    fun t(): bool { 0 }
+}
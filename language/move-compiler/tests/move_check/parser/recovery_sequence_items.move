@@ -0,0 +1,24 @@
+module 0x42::m {
+    fun t(x: u64): u64 {
+        let y = ;
+        let z = x + ;
+        if (x > 0) {
+            let w = 1 +;
+            w
+        } else {
+            0
+        };
+        x + true
+    }
+
+    fun missing_semicolon(): u64 {
+        let a = 0
+        let b = 1;
+        b
+    }
+
+    fun invalid_final_expression(): u64 {
+        let a = 0;
+        a +
+    }
+}
@@ -0,0 +1,9 @@
+module 0x42::m {
+    fun f(): u64 {
+        let x = 0;
+        x +
+
+    fun g(): u64 {
+        false
+    }
+}
@@ -0,0 +1,18 @@
+#[allow(unused_variable)]
+module 0x42::m {
+    fun t() {
+        let x: u64;
+    }
+}
+
+module 0x42::n {
+    #[allow(unused_assignment)]
+    fun t() {
+        let x = 0;
+        x = 1;
+    }
+
+    fun u() {
+        let x = 0;
+    }
+}
@@ -0,0 +1,22 @@
+module 0x42::M {
+    // generated constants do not clash with declared ones
+    const EASSERTION_0: u64 = 0;
+
+    struct S has copy, drop { f: u64 }
+
+    public fun t(x: u64, s: S, v: vector<u8>) {
+        assert!(x == 0, b"x is not zero");
+        assert!(x == 0, EASSERTION_0);
+        assert_eq!(x, 1);
+        assert_eq!(s, S { f: 0 }, b"unexpected S");
+        assert_ne!(v, b"", b"v is empty");
+        assert_ne!(&s, &S { f: x }, x + 1);
+    }
+}
+
+script {
+    fun main(x: u64) {
+        assert!(x > 0, b"x is zero");
+        assert_eq!(x, 1, b"x is not one");
+    }
+}
@@ -0,0 +1,10 @@
+module 0x42::M {
+    struct NoDrop {}
+
+    public fun t(x: u64, b: bool, r: NoDrop) {
+        assert_eq!(x, b);
+        assert_ne!(x, 0, b"\x");
+        assert_eq!(x, 0, b);
+        assert_ne!(r, NoDrop {}, b"no drop");
+    }
+}
@@ -0,0 +1,16 @@
+module 0x42::M {
+    struct S has key, drop { f: u64 }
+
+    fun t(r: &u64, s: &S, x: u64, y: u8) {
+        *r += 1;
+        s.f -= 1;
+        x += true;
+        y <<= 1u64;
+        x;
+        y;
+    }
+
+    fun t2(c: u64) acquires S {
+        borrow_global<S>(@0x42).f *= c;
+    }
+}
@@ -0,0 +1,35 @@
+address 0x42 {
+module X {
+    const BASE: u64 = 5;
+    const BYTES: vector<u8> = b"move";
+}
+
+module M {
+    use 0x42::X;
+
+    const MAX: u64 = ((1u128 << 64) - 1 as u64);
+    const HALF: u64 = MAX / 2;
+    const SCALED: u128 = (X::BASE as u128) * 1000;
+    const SHIFTED: u256 = (SCALED as u256) << (BITS as u8);
+    const BITS: u64 = 8 * 8;
+    const IS_BIG: bool = HALF > X::BASE && !FLAG;
+    const FLAG: bool = false;
+    const ITEMS: vector<u64> = vector[X::BASE, HALF, MAX];
+    const NESTED: vector<vector<u8>> = vector[X::BYTES, X::BYTES];
+    const ADDR: address = @0x42;
+    const ADDRS: vector<address> = vector[ADDR, @0x1];
+
+    fun f(): (u64, u128, u256, bool, vector<u64>, vector<vector<u8>>, vector<address>) {
+        (HALF, SCALED, SHIFTED, IS_BIG, ITEMS, NESTED, ADDRS)
+    }
+}
+}
+
+script {
+    const A: u64 = B + 1;
+    const B: u64 = 0x42::X::BASE;
+
+    fun main() {
+        assert!(A == 6, A);
+    }
+}
@@ -0,0 +1,22 @@
+address 0x42 {
+module M {
+    const SELF: u64 = SELF + 1;
+
+    const A: u64 = B;
+    const B: u64 = C * 2;
+    const C: u64 = A;
+
+    const X: u8 = 0x42::N::Y;
+}
+
+module N {
+    const Y: u8 = 0x42::M::X + 1;
+}
+}
+
+script {
+    const A: bool = !B;
+    const B: bool = A;
+
+    fun main() {}
+}
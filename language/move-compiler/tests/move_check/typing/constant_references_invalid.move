@@ -0,0 +1,18 @@
+address 0x42 {
+module X {
+    const C: u64 = 0;
+    public fun c(): u64 { C }
+}
+
+module M {
+    use 0x42::X;
+
+    // references to other constants are only allowed in constants
+    const D: u64 = X::C + 1;
+    const E: u64 = X::c();
+
+    fun f(): u64 {
+        X::C + D
+    }
+}
+}
@@ -0,0 +1,22 @@
+module 0x42::old {
+    struct Bag has drop { values: vector<u64> }
+
+    #[deprecated(note = b"Use 'value' instead")]
+    public fun get(self: &Bag): u64 { self.values[0] }
+
+    #[deprecated]
+    #[syntax(index)]
+    public fun borrow(self: &Bag, i: u64): &u64 { &self.values[i] }
+}
+
+module 0x42::user {
+    use 0x42::old::Bag;
+
+    public fun method(b: &Bag): u64 {
+        b.get()
+    }
+
+    public fun index(b: &Bag): u64 {
+        b[0]
+    }
+}
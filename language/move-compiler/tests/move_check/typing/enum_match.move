@@ -0,0 +1,69 @@
+module 0x42::m {
+    enum Shape has copy, drop {
+        Circle { radius: u64 },
+        Rectangle { width: u64, height: u64 },
+        Empty,
+    }
+
+    enum Option<T> has copy, drop {
+        None,
+        Some { value: T },
+    }
+
+    fun area(s: Shape): u64 {
+        match (s) {
+            Shape::Circle { radius } => 3 * radius * radius,
+            Shape::Rectangle { width, height } => width * height,
+            Shape::Empty => 0,
+        }
+    }
+
+    fun is_empty(s: &Shape): bool {
+        match (s) {
+            Shape::Empty => true,
+            _ => false,
+        }
+    }
+
+    fun scale(s: &mut Shape, factor: u64) {
+        match (s) {
+            Shape::Circle { radius } => *radius = *radius * factor,
+            Shape::Rectangle { width, height: h } => {
+                *width = *width * factor;
+                *h = *h * factor;
+            },
+            Shape::Empty => (),
+        }
+    }
+
+    fun unwrap_or<T: drop>(o: Option<T>, default: T): T {
+        match (o) {
+            Option::Some { value } => value,
+            Option::None => default,
+        }
+    }
+
+    fun borrow_value<T>(o: &Option<T>, default: &T): &T {
+        match (o) {
+            Option::Some { value } => value,
+            Option::None => default,
+        }
+    }
+
+    fun make(): vector<Shape> {
+        vector[
+            Shape::Circle { radius: 1 },
+            Shape::Rectangle { width: 2, height: 3 },
+            Shape::Empty,
+            Shape::Empty {},
+        ]
+    }
+
+    fun nested(o: Option<Shape>): u64 {
+        let inner = Option::Some<u64> { value: 1 };
+        match (o) {
+            Option::Some { value } => area(value) + unwrap_or(inner, 0),
+            Option::None => abort 0,
+        }
+    }
+}
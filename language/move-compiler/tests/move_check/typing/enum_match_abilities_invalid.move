@@ -0,0 +1,27 @@
+module 0x42::m {
+    struct R {}
+
+    enum E {
+        A { r: R },
+        B,
+    }
+
+    enum NoDrop {
+        A,
+        B,
+    }
+
+    fun wildcard_drops(e: NoDrop): u64 {
+        match (e) {
+            NoDrop::A => 0,
+            _ => 1,
+        }
+    }
+
+    fun unused_resource(e: E): u64 {
+        match (e) {
+            E::A { r: _ } => 0,
+            E::B => 1,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+module 0x42::m {
+    enum E has drop {
+        A,
+        B { x: u64 },
+    }
+
+    fun after_wildcard(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            _ => 1,
+            E::B { x } => x,
+        }
+    }
+
+    fun duplicate(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B { x } => x,
+            E::A => 1,
+        }
+    }
+}
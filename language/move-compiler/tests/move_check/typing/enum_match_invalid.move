@@ -0,0 +1,67 @@
+module 0x42::m {
+    struct S has drop { f: u64 }
+
+    enum E has drop {
+        A,
+        B { x: u64 },
+    }
+
+    enum F has drop {
+        A,
+    }
+
+    fun unbound_variant(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B { x } => x,
+            E::C => 1,
+        }
+    }
+
+    fun wrong_enum(e: E): u64 {
+        match (e) {
+            F::A => 0,
+            _ => 1,
+        }
+    }
+
+    fun not_an_enum(s: S): u64 {
+        match (s) {
+            S::A => 0,
+            _ => 1,
+        }
+    }
+
+    fun missing_field(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B {} => 1,
+        }
+    }
+
+    fun unbound_field(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B { x, y } => x + y,
+        }
+    }
+
+    fun arm_type_mismatch(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B { x: _ } => false,
+        }
+    }
+
+    fun pack_missing_field(): E {
+        E::B {}
+    }
+
+    fun pack_unbound_variant(): E {
+        E::C
+    }
+
+    fun field_access(e: &E): u64 {
+        *&e.x
+    }
+}
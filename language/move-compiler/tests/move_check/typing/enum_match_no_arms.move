@@ -0,0 +1,9 @@
+module 0x42::m {
+    enum E has drop {
+        A,
+    }
+
+    fun no_arms(e: E) {
+        match (e) {}
+    }
+}
@@ -0,0 +1,20 @@
+module 0x42::m {
+    enum E has drop {
+        A,
+        B { x: u64 },
+        C { x: u64, y: bool },
+    }
+
+    fun missing_one(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B { x } => x,
+        }
+    }
+
+    fun missing_two(e: &E): u64 {
+        match (e) {
+            E::C { x, y: _ } => *x,
+        }
+    }
+}
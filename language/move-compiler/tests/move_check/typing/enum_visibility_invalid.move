@@ -0,0 +1,37 @@
+module 0x42::a {
+    enum E has drop {
+        A,
+        B { x: u64 },
+    }
+
+    public fun make(): E {
+        E::A
+    }
+}
+
+module 0x42::b {
+    use 0x42::a::{Self, E};
+
+    fun pack(): E {
+        E::B { x: 0 }
+    }
+
+    fun pack_qualified(): a::E {
+        a::E::A
+    }
+
+    fun unpack(e: E): u64 {
+        match (e) {
+            E::A => 0,
+            E::B { x } => x,
+        }
+    }
+
+    fun by_ref(): bool {
+        let e = a::make();
+        match (&e) {
+            E::A => true,
+            _ => false,
+        }
+    }
+}
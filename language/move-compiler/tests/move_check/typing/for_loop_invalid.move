@@ -0,0 +1,11 @@
+module 0x42::M {
+    struct S has drop {}
+
+    fun t(s: S) {
+        for (i in 0..s) { i; };
+        for (i in 0u8..10u64) { i; };
+        let r = 0;
+        r = for (i in 0..10) { i; };
+        r;
+    }
+}
@@ -0,0 +1,44 @@
+module 0x42::M {
+    use std::vector;
+
+    fun sum_range(n: u64): u64 {
+        let s = 0;
+        for (i in 0..n) {
+            s = s + i;
+        };
+        s
+    }
+
+    fun sum_borrow(v: vector<u64>): u64 {
+        let s = 0;
+        for (x in &v) {
+            if (*x == 0) continue;
+            if (*x > 100) break;
+            s = s + *x;
+        };
+        s
+    }
+
+    fun double(v: vector<u64>): vector<u64> {
+        for (x in &mut v) *x = *x * 2;
+        v
+    }
+
+    fun nested(v: vector<vector<u8>>): u64 {
+        let count = 0;
+        for (inner in &v) {
+            for (i in 0..vector::length(inner)) {
+                if (*vector::borrow(inner, i) == 0) count = count + 1;
+            }
+        };
+        count
+    }
+
+    fun shadowed(n: u8): u8 {
+        let i = 7;
+        for (i in 0..n) {
+            i;
+        };
+        i
+    }
+}
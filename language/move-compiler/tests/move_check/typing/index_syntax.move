@@ -0,0 +1,49 @@
+module 0x42::Table {
+    struct Table<V> has drop {
+        values: vector<V>,
+    }
+
+    struct Entry has copy, drop {
+        value: u64,
+        weights: vector<u64>,
+    }
+
+    struct Registry has drop {
+        entries: vector<Entry>,
+        table: Table<Entry>,
+    }
+
+    #[syntax(index)]
+    public fun borrow<V>(self: &Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax(index)]
+    public fun borrow_mut<V>(self: &mut Table<V>, i: u64): &mut V {
+        &mut self.values[i]
+    }
+
+    fun vectors(v: vector<u64>, r: &vector<u64>, m: &mut vector<u64>): u64 {
+        v[0] = 1;
+        m[0] = v[0] + r[0];
+        m[1] += 1;
+        let x = &mut m[2];
+        *x = 0;
+        let y: &u64 = &r[0];
+        *y + v[1]
+    }
+
+    fun nested(registry: &mut Registry): u64 {
+        registry.entries[0].value = 1;
+        registry.entries[0].weights[1] += registry.entries[1].weights[0];
+        registry.table[0].value = registry.entries[0].value;
+        let table = &mut registry.table;
+        table[1].weights[0] = 2;
+        let weights = vector[vector[1, 2], vector[3]];
+        weights[0][1] + registry.table[0].weights[0]
+    }
+
+    fun copies(entries: &vector<Entry>, table: &Table<Entry>): (Entry, Entry) {
+        (entries[0], table[1])
+    }
+}
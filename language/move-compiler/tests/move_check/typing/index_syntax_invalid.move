@@ -0,0 +1,48 @@
+module 0x42::Table {
+    struct Table<V> has drop {
+        values: vector<V>,
+    }
+
+    struct Cell has drop {
+        value: u64,
+    }
+
+    struct Unindexed has drop {}
+
+    #[syntax(index)]
+    public fun borrow<V>(self: &Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    fun no_index_functions(u: Unindexed, x: u64) {
+        u[0];
+        x[0];
+    }
+
+    fun no_mutable_index(t: &mut Table<u64>) {
+        t[0] = 1;
+        let _ = &mut t[0];
+    }
+
+    fun immutable_ref(v: &vector<u64>) {
+        v[0] = 1;
+        let _ = &mut v[0];
+    }
+
+    fun not_copyable(cells: &vector<Cell>): Cell {
+        cells[0]
+    }
+
+    fun wrong_index(t: &Table<u64>): u64 {
+        t[true]
+    }
+
+    fun wrong_element(v: vector<u64>, t: Table<bool>): u64 {
+        v[0] + t[0]
+    }
+
+    fun uninferred() {
+        let v = vector[];
+        v[0];
+    }
+}
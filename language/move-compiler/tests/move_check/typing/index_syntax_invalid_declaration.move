@@ -0,0 +1,56 @@
+module 0x42::Table {
+    struct Table<V> has drop {
+        values: vector<V>,
+    }
+
+    struct Other has drop {}
+
+    #[syntax(index)]
+    public fun borrow<V>(self: &Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax(index)]
+    public fun borrow_again<V>(self: &Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax]
+    public fun no_kind<V>(self: &Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax(method)]
+    public fun unknown_kind<V>(self: &Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax(index)]
+    public fun by_value<V: copy + drop>(self: Table<V>, i: u64): V {
+        *&self.values[i]
+    }
+
+    #[syntax(index)]
+    public fun too_many<V>(self: &Table<V>, i: u64, _j: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax(index)]
+    public fun wrong_mutability<V>(self: &mut Table<V>, i: u64): &V {
+        &self.values[i]
+    }
+
+    #[syntax(index)]
+    public fun vector_index(v: &vector<u64>, i: u64): &u64 {
+        &v[i]
+    }
+}
+
+module 0x42::Other {
+    use 0x42::Table::Table;
+
+    #[syntax(index)]
+    public fun foreign<V>(_t: &Table<V>, _i: u64): &V {
+        abort 0
+    }
+}
@@ -0,0 +1,50 @@
+module 0x42::m {
+    struct Box<T> has copy, drop { value: T }
+
+    inline fun apply<T, U>(x: T, f: |T| U): U {
+        f(x)
+    }
+
+    inline fun repeat(n: u64, f: |u64|) {
+        let i = 0;
+        while (i < n) {
+            f(i);
+            i = i + 1;
+        }
+    }
+
+    inline fun twice<T>(x: T, f: |T| T): T {
+        apply(apply(x, |y| f(y)), f)
+    }
+
+    public fun value<T: copy>(b: &Box<T>): T {
+        b.value
+    }
+
+    public inline fun unbox<T: copy>(b: &Box<T>): T {
+        value(b)
+    }
+
+    fun t0(): u64 {
+        let sum = 0;
+        repeat(10, |i| sum = sum + i);
+        sum
+    }
+
+    fun t1(): Box<u8> {
+        let b = apply(0u8, |x| Box { value: x });
+        twice(b, |b| Box { value: b.value + 1 })
+    }
+
+    fun t2(b: Box<bool>): bool {
+        unbox(&b) && apply(b, |Box { value }| value)
+    }
+}
+
+module 0x42::n {
+    use 0x42::m;
+
+    fun t(b: m::Box<u64>): u64 {
+        m::unbox(&b)
+    }
+}
@@ -0,0 +1,38 @@
+module 0x42::m {
+    inline fun apply(x: u64, f: |u64| u64): u64 {
+        f(x)
+    }
+
+    fun call(x: u64, f: u64): u64 {
+        f + x
+    }
+
+    fun t0(): u64 {
+        call(0, |x| x)
+    }
+
+    fun t1() {
+        let f = |x| x;
+    }
+
+    fun t2(): u64 {
+        apply(0, |x| return x)
+    }
+
+    fun t3(): u64 {
+        apply(0, 1)
+    }
+
+    fun t4(): u64 {
+        apply(0, |x, y| x + y)
+    }
+
+    inline fun t5(f: |u64| u64): u64 {
+        let g = f;
+        0
+    }
+
+    inline fun t6(f: |u64| u64): u64 {
+        apply(0, f)
+    }
+}
@@ -0,0 +1,17 @@
+module 0x42::m {
+    inline fun f(x: u64): u64 {
+        if (x == 0) 0 else f(x - 1)
+    }
+
+    inline fun g(x: u64): u64 {
+        h(x) + 1
+    }
+
+    inline fun h(x: u64): u64 {
+        g(x) + 1
+    }
+
+    fun t(): u64 {
+        f(1) + g(2)
+    }
+}
@@ -0,0 +1,19 @@
+module 0x42::m {
+    inline fun f(x: u64): u64 {
+        if (x == 0) return 1;
+        x
+    }
+
+    inline fun repeat(n: u64, f: |u64|) {
+        let i = 0;
+        while (i < n) {
+            f(i);
+            i = i + 1;
+        }
+    }
+
+    fun t(): u64 {
+        repeat(10, |i| if (i == 5) break);
+        f(0)
+    }
+}
@@ -0,0 +1,40 @@
+module 0x42::m {
+    struct S has drop { f: u64 }
+
+    const C: u64 = 0;
+
+    fun private(): u64 { 0 }
+
+    public inline fun pack(f: u64): S {
+        S { f }
+    }
+
+    public inline fun field(s: &S): u64 {
+        s.f
+    }
+
+    public inline fun call(): u64 {
+        private()
+    }
+
+    public inline fun nested(): u64 {
+        call()
+    }
+
+    public inline fun constant(): u64 {
+        C
+    }
+
+    fun t(s: &S): u64 {
+        field(s) + call() + nested() + constant() + pack(0).f
+    }
+}
+
+module 0x42::n {
+    use 0x42::m;
+
+    fun t(): u64 {
+        let s = m::pack(0);
+        m::field(&s) + m::call() + m::nested() + m::constant()
+    }
+}
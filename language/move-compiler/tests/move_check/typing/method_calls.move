@@ -0,0 +1,71 @@
+module 0x42::Items {
+    struct Item has copy, drop {
+        value: u64,
+        children: vector<u64>,
+    }
+
+    struct Bag has drop {
+        items: vector<Item>,
+    }
+
+    public fun new(value: u64): Item {
+        Item { value, children: vector[] }
+    }
+
+    public fun value(self: &Item): u64 {
+        self.value
+    }
+
+    public fun set_value(self: &mut Item, value: u64) {
+        self.value = value
+    }
+
+    public fun add_child(self: &mut Item, child: u64) {
+        self.children.push_back(child)
+    }
+
+    public fun child(self: &Item, i: u64): u64 {
+        *self.children.borrow(i)
+    }
+
+    public fun doubled(self: Item): u64 {
+        self.value * 2
+    }
+
+    public fun items(self: &Bag): &vector<Item> {
+        &self.items
+    }
+
+    fun t(bag: &mut Bag, item: Item, r: &Item) {
+        item.set_value(1);
+        item.add_child(2);
+        item.value();
+        item.child(0);
+        item.doubled();
+        r.value();
+        r.doubled();
+        bag.items.push_back(item);
+        bag.items.borrow_mut(0).set_value(3);
+        bag.items().borrow(0).children.length();
+        bag.items.borrow<Item>(0).child(0);
+        new(0).doubled();
+        (new(1)).value();
+    }
+}
+
+module 0x42::Aliases {
+    use 0x42::Items::{Self, Item};
+
+    use fun add as u64.plus;
+    use fun Items::value as Item.get;
+
+    fun add(x: u64, y: u64): u64 {
+        x + y
+    }
+
+    fun t(item: Item, v: vector<u64>): u64 {
+        let x: u64 = 1;
+        v.push_back(item.get());
+        item.value() + x.plus(2) + v.length()
+    }
+}
@@ -0,0 +1,36 @@
+module 0x42::Items {
+    struct Item has drop {
+        value: u64,
+    }
+
+    public fun value(self: &Item): u64 {
+        self.value
+    }
+
+    fun secret(self: &Item): u64 {
+        self.value
+    }
+
+    public fun take(self: Item): u64 {
+        let Item { value } = self;
+        value
+    }
+}
+
+module 0x42::M {
+    use 0x42::Items::Item;
+
+    struct S has drop {}
+
+    fun t<T: drop>(item: &Item, x: u64, s: S, t: T) {
+        item.missing();
+        item.secret();
+        item.take();
+        x.plus(1);
+        s.value();
+        t.value();
+        (x, x).value();
+        let y = 0;
+        y.value();
+    }
+}
@@ -0,0 +1,26 @@
+address 0x2 {
+
+module X {
+    public(package) fun f_package() {}
+
+    public inline fun f_inline_call_package() { Self::f_package() }
+}
+
+module M {
+    use 0x2::X;
+
+    public(package) fun f_package() {}
+    fun f_private() {}
+
+    // a public(package) fun can call private funs defined in its own module
+    public(package) fun f_package_call_self_private() { Self::f_private() }
+
+    // any module of the same package at the same address can call a public(package) fun,
+    // directly or through an inline function
+    public fun f_public_call_package() { X::f_package() }
+    fun f_private_call_package() { X::f_package() }
+    fun f_private_call_inline() { X::f_inline_call_package() }
+    public(package) fun f_package_call_self_package() { Self::f_package() }
+}
+
+}
@@ -0,0 +1,9 @@
+module 0x2::X {
+    public(package) fun f_package() {}
+
+    public inline fun f_inline_call_package() { Self::f_package() }
+}
+
+module 0x3::Y {
+    fun f_private_call_inline() { 0x2::X::f_inline_call_package() }
+}
@@ -0,0 +1,15 @@
+module 0x2::X {
+    public(package) fun f_package() {}
+}
+
+module 0x3::Y {
+    // public(package) funs are compiled to public(friend) funs, so they cannot be called from
+    // another address
+    fun f_private_call_package() { 0x2::X::f_package() }
+}
+
+script {
+    fun main() {
+        0x2::X::f_package()
+    }
+}
@@ -0,0 +1,39 @@
+module 0x42::m {
+    struct Coin<phantom T>(u64) has store, drop;
+    struct Pair<T>(T, bool) has copy, drop;
+    struct Outer has drop { inner: Pair<u64>, z: u8 }
+    struct USD {}
+
+    fun pack(v: u64): Coin<USD> {
+        Coin(v)
+    }
+
+    fun access<T>(c: &mut Coin<T>, p: &Pair<u8>): u64 {
+        c.0 = c.0 + (p.0 as u64);
+        *&mut c.0 = 0;
+        if (p.1) c.0 else 0
+    }
+
+    fun unpack(c: Coin<USD>, p: &Pair<u64>, o: Outer): u64 {
+        let Coin(v) = c;
+        let Pair(x, _) = p;
+        let Outer { inner: Pair(y, b), z } = o;
+        let _: bool = b;
+        v + *x + y + (z as u64)
+    }
+
+    fun assign(p: Pair<u64>): u64 {
+        let x; let b;
+        Pair(x, b) = p;
+        if (b) x else 0
+    }
+
+    fun fun_not_struct(): u64 {
+        value(Coin<USD>(1))
+    }
+
+    fun value<T>(c: Coin<T>): u64 {
+        let Coin<T>(v) = c;
+        v
+    }
+}
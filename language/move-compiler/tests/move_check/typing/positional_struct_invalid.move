@@ -0,0 +1,18 @@
+module 0x42::m {
+    struct Pair(u64, bool) has copy, drop;
+
+    fun arity() {
+        Pair(0);
+        Pair(0, true, 1);
+        let Pair(_) = Pair(0, false);
+    }
+
+    fun types(p: Pair): u64 {
+        Pair(false, 0);
+        p.1
+    }
+
+    fun fields(p: Pair): u64 {
+        p.2
+    }
+}
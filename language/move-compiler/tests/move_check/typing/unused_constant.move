@@ -0,0 +1,24 @@
+module 0x42::m {
+    const USED_IN_FUNCTION: u64 = 0;
+    const USED_IN_CONSTANT: u64 = 1;
+    const DERIVED: u64 = USED_IN_CONSTANT + 1;
+    const USED_IN_OTHER_MODULE: u64 = 2;
+
+    // unused
+    const UNUSED: u64 = 3;
+
+    #[allow(unused_constant)]
+    const ALLOWED: u64 = 4;
+
+    public fun f(): u64 {
+        USED_IN_FUNCTION + DERIVED
+    }
+}
+
+module 0x42::n {
+    const FROM_OTHER_MODULE: u64 = 0x42::m::USED_IN_OTHER_MODULE;
+
+    public fun f(): u64 {
+        FROM_OTHER_MODULE
+    }
+}
@@ -0,0 +1,31 @@
+module 0x42::m {
+    friend 0x42::calls_friend_function;
+    friend 0x42::calls_inline_function;
+    friend 0x42::calls_package_function;
+    // unused
+    friend 0x42::calls_public_function;
+
+    public(friend) fun friend_function(): u64 { 0 }
+
+    public inline fun inline_function(): u64 { friend_function() }
+
+    public(package) fun package_function(): u64 { 0 }
+
+    public fun public_function(): u64 { 0 }
+}
+
+module 0x42::calls_friend_function {
+    public fun f(): u64 { 0x42::m::friend_function() }
+}
+
+module 0x42::calls_inline_function {
+    public fun f(): u64 { 0x42::m::inline_function() }
+}
+
+module 0x42::calls_package_function {
+    public fun f(): u64 { 0x42::m::package_function() }
+}
+
+module 0x42::calls_public_function {
+    public fun f(): u64 { 0x42::m::public_function() }
+}
@@ -0,0 +1,38 @@
+module 0x42::m {
+    struct S has drop { f: u64 }
+
+    use fun get as S.get;
+
+    public fun run(s: S): u64 {
+        called() + s.get() + apply(|x| x + 1)
+    }
+
+    fun called(): u64 { 0 }
+
+    fun get(s: &S): u64 { s.f }
+
+    inline fun apply(f: |u64| u64): u64 { f(1) }
+
+    entry fun entry_point() {}
+
+    native fun native_function();
+
+    // unused
+    fun never_called() {}
+
+    // unused, only calls itself
+    fun recursive(n: u64): u64 {
+        if (n == 0) 0 else recursive(n - 1)
+    }
+
+    // unused, only called from an unused function
+    fun called_by_unused() {}
+
+    fun unused_caller() { called_by_unused() }
+
+    // unused
+    inline fun unused_inline() {}
+
+    #[allow(unused_function)]
+    fun allowed() {}
+}
@@ -0,0 +1,35 @@
+module 0x42::m {
+    friend 0x42::n;
+
+    const LIMIT: u64 = 10;
+
+    struct Config has key { limit: u64 }
+
+    public fun f(x: u64): u64 {
+        spec {
+            assert x <= limit();
+        };
+        x
+    }
+
+    fun limit(): u64 { LIMIT }
+
+    spec f {
+        aborts_if !exists<Config>(@0x42);
+    }
+
+    // unused, the spec block only targets it
+    fun only_specified() {}
+
+    spec only_specified {
+        aborts_if false;
+    }
+}
+
+module 0x42::n {
+    public fun g() {}
+
+    spec g {
+        ensures 0x42::m::f(1) == 1;
+    }
+}
@@ -0,0 +1,32 @@
+module 0x42::m {
+    struct Packed has drop { f: u64 }
+    struct Stored has key { f: u64 }
+    struct InSignature { f: u64 }
+    struct InField has store { f: u64 }
+    struct Outer has store { inner: InField }
+    struct InOtherModule has drop { f: u64 }
+    native struct Native;
+
+    // unused
+    struct Unused { f: u64 }
+
+    #[allow(unused_struct)]
+    struct Allowed { f: u64 }
+
+    public fun f(): u64 {
+        let Packed { f } = Packed { f: 0 };
+        f
+    }
+
+    public fun g(a: address): bool acquires Stored {
+        exists<Stored>(a) && borrow_global<Stored>(a).f == 0
+    }
+
+    public fun h(s: &InSignature, o: &Outer): u64 {
+        s.f + o.inner.f
+    }
+}
+
+module 0x42::n {
+    public fun f(_v: vector<0x42::m::InOtherModule>) {}
+}
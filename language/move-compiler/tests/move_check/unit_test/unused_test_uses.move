@@ -0,0 +1,48 @@
+// Private members used only by test code are not reported, whether the test code is filtered out
+// or not. Unused test-only members are reported when compiling for tests
+module 0x42::m {
+    const EFAILURE: u64 = 1;
+
+    struct UsedInTests has drop { f: u64 }
+
+    public fun f() {}
+
+    fun used_in_test(): u64 { 0 }
+
+    #[test_only]
+    fun test_helper(): u64 { used_in_test() }
+
+    // unused
+    #[test_only]
+    fun unused_test_helper() {}
+
+    #[test]
+    fun test_f() {
+        assert!(test_helper() == 0, 0)
+    }
+}
+
+#[test_only]
+module 0x42::m_tests {
+    #[test]
+    #[expected_failure(abort_code = 0x42::m::EFAILURE)]
+    fun test_failure() {
+        let _v: vector<0x42::m::UsedInTests> = vector[];
+        abort 1
+    }
+}
+
+// Filtered test code only hides the members it refers to
+module 0x42::n {
+    const EUNUSED: u64 = 1;
+
+    struct Unused has drop {}
+
+    fun unused() {}
+
+    #[test]
+    #[expected_failure(abort_code = 0x42::m::EFAILURE)]
+    fun test_abort() {
+        abort 1
+    }
+}
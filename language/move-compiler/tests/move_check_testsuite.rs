@@ -24,6 +24,9 @@ const VERIFICATION_EXT: &str = "verification";
 /// Root of tests which require to set flavor flags.
 const FLAVOR_PATH: &str = "flavors/";
 
+/// Root of tests which are compiled with the linters enabled.
+const LINTER_PATH: &str = "linter/";
+
 fn default_testing_addresses() -> BTreeMap<String, NumericalAddress> {
     let mapping = [
         ("std", "0x1"),
@@ -98,6 +101,7 @@ fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
                 .to_string();
             flags = flags.set_flavor(flavor)
         }
+        Some(p) if p.contains(LINTER_PATH) => flags = flags.set_lint(true),
         _ => {}
     };
     run_test(path, &exp_path, &out_path, flags)?;
@@ -1,4 +1,5 @@
 //# publish
+#[allow(unused_constant)]
 module 0x42::M {
     const B0: vector<bool> = vector[];
     const B1: vector<bool> = vector[true];
@@ -1,4 +1,5 @@
 //# publish
+#[allow(unused_constant)]
 module 0x42::M {
     const V0: vector<u8> = vector[
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
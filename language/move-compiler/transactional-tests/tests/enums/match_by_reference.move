@@ -0,0 +1,46 @@
+//# publish
+module 0x42::m {
+    enum Counter has drop {
+        Single { count: u64 },
+        Pair { left: u64, right: u64 },
+    }
+
+    public fun single(count: u64): Counter {
+        Counter::Single { count }
+    }
+
+    public fun pair(left: u64, right: u64): Counter {
+        Counter::Pair { left, right }
+    }
+
+    public fun bump(c: &mut Counter) {
+        match (c) {
+            Counter::Single { count } => *count = *count + 1,
+            Counter::Pair { left, right } => {
+                *left = *left + 1;
+                *right = *right + 1;
+            },
+        }
+    }
+
+    public fun total(c: &Counter): u64 {
+        match (c) {
+            Counter::Single { count } => *count,
+            Counter::Pair { left, right } => *left + *right,
+        }
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    let c = m::single(1);
+    m::bump(&mut c);
+    assert!(m::total(&c) == 2, 0);
+    let p = m::pair(1, 2);
+    m::bump(&mut p);
+    m::bump(&mut p);
+    assert!(m::total(&p) == 7, 1);
+}
+}
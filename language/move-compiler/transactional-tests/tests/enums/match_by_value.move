@@ -0,0 +1,47 @@
+//# publish
+module 0x42::m {
+    enum Shape has drop {
+        Circle { radius: u64 },
+        Rectangle { width: u64, height: u64 },
+        Empty,
+    }
+
+    public fun circle(radius: u64): Shape {
+        Shape::Circle { radius }
+    }
+
+    public fun rectangle(width: u64, height: u64): Shape {
+        Shape::Rectangle { width, height }
+    }
+
+    public fun empty(): Shape {
+        Shape::Empty
+    }
+
+    public fun area(s: Shape): u64 {
+        match (s) {
+            Shape::Circle { radius } => 3 * radius * radius,
+            Shape::Rectangle { width, height } => width * height,
+            Shape::Empty => 0,
+        }
+    }
+
+    public fun is_empty(s: Shape): bool {
+        match (s) {
+            Shape::Empty => true,
+            _ => false,
+        }
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    assert!(m::area(m::circle(2)) == 12, 0);
+    assert!(m::area(m::rectangle(2, 5)) == 10, 1);
+    assert!(m::area(m::empty()) == 0, 2);
+    assert!(m::is_empty(m::empty()), 3);
+    assert!(!m::is_empty(m::circle(1)), 4);
+}
+}
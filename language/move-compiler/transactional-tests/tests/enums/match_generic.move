@@ -0,0 +1,56 @@
+//# publish
+module 0x42::m {
+    enum Option<T> has copy, drop {
+        None,
+        Some { value: T },
+    }
+
+    public fun none<T>(): Option<T> {
+        Option::None
+    }
+
+    public fun some<T>(value: T): Option<T> {
+        Option::Some { value }
+    }
+
+    public fun unwrap_or<T: drop>(o: Option<T>, default: T): T {
+        match (o) {
+            Option::Some { value } => value,
+            Option::None => default,
+        }
+    }
+
+    public fun is_some<T>(o: &Option<T>): bool {
+        match (o) {
+            Option::Some { value: _ } => true,
+            Option::None => false,
+        }
+    }
+
+    public fun unwrap<T>(o: Option<T>): T {
+        match (o) {
+            Option::Some { value } => value,
+            Option::None => abort 42,
+        }
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    assert!(m::unwrap_or(m::some(1u64), 0) == 1, 0);
+    assert!(m::unwrap_or(m::none<u64>(), 7) == 7, 1);
+    assert!(m::is_some(&m::some(true)), 2);
+    assert!(!m::is_some(&m::none<vector<u8>>()), 3);
+    assert!(m::unwrap(m::some(b"abc")) == b"abc", 4);
+}
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    m::unwrap(m::none<u64>());
+}
+}
@@ -0,0 +1,67 @@
+//# publish
+module 0x42::m {
+    use std::vector;
+
+    public fun sum_range(lo: u64, hi: u64): u64 {
+        let s = 0;
+        for (i in lo..hi) s = s + i;
+        s
+    }
+
+    public fun sum_until_zero(v: vector<u64>): u64 {
+        let s = 0;
+        for (x in &v) {
+            if (*x == 0) break;
+            s = s + *x;
+        };
+        s
+    }
+
+    public fun sum_odd(v: vector<u64>): u64 {
+        let s = 0;
+        for (x in &v) {
+            if (*x % 2 == 0) continue;
+            s = s + *x;
+        };
+        s
+    }
+
+    public fun double_all(v: vector<u64>): vector<u64> {
+        for (x in &mut v) *x = *x * 2;
+        v
+    }
+
+    public fun count_pairs(n: u8): u64 {
+        let count = 0;
+        for (i in 0..n) {
+            for (j in i..n) {
+                if (i != j) count = count + 1;
+            }
+        };
+        count
+    }
+
+    public fun last_index(v: vector<u64>): u64 {
+        let last = 0;
+        for (i in 0..vector::length(&v)) last = i;
+        last
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    assert!(m::sum_range(0, 5) == 10, 0);
+    assert!(m::sum_range(3, 3) == 0, 1);
+    assert!(m::sum_range(5, 3) == 0, 2);
+    // the counter must not overflow when the range ends at the maximum value
+    assert!(m::sum_range(18446744073709551614, 18446744073709551615) == 18446744073709551614, 3);
+    assert!(m::sum_until_zero(vector[1, 2, 0, 4]) == 3, 4);
+    assert!(m::sum_odd(vector[1, 2, 3, 4, 5]) == 9, 5);
+    let v = m::double_all(vector[1, 2, 3]);
+    assert!(v == vector[2, 4, 6], 6);
+    assert!(m::count_pairs(4) == 6, 7);
+    assert!(m::last_index(v) == 2, 8);
+}
+}
@@ -0,0 +1,82 @@
+//# publish
+module 0x42::grid {
+    struct Row has copy, drop {
+        cells: vector<u64>,
+    }
+
+    struct Grid has copy, drop {
+        rows: vector<Row>,
+    }
+
+    public fun new(width: u64, height: u64): Grid {
+        let rows = vector[];
+        for (_ in 0..height) {
+            let cells = vector[];
+            for (_ in 0..width) cells.push_back(0);
+            rows.push_back(Row { cells });
+        };
+        Grid { rows }
+    }
+
+    #[syntax(index)]
+    public fun row(self: &Grid, i: u64): &Row {
+        &self.rows[i]
+    }
+
+    #[syntax(index)]
+    public fun row_mut(self: &mut Grid, i: u64): &mut Row {
+        &mut self.rows[i]
+    }
+
+    #[syntax(index)]
+    public fun cell(self: &Row, i: u64): &u64 {
+        &self.cells[i]
+    }
+
+    #[syntax(index)]
+    public fun cell_mut(self: &mut Row, i: u64): &mut u64 {
+        &mut self.cells[i]
+    }
+
+    public fun sum(self: &Grid): u64 {
+        let sum = 0;
+        let i = 0;
+        while (i < self.rows.length()) {
+            for (cell in &self.rows[i].cells) sum = sum + *cell;
+            i = i + 1;
+        };
+        sum
+    }
+}
+
+//# run
+script {
+use 0x42::grid;
+fun main() {
+    let grid = grid::new(3, 2);
+    grid[0][1] = 5;
+    grid[1][2] += 7;
+    let cell = &mut grid[1][0];
+    *cell = *cell + 1;
+    assert!(grid[0][1] == 5, 0);
+    assert!(*&grid[1][2] == 7, 1);
+    assert!(grid.sum() == 13, 2);
+
+    let v = vector[10, 20, 30];
+    v[0] = v[1] + v[2];
+    v[2] -= 5;
+    assert!(v == vector[50, 20, 25], 3);
+    let nested = vector[vector[1], vector[2, 3]];
+    nested[1][0] *= 10;
+    assert!(nested[1][0] + nested[0][0] == 21, 4);
+}
+}
+
+//# run
+script {
+fun main() {
+    let v = vector[1, 2, 3];
+    // out of bounds indices abort like 'vector::borrow'
+    v[3];
+}
+}
@@ -0,0 +1,58 @@
+//# publish
+module 0x42::vec {
+    use std::vector;
+
+    public inline fun for_each_ref<T>(v: &vector<T>, f: |&T|) {
+        let i = 0;
+        let n = vector::length(v);
+        while (i < n) {
+            f(vector::borrow(v, i));
+            i = i + 1;
+        }
+    }
+
+    public inline fun fold<T, R>(v: vector<T>, init: R, f: |R, T| R): R {
+        let acc = init;
+        vector::reverse(&mut v);
+        while (!vector::is_empty(&v)) {
+            acc = f(acc, vector::pop_back(&mut v));
+        };
+        vector::destroy_empty(v);
+        acc
+    }
+
+    public inline fun map<T, U>(v: vector<T>, f: |T| U): vector<U> {
+        let result = vector[];
+        vector::reverse(&mut v);
+        while (!vector::is_empty(&v)) {
+            vector::push_back(&mut result, f(vector::pop_back(&mut v)));
+        };
+        vector::destroy_empty(v);
+        result
+    }
+
+    public inline fun sum_by<T>(v: vector<T>, f: |T| u64): u64 {
+        fold(v, 0, |acc, e| acc + f(e))
+    }
+
+    public fun sum(v: &vector<u64>): u64 {
+        let s = 0;
+        for_each_ref(v, |e| s = s + *e);
+        s
+    }
+}
+
+//# run
+script {
+use 0x42::vec;
+fun main() {
+    let v = vector[1, 2, 3, 4];
+    assert!(vec::sum(&v) == 10, 0);
+    let doubled = vec::map(v, |x| x * 2);
+    assert!(doubled == vector[2, 4, 6, 8], 1);
+    let product = vec::fold(doubled, 1, |acc, x| acc * x);
+    assert!(product == 384, 2);
+    let total = vec::sum_by(vector[vector[1], vector[2, 3]], |v| std::vector::length(&v));
+    assert!(total == 3, 3);
+}
+}
@@ -0,0 +1,85 @@
+//# publish
+module 0x42::m {
+    // Returns the first pair of indices whose elements sum to 'target'
+    public fun find_pair(v: &vector<u64>, target: u64): (u64, u64) {
+        let n = std::vector::length(v);
+        let (i, j) = (0, 0);
+        let found = false;
+        'outer: while (i < n) {
+            j = i + 1;
+            while (j < n) {
+                if (*std::vector::borrow(v, i) + *std::vector::borrow(v, j) == target) {
+                    found = true;
+                    break 'outer
+                };
+                j = j + 1;
+            };
+            i = i + 1;
+        };
+        if (found) (i, j) else (n, n)
+    }
+
+    // Counts the rows that contain no zero
+    public fun rows_without_zero(rows: &vector<vector<u64>>): u64 {
+        let count = 0;
+        'rows: for (i in 0..std::vector::length(rows)) {
+            let row = std::vector::borrow(rows, i);
+            for (j in 0..std::vector::length(row)) {
+                if (*std::vector::borrow(row, j) == 0) continue 'rows;
+            };
+            count = count + 1;
+        };
+        count
+    }
+
+    // The inner loop never breaks by itself, only through its enclosing loop's label
+    public fun first_multiple(start: u64, k: u64): u64 {
+        let x = start;
+        'search: loop {
+            loop {
+                if (x % k == 0) break 'search;
+                x = x + 1;
+            }
+        };
+        x
+    }
+
+    // A labeled continue skips the rest of the enclosing loop body
+    public fun sum_skipping(n: u64, skip: u64): u64 {
+        let (i, s) = (0, 0);
+        'outer: loop {
+            i = i + 1;
+            if (i > n) break;
+            let j = 0;
+            while (j < 2) {
+                j = j + 1;
+                if (i == skip) continue 'outer;
+            };
+            s = s + i;
+        };
+        s
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    let v = vector[1, 4, 6, 9];
+    let (i, j) = m::find_pair(&v, 10);
+    assert!(i == 0 && j == 3, 0);
+    let (i, j) = m::find_pair(&v, 15);
+    assert!(i == 2 && j == 3, 1);
+    let (i, j) = m::find_pair(&v, 100);
+    assert!(i == 4 && j == 4, 2);
+
+    let rows = vector[vector[1, 2], vector[0, 3], vector[], vector[4, 0]];
+    assert!(m::rows_without_zero(&rows) == 2, 3);
+
+    assert!(m::first_multiple(10, 7) == 14, 4);
+    assert!(m::first_multiple(21, 7) == 21, 5);
+
+    assert!(m::sum_skipping(5, 3) == 12, 6);
+    assert!(m::sum_skipping(5, 0) == 15, 7);
+}
+}
@@ -0,0 +1,102 @@
+//# publish
+module 0x42::item {
+    struct Item has copy, drop {
+        value: u64,
+        tags: vector<u64>,
+    }
+
+    struct Bag has copy, drop {
+        items: vector<Item>,
+    }
+
+    public fun new(value: u64): Item {
+        Item { value, tags: vector[] }
+    }
+
+    public fun value(self: &Item): u64 {
+        self.value
+    }
+
+    public fun set_value(self: &mut Item, value: u64) {
+        self.value = value
+    }
+
+    public fun tag(self: &mut Item, tag: u64) {
+        self.tags.push_back(tag)
+    }
+
+    public fun tag_count(self: Item): u64 {
+        self.tags.length()
+    }
+
+    public fun empty_bag(): Bag {
+        Bag { items: vector[] }
+    }
+
+    public fun add(self: &mut Bag, item: Item) {
+        self.items.push_back(item)
+    }
+
+    public fun get(self: &Bag, i: u64): &Item {
+        self.items.borrow(i)
+    }
+
+    public fun get_mut(self: &mut Bag, i: u64): &mut Item {
+        self.items.borrow_mut(i)
+    }
+
+    public fun total(self: &Bag): u64 {
+        let total = 0;
+        for (item in &self.items) total = total + item.value();
+        total
+    }
+}
+
+//# publish
+module 0x42::m {
+    use 0x42::item::{Self, Bag, Item};
+
+    use fun item::value as Item.get;
+    use fun times as u64.times;
+
+    fun times(x: u64, y: u64): u64 {
+        x * y
+    }
+
+    public fun bag(): Bag {
+        let bag = item::empty_bag();
+        bag.add(item::new(1));
+        bag.add(item::new(2));
+        bag.get_mut(1).tag(7);
+        bag.get_mut(1).set_value(20);
+        bag
+    }
+
+    public fun second(bag: &Bag): u64 {
+        bag.get(1).get()
+    }
+
+    public fun scaled(bag: &Bag, factor: u64): u64 {
+        bag.total().times(factor)
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    let bag = m::bag();
+    assert!(bag.total() == 21, 0);
+    assert!(bag.get(0).value() == 1, 1);
+    assert!(m::second(&bag) == 20, 2);
+    assert!(m::scaled(&bag, 2) == 42, 3);
+    // passing the receiver by value copies it out of the reference
+    assert!(bag.get(1).tag_count() == 1, 4);
+    assert!(bag.get(0).tag_count() == 0, 5);
+    let v = vector[1, 2];
+    v.push_back(3);
+    v.reverse();
+    assert!(v == vector[3, 2, 1], 6);
+    assert!(*v.borrow(0) + v.length() == 6, 7);
+}
+}
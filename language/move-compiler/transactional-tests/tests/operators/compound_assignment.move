@@ -0,0 +1,94 @@
+//# publish
+module 0x42::m {
+    use std::vector;
+
+    struct Counter has key { value: u64 }
+
+    struct Pair { fst: u64, snd: u8 }
+
+    public fun bump_and_borrow(calls: &mut u64, v: &mut vector<u64>): &mut u64 {
+        *calls = *calls + 1;
+        vector::borrow_mut(v, 0)
+    }
+
+    public fun add_through_call(): (u64, u64) {
+        let calls = 0;
+        let v = vector[10];
+        *bump_and_borrow(&mut calls, &mut v) += 5;
+        (calls, *vector::borrow(&v, 0))
+    }
+
+    public fun fields(): (u64, u8) {
+        let p = Pair { fst: 1, snd: 1 };
+        p.fst += p.fst;
+        p.snd <<= 3;
+        let r = &mut p;
+        r.fst *= 10;
+        let Pair { fst, snd } = p;
+        (fst, snd)
+    }
+
+    public fun publish(account: &signer) {
+        move_to(account, Counter { value: 0 })
+    }
+
+    public fun incr(addr: address, by: u64) acquires Counter {
+        borrow_global_mut<Counter>(addr).value += by;
+    }
+
+    public fun value(addr: address): u64 acquires Counter {
+        borrow_global<Counter>(addr).value
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    let x = 100u64;
+    x += 5;
+    x -= 3;
+    x *= 2;
+    x /= 4;
+    x %= 7;
+    assert!(x == 2, 0);
+    let b = 0x0Fu8;
+    b &= 0x3C;
+    b |= 0x80;
+    b ^= 0x01;
+    assert!(b == 0x8D, 1);
+    b >>= 4;
+    b <<= 1;
+    assert!(b == 0x10, 2);
+
+    let (calls, first) = m::add_through_call();
+    assert!(calls == 1, 3);
+    assert!(first == 15, 4);
+
+    let (fst, snd) = m::fields();
+    assert!(fst == 20, 5);
+    assert!(snd == 8, 6);
+}
+}
+
+//# run 0x42::m::publish --signers 0x42
+
+//# run 0x42::m::incr --args @0x42 7
+
+//# run 0x42::m::incr --args @0x42 3
+
+//# run
+script {
+fun main() {
+    assert!(0x42::m::value(@0x42) == 10, 0);
+}
+}
+
+//# run
+script {
+fun main() {
+    // should fail with an arithmetic error
+    let x = 255u8;
+    x += 1;
+}
+}
@@ -1,5 +1,6 @@
 //# publish
 module 0x42::M {
+    #[allow(unused_function)]
     fun t(): u64 {
         // 1 + (if (false) 0 else (10 + 10))
         let x = 1 + if (false) 0 else 10 + 10;
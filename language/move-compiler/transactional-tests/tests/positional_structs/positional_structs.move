@@ -0,0 +1,76 @@
+//# publish
+module 0x42::m {
+    struct Coin<phantom T>(u64) has store, drop;
+    struct Pair(u64, bool) has copy, drop;
+    struct Outer has drop { inner: Pair, tag: u8 }
+    struct Nested(Pair, Coin<USD>) has drop;
+    struct USD {}
+
+    public fun mint(v: u64): Coin<USD> {
+        Coin(v)
+    }
+
+    public fun value<T>(c: &Coin<T>): u64 {
+        c.0
+    }
+
+    public fun deposit<T>(c: &mut Coin<T>, v: u64) {
+        c.0 = c.0 + v;
+    }
+
+    public fun pair(x: u64, b: bool): Pair {
+        Pair(x, b)
+    }
+
+    public fun outer(inner: Pair, tag: u8): Outer {
+        Outer { inner, tag }
+    }
+
+    public fun nested(x: u64, v: u64): Nested {
+        Nested(Pair(x, false), Coin(v))
+    }
+
+    // Destructures nested named and positional structs in a single binding
+    public fun sum_outer(o: Outer): u64 {
+        let Outer { inner: Pair(x, b), tag } = o;
+        if (b) x + (tag as u64) else 0
+    }
+
+    public fun sum_nested(n: Nested): u64 {
+        let Nested(Pair(x, _), Coin(v)) = n;
+        x + v
+    }
+
+    public fun sum_nested_ref(n: &Nested): u64 {
+        n.0.0 + n.1.0
+    }
+
+    public fun swap(p: Pair, v: u64): (u64, bool) {
+        let x; let b;
+        Pair(x, b) = p;
+        p = Pair(v, !b);
+        (x + p.0, p.1)
+    }
+}
+
+//# run
+script {
+use 0x42::m;
+fun main() {
+    let c = m::mint(5);
+    m::deposit(&mut c, 2);
+    assert!(m::value(&c) == 7, 0);
+
+    let p = m::pair(10, true);
+    assert!(m::sum_outer(m::outer(p, 3)) == 13, 1);
+    assert!(m::sum_outer(m::outer(m::pair(10, false), 3)) == 0, 2);
+
+    let n = m::nested(4, 6);
+    assert!(m::sum_nested_ref(&n) == 10, 3);
+    assert!(m::sum_nested(n) == 10, 4);
+
+    let (x, b) = m::swap(p, 1);
+    assert!(x == 11, 5);
+    assert!(!b, 6);
+}
+}
@@ -78,7 +78,7 @@ pub struct AccountChangeSet {
 ///
 /// It is possible to have a pair of operations resulting in conflicting states, in which case the
 /// squash will fail.
-fn squash<K, V>(map: &mut BTreeMap<K, Op<V>>, other: BTreeMap<K, Op<V>>) -> Result<()>
+pub fn squash<K, V>(map: &mut BTreeMap<K, Op<V>>, other: BTreeMap<K, Op<V>>) -> Result<()>
 where
     K: Ord,
 {
@@ -16,10 +16,20 @@ use std::{
 pub struct ErrorDescription {
     /// The constant name of error e.g., ECANT_PAY_DEPOSIT
     pub code_name: String,
-    /// The code description. This is generated from the doc comments on the constant.
+    /// The code description. This is generated from the doc comments on the constant, or is the
+    /// message of the assertion raising the error.
     pub code_description: String,
+    /// The source location of the assertion raising the error, e.g. `sources/m.move:12:9`, if the
+    /// error is raised by an assertion with a message. It is not part of the encoding of the
+    /// description, see `ErrorMapping::to_bytes`.
+    #[serde(skip)]
+    pub location: Option<String>,
 }
 
+/// The source locations of the module-specific errors that have one, stored after the error mapping
+/// they belong to
+type ErrorLocations = BTreeMap<ModuleId, BTreeMap<u64, String>>;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ErrorMapping {
     /// The set of error categories and their descriptions
@@ -59,14 +69,71 @@ impl ErrorMapping {
         Ok(())
     }
 
+    /// Adds the error categories and the module-specific errors of `other` to this mapping
+    pub fn extend(&mut self, other: ErrorMapping) -> Result<()> {
+        for (category_id, description) in other.error_categories {
+            self.add_error_category(category_id, description)?
+        }
+        for (module_id, module_error_map) in other.module_error_maps {
+            for (abort_code, description) in module_error_map {
+                self.add_module_error(module_id.clone(), abort_code, description)?
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes a mapping encoded by `to_bytes`, or by versions which did not record the locations
+    /// of errors.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (mut mapping, locations) =
+            match bcs::from_bytes::<(ErrorMapping, ErrorLocations)>(bytes) {
+                Ok(mapping_and_locations) => mapping_and_locations,
+                Err(_) => (bcs::from_bytes::<ErrorMapping>(bytes)?, BTreeMap::new()),
+            };
+        for (module_id, module_locations) in locations {
+            for (abort_code, location) in module_locations {
+                if let Some(description) = mapping
+                    .module_error_maps
+                    .get_mut(&module_id)
+                    .and_then(|module_error_map| module_error_map.get_mut(&abort_code))
+                {
+                    description.location = Some(location)
+                }
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// Encodes the mapping in BCS. The locations of the errors are encoded in a separate table
+    /// after the mapping, and only if there are any, so that mappings without locations keep the
+    /// encoding of the versions which did not record them.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut locations = ErrorLocations::new();
+        for (module_id, module_error_map) in &self.module_error_maps {
+            for (abort_code, description) in module_error_map {
+                if let Some(location) = &description.location {
+                    locations
+                        .entry(module_id.clone())
+                        .or_default()
+                        .insert(*abort_code, location.clone());
+                }
+            }
+        }
+        Ok(if locations.is_empty() {
+            bcs::to_bytes(self)?
+        } else {
+            bcs::to_bytes(&(self, locations))?
+        })
+    }
+
     pub fn from_file<P: AsRef<Path>>(path: P) -> Self {
         let mut bytes = Vec::new();
         File::open(path).unwrap().read_to_end(&mut bytes).unwrap();
-        bcs::from_bytes(&bytes).unwrap()
+        Self::from_bytes(&bytes).unwrap()
     }
 
     pub fn to_file<P: AsRef<Path>>(&self, path: P) {
-        let bytes = bcs::to_bytes(self).unwrap();
+        let bytes = self.to_bytes().unwrap();
         let mut file = File::create(path).unwrap();
         file.write_all(&bytes).unwrap();
     }
@@ -0,0 +1,81 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    errmap::{ErrorDescription, ErrorMapping},
+    identifier::Identifier,
+    language_storage::ModuleId,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// The encoding of error mappings before they recorded the locations of errors
+#[derive(Serialize)]
+struct OldErrorDescription {
+    code_name: String,
+    code_description: String,
+}
+
+#[derive(Serialize)]
+struct OldErrorMapping {
+    error_categories: BTreeMap<u64, OldErrorDescription>,
+    module_error_maps: BTreeMap<ModuleId, BTreeMap<u64, OldErrorDescription>>,
+}
+
+fn module_id() -> ModuleId {
+    ModuleId::new(AccountAddress::ONE, Identifier::new("M").unwrap())
+}
+
+fn mapping(location: Option<&str>) -> ErrorMapping {
+    let mut mapping = ErrorMapping::default();
+    mapping
+        .add_module_error(
+            module_id(),
+            1,
+            ErrorDescription {
+                code_name: "EFAIL".to_owned(),
+                code_description: "failed".to_owned(),
+                location: location.map(str::to_owned),
+            },
+        )
+        .unwrap();
+    mapping
+}
+
+#[test]
+fn decodes_mappings_without_locations() {
+    let old = OldErrorMapping {
+        error_categories: BTreeMap::new(),
+        module_error_maps: BTreeMap::from([(
+            module_id(),
+            BTreeMap::from([(
+                1,
+                OldErrorDescription {
+                    code_name: "EFAIL".to_owned(),
+                    code_description: "failed".to_owned(),
+                },
+            )]),
+        )]),
+    };
+    let old_bytes = bcs::to_bytes(&old).unwrap();
+    let description = ErrorMapping::from_bytes(&old_bytes)
+        .unwrap()
+        .get_explanation(&module_id(), 1)
+        .unwrap();
+    assert_eq!(description.code_name, "EFAIL");
+    assert_eq!(description.location, None);
+
+    // mappings without locations are encoded as before
+    assert_eq!(mapping(None).to_bytes().unwrap(), old_bytes);
+}
+
+#[test]
+fn round_trips_locations() {
+    let bytes = mapping(Some("sources/M.move:3:9")).to_bytes().unwrap();
+    let description = ErrorMapping::from_bytes(&bytes)
+        .unwrap()
+        .get_explanation(&module_id(), 1)
+        .unwrap();
+    assert_eq!(description.location.as_deref(), Some("sources/M.move:3:9"));
+}
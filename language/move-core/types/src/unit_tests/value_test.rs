@@ -7,7 +7,7 @@ use crate::{
     ident_str,
     identifier::Identifier,
     language_storage::{StructTag, TypeTag},
-    value::{MoveStruct, MoveValue},
+    value::{MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
 };
 use serde_json::json;
 
@@ -136,3 +136,35 @@ fn nested_typed_struct_deserialization() {
         })
     );
 }
+
+/// A test which verifies that a value of a struct with variants is encoded in BCS as the
+/// variant index followed by the fields of the variant, and that it round-trips through its
+/// layout.
+#[test]
+fn struct_variant_roundtrip() {
+    let layout = MoveTypeLayout::Struct(MoveStructLayout::RuntimeVariants(vec![
+        vec![],
+        vec![MoveTypeLayout::U64, MoveTypeLayout::Bool],
+    ]));
+    let value = MoveValue::Struct(MoveStruct::RuntimeVariant(
+        1,
+        vec![MoveValue::U64(7), MoveValue::Bool(true)],
+    ));
+    let blob = value.simple_serialize().unwrap();
+    assert_eq!(blob, vec![1, 7, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(
+        MoveValue::simple_deserialize(&blob, &layout).unwrap(),
+        value
+    );
+
+    let empty = MoveValue::Struct(MoveStruct::RuntimeVariant(0, vec![]));
+    let blob = empty.simple_serialize().unwrap();
+    assert_eq!(blob, vec![0]);
+    assert_eq!(
+        MoveValue::simple_deserialize(&blob, &layout).unwrap(),
+        empty
+    );
+
+    // an out of range variant index is rejected
+    assert!(MoveValue::simple_deserialize(&[2], &layout).is_err());
+}
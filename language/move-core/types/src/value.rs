@@ -11,7 +11,7 @@ use crate::{
 use anyhow::{anyhow, bail, Result as AResult};
 use serde::{
     de::Error as DeError,
-    ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple},
+    ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleVariant},
     Deserialize, Serialize,
 };
 use std::{
@@ -28,6 +28,13 @@ pub const MOVE_STRUCT_TYPE: &str = "type";
 /// In the `WithTypes` configuration, a Move struct gets serialized into a Serde struct with this as the second field
 pub const MOVE_STRUCT_FIELDS: &str = "fields";
 
+/// A Move struct with variants gets serialized into a Serde enum with this name
+pub const MOVE_ENUM_NAME: &str = "enum";
+
+/// Serde requires static variant names; since the BCS encoding of an enum only uses the variant
+/// index, all variants of a Move struct with variants share this placeholder name
+pub const MOVE_VARIANT_NAME: &str = "variant";
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum MoveStruct {
     /// The representation used by the MoveVM
@@ -39,6 +46,12 @@ pub enum MoveStruct {
         type_: StructTag,
         fields: Vec<(Identifier, MoveValue)>,
     },
+    /// The representation used by the MoveVM for a value of a struct declared with variants:
+    /// the index of the variant and the values of its fields
+    RuntimeVariant(u16, Vec<MoveValue>),
+    /// A decorated representation of a variant value with the variant name and human-readable
+    /// field names
+    WithVariantFields(Identifier, u16, Vec<(Identifier, MoveValue)>),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -70,6 +83,19 @@ impl MoveFieldLayout {
     }
 }
 
+/// A layout associated with a named variant of a struct declared with variants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveVariantLayout {
+    pub name: Identifier,
+    pub fields: Vec<MoveFieldLayout>,
+}
+
+impl MoveVariantLayout {
+    pub fn new(name: Identifier, fields: Vec<MoveFieldLayout>) -> Self {
+        Self { name, fields }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MoveStructLayout {
     /// The representation used by the MoveVM
@@ -81,6 +107,12 @@ pub enum MoveStructLayout {
         type_: StructTag,
         fields: Vec<MoveFieldLayout>,
     },
+    /// The representation used by the MoveVM for a struct declared with variants: the field
+    /// layouts of each variant, indexed by variant
+    RuntimeVariants(Vec<Vec<MoveTypeLayout>>),
+    /// A decorated representation of a struct declared with variants with human-readable variant
+    /// and field names
+    WithVariants(Vec<MoveVariantLayout>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -229,14 +261,27 @@ impl MoveStruct {
                         .collect(),
                 }
             }
+            (MoveStruct::RuntimeVariant(tag, vals), MoveStructLayout::WithVariants(variants))
+                if (tag as usize) < variants.len() =>
+            {
+                let variant = &variants[tag as usize];
+                MoveStruct::WithVariantFields(
+                    variant.name.clone(),
+                    tag,
+                    vals.into_iter()
+                        .zip(&variant.fields)
+                        .map(|(v, l)| (l.name.clone(), v.decorate(&l.layout)))
+                        .collect(),
+                )
+            }
             (v, _) => v, // already decorated
         }
     }
 
     pub fn fields(&self) -> &[MoveValue] {
         match self {
-            Self::Runtime(vals) => vals,
-            Self::WithFields(_) | Self::WithTypes { .. } => {
+            Self::Runtime(vals) | Self::RuntimeVariant(_, vals) => vals,
+            Self::WithFields(_) | Self::WithTypes { .. } | Self::WithVariantFields(..) => {
                 // It's not possible to implement this without changing the return type, and thus
                 // panicking is the best move
                 panic!("Getting fields for decorated representation")
@@ -246,20 +291,32 @@ impl MoveStruct {
 
     pub fn into_fields(self) -> Vec<MoveValue> {
         match self {
-            Self::Runtime(vals) => vals,
-            Self::WithFields(fields) | Self::WithTypes { fields, .. } => {
-                fields.into_iter().map(|(_, f)| f).collect()
-            }
+            Self::Runtime(vals) | Self::RuntimeVariant(_, vals) => vals,
+            Self::WithFields(fields)
+            | Self::WithTypes { fields, .. }
+            | Self::WithVariantFields(_, _, fields) => fields.into_iter().map(|(_, f)| f).collect(),
+        }
+    }
+
+    /// Returns the variant index if this is a value of a struct declared with variants.
+    pub fn variant_tag(&self) -> Option<u16> {
+        match self {
+            Self::RuntimeVariant(tag, _) | Self::WithVariantFields(_, tag, _) => Some(*tag),
+            Self::Runtime(_) | Self::WithFields(_) | Self::WithTypes { .. } => None,
         }
     }
 
     pub fn undecorate(self) -> Self {
-        Self::Runtime(
-            self.into_fields()
-                .into_iter()
-                .map(MoveValue::undecorate)
-                .collect(),
-        )
+        let tag = self.variant_tag();
+        let fields = self
+            .into_fields()
+            .into_iter()
+            .map(MoveValue::undecorate)
+            .collect();
+        match tag {
+            Some(tag) => Self::RuntimeVariant(tag, fields),
+            None => Self::Runtime(fields),
+        }
     }
 }
 
@@ -279,7 +336,10 @@ impl MoveStructLayout {
     pub fn fields(&self) -> &[MoveTypeLayout] {
         match self {
             Self::Runtime(vals) => vals,
-            Self::WithFields(_) | Self::WithTypes { .. } => {
+            Self::WithFields(_)
+            | Self::WithTypes { .. }
+            | Self::RuntimeVariants(_)
+            | Self::WithVariants(_) => {
                 // It's not possible to implement this without changing the return type, and some
                 // performance-critical VM serialization code uses the Runtime case of this.
                 // panicking is the best move
@@ -294,6 +354,10 @@ impl MoveStructLayout {
             Self::WithFields(fields) | Self::WithTypes { fields, .. } => {
                 fields.into_iter().map(|f| f.layout).collect()
             }
+            Self::RuntimeVariants(_) | Self::WithVariants(_) => {
+                // A struct with variants has no single list of fields
+                panic!("Getting fields for a struct with variants")
+            }
         }
     }
 }
@@ -395,6 +459,51 @@ impl<'d, 'a> serde::de::Visitor<'d> for StructFieldVisitor<'a> {
     }
 }
 
+struct StructVariantVisitor<'a>(&'a MoveStructLayout);
+
+impl<'d, 'a> serde::de::Visitor<'d> for StructVariantVisitor<'a> {
+    type Value = MoveStruct;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Struct variant")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'d>,
+    {
+        use serde::de::VariantAccess;
+
+        let (tag, variant) = data.variant::<u32>()?;
+        let out_of_range =
+            || A::Error::custom(format!("variant index {} out of range", tag).as_str());
+        match self.0 {
+            MoveStructLayout::RuntimeVariants(variants) => {
+                let layout = variants.get(tag as usize).ok_or_else(out_of_range)?;
+                let fields = variant.tuple_variant(layout.len(), StructFieldVisitor(layout))?;
+                Ok(MoveStruct::RuntimeVariant(tag as u16, fields))
+            }
+            MoveStructLayout::WithVariants(variants) => {
+                let layout = variants.get(tag as usize).ok_or_else(out_of_range)?;
+                let fields = variant.tuple_variant(
+                    layout.fields.len(),
+                    DecoratedStructFieldVisitor(&layout.fields),
+                )?;
+                Ok(MoveStruct::WithVariantFields(
+                    layout.name.clone(),
+                    tag as u16,
+                    fields,
+                ))
+            }
+            MoveStructLayout::Runtime(_)
+            | MoveStructLayout::WithFields(_)
+            | MoveStructLayout::WithTypes { .. } => Err(A::Error::custom(
+                "expected a layout for a struct with variants",
+            )),
+        }
+    }
+}
+
 impl<'d> serde::de::DeserializeSeed<'d> for &MoveFieldLayout {
     type Value = (Identifier, MoveValue);
 
@@ -435,6 +544,13 @@ impl<'d> serde::de::DeserializeSeed<'d> for &MoveStructLayout {
                     fields,
                 })
             }
+            MoveStructLayout::RuntimeVariants(_) | MoveStructLayout::WithVariants(_) => {
+                deserializer.deserialize_enum(
+                    MOVE_ENUM_NAME,
+                    &[MOVE_VARIANT_NAME],
+                    StructVariantVisitor(self),
+                )
+            }
         }
     }
 }
@@ -498,6 +614,30 @@ impl serde::Serialize for MoveStruct {
                 t.serialize_field(MOVE_STRUCT_FIELDS, &MoveFields(fields))?;
                 t.end()
             }
+            Self::RuntimeVariant(tag, s) => {
+                let mut t = serializer.serialize_tuple_variant(
+                    MOVE_ENUM_NAME,
+                    *tag as u32,
+                    MOVE_VARIANT_NAME,
+                    s.len(),
+                )?;
+                for v in s.iter() {
+                    t.serialize_field(v)?;
+                }
+                t.end()
+            }
+            Self::WithVariantFields(_, tag, fields) => {
+                let mut t = serializer.serialize_tuple_variant(
+                    MOVE_ENUM_NAME,
+                    *tag as u32,
+                    MOVE_VARIANT_NAME,
+                    fields.len(),
+                )?;
+                for (_, v) in fields.iter() {
+                    t.serialize_field(v)?;
+                }
+                t.end()
+            }
         }
     }
 }
@@ -548,6 +688,24 @@ impl fmt::Display for MoveStructLayout {
                     write!(f, "{}, ", field)?
                 }
             }
+            Self::RuntimeVariants(variants) => {
+                for (i, layouts) in variants.iter().enumerate() {
+                    write!(f, "{}: {{ ", i)?;
+                    for (j, l) in layouts.iter().enumerate() {
+                        write!(f, "{}: {}, ", j, l)?
+                    }
+                    write!(f, "}}, ")?
+                }
+            }
+            Self::WithVariants(variants) => {
+                for variant in variants {
+                    write!(f, "{}: {{ ", variant.name)?;
+                    for field in &variant.fields {
+                        write!(f, "{}, ", field)?
+                    }
+                    write!(f, "}}, ")?
+                }
+            }
         }
         write!(f, "}}")
     }
@@ -582,7 +740,7 @@ impl TryInto<StructTag> for &MoveStructLayout {
     fn try_into(self) -> Result<StructTag, Self::Error> {
         use MoveStructLayout::*;
         match self {
-            Runtime(..) | WithFields(..) => bail!(
+            Runtime(..) | WithFields(..) | RuntimeVariants(..) | WithVariants(..) => bail!(
                 "Invalid MoveTypeLayout -> StructTag conversion--needed MoveLayoutType::WithTypes"
             ),
             WithTypes { type_, .. } => Ok(type_.clone()),
@@ -620,6 +778,14 @@ impl fmt::Display for MoveStruct {
                 fmt::Display::fmt(type_, f)?;
                 fmt_list(f, " {", fields.iter().map(DisplayFieldBinding), "}")
             }
+            MoveStruct::RuntimeVariant(tag, v) => {
+                write!(f, "#{}", tag)?;
+                fmt_list(f, "[", v, "]")
+            }
+            MoveStruct::WithVariantFields(name, _, fields) => {
+                write!(f, "{}", name)?;
+                fmt_list(f, " {", fields.iter().map(DisplayFieldBinding), "}")
+            }
         }
     }
 }
@@ -608,6 +608,10 @@ pub enum StatusCode {
     MAX_FUNCTION_DEFINITIONS_REACHED = 1119,
     MAX_STRUCT_DEFINITIONS_REACHED = 1120,
     MAX_FIELD_DEFINITIONS_REACHED = 1121,
+    // A plain struct operation was used on a struct with variants, or vice versa.
+    STRUCT_VARIANT_MISMATCH = 1122,
+    // The operand of a variant test is not a reference to the tested struct.
+    TEST_VARIANT_TYPE_MISMATCH_ERROR = 1123,
 
     // These are errors that the VM might raise if a violation of internal
     // invariants takes place.
@@ -679,6 +683,17 @@ pub enum StatusCode {
     STORAGE_WRITE_LIMIT_REACHED = 4027,
     MEMORY_LIMIT_EXCEEDED = 4028,
     VM_MAX_TYPE_NODES_REACHED = 4029,
+    // A variant operation was applied to a value holding a different variant.
+    VARIANT_TAG_MISMATCH = 4030,
+    // A vector instruction would exceed the maximal vector length configured for the VM.
+    VM_MAX_VECTOR_LENGTH_REACHED = 4031,
+    // Loading a resource would exceed the maximal bytes of resources loaded in a session.
+    VM_MAX_LOADED_RESOURCE_BYTES_REACHED = 4032,
+    // Emitting an event would exceed the maximal number of events in a session.
+    VM_MAX_EVENTS_REACHED = 4033,
+    // Converting a type to a layout would exceed the maximal number of type nodes configured for
+    // the VM.
+    VM_MAX_TYPE_LAYOUT_NODES_REACHED = 4034,
 
     // A reserved status to represent an unknown vm status.
     // this is std::u64::MAX, but we can't pattern match on that, so put the hardcoded value in
@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+pub mod line_table;
 pub mod mapping;
 pub mod marking;
 pub mod source_map;
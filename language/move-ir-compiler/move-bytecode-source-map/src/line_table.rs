@@ -0,0 +1,133 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::{CodeOffset, FunctionDefinitionIndex, TableIndex};
+use move_command_line_common::files::FileHash;
+use move_ir_types::location::Loc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+//***************************************************************************
+// Line tables
+//***************************************************************************
+
+/// A zero-based line and column in a source file. The column is the byte offset of the position
+/// from the start of its line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LinePosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// The start and end positions of a `Loc` in its source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceRange {
+    pub file_hash: FileHash,
+    pub start: LinePosition,
+    pub end: LinePosition,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SourceFileLines {
+    /// The path of the source file, as given to the compiler.
+    pub path: String,
+
+    /// The byte offset at which each line of the file starts. The first line starts at 0.
+    line_starts: Vec<u32>,
+}
+
+/// Line and column information for the source files referenced by a source map, along with the
+/// inverse mapping from source lines to the code offsets that start on them. Unlike the rest of
+/// the source map, which only records byte spans, this allows the locations of instructions to be
+/// displayed without access to the source files.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LineTable {
+    // The lines of each source file, by file hash.
+    files: BTreeMap<FileHash, SourceFileLines>,
+
+    // A mapping of each (file, zero-based line) to the function definition indices and code
+    // offsets of the code segments whose source locations start on that line, in ascending order.
+    code_offsets: BTreeMap<(FileHash, u32), Vec<(TableIndex, CodeOffset)>>,
+}
+
+impl LineTable {
+    /// Records the lines of the file with the given contents.
+    pub fn add_file(&mut self, file_hash: FileHash, path: impl Into<String>, contents: &str) {
+        let line_starts = std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(idx, _)| idx as u32 + 1))
+            .collect();
+        self.files.insert(
+            file_hash,
+            SourceFileLines {
+                path: path.into(),
+                line_starts,
+            },
+        );
+    }
+
+    /// Records that the code segment of the function at `fdef_idx` starting at `offset` has the
+    /// source location `location`. Does nothing if the file of the location has not been added.
+    pub fn add_code_offset(
+        &mut self,
+        fdef_idx: FunctionDefinitionIndex,
+        offset: CodeOffset,
+        location: Loc,
+    ) {
+        let file_hash = location.file_hash();
+        if let Some(position) = self.position(file_hash, location.start()) {
+            let offsets = self
+                .code_offsets
+                .entry((file_hash, position.line))
+                .or_default();
+            let entry = (fdef_idx.0, offset);
+            if let Err(idx) = offsets.binary_search(&entry) {
+                offsets.insert(idx, entry);
+            }
+        }
+    }
+
+    pub fn contains_file(&self, file_hash: FileHash) -> bool {
+        self.files.contains_key(&file_hash)
+    }
+
+    pub fn file_path(&self, file_hash: FileHash) -> Option<&str> {
+        self.files.get(&file_hash).map(|file| file.path.as_str())
+    }
+
+    /// Returns the line and column of the byte offset `byte_offset` in the given file.
+    pub fn position(&self, file_hash: FileHash, byte_offset: u32) -> Option<LinePosition> {
+        let line_starts = &self.files.get(&file_hash)?.line_starts;
+        let line = match line_starts.binary_search(&byte_offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+        Some(LinePosition {
+            line: line as u32,
+            column: byte_offset - line_starts[line],
+        })
+    }
+
+    /// Returns the start and end positions of `location`.
+    pub fn range(&self, location: Loc) -> Option<SourceRange> {
+        let file_hash = location.file_hash();
+        Some(SourceRange {
+            file_hash,
+            start: self.position(file_hash, location.start())?,
+            end: self.position(file_hash, location.end())?,
+        })
+    }
+
+    /// Returns the function definition indices and code offsets of the code segments whose source
+    /// locations start on the zero-based `line` of the given file.
+    pub fn code_offsets(
+        &self,
+        file_hash: FileHash,
+        line: u32,
+    ) -> impl Iterator<Item = (FunctionDefinitionIndex, CodeOffset)> + '_ {
+        self.code_offsets
+            .get(&(file_hash, line))
+            .into_iter()
+            .flatten()
+            .map(|(fdef_idx, offset)| (FunctionDefinitionIndex(*fdef_idx), *offset))
+    }
+}
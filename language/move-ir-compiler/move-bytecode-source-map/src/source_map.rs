@@ -2,6 +2,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::line_table::{LineTable, SourceRange};
 use anyhow::{format_err, Result};
 use move_binary_format::{
     access::ModuleAccess,
@@ -13,7 +14,9 @@ use move_binary_format::{
     },
 };
 use move_command_line_common::files::FileHash;
-use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use move_core_types::{
+    account_address::AccountAddress, errmap::ErrorDescription, identifier::Identifier,
+};
 use move_ir_types::{
     ast::{ConstantName, ModuleIdent, ModuleName, NopLabel},
     location::Loc,
@@ -70,6 +73,18 @@ pub struct FunctionSourceMap {
     pub is_native: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssertionSourceMap {
+    /// The name of the constant declaring the abort code of the assertion.
+    pub constant_name: ConstantName,
+
+    /// The message of the assertion.
+    pub message: String,
+
+    /// The source location of the assertion.
+    pub location: Loc,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SourceMap {
     /// The source location for the definition of the module or script that this source map is for.
@@ -89,6 +104,13 @@ pub struct SourceMap {
 
     // A mapping of constant name to its `ConstantPoolIndex`.
     pub constant_map: BTreeMap<ConstantName, TableIndex>,
+
+    // A mapping of the abort code of each assertion with a message to its source map.
+    assertion_map: BTreeMap<u64, AssertionSourceMap>,
+
+    // Line and column information for the locations above. Empty unless built with
+    // `build_line_table`.
+    line_table: LineTable,
 }
 
 impl StructSourceMap {
@@ -278,6 +300,8 @@ impl SourceMap {
             struct_map: BTreeMap::new(),
             function_map: BTreeMap::new(),
             constant_map: BTreeMap::new(),
+            assertion_map: BTreeMap::new(),
+            line_table: LineTable::default(),
         }
     }
 
@@ -363,6 +387,53 @@ impl SourceMap {
             .ok_or_else(|| format_err!("Tried to get code location from undefined function index"))
     }
 
+    /// Given a function definition and a code offset within that function definition, this returns
+    /// the lines and columns in the source code associated with the instruction at that offset.
+    pub fn get_code_range(
+        &self,
+        fdef_idx: FunctionDefinitionIndex,
+        offset: CodeOffset,
+    ) -> Result<SourceRange> {
+        let location = self.get_code_location(fdef_idx, offset)?;
+        self.line_table
+            .range(location)
+            .ok_or_else(|| format_err!("Tried to get code range without a line table for its file"))
+    }
+
+    pub fn line_table(&self) -> &LineTable {
+        &self.line_table
+    }
+
+    /// Builds the line table from the files referenced by the source map. `file` returns the path
+    /// and contents of the file with the given hash, if it is available.
+    pub fn build_line_table<'a>(
+        &mut self,
+        mut file: impl FnMut(FileHash) -> Option<(&'a str, &'a str)>,
+    ) {
+        let mut line_table = LineTable::default();
+        let locations = std::iter::once(self.definition_location)
+            .chain(self.struct_map.values().map(|s| s.definition_location))
+            .chain(self.assertion_map.values().map(|a| a.location))
+            .chain(self.function_map.values().flat_map(|f| {
+                std::iter::once(f.definition_location).chain(f.code_map.values().copied())
+            }));
+        for location in locations {
+            let file_hash = location.file_hash();
+            if line_table.contains_file(file_hash) {
+                continue;
+            }
+            if let Some((path, contents)) = file(file_hash) {
+                line_table.add_file(file_hash, path, contents);
+            }
+        }
+        for (fdef_idx, function_map) in &self.function_map {
+            for (offset, location) in &function_map.code_map {
+                line_table.add_code_offset(FunctionDefinitionIndex(*fdef_idx), *offset, *location);
+            }
+        }
+        self.line_table = line_table;
+    }
+
     pub fn add_local_mapping(
         &mut self,
         fdef_idx: FunctionDefinitionIndex,
@@ -423,6 +494,68 @@ impl SourceMap {
             })
     }
 
+    pub fn add_assertion_mapping(
+        &mut self,
+        abort_code: u64,
+        constant_name: ConstantName,
+        message: String,
+        location: Loc,
+    ) -> Result<()> {
+        let assertion = AssertionSourceMap {
+            constant_name,
+            message,
+            location,
+        };
+        self.assertion_map
+            .insert(abort_code, assertion)
+            .map_or(Ok(()), |_| {
+                Err(format_err!(
+                    "Multiple assertions with same abort code encountered when constructing \
+                     source map"
+                ))
+            })
+    }
+
+    pub fn get_assertion_source_map(&self, abort_code: u64) -> Option<&AssertionSourceMap> {
+        self.assertion_map.get(&abort_code)
+    }
+
+    /// Returns the description of the error raised by the assertion with the abort code
+    /// `abort_code`, with its location in the line table, if it is an assertion with a message.
+    pub fn get_error_description(&self, abort_code: u64) -> Option<ErrorDescription> {
+        let assertion = self.assertion_map.get(&abort_code)?;
+        Some(self.error_description(assertion))
+    }
+
+    /// Returns the descriptions of the errors raised by the assertions with messages, by abort
+    /// code.
+    pub fn error_descriptions(&self) -> impl Iterator<Item = (u64, ErrorDescription)> + '_ {
+        self.assertion_map
+            .iter()
+            .map(|(abort_code, assertion)| (*abort_code, self.error_description(assertion)))
+    }
+
+    fn error_description(&self, assertion: &AssertionSourceMap) -> ErrorDescription {
+        let file_hash = assertion.location.file_hash();
+        let location = self
+            .line_table
+            .file_path(file_hash)
+            .zip(self.line_table.range(assertion.location))
+            .map(|(path, range)| {
+                format!(
+                    "{}:{}:{}",
+                    path,
+                    range.start.line + 1,
+                    range.start.column + 1
+                )
+            });
+        ErrorDescription {
+            code_name: assertion.constant_name.to_string(),
+            code_description: assertion.message.clone(),
+            location,
+        }
+    }
+
     pub fn add_struct_field_mapping(
         &mut self,
         struct_def_idx: StructDefinitionIndex,
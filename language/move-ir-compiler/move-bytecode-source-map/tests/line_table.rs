@@ -0,0 +1,77 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_bytecode_source_map::{line_table::LinePosition, source_map::SourceMap};
+use move_command_line_common::files::FileHash;
+use move_ir_types::location::Loc;
+
+const SOURCE: &str = "module 0x1::M {\n    fun f() {\n        g();\n        g()\n    }\n}\n";
+
+fn loc(text: &str) -> Loc {
+    let start = SOURCE.find(text).unwrap() as u32;
+    Loc::new(FileHash::new(SOURCE), start, start + text.len() as u32)
+}
+
+fn source_map() -> SourceMap {
+    let fdef_idx = FunctionDefinitionIndex(0);
+    let mut source_map = SourceMap::new(loc(SOURCE), None);
+    source_map
+        .add_top_level_function_mapping(
+            fdef_idx,
+            loc("fun f() {\n        g();\n        g()\n    }"),
+            false,
+        )
+        .unwrap();
+    source_map
+        .add_code_mapping(fdef_idx, 0, loc("g();"))
+        .unwrap();
+    source_map
+        .add_code_mapping(fdef_idx, 1, loc("g()\n    }"))
+        .unwrap();
+    source_map
+}
+
+#[test]
+fn test_code_positions() {
+    let mut source_map = source_map();
+    let fdef_idx = FunctionDefinitionIndex(0);
+    assert!(source_map.get_code_range(fdef_idx, 0).is_err());
+
+    source_map.build_line_table(|_| Some(("sources/M.move", SOURCE)));
+    let line_table = source_map.line_table();
+    assert_eq!(
+        line_table.file_path(FileHash::new(SOURCE)),
+        Some("sources/M.move")
+    );
+
+    let range = source_map.get_code_range(fdef_idx, 0).unwrap();
+    assert_eq!(range.start, LinePosition { line: 2, column: 8 });
+    assert_eq!(
+        range.end,
+        LinePosition {
+            line: 2,
+            column: 12
+        }
+    );
+    let range = source_map.get_code_range(fdef_idx, 2).unwrap();
+    assert_eq!(range.start, LinePosition { line: 3, column: 8 });
+    assert_eq!(range.end, LinePosition { line: 4, column: 5 });
+}
+
+#[test]
+fn test_line_offsets() {
+    let mut source_map = source_map();
+    source_map.build_line_table(|_| Some(("sources/M.move", SOURCE)));
+
+    // The table survives serialization with the rest of the source map
+    let bytes = bcs::to_bytes(&source_map).unwrap();
+    let source_map: SourceMap = bcs::from_bytes(&bytes).unwrap();
+    let line_table = source_map.line_table();
+
+    let file_hash = FileHash::new(SOURCE);
+    let offsets = |line| line_table.code_offsets(file_hash, line).collect::<Vec<_>>();
+    assert_eq!(offsets(1), vec![]);
+    assert_eq!(offsets(2), vec![(FunctionDefinitionIndex(0), 0)]);
+    assert_eq!(offsets(3), vec![(FunctionDefinitionIndex(0), 1)]);
+}
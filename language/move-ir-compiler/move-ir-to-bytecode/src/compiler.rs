@@ -9,7 +9,9 @@ use move_binary_format::{
         Ability, AbilitySet, Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript,
         Constant, FieldDefinition, FunctionDefinition, FunctionSignature, ModuleHandle, Signature,
         SignatureToken, StructDefinition, StructDefinitionIndex, StructFieldInformation,
-        StructHandleIndex, StructTypeParameter, TableIndex, TypeParameterIndex, TypeSignature,
+        StructHandleIndex, StructTypeParameter, StructVariantHandleIndex,
+        StructVariantInstantiationIndex, TableIndex, TypeParameterIndex, TypeSignature,
+        VariantDefinition, VariantFieldHandleIndex, VariantFieldInstantiationIndex, VariantIndex,
         Visibility,
     },
     file_format_common::VERSION_MAX,
@@ -463,6 +465,10 @@ pub fn compile_module<'a>(
             function_instantiations,
             struct_def_instantiations,
             field_instantiations,
+            struct_variant_handles,
+            struct_variant_instantiations,
+            variant_field_handles,
+            variant_field_instantiations,
         },
         _compiled_deps,
         source_map,
@@ -478,6 +484,10 @@ pub fn compile_module<'a>(
         struct_def_instantiations,
         function_instantiations,
         field_instantiations,
+        struct_variant_handles,
+        struct_variant_instantiations,
+        variant_field_handles,
+        variant_field_instantiations,
         signatures,
         identifiers,
         address_identifiers,
@@ -542,6 +552,10 @@ fn compile_explicit_dependency_declarations(
                 function_instantiations,
                 struct_def_instantiations,
                 field_instantiations,
+                struct_variant_handles,
+                struct_variant_instantiations,
+                variant_field_handles,
+                variant_field_instantiations,
             },
             compiled_deps,
             _source_map,
@@ -557,6 +571,10 @@ fn compile_explicit_dependency_declarations(
             struct_def_instantiations,
             function_instantiations,
             field_instantiations,
+            struct_variant_handles,
+            struct_variant_instantiations,
+            variant_field_handles,
+            variant_field_instantiations,
             signatures,
             identifiers,
             address_identifiers,
@@ -730,8 +748,10 @@ fn compile_structs(
         record_src_loc!(struct_decl: context, s.loc);
         record_src_loc!(struct_type_formals: context, &s.value.type_formals);
         let m = type_parameter_indexes(s.value.type_formals.iter().map(|formal| &formal.1))?;
+        let sname = s.value.name.clone();
         let sd_idx = context.declare_struct_definition_index(s.value.name)?;
-        let field_information = compile_fields(context, &m, sh_idx, sd_idx, s.value.fields)?;
+        let field_information =
+            compile_fields(context, &m, &sname, sh_idx, sd_idx, s.value.fields)?;
         struct_defs.push(StructDefinition {
             struct_handle: sh_idx,
             field_information,
@@ -743,6 +763,7 @@ fn compile_structs(
 fn compile_fields(
     context: &mut Context,
     type_parameters: &HashMap<TypeVar_, TypeParameterIndex>,
+    sname: &StructName,
     sh_idx: StructHandleIndex,
     sd_idx: StructDefinitionIndex,
     sfields: StructDefinitionFields,
@@ -763,6 +784,32 @@ fn compile_fields(
             }
             StructFieldInformation::Declared(decl_fields)
         }
+        StructDefinitionFields::Variants { variants } => {
+            let mut decl_variants = vec![];
+            for (variant_idx, (v, fields)) in variants.into_iter().enumerate() {
+                let name = context.identifier_index(v.0)?;
+                context.declare_variant(
+                    sname.clone(),
+                    v,
+                    variant_idx as VariantIndex,
+                    fields.iter().map(|(f, _)| f.value.clone()),
+                );
+                let mut decl_fields = vec![];
+                for (f, ty) in fields {
+                    let name = context.identifier_index(f.value.0)?;
+                    let sig_token = compile_type(context, type_parameters, &ty)?;
+                    decl_fields.push(FieldDefinition {
+                        name,
+                        signature: TypeSignature(sig_token),
+                    });
+                }
+                decl_variants.push(VariantDefinition {
+                    name,
+                    fields: decl_fields,
+                });
+            }
+            StructFieldInformation::DeclaredVariants(decl_variants)
+        }
     })
 }
 
@@ -1714,6 +1761,24 @@ fn compile_bytecode(
                 Bytecode::UnpackGeneric(si_idx)
             }
         }
+        IRBytecode_::PackVariant(n, v, tys) => {
+            match compile_variant_op(context, function_frame, n, v, tys)? {
+                (sv_idx, None) => Bytecode::PackVariant(sv_idx),
+                (_, Some(si_idx)) => Bytecode::PackVariantGeneric(si_idx),
+            }
+        }
+        IRBytecode_::UnpackVariant(n, v, tys) => {
+            match compile_variant_op(context, function_frame, n, v, tys)? {
+                (sv_idx, None) => Bytecode::UnpackVariant(sv_idx),
+                (_, Some(si_idx)) => Bytecode::UnpackVariantGeneric(si_idx),
+            }
+        }
+        IRBytecode_::TestVariant(n, v, tys) => {
+            match compile_variant_op(context, function_frame, n, v, tys)? {
+                (sv_idx, None) => Bytecode::TestVariant(sv_idx),
+                (_, Some(si_idx)) => Bytecode::TestVariantGeneric(si_idx),
+            }
+        }
         IRBytecode_::ReadRef => Bytecode::ReadRef,
         IRBytecode_::WriteRef => Bytecode::WriteRef,
         IRBytecode_::FreezeRef => Bytecode::FreezeRef,
@@ -1767,6 +1832,18 @@ fn compile_bytecode(
                 Bytecode::ImmBorrowFieldGeneric(fi_idx)
             }
         }
+        IRBytecode_::MutBorrowVariantField(n, v, tys, sp!(_, field_)) => {
+            match compile_variant_field_op(context, function_frame, n, v, tys, field_)? {
+                (fh_idx, None) => Bytecode::MutBorrowVariantField(fh_idx),
+                (_, Some(fi_idx)) => Bytecode::MutBorrowVariantFieldGeneric(fi_idx),
+            }
+        }
+        IRBytecode_::ImmBorrowVariantField(n, v, tys, sp!(_, field_)) => {
+            match compile_variant_field_op(context, function_frame, n, v, tys, field_)? {
+                (fh_idx, None) => Bytecode::ImmBorrowVariantField(fh_idx),
+                (_, Some(fi_idx)) => Bytecode::ImmBorrowVariantFieldGeneric(fi_idx),
+            }
+        }
         IRBytecode_::MutBorrowGlobal(n, tys) => {
             let tokens = Signature(compile_types(
                 context,
@@ -1907,6 +1984,64 @@ fn compile_bytecode(
     Ok(())
 }
 
+// Returns the variant handle for a variant instruction, and its instantiation if the
+// instruction is generic.
+fn compile_variant_op(
+    context: &mut Context,
+    function_frame: &FunctionFrame,
+    n: StructName,
+    v: VariantName,
+    tys: Vec<Type>,
+) -> Result<(
+    StructVariantHandleIndex,
+    Option<StructVariantInstantiationIndex>,
+)> {
+    let def_idx = context.struct_definition_index(&n)?;
+    let variant = context.variant(&n, &v)?;
+    let sv_idx = context.struct_variant_handle_index(def_idx, variant)?;
+    if tys.is_empty() {
+        return Ok((sv_idx, None));
+    }
+    let tokens = Signature(compile_types(
+        context,
+        function_frame.type_parameters(),
+        &tys,
+    )?);
+    let type_actuals_id = context.signature_index(tokens)?;
+    let si_idx = context.struct_variant_instantiation_index(sv_idx, type_actuals_id)?;
+    Ok((sv_idx, Some(si_idx)))
+}
+
+// Returns the variant field handle for a variant field borrow, and its instantiation if the
+// instruction is generic.
+fn compile_variant_field_op(
+    context: &mut Context,
+    function_frame: &FunctionFrame,
+    n: StructName,
+    v: VariantName,
+    tys: Vec<Type>,
+    field_: Field_,
+) -> Result<(
+    VariantFieldHandleIndex,
+    Option<VariantFieldInstantiationIndex>,
+)> {
+    let def_idx = context.struct_definition_index(&n)?;
+    let variant = context.variant(&n, &v)?;
+    let field_offset = context.variant_field(&n, &v, field_)?;
+    let fh_idx = context.variant_field_handle_index(def_idx, variant, field_offset as u16)?;
+    if tys.is_empty() {
+        return Ok((fh_idx, None));
+    }
+    let tokens = Signature(compile_types(
+        context,
+        function_frame.type_parameters(),
+        &tys,
+    )?);
+    let type_actuals_id = context.signature_index(tokens)?;
+    let fi_idx = context.variant_field_instantiation_index(fh_idx, type_actuals_id)?;
+    Ok((fh_idx, Some(fi_idx)))
+}
+
 fn remap_branch_offsets(code: &mut Vec<Bytecode>, fake_to_actual: &HashMap<u16, u16>) {
     for instr in code {
         match instr {
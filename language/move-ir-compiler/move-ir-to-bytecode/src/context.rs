@@ -11,7 +11,10 @@ use move_binary_format::{
         FunctionHandle, FunctionHandleIndex, FunctionInstantiation, FunctionInstantiationIndex,
         FunctionSignature, IdentifierIndex, ModuleHandle, ModuleHandleIndex, Signature,
         SignatureIndex, SignatureToken, StructDefInstantiation, StructDefInstantiationIndex,
-        StructDefinitionIndex, StructHandle, StructHandleIndex, StructTypeParameter, TableIndex,
+        StructDefinitionIndex, StructHandle, StructHandleIndex, StructTypeParameter,
+        StructVariantHandle, StructVariantHandleIndex, StructVariantInstantiation,
+        StructVariantInstantiationIndex, TableIndex, VariantFieldHandle, VariantFieldHandleIndex,
+        VariantFieldInstantiation, VariantFieldInstantiationIndex, VariantIndex,
     },
     CompiledModule,
 };
@@ -23,7 +26,7 @@ use move_core_types::{
 use move_ir_types::{
     ast::{
         BlockLabel_, ConstantName, Field_, FunctionName, ModuleIdent, ModuleName,
-        QualifiedStructIdent, StructName,
+        QualifiedStructIdent, StructName, VariantName,
     },
     location::Loc,
 };
@@ -222,6 +225,14 @@ pub struct MaterializedPools {
     pub function_instantiations: Vec<FunctionInstantiation>,
     /// Field instantiation pool
     pub field_instantiations: Vec<FieldInstantiation>,
+    /// Struct variant handle pool
+    pub struct_variant_handles: Vec<StructVariantHandle>,
+    /// Struct variant instantiation pool
+    pub struct_variant_instantiations: Vec<StructVariantInstantiation>,
+    /// Variant field handle pool
+    pub variant_field_handles: Vec<VariantFieldHandle>,
+    /// Variant field instantiation pool
+    pub variant_field_instantiations: Vec<VariantFieldInstantiation>,
     /// Locals signatures pool
     pub signatures: Vec<Signature>,
     /// Identifier pool
@@ -251,6 +262,8 @@ pub(crate) struct Context<'a> {
     // TODO: lookup for Fields is not that seemless after binary format changes
     // We need multiple lookups or a better representation for fields
     fields: HashMap<(StructHandleIndex, Field_), (StructDefinitionIndex, SignatureToken, usize)>,
+    variants: HashMap<(StructName, VariantName), VariantIndex>,
+    variant_fields: HashMap<(StructName, VariantName, Field_), usize>,
     function_handles: HashMap<(ModuleName, FunctionName), (FunctionHandle, FunctionHandleIndex)>,
     function_signatures: HashMap<(ModuleName, FunctionName), FunctionSignature>,
 
@@ -265,6 +278,10 @@ pub(crate) struct Context<'a> {
     struct_instantiations: HashMap<StructDefInstantiation, TableIndex>,
     function_instantiations: HashMap<FunctionInstantiation, TableIndex>,
     field_instantiations: HashMap<FieldInstantiation, TableIndex>,
+    struct_variant_handles: HashMap<StructVariantHandle, TableIndex>,
+    struct_variant_instantiations: HashMap<StructVariantInstantiation, TableIndex>,
+    variant_field_handles: HashMap<VariantFieldHandle, TableIndex>,
+    variant_field_instantiations: HashMap<VariantFieldInstantiation, TableIndex>,
 
     // The current function index that we are on
     current_function_index: FunctionDefinitionIndex,
@@ -291,6 +308,8 @@ impl<'a> Context<'a> {
             named_constants: HashMap::new(),
             labels: HashMap::new(),
             fields: HashMap::new(),
+            variants: HashMap::new(),
+            variant_fields: HashMap::new(),
             function_handles: HashMap::new(),
             function_signatures: HashMap::new(),
             module_handles: HashMap::new(),
@@ -299,6 +318,10 @@ impl<'a> Context<'a> {
             struct_instantiations: HashMap::new(),
             function_instantiations: HashMap::new(),
             field_instantiations: HashMap::new(),
+            struct_variant_handles: HashMap::new(),
+            struct_variant_instantiations: HashMap::new(),
+            variant_field_handles: HashMap::new(),
+            variant_field_instantiations: HashMap::new(),
             signatures: HashMap::new(),
             identifiers: HashMap::new(),
             address_identifiers: HashMap::new(),
@@ -371,6 +394,12 @@ impl<'a> Context<'a> {
             function_instantiations: Self::materialize_map(self.function_instantiations),
             struct_def_instantiations: Self::materialize_map(self.struct_instantiations),
             field_instantiations: Self::materialize_map(self.field_instantiations),
+            struct_variant_handles: Self::materialize_map(self.struct_variant_handles),
+            struct_variant_instantiations: Self::materialize_map(
+                self.struct_variant_instantiations,
+            ),
+            variant_field_handles: Self::materialize_map(self.variant_field_handles),
+            variant_field_instantiations: Self::materialize_map(self.variant_field_instantiations),
         };
         (materialized_pools, self.dependencies, self.source_map)
     }
@@ -484,6 +513,72 @@ impl<'a> Context<'a> {
         )?))
     }
 
+    /// Get the struct variant handle index, adds it if missing.
+    pub fn struct_variant_handle_index(
+        &mut self,
+        struct_index: StructDefinitionIndex,
+        variant: VariantIndex,
+    ) -> Result<StructVariantHandleIndex> {
+        let handle = StructVariantHandle {
+            struct_index,
+            variant,
+        };
+        Ok(StructVariantHandleIndex(get_or_add_item(
+            &mut self.struct_variant_handles,
+            handle,
+        )?))
+    }
+
+    /// Get the struct variant instantiation index, adds it if missing.
+    pub fn struct_variant_instantiation_index(
+        &mut self,
+        handle: StructVariantHandleIndex,
+        type_parameters: SignatureIndex,
+    ) -> Result<StructVariantInstantiationIndex> {
+        let inst = StructVariantInstantiation {
+            handle,
+            type_parameters,
+        };
+        Ok(StructVariantInstantiationIndex(get_or_add_item(
+            &mut self.struct_variant_instantiations,
+            inst,
+        )?))
+    }
+
+    /// Get the variant field handle index, adds it if missing.
+    pub fn variant_field_handle_index(
+        &mut self,
+        owner: StructDefinitionIndex,
+        variant: VariantIndex,
+        field: u16,
+    ) -> Result<VariantFieldHandleIndex> {
+        let handle = VariantFieldHandle {
+            owner,
+            variant,
+            field,
+        };
+        Ok(VariantFieldHandleIndex(get_or_add_item(
+            &mut self.variant_field_handles,
+            handle,
+        )?))
+    }
+
+    /// Get the variant field instantiation index, adds it if missing.
+    pub fn variant_field_instantiation_index(
+        &mut self,
+        handle: VariantFieldHandleIndex,
+        type_parameters: SignatureIndex,
+    ) -> Result<VariantFieldInstantiationIndex> {
+        let inst = VariantFieldInstantiation {
+            handle,
+            type_parameters,
+        };
+        Ok(VariantFieldInstantiationIndex(get_or_add_item(
+            &mut self.variant_field_instantiations,
+            inst,
+        )?))
+    }
+
     /// Get the fake offset for the label. Labels will be fixed to real offsets after compilation
     pub fn label_index(&mut self, label: BlockLabel_) -> Result<CodeOffset> {
         get_or_add_item(&mut self.labels, label)
@@ -533,6 +628,22 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Get the index of a variant of a struct declared in this module, fails if it is not bound.
+    pub fn variant(&self, s: &StructName, v: &VariantName) -> Result<VariantIndex> {
+        match self.variants.get(&(s.clone(), v.clone())) {
+            None => bail!("Unbound variant {}::{}", s, v),
+            Some(idx) => Ok(*idx),
+        }
+    }
+
+    /// Get the offset of a field of a variant, fails if it is not bound.
+    pub fn variant_field(&self, s: &StructName, v: &VariantName, f: Field_) -> Result<usize> {
+        match self.variant_fields.get(&(s.clone(), v.clone(), f.clone())) {
+            None => bail!("Unbound field {}::{}.{}", s, v, f),
+            Some(decl_order) => Ok(*decl_order),
+        }
+    }
+
     /// Get the struct definition index, fails if it is not bound.
     pub fn struct_definition_index(&self, s: &StructName) -> Result<StructDefinitionIndex> {
         match self.struct_defs.get(s) {
@@ -707,6 +818,22 @@ impl<'a> Context<'a> {
             .or_insert((sd_idx, token, decl_order));
     }
 
+    /// Given a struct declared with variants, adds a variant and its fields.
+    pub fn declare_variant(
+        &mut self,
+        s: StructName,
+        v: VariantName,
+        idx: VariantIndex,
+        fields: impl IntoIterator<Item = Field_>,
+    ) {
+        for (decl_order, f) in fields.into_iter().enumerate() {
+            self.variant_fields
+                .entry((s.clone(), v.clone(), f))
+                .or_insert(decl_order);
+        }
+        self.variants.entry((s, v)).or_insert(idx);
+    }
+
     //**********************************************************************************************
     // Dependency Resolution
     //**********************************************************************************************
@@ -251,6 +251,13 @@ pub type Fields<T> = Vec<(Field, T)>;
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct StructName(pub Symbol);
 
+/// Newtype for the name of a variant of a struct declared with variants
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct VariantName(pub Symbol);
+
+/// The variants of a struct declared with variants, in declaration order
+pub type Variants<T> = Vec<(VariantName, Fields<T>)>;
+
 /// A struct type parameter with its constraints and whether it's declared as phantom.
 pub type StructTypeParameter = (bool, TypeVar, BTreeSet<Ability>);
 
@@ -287,6 +294,8 @@ pub struct StructDependency {
 pub enum StructDefinitionFields {
     /// The fields are declared
     Move { fields: Fields<Type> },
+    /// The struct is declared with variants, each with its own fields
+    Variants { variants: Variants<Type> },
     /// The struct is a type provided by the VM
     Native,
 }
@@ -686,6 +695,9 @@ pub enum Bytecode_ {
     Call(ModuleName, FunctionName, Vec<Type>),
     Pack(StructName, Vec<Type>),
     Unpack(StructName, Vec<Type>),
+    PackVariant(StructName, VariantName, Vec<Type>),
+    UnpackVariant(StructName, VariantName, Vec<Type>),
+    TestVariant(StructName, VariantName, Vec<Type>),
     ReadRef,
     WriteRef,
     FreezeRef,
@@ -693,6 +705,8 @@ pub enum Bytecode_ {
     ImmBorrowLoc(Var),
     MutBorrowField(StructName, Vec<Type>, Field),
     ImmBorrowField(StructName, Vec<Type>, Field),
+    MutBorrowVariantField(StructName, VariantName, Vec<Type>, Field),
+    ImmBorrowVariantField(StructName, VariantName, Vec<Type>, Field),
     MutBorrowGlobal(StructName, Vec<Type>),
     ImmBorrowGlobal(StructName, Vec<Type>),
     Add,
@@ -932,6 +946,22 @@ impl StructDefinition_ {
         }
     }
 
+    /// Creates a new StructDefinition declared with variants, each with its own fields
+    pub fn move_declared_variants(
+        abilities: BTreeSet<Ability>,
+        name: Symbol,
+        type_formals: Vec<StructTypeParameter>,
+        variants: Variants<Type>,
+    ) -> Self {
+        StructDefinition_ {
+            abilities,
+            name: StructName(name),
+            type_formals,
+            fields: StructDefinitionFields::Variants { variants },
+            invariants: vec![],
+        }
+    }
+
     /// Creates a new StructDefinition from the abilities, the string representation of the name,
     /// and the user specified fields, a map from their names to their types
     pub fn native(
@@ -1297,6 +1327,11 @@ impl fmt::Display for StructDefinition_ {
         )?;
         match &self.fields {
             StructDefinitionFields::Move { fields } => writeln!(f, "{}", format_fields(fields))?,
+            StructDefinitionFields::Variants { variants } => {
+                for (name, fields) in variants {
+                    writeln!(f, "{} {{{}}}", name, format_fields(fields))?
+                }
+            }
             StructDefinitionFields::Native => writeln!(f, "{{native}}")?,
         }
         write!(f, ")")
@@ -1339,6 +1374,12 @@ impl fmt::Display for StructName {
     }
 }
 
+impl fmt::Display for VariantName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl fmt::Display for FunctionName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -1740,6 +1781,15 @@ impl fmt::Display for Bytecode_ {
             Bytecode_::Call(m, n, tys) => write!(f, "Call {}.{}{}", m, n, format_type_actuals(tys)),
             Bytecode_::Pack(n, tys) => write!(f, "Pack {}{}", n, format_type_actuals(tys)),
             Bytecode_::Unpack(n, tys) => write!(f, "Unpack {}{}", n, format_type_actuals(tys)),
+            Bytecode_::PackVariant(n, v, tys) => {
+                write!(f, "PackVariant {}{}::{}", n, format_type_actuals(tys), v)
+            }
+            Bytecode_::UnpackVariant(n, v, tys) => {
+                write!(f, "UnpackVariant {}{}::{}", n, format_type_actuals(tys), v)
+            }
+            Bytecode_::TestVariant(n, v, tys) => {
+                write!(f, "TestVariant {}{}::{}", n, format_type_actuals(tys), v)
+            }
             Bytecode_::ReadRef => write!(f, "ReadRef"),
             Bytecode_::WriteRef => write!(f, "WriteRef"),
             Bytecode_::FreezeRef => write!(f, "FreezeRef"),
@@ -1759,6 +1809,22 @@ impl fmt::Display for Bytecode_ {
                 format_type_actuals(tys),
                 field
             ),
+            Bytecode_::MutBorrowVariantField(n, v, tys, field) => write!(
+                f,
+                "MutBorrowVariantField {}{}::{}.{}",
+                n,
+                format_type_actuals(tys),
+                v,
+                field
+            ),
+            Bytecode_::ImmBorrowVariantField(n, v, tys, field) => write!(
+                f,
+                "ImmBorrowVariantField {}{}::{}.{}",
+                n,
+                format_type_actuals(tys),
+                v,
+                field
+            ),
             Bytecode_::MutBorrowGlobal(n, tys) => {
                 write!(f, "MutBorrowGlobal {}{}", n, format_type_actuals(tys))
             }
@@ -1258,6 +1258,12 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                     self.new_error_exp()
                 }
             }
+            // index expressions in specifications are translated as `EA::Exp_::Index`
+            EA::ExpDotted_::Index(..) => {
+                let loc = self.to_loc(&dotted.loc);
+                self.error(&loc, "expression construct not supported in specifications");
+                self.new_error_exp()
+            }
         }
     }
 
@@ -1327,7 +1333,9 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
         // 'type var X where X has field F'. This makes unification significant more complex,
         // so lets see how far we get without this.
         let struct_ty = self.subs.specialize(struct_ty);
-        let field_name = self.symbol_pool().make(&name.value);
+        let field_name = self
+            .symbol_pool()
+            .make(&PA::bytecode_field_name(name.value));
         if let Type::Struct(mid, sid, targs) = &struct_ty {
             // Lookup the StructEntry in the build. It must be defined for valid
             // Type::Struct instances.
@@ -1695,7 +1703,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                 fields_not_covered.extend(field_decls.keys());
                 let mut args = BTreeMap::new();
                 for (name_loc, name_, (_, exp)) in fields.iter() {
-                    let field_name = self.symbol_pool().make(name_);
+                    let field_name = self.symbol_pool().make(&PA::bytecode_field_name(*name_));
                     if let Some((idx, field_ty)) = field_decls.get(&field_name) {
                         let exp = self.translate_exp(exp, &field_ty.instantiate(&instantiation));
                         fields_not_covered.remove(&field_name);
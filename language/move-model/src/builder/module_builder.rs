@@ -374,13 +374,20 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         let move_value =
             Constant::deserialize_constant(&compiled_module.constant_pool()[*const_idx as usize])
                 .unwrap();
+        let attributes = self.translate_attributes(&def.attributes);
         let mut et = ExpTranslator::new(self);
         let loc = et.to_loc(&def.loc);
         let ty = et.translate_type(&def.signature);
         let value = et.translate_from_move_value(&loc, &ty, &move_value);
-        et.parent
-            .parent
-            .define_const(qsym, ConstEntry { loc, ty, value });
+        et.parent.parent.define_const(
+            qsym,
+            ConstEntry {
+                loc,
+                ty,
+                value,
+                attributes,
+            },
+        );
     }
 
     fn decl_ana_struct(&mut self, name: &PA::StructName, def: &EA::StructDefinition) {
@@ -423,7 +430,8 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         let is_entry = def.entry.is_some();
         let visibility = match def.visibility {
             EA::Visibility::Public(_) => FunctionVisibility::Public,
-            EA::Visibility::Friend(_) => FunctionVisibility::Friend,
+            // 'public(package)' is compiled to 'public(friend)'
+            EA::Visibility::Friend(_) | EA::Visibility::Package(_) => FunctionVisibility::Friend,
             EA::Visibility::Internal => FunctionVisibility::Private,
         };
         let loc = et.to_loc(&def.loc);
@@ -741,9 +749,14 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             }
         }
 
-        // Analyze in-function spec blocks.
+        // Analyze in-function spec blocks. Inline functions have no bytecode and hence no
+        // function info; their spec blocks are dropped when they are expanded at call sites.
         for (name, fun_def) in module_def.functions.key_cloned_iter() {
-            let fun_spec_info = &function_infos.get(&name).unwrap().spec_info;
+            let fun_spec_info = match function_infos.get(&name) {
+                Some(info) => &info.spec_info,
+                None if fun_def.inline => continue,
+                None => panic!("function info for `{}` not found", name),
+            };
             let qsym = self.qualified_by_module_from_name(&name.0);
             for (spec_id, spec_block) in fun_def.specs.iter() {
                 for member in &spec_block.value.members {
@@ -856,13 +869,17 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             EA::StructFields::Defined(fields) => {
                 let mut field_map = BTreeMap::new();
                 for (_name_loc, field_name_, (idx, ty)) in fields {
-                    let field_sym = et.symbol_pool().make(field_name_);
+                    // Use the bytecode name, which differs for positional fields
+                    let field_sym = et
+                        .symbol_pool()
+                        .make(&PA::bytecode_field_name(*field_name_));
                     let field_ty = et.translate_type(ty);
                     field_map.insert(field_sym, (*idx, field_ty));
                 }
                 Some(field_map)
             }
-            EA::StructFields::Native(_) => None,
+            // Fields of enum variants are not accessible from specifications
+            EA::StructFields::Native(_) | EA::StructFields::Variants(_) => None,
         };
         self.parent
             .struct_table
@@ -2795,6 +2812,10 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                     // TODO: model friend visibility properly
                     unimplemented!("Friend visibility not supported yet")
                 }
+                PA::Visibility::Package(..) => {
+                    // TODO: model package visibility properly
+                    unimplemented!("Package visibility not supported yet")
+                }
             }
         }
         let rex = Regex::new(&format!(
@@ -3213,12 +3234,21 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             .iter()
             .filter(|(name, _)| name.module_name == self.module_name)
             .map(|(name, const_entry)| {
-                let ConstEntry { loc, value, ty } = const_entry.clone();
+                let ConstEntry {
+                    loc,
+                    value,
+                    ty,
+                    attributes,
+                } = const_entry.clone();
                 (
                     NamedConstantId::new(name.symbol),
-                    self.parent
-                        .env
-                        .create_named_constant_data(name.symbol, loc, ty, value),
+                    self.parent.env.create_named_constant_data(
+                        name.symbol,
+                        loc,
+                        ty,
+                        value,
+                        attributes,
+                    ),
                 )
             })
             .collect();
@@ -460,6 +460,10 @@ fn script_into_module(compiled_script: CompiledScript) -> CompiledModule {
         struct_def_instantiations: vec![],
         function_instantiations: script.function_instantiations,
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
 
         signatures: script.signatures,
 
@@ -548,11 +552,13 @@ fn run_spec_checker(env: &mut GlobalEnv, units: Vec<AnnotatedCompiledUnit>, mut
                         dependency_order: usize::MAX,
                         immediate_neighbors,
                         used_addresses,
+                        spec_uses: BTreeSet::new(),
                         is_source_module: true,
                         friends: UniqueMap::new(),
                         structs: UniqueMap::new(),
                         constants,
                         functions,
+                        use_funs: vec![],
                         specs,
                     };
                     let module = script_into_module(script.script);
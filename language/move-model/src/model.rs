@@ -1166,12 +1166,14 @@ impl GlobalEnv {
         loc: Loc,
         typ: Type,
         value: Value,
+        attributes: Vec<Attribute>,
     ) -> NamedConstantData {
         NamedConstantData {
             name,
             loc,
             typ,
             value,
+            attributes,
         }
     }
 
@@ -2008,6 +2010,11 @@ impl<'env> ModuleEnv<'env> {
         &self.data.module
     }
 
+    /// Gets the source map of the underlying bytecode module.
+    pub fn get_source_map(&'env self) -> &'env SourceMap {
+        &self.data.source_map
+    }
+
     /// Gets a `NamedConstantEnv` in this module by name
     pub fn find_named_constant(&'env self, name: Symbol) -> Option<NamedConstantEnv<'env>> {
         let id = NamedConstantId(name);
@@ -2859,7 +2866,8 @@ impl<'env> FieldEnv<'env> {
                     .struct_def_at(*def_idx);
                 let field = match &struct_def.field_information {
                     StructFieldInformation::Declared(fields) => &fields[self.data.offset],
-                    StructFieldInformation::Native => unreachable!(),
+                    StructFieldInformation::Native
+                    | StructFieldInformation::DeclaredVariants(_) => unreachable!(),
                 };
                 self.struct_env
                     .module_env
@@ -2891,6 +2899,9 @@ pub struct NamedConstantData {
 
     /// The value of this constant
     value: Value,
+
+    /// Attributes attached to this constant.
+    attributes: Vec<Attribute>,
 }
 
 #[derive(Debug)]
@@ -2931,6 +2942,11 @@ impl<'env> NamedConstantEnv<'env> {
     pub fn get_value(&self) -> Value {
         self.data.value.clone()
     }
+
+    /// Returns the attributes of this constant.
+    pub fn get_attributes(&self) -> &[Attribute] {
+        &self.data.attributes
+    }
 }
 
 // =================================================================================================
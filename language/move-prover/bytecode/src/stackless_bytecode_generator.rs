@@ -1162,6 +1162,20 @@ impl<'a> StacklessBytecodeGenerator<'a> {
 
             MoveBytecode::Nop => self.code.push(Bytecode::Nop(attr_id)),
 
+            // TODO prover support for enum variant bytecode instructions
+            MoveBytecode::PackVariant(_)
+            | MoveBytecode::PackVariantGeneric(_)
+            | MoveBytecode::UnpackVariant(_)
+            | MoveBytecode::UnpackVariantGeneric(_)
+            | MoveBytecode::TestVariant(_)
+            | MoveBytecode::TestVariantGeneric(_)
+            | MoveBytecode::MutBorrowVariantField(_)
+            | MoveBytecode::MutBorrowVariantFieldGeneric(_)
+            | MoveBytecode::ImmBorrowVariantField(_)
+            | MoveBytecode::ImmBorrowVariantFieldGeneric(_) => {
+                panic!("enum variant instructions are not supported by the prover")
+            }
+
             // TODO full prover support for vector bytecode instructions
             // These should go to non-functional call operations
             MoveBytecode::VecLen(sig) => {
@@ -7,9 +7,12 @@ use log::{debug, info, warn};
 
 use codespan::{ByteIndex, Span};
 use itertools::Itertools;
-use move_compiler::parser::keywords::{BUILTINS, CONTEXTUAL_KEYWORDS, KEYWORDS};
+use move_compiler::{
+    parser::keywords::{BUILTINS, CONTEXTUAL_KEYWORDS, KEYWORDS},
+    shared::known_attributes::DeprecationAttribute,
+};
 use move_model::{
-    ast::{ModuleName, SpecBlockInfo, SpecBlockTarget},
+    ast::{Attribute, AttributeValue, ModuleName, SpecBlockInfo, SpecBlockTarget, Value},
     code_writer::{CodeWriter, CodeWriterLabel},
     emit, emitln,
     model::{
@@ -902,6 +905,7 @@ impl<'env> Docgen<'env> {
         self.increment_section_nest();
         for const_env in self.current_module.as_ref().unwrap().get_named_constants() {
             self.label(&self.label_for_module_item(&const_env.module_env, const_env.get_name()));
+            self.gen_deprecation(const_env.get_attributes());
             self.doc_text(const_env.get_doc());
             self.code_block(&self.named_constant_display(&const_env));
         }
@@ -917,6 +921,7 @@ impl<'env> Docgen<'env> {
             &self.label_for_module_item(&struct_env.module_env, name),
         );
         self.increment_section_nest();
+        self.gen_deprecation(struct_env.get_attributes());
         self.doc_text(struct_env.get_doc());
         self.code_block(&self.struct_header_display(struct_env));
 
@@ -939,6 +944,36 @@ impl<'env> Docgen<'env> {
         self.decrement_section_nest();
     }
 
+    /// Generates a notice for an item marked `#[deprecated]`, including the note of the attribute.
+    fn gen_deprecation(&self, attributes: &[Attribute]) {
+        let pool = self.env.symbol_pool();
+        let args = attributes.iter().find_map(|attr| match attr {
+            Attribute::Apply(_, name, args)
+                if pool.string(*name).as_str() == DeprecationAttribute::DEPRECATED =>
+            {
+                Some(args)
+            }
+            _ => None,
+        });
+        let args = match args {
+            Some(args) => args,
+            None => return,
+        };
+        let note = args.iter().find_map(|arg| match arg {
+            Attribute::Assign(_, name, AttributeValue::Value(_, Value::ByteArray(bytes)))
+                if pool.string(*name).as_str() == DeprecationAttribute::NOTE =>
+            {
+                Some(String::from_utf8_lossy(bytes).into_owned())
+            }
+            _ => None,
+        });
+        match note {
+            Some(note) => emitln!(self.writer, "**Deprecated**: {}", note),
+            None => emitln!(self.writer, "**Deprecated**"),
+        }
+        emitln!(self.writer);
+    }
+
     /// Returns "Struct `N`" or "Resource `N`".
     fn struct_title(&self, struct_env: &StructEnv<'_>) -> String {
         // NOTE(mengxu): although we no longer declare structs with the `resource` keyword, it
@@ -1014,6 +1049,7 @@ impl<'env> Docgen<'env> {
             );
             self.increment_section_nest();
         }
+        self.gen_deprecation(func_env.get_attributes());
         self.doc_text(func_env.get_doc());
         let sig = self.function_header_display(func_env);
         self.code_block(&sig);
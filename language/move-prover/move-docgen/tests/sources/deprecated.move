@@ -0,0 +1,19 @@
+module 0x2::Deprecated {
+    #[deprecated(note = b"Use `NEW_MAX` instead.")]
+    /// The maximum value.
+    const MAX: u64 = 10;
+
+    /// The new maximum value.
+    const NEW_MAX: u64 = 20;
+
+    #[deprecated]
+    /// An old struct.
+    struct Old has drop {}
+
+    #[deprecated(note = b"Use `new_max` instead.")]
+    /// An old function.
+    public fun max(): u64 { MAX }
+
+    /// A new function.
+    public fun new_max(): u64 { NEW_MAX }
+}
@@ -90,6 +90,7 @@ impl<'env> ErrmapGen<'env> {
                 ErrorDescription {
                     code_name: name.to_string(),
                     code_description: named_constant.get_doc().to_string(),
+                    location: None,
                 },
             )?
         }
@@ -103,7 +104,25 @@ impl<'env> ErrmapGen<'env> {
     ) -> Result<()> {
         for named_constant in module.get_named_constants() {
             let name = self.name_string(named_constant.get_name());
-            if name.starts_with(&self.options.error_prefix) {
+            if let Some(message) = self.get_assertion_message(module, &named_constant) {
+                // the constant declares the abort code of an assertion with a message
+                let abort_code = self.get_abort_code(&named_constant)?;
+                let location = self
+                    .env
+                    .get_file_and_location(&named_constant.get_loc())
+                    .map(|(file, location)| {
+                        format!("{}:{}:{}", file, location.line.0 + 1, location.column.0 + 1)
+                    });
+                self.output.add_module_error(
+                    module_id.clone(),
+                    abort_code,
+                    ErrorDescription {
+                        code_name: name.to_string(),
+                        code_description: message,
+                        location,
+                    },
+                )?
+            } else if name.starts_with(&self.options.error_prefix) {
                 let abort_code = self.get_abort_code(&named_constant)?;
                 self.output.add_module_error(
                     module_id.clone(),
@@ -111,6 +130,7 @@ impl<'env> ErrmapGen<'env> {
                     ErrorDescription {
                         code_name: name.to_string(),
                         code_description: named_constant.get_doc().to_string(),
+                        location: None,
                     },
                 )?
             }
@@ -118,6 +138,19 @@ impl<'env> ErrmapGen<'env> {
         Ok(())
     }
 
+    fn get_assertion_message(
+        &self,
+        module: &ModuleEnv<'_>,
+        constant: &NamedConstantEnv<'_>,
+    ) -> Option<String> {
+        let abort_code = self.get_abort_code(constant).ok()?;
+        let assertion = module
+            .get_source_map()
+            .get_assertion_source_map(abort_code)?;
+        let name = self.name_string(constant.get_name());
+        (assertion.constant_name.0.as_str() == name.as_str()).then(|| assertion.message.clone())
+    }
+
     fn get_abort_code(&self, constant: &NamedConstantEnv<'_>) -> Result<u64> {
         match constant.get_value() {
             Value::Number(big_int) => u64::try_from(big_int).map_err(|err| err.into()),
@@ -27,6 +27,7 @@ module std::event {
     }
 
     /// Deprecated. Only kept around so Diem clients know how to deserialize existing EventHandleGenerator's
+    #[allow(unused_struct)]
     struct EventHandleGenerator has key {
         // A monotonically increasing counter
         counter: u64,
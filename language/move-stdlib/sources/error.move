@@ -49,6 +49,7 @@ module std::error {
   const RESOURCE_EXHAUSTED: u64 = 0x9;
 
   /// Request cancelled by the client (http: 499)
+  #[allow(unused_constant)]
   const CANCELLED: u64 = 0xA;
 
   /// Internal error (http: 500)
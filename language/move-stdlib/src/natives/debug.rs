@@ -146,6 +146,7 @@ mod testing {
     use move_binary_format::errors::{PartialVMError, PartialVMResult};
     use move_core_types::{
         account_address::AccountAddress,
+        identifier::Identifier,
         language_storage::TypeTag,
         value::{MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
         vm_status::StatusCode,
@@ -507,61 +508,29 @@ mod testing {
                         write!(out, "\"{}\"", str).map_err(fmt_error_to_partial_vm_error)?
                     } else {
                         write!(out, "{} ", type_tag).map_err(fmt_error_to_partial_vm_error)?;
-                        write!(out, "{}", STRUCT_BEGIN).map_err(fmt_error_to_partial_vm_error)?;
-
-                        // For each field, print its name and value (and type)
-                        let mut iter = fields.into_iter();
-                        if let Some((field_name, field_value)) = iter.next() {
-                            // Start an indented new line
-                            if !single_line {
-                                writeln!(out).map_err(fmt_error_to_partial_vm_error)?;
-                                print_padding_at_depth(out, depth + 1)?;
-                            }
-
-                            write!(out, "{}: ", field_name.into_string())
-                                .map_err(fmt_error_to_partial_vm_error)?;
-                            print_move_value(
-                                out,
-                                field_value,
-                                move_std_addr,
-                                depth + 1,
-                                canonicalize,
-                                single_line,
-                                include_int_types,
-                            )?;
-
-                            for (field_name, field_value) in iter {
-                                write!(out, "{}", VECTOR_OR_STRUCT_SEP)
-                                    .map_err(fmt_error_to_partial_vm_error)?;
-
-                                if !single_line {
-                                    writeln!(out).map_err(fmt_error_to_partial_vm_error)?;
-                                    print_padding_at_depth(out, depth + 1)?;
-                                } else {
-                                    write!(out, " ").map_err(fmt_error_to_partial_vm_error)?;
-                                }
-                                write!(out, "{}: ", field_name.into_string())
-                                    .map_err(fmt_error_to_partial_vm_error)?;
-                                print_move_value(
-                                    out,
-                                    field_value,
-                                    move_std_addr,
-                                    depth + 1,
-                                    canonicalize,
-                                    single_line,
-                                    include_int_types,
-                                )?;
-                            }
-                        }
-
-                        // Ends printing the struct with "}", which could be on its own line
-                        if !single_line {
-                            writeln!(out).map_err(fmt_error_to_partial_vm_error)?;
-                            print_padding_at_depth(out, depth)?;
-                        }
-                        write!(out, "{}", STRUCT_END).map_err(fmt_error_to_partial_vm_error)?;
+                        print_struct_fields(
+                            out,
+                            fields,
+                            move_std_addr,
+                            depth,
+                            canonicalize,
+                            single_line,
+                            include_int_types,
+                        )?;
                     }
                 }
+                MoveStruct::WithVariantFields(variant, _, fields) => {
+                    write!(out, "{} ", variant).map_err(fmt_error_to_partial_vm_error)?;
+                    print_struct_fields(
+                        out,
+                        fields,
+                        move_std_addr,
+                        depth,
+                        canonicalize,
+                        single_line,
+                        include_int_types,
+                    )?;
+                }
                 _ => {
                     return Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR)
                         .with_message("Expected MoveStruct::WithTypes".to_string()))
@@ -572,6 +541,71 @@ mod testing {
         Ok(())
     }
 
+    /// Prints the fields of a struct (or of an enum variant) between braces.
+    fn print_struct_fields(
+        out: &mut String,
+        fields: Vec<(Identifier, MoveValue)>,
+        move_std_addr: &AccountAddress,
+        depth: usize,
+        canonicalize: bool,
+        single_line: bool,
+        include_int_types: bool,
+    ) -> PartialVMResult<()> {
+        write!(out, "{}", STRUCT_BEGIN).map_err(fmt_error_to_partial_vm_error)?;
+
+        // For each field, print its name and value (and type)
+        let mut iter = fields.into_iter();
+        if let Some((field_name, field_value)) = iter.next() {
+            // Start an indented new line
+            if !single_line {
+                writeln!(out).map_err(fmt_error_to_partial_vm_error)?;
+                print_padding_at_depth(out, depth + 1)?;
+            }
+
+            write!(out, "{}: ", field_name.into_string()).map_err(fmt_error_to_partial_vm_error)?;
+            print_move_value(
+                out,
+                field_value,
+                move_std_addr,
+                depth + 1,
+                canonicalize,
+                single_line,
+                include_int_types,
+            )?;
+
+            for (field_name, field_value) in iter {
+                write!(out, "{}", VECTOR_OR_STRUCT_SEP).map_err(fmt_error_to_partial_vm_error)?;
+
+                if !single_line {
+                    writeln!(out).map_err(fmt_error_to_partial_vm_error)?;
+                    print_padding_at_depth(out, depth + 1)?;
+                } else {
+                    write!(out, " ").map_err(fmt_error_to_partial_vm_error)?;
+                }
+                write!(out, "{}: ", field_name.into_string())
+                    .map_err(fmt_error_to_partial_vm_error)?;
+                print_move_value(
+                    out,
+                    field_value,
+                    move_std_addr,
+                    depth + 1,
+                    canonicalize,
+                    single_line,
+                    include_int_types,
+                )?;
+            }
+        }
+
+        // Ends printing the struct with "}", which could be on its own line
+        if !single_line {
+            writeln!(out).map_err(fmt_error_to_partial_vm_error)?;
+            print_padding_at_depth(out, depth)?;
+        }
+        write!(out, "{}", STRUCT_END).map_err(fmt_error_to_partial_vm_error)?;
+
+        Ok(())
+    }
+
     fn print_non_u8_vector<ValType>(
         out: &mut String,
         move_std_addr: &AccountAddress,
@@ -95,7 +95,7 @@ pub struct PushBackGasParameters {
 
 pub fn native_push_back(
     gas_params: &PushBackGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -104,6 +104,10 @@ pub fn native_push_back(
 
     let e = args.pop_back().unwrap();
     let r = pop_arg!(args, VectorRef);
+    if context.max_vector_length().is_some() {
+        let len = r.len(&ty_args[0])?.value_as::<u64>()?;
+        context.check_vector_length(len + 1)?;
+    }
 
     let mut cost = gas_params.base;
     if gas_params.legacy_per_abstract_memory_unit != 0.into() {
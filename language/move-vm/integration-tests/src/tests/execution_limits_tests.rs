@@ -0,0 +1,250 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::InternalGas,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag},
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    config::VMConfig,
+    move_vm::MoveVM,
+    native_functions::{NativeContext, NativeFunction},
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::{
+    gas::UnmeteredGasMeter, loaded_data::runtime_types::Type, natives::function::NativeResult,
+    pop_arg, values::Value,
+};
+use std::{collections::VecDeque, sync::Arc};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+const OTHER_ADDR: AccountAddress = AccountAddress::new([43; AccountAddress::LENGTH]);
+
+fn module_id() -> ModuleId {
+    ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap())
+}
+
+fn storage() -> InMemoryStorage {
+    let code = format!(
+        r#"
+        module 0x{}::M {{
+            struct R has key {{ v: u64 }}
+            struct Inner has store {{ v: u64 }}
+            struct Nested has key {{ inner: Inner }}
+            struct W<phantom A, phantom B> {{}}
+
+            native fun emit(v: u64);
+
+            fun recurse(n: u64) {{ if (n > 0) recurse(n - 1) }}
+
+            fun sum(a: u64, b: u64, c: u64): u64 {{ a + (b + c) }}
+
+            fun exists_nested(addr: address): bool {{ exists<Nested>(addr) }}
+
+            fun id<T>(x: T): T {{ x }}
+
+            fun instantiate() {{ id<vector<vector<vector<u64>>>>(vector[]); }}
+
+            // instantiates `grow8` with a type of 255 nodes
+            fun grow() {{ grow1<u8>() }}
+            fun grow1<T>() {{ grow2<W<T, T>>() }}
+            fun grow2<T>() {{ grow3<W<T, T>>() }}
+            fun grow3<T>() {{ grow4<W<T, T>>() }}
+            fun grow4<T>() {{ grow5<W<T, T>>() }}
+            fun grow5<T>() {{ grow6<W<T, T>>() }}
+            fun grow6<T>() {{ grow7<W<T, T>>() }}
+            fun grow7<T>() {{ grow8<W<T, T>>() }}
+            fun grow8<T>() {{}}
+
+            fun pack(v: u64): vector<u64> {{ vector[v, v, v] }}
+
+            fun get_both(a: address, b: address): u64 acquires R {{
+                borrow_global<R>(a).v + borrow_global<R>(b).v
+            }}
+
+            fun emit_twice() {{ emit(1); emit(2) }}
+        }}
+        "#,
+        TEST_ADDR
+    );
+    let mut units = compile_units(&code).unwrap();
+    let module = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module_id(), blob);
+    let r_tag = StructTag {
+        address: TEST_ADDR,
+        module: Identifier::new("M").unwrap(),
+        name: Identifier::new("R").unwrap(),
+        type_params: vec![],
+    };
+    for addr in [TEST_ADDR, OTHER_ADDR] {
+        storage.publish_or_overwrite_resource(
+            addr,
+            r_tag.clone(),
+            MoveValue::U64(1).simple_serialize().unwrap(),
+        );
+    }
+    storage
+}
+
+/// Runs `function` of the module `M` with a VM configured with `config`, and returns the status
+/// code of the error if it fails.
+fn run(config: VMConfig, function: &str, args: Vec<MoveValue>) -> Result<(), StatusCode> {
+    let emit: NativeFunction = Arc::new(
+        |context: &mut NativeContext, _, mut args: VecDeque<Value>| {
+            let v = pop_arg!(args, u64);
+            context.save_event(vec![0], v, Type::U64, Value::u64(v))?;
+            Ok(NativeResult::ok(InternalGas::new(0), vec![].into()))
+        },
+    );
+    let natives = vec![(
+        TEST_ADDR,
+        Identifier::new("M").unwrap(),
+        Identifier::new("emit").unwrap(),
+        emit,
+    )];
+    let storage = storage();
+    let vm = MoveVM::new_with_config(natives, config).unwrap();
+    let mut session = vm.new_session(&storage);
+    session
+        .execute_function_bypass_visibility(
+            &module_id(),
+            IdentStr::new(function).unwrap(),
+            vec![],
+            serialize_values(&args),
+            &mut UnmeteredGasMeter,
+        )
+        .map(|_| ())
+        .map_err(|err| err.major_status())
+}
+
+#[test]
+fn call_stack_size() {
+    let config = || VMConfig {
+        max_call_stack_size: 5,
+        ..Default::default()
+    };
+    assert_eq!(run(config(), "recurse", vec![MoveValue::U64(2)]), Ok(()));
+    assert_eq!(
+        run(config(), "recurse", vec![MoveValue::U64(10)]),
+        Err(StatusCode::CALL_STACK_OVERFLOW)
+    );
+}
+
+#[test]
+fn operand_stack_size() {
+    let args = || vec![MoveValue::U64(1), MoveValue::U64(2), MoveValue::U64(3)];
+    assert_eq!(run(VMConfig::default(), "sum", args()), Ok(()));
+    let config = VMConfig {
+        max_operand_stack_size: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        run(config, "sum", args()),
+        Err(StatusCode::EXECUTION_STACK_OVERFLOW)
+    );
+}
+
+#[test]
+fn value_depth() {
+    let config = |max_value_depth| VMConfig {
+        max_value_depth,
+        ..Default::default()
+    };
+    let args = || vec![MoveValue::Address(TEST_ADDR)];
+    assert_eq!(run(config(3), "exists_nested", args()), Ok(()));
+    assert_eq!(
+        run(config(2), "exists_nested", args()),
+        Err(StatusCode::VM_MAX_VALUE_DEPTH_REACHED)
+    );
+}
+
+#[test]
+fn type_instantiation_nodes() {
+    let config = |max_type_instantiation_nodes| VMConfig {
+        max_type_instantiation_nodes,
+        ..Default::default()
+    };
+    assert_eq!(run(config(None), "instantiate", vec![]), Ok(()));
+    assert_eq!(run(config(Some(5)), "instantiate", vec![]), Ok(()));
+    assert_eq!(
+        run(config(Some(4)), "instantiate", vec![]),
+        Err(StatusCode::VM_MAX_TYPE_NODES_REACHED)
+    );
+
+    // the configured maximum replaces the default one of 128 nodes
+    assert_eq!(
+        run(config(None), "grow", vec![]),
+        Err(StatusCode::TOO_MANY_TYPE_NODES)
+    );
+    assert_eq!(run(config(Some(1000)), "grow", vec![]), Ok(()));
+    assert_eq!(
+        run(config(Some(300)), "grow", vec![]),
+        Err(StatusCode::VM_MAX_TYPE_NODES_REACHED)
+    );
+}
+
+#[test]
+fn type_to_layout_nodes() {
+    let config = |max_type_to_layout_nodes| VMConfig {
+        max_type_to_layout_nodes,
+        ..Default::default()
+    };
+    let args = || vec![MoveValue::Address(TEST_ADDR)];
+    assert_eq!(run(config(None), "exists_nested", args()), Ok(()));
+    assert_eq!(run(config(Some(2)), "exists_nested", args()), Ok(()));
+    assert_eq!(
+        run(config(Some(1)), "exists_nested", args()),
+        Err(StatusCode::VM_MAX_TYPE_LAYOUT_NODES_REACHED)
+    );
+}
+
+#[test]
+fn vector_length() {
+    let config = |max_vector_length| VMConfig {
+        max_vector_length,
+        ..Default::default()
+    };
+    let args = || vec![MoveValue::U64(1)];
+    assert_eq!(run(config(Some(3)), "pack", args()), Ok(()));
+    assert_eq!(
+        run(config(Some(2)), "pack", args()),
+        Err(StatusCode::VM_MAX_VECTOR_LENGTH_REACHED)
+    );
+}
+
+#[test]
+fn loaded_resource_bytes() {
+    let config = |max_loaded_resource_bytes| VMConfig {
+        max_loaded_resource_bytes,
+        ..Default::default()
+    };
+    let args = |b| vec![MoveValue::Address(TEST_ADDR), MoveValue::Address(b)];
+    // resources already loaded in the session are not counted again
+    assert_eq!(run(config(Some(8)), "get_both", args(TEST_ADDR)), Ok(()));
+    assert_eq!(run(config(Some(16)), "get_both", args(OTHER_ADDR)), Ok(()));
+    assert_eq!(
+        run(config(Some(15)), "get_both", args(OTHER_ADDR)),
+        Err(StatusCode::VM_MAX_LOADED_RESOURCE_BYTES_REACHED)
+    );
+}
+
+#[test]
+fn events() {
+    let config = |max_events| VMConfig {
+        max_events,
+        ..Default::default()
+    };
+    assert_eq!(run(config(Some(2)), "emit_twice", vec![]), Ok(()));
+    assert_eq!(
+        run(config(Some(1)), "emit_twice", vec![]),
+        Err(StatusCode::VM_MAX_EVENTS_REACHED)
+    );
+}
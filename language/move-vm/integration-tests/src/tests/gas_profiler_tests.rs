@@ -0,0 +1,296 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_binary_format::errors::{PartialVMError, VMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::InternalGas,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
+    value::MoveValue,
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    gas_profiler::{function_name, GasProfile, GasProfiler},
+    move_vm::MoveVM,
+    native_functions::NativeFunction,
+};
+use move_vm_test_utils::{
+    gas_schedule::{zero_cost_schedule, CostTable, Gas, GasCost, GasStatus},
+    InMemoryStorage,
+};
+use move_vm_types::{
+    gas::{GasMeter, UnmeteredGasMeter},
+    natives::function::NativeResult,
+    values::Value,
+};
+use std::sync::Arc;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+/// The gas charged by the native function `M::cost`
+const NATIVE_COST: u64 = 100;
+
+fn module_id() -> ModuleId {
+    ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap())
+}
+
+fn name(function: &str) -> String {
+    function_name(&module_id(), function)
+}
+
+fn vm_and_storage() -> (MoveVM, InMemoryStorage) {
+    let code = format!(
+        r#"
+        module 0x{}::M {{
+            struct R has key {{ v: u64 }}
+
+            native fun cost(): u64;
+
+            native fun fail();
+
+            fun double(x: u64): u64 {{ x * 2 }}
+
+            fun count(n: u64): u64 {{ if (n == 0) 0 else count(n - 1) + 1 }}
+
+            fun run(): u64 {{ count(2) + double(cost()) }}
+
+            fun read(): u64 acquires R {{ borrow_global<R>(@0x{}).v }}
+
+            fun crash() {{ fail() }}
+        }}
+        "#,
+        TEST_ADDR, TEST_ADDR
+    );
+    let mut units = compile_units(&code).unwrap();
+    let module = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module.self_id(), blob);
+    let tag = StructTag {
+        address: TEST_ADDR,
+        module: Identifier::new("M").unwrap(),
+        name: Identifier::new("R").unwrap(),
+        type_params: vec![],
+    };
+    storage.publish_or_overwrite_resource(
+        TEST_ADDR,
+        tag,
+        MoveValue::U64(5).simple_serialize().unwrap(),
+    );
+
+    let cost: NativeFunction = Arc::new(|_, _, _| {
+        Ok(NativeResult::ok(
+            InternalGas::new(NATIVE_COST),
+            vec![Value::u64(3)].into(),
+        ))
+    });
+    // fails before its implementation is charged
+    let fail: NativeFunction = Arc::new(|_, _, _| {
+        Err(PartialVMError::new(
+            StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+        ))
+    });
+    let natives = vec![
+        (
+            TEST_ADDR,
+            Identifier::new("M").unwrap(),
+            Identifier::new("cost").unwrap(),
+            cost,
+        ),
+        (
+            TEST_ADDR,
+            Identifier::new("M").unwrap(),
+            Identifier::new("fail").unwrap(),
+            fail,
+        ),
+    ];
+    (MoveVM::new(natives).unwrap(), storage)
+}
+
+fn execute<G: GasMeter>(
+    vm: &MoveVM,
+    storage: &InMemoryStorage,
+    function: &str,
+    gas_profiler: &mut GasProfiler<G>,
+) -> VMResult<()> {
+    let mut session = vm.new_session(storage);
+    session
+        .execute_function_bypass_visibility(
+            &module_id(),
+            &Identifier::new(function).unwrap(),
+            vec![],
+            Vec::<Vec<u8>>::new(),
+            gas_profiler,
+        )
+        .map(|_| ())
+}
+
+fn profile<G: GasMeter>(function: &str, gas_meter: G) -> GasProfile {
+    let (vm, storage) = vm_and_storage();
+    let mut gas_profiler = GasProfiler::new_function(gas_meter, &module_id(), function);
+    execute(&vm, &storage, function, &mut gas_profiler).unwrap();
+    gas_profiler.finish().1
+}
+
+fn cost_table() -> CostTable {
+    let mut cost_table = zero_cost_schedule();
+    for cost in cost_table.instruction_table.iter_mut() {
+        *cost = GasCost::new(1, 1);
+    }
+    cost_table
+}
+
+fn metered_profile(function: &str) -> GasProfile {
+    profile(function, GasStatus::new(&cost_table(), Gas::new(1_000_000)))
+}
+
+#[test]
+fn profile_calls() {
+    let profile = metered_profile("run");
+    let run = &profile.functions[&name("run")];
+    let count = &profile.functions[&name("count")];
+    let double = &profile.functions[&name("double")];
+    let cost = &profile.functions[&name("cost")];
+
+    assert_eq!(run.calls, 1);
+    assert_eq!(count.calls, 3);
+    assert_eq!(double.calls, 1);
+    assert_eq!(cost.calls, 1);
+
+    // all the gas is charged within the entry function, to a single function
+    assert_eq!(run.inclusive_gas, profile.total_gas);
+    assert_eq!(
+        profile
+            .functions
+            .values()
+            .map(|function| function.exclusive_gas)
+            .sum::<u64>(),
+        profile.total_gas
+    );
+    assert_eq!(profile.stacks.values().sum::<u64>(), profile.total_gas);
+
+    // `x * 2` moves `x`, loads `2`, multiplies them and returns
+    assert_eq!(double.instructions, 4);
+    assert!(double.exclusive_gas > 0);
+    assert_eq!(double.inclusive_gas, double.exclusive_gas);
+
+    // the gas of the native function is charged by its implementation
+    assert_eq!(cost.native_gas, NATIVE_COST);
+    assert_eq!(cost.instructions, 0);
+    assert!(cost.exclusive_gas >= NATIVE_COST);
+    assert_eq!(run.native_gas, 0);
+
+    // the recursive calls of `count` are counted once in its inclusive gas
+    let count_stack = format!("{};{}", name("run"), name("count"));
+    assert_eq!(
+        count.inclusive_gas,
+        profile
+            .stacks
+            .iter()
+            .filter(|(stack, _)| stack.starts_with(&count_stack))
+            .map(|(_, gas)| gas)
+            .sum::<u64>()
+    );
+    assert!(profile.stacks.contains_key(&format!(
+        "{};{};{}",
+        count_stack,
+        name("count"),
+        name("count")
+    )));
+    assert!(count.inclusive_gas < run.inclusive_gas);
+}
+
+#[test]
+fn profile_storage() {
+    let profile = metered_profile("read");
+    let read = &profile.functions[&name("read")];
+    // a `u64` field
+    assert_eq!(read.loaded_bytes, 8);
+    assert_eq!(read.inclusive_gas, profile.total_gas);
+}
+
+#[test]
+fn profile_unmetered() {
+    let profile = profile("run", UnmeteredGasMeter);
+    assert!(profile.unmetered);
+    assert_eq!(profile.total_gas, 0);
+    assert!(profile.stacks.is_empty());
+    assert_eq!(profile.functions[&name("count")].calls, 3);
+
+    let mut text = vec![];
+    profile.write_text_report(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.starts_with("Total gas: 0 (internal units, not metered)"));
+    assert!(!metered_profile("run").unmetered);
+}
+
+#[test]
+fn profile_failed_native() {
+    let (vm, storage) = vm_and_storage();
+    let cost_table = cost_table();
+    let gas_meter = GasStatus::new(&cost_table, Gas::new(1_000_000));
+    let mut gas_profiler = GasProfiler::new_function(gas_meter, &module_id(), "crash");
+    assert!(execute(&vm, &storage, "crash", &mut gas_profiler).is_err());
+    // the gas of a later execution is not charged to the native function
+    execute(&vm, &storage, "read", &mut gas_profiler).unwrap();
+    let profile = gas_profiler.finish().1;
+
+    let fail = &profile.functions[&name("fail")];
+    assert_eq!(fail.calls, 1);
+    assert_eq!(fail.exclusive_gas, 0);
+    assert!(profile
+        .stacks
+        .keys()
+        .all(|stack| !stack.contains(&name("fail"))));
+    assert_eq!(profile.stacks.values().sum::<u64>(), profile.total_gas);
+    assert_eq!(
+        profile.functions[&name("crash")].exclusive_gas,
+        profile.total_gas
+    );
+}
+
+#[test]
+fn profile_reports() {
+    let mut profile = metered_profile("run");
+    profile.merge(&metered_profile("read"));
+
+    let mut folded = vec![];
+    profile.write_folded_stacks(&mut folded).unwrap();
+    let folded = String::from_utf8(folded).unwrap();
+    for (line, (stack, gas)) in folded.lines().zip(&profile.stacks) {
+        assert_eq!(line, format!("{} {}", stack, gas));
+    }
+    assert_eq!(folded.lines().count(), profile.stacks.len());
+
+    // functions are sorted by exclusive gas
+    let mut json = vec![];
+    profile.write_json_report(&mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["total_gas"], profile.total_gas);
+    let exclusive_gas = json["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|function| function["exclusive_gas"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(exclusive_gas.len(), 5);
+    assert!(exclusive_gas.windows(2).all(|pair| pair[0] >= pair[1]));
+
+    let mut text = vec![];
+    profile.write_text_report(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    let names = text
+        .lines()
+        .skip(3)
+        .map(|line| line.split_whitespace().last().unwrap())
+        .collect::<Vec<_>>();
+    let sorted_names = profile
+        .sorted_functions()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, sorted_names);
+}
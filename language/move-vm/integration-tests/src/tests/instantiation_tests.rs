@@ -572,6 +572,10 @@ fn make_module(
         field_handles: vec![],
         friend_decls: vec![],
         field_instantiations: vec![],
+        struct_variant_handles: vec![],
+        struct_variant_instantiations: vec![],
+        variant_field_handles: vec![],
+        variant_field_instantiations: vec![],
         metadata: vec![],
     };
     // uncomment to see the module generated
@@ -6,7 +6,9 @@ mod bad_entry_point_tests;
 mod bad_storage_tests;
 mod binary_format_version;
 mod exec_func_effects_tests;
+mod execution_limits_tests;
 mod function_arg_tests;
+mod gas_profiler_tests;
 mod instantiation_tests;
 mod invariant_violation_tests;
 mod leak_tests;
@@ -14,3 +16,5 @@ mod loader_tests;
 mod mutated_accounts_tests;
 mod nested_loop_tests;
 mod return_value_tests;
+mod session_savepoint_tests;
+mod tracer_tests;
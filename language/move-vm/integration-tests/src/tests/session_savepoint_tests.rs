@@ -0,0 +1,382 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Op},
+    gas_algebra::InternalGas,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag},
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    config::VMConfig,
+    move_vm::MoveVM,
+    native_functions::{NativeContext, NativeFunction},
+    session::Session,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::{
+    gas::UnmeteredGasMeter, loaded_data::runtime_types::Type, natives::function::NativeResult,
+    pop_arg, values::Value,
+};
+use std::{collections::VecDeque, sync::Arc};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+const OTHER_ADDR: AccountAddress = AccountAddress::new([43; AccountAddress::LENGTH]);
+
+fn module_id() -> ModuleId {
+    ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap())
+}
+
+fn resource_tag() -> StructTag {
+    StructTag {
+        address: TEST_ADDR,
+        module: Identifier::new("M").unwrap(),
+        name: Identifier::new("R").unwrap(),
+        type_params: vec![],
+    }
+}
+
+fn storage() -> InMemoryStorage {
+    let code = format!(
+        r#"
+        module 0x{}::M {{
+            struct R has key {{ v: u64 }}
+
+            native fun emit(v: u64);
+
+            fun publish(account: &signer, v: u64) {{ move_to(account, R {{ v }}); emit(v) }}
+
+            fun set(addr: address, v: u64) acquires R {{ borrow_global_mut<R>(addr).v = v; emit(v) }}
+
+            fun get(addr: address): u64 acquires R {{ borrow_global<R>(addr).v }}
+
+            fun remove(addr: address) acquires R {{ let R {{ v }} = move_from<R>(addr); emit(v) }}
+        }}
+        "#,
+        TEST_ADDR
+    );
+    let mut units = compile_units(&code).unwrap();
+    let module = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module_id(), blob);
+    storage.publish_or_overwrite_resource(
+        TEST_ADDR,
+        resource_tag(),
+        MoveValue::U64(5).simple_serialize().unwrap(),
+    );
+    storage
+}
+
+fn vm() -> MoveVM {
+    vm_with_config(VMConfig::default())
+}
+
+fn vm_with_config(config: VMConfig) -> MoveVM {
+    let emit: NativeFunction = Arc::new(
+        |context: &mut NativeContext, _, mut args: VecDeque<Value>| {
+            let v = pop_arg!(args, u64);
+            context.save_event(vec![0], v, Type::U64, Value::u64(v))?;
+            Ok(NativeResult::ok(InternalGas::new(0), vec![].into()))
+        },
+    );
+    MoveVM::new_with_config(
+        vec![(
+            TEST_ADDR,
+            Identifier::new("M").unwrap(),
+            Identifier::new("emit").unwrap(),
+            emit,
+        )],
+        config,
+    )
+    .unwrap()
+}
+
+fn call(
+    session: &mut Session<InMemoryStorage>,
+    function: &str,
+    args: Vec<MoveValue>,
+) -> Option<u64> {
+    let return_values = session
+        .execute_function_bypass_visibility(
+            &module_id(),
+            IdentStr::new(function).unwrap(),
+            vec![],
+            serialize_values(&args),
+            &mut UnmeteredGasMeter,
+        )
+        .ok()?
+        .return_values;
+    Some(match return_values.first() {
+        Some((bytes, _)) => bcs::from_bytes(bytes).unwrap(),
+        None => 0,
+    })
+}
+
+fn resource_op(change_set: &ChangeSet, addr: AccountAddress) -> Option<Op<u64>> {
+    change_set
+        .accounts()
+        .get(&addr)?
+        .resources()
+        .get(&resource_tag())
+        .map(|op| op.clone().map(|blob| bcs::from_bytes(&blob).unwrap()))
+}
+
+#[test]
+fn rollback_to_savepoint() {
+    let storage = storage();
+    let vm = vm();
+    let mut session = vm.new_session(&storage);
+
+    session.savepoint().unwrap();
+    assert_eq!(session.num_savepoints(), 1);
+    call(
+        &mut session,
+        "set",
+        vec![MoveValue::Address(TEST_ADDR), MoveValue::U64(7)],
+    )
+    .unwrap();
+    call(
+        &mut session,
+        "publish",
+        vec![MoveValue::Signer(OTHER_ADDR), MoveValue::U64(8)],
+    )
+    .unwrap();
+
+    let (change_set, events) = session.effects_since_savepoint().unwrap();
+    assert_eq!(resource_op(&change_set, TEST_ADDR), Some(Op::Modify(7)));
+    assert_eq!(resource_op(&change_set, OTHER_ADDR), Some(Op::New(8)));
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1],
+        (vec![0], 8, TypeTag::U64, bcs::to_bytes(&8u64).unwrap())
+    );
+    assert_eq!(session.num_mutated_accounts(&TEST_ADDR), 2);
+
+    // the values flushed by `effects_since_savepoint` are rolled back as well
+    session.rollback_to_savepoint().unwrap();
+    assert_eq!(session.num_savepoints(), 0);
+    assert_eq!(session.num_mutated_accounts(&TEST_ADDR), 1);
+    assert_eq!(
+        call(&mut session, "get", vec![MoveValue::Address(TEST_ADDR)]),
+        Some(5)
+    );
+    assert_eq!(
+        call(&mut session, "get", vec![MoveValue::Address(OTHER_ADDR)]),
+        None
+    );
+
+    let (change_set, events) = session.finish().unwrap();
+    assert_eq!(change_set, ChangeSet::new());
+    assert!(events.is_empty());
+}
+
+#[test]
+fn nested_savepoints() {
+    let storage = storage();
+    let vm = vm();
+    let mut session = vm.new_session(&storage);
+
+    call(
+        &mut session,
+        "set",
+        vec![MoveValue::Address(TEST_ADDR), MoveValue::U64(6)],
+    )
+    .unwrap();
+    call(
+        &mut session,
+        "publish",
+        vec![MoveValue::Signer(OTHER_ADDR), MoveValue::U64(1)],
+    )
+    .unwrap();
+
+    session.savepoint().unwrap();
+    call(
+        &mut session,
+        "set",
+        vec![MoveValue::Address(TEST_ADDR), MoveValue::U64(7)],
+    )
+    .unwrap();
+    call(&mut session, "remove", vec![MoveValue::Address(OTHER_ADDR)]).unwrap();
+
+    session.savepoint().unwrap();
+    call(&mut session, "remove", vec![MoveValue::Address(TEST_ADDR)]).unwrap();
+    let (change_set, events) = session.effects_since_savepoint().unwrap();
+    assert_eq!(resource_op(&change_set, TEST_ADDR), Some(Op::Delete));
+    assert_eq!(events.len(), 1);
+    session.rollback_to_savepoint().unwrap();
+
+    assert_eq!(
+        call(&mut session, "get", vec![MoveValue::Address(TEST_ADDR)]),
+        Some(7)
+    );
+    let (change_set, events) = session.effects_since_savepoint().unwrap();
+    assert_eq!(resource_op(&change_set, TEST_ADDR), Some(Op::Modify(7)));
+    assert_eq!(resource_op(&change_set, OTHER_ADDR), Some(Op::Delete));
+    assert_eq!(events.len(), 2);
+    session.commit_savepoint().unwrap();
+    assert_eq!(session.num_savepoints(), 0);
+
+    // the resource published and then removed is squashed away
+    let (change_set, events) = session.finish().unwrap();
+    assert_eq!(resource_op(&change_set, TEST_ADDR), Some(Op::Modify(7)));
+    assert_eq!(resource_op(&change_set, OTHER_ADDR), None);
+    let values = events
+        .iter()
+        .map(|(_, seq_num, _, _)| *seq_num)
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![6, 1, 7, 1]);
+}
+
+#[test]
+fn rollback_past_limits() {
+    let storage = storage();
+    let vm = vm_with_config(VMConfig {
+        max_loaded_resource_bytes: Some(8),
+        max_events: Some(1),
+        ..Default::default()
+    });
+    let mut session = vm.new_session(&storage);
+
+    // each call loads the 8 bytes of the resource and emits an event, which only fits in the
+    // limits if the calls rolled back are not counted
+    for v in 6..9 {
+        session.savepoint().unwrap();
+        call(
+            &mut session,
+            "set",
+            vec![MoveValue::Address(TEST_ADDR), MoveValue::U64(v)],
+        )
+        .unwrap();
+        session.rollback_to_savepoint().unwrap();
+    }
+    call(
+        &mut session,
+        "set",
+        vec![MoveValue::Address(TEST_ADDR), MoveValue::U64(9)],
+    )
+    .unwrap();
+
+    let (change_set, events) = session.finish().unwrap();
+    assert_eq!(resource_op(&change_set, TEST_ADDR), Some(Op::Modify(9)));
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn reads_across_savepoints() {
+    let storage = storage();
+    let vm = vm_with_config(VMConfig {
+        max_loaded_resource_bytes: Some(8),
+        ..Default::default()
+    });
+    let mut session = vm.new_session(&storage);
+
+    // the 8 bytes of the resource are only counted once, although the savepoints flush it
+    let get = |session: &mut Session<InMemoryStorage>| {
+        call(session, "get", vec![MoveValue::Address(TEST_ADDR)])
+    };
+    assert_eq!(get(&mut session), Some(5));
+    session.savepoint().unwrap();
+    assert_eq!(get(&mut session), Some(5));
+    session.savepoint().unwrap();
+    assert_eq!(get(&mut session), Some(5));
+    session.commit_savepoint().unwrap();
+    assert_eq!(get(&mut session), Some(5));
+    session.rollback_to_savepoint().unwrap();
+    assert_eq!(get(&mut session), Some(5));
+
+    let (change_set, _) = session.finish().unwrap();
+    assert_eq!(resource_op(&change_set, TEST_ADDR), None);
+}
+
+#[test]
+fn unended_savepoints_are_committed() {
+    let storage = storage();
+    let vm = vm();
+    let mut session = vm.new_session(&storage);
+
+    session.savepoint().unwrap();
+    call(
+        &mut session,
+        "publish",
+        vec![MoveValue::Signer(OTHER_ADDR), MoveValue::U64(1)],
+    )
+    .unwrap();
+    session.savepoint().unwrap();
+    call(
+        &mut session,
+        "set",
+        vec![MoveValue::Address(OTHER_ADDR), MoveValue::U64(2)],
+    )
+    .unwrap();
+    session.savepoint().unwrap();
+
+    let (change_set, events) = session.finish().unwrap();
+    assert_eq!(resource_op(&change_set, OTHER_ADDR), Some(Op::New(2)));
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn rollback_published_modules() {
+    let storage = storage();
+    let vm = vm();
+    let mut session = vm.new_session(&storage);
+
+    let code = format!(
+        "module 0x{}::N {{ public fun f(): u64 {{ 1 }} }}",
+        TEST_ADDR
+    );
+    let mut units = compile_units(&code).unwrap();
+    let module = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("N").unwrap());
+    let module_op = |change_set: &ChangeSet| {
+        change_set
+            .accounts()
+            .get(&TEST_ADDR)
+            .and_then(|account| account.modules().get(module_id.name()).cloned())
+    };
+
+    session.savepoint().unwrap();
+    session
+        .publish_module(blob.clone(), TEST_ADDR, &mut UnmeteredGasMeter)
+        .unwrap();
+    session.savepoint().unwrap();
+    // the module published before the savepoint is republished
+    session
+        .publish_module(blob.clone(), TEST_ADDR, &mut UnmeteredGasMeter)
+        .unwrap();
+    assert_eq!(
+        module_op(&session.effects_since_savepoint().unwrap().0),
+        Some(Op::Modify(blob.clone()))
+    );
+    session.commit_savepoint().unwrap();
+    session.rollback_to_savepoint().unwrap();
+    assert!(!session.get_data_store().exists_module(&module_id).unwrap());
+
+    session
+        .publish_module(blob.clone(), TEST_ADDR, &mut UnmeteredGasMeter)
+        .unwrap();
+    let (change_set, _) = session.finish().unwrap();
+    assert_eq!(module_op(&change_set), Some(Op::New(blob)));
+}
+
+#[test]
+fn no_savepoint() {
+    let storage = storage();
+    let vm = vm();
+    let mut session = vm.new_session(&storage);
+
+    for res in [session.rollback_to_savepoint(), session.commit_savepoint()] {
+        assert_eq!(
+            res.unwrap_err().major_status(),
+            StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
+        );
+    }
+}
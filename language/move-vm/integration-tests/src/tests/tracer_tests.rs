@@ -0,0 +1,218 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    move_vm::MoveVM,
+    tracer::{read_trace, write_trace, TraceEvent, TraceFormat, TraceFunction, TraceRecorder},
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::{cell::RefCell, rc::Rc};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+fn module_id() -> ModuleId {
+    ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap())
+}
+
+fn function(name: &str) -> TraceFunction {
+    TraceFunction {
+        module_id: Some(module_id()),
+        name: Identifier::new(name).unwrap(),
+    }
+}
+
+fn trace(function_name: &str, args: Vec<MoveValue>, recorder: TraceRecorder) -> Vec<TraceEvent> {
+    let code = format!(
+        r#"
+        module 0x{}::M {{
+            struct R has key {{ v: u64 }}
+
+            fun double(x: u64): u64 {{ x * 2 }}
+
+            fun double_plus_one(x: u64): u64 {{ double(x) + 1 }}
+
+            fun publish(s: &signer, v: u64) acquires R {{
+                assert!(!exists<R>(@0x{}), 1);
+                move_to(s, R {{ v }});
+                borrow_global_mut<R>(@0x{}).v = v + 1;
+                double(v);
+                abort 7
+            }}
+        }}
+        "#,
+        TEST_ADDR, TEST_ADDR, TEST_ADDR
+    );
+    let mut units = compile_units(&code).unwrap();
+    let module = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module.self_id(), blob);
+
+    let vm = MoveVM::new(vec![]).unwrap();
+    let mut session = vm.new_session(&storage);
+    let recorder = Rc::new(RefCell::new(recorder));
+    session.set_tracer(recorder.clone());
+    let _ = session.execute_function_bypass_visibility(
+        &module_id(),
+        &Identifier::new(function_name).unwrap(),
+        vec![],
+        serialize_values(&args),
+        &mut UnmeteredGasMeter,
+    );
+    drop(session);
+    Rc::try_unwrap(recorder)
+        .ok()
+        .unwrap()
+        .into_inner()
+        .into_events()
+}
+
+fn without_instructions(events: &[TraceEvent]) -> Vec<TraceEvent> {
+    events
+        .iter()
+        .filter(|event| !matches!(event, TraceEvent::Instruction { .. }))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn trace_calls() {
+    let events = trace(
+        "double_plus_one",
+        vec![MoveValue::U64(3)],
+        TraceRecorder::new(),
+    );
+    assert_eq!(
+        without_instructions(&events),
+        vec![
+            TraceEvent::EnterFunction {
+                function: function("double_plus_one"),
+                ty_args: vec![],
+                args: vec!["3".to_string()],
+            },
+            TraceEvent::EnterFunction {
+                function: function("double"),
+                ty_args: vec![],
+                args: vec!["3".to_string()],
+            },
+            TraceEvent::ExitFunction {
+                return_values: vec!["6".to_string()],
+            },
+            TraceEvent::ExitFunction {
+                return_values: vec!["7".to_string()],
+            },
+        ]
+    );
+    // instructions follow the entry of their function
+    assert_eq!(
+        events[1],
+        TraceEvent::Instruction {
+            pc: 0,
+            snapshot: None
+        }
+    );
+}
+
+#[test]
+fn trace_globals_and_aborts() {
+    let events = trace(
+        "publish",
+        vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(5)],
+        TraceRecorder::new(),
+    );
+    let ty = TypeTag::Struct(Box::new(StructTag {
+        address: TEST_ADDR,
+        module: Identifier::new("M").unwrap(),
+        name: Identifier::new("R").unwrap(),
+        type_params: vec![],
+    }));
+    assert_eq!(
+        without_instructions(&events),
+        vec![
+            TraceEvent::EnterFunction {
+                function: function("publish"),
+                ty_args: vec![],
+                args: vec![format!("(&) {{ {} }}", TEST_ADDR), "5".to_string()],
+            },
+            TraceEvent::ReadGlobal {
+                address: TEST_ADDR,
+                ty: ty.clone(),
+                exists: false,
+            },
+            TraceEvent::WriteGlobal {
+                address: TEST_ADDR,
+                ty: ty.clone(),
+                value: Some("{ 5 }".to_string()),
+            },
+            TraceEvent::MutBorrowGlobal {
+                address: TEST_ADDR,
+                ty,
+                exists: true,
+            },
+            TraceEvent::EnterFunction {
+                function: function("double"),
+                ty_args: vec![],
+                args: vec!["5".to_string()],
+            },
+            TraceEvent::ExitFunction {
+                return_values: vec!["10".to_string()],
+            },
+            TraceEvent::Abort {
+                status_code: StatusCode::ABORTED,
+                sub_status: Some(7),
+            },
+        ]
+    );
+}
+
+#[test]
+fn trace_snapshots() {
+    let events = trace(
+        "double",
+        vec![MoveValue::U64(4)],
+        TraceRecorder::with_snapshots(),
+    );
+    let snapshots = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Instruction { snapshot, .. } => snapshot.clone(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    // `x * 2` moves `x` and loads `2` before multiplying them
+    let stacks = snapshots
+        .iter()
+        .map(|snapshot| snapshot.stack.join(", "))
+        .collect::<Vec<_>>();
+    assert_eq!(stacks, vec!["", "4", "4, 2", "8"]);
+    assert_eq!(snapshots[0].locals, vec!["4".to_string()]);
+    assert_eq!(snapshots[1].locals, vec!["-".to_string()]);
+}
+
+#[test]
+fn trace_formats_round_trip() {
+    let events = trace(
+        "publish",
+        vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(5)],
+        TraceRecorder::with_snapshots(),
+    );
+    for format in [TraceFormat::Binary, TraceFormat::Json] {
+        // traces appended to each other are read back as one
+        let mut bytes = vec![];
+        write_trace(&mut bytes, &events, format).unwrap();
+        write_trace(&mut bytes, &events, format).unwrap();
+        let mut expected = events.clone();
+        expected.extend(events.iter().cloned());
+        assert_eq!(read_trace(&bytes).unwrap(), expected);
+    }
+}
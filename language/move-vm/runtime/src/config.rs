@@ -11,6 +11,32 @@ pub struct VMConfig {
     // When this flag is set to true, MoveVM will perform type check at every instruction
     // execution to ensure that type safety cannot be violated at runtime.
     pub paranoid_type_checks: bool,
+    /// Maximal number of values on the operand stack, above which execution fails with
+    /// `EXECUTION_STACK_OVERFLOW`.
+    pub max_operand_stack_size: usize,
+    /// Maximal number of frames on the call stack, above which execution fails with
+    /// `CALL_STACK_OVERFLOW`.
+    pub max_call_stack_size: usize,
+    /// Maximal depth of a value in terms of type depth, above which converting a type to a layout
+    /// fails with `VM_MAX_VALUE_DEPTH_REACHED`.
+    pub max_value_depth: usize,
+    /// Maximal number of nodes of a type instantiated at runtime, above which the instantiation
+    /// fails with `VM_MAX_TYPE_NODES_REACHED`. This does not include the field types of structs.
+    /// If it is not set, instantiations of more than 128 nodes fail with `TOO_MANY_TYPE_NODES`.
+    pub max_type_instantiation_nodes: Option<usize>,
+    /// Maximal number of nodes of a type converted to a layout, including the field types of
+    /// structs, above which the conversion fails with `VM_MAX_TYPE_LAYOUT_NODES_REACHED`. If it
+    /// is not set, conversions of more than 256 nodes fail with `TOO_MANY_TYPE_NODES`.
+    pub max_type_to_layout_nodes: Option<usize>,
+    /// Maximal length of a vector created or grown by the vector instructions, above which
+    /// execution fails with `VM_MAX_VECTOR_LENGTH_REACHED`.
+    pub max_vector_length: Option<u64>,
+    /// Maximal number of bytes of resources loaded from storage in a session, above which
+    /// loading fails with `VM_MAX_LOADED_RESOURCE_BYTES_REACHED`.
+    pub max_loaded_resource_bytes: Option<u64>,
+    /// Maximal number of events emitted in a session, above which emitting fails with
+    /// `VM_MAX_EVENTS_REACHED`.
+    pub max_events: Option<usize>,
 }
 
 impl Default for VMConfig {
@@ -19,6 +45,14 @@ impl Default for VMConfig {
             verifier: VerifierConfig::default(),
             max_binary_format_version: VERSION_MAX,
             paranoid_type_checks: false,
+            max_operand_stack_size: 1024,
+            max_call_stack_size: 1024,
+            max_value_depth: 128,
+            max_type_instantiation_nodes: None,
+            max_type_to_layout_nodes: None,
+            max_vector_length: None,
+            max_loaded_resource_bytes: None,
+            max_events: None,
         }
     }
 }
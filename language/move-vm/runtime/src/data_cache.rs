@@ -20,7 +20,7 @@ use move_vm_types::{
     loaded_data::runtime_types::Type,
     values::{GlobalValue, Value},
 };
-use std::collections::btree_map::BTreeMap;
+use std::collections::{btree_map::BTreeMap, BTreeSet};
 
 pub struct AccountDataCache {
     data_map: BTreeMap<Type, (MoveTypeLayout, GlobalValue)>,
@@ -53,7 +53,28 @@ pub(crate) struct TransactionDataCache<'r, 'l, S> {
     remote: &'r S,
     loader: &'l Loader,
     account_map: BTreeMap<AccountAddress, AccountDataCache>,
+    /// The changes flushed from `account_map` since the latest savepoint, or since the start of
+    /// the transaction
+    change_set: ChangeSet,
+    savepoints: Vec<Savepoint>,
     event_data: Vec<(Vec<u8>, u64, Type, MoveTypeLayout, Value)>,
+    /// The bytes of the resources loaded from the remote cache, checked against
+    /// `VMConfig::max_loaded_resource_bytes`
+    loaded_resource_bytes: u64,
+    /// The resources loaded from the remote cache since the latest savepoint, or since the start
+    /// of the transaction. They are counted and charged the first time they are loaded only.
+    loaded_resources: BTreeSet<(AccountAddress, Type)>,
+}
+
+/// The state of the transaction when a savepoint was created, restored when rolling back to it
+struct Savepoint {
+    /// The changes before the savepoint which were not part of an outer savepoint
+    change_set: ChangeSet,
+    /// The number of events, which is also checked against `VMConfig::max_events`
+    num_events: usize,
+    loaded_resource_bytes: u64,
+    /// The resources loaded before the savepoint which were not loaded before an outer savepoint
+    loaded_resources: BTreeSet<(AccountAddress, Type)>,
 }
 
 impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
@@ -64,7 +85,11 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
             remote,
             loader,
             account_map: BTreeMap::new(),
+            change_set: ChangeSet::new(),
+            savepoints: vec![],
             event_data: vec![],
+            loaded_resource_bytes: 0,
+            loaded_resources: BTreeSet::new(),
         }
     }
 
@@ -72,9 +97,79 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
     /// published modules.
     ///
     /// Gives all proper guarantees on lifetime of global data as well.
-    pub(crate) fn into_effects(self) -> PartialVMResult<(ChangeSet, Vec<Event>)> {
+    pub(crate) fn into_effects(mut self) -> PartialVMResult<(ChangeSet, Vec<Event>)> {
+        self.flush()?;
+        let mut change_set = ChangeSet::new();
+        for savepoint in std::mem::take(&mut self.savepoints) {
+            squash_change_sets(&mut change_set, savepoint.change_set)?;
+        }
+        let events = self.serialize_events(&self.event_data)?;
+        squash_change_sets(&mut change_set, self.change_set)?;
+        Ok((change_set, events))
+    }
+
+    /// Creates a savepoint, which the changes and events of the transaction can be rolled back to.
+    ///
+    /// The values loaded before the savepoint are flushed to a change set, from which they are
+    /// loaded again when accessed after it. The unchanged ones are loaded again from the remote
+    /// cache, without being counted or charged again.
+    pub(crate) fn savepoint(&mut self) -> PartialVMResult<()> {
+        self.flush()?;
+        let change_set = std::mem::replace(&mut self.change_set, ChangeSet::new());
+        self.savepoints.push(Savepoint {
+            change_set,
+            num_events: self.event_data.len(),
+            loaded_resource_bytes: self.loaded_resource_bytes,
+            loaded_resources: std::mem::take(&mut self.loaded_resources),
+        });
+        Ok(())
+    }
+
+    /// Discards the changes and events since the latest savepoint, and removes it. The resources
+    /// loaded and the events emitted since the savepoint no longer count towards the limits of the
+    /// `VMConfig`.
+    pub(crate) fn rollback_to_savepoint(&mut self) -> PartialVMResult<()> {
+        let savepoint = self.savepoints.pop().ok_or_else(no_savepoint)?;
+        self.account_map.clear();
+        self.change_set = savepoint.change_set;
+        self.event_data.truncate(savepoint.num_events);
+        self.loaded_resource_bytes = savepoint.loaded_resource_bytes;
+        self.loaded_resources = savepoint.loaded_resources;
+        Ok(())
+    }
+
+    /// Keeps the changes and events since the latest savepoint, and removes it. They are
+    /// squashed with the changes before the savepoint.
+    pub(crate) fn commit_savepoint(&mut self) -> PartialVMResult<()> {
+        let savepoint = self.savepoints.pop().ok_or_else(no_savepoint)?;
+        let change_set = std::mem::replace(&mut self.change_set, savepoint.change_set);
+        let loaded_resources =
+            std::mem::replace(&mut self.loaded_resources, savepoint.loaded_resources);
+        self.loaded_resources.extend(loaded_resources);
+        squash_change_sets(&mut self.change_set, change_set)
+    }
+
+    /// The changes and events since the latest savepoint, or since the start of the transaction if
+    /// there is none.
+    pub(crate) fn effects_since_savepoint(&mut self) -> PartialVMResult<(ChangeSet, Vec<Event>)> {
+        self.flush()?;
+        let num_events = self
+            .savepoints
+            .last()
+            .map(|savepoint| savepoint.num_events)
+            .unwrap_or(0);
+        let events = self.serialize_events(&self.event_data[num_events..])?;
+        Ok((self.change_set.clone(), events))
+    }
+
+    pub(crate) fn num_savepoints(&self) -> usize {
+        self.savepoints.len()
+    }
+
+    /// Moves the changes of the values loaded in `account_map` to `change_set`.
+    fn flush(&mut self) -> PartialVMResult<()> {
         let mut change_set = ChangeSet::new();
-        for (addr, account_data_cache) in self.account_map.into_iter() {
+        for (addr, account_data_cache) in std::mem::take(&mut self.account_map) {
             let mut modules = BTreeMap::new();
             for (module_name, (module_blob, is_republishing)) in account_data_cache.module_map {
                 let op = if is_republishing {
@@ -124,28 +219,77 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
                     .expect("accounts should be unique");
             }
         }
+        squash_change_sets(&mut self.change_set, change_set)
+    }
 
+    fn serialize_events(
+        &self,
+        event_data: &[(Vec<u8>, u64, Type, MoveTypeLayout, Value)],
+    ) -> PartialVMResult<Vec<Event>> {
         let mut events = vec![];
-        for (guid, seq_num, ty, ty_layout, val) in self.event_data {
-            let ty_tag = self.loader.type_to_type_tag(&ty)?;
+        for (guid, seq_num, ty, ty_layout, val) in event_data {
+            let ty_tag = self.loader.type_to_type_tag(ty)?;
             let blob = val
-                .simple_serialize(&ty_layout)
+                .simple_serialize(ty_layout)
                 .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))?;
-            events.push((guid, seq_num, ty_tag, blob))
+            events.push((guid.clone(), *seq_num, ty_tag, blob))
         }
+        Ok(events)
+    }
 
-        Ok((change_set, events))
+    /// The change sets flushed from `account_map`, from the latest one
+    fn flushed_change_sets<'a>(
+        change_set: &'a ChangeSet,
+        savepoints: &'a [Savepoint],
+    ) -> impl Iterator<Item = &'a ChangeSet> {
+        std::iter::once(change_set).chain(
+            savepoints
+                .iter()
+                .rev()
+                .map(|savepoint| &savepoint.change_set),
+        )
+    }
+
+    /// Whether `resource` was loaded from the remote cache before, and counted then
+    fn is_loaded(
+        loaded_resources: &BTreeSet<(AccountAddress, Type)>,
+        savepoints: &[Savepoint],
+        resource: &(AccountAddress, Type),
+    ) -> bool {
+        loaded_resources.contains(resource)
+            || savepoints
+                .iter()
+                .any(|savepoint| savepoint.loaded_resources.contains(resource))
+    }
+
+    /// The latest flushed change of the module `module_id`, if any
+    fn flushed_module(&self, module_id: &ModuleId) -> Option<&Op<Vec<u8>>> {
+        Self::flushed_change_sets(&self.change_set, &self.savepoints).find_map(|change_set| {
+            change_set
+                .accounts()
+                .get(module_id.address())?
+                .modules()
+                .get(module_id.name())
+        })
     }
 
     pub(crate) fn num_mutated_accounts(&self, sender: &AccountAddress) -> u64 {
-        // The sender's account will always be mutated.
-        let mut total_mutated_accounts: u64 = 1;
+        let mut mutated_accounts = BTreeSet::new();
         for (addr, entry) in self.account_map.iter() {
-            if addr != sender && entry.data_map.values().any(|(_, v)| v.is_mutated()) {
-                total_mutated_accounts += 1;
+            if entry.data_map.values().any(|(_, v)| v.is_mutated()) {
+                mutated_accounts.insert(addr);
             }
         }
-        total_mutated_accounts
+        for change_set in Self::flushed_change_sets(&self.change_set, &self.savepoints) {
+            for (addr, account_change_set) in change_set.accounts() {
+                if !account_change_set.resources().is_empty() {
+                    mutated_accounts.insert(addr);
+                }
+            }
+        }
+        mutated_accounts.remove(sender);
+        // The sender's account will always be mutated.
+        1 + mutated_accounts.len() as u64
     }
 
     fn get_mut_or_insert_with<'a, K, V, F>(map: &'a mut BTreeMap<K, V>, k: &K, gen: F) -> &'a mut V
@@ -161,6 +305,18 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
     }
 }
 
+fn squash_change_sets(change_set: &mut ChangeSet, other: ChangeSet) -> PartialVMResult<()> {
+    change_set.squash(other).map_err(|err| {
+        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            .with_message(format!("Failed to squash change sets: {}", err))
+    })
+}
+
+fn no_savepoint() -> PartialVMError {
+    PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+        .with_message("No savepoint in the transaction".to_string())
+}
+
 // `DataStore` implementation for the `TransactionDataCache`
 impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
     // Retrieve data from the local cache or loads it from the remote cache into the local cache.
@@ -188,36 +344,71 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
             // TODO(Gas): Shall we charge for this?
             let ty_layout = self.loader.type_to_type_layout(ty)?;
 
-            let gv = match self.remote.get_resource(&addr, &ty_tag) {
-                Ok(Some(blob)) => {
-                    load_res = Some(Some(NumBytes::new(blob.len() as u64)));
-                    let val = match Value::simple_deserialize(&blob, &ty_layout) {
-                        Some(val) => val,
-                        None => {
-                            let msg =
-                                format!("Failed to deserialize resource {} at {}!", ty_tag, addr);
-                            return Err(PartialVMError::new(
-                                StatusCode::FAILED_TO_DESERIALIZE_RESOURCE,
-                            )
-                            .with_message(msg));
-                        }
-                    };
-
-                    GlobalValue::cached(val)?
-                }
-                Ok(None) => {
-                    load_res = Some(None);
-                    GlobalValue::none()
-                }
-                Err(err) => {
-                    let msg = format!("Unexpected storage error: {:?}", err);
-                    return Err(
-                        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            let deserialize = |blob: &[u8]| match Value::simple_deserialize(blob, &ty_layout) {
+                Some(val) => Ok(val),
+                None => {
+                    let msg = format!("Failed to deserialize resource {} at {}!", ty_tag, addr);
+                    Err(
+                        PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE)
                             .with_message(msg),
-                    );
+                    )
                 }
             };
 
+            // Resources changed before a savepoint are not loaded from the remote cache again
+            let flushed = Self::flushed_change_sets(&self.change_set, &self.savepoints).find_map(
+                |change_set| {
+                    change_set
+                        .accounts()
+                        .get(&addr)?
+                        .resources()
+                        .get(ty_tag.as_ref())
+                },
+            );
+            let resource = (addr, ty.clone());
+            // Resources unchanged before a savepoint are loaded again, but only counted once
+            let counted = Self::is_loaded(&self.loaded_resources, &self.savepoints, &resource);
+            let gv = match flushed {
+                Some(Op::New(blob) | Op::Modify(blob)) => GlobalValue::cached(deserialize(blob)?)?,
+                Some(Op::Delete) => GlobalValue::none(),
+                None => match self.remote.get_resource(&addr, &ty_tag) {
+                    Ok(Some(blob)) if counted => GlobalValue::cached(deserialize(&blob)?)?,
+                    Ok(None) if counted => GlobalValue::none(),
+                    Ok(Some(blob)) => {
+                        self.loaded_resource_bytes += blob.len() as u64;
+                        if let Some(max_loaded_resource_bytes) =
+                            self.loader.vm_config().max_loaded_resource_bytes
+                        {
+                            if self.loaded_resource_bytes > max_loaded_resource_bytes {
+                                return Err(PartialVMError::new(
+                                    StatusCode::VM_MAX_LOADED_RESOURCE_BYTES_REACHED,
+                                )
+                                .with_message(format!(
+                                    "Loading resource {} at {} exceeds the maximal {} bytes of \
+                                     resources loaded in a session",
+                                    ty_tag, addr, max_loaded_resource_bytes
+                                )));
+                            }
+                        }
+                        load_res = Some(Some(NumBytes::new(blob.len() as u64)));
+                        self.loaded_resources.insert(resource);
+                        GlobalValue::cached(deserialize(&blob)?)?
+                    }
+                    Ok(None) => {
+                        load_res = Some(None);
+                        self.loaded_resources.insert(resource);
+                        GlobalValue::none()
+                    }
+                    Err(err) => {
+                        let msg = format!("Unexpected storage error: {:?}", err);
+                        return Err(PartialVMError::new(
+                            StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                        )
+                        .with_message(msg));
+                    }
+                },
+            };
+
             account_cache.data_map.insert(ty.clone(), (ty_layout, gv));
         }
 
@@ -237,6 +428,9 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
                 return Ok(blob.clone());
             }
         }
+        if let Some(Op::New(blob) | Op::Modify(blob)) = self.flushed_module(module_id) {
+            return Ok(blob.clone());
+        }
         match self.remote.get_module(module_id) {
             Ok(Some(bytes)) => Ok(bytes),
             Ok(None) => Err(PartialVMError::new(StatusCode::LINKER_ERROR)
@@ -277,6 +471,9 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
                 return Ok(true);
             }
         }
+        if let Some(op) = self.flushed_module(module_id) {
+            return Ok(!matches!(op, Op::Delete));
+        }
         Ok(self
             .remote
             .get_module(module_id)
@@ -293,6 +490,16 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
         ty: Type,
         val: Value,
     ) -> PartialVMResult<()> {
+        if let Some(max_events) = self.loader.vm_config().max_events {
+            if self.event_data.len() >= max_events {
+                return Err(
+                    PartialVMError::new(StatusCode::VM_MAX_EVENTS_REACHED).with_message(format!(
+                        "Emitting an event exceeds the maximal {} events in a session",
+                        max_events
+                    )),
+                );
+            }
+        }
         let ty_layout = self.loader.type_to_type_layout(&ty)?;
         Ok(self.event_data.push((guid, seq_num, ty, ty_layout, val)))
     }
@@ -0,0 +1,608 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiles of the gas used by executions.
+//!
+//! A `GasProfiler` wraps the `GasMeter` of an execution and attributes every charge to the
+//! function being executed, which it follows through the charges of calls and returns. The
+//! resulting `GasProfile` has the exclusive and inclusive gas of each function, with the numbers
+//! of calls and instructions, the bytes loaded from storage and the gas charged by natives, as
+//! well as the gas of each call stack. It can be written as folded stacks, the input of flamegraph
+//! tools such as `inferno` or `flamegraph.pl`, and as text or JSON reports sorted by exclusive gas.
+//!
+//! Gas is measured in internal units, as the difference of the balance of the wrapped meter around
+//! each charge. Meters with an unlimited balance, such as `UnmeteredGasMeter` or those that do not
+//! implement `GasMeter::balance_internal`, cannot be measured: their profiles are marked as
+//! unmetered and count the calls and instructions of functions, without any gas.
+
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    gas_algebra::{InternalGas, NumArgs, NumBytes},
+    language_storage::ModuleId,
+};
+use move_vm_types::{
+    gas::{GasMeter, SimpleInstruction},
+    views::{TypeView, ValueView},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// The name of the root frame of the executions of scripts
+pub const SCRIPT_FRAME_NAME: &str = "script";
+
+/// The files of a profile saved by `GasProfile::save`
+pub const FOLDED_STACKS_FILE: &str = "stacks.folded";
+pub const TEXT_REPORT_FILE: &str = "report.txt";
+pub const JSON_REPORT_FILE: &str = "report.json";
+
+/// The gas used by a function over all its calls
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionGasProfile {
+    pub calls: u64,
+    /// Gas charged while the function is executed, excluding its callees
+    pub exclusive_gas: u64,
+    /// Gas charged from the call of the function to its return. Recursive calls are counted once.
+    pub inclusive_gas: u64,
+    pub instructions: u64,
+    /// Bytes of the resources loaded from storage, the first time they are accessed
+    pub loaded_bytes: u64,
+    /// Gas charged by the implementation of a native function
+    pub native_gas: u64,
+}
+
+impl FunctionGasProfile {
+    fn combine(&mut self, other: &FunctionGasProfile) {
+        self.calls += other.calls;
+        self.exclusive_gas += other.exclusive_gas;
+        self.inclusive_gas += other.inclusive_gas;
+        self.instructions += other.instructions;
+        self.loaded_bytes += other.loaded_bytes;
+        self.native_gas += other.native_gas;
+    }
+}
+
+/// The gas used by executions, by function and by call stack
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasProfile {
+    pub total_gas: u64,
+    /// Whether the gas of some execution could not be measured, its meter having an unlimited
+    /// balance
+    pub unmetered: bool,
+    /// The profile of each function, named `0x<address>::<module>::<function>`
+    pub functions: BTreeMap<String, FunctionGasProfile>,
+    /// The exclusive gas of the top function of each call stack, by the names of its functions
+    /// from the outermost one, separated by `;`
+    pub stacks: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+struct FunctionReport<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    profile: &'a FunctionGasProfile,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    total_gas: u64,
+    unmetered: bool,
+    functions: Vec<FunctionReport<'a>>,
+}
+
+impl GasProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the gas of `other` to this profile, as if their executions were profiled together
+    pub fn merge(&mut self, other: &GasProfile) {
+        self.total_gas += other.total_gas;
+        self.unmetered |= other.unmetered;
+        for (name, profile) in &other.functions {
+            self.functions
+                .entry(name.clone())
+                .or_default()
+                .combine(profile);
+        }
+        for (stack, gas) in &other.stacks {
+            *self.stacks.entry(stack.clone()).or_default() += gas;
+        }
+    }
+
+    /// The functions by decreasing exclusive gas, then inclusive gas and name
+    pub fn sorted_functions(&self) -> Vec<(&str, &FunctionGasProfile)> {
+        let mut functions = self
+            .functions
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+            .collect::<Vec<_>>();
+        functions.sort_by(|(name1, profile1), (name2, profile2)| {
+            profile2
+                .exclusive_gas
+                .cmp(&profile1.exclusive_gas)
+                .then(profile2.inclusive_gas.cmp(&profile1.inclusive_gas))
+                .then(name1.cmp(name2))
+        });
+        functions
+    }
+
+    /// Writes a line `<function>;...;<function> <gas>` for each call stack
+    pub fn write_folded_stacks<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for (stack, gas) in &self.stacks {
+            writeln!(writer, "{} {}", stack, gas)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a table of the functions sorted by exclusive gas
+    pub fn write_text_report<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let units = if self.unmetered {
+            "internal units, not metered"
+        } else {
+            "internal units"
+        };
+        writeln!(writer, "Total gas: {} ({})", self.total_gas, units)?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "{:>12} {:>12} {:>8} {:>12} {:>12} {:>12}  function",
+            "exclusive", "inclusive", "calls", "instructions", "loaded bytes", "native gas"
+        )?;
+        for (name, profile) in self.sorted_functions() {
+            writeln!(
+                writer,
+                "{:>12} {:>12} {:>8} {:>12} {:>12} {:>12}  {}",
+                profile.exclusive_gas,
+                profile.inclusive_gas,
+                profile.calls,
+                profile.instructions,
+                profile.loaded_bytes,
+                profile.native_gas,
+                name
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the total gas and the functions sorted by exclusive gas as a JSON object
+    pub fn write_json_report<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let report = JsonReport {
+            total_gas: self.total_gas,
+            unmetered: self.unmetered,
+            functions: self
+                .sorted_functions()
+                .into_iter()
+                .map(|(name, profile)| FunctionReport { name, profile })
+                .collect(),
+        };
+        serde_json::to_writer_pretty(&mut *writer, &report)?;
+        writeln!(writer)
+    }
+
+    /// Writes the folded stacks, the text report and the JSON report in the directory `dir`,
+    /// creating it if needed
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut folded = BufWriter::new(File::create(dir.join(FOLDED_STACKS_FILE))?);
+        self.write_folded_stacks(&mut folded)?;
+        folded.flush()?;
+        let mut text = BufWriter::new(File::create(dir.join(TEXT_REPORT_FILE))?);
+        self.write_text_report(&mut text)?;
+        text.flush()?;
+        let mut json = BufWriter::new(File::create(dir.join(JSON_REPORT_FILE))?);
+        self.write_json_report(&mut json)?;
+        json.flush()
+    }
+}
+
+/// A call of a function, not returned yet
+struct Frame {
+    name: String,
+    /// The total gas charged before the call
+    gas_at_entry: u64,
+    /// Whether the function is native, to be returned from when its implementation is charged
+    native: bool,
+    profile: FunctionGasProfile,
+}
+
+impl Frame {
+    fn new(name: String, gas_at_entry: u64) -> Self {
+        Self {
+            name,
+            gas_at_entry,
+            native: false,
+            profile: FunctionGasProfile {
+                calls: 1,
+                ..FunctionGasProfile::default()
+            },
+        }
+    }
+}
+
+/// A `GasMeter` profiling the gas charged by `gas_meter`, to which it delegates the charges
+pub struct GasProfiler<G> {
+    gas_meter: G,
+    frames: Vec<Frame>,
+    profile: GasProfile,
+}
+
+/// The name of a function in profiles
+pub fn function_name(module_id: &ModuleId, name: &str) -> String {
+    format!(
+        "{}::{}::{}",
+        module_id.address().to_hex_literal(),
+        module_id.name(),
+        name
+    )
+}
+
+impl<G: GasMeter> GasProfiler<G> {
+    /// Profiles the execution of the function `name` of `module_id`
+    pub fn new_function(gas_meter: G, module_id: &ModuleId, name: &str) -> Self {
+        Self::new(gas_meter, function_name(module_id, name))
+    }
+
+    /// Profiles the execution of a script
+    pub fn new_script(gas_meter: G) -> Self {
+        Self::new(gas_meter, SCRIPT_FRAME_NAME.to_string())
+    }
+
+    fn new(gas_meter: G, root: String) -> Self {
+        let unmetered = u64::from(gas_meter.balance_internal()) == u64::MAX;
+        Self {
+            gas_meter,
+            frames: vec![Frame::new(root, 0)],
+            profile: GasProfile {
+                unmetered,
+                ..GasProfile::new()
+            },
+        }
+    }
+
+    pub fn gas_meter(&self) -> &G {
+        &self.gas_meter
+    }
+
+    /// Ends the profile, returning the wrapped gas meter with it. The functions not returned yet,
+    /// after an abort or an error, end there.
+    pub fn finish(mut self) -> (G, GasProfile) {
+        while !self.frames.is_empty() {
+            self.pop_frame();
+        }
+        (self.gas_meter, self.profile)
+    }
+
+    /// Delegates a charge to the wrapped gas meter, attributing the gas it charges to the current
+    /// function
+    fn charge<T>(
+        &mut self,
+        is_instruction: bool,
+        charge: impl FnOnce(&mut G) -> PartialVMResult<T>,
+    ) -> PartialVMResult<T> {
+        // a native function that was not charged for its implementation failed, ending the
+        // execution that called it, so the charge is for a later execution
+        if self.frames.last().map_or(false, |frame| frame.native) {
+            self.pop_frame();
+        }
+        self.charge_current(is_instruction, charge)
+    }
+
+    /// Delegates a charge to the wrapped gas meter, attributing the gas it charges to the function
+    /// at the top of the stack, even if it is a native one
+    fn charge_current<T>(
+        &mut self,
+        is_instruction: bool,
+        charge: impl FnOnce(&mut G) -> PartialVMResult<T>,
+    ) -> PartialVMResult<T> {
+        let balance = self.gas_meter.balance_internal();
+        let res = charge(&mut self.gas_meter);
+        let gas: u64 = balance
+            .checked_sub(self.gas_meter.balance_internal())
+            .unwrap_or_else(|| InternalGas::new(0))
+            .into();
+        self.profile.total_gas += gas;
+        if let Some(frame) = self.frames.last_mut() {
+            frame.profile.exclusive_gas += gas;
+            if is_instruction {
+                frame.profile.instructions += 1;
+            }
+        }
+        res
+    }
+
+    fn push_frame(&mut self, module_id: &ModuleId, name: &str) {
+        let frame = Frame::new(function_name(module_id, name), self.profile.total_gas);
+        self.frames.push(frame);
+    }
+
+    fn pop_frame(&mut self) {
+        let mut frame = match self.frames.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+        // recursive calls are included in the outermost one
+        if self.frames.iter().all(|caller| caller.name != frame.name) {
+            frame.profile.inclusive_gas = self.profile.total_gas - frame.gas_at_entry;
+        }
+        if frame.profile.exclusive_gas > 0 {
+            let stack = self
+                .frames
+                .iter()
+                .map(|caller| caller.name.as_str())
+                .chain(std::iter::once(frame.name.as_str()))
+                .collect::<Vec<_>>()
+                .join(";");
+            *self.profile.stacks.entry(stack).or_default() += frame.profile.exclusive_gas;
+        }
+        self.profile
+            .functions
+            .entry(frame.name)
+            .or_default()
+            .combine(&frame.profile);
+    }
+}
+
+impl<G: GasMeter> GasMeter for GasProfiler<G> {
+    fn balance_internal(&self) -> InternalGas {
+        self.gas_meter.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_simple_instr(instr))
+    }
+
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_pop(popped_val))
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_call(module_id, func_name, args, num_locals)
+        })?;
+        self.push_frame(module_id, func_name);
+        Ok(())
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_call_generic(module_id, func_name, ty_args, args, num_locals)
+        })?;
+        self.push_frame(module_id, func_name);
+        Ok(())
+    }
+
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_ld_const(size))
+    }
+
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        // part of the charges of the `LdConst` instruction
+        self.charge(false, |gas_meter| {
+            gas_meter.charge_ld_const_after_deserialization(val)
+        })
+    }
+
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_copy_loc(val))
+    }
+
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_move_loc(val))
+    }
+
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_store_loc(val))
+    }
+
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_pack(is_generic, args))
+    }
+
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_unpack(is_generic, args))
+    }
+
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_read_ref(val))
+    }
+
+    fn charge_write_ref(
+        &mut self,
+        new_val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_write_ref(new_val, old_val)
+        })
+    }
+
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_eq(lhs, rhs))
+    }
+
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_neq(lhs, rhs))
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_borrow_global(is_mut, is_generic, ty, is_success)
+        })
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_exists(is_generic, ty, exists)
+        })
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_move_from(is_generic, ty, val)
+        })
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_move_to(is_generic, ty, val, is_success)
+        })
+    }
+
+    fn charge_vec_pack<'a>(
+        &mut self,
+        ty: impl TypeView + 'a,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_vec_pack(ty, args))
+    }
+
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_vec_len(ty))
+    }
+
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_vec_borrow(is_mut, ty, is_success)
+        })
+    }
+
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_vec_push_back(ty, val))
+    }
+
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_vec_pop_back(ty, val))
+    }
+
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| {
+            gas_meter.charge_vec_unpack(ty, expect_num_elements, elems)
+        })
+    }
+
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge(true, |gas_meter| gas_meter.charge_vec_swap(ty))
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        if let (Some((bytes, _)), Some(frame)) = (&loaded, self.frames.last_mut()) {
+            frame.profile.loaded_bytes += u64::from(*bytes);
+        }
+        self.charge(false, |gas_meter| gas_meter.charge_load_resource(loaded))
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        let balance = self.gas_meter.balance_internal();
+        let res = self.charge_current(false, |gas_meter| {
+            gas_meter.charge_native_function(amount, ret_vals)
+        });
+        if let Some(frame) = self.frames.last_mut() {
+            let gas = balance
+                .checked_sub(self.gas_meter.balance_internal())
+                .unwrap_or_else(|| InternalGas::new(0));
+            frame.profile.native_gas += u64::from(gas);
+        }
+        // the native function returns
+        self.pop_frame();
+        res
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        // the function called last is native
+        if let Some(frame) = self.frames.last_mut() {
+            frame.native = true;
+        }
+        self.charge_current(false, |gas_meter| {
+            gas_meter.charge_native_function_before_execution(ty_args, args)
+        })
+    }
+
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let res = self.charge(false, |gas_meter| gas_meter.charge_drop_frame(locals));
+        self.pop_frame();
+        res
+    }
+}
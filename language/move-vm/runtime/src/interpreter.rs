@@ -6,6 +6,7 @@ use crate::{
     loader::{Function, Loader, Resolver},
     native_functions::NativeContext,
     trace,
+    tracer::{TracedFunction, TracedGlobals, Tracer},
 };
 use fail::fail_point;
 use move_binary_format::{
@@ -15,7 +16,7 @@ use move_binary_format::{
 use move_core_types::{
     account_address::AccountAddress,
     gas_algebra::{NumArgs, NumBytes},
-    language_storage::TypeTag,
+    language_storage::{StructTag, TypeTag},
     vm_status::{StatusCode, StatusType},
 };
 use move_vm_types::{
@@ -69,6 +70,8 @@ pub(crate) struct Interpreter {
     call_stack: CallStack,
     /// Whether to perform a paranoid type safety checks at runtime.
     paranoid_type_checks: bool,
+    /// The maximal length of vectors created or grown by vector instructions, if any.
+    max_vector_length: Option<u64>,
 }
 
 struct TypeWithLoader<'a, 'b> {
@@ -92,16 +95,30 @@ impl Interpreter {
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        mut tracer: Option<&mut dyn Tracer>,
         loader: &Loader,
     ) -> VMResult<Vec<Value>> {
-        Interpreter {
-            operand_stack: Stack::new(),
-            call_stack: CallStack::new(),
-            paranoid_type_checks: loader.vm_config().paranoid_type_checks,
+        let vm_config = loader.vm_config();
+        let result = Interpreter {
+            operand_stack: Stack::new(vm_config.max_operand_stack_size),
+            call_stack: CallStack::new(vm_config.max_call_stack_size),
+            paranoid_type_checks: vm_config.paranoid_type_checks,
+            max_vector_length: vm_config.max_vector_length,
         }
         .execute_main(
-            loader, data_store, gas_meter, extensions, function, ty_args, args,
-        )
+            loader,
+            data_store,
+            gas_meter,
+            extensions,
+            &mut tracer,
+            function,
+            ty_args,
+            args,
+        );
+        if let (Some(tracer), Err(err)) = (&mut tracer, &result) {
+            tracer.abort(err)
+        }
+        result
     }
 
     /// Main loop for the execution of a function.
@@ -116,10 +133,15 @@ impl Interpreter {
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        tracer: &mut Option<&mut dyn Tracer>,
         function: Arc<Function>,
         ty_args: Vec<Type>,
         args: Vec<Value>,
     ) -> VMResult<Vec<Value>> {
+        if let Some(tracer) = tracer {
+            let ty_tags = type_tags(loader, &ty_args).map_err(|e| self.set_location(e))?;
+            tracer.enter_function(TracedFunction(&function), &ty_tags, &args);
+        }
         let mut locals = Locals::new(function.local_count());
         for (i, value) in args.into_iter().enumerate() {
             locals
@@ -134,10 +156,18 @@ impl Interpreter {
             let resolver = current_frame.resolver(loader);
             let exit_code =
                 current_frame //self
-                    .execute_code(&resolver, &mut self, data_store, gas_meter)
+                    .execute_code(&resolver, &mut self, data_store, gas_meter, tracer)
                     .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
             match exit_code {
                 ExitCode::Return => {
+                    if let Some(tracer) = tracer {
+                        let return_values = self
+                            .operand_stack
+                            .last_n(current_frame.function.return_type_count())
+                            .map_err(|e| set_err_info!(current_frame, e))?;
+                        tracer
+                            .exit_function(TracedFunction(&current_frame.function), return_values);
+                    }
                     let non_ref_vals = current_frame
                         .locals
                         .drop_all_values()
@@ -178,7 +208,8 @@ impl Interpreter {
                             func.name(),
                             self.operand_stack
                                 .last_n(func.arg_count())
-                                .map_err(|e| set_err_info!(current_frame, e))?,
+                                .map_err(|e| set_err_info!(current_frame, e))?
+                                .iter(),
                             (func.local_count() as u64).into(),
                         )
                         .map_err(|e| set_err_info!(current_frame, e))?;
@@ -189,12 +220,20 @@ impl Interpreter {
                             data_store,
                             gas_meter,
                             extensions,
+                            tracer,
                             func,
                             vec![],
                         )?;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
                     }
+                    if let Some(tracer) = tracer {
+                        let args = self
+                            .operand_stack
+                            .last_n(func.arg_count())
+                            .map_err(|e| set_err_info!(current_frame, e))?;
+                        tracer.enter_function(TracedFunction(&func), &[], args);
+                    }
                     let frame = self
                         .make_call_frame(loader, func, vec![])
                         .map_err(|e| self.set_location(e))
@@ -233,18 +272,28 @@ impl Interpreter {
                             ty_args.iter().map(|ty| TypeWithLoader { ty, loader }),
                             self.operand_stack
                                 .last_n(func.arg_count())
-                                .map_err(|e| set_err_info!(current_frame, e))?,
+                                .map_err(|e| set_err_info!(current_frame, e))?
+                                .iter(),
                             (func.local_count() as u64).into(),
                         )
                         .map_err(|e| set_err_info!(current_frame, e))?;
 
                     if func.is_native() {
                         self.call_native(
-                            &resolver, data_store, gas_meter, extensions, func, ty_args,
+                            &resolver, data_store, gas_meter, extensions, tracer, func, ty_args,
                         )?;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
                     }
+                    if let Some(tracer) = tracer {
+                        let ty_tags = type_tags(loader, &ty_args)
+                            .map_err(|e| set_err_info!(current_frame, e))?;
+                        let args = self
+                            .operand_stack
+                            .last_n(func.arg_count())
+                            .map_err(|e| set_err_info!(current_frame, e))?;
+                        tracer.enter_function(TracedFunction(&func), &ty_tags, args);
+                    }
                     let frame = self
                         .make_call_frame(loader, func, ty_args)
                         .map_err(|e| self.set_location(e))
@@ -333,6 +382,7 @@ impl Interpreter {
         data_store: &mut dyn DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        tracer: &mut Option<&mut dyn Tracer>,
         function: Arc<Function>,
         ty_args: Vec<Type>,
     ) -> VMResult<()> {
@@ -342,6 +392,7 @@ impl Interpreter {
             data_store,
             gas_meter,
             extensions,
+            tracer,
             function.clone(),
             ty_args,
         )
@@ -363,6 +414,7 @@ impl Interpreter {
         data_store: &mut dyn DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        tracer: &mut Option<&mut dyn Tracer>,
         function: Arc<Function>,
         ty_args: Vec<Type>,
     ) -> PartialVMResult<()> {
@@ -382,6 +434,12 @@ impl Interpreter {
             }
         }
 
+        if let Some(tracer) = tracer {
+            let ty_tags = type_tags(resolver.loader(), &ty_args)?;
+            tracer.native_call(TracedFunction(&function), &ty_tags, args.make_contiguous());
+        }
+        let event_count = data_store.events().len();
+
         let mut native_context = NativeContext::new(self, data_store, resolver, extensions);
         let native_function = function.get_native()?;
 
@@ -421,6 +479,14 @@ impl Interpreter {
                 ),
             );
         }
+        // Events can only be emitted by native functions
+        if let Some(tracer) = tracer {
+            for (guid, seq_num, ty, _, value) in &data_store.events()[event_count..] {
+                let ty_tag = resolver.loader().type_to_type_tag(ty)?;
+                tracer.emit_event(guid, *seq_num, &ty_tag, value);
+            }
+        }
+
         // Put return values on the top of the operand stack, where the caller will find them.
         // This is one of only two times the operand stack is shared across call stack frames; the other is in handling
         // the Return instruction for normal calls
@@ -551,6 +617,7 @@ impl Interpreter {
         loader: &Loader,
         gas_meter: &mut impl GasMeter,
         data_store: &mut impl DataStore,
+        tracer: &mut Option<&mut dyn Tracer>,
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
@@ -561,10 +628,39 @@ impl Interpreter {
             TypeWithLoader { ty, loader },
             res.is_ok(),
         )?;
+        if let Some(tracer) = tracer {
+            let ty_tag = loader.type_to_type_tag(ty)?;
+            if is_mut {
+                tracer.mut_borrow_global(addr, &ty_tag, res.is_ok())
+            } else {
+                tracer.read_global(addr, &ty_tag, res.is_ok())
+            }
+        }
         self.operand_stack.push(res?)?;
         Ok(())
     }
 
+    /// The maximal length of the vectors created or grown by the VM, if any.
+    pub(crate) fn max_vector_length(&self) -> Option<u64> {
+        self.max_vector_length
+    }
+
+    /// Fails if a vector of `len` elements exceeds the maximal vector length, if any.
+    pub(crate) fn check_vector_length(&self, len: u64) -> PartialVMResult<()> {
+        if let Some(max_vector_length) = self.max_vector_length {
+            if len > max_vector_length {
+                let msg = format!(
+                    "Vector of length {} exceeds the maximal length {}",
+                    len, max_vector_length
+                );
+                return Err(
+                    PartialVMError::new(StatusCode::VM_MAX_VECTOR_LENGTH_REACHED).with_message(msg),
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Exists opcode.
     fn exists(
         &mut self,
@@ -572,12 +668,16 @@ impl Interpreter {
         loader: &Loader,
         gas_meter: &mut impl GasMeter,
         data_store: &mut impl DataStore,
+        tracer: &mut Option<&mut dyn Tracer>,
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
         let gv = Self::load_resource(gas_meter, data_store, addr, ty)?;
         let exists = gv.exists()?;
         gas_meter.charge_exists(is_generic, TypeWithLoader { ty, loader }, exists)?;
+        if let Some(tracer) = tracer {
+            tracer.read_global(addr, &loader.type_to_type_tag(ty)?, exists);
+        }
         self.operand_stack.push(Value::bool(exists))?;
         Ok(())
     }
@@ -589,6 +689,7 @@ impl Interpreter {
         loader: &Loader,
         gas_meter: &mut impl GasMeter,
         data_store: &mut impl DataStore,
+        tracer: &mut Option<&mut dyn Tracer>,
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
@@ -607,6 +708,9 @@ impl Interpreter {
                 return Err(err);
             }
         };
+        if let Some(tracer) = tracer {
+            tracer.write_global(addr, &loader.type_to_type_tag(ty)?, None);
+        }
         self.operand_stack.push(resource)?;
         Ok(())
     }
@@ -618,6 +722,7 @@ impl Interpreter {
         loader: &Loader,
         gas_meter: &mut impl GasMeter,
         data_store: &mut impl DataStore,
+        tracer: &mut Option<&mut dyn Tracer>,
         addr: AccountAddress,
         ty: &Type,
         resource: Value,
@@ -625,6 +730,10 @@ impl Interpreter {
         let gv = Self::load_resource(gas_meter, data_store, addr, ty)?;
         // NOTE(Gas): To maintain backward compatibility, we need to charge gas after attempting
         //            the move_to operation.
+        let traced_resource = match tracer {
+            Some(_) => Some(resource.copy_value()?),
+            None => None,
+        };
         match gv.move_to(resource) {
             Ok(()) => {
                 gas_meter.charge_move_to(
@@ -633,6 +742,9 @@ impl Interpreter {
                     gv.view().unwrap(),
                     true,
                 )?;
+                if let (Some(tracer), Some(resource)) = (tracer, traced_resource) {
+                    tracer.write_global(addr, &loader.type_to_type_tag(ty)?, Some(&resource));
+                }
                 Ok(())
             }
             Err((err, resource)) => {
@@ -745,7 +857,7 @@ impl Interpreter {
         loader: &Loader,
     ) -> PartialVMResult<()> {
         debug_writeln!(buf, "Call Stack:")?;
-        for (i, frame) in self.call_stack.0.iter().enumerate() {
+        for (i, frame) in self.call_stack.frames.iter().enumerate() {
             self.debug_print_frame(buf, loader, i, frame)?;
         }
         debug_writeln!(buf, "Operand Stack:")?;
@@ -767,7 +879,7 @@ impl Interpreter {
     /// of an execution.
     fn internal_state_str(&self, current_frame: &Frame) -> String {
         let mut internal_state = "Call stack:\n".to_string();
-        for (i, frame) in self.call_stack.0.iter().enumerate() {
+        for (i, frame) in self.call_stack.frames.iter().enumerate() {
             internal_state.push_str(
                 format!(
                     " frame #{}: {} [pc = {}]\n",
@@ -781,7 +893,7 @@ impl Interpreter {
         internal_state.push_str(
             format!(
                 "*frame #{}: {} [pc = {}]:\n",
-                self.call_stack.0.len(),
+                self.call_stack.frames.len(),
                 current_frame.function.pretty_string(),
                 current_frame.pc,
             )
@@ -843,29 +955,27 @@ impl Interpreter {
     }
 }
 
-// TODO Determine stack size limits based on gas limit
-const OPERAND_STACK_SIZE_LIMIT: usize = 1024;
-const CALL_STACK_SIZE_LIMIT: usize = 1024;
-
 /// The operand stack.
 struct Stack {
     value: Vec<Value>,
     types: Vec<Type>,
+    size_limit: usize,
 }
 
 impl Stack {
-    /// Create a new empty operand stack.
-    fn new() -> Self {
+    /// Create a new empty operand stack holding at most `size_limit` values.
+    fn new(size_limit: usize) -> Self {
         Stack {
             value: vec![],
             types: vec![],
+            size_limit,
         }
     }
 
     /// Push a `Value` on the stack if the max stack size has not been reached. Abort execution
     /// otherwise.
     fn push(&mut self, value: Value) -> PartialVMResult<()> {
-        if self.value.len() < OPERAND_STACK_SIZE_LIMIT {
+        if self.value.len() < self.size_limit {
             self.value.push(value);
             Ok(())
         } else {
@@ -900,18 +1010,18 @@ impl Stack {
         Ok(args)
     }
 
-    fn last_n(&self, n: usize) -> PartialVMResult<impl ExactSizeIterator<Item = &Value>> {
+    fn last_n(&self, n: usize) -> PartialVMResult<&[Value]> {
         if self.value.len() < n {
             return Err(PartialVMError::new(StatusCode::EMPTY_VALUE_STACK)
                 .with_message("Failed to get last n arguments on the argument stack".to_string()));
         }
-        Ok(self.value[(self.value.len() - n)..].iter())
+        Ok(&self.value[(self.value.len() - n)..])
     }
 
     /// Push a `Value` on the stack if the max stack size has not been reached. Abort execution
     /// otherwise.
     fn push_ty(&mut self, ty: Type) -> PartialVMResult<()> {
-        if self.types.len() < OPERAND_STACK_SIZE_LIMIT {
+        if self.types.len() < self.size_limit {
             self.types.push(ty);
             Ok(())
         } else {
@@ -951,18 +1061,24 @@ impl Stack {
 
 /// A call stack.
 // #[derive(Debug)]
-struct CallStack(Vec<Frame>);
+struct CallStack {
+    frames: Vec<Frame>,
+    size_limit: usize,
+}
 
 impl CallStack {
-    /// Create a new empty call stack.
-    fn new() -> Self {
-        CallStack(vec![])
+    /// Create a new empty call stack holding at most `size_limit` frames.
+    fn new(size_limit: usize) -> Self {
+        CallStack {
+            frames: vec![],
+            size_limit,
+        }
     }
 
     /// Push a `Frame` on the call stack.
     fn push(&mut self, frame: Frame) -> ::std::result::Result<(), Frame> {
-        if self.0.len() < CALL_STACK_SIZE_LIMIT {
-            self.0.push(frame);
+        if self.frames.len() < self.size_limit {
+            self.frames.push(frame);
             Ok(())
         } else {
             Err(frame)
@@ -971,11 +1087,11 @@ impl CallStack {
 
     /// Pop a `Frame` off the call stack.
     fn pop(&mut self) -> Option<Frame> {
-        self.0.pop()
+        self.frames.pop()
     }
 
     fn current_location(&self) -> Location {
-        let location_opt = self.0.last().map(|frame| frame.location());
+        let location_opt = self.frames.last().map(|frame| frame.location());
         location_opt.unwrap_or(Location::Undefined)
     }
 }
@@ -1010,6 +1126,39 @@ fn check_ability(has_ability: bool) -> PartialVMResult<()> {
     }
 }
 
+/// The global resources of a data store, as read by a `Tracer`.
+struct DataStoreGlobals<'a, D> {
+    loader: &'a Loader,
+    data_store: &'a mut D,
+}
+
+impl<'a, D: DataStore> TracedGlobals for DataStoreGlobals<'a, D> {
+    fn resource(
+        &mut self,
+        address: AccountAddress,
+        ty: &StructTag,
+    ) -> PartialVMResult<Option<Value>> {
+        let ty = self
+            .loader
+            .load_type(&TypeTag::Struct(Box::new(ty.clone())), &*self.data_store)
+            .map_err(|err| err.to_partial())?;
+        let (gv, _) = self.data_store.load_resource(address, &ty)?;
+        if !gv.exists()? {
+            return Ok(None);
+        }
+        let resource = gv.borrow_global()?.value_as::<Reference>()?.read_ref()?;
+        Ok(Some(resource))
+    }
+}
+
+/// Converts type arguments to the type tags passed to a `Tracer`.
+fn type_tags(loader: &Loader, ty_args: &[Type]) -> PartialVMResult<Vec<TypeTag>> {
+    ty_args
+        .iter()
+        .map(|ty| loader.type_to_type_tag(ty))
+        .collect()
+}
+
 impl Frame {
     /// Execute a Move function until a return or a call opcode is found.
     fn execute_code(
@@ -1018,8 +1167,9 @@ impl Frame {
         interpreter: &mut Interpreter,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut Option<&mut dyn Tracer>,
     ) -> VMResult<ExitCode> {
-        self.execute_code_impl(resolver, interpreter, data_store, gas_meter)
+        self.execute_code_impl(resolver, interpreter, data_store, gas_meter, tracer)
             .map_err(|e| {
                 let e = if cfg!(feature = "testing") || cfg!(feature = "stacktrace") {
                     e.with_exec_state(interpreter.get_internal_state())
@@ -1138,7 +1288,17 @@ impl Frame {
             | Bytecode::VecPushBack(_)
             | Bytecode::VecPopBack(_)
             | Bytecode::VecUnpack(_, _)
-            | Bytecode::VecSwap(_) => (),
+            | Bytecode::VecSwap(_)
+            | Bytecode::PackVariant(_)
+            | Bytecode::PackVariantGeneric(_)
+            | Bytecode::UnpackVariant(_)
+            | Bytecode::UnpackVariantGeneric(_)
+            | Bytecode::TestVariant(_)
+            | Bytecode::TestVariantGeneric(_)
+            | Bytecode::ImmBorrowVariantField(_)
+            | Bytecode::MutBorrowVariantField(_)
+            | Bytecode::ImmBorrowVariantFieldGeneric(_)
+            | Bytecode::MutBorrowVariantFieldGeneric(_) => (),
         };
         Ok(())
     }
@@ -1333,6 +1493,110 @@ impl Frame {
                     interpreter.operand_stack.push_ty(ty.clone())?;
                 }
             }
+            Bytecode::PackVariant(_) | Bytecode::PackVariantGeneric(_) => {
+                let info = match instruction {
+                    Bytecode::PackVariant(sv_idx) => resolver.struct_variant_at(*sv_idx),
+                    Bytecode::PackVariantGeneric(si_idx) => {
+                        resolver.struct_variant_instantiation_at(*si_idx)
+                    }
+                    _ => unreachable!(),
+                };
+                let field_count = info.field_count;
+                let args_ty = resolver.struct_variant_fields(info, ty_args)?;
+                let output_ty = resolver.struct_variant_to_struct(info, ty_args)?;
+                let ability = resolver.loader().abilities(&output_ty)?;
+
+                // If the struct has a key ability, we expects all of its field to have store ability but not key ability.
+                let field_expected_abilities = if ability.has_key() {
+                    ability
+                        .remove(Ability::Key)
+                        .union(AbilitySet::singleton(Ability::Store))
+                } else {
+                    ability
+                };
+
+                if field_count as usize != args_ty.len() {
+                    return Err(
+                        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                            .with_message("Args count mismatch".to_string()),
+                    );
+                }
+
+                for (ty, expected_ty) in interpreter
+                    .operand_stack
+                    .popn_tys(field_count)?
+                    .into_iter()
+                    .zip(args_ty.iter())
+                {
+                    check_ability(
+                        field_expected_abilities.is_subset(resolver.loader().abilities(&ty)?),
+                    )?;
+                    ty.check_eq(expected_ty)?;
+                }
+
+                interpreter.operand_stack.push_ty(output_ty)?;
+            }
+            Bytecode::UnpackVariant(_) | Bytecode::UnpackVariantGeneric(_) => {
+                let info = match instruction {
+                    Bytecode::UnpackVariant(sv_idx) => resolver.struct_variant_at(*sv_idx),
+                    Bytecode::UnpackVariantGeneric(si_idx) => {
+                        resolver.struct_variant_instantiation_at(*si_idx)
+                    }
+                    _ => unreachable!(),
+                };
+                let struct_ty = interpreter.operand_stack.pop_ty()?;
+                struct_ty.check_eq(&resolver.struct_variant_to_struct(info, ty_args)?)?;
+                for ty in resolver.struct_variant_fields(info, ty_args)? {
+                    interpreter.operand_stack.push_ty(ty)?;
+                }
+            }
+            Bytecode::TestVariant(_) | Bytecode::TestVariantGeneric(_) => {
+                let info = match instruction {
+                    Bytecode::TestVariant(sv_idx) => resolver.struct_variant_at(*sv_idx),
+                    Bytecode::TestVariantGeneric(si_idx) => {
+                        resolver.struct_variant_instantiation_at(*si_idx)
+                    }
+                    _ => unreachable!(),
+                };
+                let expected_ty = resolver.struct_variant_to_struct(info, ty_args)?;
+                let top_ty = interpreter.operand_stack.pop_ty()?;
+                top_ty.check_ref_eq(&expected_ty)?;
+                interpreter.operand_stack.push_ty(Type::Bool)?;
+            }
+            Bytecode::ImmBorrowVariantField(_)
+            | Bytecode::MutBorrowVariantField(_)
+            | Bytecode::ImmBorrowVariantFieldGeneric(_)
+            | Bytecode::MutBorrowVariantFieldGeneric(_) => {
+                let (info, is_mut) = match instruction {
+                    Bytecode::ImmBorrowVariantField(fh_idx) => {
+                        (resolver.variant_field_at(*fh_idx), false)
+                    }
+                    Bytecode::MutBorrowVariantField(fh_idx) => {
+                        (resolver.variant_field_at(*fh_idx), true)
+                    }
+                    Bytecode::ImmBorrowVariantFieldGeneric(fi_idx) => {
+                        (resolver.variant_field_instantiation_at(*fi_idx), false)
+                    }
+                    Bytecode::MutBorrowVariantFieldGeneric(fi_idx) => {
+                        (resolver.variant_field_instantiation_at(*fi_idx), true)
+                    }
+                    _ => unreachable!(),
+                };
+                let expected_ty = resolver.variant_field_to_struct(info, ty_args)?;
+                let top_ty = interpreter.operand_stack.pop_ty()?;
+                let field_ty = resolver.variant_field_type(info, ty_args)?;
+                if is_mut {
+                    top_ty.check_eq(&Type::MutableReference(Box::new(expected_ty)))?;
+                    interpreter
+                        .operand_stack
+                        .push_ty(Type::MutableReference(Box::new(field_ty)))?;
+                } else {
+                    top_ty.check_ref_eq(&expected_ty)?;
+                    interpreter
+                        .operand_stack
+                        .push_ty(Type::Reference(Box::new(field_ty)))?;
+                }
+            }
             Bytecode::ReadRef => {
                 let ref_ty = interpreter.operand_stack.pop_ty()?;
                 match ref_ty {
@@ -1637,6 +1901,7 @@ impl Frame {
         interpreter: &mut Interpreter,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut Option<&mut dyn Tracer>,
     ) -> PartialVMResult<ExitCode> {
         use SimpleInstruction as S;
 
@@ -1660,6 +1925,19 @@ impl Frame {
                     resolver,
                     interpreter
                 );
+                if let Some(tracer) = tracer {
+                    tracer.instruction(
+                        TracedFunction(&self.function),
+                        self.pc,
+                        instruction,
+                        &interpreter.operand_stack.value,
+                        &self.locals,
+                        &mut DataStoreGlobals {
+                            loader: resolver.loader(),
+                            data_store: &mut *data_store,
+                        },
+                    );
+                }
 
                 fail_point!("move_vm::interpreter_loop", |_| {
                     Err(
@@ -1829,7 +2107,10 @@ impl Frame {
                         let field_count = resolver.field_count(*sd_idx);
                         gas_meter.charge_pack(
                             false,
-                            interpreter.operand_stack.last_n(field_count as usize)?,
+                            interpreter
+                                .operand_stack
+                                .last_n(field_count as usize)?
+                                .iter(),
                         )?;
                         let args = interpreter.operand_stack.popn(field_count)?;
                         interpreter
@@ -1840,7 +2121,10 @@ impl Frame {
                         let field_count = resolver.field_instantiation_count(*si_idx);
                         gas_meter.charge_pack(
                             true,
-                            interpreter.operand_stack.last_n(field_count as usize)?,
+                            interpreter
+                                .operand_stack
+                                .last_n(field_count as usize)?
+                                .iter(),
                         )?;
                         let args = interpreter.operand_stack.popn(field_count)?;
                         interpreter
@@ -1868,6 +2152,98 @@ impl Frame {
                             interpreter.operand_stack.push(value)?;
                         }
                     }
+                    Bytecode::PackVariant(idx) => {
+                        let info = resolver.struct_variant_at(*idx);
+                        gas_meter.charge_pack(
+                            false,
+                            interpreter
+                                .operand_stack
+                                .last_n(info.field_count as usize)?
+                                .iter(),
+                        )?;
+                        let args = interpreter.operand_stack.popn(info.field_count)?;
+                        interpreter
+                            .operand_stack
+                            .push(Value::struct_(Struct::pack_variant(info.variant, args)))?;
+                    }
+                    Bytecode::PackVariantGeneric(si_idx) => {
+                        let info = resolver.struct_variant_instantiation_at(*si_idx);
+                        gas_meter.charge_pack(
+                            true,
+                            interpreter
+                                .operand_stack
+                                .last_n(info.field_count as usize)?
+                                .iter(),
+                        )?;
+                        let args = interpreter.operand_stack.popn(info.field_count)?;
+                        interpreter
+                            .operand_stack
+                            .push(Value::struct_(Struct::pack_variant(info.variant, args)))?;
+                    }
+                    Bytecode::UnpackVariant(_) | Bytecode::UnpackVariantGeneric(_) => {
+                        let (info, is_generic) = match instruction {
+                            Bytecode::UnpackVariantGeneric(si_idx) => {
+                                (resolver.struct_variant_instantiation_at(*si_idx), true)
+                            }
+                            Bytecode::UnpackVariant(sv_idx) => {
+                                (resolver.struct_variant_at(*sv_idx), false)
+                            }
+                            _ => unreachable!(),
+                        };
+                        let struct_ = interpreter.operand_stack.pop_as::<Struct>()?;
+
+                        gas_meter.charge_unpack(is_generic, struct_.field_views())?;
+
+                        for value in struct_.unpack_variant(info.variant)? {
+                            interpreter.operand_stack.push(value)?;
+                        }
+                    }
+                    Bytecode::TestVariant(_) | Bytecode::TestVariantGeneric(_) => {
+                        let (info, instr) = match instruction {
+                            Bytecode::TestVariantGeneric(si_idx) => (
+                                resolver.struct_variant_instantiation_at(*si_idx),
+                                S::TestVariantGeneric,
+                            ),
+                            Bytecode::TestVariant(sv_idx) => {
+                                (resolver.struct_variant_at(*sv_idx), S::TestVariant)
+                            }
+                            _ => unreachable!(),
+                        };
+                        gas_meter.charge_simple_instr(instr)?;
+
+                        let reference = interpreter.operand_stack.pop_as::<StructRef>()?;
+                        interpreter
+                            .operand_stack
+                            .push(reference.test_variant(info.variant)?)?;
+                    }
+                    Bytecode::ImmBorrowVariantField(_)
+                    | Bytecode::MutBorrowVariantField(_)
+                    | Bytecode::ImmBorrowVariantFieldGeneric(_)
+                    | Bytecode::MutBorrowVariantFieldGeneric(_) => {
+                        let (info, instr) = match instruction {
+                            Bytecode::ImmBorrowVariantField(fh_idx) => {
+                                (resolver.variant_field_at(*fh_idx), S::ImmBorrowVariantField)
+                            }
+                            Bytecode::MutBorrowVariantField(fh_idx) => {
+                                (resolver.variant_field_at(*fh_idx), S::MutBorrowVariantField)
+                            }
+                            Bytecode::ImmBorrowVariantFieldGeneric(fi_idx) => (
+                                resolver.variant_field_instantiation_at(*fi_idx),
+                                S::ImmBorrowVariantFieldGeneric,
+                            ),
+                            Bytecode::MutBorrowVariantFieldGeneric(fi_idx) => (
+                                resolver.variant_field_instantiation_at(*fi_idx),
+                                S::MutBorrowVariantFieldGeneric,
+                            ),
+                            _ => unreachable!(),
+                        };
+                        gas_meter.charge_simple_instr(instr)?;
+
+                        let reference = interpreter.operand_stack.pop_as::<StructRef>()?;
+                        let field_ref =
+                            reference.borrow_variant_field(info.variant, info.offset)?;
+                        interpreter.operand_stack.push(field_ref)?;
+                    }
                     Bytecode::ReadRef => {
                         let reference = interpreter.operand_stack.pop_as::<Reference>()?;
                         gas_meter.charge_read_ref(reference.value_view())?;
@@ -2033,6 +2409,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                         )?;
@@ -2048,6 +2425,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                         )?;
@@ -2060,6 +2438,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                         )?;
@@ -2072,6 +2451,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                         )?;
@@ -2084,6 +2464,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                         )?;
@@ -2096,6 +2477,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                         )?;
@@ -2115,6 +2497,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                             resource,
@@ -2134,6 +2517,7 @@ impl Frame {
                             resolver.loader(),
                             gas_meter,
                             data_store,
+                            tracer,
                             addr,
                             &ty,
                             resource,
@@ -2156,8 +2540,9 @@ impl Frame {
                         let ty = resolver.instantiate_single_type(*si, self.ty_args())?;
                         gas_meter.charge_vec_pack(
                             make_ty!(&ty),
-                            interpreter.operand_stack.last_n(*num as usize)?,
+                            interpreter.operand_stack.last_n(*num as usize)?.iter(),
                         )?;
+                        interpreter.check_vector_length(*num)?;
                         let elements = interpreter.operand_stack.popn(*num as u16)?;
                         let value = Vector::pack(&ty, elements)?;
                         interpreter.operand_stack.push(value)?;
@@ -2193,6 +2578,10 @@ impl Frame {
                         let vec_ref = interpreter.operand_stack.pop_as::<VectorRef>()?;
                         let ty = &resolver.instantiate_single_type(*si, self.ty_args())?;
                         gas_meter.charge_vec_push_back(make_ty!(ty), &elem)?;
+                        if interpreter.max_vector_length.is_some() {
+                            let len = vec_ref.len(ty)?.value_as::<u64>()?;
+                            interpreter.check_vector_length(len + 1)?;
+                        }
                         vec_ref.push_back(elem, ty)?;
                     }
                     Bytecode::VecPopBack(si) => {
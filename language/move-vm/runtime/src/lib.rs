@@ -11,6 +11,7 @@
 //! soon.
 
 pub mod data_cache;
+pub mod gas_profiler;
 mod interpreter;
 mod loader;
 pub mod logging;
@@ -22,6 +23,7 @@ pub mod session;
 #[macro_use]
 mod tracing;
 pub mod config;
+pub mod tracer;
 
 // Only include debugging functionality in debug builds
 #[cfg(any(debug_assertions, feature = "debugging"))]
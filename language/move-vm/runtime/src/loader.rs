@@ -14,10 +14,12 @@ use move_binary_format::{
     errors::{verification_error, Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
         AbilitySet, Bytecode, CompiledModule, CompiledScript, Constant, ConstantPoolIndex,
-        FieldHandleIndex, FieldInstantiationIndex, FunctionDefinition, FunctionDefinitionIndex,
-        FunctionHandleIndex, FunctionInstantiationIndex, Signature, SignatureIndex, SignatureToken,
-        StructDefInstantiationIndex, StructDefinition, StructDefinitionIndex,
-        StructFieldInformation, TableIndex, Visibility,
+        FieldDefinition, FieldHandleIndex, FieldInstantiationIndex, FunctionDefinition,
+        FunctionDefinitionIndex, FunctionHandleIndex, FunctionInstantiationIndex, Signature,
+        SignatureIndex, SignatureToken, StructDefInstantiationIndex, StructDefinition,
+        StructDefinitionIndex, StructFieldInformation, StructVariantHandleIndex,
+        StructVariantInstantiationIndex, TableIndex, VariantFieldHandleIndex,
+        VariantFieldInstantiationIndex, Visibility,
     },
     IndexKind,
 };
@@ -26,12 +28,12 @@ use move_core_types::{
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, StructTag, TypeTag},
     metadata::Metadata,
-    value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout},
+    value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout, MoveVariantLayout},
     vm_status::StatusCode,
 };
 use move_vm_types::{
     data_store::DataStore,
-    loaded_data::runtime_types::{CachedStructIndex, StructType, Type},
+    loaded_data::runtime_types::{CachedStructIndex, StructType, StructVariantType, Type},
 };
 use parking_lot::RwLock;
 use sha3::{Digest, Sha3_256};
@@ -239,12 +241,26 @@ impl ModuleCache {
         idx: StructDefinitionIndex,
     ) -> StructType {
         let struct_handle = module.struct_handle_at(struct_def.struct_handle);
-        let field_names = match &struct_def.field_information {
-            StructFieldInformation::Native => vec![],
-            StructFieldInformation::Declared(field_info) => field_info
+        let field_names_of = |fields: &[FieldDefinition]| -> Vec<Identifier> {
+            fields
                 .iter()
                 .map(|f| module.identifier_at(f.name).to_owned())
-                .collect(),
+                .collect()
+        };
+        let (field_names, variants) = match &struct_def.field_information {
+            StructFieldInformation::Native => (vec![], vec![]),
+            StructFieldInformation::Declared(field_info) => (field_names_of(field_info), vec![]),
+            StructFieldInformation::DeclaredVariants(variants) => (
+                vec![],
+                variants
+                    .iter()
+                    .map(|v| StructVariantType {
+                        name: module.identifier_at(v.name).to_owned(),
+                        fields: vec![],
+                        field_names: field_names_of(&v.fields),
+                    })
+                    .collect(),
+            ),
         };
         let abilities = struct_handle.abilities;
         let name = module.identifier_at(struct_handle.name).to_owned();
@@ -258,6 +274,7 @@ impl ModuleCache {
             name,
             module,
             struct_def: idx,
+            variants,
         }
     }
 
@@ -266,26 +283,40 @@ impl ModuleCache {
         module: &CompiledModule,
         starting_idx: usize,
     ) -> PartialVMResult<()> {
-        let mut field_types = vec![];
-        for struct_def in module.struct_defs() {
-            let fields = match &struct_def.field_information {
-                StructFieldInformation::Native => unreachable!("native structs have been removed"),
-                StructFieldInformation::Declared(fields) => fields,
-            };
-
+        let make_field_types = |fields: &[FieldDefinition]| -> PartialVMResult<Vec<Type>> {
             let mut field_tys = vec![];
             for field in fields {
                 let ty = self.make_type_while_loading(module, &field.signature.0)?;
                 debug_assert!(field_tys.len() < usize::max_value());
                 field_tys.push(ty);
             }
-
-            field_types.push(field_tys);
+            Ok(field_tys)
+        };
+        let mut field_types = vec![];
+        for struct_def in module.struct_defs() {
+            match &struct_def.field_information {
+                StructFieldInformation::Native => unreachable!("native structs have been removed"),
+                StructFieldInformation::Declared(fields) => {
+                    field_types.push((make_field_types(fields)?, vec![]))
+                }
+                StructFieldInformation::DeclaredVariants(variants) => {
+                    let mut variant_tys = vec![];
+                    for variant in variants {
+                        variant_tys.push(make_field_types(&variant.fields)?);
+                    }
+                    field_types.push((vec![], variant_tys))
+                }
+            }
         }
         let mut struct_idx = starting_idx;
-        for fields in field_types {
+        for (fields, variant_fields) in field_types {
             match Arc::get_mut(&mut self.structs[struct_idx]) {
-                Some(struct_type) => struct_type.fields = fields,
+                Some(struct_type) => {
+                    struct_type.fields = fields;
+                    for (variant, tys) in struct_type.variants.iter_mut().zip(variant_fields) {
+                        variant.fields = tys;
+                    }
+                }
                 None => {
                     // we have pending references to the `Arc` which is impossible,
                     // given the code that adds the `Arc` is above and no reference to
@@ -295,6 +326,9 @@ impl ModuleCache {
                     error!("Arc<StructType> cannot have any live reference while publishing");
                     let mut struct_type = (*self.structs[struct_idx]).clone();
                     struct_type.fields = fields;
+                    for (variant, tys) in struct_type.variants.iter_mut().zip(variant_fields) {
+                        variant.fields = tys;
+                    }
                     self.structs[struct_idx] = Arc::new(struct_type);
                 }
             }
@@ -1276,20 +1310,34 @@ impl Loader {
     fn subst(&self, ty: &Type, ty_args: &[Type]) -> PartialVMResult<Type> {
         // Before instantiating the type, count the # of nodes of all type arguments plus
         // existing type instantiation.
-        // If that number is larger than MAX_TYPE_INSTANTIATION_NODES, refuse to construct this type.
+        // If that number is larger than the `max_type_instantiation_nodes` configured for the VM,
+        // or than MAX_TYPE_INSTANTIATION_NODES by default, refuse to construct this type.
         // This prevents constructing larger and lager types via struct instantiation.
         if let Type::StructInstantiation(_, struct_inst) = ty {
             let mut sum_nodes: usize = 1;
             for ty in ty_args.iter().chain(struct_inst.iter()) {
                 sum_nodes = sum_nodes.saturating_add(self.count_type_nodes(ty));
-                if sum_nodes > MAX_TYPE_INSTANTIATION_NODES {
-                    return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
-                }
+                self.check_type_instantiation_nodes(sum_nodes)?;
             }
         }
         ty.subst(ty_args)
     }
 
+    // Fails if an instantiation of `sum_nodes` type nodes is larger than the
+    // `max_type_instantiation_nodes` configured for the VM, or than MAX_TYPE_INSTANTIATION_NODES
+    // by default.
+    fn check_type_instantiation_nodes(&self, sum_nodes: usize) -> PartialVMResult<()> {
+        match self.vm_config.max_type_instantiation_nodes {
+            Some(max_nodes) if sum_nodes > max_nodes => {
+                Err(PartialVMError::new(StatusCode::VM_MAX_TYPE_NODES_REACHED))
+            }
+            None if sum_nodes > MAX_TYPE_INSTANTIATION_NODES => {
+                Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES))
+            }
+            _ => Ok(()),
+        }
+    }
+
     // Verify the kind (constraints) of an instantiation.
     // Both function and script invocation use this function to verify correctness
     // of type arguments provided
@@ -1466,13 +1514,11 @@ impl<'a> Resolver<'a> {
             instantiation.push(self.subst(ty, type_params)?);
         }
         // Check if the function instantiation over all generics is larger
-        // than MAX_TYPE_INSTANTIATION_NODES.
+        // than `max_type_instantiation_nodes`, or than MAX_TYPE_INSTANTIATION_NODES by default.
         let mut sum_nodes: usize = 1;
         for ty in type_params.iter().chain(instantiation.iter()) {
             sum_nodes = sum_nodes.saturating_add(self.loader.count_type_nodes(ty));
-            if sum_nodes > MAX_TYPE_INSTANTIATION_NODES {
-                return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
-            }
+            self.loader.check_type_instantiation_nodes(sum_nodes)?;
         }
         Ok(instantiation)
     }
@@ -1510,14 +1556,13 @@ impl<'a> Resolver<'a> {
 
         // Before instantiating the type, count the # of nodes of all type arguments plus
         // existing type instantiation.
-        // If that number is larger than MAX_TYPE_INSTANTIATION_NODES, refuse to construct this type.
+        // If that number is larger than the `max_type_instantiation_nodes` configured for the VM,
+        // or than MAX_TYPE_INSTANTIATION_NODES by default, refuse to construct this type.
         // This prevents constructing larger and lager types via struct instantiation.
         let mut sum_nodes: usize = 1;
         for ty in ty_args.iter().chain(struct_inst.instantiation.iter()) {
             sum_nodes = sum_nodes.saturating_add(self.loader.count_type_nodes(ty));
-            if sum_nodes > MAX_TYPE_INSTANTIATION_NODES {
-                return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
-            }
+            self.loader.check_type_instantiation_nodes(sum_nodes)?;
         }
 
         Ok(Type::StructInstantiation(
@@ -1695,6 +1740,129 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    //
+    // Variants resolution
+    //
+
+    pub(crate) fn struct_variant_at(&self, idx: StructVariantHandleIndex) -> &StructVariantInfo {
+        match &self.binary {
+            BinaryType::Module(module) => module.struct_variant_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have type instructions"),
+        }
+    }
+
+    pub(crate) fn struct_variant_instantiation_at(
+        &self,
+        idx: StructVariantInstantiationIndex,
+    ) -> &StructVariantInfo {
+        match &self.binary {
+            BinaryType::Module(module) => module.struct_variant_instantiation_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have type instructions"),
+        }
+    }
+
+    pub(crate) fn variant_field_at(&self, idx: VariantFieldHandleIndex) -> &VariantFieldInfo {
+        match &self.binary {
+            BinaryType::Module(module) => module.variant_field_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have field instructions"),
+        }
+    }
+
+    pub(crate) fn variant_field_instantiation_at(
+        &self,
+        idx: VariantFieldInstantiationIndex,
+    ) -> &VariantFieldInfo {
+        match &self.binary {
+            BinaryType::Module(module) => module.variant_field_instantiation_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have field instructions"),
+        }
+    }
+
+    // Materializes the struct type owning a variant, or a variant field, under `ty_args`.
+    fn variant_owner_type(
+        &self,
+        owner: CachedStructIndex,
+        instantiation: &[Type],
+        ty_args: &[Type],
+    ) -> PartialVMResult<Type> {
+        if instantiation.is_empty() {
+            return Ok(Type::Struct(owner));
+        }
+        let mut sum_nodes: usize = 1;
+        for ty in ty_args.iter().chain(instantiation.iter()) {
+            sum_nodes = sum_nodes.saturating_add(self.loader.count_type_nodes(ty));
+            self.loader.check_type_instantiation_nodes(sum_nodes)?;
+        }
+        Ok(Type::StructInstantiation(
+            owner,
+            instantiation
+                .iter()
+                .map(|ty| self.subst(ty, ty_args))
+                .collect::<PartialVMResult<_>>()?,
+        ))
+    }
+
+    // Returns the declared field types of `variant` of `owner` instantiated under `ty_args`.
+    fn variant_field_types(
+        &self,
+        owner: CachedStructIndex,
+        variant: u16,
+        instantiation: &[Type],
+        ty_args: &[Type],
+    ) -> PartialVMResult<Vec<Type>> {
+        let struct_type = self.loader.get_struct_type(owner).ok_or_else(|| {
+            PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                .with_message("Struct Definition not resolved".to_string())
+        })?;
+        let instantiation_types = instantiation
+            .iter()
+            .map(|inst_ty| inst_ty.subst(ty_args))
+            .collect::<PartialVMResult<Vec<_>>>()?;
+        struct_type
+            .fields_of(Some(variant))?
+            .iter()
+            .map(|ty| ty.subst(&instantiation_types))
+            .collect::<PartialVMResult<Vec<_>>>()
+    }
+
+    pub(crate) fn struct_variant_to_struct(
+        &self,
+        info: &StructVariantInfo,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Type> {
+        self.variant_owner_type(info.def, &info.instantiation, ty_args)
+    }
+
+    pub(crate) fn struct_variant_fields(
+        &self,
+        info: &StructVariantInfo,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Vec<Type>> {
+        self.variant_field_types(info.def, info.variant, &info.instantiation, ty_args)
+    }
+
+    pub(crate) fn variant_field_to_struct(
+        &self,
+        info: &VariantFieldInfo,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Type> {
+        self.variant_owner_type(info.owner, &info.instantiation, ty_args)
+    }
+
+    pub(crate) fn variant_field_type(
+        &self,
+        info: &VariantFieldInfo,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Type> {
+        self.variant_field_types(info.owner, info.variant, &info.instantiation, ty_args)?
+            .into_iter()
+            .nth(info.offset)
+            .ok_or_else(|| {
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                    .with_message("variant field offset out of bounds".to_string())
+            })
+    }
+
     pub(crate) fn type_to_type_layout(&self, ty: &Type) -> PartialVMResult<MoveTypeLayout> {
         self.loader.type_to_type_layout(ty)
     }
@@ -1751,6 +1919,14 @@ pub(crate) struct Module {
     // materialized instantiations, whether partial or not
     field_instantiations: Vec<FieldInstantiation>,
 
+    // variants of structs declared with variants, and their materialized instantiations
+    struct_variant_infos: Vec<StructVariantInfo>,
+    struct_variant_instantiation_infos: Vec<StructVariantInfo>,
+
+    // fields of variants, and their materialized instantiations
+    variant_field_infos: Vec<VariantFieldInfo>,
+    variant_field_instantiation_infos: Vec<VariantFieldInfo>,
+
     // function name to index into the Loader function list.
     // This allows a direct access from function name to `Function`
     function_map: HashMap<Identifier, usize>,
@@ -1779,6 +1955,10 @@ impl Module {
         let mut function_instantiations = vec![];
         let mut field_handles = vec![];
         let mut field_instantiations: Vec<FieldInstantiation> = vec![];
+        let mut struct_variant_infos = vec![];
+        let mut struct_variant_instantiation_infos = vec![];
+        let mut variant_field_infos = vec![];
+        let mut variant_field_instantiation_infos = vec![];
         let mut function_map = HashMap::new();
         let mut struct_map = HashMap::new();
         let mut single_signature_token_map = BTreeMap::new();
@@ -1936,6 +2116,57 @@ impl Module {
                 });
             }
 
+            let variant_field_count = |def: StructDefinitionIndex, variant: u16| {
+                let struct_type = &cache.structs[structs[def.0 as usize].idx.0];
+                struct_type.variants[variant as usize].fields.len() as u16
+            };
+
+            for handle in module.struct_variant_handles() {
+                struct_variant_infos.push(StructVariantInfo {
+                    field_count: variant_field_count(handle.struct_index, handle.variant),
+                    variant: handle.variant,
+                    def: structs[handle.struct_index.0 as usize].idx,
+                    instantiation: vec![],
+                });
+            }
+
+            for inst in module.struct_variant_instantiations() {
+                let info = &struct_variant_infos[inst.handle.0 as usize];
+                let mut instantiation = vec![];
+                for ty in &module.signature_at(inst.type_parameters).0 {
+                    instantiation.push(cache.make_type_while_loading(&module, ty)?);
+                }
+                struct_variant_instantiation_infos.push(StructVariantInfo {
+                    field_count: info.field_count,
+                    variant: info.variant,
+                    def: info.def,
+                    instantiation,
+                });
+            }
+
+            for handle in module.variant_field_handles() {
+                variant_field_infos.push(VariantFieldInfo {
+                    offset: handle.field as usize,
+                    variant: handle.variant,
+                    owner: structs[handle.owner.0 as usize].idx,
+                    instantiation: vec![],
+                });
+            }
+
+            for inst in module.variant_field_instantiations() {
+                let info = &variant_field_infos[inst.handle.0 as usize];
+                let mut instantiation = vec![];
+                for ty in &module.signature_at(inst.type_parameters).0 {
+                    instantiation.push(cache.make_type_while_loading(&module, ty)?);
+                }
+                variant_field_instantiation_infos.push(VariantFieldInfo {
+                    offset: info.offset,
+                    variant: info.variant,
+                    owner: info.owner,
+                    instantiation,
+                });
+            }
+
             Ok(())
         };
 
@@ -1950,6 +2181,10 @@ impl Module {
                 function_instantiations,
                 field_handles,
                 field_instantiations,
+                struct_variant_infos,
+                struct_variant_instantiation_infos,
+                variant_field_infos,
+                variant_field_instantiation_infos,
                 function_map,
                 struct_map,
                 single_signature_token_map,
@@ -1998,6 +2233,28 @@ impl Module {
         self.field_instantiations[idx.0 as usize].offset
     }
 
+    fn struct_variant_at(&self, idx: StructVariantHandleIndex) -> &StructVariantInfo {
+        &self.struct_variant_infos[idx.0 as usize]
+    }
+
+    fn struct_variant_instantiation_at(
+        &self,
+        idx: StructVariantInstantiationIndex,
+    ) -> &StructVariantInfo {
+        &self.struct_variant_instantiation_infos[idx.0 as usize]
+    }
+
+    fn variant_field_at(&self, idx: VariantFieldHandleIndex) -> &VariantFieldInfo {
+        &self.variant_field_infos[idx.0 as usize]
+    }
+
+    fn variant_field_instantiation_at(
+        &self,
+        idx: VariantFieldInstantiationIndex,
+    ) -> &VariantFieldInfo {
+        &self.variant_field_instantiation_infos[idx.0 as usize]
+    }
+
     fn single_type_at(&self, idx: SignatureIndex) -> &Type {
         self.single_signature_token_map.get(&idx).unwrap()
     }
@@ -2456,6 +2713,28 @@ struct FieldInstantiation {
     instantiation: Vec<Type>,
 }
 
+// A variant of a struct declared with variants, possibly instantiated. The instantiation is
+// empty for the non generic form.
+#[derive(Debug)]
+pub(crate) struct StructVariantInfo {
+    pub(crate) field_count: u16,
+    pub(crate) variant: u16,
+    // `ModuelCache::structs` global table index. It is the generic type.
+    def: CachedStructIndex,
+    instantiation: Vec<Type>,
+}
+
+// A field of a variant, possibly instantiated. The instantiation is empty for the non generic
+// form.
+#[derive(Debug)]
+pub(crate) struct VariantFieldInfo {
+    pub(crate) offset: usize,
+    pub(crate) variant: u16,
+    // `ModuelCache::structs` global table index. It is the generic type.
+    owner: CachedStructIndex,
+    instantiation: Vec<Type>,
+}
+
 //
 // Cache for data associated to a Struct, used for de/serialization and more
 //
@@ -2492,18 +2771,29 @@ impl TypeCache {
     }
 }
 
-/// Maximal depth of a value in terms of type depth.
-const VALUE_DEPTH_MAX: usize = 128;
+/// Maximal nodes which are all allowed when instantiating a generic type, unless configured for
+/// the VM. This does not include field types of structs.
+const MAX_TYPE_INSTANTIATION_NODES: usize = 128;
 
-/// Maximal nodes which are allowed when converting to layout. This includes the the types of
-/// fields for struct types.
+/// Maximal nodes which are allowed when converting to layout, unless configured for the VM. This
+/// includes the types of fields for struct types.
 const MAX_TYPE_TO_LAYOUT_NODES: usize = 256;
 
-/// Maximal nodes which are all allowed when instantiating a generic type. This does not include
-/// field types of structs.
-const MAX_TYPE_INSTANTIATION_NODES: usize = 128;
-
 impl Loader {
+    // Fails if a layout of `count` type nodes is larger than the `max_type_to_layout_nodes`
+    // configured for the VM, or than MAX_TYPE_TO_LAYOUT_NODES by default.
+    fn check_type_to_layout_nodes(&self, count: usize) -> PartialVMResult<()> {
+        match self.vm_config.max_type_to_layout_nodes {
+            Some(max_nodes) if count > max_nodes => Err(PartialVMError::new(
+                StatusCode::VM_MAX_TYPE_LAYOUT_NODES_REACHED,
+            )),
+            None if count > MAX_TYPE_TO_LAYOUT_NODES => {
+                Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES))
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn struct_gidx_to_type_tag(
         &self,
         gidx: CachedStructIndex,
@@ -2609,19 +2899,28 @@ impl Loader {
 
         let count_before = *count;
         let struct_type = self.module_cache.read().struct_at(gidx);
-        let field_tys = struct_type
-            .fields
-            .iter()
-            .map(|ty| self.subst(ty, ty_args))
-            .collect::<PartialVMResult<Vec<_>>>()?;
-        let field_layouts = field_tys
-            .iter()
-            .map(|ty| self.type_to_type_layout_impl(ty, count, depth + 1))
-            .collect::<PartialVMResult<Vec<_>>>()?;
+        let mut fields_to_layouts = |fields: &[Type]| {
+            fields
+                .iter()
+                .map(|ty| {
+                    let ty = self.subst(ty, ty_args)?;
+                    self.type_to_type_layout_impl(&ty, count, depth + 1)
+                })
+                .collect::<PartialVMResult<Vec<_>>>()
+        };
+        let struct_layout = if struct_type.has_variants() {
+            MoveStructLayout::RuntimeVariants(
+                struct_type
+                    .variants
+                    .iter()
+                    .map(|variant| fields_to_layouts(&variant.fields))
+                    .collect::<PartialVMResult<Vec<_>>>()?,
+            )
+        } else {
+            MoveStructLayout::new(fields_to_layouts(&struct_type.fields)?)
+        };
         let field_node_count = *count - count_before;
 
-        let struct_layout = MoveStructLayout::new(field_layouts);
-
         let mut cache = self.type_cache.write();
         let info = cache
             .structs
@@ -2641,10 +2940,8 @@ impl Loader {
         count: &mut usize,
         depth: usize,
     ) -> PartialVMResult<MoveTypeLayout> {
-        if *count > MAX_TYPE_TO_LAYOUT_NODES {
-            return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
-        }
-        if depth > VALUE_DEPTH_MAX {
+        self.check_type_to_layout_nodes(*count)?;
+        if depth > self.vm_config.max_value_depth {
             return Err(PartialVMError::new(StatusCode::VM_MAX_VALUE_DEPTH_REACHED));
         }
         Ok(match ty {
@@ -2739,18 +3036,37 @@ impl Loader {
             );
         }
         let count_before = *count;
-        let struct_tag = self.struct_gidx_to_type_tag(gidx, ty_args)?;
-        let field_layouts = struct_type
-            .field_names
-            .iter()
-            .zip(&struct_type.fields)
-            .map(|(n, ty)| {
-                let ty = self.subst(ty, ty_args)?;
-                let l = self.type_to_fully_annotated_layout_impl(&ty, count, depth + 1)?;
-                Ok(MoveFieldLayout::new(n.clone(), l))
-            })
-            .collect::<PartialVMResult<Vec<_>>>()?;
-        let struct_layout = MoveStructLayout::with_types(struct_tag, field_layouts);
+        let mut fields_to_layouts = |names: &[Identifier], fields: &[Type]| {
+            names
+                .iter()
+                .zip(fields)
+                .map(|(n, ty)| {
+                    let ty = self.subst(ty, ty_args)?;
+                    let l = self.type_to_fully_annotated_layout_impl(&ty, count, depth + 1)?;
+                    Ok(MoveFieldLayout::new(n.clone(), l))
+                })
+                .collect::<PartialVMResult<Vec<_>>>()
+        };
+        let struct_layout = if struct_type.has_variants() {
+            MoveStructLayout::WithVariants(
+                struct_type
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        Ok(MoveVariantLayout::new(
+                            variant.name.clone(),
+                            fields_to_layouts(&variant.field_names, &variant.fields)?,
+                        ))
+                    })
+                    .collect::<PartialVMResult<Vec<_>>>()?,
+            )
+        } else {
+            let struct_tag = self.struct_gidx_to_type_tag(gidx, ty_args)?;
+            MoveStructLayout::with_types(
+                struct_tag,
+                fields_to_layouts(&struct_type.field_names, &struct_type.fields)?,
+            )
+        };
         let field_node_count = *count - count_before;
 
         let mut cache = self.type_cache.write();
@@ -2772,10 +3088,8 @@ impl Loader {
         count: &mut usize,
         depth: usize,
     ) -> PartialVMResult<MoveTypeLayout> {
-        if *count > MAX_TYPE_TO_LAYOUT_NODES {
-            return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
-        }
-        if depth > VALUE_DEPTH_MAX {
+        self.check_type_to_layout_nodes(*count)?;
+        if depth > self.vm_config.max_value_depth {
             return Err(PartialVMError::new(StatusCode::VM_MAX_VALUE_DEPTH_REACHED));
         }
         Ok(match ty {
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use better_any::{Tid, TidAble, TidExt};
+use move_binary_format::errors::PartialVMResult;
 use std::{any::TypeId, collections::HashMap};
 
 /// A data type to represent a heterogeneous collection of extensions which are available to
@@ -16,6 +17,41 @@ use std::{any::TypeId, collections::HashMap};
 #[derive(Default)]
 pub struct NativeContextExtensions<'a> {
     map: HashMap<TypeId, Box<dyn Tid<'a>>>,
+    savepoint_handlers: HashMap<TypeId, SavepointHandler<'a>>,
+}
+
+/// An extension with state which is rolled back together with the changes of a session. See
+/// `Session::savepoint`.
+pub trait SavepointExtension {
+    /// Creates a savepoint, which the state can be rolled back to. Savepoints are nested.
+    fn savepoint(&mut self) -> PartialVMResult<()>;
+
+    /// Discards the changes to the state since the latest savepoint, and removes it.
+    fn rollback_to_savepoint(&mut self) -> PartialVMResult<()>;
+
+    /// Keeps the changes to the state since the latest savepoint, and removes it.
+    fn commit_savepoint(&mut self) -> PartialVMResult<()>;
+}
+
+#[derive(Clone, Copy)]
+enum SavepointOperation {
+    Create,
+    Rollback,
+    Commit,
+}
+
+type SavepointHandler<'a> = fn(&mut dyn Tid<'a>, SavepointOperation) -> PartialVMResult<()>;
+
+fn handle_savepoint<'a, T: TidAble<'a> + SavepointExtension>(
+    ext: &mut dyn Tid<'a>,
+    operation: SavepointOperation,
+) -> PartialVMResult<()> {
+    let ext = ext.downcast_mut::<T>().unwrap();
+    match operation {
+        SavepointOperation::Create => ext.savepoint(),
+        SavepointOperation::Rollback => ext.rollback_to_savepoint(),
+        SavepointOperation::Commit => ext.commit_savepoint(),
+    }
 }
 
 impl<'a> NativeContextExtensions<'a> {
@@ -26,6 +62,37 @@ impl<'a> NativeContextExtensions<'a> {
         )
     }
 
+    /// Adds an extension whose state is rolled back with the changes of the session. It must be
+    /// added before the savepoints of the session are created.
+    pub fn add_with_savepoints<T: TidAble<'a> + SavepointExtension>(&mut self, ext: T) {
+        self.add(ext);
+        self.savepoint_handlers
+            .insert(T::id(), handle_savepoint::<T>);
+    }
+
+    pub(crate) fn savepoint(&mut self) -> PartialVMResult<()> {
+        self.for_each_savepoint_extension(SavepointOperation::Create)
+    }
+
+    pub(crate) fn rollback_to_savepoint(&mut self) -> PartialVMResult<()> {
+        self.for_each_savepoint_extension(SavepointOperation::Rollback)
+    }
+
+    pub(crate) fn commit_savepoint(&mut self) -> PartialVMResult<()> {
+        self.for_each_savepoint_extension(SavepointOperation::Commit)
+    }
+
+    fn for_each_savepoint_extension(
+        &mut self,
+        operation: SavepointOperation,
+    ) -> PartialVMResult<()> {
+        for (id, handler) in &self.savepoint_handlers {
+            let ext = self.map.get_mut(id).expect("extension unknown");
+            handler(ext.as_mut(), operation)?;
+        }
+        Ok(())
+    }
+
     pub fn get<T: TidAble<'a>>(&self) -> &T {
         self.map
             .get(&T::id())
@@ -45,6 +112,7 @@ impl<'a> NativeContextExtensions<'a> {
     }
 
     pub fn remove<T: TidAble<'a>>(&mut self) -> T {
+        self.savepoint_handlers.remove(&T::id());
         // can't use expect below because it requires `T: Debug`.
         match self
             .map
@@ -128,10 +128,23 @@ impl<'a, 'b> NativeContext<'a, 'b> {
         match self.data_store.emit_event(guid, seq_num, ty, val) {
             Ok(()) => Ok(true),
             Err(e) if e.major_status().status_type() == StatusType::InvariantViolation => Err(e),
+            Err(e) if e.major_status() == StatusCode::VM_MAX_EVENTS_REACHED => Err(e),
             Err(_) => Ok(false),
         }
     }
 
+    /// The maximal vector length configured for the VM, if any. Natives only need to check the
+    /// length of the vectors they grow if there is one.
+    pub fn max_vector_length(&self) -> Option<u64> {
+        self.interpreter.max_vector_length()
+    }
+
+    /// Fails with `VM_MAX_VECTOR_LENGTH_REACHED` if a vector of `len` elements exceeds the
+    /// maximal vector length configured for the VM.
+    pub fn check_vector_length(&self, len: u64) -> PartialVMResult<()> {
+        self.interpreter.check_vector_length(len)
+    }
+
     pub fn events(&self) -> &Vec<(Vec<u8>, u64, Type, MoveTypeLayout, Value)> {
         self.data_store.events()
     }
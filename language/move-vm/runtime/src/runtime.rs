@@ -10,6 +10,7 @@ use crate::{
     native_extensions::NativeContextExtensions,
     native_functions::{NativeFunction, NativeFunctions},
     session::{LoadedFunctionInstantiation, SerializedReturnValues, Session},
+    tracer::Tracer,
 };
 use move_binary_format::{
     access::ModuleAccess,
@@ -64,6 +65,7 @@ impl VMRuntime {
             runtime: self,
             data_cache: TransactionDataCache::new(remote, &self.loader),
             native_extensions,
+            tracer: None,
         }
     }
 
@@ -326,6 +328,7 @@ impl VMRuntime {
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        tracer: Option<&mut dyn Tracer>,
     ) -> VMResult<SerializedReturnValues> {
         let arg_types = param_types
             .into_iter()
@@ -356,6 +359,7 @@ impl VMRuntime {
             data_store,
             gas_meter,
             extensions,
+            tracer,
             &self.loader,
         )?;
 
@@ -391,6 +395,7 @@ impl VMRuntime {
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        tracer: Option<&mut dyn Tracer>,
         bypass_declared_entry_check: bool,
     ) -> VMResult<SerializedReturnValues> {
         use move_binary_format::{binary_views::BinaryIndexedView, file_format::SignatureIndex};
@@ -443,6 +448,7 @@ impl VMRuntime {
             data_store,
             gas_meter,
             extensions,
+            tracer,
         )
     }
 
@@ -455,6 +461,7 @@ impl VMRuntime {
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        tracer: Option<&mut dyn Tracer>,
     ) -> VMResult<SerializedReturnValues> {
         // load the script, perform verification
         let (
@@ -477,6 +484,7 @@ impl VMRuntime {
             data_store,
             gas_meter,
             extensions,
+            tracer,
         )
     }
 
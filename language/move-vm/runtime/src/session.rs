@@ -4,7 +4,7 @@
 
 use crate::{
     data_cache::TransactionDataCache, native_extensions::NativeContextExtensions,
-    runtime::VMRuntime,
+    runtime::VMRuntime, tracer::Tracer,
 };
 use move_binary_format::{
     compatibility::Compatibility,
@@ -30,6 +30,7 @@ pub struct Session<'r, 'l, S> {
     pub(crate) runtime: &'l VMRuntime,
     pub(crate) data_cache: TransactionDataCache<'r, 'l, S>,
     pub(crate) native_extensions: NativeContextExtensions<'r>,
+    pub(crate) tracer: Option<Box<dyn Tracer + 'r>>,
 }
 
 /// Serialized return values from function/script execution
@@ -86,6 +87,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
             &mut self.data_cache,
             gas_meter,
             &mut self.native_extensions,
+            self.tracer.as_deref_mut().map(|t| t as &mut dyn Tracer),
             bypass_declared_entry_check,
         )
     }
@@ -108,6 +110,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
             &mut self.data_cache,
             gas_meter,
             &mut self.native_extensions,
+            self.tracer.as_deref_mut().map(|t| t as &mut dyn Tracer),
             bypass_declared_entry_check,
         )
     }
@@ -142,6 +145,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
             &mut self.data_cache,
             gas_meter,
             &mut self.native_extensions,
+            self.tracer.as_deref_mut().map(|t| t as &mut dyn Tracer),
         )
     }
 
@@ -233,6 +237,61 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
         self.data_cache.num_mutated_accounts(sender)
     }
 
+    /// Create a savepoint, which the changes and events of the session, and the state of the native
+    /// extensions added with `NativeContextExtensions::add_with_savepoints`, can be rolled back to.
+    ///
+    /// Savepoints are nested: `rollback_to_savepoint` and `commit_savepoint` end the latest one.
+    /// The savepoints not ended when the session finishes are committed.
+    ///
+    /// The resources read before a savepoint and not changed are loaded again from storage when
+    /// accessed after it, but they are only charged and counted towards
+    /// `VMConfig::max_loaded_resource_bytes` the first time.
+    pub fn savepoint(&mut self) -> VMResult<()> {
+        self.data_cache
+            .savepoint()
+            .and_then(|()| self.native_extensions.savepoint())
+            .map_err(|e| e.finish(Location::Undefined))
+    }
+
+    /// Discard the changes and events since the latest savepoint, and remove it.
+    ///
+    /// Like discarding a session, this does not unload the modules published since the savepoint
+    /// from the loader cache: see `MoveVM::mark_loader_cache_as_invalid`.
+    ///
+    /// In case an error occurs, the whole Session should be considered corrupted and one shall not
+    /// proceed with effect generation.
+    pub fn rollback_to_savepoint(&mut self) -> VMResult<()> {
+        self.data_cache
+            .rollback_to_savepoint()
+            .and_then(|()| self.native_extensions.rollback_to_savepoint())
+            .map_err(|e| e.finish(Location::Undefined))
+    }
+
+    /// Keep the changes and events since the latest savepoint, and remove it. They are squashed
+    /// with the changes before the savepoint, see `ChangeSet::squash`.
+    ///
+    /// In case an error occurs, the whole Session should be considered corrupted and one shall not
+    /// proceed with effect generation.
+    pub fn commit_savepoint(&mut self) -> VMResult<()> {
+        self.data_cache
+            .commit_savepoint()
+            .and_then(|()| self.native_extensions.commit_savepoint())
+            .map_err(|e| e.finish(Location::Undefined))
+    }
+
+    /// The changes and events since the latest savepoint, or since the start of the session if
+    /// there is none. The changes of native extensions are not included.
+    pub fn effects_since_savepoint(&mut self) -> VMResult<(ChangeSet, Vec<Event>)> {
+        self.data_cache
+            .effects_since_savepoint()
+            .map_err(|e| e.finish(Location::Undefined))
+    }
+
+    /// The number of savepoints not ended yet
+    pub fn num_savepoints(&self) -> usize {
+        self.data_cache.num_savepoints()
+    }
+
     /// Finish up the session and produce the side effects.
     ///
     /// This function should always succeed with no user errors returned, barring invariant violations.
@@ -334,6 +393,16 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
     pub fn get_native_extensions(&mut self) -> &mut NativeContextExtensions<'r> {
         &mut self.native_extensions
     }
+
+    /// Sets the tracer notified of the execution of the functions and scripts of this session.
+    pub fn set_tracer(&mut self, tracer: impl Tracer + 'r) {
+        self.tracer = Some(Box::new(tracer))
+    }
+
+    /// Removes the tracer of this session, if any.
+    pub fn take_tracer(&mut self) -> Option<Box<dyn Tracer + 'r>> {
+        self.tracer.take()
+    }
 }
 
 pub struct LoadedFunctionInstantiation {
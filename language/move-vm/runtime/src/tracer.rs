@@ -0,0 +1,512 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured traces of executions.
+//!
+//! A `Tracer` set on a `Session` receives the events of the executions of the session as they
+//! happen: calls and returns, instructions with the operand stack, the locals and the current
+//! global resources, accesses to global storage, native calls, events and aborts. A
+//! `TraceRecorder` keeps them as `TraceEvent`s, which can be written in a compact binary format or
+//! as JSON lines, and read back in either format with `read_trace`.
+//!
+//! The events of an execution are nested: the function of an instruction, of a native call or of
+//! an exit is the last one entered and not exited yet. An abort ends all the functions entered.
+
+use crate::loader::Function;
+use move_binary_format::{
+    errors::{PartialVMResult, VMError},
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    vm_status::StatusCode,
+};
+use move_vm_types::values::{self, Locals, Value};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fmt,
+    io::{self, BufRead, Write},
+    rc::Rc,
+};
+
+/// Receives the events of executions. All events are ignored by default.
+pub trait Tracer {
+    /// A Move function is called with `args`, before its first instruction
+    fn enter_function(&mut self, _function: TracedFunction, _ty_args: &[TypeTag], _args: &[Value]) {
+    }
+
+    /// The function being executed returns `return_values`
+    fn exit_function(&mut self, _function: TracedFunction, _return_values: &[Value]) {}
+
+    /// The instruction at `pc` is about to be executed, with the operand stack of the execution,
+    /// the locals of the function and the global resources as modified so far
+    fn instruction(
+        &mut self,
+        _function: TracedFunction,
+        _pc: CodeOffset,
+        _instruction: &Bytecode,
+        _stack: &[Value],
+        _locals: &Locals,
+        _globals: &mut dyn TracedGlobals,
+    ) {
+    }
+
+    /// A global resource is read by `exists` or borrowed by `borrow_global`
+    fn read_global(&mut self, _address: AccountAddress, _ty: &TypeTag, _exists: bool) {}
+
+    /// A global resource is borrowed by `borrow_global_mut`, through which it can be written
+    fn mut_borrow_global(&mut self, _address: AccountAddress, _ty: &TypeTag, _exists: bool) {}
+
+    /// A global resource is published by `move_to` with `value`, or removed by `move_from`
+    fn write_global(&mut self, _address: AccountAddress, _ty: &TypeTag, _value: Option<&Value>) {}
+
+    /// A native function is called with `args`
+    fn native_call(&mut self, _function: TracedFunction, _ty_args: &[TypeTag], _args: &[Value]) {}
+
+    /// An event is emitted, by a native function
+    fn emit_event(&mut self, _guid: &[u8], _seq_num: u64, _ty: &TypeTag, _value: &Value) {}
+
+    /// The execution fails with `error`, from an abort or any other error
+    fn abort(&mut self, _error: &VMError) {}
+}
+
+impl<T: Tracer + ?Sized> Tracer for &mut T {
+    fn enter_function(&mut self, function: TracedFunction, ty_args: &[TypeTag], args: &[Value]) {
+        (**self).enter_function(function, ty_args, args)
+    }
+
+    fn exit_function(&mut self, function: TracedFunction, return_values: &[Value]) {
+        (**self).exit_function(function, return_values)
+    }
+
+    fn instruction(
+        &mut self,
+        function: TracedFunction,
+        pc: CodeOffset,
+        instruction: &Bytecode,
+        stack: &[Value],
+        locals: &Locals,
+        globals: &mut dyn TracedGlobals,
+    ) {
+        (**self).instruction(function, pc, instruction, stack, locals, globals)
+    }
+
+    fn read_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        (**self).read_global(address, ty, exists)
+    }
+
+    fn mut_borrow_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        (**self).mut_borrow_global(address, ty, exists)
+    }
+
+    fn write_global(&mut self, address: AccountAddress, ty: &TypeTag, value: Option<&Value>) {
+        (**self).write_global(address, ty, value)
+    }
+
+    fn native_call(&mut self, function: TracedFunction, ty_args: &[TypeTag], args: &[Value]) {
+        (**self).native_call(function, ty_args, args)
+    }
+
+    fn emit_event(&mut self, guid: &[u8], seq_num: u64, ty: &TypeTag, value: &Value) {
+        (**self).emit_event(guid, seq_num, ty, value)
+    }
+
+    fn abort(&mut self, error: &VMError) {
+        (**self).abort(error)
+    }
+}
+
+/// Shares a tracer with the session it is set on, to read what it traced after the execution
+impl<T: Tracer + ?Sized> Tracer for Rc<RefCell<T>> {
+    fn enter_function(&mut self, function: TracedFunction, ty_args: &[TypeTag], args: &[Value]) {
+        self.borrow_mut().enter_function(function, ty_args, args)
+    }
+
+    fn exit_function(&mut self, function: TracedFunction, return_values: &[Value]) {
+        self.borrow_mut().exit_function(function, return_values)
+    }
+
+    fn instruction(
+        &mut self,
+        function: TracedFunction,
+        pc: CodeOffset,
+        instruction: &Bytecode,
+        stack: &[Value],
+        locals: &Locals,
+        globals: &mut dyn TracedGlobals,
+    ) {
+        self.borrow_mut()
+            .instruction(function, pc, instruction, stack, locals, globals)
+    }
+
+    fn read_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        self.borrow_mut().read_global(address, ty, exists)
+    }
+
+    fn mut_borrow_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        self.borrow_mut().mut_borrow_global(address, ty, exists)
+    }
+
+    fn write_global(&mut self, address: AccountAddress, ty: &TypeTag, value: Option<&Value>) {
+        self.borrow_mut().write_global(address, ty, value)
+    }
+
+    fn native_call(&mut self, function: TracedFunction, ty_args: &[TypeTag], args: &[Value]) {
+        self.borrow_mut().native_call(function, ty_args, args)
+    }
+
+    fn emit_event(&mut self, guid: &[u8], seq_num: u64, ty: &TypeTag, value: &Value) {
+        self.borrow_mut().emit_event(guid, seq_num, ty, value)
+    }
+
+    fn abort(&mut self, error: &VMError) {
+        self.borrow_mut().abort(error)
+    }
+}
+
+/// The global resources of a traced execution, as modified by it so far
+pub trait TracedGlobals {
+    /// The resource of type `ty` at `address`, if it exists
+    fn resource(
+        &mut self,
+        address: AccountAddress,
+        ty: &StructTag,
+    ) -> PartialVMResult<Option<Value>>;
+}
+
+/// A function of a traced execution
+#[derive(Clone, Copy)]
+pub struct TracedFunction<'a>(pub(crate) &'a Function);
+
+impl<'a> TracedFunction<'a> {
+    /// The module of the function, or `None` for the function of a script
+    pub fn module_id(&self) -> Option<&'a ModuleId> {
+        self.0.module_id()
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.0.name()
+    }
+
+    pub fn index(&self) -> FunctionDefinitionIndex {
+        self.0.index()
+    }
+
+    /// The code of the function, empty for a native function
+    pub fn code(&self) -> &'a [Bytecode] {
+        self.0.code()
+    }
+
+    pub fn local_count(&self) -> usize {
+        self.0.local_count()
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.0.is_native()
+    }
+
+    /// The function as recorded in a `TraceEvent`
+    pub fn to_trace_function(&self) -> TraceFunction {
+        TraceFunction {
+            module_id: self.module_id().cloned(),
+            name: Identifier::new(self.name()).unwrap(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for TracedFunction<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.pretty_string())
+    }
+}
+
+//**************************************************************************************************
+// Trace events
+//**************************************************************************************************
+
+/// A function in a `TraceEvent`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TraceFunction {
+    /// The module of the function, or `None` for the function of a script
+    pub module_id: Option<ModuleId>,
+    pub name: Identifier,
+}
+
+/// An event recorded by a `TraceRecorder`. Values are recorded as printed by
+/// `move_vm_types::values::debug`, as their types are not known at runtime.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceEvent {
+    EnterFunction {
+        function: TraceFunction,
+        ty_args: Vec<TypeTag>,
+        args: Vec<String>,
+    },
+    ExitFunction {
+        return_values: Vec<String>,
+    },
+    Instruction {
+        pc: CodeOffset,
+        /// Recorded if the recorder takes snapshots
+        snapshot: Option<Snapshot>,
+    },
+    ReadGlobal {
+        address: AccountAddress,
+        ty: TypeTag,
+        exists: bool,
+    },
+    MutBorrowGlobal {
+        address: AccountAddress,
+        ty: TypeTag,
+        exists: bool,
+    },
+    WriteGlobal {
+        address: AccountAddress,
+        ty: TypeTag,
+        /// The value published, or `None` if the resource is removed
+        value: Option<String>,
+    },
+    NativeCall {
+        function: TraceFunction,
+        ty_args: Vec<TypeTag>,
+        args: Vec<String>,
+    },
+    EmitEvent {
+        guid: Vec<u8>,
+        seq_num: u64,
+        ty: TypeTag,
+        value: String,
+    },
+    Abort {
+        status_code: StatusCode,
+        sub_status: Option<u64>,
+    },
+}
+
+/// The operand stack and the locals before an instruction, with `-` for locals without a value
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub stack: Vec<String>,
+    pub locals: Vec<String>,
+}
+
+/// A `Tracer` recording the events it receives, with snapshots of the operand stack and locals
+/// at each instruction if requested
+#[derive(Default)]
+pub struct TraceRecorder {
+    snapshots: bool,
+    events: Vec<TraceEvent>,
+}
+
+impl TraceRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A recorder taking snapshots of the operand stack and locals at each instruction
+    pub fn with_snapshots() -> Self {
+        Self {
+            snapshots: true,
+            events: vec![],
+        }
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    pub fn into_events(self) -> Vec<TraceEvent> {
+        self.events
+    }
+}
+
+fn print_value(value: &Value) -> String {
+    let mut buf = String::new();
+    match values::debug::print_value(&mut buf, value) {
+        Ok(()) => buf,
+        Err(_) => "?".to_string(),
+    }
+}
+
+fn print_values(values: &[Value]) -> Vec<String> {
+    values.iter().map(print_value).collect()
+}
+
+fn print_local(locals: &Locals, idx: usize) -> String {
+    let mut buf = String::new();
+    match values::debug::print_local(&mut buf, locals, idx) {
+        Ok(()) => buf,
+        Err(_) => "?".to_string(),
+    }
+}
+
+impl Tracer for TraceRecorder {
+    fn enter_function(&mut self, function: TracedFunction, ty_args: &[TypeTag], args: &[Value]) {
+        self.events.push(TraceEvent::EnterFunction {
+            function: function.to_trace_function(),
+            ty_args: ty_args.to_vec(),
+            args: print_values(args),
+        })
+    }
+
+    fn exit_function(&mut self, _function: TracedFunction, return_values: &[Value]) {
+        self.events.push(TraceEvent::ExitFunction {
+            return_values: print_values(return_values),
+        })
+    }
+
+    fn instruction(
+        &mut self,
+        function: TracedFunction,
+        pc: CodeOffset,
+        _instruction: &Bytecode,
+        stack: &[Value],
+        locals: &Locals,
+        _globals: &mut dyn TracedGlobals,
+    ) {
+        let snapshot = self.snapshots.then(|| Snapshot {
+            stack: print_values(stack),
+            locals: (0..function.local_count())
+                .map(|idx| print_local(locals, idx))
+                .collect(),
+        });
+        self.events.push(TraceEvent::Instruction { pc, snapshot })
+    }
+
+    fn read_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        self.events.push(TraceEvent::ReadGlobal {
+            address,
+            ty: ty.clone(),
+            exists,
+        })
+    }
+
+    fn mut_borrow_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        self.events.push(TraceEvent::MutBorrowGlobal {
+            address,
+            ty: ty.clone(),
+            exists,
+        })
+    }
+
+    fn write_global(&mut self, address: AccountAddress, ty: &TypeTag, value: Option<&Value>) {
+        self.events.push(TraceEvent::WriteGlobal {
+            address,
+            ty: ty.clone(),
+            value: value.map(print_value),
+        })
+    }
+
+    fn native_call(&mut self, function: TracedFunction, ty_args: &[TypeTag], args: &[Value]) {
+        self.events.push(TraceEvent::NativeCall {
+            function: function.to_trace_function(),
+            ty_args: ty_args.to_vec(),
+            args: print_values(args),
+        })
+    }
+
+    fn emit_event(&mut self, guid: &[u8], seq_num: u64, ty: &TypeTag, value: &Value) {
+        self.events.push(TraceEvent::EmitEvent {
+            guid: guid.to_vec(),
+            seq_num,
+            ty: ty.clone(),
+            value: print_value(value),
+        })
+    }
+
+    fn abort(&mut self, error: &VMError) {
+        self.events.push(TraceEvent::Abort {
+            status_code: error.major_status(),
+            sub_status: error.sub_status(),
+        })
+    }
+}
+
+//**************************************************************************************************
+// Trace formats
+//**************************************************************************************************
+
+/// The header of a trace in the binary format, followed by the BCS serialization of each event,
+/// prefixed by its ULEB128 encoded length. Traces can be appended to each other in either format.
+pub const BINARY_TRACE_HEADER: &[u8] = b"MOVETRC1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    Binary,
+    /// A JSON object per line for each event
+    Json,
+}
+
+/// Writes `events` in `format`
+pub fn write_trace<W: Write>(
+    writer: &mut W,
+    events: &[TraceEvent],
+    format: TraceFormat,
+) -> io::Result<()> {
+    match format {
+        TraceFormat::Binary => {
+            writer.write_all(BINARY_TRACE_HEADER)?;
+            for event in events {
+                let bytes = bcs::to_bytes(event).map_err(invalid_data)?;
+                let mut len = bytes.len();
+                while len >= 0x80 {
+                    writer.write_all(&[(len as u8 & 0x7f) | 0x80])?;
+                    len >>= 7;
+                }
+                writer.write_all(&[len as u8])?;
+                writer.write_all(&bytes)?;
+            }
+        }
+        TraceFormat::Json => {
+            for event in events {
+                serde_json::to_writer(&mut *writer, event).map_err(invalid_data)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the events of a trace written by `write_trace`, in either format
+pub fn read_trace(mut bytes: &[u8]) -> io::Result<Vec<TraceEvent>> {
+    let mut events = vec![];
+    while !bytes.is_empty() {
+        if let Some(rest) = bytes.strip_prefix(BINARY_TRACE_HEADER) {
+            bytes = rest;
+            while !bytes.is_empty() && !bytes.starts_with(BINARY_TRACE_HEADER) {
+                let (len, rest) = read_uleb128(bytes)?;
+                if rest.len() < len {
+                    return Err(invalid_data("Truncated trace event"));
+                }
+                let (event, rest) = rest.split_at(len);
+                events.push(bcs::from_bytes(event).map_err(invalid_data)?);
+                bytes = rest;
+            }
+        } else {
+            // reading a line advances `bytes` past it
+            let mut line = String::new();
+            bytes.read_line(&mut line)?;
+            if !line.trim().is_empty() {
+                events.push(serde_json::from_str(&line).map_err(invalid_data)?);
+            }
+        }
+    }
+    Ok(events)
+}
+
+fn read_uleb128(bytes: &[u8]) -> io::Result<(usize, &[u8])> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().enumerate().take(5) {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+    Err(invalid_data("Invalid trace event length"))
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
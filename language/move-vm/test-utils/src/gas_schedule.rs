@@ -12,7 +12,8 @@ use move_binary_format::{
     file_format::{
         Bytecode, ConstantPoolIndex, FieldHandleIndex, FieldInstantiationIndex,
         FunctionHandleIndex, FunctionInstantiationIndex, SignatureIndex,
-        StructDefInstantiationIndex, StructDefinitionIndex,
+        StructDefInstantiationIndex, StructDefinitionIndex, StructVariantHandleIndex,
+        StructVariantInstantiationIndex, VariantFieldHandleIndex, VariantFieldInstantiationIndex,
     },
     file_format_common::{instruction_key, Opcodes},
 };
@@ -257,10 +258,21 @@ fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
         CastU16 => CAST_U16,
         CastU32 => CAST_U32,
         CastU256 => CAST_U256,
+
+        TestVariant => TEST_VARIANT,
+        TestVariantGeneric => TEST_VARIANT_GENERIC,
+        ImmBorrowVariantField => IMM_BORROW_VARIANT_FIELD,
+        MutBorrowVariantField => MUT_BORROW_VARIANT_FIELD,
+        ImmBorrowVariantFieldGeneric => IMM_BORROW_VARIANT_FIELD_GENERIC,
+        MutBorrowVariantFieldGeneric => MUT_BORROW_VARIANT_FIELD_GENERIC,
     }
 }
 
 impl<'b> GasMeter for GasStatus<'b> {
+    fn balance_internal(&self) -> InternalGas {
+        self.gas_left
+    }
+
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
         self.charge_instr(get_simple_instruction_opcode(instr))
@@ -691,6 +703,46 @@ pub fn zero_cost_instruction_table() -> Vec<(Bytecode, GasCost)> {
         (CastU16, GasCost::new(0, 0)),
         (CastU32, GasCost::new(0, 0)),
         (CastU256, GasCost::new(0, 0)),
+        (
+            PackVariant(StructVariantHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            PackVariantGeneric(StructVariantInstantiationIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariant(StructVariantHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariantGeneric(StructVariantInstantiationIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            TestVariant(StructVariantHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            TestVariantGeneric(StructVariantInstantiationIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            MutBorrowVariantField(VariantFieldHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            MutBorrowVariantFieldGeneric(VariantFieldInstantiationIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            ImmBorrowVariantField(VariantFieldHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            ImmBorrowVariantFieldGeneric(VariantFieldInstantiationIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
     ]
 }
 
@@ -824,6 +876,46 @@ pub fn bytecode_instruction_costs() -> Vec<(Bytecode, GasCost)> {
         (CastU16, GasCost::new(2, 1)),
         (CastU32, GasCost::new(2, 1)),
         (CastU256, GasCost::new(2, 1)),
+        (
+            PackVariant(StructVariantHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            PackVariantGeneric(StructVariantInstantiationIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            UnpackVariant(StructVariantHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            UnpackVariantGeneric(StructVariantInstantiationIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            TestVariant(StructVariantHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            TestVariantGeneric(StructVariantInstantiationIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            MutBorrowVariantField(VariantFieldHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            MutBorrowVariantFieldGeneric(VariantFieldInstantiationIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            ImmBorrowVariantField(VariantFieldHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            ImmBorrowVariantFieldGeneric(VariantFieldInstantiationIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
     ]
 }
 
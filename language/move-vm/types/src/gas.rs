@@ -65,11 +65,25 @@ pub enum SimpleInstruction {
     CastU16,
     CastU32,
     CastU256,
+
+    TestVariant,
+    TestVariantGeneric,
+    ImmBorrowVariantField,
+    MutBorrowVariantField,
+    ImmBorrowVariantFieldGeneric,
+    MutBorrowVariantFieldGeneric,
 }
 
 /// Trait that defines a generic gas meter interface, allowing clients of the Move VM to implement
 /// their own metering scheme.
 pub trait GasMeter {
+    /// Return the gas left, in internal units. It is only used to measure the gas of each charge,
+    /// as `GasProfiler` does: meters that do not meter anything, and those that do not implement
+    /// it, have an unlimited balance, and their charges are measured as free.
+    fn balance_internal(&self) -> InternalGas {
+        InternalGas::new(u64::MAX)
+    }
+
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()>;
 
@@ -22,12 +22,42 @@ pub struct StructType {
     pub name: Identifier,
     pub module: ModuleId,
     pub struct_def: StructDefinitionIndex,
+    // Non-empty only for structs declared with variants, in which case `fields` and
+    // `field_names` are empty.
+    pub variants: Vec<StructVariantType>,
+}
+
+#[derive(Debug, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct StructVariantType {
+    pub name: Identifier,
+    pub fields: Vec<Type>,
+    pub field_names: Vec<Identifier>,
 }
 
 impl StructType {
     pub fn type_param_constraints(&self) -> impl ExactSizeIterator<Item = &AbilitySet> {
         self.type_parameters.iter().map(|param| &param.constraints)
     }
+
+    pub fn has_variants(&self) -> bool {
+        !self.variants.is_empty()
+    }
+
+    /// Returns the field types of the given variant, or of the struct itself if `variant` is
+    /// `None`.
+    pub fn fields_of(&self, variant: Option<u16>) -> PartialVMResult<&[Type]> {
+        match variant {
+            None => Ok(&self.fields),
+            Some(tag) => self
+                .variants
+                .get(tag as usize)
+                .map(|v| v.fields.as_slice())
+                .ok_or_else(|| {
+                    PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                        .with_message(format!("variant index {} out of bounds", tag))
+                }),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -15,7 +15,7 @@ use move_core_types::{
     effects::Op,
     gas_algebra::AbstractMemorySize,
     u256,
-    value::{MoveStructLayout, MoveTypeLayout},
+    value::{MoveStructLayout, MoveTypeLayout, MOVE_ENUM_NAME, MOVE_VARIANT_NAME},
     vm_status::{sub_status::NFE_VECTOR_ERROR_BASE, StatusCode},
 };
 use std::{
@@ -914,6 +914,40 @@ impl StructRef {
     pub fn borrow_field(&self, idx: usize) -> PartialVMResult<Value> {
         Ok(Value(self.0.borrow_elem(idx)?))
     }
+
+    /// Returns the variant tag of a struct declared with variants.
+    fn variant_tag(&self) -> PartialVMResult<u16> {
+        match self.0.container() {
+            Container::Struct(r) => match r.borrow().first() {
+                Some(ValueImpl::U16(tag)) => Ok(*tag),
+                _ => Err(
+                    PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                        .with_message("struct value does not hold a variant tag".to_string()),
+                ),
+            },
+            _ => Err(
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                    .with_message("expected a struct reference".to_string()),
+            ),
+        }
+    }
+
+    /// Tests whether the referenced struct holds the given variant.
+    pub fn test_variant(&self, tag: u16) -> PartialVMResult<Value> {
+        Ok(Value::bool(self.variant_tag()? == tag))
+    }
+
+    /// Borrows the field at `idx` of the given variant, failing with `VARIANT_TAG_MISMATCH` if
+    /// the referenced struct holds a different variant.
+    pub fn borrow_variant_field(&self, tag: u16, idx: usize) -> PartialVMResult<Value> {
+        let actual = self.variant_tag()?;
+        if actual != tag {
+            return Err(PartialVMError::new(StatusCode::VARIANT_TAG_MISMATCH)
+                .with_message(format!("expected variant {}, found {}", tag, actual)));
+        }
+        // The variant tag is stored as the first element of the container.
+        self.borrow_field(idx + 1)
+    }
 }
 
 impl Locals {
@@ -2414,6 +2448,33 @@ impl Struct {
     pub fn unpack(self) -> PartialVMResult<impl Iterator<Item = Value>> {
         Ok(self.fields.into_iter().map(Value))
     }
+
+    /// Packs a variant of a struct declared with variants. The variant tag is kept as the first
+    /// element of the struct container, followed by the fields of the variant.
+    pub fn pack_variant<I: IntoIterator<Item = Value>>(tag: u16, vals: I) -> Self {
+        Self {
+            fields: iter::once(ValueImpl::U16(tag))
+                .chain(vals.into_iter().map(|v| v.0))
+                .collect(),
+        }
+    }
+
+    /// Unpacks the fields of the given variant, failing with `VARIANT_TAG_MISMATCH` if the
+    /// struct holds a different variant.
+    pub fn unpack_variant(self, tag: u16) -> PartialVMResult<impl Iterator<Item = Value>> {
+        let mut fields = self.fields.into_iter();
+        match fields.next() {
+            Some(ValueImpl::U16(actual)) if actual == tag => Ok(fields.map(Value)),
+            Some(ValueImpl::U16(actual)) => {
+                Err(PartialVMError::new(StatusCode::VARIANT_TAG_MISMATCH)
+                    .with_message(format!("expected variant {}, found {}", tag, actual)))
+            }
+            _ => Err(
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                    .with_message("struct value does not hold a variant tag".to_string()),
+            ),
+        }
+    }
 }
 
 /***************************************************************************************
@@ -2885,6 +2946,19 @@ pub mod debug {
     pub fn print_value<B: Write>(buf: &mut B, val: &Value) -> PartialVMResult<()> {
         print_value_impl(buf, &val.0)
     }
+
+    /// Prints the local at `idx`, or `-` if it holds no value
+    pub fn print_local<B: Write>(buf: &mut B, locals: &Locals, idx: usize) -> PartialVMResult<()> {
+        let v = locals.0.borrow();
+        match v.get(idx) {
+            Some(val) => print_value_impl(buf, val),
+            None => Err(
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR).with_message(
+                    format!("local index out of bounds: got {}, len: {}", idx, v.len()),
+                ),
+            ),
+        }
+    }
 }
 
 /***************************************************************************************
@@ -2908,7 +2982,7 @@ pub mod debug {
  **************************************************************************************/
 use serde::{
     de::Error as DeError,
-    ser::{Error as SerError, SerializeSeq, SerializeTuple},
+    ser::{Error as SerError, SerializeSeq, SerializeTuple, SerializeTupleVariant},
     Deserialize,
 };
 
@@ -3033,6 +3107,9 @@ impl<'a, 'b> serde::Serialize for AnnotatedValue<'a, 'b, MoveTypeLayout, ValueIm
 impl<'a, 'b> serde::Serialize for AnnotatedValue<'a, 'b, MoveStructLayout, Vec<ValueImpl>> {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let values = &self.val;
+        if let MoveStructLayout::RuntimeVariants(variants) = self.layout {
+            return self.serialize_variant(variants, serializer);
+        }
         let fields = self.layout.fields();
         if fields.len() != values.len() {
             return Err(invariant_violation::<S>(format!(
@@ -3051,6 +3128,46 @@ impl<'a, 'b> serde::Serialize for AnnotatedValue<'a, 'b, MoveStructLayout, Vec<V
     }
 }
 
+impl<'a, 'b> AnnotatedValue<'a, 'b, MoveStructLayout, Vec<ValueImpl>> {
+    fn serialize_variant<S: serde::Serializer>(
+        &self,
+        variants: &[Vec<MoveTypeLayout>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let (tag, values) = match self.val.split_first() {
+            Some((ValueImpl::U16(tag), values)) => (*tag, values),
+            _ => {
+                return Err(invariant_violation::<S>(format!(
+                    "cannot serialize struct value {:?} as {:?} -- missing variant tag",
+                    self.val, self.layout
+                )))
+            }
+        };
+        let fields = match variants.get(tag as usize) {
+            Some(fields) if fields.len() == values.len() => fields,
+            _ => {
+                return Err(invariant_violation::<S>(format!(
+                    "cannot serialize struct value {:?} as {:?} -- variant mismatch",
+                    self.val, self.layout
+                )))
+            }
+        };
+        let mut t = serializer.serialize_tuple_variant(
+            MOVE_ENUM_NAME,
+            tag as u32,
+            MOVE_VARIANT_NAME,
+            values.len(),
+        )?;
+        for (field_layout, val) in fields.iter().zip(values.iter()) {
+            t.serialize_field(&AnnotatedValue {
+                layout: field_layout,
+                val,
+            })?;
+        }
+        t.end()
+    }
+}
+
 #[derive(Clone)]
 struct SeedWrapper<L> {
     layout: L,
@@ -3128,6 +3245,13 @@ impl<'d> serde::de::DeserializeSeed<'d> for SeedWrapper<&MoveStructLayout> {
         self,
         deserializer: D,
     ) -> Result<Self::Value, D::Error> {
+        if let MoveStructLayout::RuntimeVariants(variants) = self.layout {
+            return deserializer.deserialize_enum(
+                MOVE_ENUM_NAME,
+                &[MOVE_VARIANT_NAME],
+                StructVariantVisitor(variants),
+            );
+        }
         let field_layouts = self.layout.fields();
         let fields = deserializer
             .deserialize_tuple(field_layouts.len(), StructFieldVisitor(field_layouts))?;
@@ -3135,6 +3259,31 @@ impl<'d> serde::de::DeserializeSeed<'d> for SeedWrapper<&MoveStructLayout> {
     }
 }
 
+struct StructVariantVisitor<'a>(&'a [Vec<MoveTypeLayout>]);
+
+impl<'d, 'a> serde::de::Visitor<'d> for StructVariantVisitor<'a> {
+    type Value = Struct;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Struct variant")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'d>,
+    {
+        use serde::de::VariantAccess;
+
+        let (tag, variant) = data.variant::<u32>()?;
+        let field_layouts = self.0.get(tag as usize).ok_or_else(|| {
+            A::Error::custom(format!("variant index {} out of range", tag).as_str())
+        })?;
+        let fields =
+            variant.tuple_variant(field_layouts.len(), StructFieldVisitor(field_layouts))?;
+        Ok(Struct::pack_variant(tag as u16, fields))
+    }
+}
+
 struct VectorElementVisitor<'a>(SeedWrapper<&'a MoveTypeLayout>);
 
 impl<'d, 'a> serde::de::Visitor<'d> for VectorElementVisitor<'a> {
@@ -3642,6 +3791,22 @@ impl ValueImpl {
             (L::Bool, ValueImpl::Bool(x)) => MoveValue::Bool(*x),
             (L::Address, ValueImpl::Address(x)) => MoveValue::Address(*x),
 
+            (
+                L::Struct(MoveStructLayout::RuntimeVariants(variants)),
+                ValueImpl::Container(Container::Struct(r)),
+            ) => {
+                let r = r.borrow();
+                let (tag, values) = match r.split_first() {
+                    Some((ValueImpl::U16(tag), values)) => (*tag, values),
+                    _ => panic!("struct value does not hold a variant tag"),
+                };
+                let mut fields = vec![];
+                for (v, field_layout) in values.iter().zip(variants[tag as usize].iter()) {
+                    fields.push(v.as_move_value(field_layout));
+                }
+                MoveValue::Struct(MoveStruct::RuntimeVariant(tag, fields))
+            }
+
             (L::Struct(struct_layout), ValueImpl::Container(Container::Struct(r))) => {
                 let mut fields = vec![];
                 for (v, field_layout) in r.borrow().iter().zip(struct_layout.fields().iter()) {
@@ -928,6 +928,9 @@ impl<'a> BytecodeGenerator<'a> {
                     .struct_def_at(StructDefinitionIndex(struct_def_idx as TableIndex));
                 let fields = match &struct_def.field_information {
                     StructFieldInformation::Native => panic!("Can't inhabit native structs"),
+                    StructFieldInformation::DeclaredVariants(_) => {
+                        panic!("Can't inhabit structs with variants")
+                    }
                     StructFieldInformation::Declared(fields) => fields.clone(),
                 };
                 let mut bytecodes: Vec<Bytecode> = fields
@@ -956,6 +959,9 @@ impl<'a> BytecodeGenerator<'a> {
                     .struct_def_at(StructDefinitionIndex(struct_def_idx as TableIndex));
                 let fields = match &struct_def.field_information {
                     StructFieldInformation::Native => panic!("Can't inhabit native structs"),
+                    StructFieldInformation::DeclaredVariants(_) => {
+                        panic!("Can't inhabit structs with variants")
+                    }
                     StructFieldInformation::Declared(fields) => fields.clone(),
                 };
                 let mut bytecodes: Vec<Bytecode> = fields
@@ -617,5 +617,17 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
         | Bytecode::VecPopBack(_)
         | Bytecode::VecUnpack(..)
         | Bytecode::VecSwap(_) => unimplemented!("Vector bytecode not supported yet"),
+        Bytecode::PackVariant(_)
+        | Bytecode::PackVariantGeneric(_)
+        | Bytecode::UnpackVariant(_)
+        | Bytecode::UnpackVariantGeneric(_)
+        | Bytecode::TestVariant(_)
+        | Bytecode::TestVariantGeneric(_)
+        | Bytecode::MutBorrowVariantField(_)
+        | Bytecode::MutBorrowVariantFieldGeneric(_)
+        | Bytecode::ImmBorrowVariantField(_)
+        | Bytecode::ImmBorrowVariantFieldGeneric(_) => {
+            unimplemented!("Variant bytecode not supported yet")
+        }
     }
 }
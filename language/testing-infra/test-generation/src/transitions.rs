@@ -839,6 +839,11 @@ pub fn stack_struct_borrow_field(
         StructFieldInformation::Native => {
             return Err(VMError::new("Borrow field on a native struct".to_string()));
         }
+        StructFieldInformation::DeclaredVariants(_) => {
+            return Err(VMError::new(
+                "Borrow field on a struct with variants".to_string(),
+            ));
+        }
         StructFieldInformation::Declared(fields) => {
             let field_def = &fields[field_handle.field as usize];
             &field_def.signature.0
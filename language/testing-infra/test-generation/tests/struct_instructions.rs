@@ -88,6 +88,9 @@ fn get_field_signature<'a>(module: &'a CompiledModule, handle: &FieldHandle) ->
     let struct_def = &module.struct_defs[handle.owner.0 as usize];
     match &struct_def.field_information {
         StructFieldInformation::Native => panic!("borrow field on a native struct"),
+        StructFieldInformation::DeclaredVariants(_) => {
+            panic!("borrow field on a struct with variants")
+        }
         StructFieldInformation::Declared(fields) => &fields[handle.field as usize].signature.0,
     }
 }
@@ -7,16 +7,16 @@ use anyhow::{anyhow, bail, Result};
 use move_binary_format::{
     access::ModuleAccess,
     file_format::{SignatureToken, StructDefinition, StructFieldInformation, StructHandleIndex},
-    normalized::{Struct, Type},
+    normalized::{Field, Struct, Type},
     CompiledModule,
 };
 use move_core_types::{
     account_address::AccountAddress,
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, StructTag, TypeTag},
-    value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout},
+    value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout, MoveVariantLayout},
 };
-use serde_reflection::{ContainerFormat, Format, Named, Registry};
+use serde_reflection::{ContainerFormat, Format, Named, Registry, VariantFormat};
 use std::{
     borrow::Borrow,
     collections::BTreeMap,
@@ -274,8 +274,36 @@ impl<'a, T: GetModule> SerdeLayoutBuilder<'a, T> {
         normalized_struct: Struct,
         type_arguments: &[Format],
     ) -> Result<ContainerFormat, T::Error> {
-        let fields = normalized_struct
-            .fields
+        if !normalized_struct.variants.is_empty() {
+            let mut variants = BTreeMap::new();
+            for (tag, variant) in normalized_struct.variants.iter().enumerate() {
+                let format = if variant.fields.is_empty() {
+                    VariantFormat::Unit
+                } else {
+                    VariantFormat::Struct(
+                        self.generate_serde_fields(&variant.fields, type_arguments)?,
+                    )
+                };
+                variants.insert(
+                    tag as u32,
+                    Named {
+                        name: variant.name.to_string(),
+                        value: format,
+                    },
+                );
+            }
+            return Ok(ContainerFormat::Enum(variants));
+        }
+        let fields = self.generate_serde_fields(&normalized_struct.fields, type_arguments)?;
+        Ok(ContainerFormat::Struct(fields))
+    }
+
+    fn generate_serde_fields(
+        &mut self,
+        fields: &[Field],
+        type_arguments: &[Format],
+    ) -> Result<Vec<Named<Format>>, T::Error> {
+        fields
             .iter()
             .map(|f| {
                 self.build_normalized_type_layout(&f.type_, type_arguments)
@@ -284,8 +312,7 @@ impl<'a, T: GetModule> SerdeLayoutBuilder<'a, T> {
                         value,
                     })
             })
-            .collect::<Result<Vec<Named<Format>>, T::Error>>()?;
-        Ok(ContainerFormat::Struct(fields))
+            .collect()
     }
 }
 
@@ -466,6 +493,49 @@ impl StructLayoutBuilder {
             StructFieldInformation::Native => {
                 bail!("Can't extract fields for native struct")
             }
+            StructFieldInformation::DeclaredVariants(variants) => {
+                let variant_layouts = variants
+                    .iter()
+                    .map(|v| {
+                        v.fields
+                            .iter()
+                            .map(|f| {
+                                TypeLayoutBuilder::build_from_signature_token(
+                                    m,
+                                    &f.signature.0,
+                                    &type_arguments,
+                                    resolver,
+                                    layout_type,
+                                )
+                            })
+                            .collect::<Result<Vec<MoveTypeLayout>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(match layout_type {
+                    LayoutType::Runtime => MoveStructLayout::RuntimeVariants(variant_layouts),
+                    LayoutType::WithFields | LayoutType::WithTypes => {
+                        MoveStructLayout::WithVariants(
+                            variants
+                                .iter()
+                                .zip(variant_layouts)
+                                .map(|(v, layouts)| {
+                                    let fields = v
+                                        .fields
+                                        .iter()
+                                        .map(|f| m.identifier_at(f.name).to_owned())
+                                        .zip(layouts)
+                                        .map(|(name, layout)| MoveFieldLayout::new(name, layout))
+                                        .collect();
+                                    MoveVariantLayout::new(
+                                        m.identifier_at(v.name).to_owned(),
+                                        fields,
+                                    )
+                                })
+                                .collect(),
+                        )
+                    }
+                })
+            }
             StructFieldInformation::Declared(fields) => {
                 let layouts = fields
                     .iter()
@@ -0,0 +1,252 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{reroot_path, test::build_test_plan};
+use crate::{
+    debugger::{self, protocol::Connection, SourceMaps},
+    sandbox::{
+        commands::{execute_script_or_function, script_bytecode, transaction_arguments},
+        utils::PackageContext,
+    },
+    NativeFunctionRecord, DEFAULT_STORAGE_DIR,
+};
+use anyhow::{bail, Result};
+use clap::*;
+use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::IdentStr,
+    language_storage::TypeTag,
+    parser,
+    transaction_argument::{convert_txn_args, TransactionArgument},
+    value::serialize_values,
+};
+use move_package::BuildConfig;
+use move_unit_test::{extensions::new_extensions, UnitTestingConfig};
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+};
+
+/// Debug a script or a unit test of this package with a client of the Debug Adapter Protocol
+/// (DAP), such as VS Code, connected to the standard input and output. Gas is not metered.
+#[derive(Parser)]
+#[clap(name = "debug")]
+pub struct Debug {
+    /// Serve the client connecting to this TCP port on the local host instead, leaving the
+    /// standard output to the executed code.
+    #[clap(long = "port")]
+    pub port: Option<u16>,
+    #[clap(subcommand)]
+    pub target: DebugTarget,
+}
+
+#[derive(Parser)]
+pub enum DebugTarget {
+    /// Run a script, or an entry function of a module, against the storage of the sandbox. Its
+    /// effects are not committed.
+    #[clap(name = "run")]
+    Run {
+        /// Directory storing Move resources, events, and module bytecodes produced by module
+        /// publishing and script execution.
+        #[clap(long, default_value = DEFAULT_STORAGE_DIR, parse(from_os_str))]
+        storage_dir: PathBuf,
+        /// Path to a script source file, or to a .mv file containing either script or module
+        /// bytecodes. If the file is a module, the `script_name` parameter must be set.
+        #[clap(name = "script", parse(from_os_str))]
+        script_file: PathBuf,
+        /// Name of the script function inside `script_file` to call. Should only be set if
+        /// `script_file` points to a module.
+        #[clap(name = "name")]
+        script_name: Option<String>,
+        /// Possibly-empty list of signers for the current transaction.
+        #[clap(
+            long = "signers",
+            takes_value(true),
+            multiple_values(true),
+            multiple_occurrences(true)
+        )]
+        signers: Vec<String>,
+        /// Possibly-empty list of arguments passed to the transaction, as for `sandbox run`.
+        #[clap(
+            long = "args",
+            parse(try_from_str = parser::parse_transaction_argument),
+            takes_value(true),
+            multiple_values(true),
+            multiple_occurrences(true)
+        )]
+        args: Vec<TransactionArgument>,
+        /// Possibly-empty list of type arguments passed to the transaction.
+        #[clap(
+            long = "type-args",
+            parse(try_from_str = parser::parse_type_tag),
+            takes_value(true),
+            multiple_values(true),
+            multiple_occurrences(true)
+        )]
+        type_args: Vec<TypeTag>,
+    },
+    /// Run a unit test.
+    #[clap(name = "test")]
+    Test {
+        /// The name of the test function, optionally qualified by its module and address (e.g.
+        /// `M::test` or `0x2::M::test`).
+        #[clap(name = "name")]
+        name: String,
+    },
+}
+
+impl Debug {
+    pub fn execute(
+        self,
+        path: Option<PathBuf>,
+        config: BuildConfig,
+        natives: Vec<NativeFunctionRecord>,
+    ) -> Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        match self.port {
+            None => {
+                let stdin = io::stdin();
+                let connection = Connection::new(stdin.lock(), io::stdout());
+                self.target
+                    .debug(&rerooted_path, config, natives, connection)
+            }
+            Some(port) => {
+                let listener = TcpListener::bind(("127.0.0.1", port))?;
+                eprintln!("Waiting for a DAP client on port {}", port);
+                let (stream, _) = listener.accept()?;
+                let connection = Connection::new(BufReader::new(stream.try_clone()?), stream);
+                self.target
+                    .debug(&rerooted_path, config, natives, connection)
+            }
+        }
+    }
+}
+
+impl DebugTarget {
+    /// Debugs the target in the package at `pkg_path` with the client of `connection`
+    pub fn debug<R: BufRead, W: Write>(
+        self,
+        pkg_path: &Path,
+        config: BuildConfig,
+        natives: Vec<NativeFunctionRecord>,
+        connection: Connection<R, W>,
+    ) -> Result<()> {
+        match self {
+            DebugTarget::Run {
+                storage_dir,
+                script_file,
+                script_name,
+                signers,
+                args,
+                type_args,
+            } => {
+                let context = PackageContext::new(&Some(pkg_path.to_path_buf()), &config)?;
+                let state = context.prepare_state(&storage_dir)?;
+                let package = context.package();
+                let bytecode = script_bytecode(&state, package, &script_file)?;
+
+                let mut source_maps = SourceMaps::new();
+                for unit in package.all_modules() {
+                    if let CompiledUnitEnum::Module(module) = &unit.unit {
+                        let module_id = module.module.self_id();
+                        source_maps.insert(Some(module_id), module.source_map.clone());
+                    }
+                }
+                if let Ok(contents) = fs::read_to_string(&script_file) {
+                    let script = package
+                        .scripts()
+                        .find(|unit| unit.unit.source_map().check(&contents));
+                    if let Some(script) = script {
+                        source_maps.insert(None, script.unit.source_map().clone());
+                    }
+                }
+
+                let signer_addresses = signers
+                    .iter()
+                    .map(|s| AccountAddress::from_hex_literal(s))
+                    .collect::<Result<Vec<AccountAddress>, _>>()?;
+                let vm_args = transaction_arguments(&signer_addresses, convert_txn_args(&args));
+                let vm = MoveVM::new(natives)?;
+                debugger::debug(connection, source_maps, &state, |debugger| {
+                    let mut session = vm.new_session(&state);
+                    session.set_tracer(debugger);
+                    let res = execute_script_or_function(
+                        &mut session,
+                        &bytecode,
+                        &script_name,
+                        type_args,
+                        vm_args,
+                        &mut UnmeteredGasMeter,
+                    )?;
+                    Ok(res.map(|_| ()))
+                })
+            }
+            DebugTarget::Test { name } => {
+                // the output of the build is not for the client
+                let mut unit_test_config = UnitTestingConfig::default_with_bound(None);
+                let test_plan =
+                    build_test_plan(pkg_path, config, &mut unit_test_config, &mut io::stderr())?;
+                let tests = test_plan
+                    .module_tests
+                    .values()
+                    .flat_map(|module_test| {
+                        module_test
+                            .tests
+                            .values()
+                            .map(move |test| (&module_test.module_id, test))
+                    })
+                    .filter(|(module_id, test)| {
+                        let qualified_name = format!(
+                            "{}::{}::{}",
+                            module_id.address().to_hex_literal(),
+                            module_id.name(),
+                            test.test_name
+                        );
+                        qualified_name == name || qualified_name.ends_with(&format!("::{}", name))
+                    })
+                    .collect::<Vec<_>>();
+                let (module_id, test) = match tests.as_slice() {
+                    [test] => *test,
+                    [] => bail!("No unit test is named {}", name),
+                    _ => bail!(
+                        "Several unit tests are named {}: {}",
+                        name,
+                        tests
+                            .iter()
+                            .map(|(module_id, test)| format!("{}::{}", module_id, test.test_name))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                };
+
+                let mut storage = InMemoryStorage::new();
+                let mut source_maps = SourceMaps::new();
+                for (module_id, info) in &test_plan.module_info {
+                    let mut module_bytes = vec![];
+                    info.module.serialize(&mut module_bytes)?;
+                    storage.publish_or_overwrite_module(module_id.clone(), module_bytes);
+                    source_maps.insert(Some(module_id.clone()), info.source_map.clone());
+                }
+                let vm = MoveVM::new(natives)?;
+                debugger::debug(connection, source_maps, &storage, |debugger| {
+                    let mut session = vm.new_session_with_extensions(&storage, new_extensions());
+                    session.set_tracer(debugger);
+                    let res = session.execute_function_bypass_visibility(
+                        module_id,
+                        IdentStr::new(&test.test_name)?,
+                        vec![],
+                        serialize_values(test.arguments.iter()),
+                        &mut UnmeteredGasMeter,
+                    );
+                    Ok(res.map(|_| ()))
+                })
+            }
+        }
+    }
+}
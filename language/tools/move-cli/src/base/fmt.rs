@@ -0,0 +1,83 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use clap::*;
+use difference::{Changeset, Difference};
+use move_command_line_common::files::{find_move_filenames, FileHash};
+use move_compiler::diagnostics::{report_diagnostics_to_buffer, FilesSourceText};
+use move_formatter::FormatConfig;
+use move_package::source_package::layout::SourcePackageLayout;
+use std::{io::Write, path::PathBuf};
+
+/// Format the Move source files of the package at `path`, i.e., the ones in its `sources`,
+/// `scripts`, `examples`, `specifications` and `tests` directories. The formatting can be
+/// configured by a `movefmt.toml` file at the root of the package.
+#[derive(Parser)]
+#[clap(name = "fmt")]
+pub struct Fmt {
+    /// Check that the files are formatted instead of rewriting them, printing the changes the
+    /// formatter would make and failing if there are any.
+    #[clap(long = "check")]
+    pub check: bool,
+}
+
+impl Fmt {
+    pub fn execute(self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        let config = FormatConfig::load(&rerooted_path)?;
+        let places_to_look = [
+            SourcePackageLayout::Sources,
+            SourcePackageLayout::Scripts,
+            SourcePackageLayout::Examples,
+            SourcePackageLayout::Specifications,
+            SourcePackageLayout::Tests,
+        ]
+        .iter()
+        .map(|layout| rerooted_path.join(layout.path()))
+        .filter(|path| path.is_dir())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+
+        let mut unformatted = 0;
+        for file in find_move_filenames(&places_to_look, false)? {
+            let source = std::fs::read_to_string(&file)?;
+            let formatted = match move_formatter::format(&source, &config) {
+                Ok(formatted) => formatted,
+                Err(diags) => {
+                    let files: FilesSourceText = [(
+                        FileHash::new(&source),
+                        (file.as_str().into(), source.clone()),
+                    )]
+                    .into_iter()
+                    .collect();
+                    std::io::stderr().write_all(&report_diagnostics_to_buffer(&files, diags))?;
+                    anyhow::bail!("Unable to format '{}'", file)
+                }
+            };
+            if formatted == source {
+                continue;
+            }
+            if self.check {
+                unformatted += 1;
+                println!("Diff in {}:", file);
+                for diff in Changeset::new(&source, &formatted, "\n").diffs {
+                    let (prefix, lines) = match &diff {
+                        Difference::Same(_) => continue,
+                        Difference::Rem(lines) => ('-', lines),
+                        Difference::Add(lines) => ('+', lines),
+                    };
+                    for line in lines.split('\n') {
+                        println!("{}{}", prefix, line);
+                    }
+                }
+            } else {
+                std::fs::write(&file, formatted)?;
+            }
+        }
+        if unformatted > 0 {
+            anyhow::bail!("{} file(s) are not formatted", unformatted)
+        }
+        Ok(())
+    }
+}
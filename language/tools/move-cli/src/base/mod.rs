@@ -3,9 +3,11 @@
 
 pub mod build;
 pub mod coverage;
+pub mod debug;
 pub mod disassemble;
 pub mod docgen;
 pub mod errmap;
+pub mod fmt;
 pub mod info;
 pub mod new;
 pub mod prove;
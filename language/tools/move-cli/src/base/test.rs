@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::reroot_path;
-use crate::NativeFunctionRecord;
+use crate::{NativeFunctionRecord, DEFAULT_GAS_PROFILE_DIR};
 use anyhow::Result;
 use clap::*;
 use move_command_line_common::files::{FileHash, MOVE_COVERAGE_MAP_EXTENSION};
@@ -76,6 +76,14 @@ pub struct Test {
     /// Collect coverage information for later use with the various `move coverage` subcommands
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
+    /// Append a trace of the execution of the tests, as JSON lines, to this file
+    #[clap(long = "trace", parse(from_os_str))]
+    pub trace: Option<PathBuf>,
+    /// Profile the gas used by the tests, writing the gas of their call stacks as folded stacks
+    /// for flamegraph tools, and reports of the gas of their functions, to the `gas-profile`
+    /// directory
+    #[clap(long = "profile-gas")]
+    pub profile_gas: bool,
 
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
@@ -92,6 +100,11 @@ impl Test {
         natives: Vec<NativeFunctionRecord>,
         cost_table: Option<CostTable>,
     ) -> anyhow::Result<()> {
+        // the trace file is relative to the current directory, not to the package root
+        let trace = match &self.trace {
+            Some(trace) => Some(std::env::current_dir()?.join(trace)),
+            None => None,
+        };
         let rerooted_path = reroot_path(path)?;
         let Self {
             gas_limit,
@@ -104,6 +117,8 @@ impl Test {
             check_stackless_vm,
             verbose_mode,
             compute_coverage,
+            trace: _,
+            profile_gas,
             #[cfg(feature = "evm-backend")]
             evm,
         } = self;
@@ -117,6 +132,8 @@ impl Test {
             check_stackless_vm,
             verbose: verbose_mode,
             ignore_compile_warnings,
+            trace_file: trace.map(|path| path.to_string_lossy().into_owned()),
+            gas_profile_dir: profile_gas.then(|| DEFAULT_GAS_PROFILE_DIR.to_string()),
             #[cfg(feature = "evm-backend")]
             evm,
 
@@ -149,16 +166,65 @@ pub enum UnitTestResult {
 
 pub fn run_move_unit_tests<W: Write + Send>(
     pkg_path: &Path,
-    mut build_config: move_package::BuildConfig,
+    build_config: move_package::BuildConfig,
     mut unit_test_config: UnitTestingConfig,
     natives: Vec<NativeFunctionRecord>,
     cost_table: Option<CostTable>,
     compute_coverage: bool,
     writer: &mut W,
 ) -> Result<UnitTestResult> {
+    let test_plan = build_test_plan(pkg_path, build_config, &mut unit_test_config, writer)?;
+    let no_tests = test_plan.module_tests.is_empty();
+
+    let trace_path = pkg_path.join(".trace");
+    let coverage_map_path = pkg_path
+        .join(".coverage_map")
+        .with_extension(MOVE_COVERAGE_MAP_EXTENSION);
+    let cleanup_trace = || {
+        if compute_coverage && trace_path.exists() {
+            std::fs::remove_file(&trace_path).unwrap();
+        }
+    };
+
+    cleanup_trace();
+
+    // If we need to compute test coverage trace the execution of the tests since we will need this
+    // trace to construct the coverage information. It replaces any trace file of the config.
+    if compute_coverage {
+        unit_test_config.trace_file = Some(trace_path.to_string_lossy().into_owned());
+    }
+
+    // Run the tests. If any of the tests fail, then we don't produce a coverage report, so cleanup
+    // the trace files.
+    if !unit_test_config
+        .run_and_report_unit_tests(test_plan, Some(natives), cost_table, writer)
+        .unwrap()
+        .1
+    {
+        cleanup_trace();
+        return Ok(UnitTestResult::Failure);
+    }
+
+    // Compute the coverage map. This will be used by other commands after this.
+    if compute_coverage && !no_tests {
+        let coverage_map = CoverageMap::from_trace_file(trace_path);
+        output_map_to_file(&coverage_map_path, &coverage_map).unwrap();
+    }
+    Ok(UnitTestResult::Success)
+}
+
+/// Builds the package at `pkg_path` in test mode and returns the plan of its unit tests. The named
+/// addresses of the package are set in `unit_test_config`.
+pub fn build_test_plan<W: Write>(
+    pkg_path: &Path,
+    mut build_config: move_package::BuildConfig,
+    unit_test_config: &mut UnitTestingConfig,
+    writer: &mut W,
+) -> Result<TestPlan> {
     let mut test_plan = None;
     build_config.test_mode = true;
     build_config.dev_mode = true;
+    let diagnostics_format = build_config.diagnostics_format;
 
     // Build the resolution graph (resolution graph diagnostics are only needed for CLI commands so
     // ignore them by passing a vector as the writer)
@@ -201,8 +267,11 @@ pub fn run_move_unit_tests<W: Write + Send>(
     // control back to the Move package system.
     build_plan.compile_with_driver(writer, |compiler| {
         let (files, comments_and_compiler_res) = compiler.run::<PASS_CFGIR>().unwrap();
-        let (_, compiler) =
-            diagnostics::unwrap_or_report_diagnostics(&files, comments_and_compiler_res);
+        let (_, compiler) = diagnostics::unwrap_or_report_diagnostics_with_format(
+            diagnostics_format,
+            &files,
+            comments_and_compiler_res,
+        );
         let (mut compiler, cfgir) = compiler.into_ast();
         let compilation_env = compiler.compilation_env();
         let built_test_plan = construct_test_plan(compilation_env, Some(root_package), &cfgir);
@@ -213,57 +282,23 @@ pub fn run_move_unit_tests<W: Write + Send>(
                 Severity::Warning
             },
         ) {
-            diagnostics::report_diagnostics(&files, diags);
+            diagnostics::report_diagnostics_with_format(diagnostics_format, &files, diags);
         }
 
         let compilation_result = compiler.at_cfgir(cfgir).build();
 
-        let (units, _) = diagnostics::unwrap_or_report_diagnostics(&files, compilation_result);
+        let (units, _) = diagnostics::unwrap_or_report_diagnostics_with_format(
+            diagnostics_format,
+            &files,
+            compilation_result,
+        );
         test_plan = Some((built_test_plan, files.clone(), units.clone()));
         Ok((files, units))
     })?;
 
     let (test_plan, mut files, units) = test_plan.unwrap();
     files.extend(dep_file_map);
-    let test_plan = test_plan.unwrap();
-    let no_tests = test_plan.is_empty();
-    let test_plan = TestPlan::new(test_plan, files, units);
-
-    let trace_path = pkg_path.join(".trace");
-    let coverage_map_path = pkg_path
-        .join(".coverage_map")
-        .with_extension(MOVE_COVERAGE_MAP_EXTENSION);
-    let cleanup_trace = || {
-        if compute_coverage && trace_path.exists() {
-            std::fs::remove_file(&trace_path).unwrap();
-        }
-    };
-
-    cleanup_trace();
-
-    // If we need to compute test coverage set the VM tracking environment variable since we will
-    // need this trace to construct the coverage information.
-    if compute_coverage {
-        std::env::set_var("MOVE_VM_TRACE", &trace_path);
-    }
-
-    // Run the tests. If any of the tests fail, then we don't produce a coverage report, so cleanup
-    // the trace files.
-    if !unit_test_config
-        .run_and_report_unit_tests(test_plan, Some(natives), cost_table, writer)
-        .unwrap()
-        .1
-    {
-        cleanup_trace();
-        return Ok(UnitTestResult::Failure);
-    }
-
-    // Compute the coverage map. This will be used by other commands after this.
-    if compute_coverage && !no_tests {
-        let coverage_map = CoverageMap::from_trace_file(trace_path);
-        output_map_to_file(&coverage_map_path, &coverage_map).unwrap();
-    }
-    Ok(UnitTestResult::Success)
+    Ok(TestPlan::new(test_plan.unwrap(), files, units))
 }
 
 impl From<UnitTestResult> for ExitStatus {
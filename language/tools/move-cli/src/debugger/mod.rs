@@ -0,0 +1,705 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A debugger of Move executions, serving a client of the Debug Adapter Protocol (DAP) such as
+//! VS Code.
+//!
+//! The `Debugger` is the tracer of the session running the execution: at each instruction, it
+//! checks whether the execution should stop, on a breakpoint or after a step, and then serves the
+//! requests of the client until it resumes the execution. Source locations, breakpoints and the
+//! names of locals come from the source maps of the executed code, and the global resources
+//! accessed by the execution are shown as it modified them, annotated by `move-resource-viewer`.
+
+pub mod protocol;
+
+use anyhow::Result;
+use move_binary_format::{
+    errors::{VMError, VMResult},
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex, TableIndex},
+};
+use move_bytecode_source_map::{line_table::SourceRange, source_map::SourceMap};
+use move_command_line_common::files::FileHash;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    resolver::MoveResolver,
+};
+use move_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValueAnnotator};
+use move_vm_runtime::tracer::{TracedFunction, TracedGlobals, Tracer};
+use move_vm_types::values::{self, Locals, Value};
+use protocol::{Connection, Request};
+use serde_json::{json, Value as Json};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{BufRead, Write},
+    rc::Rc,
+};
+
+/// The only thread of an execution
+const THREAD_ID: u64 = 1;
+
+/// The source maps of the executed code, by module, with `None` for the script
+pub type SourceMaps = BTreeMap<Option<ModuleId>, SourceMap>;
+
+/// Debugs the execution run by `execute`, which must set the debugger it is given as the tracer of
+/// its session, with the client of `connection`. The client configures and launches the execution,
+/// which is then reported to it as terminated, successfully or not.
+pub fn debug<'a, S, R, W>(
+    connection: Connection<R, W>,
+    source_maps: SourceMaps,
+    storage: &'a S,
+    execute: impl FnOnce(Rc<RefCell<Debugger<'a, S, R, W>>>) -> Result<VMResult<()>>,
+) -> Result<()>
+where
+    S: MoveResolver + ?Sized,
+    R: BufRead,
+    W: Write,
+{
+    let debugger = Rc::new(RefCell::new(Debugger::new(
+        connection,
+        source_maps,
+        storage,
+    )));
+    if !debugger.borrow_mut().configure() {
+        return Ok(());
+    }
+    let outcome = execute(debugger.clone())?;
+    let mut debugger = debugger.borrow_mut();
+    match outcome {
+        Ok(()) => debugger.terminate("Execution completed successfully", 0),
+        Err(err) => debugger.terminate(&format!("Execution failed: {}", err), 1),
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Configuring { launched: bool, configured: bool },
+    Running,
+    Paused,
+    Terminated,
+    Disconnected,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StepKind {
+    In,
+    Over,
+    Out,
+}
+
+/// A step requested from the frame at `depth`, stopped at `line`
+struct Step {
+    kind: StepKind,
+    depth: usize,
+    line: Option<(FileHash, u32)>,
+}
+
+struct Frame {
+    module_id: Option<ModuleId>,
+    function: FunctionDefinitionIndex,
+    name: String,
+    pc: CodeOffset,
+    /// The locals, as printed when the frame was last stopped or called another function
+    locals: Vec<String>,
+    /// The code offsets of the breakpoints in the function
+    breakpoints: BTreeSet<CodeOffset>,
+}
+
+#[derive(Clone)]
+struct Variable {
+    name: String,
+    value: String,
+    children: Vec<Variable>,
+}
+
+pub struct Debugger<'a, S: ?Sized, R, W> {
+    connection: Connection<R, W>,
+    source_maps: SourceMaps,
+    annotator: MoveValueAnnotator<'a, S>,
+    state: State,
+    stop_on_entry: bool,
+    /// The breakpoints of each source file, as the functions and code offsets they stop at
+    breakpoints: BTreeMap<FileHash, BTreeSet<(Option<ModuleId>, TableIndex, CodeOffset)>>,
+    step: Option<Step>,
+    frames: Vec<Frame>,
+    /// The global resources accessed by the execution, with whether it last removed them
+    accessed: BTreeMap<(AccountAddress, StructTag), bool>,
+    /// The accessed global resources, as they were when the execution last stopped
+    globals: Vec<Variable>,
+    /// The variables given to the client since the execution stopped, by reference minus one
+    variables: Vec<Vec<Variable>>,
+}
+
+impl<'a, S, R, W> Debugger<'a, S, R, W>
+where
+    S: MoveResolver + ?Sized,
+    R: BufRead,
+    W: Write,
+{
+    fn new(connection: Connection<R, W>, source_maps: SourceMaps, storage: &'a S) -> Self {
+        Self {
+            connection,
+            source_maps,
+            annotator: MoveValueAnnotator::new(storage),
+            state: State::Configuring {
+                launched: false,
+                configured: false,
+            },
+            stop_on_entry: false,
+            breakpoints: BTreeMap::new(),
+            step: None,
+            frames: vec![],
+            accessed: BTreeMap::new(),
+            globals: vec![],
+            variables: vec![],
+        }
+    }
+
+    /// Serves the requests of the client until it launches the execution, returning whether it
+    /// did before disconnecting
+    fn configure(&mut self) -> bool {
+        self.serve();
+        self.state == State::Running
+    }
+
+    /// Reports the end of the execution with `outcome` and serves the requests of the client
+    /// until it disconnects
+    fn terminate(&mut self, outcome: &str, exit_code: u64) {
+        if self.state == State::Disconnected {
+            return;
+        }
+        self.state = State::Terminated;
+        self.frames.clear();
+        let sent = self
+            .connection
+            .event(
+                "output",
+                json!({ "category": "console", "output": format!("{}\n", outcome) }),
+            )
+            .and_then(|()| {
+                self.connection
+                    .event("exited", json!({ "exitCode": exit_code }))
+            })
+            .and_then(|()| self.connection.event("terminated", json!({})));
+        match sent {
+            Ok(()) => self.serve(),
+            Err(_) => self.state = State::Disconnected,
+        }
+    }
+
+    /// Serves requests until one of them resumes the execution or the client disconnects
+    fn serve(&mut self) {
+        let initial_state = self.state;
+        while self.state == initial_state || matches!(self.state, State::Configuring { .. }) {
+            let handled = match self.connection.read_request() {
+                Ok(Some(request)) => self.handle(request),
+                Ok(None) => Err(anyhow::anyhow!("The client closed the connection")),
+                Err(err) => Err(err),
+            };
+            if handled.is_err() {
+                self.state = State::Disconnected;
+            }
+        }
+    }
+
+    fn handle(&mut self, request: Request) -> Result<()> {
+        let paused = self.state == State::Paused;
+        match request.command.as_str() {
+            "initialize" => {
+                self.connection.respond(
+                    &request,
+                    json!({ "supportsConfigurationDoneRequest": true }),
+                )?;
+                self.connection.event("initialized", json!({}))
+            }
+            "launch" | "configurationDone" => {
+                if let State::Configuring {
+                    mut launched,
+                    mut configured,
+                } = self.state
+                {
+                    if request.command == "launch" {
+                        launched = true;
+                        self.stop_on_entry = request.arguments["stopOnEntry"] == true;
+                    } else {
+                        configured = true;
+                    }
+                    self.state = if launched && configured {
+                        State::Running
+                    } else {
+                        State::Configuring {
+                            launched,
+                            configured,
+                        }
+                    };
+                }
+                self.connection.respond(&request, json!({}))
+            }
+            "setBreakpoints" => {
+                let body = self.set_breakpoints(&request.arguments);
+                self.connection.respond(&request, body)
+            }
+            "setExceptionBreakpoints" => self.connection.respond(&request, json!({})),
+            "threads" => self.connection.respond(
+                &request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+            ),
+            "stackTrace" if paused => {
+                let body = self.stack_trace();
+                self.connection.respond(&request, body)
+            }
+            "scopes" if paused => {
+                let body = self.scopes(&request.arguments);
+                self.connection.respond(&request, body)
+            }
+            "variables" if paused => {
+                let body = self.variables(&request.arguments);
+                self.connection.respond(&request, body)
+            }
+            "continue" | "next" | "stepIn" | "stepOut" if paused => {
+                let kind = match request.command.as_str() {
+                    "next" => Some(StepKind::Over),
+                    "stepIn" => Some(StepKind::In),
+                    "stepOut" => Some(StepKind::Out),
+                    _ => None,
+                };
+                self.step = kind.map(|kind| Step {
+                    kind,
+                    depth: self.frames.len(),
+                    line: self.frames.last().and_then(|frame| self.line(frame)),
+                });
+                self.state = State::Running;
+                self.variables.clear();
+                self.connection
+                    .respond(&request, json!({ "allThreadsContinued": true }))
+            }
+            "disconnect" => {
+                self.state = State::Disconnected;
+                self.connection.respond(&request, json!({}))
+            }
+            "stackTrace" | "scopes" | "variables" | "continue" | "next" | "stepIn" | "stepOut" => {
+                self.connection
+                    .respond_error(&request, "The execution is not paused")
+            }
+            command => self
+                .connection
+                .respond_error(&request, format!("Unsupported request: {}", command)),
+        }
+    }
+
+    //**********************************************************************************************
+    // Breakpoints
+    //**********************************************************************************************
+
+    /// Replaces the breakpoints of a source file by those at the requested lines, each moved to
+    /// the first line at or after it where some code starts
+    fn set_breakpoints(&mut self, arguments: &Json) -> Json {
+        let lines: Vec<u64> = arguments["breakpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|breakpoint| breakpoint["line"].as_u64())
+            .collect();
+        let contents = arguments["source"]["path"]
+            .as_str()
+            .and_then(|path| fs::read_to_string(path).ok());
+        let contents = match contents {
+            Some(contents) => contents,
+            None => {
+                let breakpoints = lines
+                    .iter()
+                    .map(|line| json!({ "verified": false, "line": line }))
+                    .collect::<Vec<_>>();
+                return json!({ "breakpoints": breakpoints });
+            }
+        };
+        let file_hash = FileHash::new(&contents);
+        let line_count = contents.lines().count() as u32;
+        let mut locations = BTreeSet::new();
+        let mut breakpoints = vec![];
+        for line in lines {
+            let resolved = (line.saturating_sub(1) as u32..line_count).find_map(|line| {
+                let mut functions = BTreeSet::new();
+                let mut line_locations = vec![];
+                for (module_id, source_map) in &self.source_maps {
+                    for (fdef_idx, offset) in source_map.line_table().code_offsets(file_hash, line)
+                    {
+                        // the first code offset of each function on the line
+                        if functions.insert((module_id, fdef_idx.0)) {
+                            line_locations.push((module_id.clone(), fdef_idx.0, offset));
+                        }
+                    }
+                }
+                (!line_locations.is_empty()).then_some((line, line_locations))
+            });
+            breakpoints.push(match resolved {
+                Some((resolved_line, line_locations)) => {
+                    locations.extend(line_locations);
+                    json!({ "verified": true, "line": resolved_line + 1 })
+                }
+                None => json!({ "verified": false, "line": line }),
+            });
+        }
+        self.breakpoints.insert(file_hash, locations);
+        for idx in 0..self.frames.len() {
+            let frame = &self.frames[idx];
+            let breakpoints = self.function_breakpoints(&frame.module_id, frame.function);
+            self.frames[idx].breakpoints = breakpoints;
+        }
+        json!({ "breakpoints": breakpoints })
+    }
+
+    fn function_breakpoints(
+        &self,
+        module_id: &Option<ModuleId>,
+        function: FunctionDefinitionIndex,
+    ) -> BTreeSet<CodeOffset> {
+        self.breakpoints
+            .values()
+            .flatten()
+            .filter(|(m, f, _)| m == module_id && *f == function.0)
+            .map(|(_, _, offset)| *offset)
+            .collect()
+    }
+
+    //**********************************************************************************************
+    // Stops
+    //**********************************************************************************************
+
+    fn source_range(&self, frame: &Frame) -> Option<SourceRange> {
+        self.source_maps
+            .get(&frame.module_id)?
+            .get_code_range(frame.function, frame.pc)
+            .ok()
+    }
+
+    fn line(&self, frame: &Frame) -> Option<(FileHash, u32)> {
+        self.source_range(frame)
+            .map(|range| (range.file_hash, range.start.line))
+    }
+
+    /// Why the execution stops before the instruction of the last frame, if it does
+    fn stop_reason(&mut self) -> Option<&'static str> {
+        let frame = self.frames.last()?;
+        if frame.breakpoints.contains(&frame.pc) {
+            return Some("breakpoint");
+        }
+        if self.stop_on_entry {
+            if self.line(frame).is_some() {
+                self.stop_on_entry = false;
+                return Some("entry");
+            }
+            return None;
+        }
+        let step = self.step.as_ref()?;
+        // steps stop at instructions with a source location
+        let line = self.line(frame)?;
+        let depth = self.frames.len();
+        let stops = match step.kind {
+            StepKind::In => depth != step.depth || Some(line) != step.line,
+            StepKind::Over => {
+                depth < step.depth || (depth == step.depth && Some(line) != step.line)
+            }
+            StepKind::Out => depth < step.depth,
+        };
+        stops.then_some("step")
+    }
+
+    fn stop(&mut self, reason: &str, globals: &mut dyn TracedGlobals) {
+        self.step = None;
+        self.globals = self.read_globals(globals);
+        self.state = State::Paused;
+        let stopped = self.connection.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        );
+        match stopped {
+            Ok(()) => self.serve(),
+            Err(_) => self.state = State::Disconnected,
+        }
+    }
+
+    fn stack_trace(&self) -> Json {
+        let frames = self
+            .frames
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(id, frame)| {
+                let mut stack_frame = json!({
+                    "id": id,
+                    "name": frame.name,
+                    "line": 0,
+                    "column": 0,
+                });
+                let location = self.source_range(frame).and_then(|range| {
+                    let source_map = self.source_maps.get(&frame.module_id)?;
+                    let path = source_map.line_table().file_path(range.file_hash)?;
+                    Some((range, path))
+                });
+                if let Some((range, path)) = location {
+                    let path = fs::canonicalize(path).unwrap_or_else(|_| path.into());
+                    stack_frame["source"] = json!({
+                        "name": path.file_name().map(|name| name.to_string_lossy()),
+                        "path": path.to_string_lossy(),
+                    });
+                    stack_frame["line"] = json!(range.start.line + 1);
+                    stack_frame["column"] = json!(range.start.column + 1);
+                    stack_frame["endLine"] = json!(range.end.line + 1);
+                    stack_frame["endColumn"] = json!(range.end.column + 1);
+                }
+                stack_frame
+            })
+            .collect::<Vec<_>>();
+        json!({ "stackFrames": frames, "totalFrames": self.frames.len() })
+    }
+
+    fn scopes(&mut self, arguments: &Json) -> Json {
+        let frame = arguments["frameId"]
+            .as_u64()
+            .and_then(|id| self.frames.get(id as usize));
+        let locals = match frame {
+            Some(frame) => self.locals(frame),
+            None => vec![],
+        };
+        let locals = self.add_variables(locals);
+        let globals = self.add_variables(self.globals.clone());
+        json!({
+            "scopes": [
+                { "name": "Locals", "variablesReference": locals, "expensive": false },
+                { "name": "Global Resources", "variablesReference": globals, "expensive": false },
+            ]
+        })
+    }
+
+    fn variables(&mut self, arguments: &Json) -> Json {
+        let variables = arguments["variablesReference"]
+            .as_u64()
+            .and_then(|reference| self.variables.get((reference as usize).checked_sub(1)?))
+            .cloned()
+            .unwrap_or_default();
+        let variables = variables
+            .into_iter()
+            .map(|variable| {
+                let reference = if variable.children.is_empty() {
+                    0
+                } else {
+                    self.add_variables(variable.children)
+                };
+                json!({
+                    "name": variable.name,
+                    "value": variable.value,
+                    "variablesReference": reference,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "variables": variables })
+    }
+
+    /// Gives `variables` to the client, returning their reference
+    fn add_variables(&mut self, variables: Vec<Variable>) -> usize {
+        self.variables.push(variables);
+        self.variables.len()
+    }
+
+    /// The locals of `frame` holding a value, named by the source map of its function
+    fn locals(&self, frame: &Frame) -> Vec<Variable> {
+        let source_map = self.source_maps.get(&frame.module_id);
+        frame
+            .locals
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.as_str() != "-")
+            .map(|(idx, value)| {
+                let name = source_map
+                    .and_then(|source_map| {
+                        source_map
+                            .get_parameter_or_local_name(frame.function, idx as u64)
+                            .ok()
+                    })
+                    .map_or_else(|| format!("local{}", idx), |(name, _)| name);
+                Variable {
+                    name,
+                    value: value.clone(),
+                    children: vec![],
+                }
+            })
+            .collect()
+    }
+
+    /// The global resources accessed by the execution, with their current values in `globals`
+    fn read_globals(&self, globals: &mut dyn TracedGlobals) -> Vec<Variable> {
+        self.accessed
+            .iter()
+            .map(|((address, tag), removed)| {
+                let name = format!("{} at {}", tag, address.to_hex_literal());
+                let resource = globals
+                    .resource(*address, tag)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|resource| {
+                        resource
+                            .map(|resource| self.view_resource(tag, &resource))
+                            .transpose()
+                    });
+                let value = match resource {
+                    Ok(Some(resource)) => return struct_variable(name, resource),
+                    Ok(None) if *removed => "<removed>".to_string(),
+                    Ok(None) => "<none>".to_string(),
+                    Err(err) => format!("<error: {}>", err),
+                };
+                Variable {
+                    name,
+                    value,
+                    children: vec![],
+                }
+            })
+            .collect()
+    }
+
+    fn view_resource(&self, tag: &StructTag, resource: &Value) -> Result<AnnotatedMoveStruct> {
+        let layout = self
+            .annotator
+            .get_type_layout_runtime(&TypeTag::Struct(Box::new(tag.clone())))?;
+        let bytes = resource
+            .simple_serialize(&layout)
+            .ok_or_else(|| anyhow::anyhow!("The resource cannot be serialized"))?;
+        self.annotator.view_resource(tag, &bytes)
+    }
+}
+
+fn struct_variable(name: String, value: AnnotatedMoveStruct) -> Variable {
+    let value_name = match &value.variant {
+        Some(variant) => format!("{}::{}", value.type_, variant),
+        None => value.type_.to_string(),
+    };
+    Variable {
+        name,
+        value: value_name,
+        children: value
+            .value
+            .into_iter()
+            .map(|(field, value)| annotated_variable(field.to_string(), value))
+            .collect(),
+    }
+}
+
+fn annotated_variable(name: String, value: AnnotatedMoveValue) -> Variable {
+    match value {
+        AnnotatedMoveValue::Struct(value) => struct_variable(name, value),
+        AnnotatedMoveValue::Vector(ty, elements) if !elements.is_empty() => Variable {
+            name,
+            value: format!("vector<{}>[{}]", ty, elements.len()),
+            children: elements
+                .into_iter()
+                .enumerate()
+                .map(|(idx, element)| annotated_variable(idx.to_string(), element))
+                .collect(),
+        },
+        value => Variable {
+            name,
+            value: value.to_string(),
+            children: vec![],
+        },
+    }
+}
+
+fn print_local(locals: &Locals, idx: usize) -> String {
+    let mut buf = String::new();
+    match values::debug::print_local(&mut buf, locals, idx) {
+        Ok(()) => buf,
+        Err(_) => "?".to_string(),
+    }
+}
+
+impl<'a, S, R, W> Tracer for Debugger<'a, S, R, W>
+where
+    S: MoveResolver + ?Sized,
+    R: BufRead,
+    W: Write,
+{
+    fn enter_function(&mut self, function: TracedFunction, _ty_args: &[TypeTag], _args: &[Value]) {
+        let module_id = function.module_id().cloned();
+        let name = match &module_id {
+            Some(module_id) => format!(
+                "{}::{}::{}",
+                module_id.address().to_hex_literal(),
+                module_id.name(),
+                function.name()
+            ),
+            None => function.name().to_string(),
+        };
+        let breakpoints = self.function_breakpoints(&module_id, function.index());
+        self.frames.push(Frame {
+            module_id,
+            function: function.index(),
+            name,
+            pc: 0,
+            locals: vec![],
+            breakpoints,
+        })
+    }
+
+    fn exit_function(&mut self, _function: TracedFunction, _return_values: &[Value]) {
+        self.frames.pop();
+    }
+
+    fn instruction(
+        &mut self,
+        function: TracedFunction,
+        pc: CodeOffset,
+        instruction: &Bytecode,
+        _stack: &[Value],
+        locals: &Locals,
+        globals: &mut dyn TracedGlobals,
+    ) {
+        if self.state != State::Running {
+            return;
+        }
+        let print_locals = || {
+            (0..function.local_count())
+                .map(|idx| print_local(locals, idx))
+                .collect()
+        };
+        if let Some(frame) = self.frames.last_mut() {
+            frame.pc = pc;
+        }
+        if let Some(reason) = self.stop_reason() {
+            if let Some(frame) = self.frames.last_mut() {
+                frame.locals = print_locals();
+            }
+            self.stop(reason, globals);
+        }
+        // the locals of callers are shown as they were when they called
+        if matches!(instruction, Bytecode::Call(_) | Bytecode::CallGeneric(_)) {
+            if let Some(frame) = self.frames.last_mut() {
+                frame.locals = print_locals();
+            }
+        }
+    }
+
+    fn read_global(&mut self, address: AccountAddress, ty: &TypeTag, _exists: bool) {
+        if let TypeTag::Struct(tag) = ty {
+            self.accessed
+                .entry((address, (**tag).clone()))
+                .or_insert(false);
+        }
+    }
+
+    fn mut_borrow_global(&mut self, address: AccountAddress, ty: &TypeTag, exists: bool) {
+        self.read_global(address, ty, exists)
+    }
+
+    fn write_global(&mut self, address: AccountAddress, ty: &TypeTag, value: Option<&Value>) {
+        if let TypeTag::Struct(tag) = ty {
+            self.accessed
+                .insert((address, (**tag).clone()), value.is_none());
+        }
+    }
+
+    fn abort(&mut self, _error: &VMError) {
+        self.frames.clear();
+    }
+}
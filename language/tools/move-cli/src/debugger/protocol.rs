@@ -0,0 +1,144 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The base protocol of the Debug Adapter Protocol: JSON messages preceded by a `Content-Length`
+//! header, exchanged with a single client.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+const CONTENT_LENGTH: &str = "Content-Length:";
+
+/// A request of the client
+#[derive(Debug)]
+pub struct Request {
+    pub seq: u64,
+    pub command: String,
+    pub arguments: Value,
+}
+
+/// The connection to a DAP client, reading its requests from `input` and writing responses and
+/// events to `output`
+pub struct Connection<R, W> {
+    input: R,
+    output: W,
+    seq: u64,
+}
+
+impl<R: BufRead, W: Write> Connection<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            seq: 0,
+        }
+    }
+
+    /// Reads the next request, or `None` if the client closed the connection. Messages of the
+    /// client other than requests are skipped.
+    pub fn read_request(&mut self) -> Result<Option<Request>> {
+        loop {
+            let mut content_length = None;
+            loop {
+                let mut header = String::new();
+                if self.input.read_line(&mut header)? == 0 {
+                    return Ok(None);
+                }
+                let header = header.trim();
+                if header.is_empty() {
+                    if content_length.is_some() {
+                        break;
+                    }
+                    continue;
+                }
+                if let Some(length) = header.strip_prefix(CONTENT_LENGTH) {
+                    content_length = Some(
+                        length
+                            .trim()
+                            .parse::<usize>()
+                            .with_context(|| format!("Invalid header: {}", header))?,
+                    );
+                }
+            }
+            let mut content = vec![0; content_length.unwrap()];
+            self.input.read_exact(&mut content)?;
+            let message: Value = serde_json::from_slice(&content)?;
+            if message["type"] != "request" {
+                continue;
+            }
+            let (seq, command) = match (message["seq"].as_u64(), message["command"].as_str()) {
+                (Some(seq), Some(command)) => (seq, command.to_string()),
+                _ => bail!("Invalid request: {}", message),
+            };
+            return Ok(Some(Request {
+                seq,
+                command,
+                arguments: message["arguments"].clone(),
+            }));
+        }
+    }
+
+    /// Responds successfully to `request` with `body`
+    pub fn respond(&mut self, request: &Request, body: Value) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "command": request.command,
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    /// Responds to `request` that it failed, with the error `message`
+    pub fn respond_error(&mut self, request: &Request, message: impl Into<String>) -> Result<()> {
+        let message = message.into();
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "command": request.command,
+            "success": false,
+            "message": message,
+            "body": { "error": { "id": 1, "format": message } },
+        }))
+    }
+
+    pub fn event(&mut self, event: &str, body: Value) -> Result<()> {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }))
+    }
+
+    fn send(&mut self, mut message: Value) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let content = serde_json::to_vec(&message)?;
+        write!(self.output, "{} {}\r\n\r\n", CONTENT_LENGTH, content.len())?;
+        self.output.write_all(&content)?;
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+/// Splits the messages written to a connection, for clients and tests
+pub fn parse_messages(mut bytes: &[u8]) -> Result<Vec<Value>> {
+    let mut messages = vec![];
+    while !bytes.is_empty() {
+        let header_end = match bytes.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) => end,
+            None => bail!("Missing the end of a header"),
+        };
+        let header = std::str::from_utf8(&bytes[..header_end])?;
+        let length = match header.trim().strip_prefix(CONTENT_LENGTH) {
+            Some(length) => length.trim().parse::<usize>()?,
+            None => bail!("Invalid header: {}", header),
+        };
+        let body = &bytes[header_end + 4..];
+        let (content, rest) = body.split_at(length.min(body.len()));
+        messages.push(serde_json::from_slice(content)?);
+        bytes = rest;
+    }
+    Ok(messages)
+}
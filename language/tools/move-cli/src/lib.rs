@@ -3,12 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use base::{
-    build::Build, coverage::Coverage, disassemble::Disassemble, docgen::Docgen, errmap::Errmap,
-    info::Info, new::New, prove::Prove, test::Test,
+    build::Build, coverage::Coverage, debug::Debug, disassemble::Disassemble, docgen::Docgen,
+    errmap::Errmap, fmt::Fmt, info::Info, new::New, prove::Prove, test::Test,
 };
 use move_package::BuildConfig;
 
 pub mod base;
+pub mod debugger;
 pub mod experimental;
 pub mod sandbox;
 
@@ -18,6 +19,9 @@ pub const DEFAULT_STORAGE_DIR: &str = "storage";
 /// Default directory for build output
 pub const DEFAULT_BUILD_DIR: &str = ".";
 
+/// Default directory where the profiles of `--profile-gas` are saved
+pub const DEFAULT_GAS_PROFILE_DIR: &str = "gas-profile";
+
 /// Extension for resource and event files, which are in BCS format
 const BCS_EXTENSION: &str = "bcs";
 
@@ -64,9 +68,11 @@ pub struct MoveCLI {
 pub enum Command {
     Build(Build),
     Coverage(Coverage),
+    Debug(Debug),
     Disassemble(Disassemble),
     Docgen(Docgen),
     Errmap(Errmap),
+    Fmt(Fmt),
     Info(Info),
     New(New),
     Prove(Prove),
@@ -104,11 +110,19 @@ pub fn run_cli(
     //         1. It's still using the old CostTable.
     //         2. The CostTable only affects sandbox runs, but not unit tests, which use a unit cost table.
     match cmd {
-        Command::Build(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::Build(c) => c.execute(
+            move_args.package_path,
+            BuildConfig {
+                verbose: move_args.verbose,
+                ..move_args.build_config
+            },
+        ),
         Command::Coverage(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::Debug(c) => c.execute(move_args.package_path, move_args.build_config, natives),
         Command::Disassemble(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Docgen(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Errmap(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::Fmt(c) => c.execute(move_args.package_path),
         Command::Info(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::New(c) => c.execute_with_defaults(move_args.package_path),
         Command::Prove(c) => c.execute(move_args.package_path, move_args.build_config),
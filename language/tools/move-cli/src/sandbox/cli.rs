@@ -7,7 +7,7 @@ use crate::{
         self,
         utils::{on_disk_state_view::OnDiskStateView, PackageContext},
     },
-    Move, NativeFunctionRecord, DEFAULT_BUILD_DIR,
+    Move, NativeFunctionRecord, DEFAULT_BUILD_DIR, DEFAULT_GAS_PROFILE_DIR,
 };
 use anyhow::Result;
 use clap::Parser;
@@ -109,6 +109,14 @@ pub enum SandboxCommand {
         /// deleted resources) will NOT be committed to disk.
         #[clap(long = "dry-run", short = 'n')]
         dry_run: bool,
+        /// Append a trace of the execution, as JSON lines, to this file.
+        #[clap(long = "trace", parse(from_os_str))]
+        trace: Option<PathBuf>,
+        /// Profile the gas used by the execution, writing the gas of its call stacks as folded
+        /// stacks for flamegraph tools, and reports of the gas of its functions, to the
+        /// `gas-profile` directory. Gas is metered even without a `gas-budget`.
+        #[clap(long = "profile-gas")]
+        profile_gas: bool,
     },
     /// Run expected value tests using the given batch file.
     #[clap(name = "exp-test")]
@@ -235,6 +243,8 @@ impl SandboxCommand {
                 type_args,
                 gas_budget,
                 dry_run,
+                trace,
+                profile_gas,
             } => {
                 let context =
                     PackageContext::new(&move_args.package_path, &move_args.build_config)?;
@@ -252,6 +262,8 @@ impl SandboxCommand {
                     type_args.to_vec(),
                     *gas_budget,
                     *dry_run,
+                    trace.as_deref(),
+                    profile_gas.then(|| Path::new(DEFAULT_GAS_PROFILE_DIR)),
                     move_args.verbose,
                 )
             }
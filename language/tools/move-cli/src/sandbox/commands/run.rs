@@ -10,21 +10,32 @@ use crate::{
     NativeFunctionRecord,
 };
 use anyhow::{anyhow, bail, Result};
-use move_binary_format::file_format::CompiledModule;
+use move_binary_format::{errors::VMResult, file_format::CompiledModule};
 use move_command_line_common::env::get_bytecode_version_from_env;
 use move_core_types::{
     account_address::AccountAddress,
     errmap::ErrorMapping,
     identifier::IdentStr,
     language_storage::TypeTag,
+    resolver::MoveResolver,
     transaction_argument::{convert_txn_args, TransactionArgument},
     value::MoveValue,
 };
 use move_package::compilation::compiled_package::CompiledPackage;
-use move_vm_runtime::move_vm::MoveVM;
+use move_vm_runtime::{
+    gas_profiler::GasProfiler,
+    move_vm::MoveVM,
+    session::{SerializedReturnValues, Session},
+    tracer::{write_trace, TraceFormat, TraceRecorder},
+};
 use move_vm_test_utils::gas_schedule::CostTable;
-use std::{fs, path::Path};
+use move_vm_types::gas::GasMeter;
+use std::{
+    fs::{self, OpenOptions},
+    path::Path,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     natives: impl IntoIterator<Item = NativeFunctionRecord>,
     cost_table: &CostTable,
@@ -38,14 +49,116 @@ pub fn run(
     vm_type_args: Vec<TypeTag>,
     gas_budget: Option<u64>,
     dry_run: bool,
+    trace_path: Option<&Path>,
+    gas_profile_dir: Option<&Path>,
     verbose: bool,
 ) -> Result<()> {
     if !script_path.exists() {
         bail!("Script file {:?} does not exist", script_path)
     };
-    let bytecode_version = get_bytecode_version_from_env();
+    let bytecode = script_bytecode(state, package, script_path)?;
+
+    let signer_addresses = signers
+        .iter()
+        .map(|s| AccountAddress::from_hex_literal(s))
+        .collect::<Result<Vec<AccountAddress>, _>>()?;
+    // TODO: parse Value's directly instead of going through the indirection of TransactionArgument?
+    let vm_args: Vec<Vec<u8>> = convert_txn_args(txn_args);
+
+    let vm = MoveVM::new(natives).unwrap();
+    // the gas used is only known when it is metered
+    let gas_budget = match gas_budget {
+        None if gas_profile_dir.is_some() => Some(u64::MAX / 1000 - 1),
+        _ => gas_budget,
+    };
+    let mut gas_status = get_gas_status(cost_table, gas_budget)?;
+    let mut trace_recorder = TraceRecorder::new();
+    let mut session = vm.new_session(state);
+    if trace_path.is_some() {
+        session.set_tracer(&mut trace_recorder);
+    }
+
+    let script_type_parameters = vec![];
+    let script_parameters = vec![];
+    let vm_args = transaction_arguments(&signer_addresses, vm_args);
+    let res = match gas_profile_dir {
+        None => execute_script_or_function(
+            &mut session,
+            &bytecode,
+            script_name_opt,
+            vm_type_args.clone(),
+            vm_args,
+            &mut gas_status,
+        )?,
+        Some(gas_profile_dir) => {
+            let mut gas_profiler = match script_name_opt {
+                Some(script_name) => {
+                    let module = CompiledModule::deserialize(&bytecode)
+                        .map_err(|e| anyhow!("Error deserializing module: {:?}", e))?;
+                    GasProfiler::new_function(gas_status, &module.self_id(), script_name)
+                }
+                None => GasProfiler::new_script(gas_status),
+            };
+            let res = execute_script_or_function(
+                &mut session,
+                &bytecode,
+                script_name_opt,
+                vm_type_args.clone(),
+                vm_args,
+                &mut gas_profiler,
+            )?;
+            let (_, gas_profile) = gas_profiler.finish();
+            gas_profile.save(gas_profile_dir)?;
+            res
+        }
+    };
+    let effects = res.map(|_| session.finish());
+
+    if let Some(trace_path) = trace_path {
+        let mut trace_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(trace_path)?;
+        write_trace(&mut trace_file, trace_recorder.events(), TraceFormat::Json)?;
+    }
+
+    match effects {
+        Err(err) => {
+            // the errors raised by the assertions with messages of the package are described by it
+            let mut error_descriptions = error_descriptions.clone();
+            for unit in package.all_modules() {
+                error_descriptions.extend(unit.unit.error_mapping())?
+            }
+            explain_execution_error(
+                &error_descriptions,
+                err,
+                state,
+                &script_type_parameters,
+                &script_parameters,
+                &vm_type_args,
+                &signer_addresses,
+                txn_args,
+            )
+        }
+        Ok(effects) => {
+            let (changeset, events) = effects.map_err(|e| e.into_vm_status())?;
+            if verbose {
+                explain_execution_effects(&changeset, &events, state)?
+            }
+            maybe_commit_effects(!dry_run, changeset, events, state)
+        }
+    }
+}
 
-    let bytecode = if is_bytecode_file(script_path) {
+/// The bytecode of the script or module at `script_path`, compiled in `package` if it is a source
+/// file
+pub(crate) fn script_bytecode(
+    state: &OnDiskStateView,
+    package: &CompiledPackage,
+    script_path: &Path,
+) -> Result<Vec<u8>> {
+    let bytecode_version = get_bytecode_version_from_env();
+    Ok(if is_bytecode_file(script_path) {
         assert!(
             state.is_module_path(script_path) || !contains_module(script_path),
             "Attempting to run module {:?} outside of the `storage/` directory.
@@ -65,23 +178,16 @@ move run` must be applied to a module inside `storage/`",
             Some(unit) => unit.unit.serialize(bytecode_version),
             None => bail!("Unable to find script in file {:?}", script_path),
         }
-    };
-
-    let signer_addresses = signers
-        .iter()
-        .map(|s| AccountAddress::from_hex_literal(s))
-        .collect::<Result<Vec<AccountAddress>, _>>()?;
-    // TODO: parse Value's directly instead of going through the indirection of TransactionArgument?
-    let vm_args: Vec<Vec<u8>> = convert_txn_args(txn_args);
-
-    let vm = MoveVM::new(natives).unwrap();
-    let mut gas_status = get_gas_status(cost_table, gas_budget)?;
-    let mut session = vm.new_session(state);
+    })
+}
 
-    let script_type_parameters = vec![];
-    let script_parameters = vec![];
+/// The arguments of a transaction sent by `signer_addresses`, followed by `vm_args`
+pub(crate) fn transaction_arguments(
+    signer_addresses: &[AccountAddress],
+    vm_args: Vec<Vec<u8>>,
+) -> Vec<Vec<u8>> {
     // TODO rethink move-cli arguments for executing functions
-    let vm_args = signer_addresses
+    signer_addresses
         .iter()
         .map(|a| {
             MoveValue::Signer(*a)
@@ -89,44 +195,32 @@ move run` must be applied to a module inside `storage/`",
                 .expect("transaction arguments must serialize")
         })
         .chain(vm_args)
-        .collect();
-    let res = match script_name_opt {
+        .collect()
+}
+
+/// Executes the script `bytecode`, or the entry function `script_name_opt` of the module
+/// `bytecode` if set
+pub(crate) fn execute_script_or_function<S: MoveResolver>(
+    session: &mut Session<S>,
+    bytecode: &[u8],
+    script_name_opt: &Option<String>,
+    vm_type_args: Vec<TypeTag>,
+    vm_args: Vec<Vec<u8>>,
+    gas_meter: &mut impl GasMeter,
+) -> Result<VMResult<SerializedReturnValues>> {
+    Ok(match script_name_opt {
         Some(script_name) => {
             // script fun. parse module, extract script ID to pass to VM
-            let module = CompiledModule::deserialize(&bytecode)
+            let module = CompiledModule::deserialize(bytecode)
                 .map_err(|e| anyhow!("Error deserializing module: {:?}", e))?;
             session.execute_entry_function(
                 &module.self_id(),
                 IdentStr::new(script_name)?,
-                vm_type_args.clone(),
+                vm_type_args,
                 vm_args,
-                &mut gas_status,
+                gas_meter,
             )
         }
-        None => session.execute_script(
-            bytecode.to_vec(),
-            vm_type_args.clone(),
-            vm_args,
-            &mut gas_status,
-        ),
-    };
-
-    if let Err(err) = res {
-        explain_execution_error(
-            error_descriptions,
-            err,
-            state,
-            &script_type_parameters,
-            &script_parameters,
-            &vm_type_args,
-            &signer_addresses,
-            txn_args,
-        )
-    } else {
-        let (changeset, events) = session.finish().map_err(|e| e.into_vm_status())?;
-        if verbose {
-            explain_execution_effects(&changeset, &events, state)?
-        }
-        maybe_commit_effects(!dry_run, changeset, events, state)
-    }
+        None => session.execute_script(bytecode.to_vec(), vm_type_args, vm_args, gas_meter),
+    })
 }
@@ -477,7 +477,10 @@ pub(crate) fn explain_execution_error(
                 println!(
                     " Abort code details:\nName: {}\nDescription:{}",
                     error_desc.code_name, error_desc.code_description,
-                )
+                );
+                if let Some(location) = error_desc.location {
+                    println!("Location: {}", location)
+                }
             } else {
                 println!()
             }
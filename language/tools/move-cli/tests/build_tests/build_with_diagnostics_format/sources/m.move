@@ -0,0 +1,9 @@
+module 0x42::m {
+    fun f(v: u64): u64 {
+        let y = 0;
+        let x = &mut v;
+        let w = v;
+        *x = 1;
+        w
+    }
+}
@@ -0,0 +1,18 @@
+module 0x42::m {
+    public fun foo(x: u64): u64 {
+        while (true) {
+            if (x == 0) break;
+            x = x - 1;
+        };
+        x
+    }
+
+    #[allow(lint(while_true))]
+    public fun bar(x: u64): u64 {
+        while (true) {
+            if (x == 0) break;
+            x = x - 1;
+        };
+        x
+    }
+}
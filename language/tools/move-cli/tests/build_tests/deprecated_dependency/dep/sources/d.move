@@ -0,0 +1,10 @@
+module A::d {
+    #[deprecated(note = b"Use 'd::new_limit' instead")]
+    public fun limit(): u64 {
+        new_limit()
+    }
+
+    public fun new_limit(): u64 {
+        10
+    }
+}
@@ -0,0 +1,3 @@
+module 0x42::formatted {
+  public fun one(): u64 { 1 }
+}
@@ -0,0 +1,7 @@
+/// A module
+module 0x42::m {
+    // The answer
+    const ANSWER:u64=42;
+
+    public fun answer( ):u64{ANSWER}
+}
@@ -0,0 +1,5 @@
+#[test_only]
+module 0x42::m_tests {
+    #[test]
+    fun test_answer() { assert!(0x42::m::answer()==42, 0) }
+}
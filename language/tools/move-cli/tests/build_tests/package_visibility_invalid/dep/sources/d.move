@@ -0,0 +1,5 @@
+module A::d {
+    public(package) fun internal(): u64 {
+        0
+    }
+}
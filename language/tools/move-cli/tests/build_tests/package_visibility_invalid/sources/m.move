@@ -0,0 +1,7 @@
+module A::m {
+    use A::d;
+
+    public fun f(): u64 {
+        d::internal()
+    }
+}
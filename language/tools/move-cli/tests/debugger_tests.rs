@@ -0,0 +1,246 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_cli::{
+    base::debug::DebugTarget,
+    debugger::protocol::{parse_messages, Connection},
+};
+use move_core_types::account_address::AccountAddress;
+use move_package::BuildConfig;
+use move_stdlib::natives::{all_natives, GasParameters};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+fn package_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/debugger_tests/Debugged")
+}
+
+/// Debugs the unit test `name` of the package with the client sending `requests`, returning the
+/// messages it receives
+fn debug_test(name: &str, requests: &[(&str, Value)]) -> Vec<Value> {
+    let mut input = vec![];
+    for (seq, (command, arguments)) in requests.iter().enumerate() {
+        let request = json!({
+            "seq": seq + 1,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        })
+        .to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", request.len(), request).bytes());
+    }
+    let mut output = vec![];
+    let install_dir = tempfile::tempdir().unwrap();
+    let config = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    DebugTarget::Test {
+        name: name.to_string(),
+    }
+    .debug(
+        &package_path(),
+        config,
+        all_natives(AccountAddress::ONE, GasParameters::zeros()),
+        Connection::new(input.as_slice(), &mut output),
+    )
+    .unwrap();
+    parse_messages(&output).unwrap()
+}
+
+fn response(messages: &[Value], request_seq: usize) -> &Value {
+    messages
+        .iter()
+        .find(|message| message["type"] == "response" && message["request_seq"] == request_seq)
+        .unwrap()
+}
+
+fn events<'a>(messages: &'a [Value], event: &'a str) -> impl Iterator<Item = &'a Value> {
+    messages
+        .iter()
+        .filter(move |message| message["type"] == "event" && message["event"] == event)
+}
+
+/// The function and line of each frame of a `stackTrace` response, from the top
+fn frames(response: &Value) -> Vec<(String, u64)> {
+    response["body"]["stackFrames"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|frame| {
+            (
+                frame["name"].as_str().unwrap().to_string(),
+                frame["line"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// The names and values of the variables of a `variables` response
+fn variables(response: &Value) -> Vec<(String, String)> {
+    response["body"]["variables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|variable| {
+            (
+                variable["name"].as_str().unwrap().to_string(),
+                variable["value"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn breakpoints_and_steps() {
+    let source = package_path().join("sources/M.move");
+    let messages = debug_test(
+        "publish",
+        &[
+            ("initialize", json!({ "adapterID": "move" })),
+            ("launch", json!({})),
+            (
+                "setBreakpoints",
+                json!({
+                    "source": { "path": source },
+                    "breakpoints": [{ "line": 11 }, { "line": 100 }],
+                }),
+            ),
+            ("configurationDone", json!({})),
+            // stopped at the breakpoint
+            ("stackTrace", json!({ "threadId": 1 })),
+            ("stepIn", json!({ "threadId": 1 })),
+            ("stackTrace", json!({ "threadId": 1 })),
+            ("scopes", json!({ "frameId": 1 })),
+            ("variables", json!({ "variablesReference": 1 })),
+            ("stepOut", json!({ "threadId": 1 })),
+            ("next", json!({ "threadId": 1 })),
+            ("next", json!({ "threadId": 1 })),
+            ("stackTrace", json!({ "threadId": 1 })),
+            ("scopes", json!({ "frameId": 0 })),
+            ("variables", json!({ "variablesReference": 1 })),
+            ("variables", json!({ "variablesReference": 2 })),
+            ("variables", json!({ "variablesReference": 3 })),
+            ("continue", json!({ "threadId": 1 })),
+            ("disconnect", json!({})),
+        ],
+    );
+
+    assert_eq!(
+        response(&messages, 3)["body"]["breakpoints"],
+        json!([{ "verified": true, "line": 11 }, { "verified": false, "line": 100 }])
+    );
+    let reasons = events(&messages, "stopped")
+        .map(|event| event["body"]["reason"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(reasons, vec!["breakpoint", "step", "step", "step", "step"]);
+
+    assert_eq!(
+        frames(response(&messages, 5)),
+        vec![("0x42::M::publish".to_string(), 11)]
+    );
+    assert_eq!(
+        frames(response(&messages, 7)),
+        vec![
+            ("0x42::M::double".to_string(), 5),
+            ("0x42::M::publish".to_string(), 11)
+        ]
+    );
+    assert_eq!(
+        variables(response(&messages, 9)),
+        vec![("x".to_string(), "3".to_string())]
+    );
+
+    // after stepping out of `double` and over the call and `move_to`
+    assert_eq!(
+        frames(response(&messages, 13)),
+        vec![("0x42::M::publish".to_string(), 13)]
+    );
+    // `v` is moved into the resource
+    assert_eq!(
+        variables(response(&messages, 15)),
+        vec![(
+            "s".to_string(),
+            format!(
+                "{{ {} }}",
+                AccountAddress::from_hex_literal("0x42").unwrap()
+            )
+        )]
+    );
+    assert_eq!(
+        variables(response(&messages, 16)),
+        vec![("0x42::M::R at 0x42".to_string(), "0x42::M::R".to_string())]
+    );
+    assert_eq!(
+        variables(response(&messages, 17)),
+        vec![("v".to_string(), "6".to_string())]
+    );
+
+    let exit_codes = events(&messages, "exited")
+        .map(|event| event["body"]["exitCode"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(exit_codes, vec![json!(0)]);
+    assert_eq!(events(&messages, "terminated").count(), 1);
+    assert_eq!(response(&messages, 19)["success"], true);
+}
+
+#[test]
+fn globals_as_modified() {
+    let source = package_path().join("sources/M.move");
+    let messages = debug_test(
+        "update",
+        &[
+            ("initialize", json!({})),
+            ("launch", json!({})),
+            (
+                "setBreakpoints",
+                json!({
+                    "source": { "path": source },
+                    "breakpoints": [{ "line": 20 }],
+                }),
+            ),
+            ("configurationDone", json!({})),
+            ("scopes", json!({ "frameId": 0 })),
+            ("variables", json!({ "variablesReference": 2 })),
+            ("variables", json!({ "variablesReference": 3 })),
+            ("continue", json!({ "threadId": 1 })),
+            ("disconnect", json!({})),
+        ],
+    );
+    assert_eq!(
+        variables(response(&messages, 6)),
+        vec![("0x42::M::R at 0x42".to_string(), "0x42::M::R".to_string())]
+    );
+    // the resource is shown as written through `borrow_global_mut`, not as published
+    assert_eq!(
+        variables(response(&messages, 7)),
+        vec![("v".to_string(), "2".to_string())]
+    );
+}
+
+#[test]
+fn requests_outside_of_stops() {
+    let messages = debug_test(
+        "M::publish",
+        &[
+            ("initialize", json!({})),
+            ("launch", json!({ "stopOnEntry": true })),
+            ("configurationDone", json!({})),
+            ("stackTrace", json!({ "threadId": 1 })),
+            ("continue", json!({ "threadId": 1 })),
+            ("stackTrace", json!({ "threadId": 1 })),
+            ("evaluate", json!({ "expression": "v" })),
+        ],
+    );
+    let reasons = events(&messages, "stopped")
+        .map(|event| event["body"]["reason"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(reasons, vec!["entry"]);
+    assert_eq!(
+        frames(response(&messages, 4)),
+        vec![("0x42::M::publish".to_string(), 11)]
+    );
+    // the execution has terminated
+    assert_eq!(response(&messages, 6)["success"], false);
+    assert_eq!(response(&messages, 7)["success"], false);
+}
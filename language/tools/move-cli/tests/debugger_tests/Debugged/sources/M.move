@@ -0,0 +1,22 @@
+module 0x42::M {
+    struct R has key { v: u64 }
+
+    fun double(x: u64): u64 {
+        let y = x * 2;
+        y
+    }
+
+    #[test(s = @0x42)]
+    fun publish(s: signer) acquires R {
+        let v = double(3);
+        move_to(&s, R { v });
+        assert!(borrow_global<R>(@0x42).v == 6, 0);
+    }
+
+    #[test(s = @0x42)]
+    fun update(s: signer) acquires R {
+        move_to(&s, R { v: 1 });
+        borrow_global_mut<R>(@0x42).v = 2;
+        assert!(borrow_global<R>(@0x42).v == 2, 0);
+    }
+}
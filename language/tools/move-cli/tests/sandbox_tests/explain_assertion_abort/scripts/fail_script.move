@@ -0,0 +1,7 @@
+script {
+    use 0x2::Fail;
+
+    fun main() {
+        Fail::f(1)
+    }
+}
@@ -0,0 +1,5 @@
+module 0x2::Fail {
+    public fun f(x: u64) {
+        assert_eq!(x, 0, b"x must be zero");
+    }
+}
@@ -0,0 +1,19 @@
+module 0x42::Fib {
+    public fun fib(n: u64): u64 {
+        if (n < 2) n else fib(n - 1) + fib(n - 2)
+    }
+
+    public fun square(n: u64): u64 {
+        n * n
+    }
+
+    #[test]
+    fun test_fib() {
+        assert!(fib(4) == 3, 0);
+    }
+
+    #[test]
+    fun test_square() {
+        assert!(square(3) == 9, 0);
+    }
+}
@@ -0,0 +1,7 @@
+script {
+    use 0x42::Fib;
+
+    fun main() {
+        Fib::square(Fib::fib(3));
+    }
+}
@@ -0,0 +1,5 @@
+module 0x2::m {
+    public fun f() {
+        assert!(0x2::n::helper() == 42, 0);
+    }
+}
@@ -0,0 +1,5 @@
+module 0x2::n {
+    public(package) fun helper(): u64 {
+        42
+    }
+}
@@ -8,28 +8,18 @@ TRACE_PATH=$HOME/trace
 
 [ ! -e  "$TRACE_PATH" ] || rm -f "$TRACE_PATH"
 
-export MOVE_VM_TRACE=$TRACE_PATH
-
 echo "Rebuilding stdlib..."
 pushd ../../../diem-move/diem-framework || exit 1
 cargo run
 popd || exit 1
 
-# TODO: add coverage for transactional tests
+# TODO: add coverage for transactional tests and the e2e testsuite, which cannot be given a trace
+# file
 
 echo "---------------------------------------------------------------------------"
-echo "Running e2e testsuite..."
+echo "Running Move unit tests..."
 echo "---------------------------------------------------------------------------"
-pushd ../../e2e-testsuite || exit 1
-cargo test -- --skip account_universe --skip fuzz_scripts
-popd || exit 1
-
-echo "---------------------------------------------------------------------------"
-echo "Running Move testsuite..."
-echo "---------------------------------------------------------------------------"
-pushd ../../move-compiler/functional-tests/tests || exit 1
-cargo test
-popd || exit 1
+cargo run --bin move -- test --path ../../../diem-move/diem-framework/core --trace "$TRACE_PATH"
 
 echo "---------------------------------------------------------------------------"
 echo "Building Move modules and source maps.."
@@ -57,6 +47,4 @@ echo "You can can also get a finer-grained coverage summary for each function by
 echo "> cargo run --bin coverage-summaries -- -t trace.mvcov -s ../../../diem-move/diem-framework/DPN/releases/artifacts/current/stdlib.mv"
 echo "==========================================================================="
 
-unset MOVE_VM_TRACE
-
 echo "DONE"
@@ -10,11 +10,12 @@ use move_core_types::{
     account_address::AccountAddress,
     identifier::{IdentStr, Identifier},
 };
+use move_vm_runtime::tracer::{read_trace, TraceEvent, TraceFunction};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
-    fs::File,
-    io::{BufRead, BufReader, Read, Write},
+    fs::{self, File},
+    io::{Read, Write},
     path::Path,
 };
 
@@ -63,29 +64,17 @@ impl CoverageMap {
         mut self,
         filename: P,
     ) -> Self {
-        let file = File::open(&filename)
-            .unwrap_or_else(|_| panic!("Unable to open coverage trace file '{:?}'", filename));
-        for line in BufReader::new(file).lines() {
-            let line = line.unwrap();
-            let mut splits = line.split(',');
-            let exec_id = splits.next().unwrap();
-            let context = splits.next().unwrap();
-            let pc = splits.next().unwrap().parse::<u64>().unwrap();
-
-            let mut context_segs: Vec<_> = context.split("::").collect();
-            let is_script = context_segs.len() == 2;
-            if !is_script {
-                let func_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_addr =
-                    AccountAddress::from_hex_literal(context_segs.pop().unwrap()).unwrap();
-                self.insert(exec_id, module_addr, module_name, func_name, pc);
-            } else {
-                // Don't count scripts (for now)
-                assert_eq!(context_segs.pop().unwrap(), "main",);
-                assert_eq!(context_segs.pop().unwrap(), "Script",);
-            }
-        }
+        let first_exec_id = self.exec_maps.len();
+        for_each_traced_instruction(filename, first_exec_id, |exec_id, function, pc| {
+            let module_id = function.module_id.as_ref().unwrap();
+            self.insert(
+                exec_id,
+                *module_id.address(),
+                module_id.name().to_owned(),
+                function.name.clone(),
+                pc,
+            )
+        });
         self
     }
 
@@ -272,34 +261,23 @@ impl ExecCoverageMapWithModules {
 
 impl TraceMap {
     /// Takes in a file containing a raw VM trace, and returns an updated coverage map.
-    pub fn update_from_trace_file<P: AsRef<Path>>(mut self, filename: P) -> Self {
-        let file = File::open(filename).unwrap();
-        for line in BufReader::new(file).lines() {
-            let line = line.unwrap();
-            let mut splits = line.split(',');
-            let exec_id = splits.next().unwrap();
-            let context = splits.next().unwrap();
-            let pc = splits.next().unwrap().parse::<u64>().unwrap();
-
-            let mut context_segs: Vec<_> = context.split("::").collect();
-            let is_script = context_segs.len() == 2;
-            if !is_script {
-                let func_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_addr =
-                    AccountAddress::from_hex_literal(context_segs.pop().unwrap()).unwrap();
-                self.insert(exec_id, module_addr, module_name, func_name, pc);
-            } else {
-                // Don't count scripts (for now)
-                assert_eq!(context_segs.pop().unwrap(), "main",);
-                assert_eq!(context_segs.pop().unwrap(), "Script",);
-            }
-        }
+    pub fn update_from_trace_file<P: AsRef<Path> + std::fmt::Debug>(mut self, filename: P) -> Self {
+        let first_exec_id = self.exec_maps.len();
+        for_each_traced_instruction(filename, first_exec_id, |exec_id, function, pc| {
+            let module_id = function.module_id.as_ref().unwrap();
+            self.insert(
+                exec_id,
+                *module_id.address(),
+                module_id.name().to_owned(),
+                function.name.clone(),
+                pc,
+            )
+        });
         self
     }
 
     // Takes in a file containing a raw VM trace, and returns a parsed trace.
-    pub fn from_trace_file<P: AsRef<Path>>(filename: P) -> Self {
+    pub fn from_trace_file<P: AsRef<Path> + std::fmt::Debug>(filename: P) -> Self {
         let trace_map = TraceMap {
             exec_maps: BTreeMap::new(),
         };
@@ -341,6 +319,48 @@ impl TraceMap {
     }
 }
 
+/// Reads a VM trace file, written by `move_vm_runtime::tracer::write_trace`, and calls `f` with the
+/// id of the execution, the function and the offset of each instruction executed in a module
+/// function. Each call from outside the VM is a new execution, numbered from `first_exec_id`.
+fn for_each_traced_instruction<P: AsRef<Path> + std::fmt::Debug>(
+    filename: P,
+    first_exec_id: usize,
+    mut f: impl FnMut(&str, &TraceFunction, u64),
+) {
+    let bytes = fs::read(&filename)
+        .unwrap_or_else(|_| panic!("Unable to open coverage trace file '{:?}'", filename));
+    let events = read_trace(&bytes)
+        .unwrap_or_else(|e| panic!("Unable to read coverage trace file '{:?}': {}", filename, e));
+    let mut exec_id = first_exec_id;
+    let mut call_stack: Vec<TraceFunction> = vec![];
+    for event in events {
+        match event {
+            TraceEvent::EnterFunction { function, .. } => {
+                if call_stack.is_empty() {
+                    exec_id += 1;
+                }
+                call_stack.push(function)
+            }
+            TraceEvent::ExitFunction { .. } => {
+                call_stack.pop();
+            }
+            TraceEvent::Abort { .. } => call_stack.clear(),
+            TraceEvent::Instruction { pc, .. } => match call_stack.last() {
+                // Don't count scripts (for now)
+                Some(function) if function.module_id.is_some() => {
+                    f(&exec_id.to_string(), function, pc as u64)
+                }
+                _ => (),
+            },
+            TraceEvent::ReadGlobal { .. }
+            | TraceEvent::MutBorrowGlobal { .. }
+            | TraceEvent::WriteGlobal { .. }
+            | TraceEvent::NativeCall { .. }
+            | TraceEvent::EmitEvent { .. } => (),
+        }
+    }
+}
+
 pub fn output_map_to_file<M: Serialize, P: AsRef<Path>>(file_name: P, data: &M) -> Result<()> {
     let bytes = bcs::to_bytes(data)?;
     let mut file = File::create(file_name)?;
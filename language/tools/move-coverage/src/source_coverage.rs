@@ -5,7 +5,7 @@
 #![forbid(unsafe_code)]
 
 use crate::coverage_map::CoverageMap;
-use codespan::{Files, Span};
+use codespan::Span;
 use colored::*;
 use move_binary_format::{
     access::ModuleAccess,
@@ -13,6 +13,7 @@ use move_binary_format::{
     CompiledModule,
 };
 use move_bytecode_source_map::source_map::SourceMap;
+use move_command_line_common::files::FileHash;
 use move_core_types::identifier::Identifier;
 use move_ir_types::location::Loc;
 use serde::Serialize;
@@ -138,24 +139,28 @@ impl<'a> SourceCoverageBuilder<'a> {
             self.source_map.check(&file_contents),
             "File contents out of sync with source map"
         );
-        let mut files = Files::new();
-        let file_id = files.add(file_path.as_os_str().to_os_string(), file_contents.clone());
+        // Source maps that were built without a line table are indexed from the file here
+        let file_hash = FileHash::new(&file_contents);
+        let mut line_table = self.source_map.line_table().clone();
+        if !line_table.contains_file(file_hash) {
+            line_table.add_file(file_hash, file_path.to_string_lossy(), &file_contents);
+        }
 
         let mut uncovered_segments = BTreeMap::new();
 
         for (_, fn_cov) in self.uncovered_locations.iter() {
             for span in merge_spans(fn_cov.clone()).into_iter() {
-                let start_loc = files.location(file_id, span.start()).unwrap();
-                let end_loc = files.location(file_id, span.end()).unwrap();
-                let start_line = start_loc.line.0;
-                let end_line = end_loc.line.0;
+                let start_loc = line_table.position(file_hash, span.start().0).unwrap();
+                let end_loc = line_table.position(file_hash, span.end().0).unwrap();
+                let start_line = start_loc.line;
+                let end_line = end_loc.line;
                 let segments = uncovered_segments
                     .entry(start_line)
                     .or_insert_with(Vec::new);
                 if start_line == end_line {
                     let segment = AbstractSegment::Bounded {
-                        start: start_loc.column.0,
-                        end: end_loc.column.0,
+                        start: start_loc.column,
+                        end: end_loc.column,
                     };
                     // TODO: There is some issue with the source map where we have multiple spans
                     // from different functions. This can be seen in the source map for `Roles.move`
@@ -164,7 +169,7 @@ impl<'a> SourceCoverageBuilder<'a> {
                     }
                 } else {
                     segments.push(AbstractSegment::BoundedLeft {
-                        start: start_loc.column.0,
+                        start: start_loc.column,
                     });
                     for i in start_line + 1..end_line {
                         let segment = uncovered_segments.entry(i).or_insert_with(Vec::new);
@@ -172,7 +177,7 @@ impl<'a> SourceCoverageBuilder<'a> {
                     }
                     let last_segment = uncovered_segments.entry(end_line).or_insert_with(Vec::new);
                     last_segment.push(AbstractSegment::BoundedRight {
-                        end: end_loc.column.0,
+                        end: end_loc.column,
                     });
                 }
             }
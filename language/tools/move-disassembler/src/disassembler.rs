@@ -14,12 +14,13 @@ use move_binary_format::{
         Ability, AbilitySet, Bytecode, CodeUnit, FieldHandleIndex, FunctionDefinition,
         FunctionDefinitionIndex, FunctionHandle, ModuleHandle, Signature, SignatureIndex,
         SignatureToken, StructDefinition, StructDefinitionIndex, StructFieldInformation,
-        StructTypeParameter, TableIndex, TypeSignature, Visibility,
+        StructTypeParameter, StructVariantHandleIndex, TableIndex, TypeSignature,
+        VariantDefinition, VariantFieldHandleIndex, VariantIndex, Visibility,
     },
 };
 use move_bytecode_source_map::{
     mapping::SourceMapping,
-    source_map::{FunctionSourceMap, SourceName},
+    source_map::{FunctionSourceMap, SourceName, StructSourceMap},
 };
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule, NamedCompiledScript};
 use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
@@ -305,6 +306,11 @@ impl<'a> Disassembler<'a> {
             StructFieldInformation::Native => {
                 return Err(format_err!("Attempt to access field on a native struct"));
             }
+            StructFieldInformation::DeclaredVariants(_) => {
+                return Err(format_err!(
+                    "Attempt to access field on a struct with variants"
+                ));
+            }
             StructFieldInformation::Declared(fields) => fields
                 .get(field_handle.field as usize)
                 .ok_or_else(|| format_err!("Bad field index"))?,
@@ -336,6 +342,11 @@ impl<'a> Disassembler<'a> {
             StructFieldInformation::Native => {
                 return Err(format_err!("Attempt to access field on a native struct"));
             }
+            StructFieldInformation::DeclaredVariants(_) => {
+                return Err(format_err!(
+                    "Attempt to access field on a struct with variants"
+                ));
+            }
             StructFieldInformation::Declared(fields) => fields
                 .get(field_handle.field as usize)
                 .ok_or_else(|| format_err!("Bad field index"))?,
@@ -349,6 +360,71 @@ impl<'a> Disassembler<'a> {
         Ok(ty)
     }
 
+    fn name_for_variant(
+        &self,
+        struct_idx: StructDefinitionIndex,
+        variant: VariantIndex,
+    ) -> Result<(String, &VariantDefinition)> {
+        let struct_def = self.source_mapper.bytecode.struct_def_at(struct_idx)?;
+        let variant_def = struct_def
+            .variant(variant)
+            .ok_or_else(|| format_err!("Bad variant index"))?;
+        let variant_name = self
+            .source_mapper
+            .bytecode
+            .identifier_at(variant_def.name)
+            .to_string();
+        Ok((variant_name, variant_def))
+    }
+
+    fn variant_type_info(
+        &self,
+        handle_idx: StructVariantHandleIndex,
+        signature: &Signature,
+        type_param_context: &[SourceName],
+    ) -> Result<String> {
+        let handle = self
+            .source_mapper
+            .bytecode
+            .struct_variant_handle_at(handle_idx)?;
+        let (name, ty_params) =
+            self.struct_type_info(handle.struct_index, signature, type_param_context)?;
+        let (variant_name, _) = self.name_for_variant(handle.struct_index, handle.variant)?;
+        Ok(format!("{}{}::{}", name, ty_params, variant_name))
+    }
+
+    fn variant_field_info(
+        &self,
+        handle_idx: VariantFieldHandleIndex,
+        signature: &Signature,
+        type_param_context: &[SourceName],
+    ) -> Result<String> {
+        let handle = self
+            .source_mapper
+            .bytecode
+            .variant_field_handle_at(handle_idx)?;
+        let (name, ty_params) =
+            self.struct_type_info(handle.owner, signature, type_param_context)?;
+        let (variant_name, variant_def) = self.name_for_variant(handle.owner, handle.variant)?;
+        let field_def = variant_def
+            .fields
+            .get(handle.field as usize)
+            .ok_or_else(|| format_err!("Bad field index"))?;
+        let field_name = self.source_mapper.bytecode.identifier_at(field_def.name);
+        let struct_source_info = self
+            .source_mapper
+            .source_map
+            .get_struct_source_map(handle.owner)?;
+        let ty = self.disassemble_sig_tok(
+            field_def.signature.0.clone(),
+            &struct_source_info.type_parameters,
+        )?;
+        Ok(format!(
+            "{}{}::{}.{}: {}",
+            name, ty_params, variant_name, field_name, ty
+        ))
+    }
+
     fn struct_type_info(
         &self,
         struct_idx: StructDefinitionIndex,
@@ -922,6 +998,77 @@ impl<'a> Disassembler<'a> {
                     Self::format_ret_type(&type_rets)
                 ))
             }
+            Bytecode::PackVariant(idx)
+            | Bytecode::UnpackVariant(idx)
+            | Bytecode::TestVariant(idx) => {
+                let info = self.variant_type_info(
+                    *idx,
+                    &Signature(vec![]),
+                    &function_source_map.type_parameters,
+                )?;
+                let op = match instruction {
+                    Bytecode::PackVariant(_) => "PackVariant",
+                    Bytecode::UnpackVariant(_) => "UnpackVariant",
+                    _ => "TestVariant",
+                };
+                Ok(format!("{}[{}]({})", op, idx, info))
+            }
+            Bytecode::PackVariantGeneric(idx)
+            | Bytecode::UnpackVariantGeneric(idx)
+            | Bytecode::TestVariantGeneric(idx) => {
+                let inst = self
+                    .source_mapper
+                    .bytecode
+                    .struct_variant_instantiation_at(*idx)?;
+                let type_params = self
+                    .source_mapper
+                    .bytecode
+                    .signature_at(inst.type_parameters);
+                let info = self.variant_type_info(
+                    inst.handle,
+                    type_params,
+                    &function_source_map.type_parameters,
+                )?;
+                let op = match instruction {
+                    Bytecode::PackVariantGeneric(_) => "PackVariantGeneric",
+                    Bytecode::UnpackVariantGeneric(_) => "UnpackVariantGeneric",
+                    _ => "TestVariantGeneric",
+                };
+                Ok(format!("{}[{}]({})", op, idx, info))
+            }
+            Bytecode::MutBorrowVariantField(idx) | Bytecode::ImmBorrowVariantField(idx) => {
+                let info = self.variant_field_info(
+                    *idx,
+                    &Signature(vec![]),
+                    &function_source_map.type_parameters,
+                )?;
+                let op = match instruction {
+                    Bytecode::MutBorrowVariantField(_) => "MutBorrowVariantField",
+                    _ => "ImmBorrowVariantField",
+                };
+                Ok(format!("{}[{}]({})", op, idx, info))
+            }
+            Bytecode::MutBorrowVariantFieldGeneric(idx)
+            | Bytecode::ImmBorrowVariantFieldGeneric(idx) => {
+                let inst = self
+                    .source_mapper
+                    .bytecode
+                    .variant_field_instantiation_at(*idx)?;
+                let type_params = self
+                    .source_mapper
+                    .bytecode
+                    .signature_at(inst.type_parameters);
+                let info = self.variant_field_info(
+                    inst.handle,
+                    type_params,
+                    &function_source_map.type_parameters,
+                )?;
+                let op = match instruction {
+                    Bytecode::MutBorrowVariantFieldGeneric(_) => "MutBorrowVariantFieldGeneric",
+                    _ => "ImmBorrowVariantFieldGeneric",
+                };
+                Ok(format!("{}[{}]({})", op, idx, info))
+            }
             // All other instructions are OK to be printed using the standard debug print.
             x => Ok(format!("{:#?}", x)),
         }
@@ -1177,6 +1324,13 @@ impl<'a> Disassembler<'a> {
         let field_info: Option<Vec<(&IdentStr, &TypeSignature)>> =
             match &struct_definition.field_information {
                 StructFieldInformation::Native => None,
+                StructFieldInformation::DeclaredVariants(variants) => {
+                    return self.disassemble_variants_def(
+                        struct_definition,
+                        struct_source_map,
+                        variants,
+                    )
+                }
                 StructFieldInformation::Declared(fields) => Some(
                     fields
                         .iter()
@@ -1194,16 +1348,7 @@ impl<'a> Disassembler<'a> {
 
         let native = if field_info.is_none() { "native " } else { "" };
 
-        let abilities = if struct_handle.abilities == AbilitySet::EMPTY {
-            String::new()
-        } else {
-            let ability_vec: Vec<_> = struct_handle
-                .abilities
-                .into_iter()
-                .map(Self::format_ability)
-                .collect();
-            format!(" has {}", ability_vec.join(", "))
-        };
+        let abilities = Self::format_abilities(struct_handle.abilities);
 
         let name = self
             .source_mapper
@@ -1245,6 +1390,66 @@ impl<'a> Disassembler<'a> {
         ))
     }
 
+    fn disassemble_variants_def(
+        &self,
+        struct_definition: &StructDefinition,
+        struct_source_map: &StructSourceMap,
+        variants: &[VariantDefinition],
+    ) -> Result<String> {
+        let struct_handle = self
+            .source_mapper
+            .bytecode
+            .struct_handle_at(struct_definition.struct_handle);
+        let name = self
+            .source_mapper
+            .bytecode
+            .identifier_at(struct_handle.name)
+            .to_string();
+        let ty_params = Self::disassemble_struct_type_formals(
+            &struct_source_map.type_parameters,
+            &struct_handle.type_parameters,
+        );
+        let variants = variants
+            .iter()
+            .map(|variant| {
+                let variant_name = self.source_mapper.bytecode.identifier_at(variant.name);
+                let fields = variant
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let field_name = self.source_mapper.bytecode.identifier_at(field.name);
+                        let ty_str = self.disassemble_sig_tok(
+                            field.signature.0.clone(),
+                            &struct_source_map.type_parameters,
+                        )?;
+                        Ok(format!("{}: {}", field_name, ty_str))
+                    })
+                    .collect::<Result<Vec<String>>>()?;
+                Ok(if fields.is_empty() {
+                    variant_name.to_string()
+                } else {
+                    format!("{} {{ {} }}", variant_name, fields.join(", "))
+                })
+            })
+            .collect::<Result<Vec<String>>>()?;
+        Ok(format!(
+            "enum {name}{ty_params}{abilities} {{\n\t{variants}\n}}",
+            name = name,
+            ty_params = ty_params,
+            abilities = Self::format_abilities(struct_handle.abilities),
+            variants = variants.join(",\n\t"),
+        ))
+    }
+
+    fn format_abilities(abilities: AbilitySet) -> String {
+        if abilities == AbilitySet::EMPTY {
+            String::new()
+        } else {
+            let ability_vec: Vec<_> = abilities.into_iter().map(Self::format_ability).collect();
+            format!(" has {}", ability_vec.join(", "))
+        }
+    }
+
     pub fn disassemble(&self) -> Result<String> {
         let name_opt = self.source_mapper.source_map.module_name_opt.as_ref();
         let name = name_opt.map(|(addr, n)| format!("{}.{}", addr.short_str_lossless(), n));
@@ -40,17 +40,21 @@ fn main() {
     );
 
     let errmap_bytes = std::fs::read(&args.errmap_path).expect("Could not load errmap from file");
-    let errmap: ErrorMapping =
-        bcs::from_bytes(&errmap_bytes).expect("Failed to deserialize errmap");
+    let errmap = ErrorMapping::from_bytes(&errmap_bytes).expect("Failed to deserialize errmap");
 
     match errmap.get_explanation(&module_id, args.abort_code) {
         None => println!(
             "Unable to find a description for {}::{}",
             args.location, args.abort_code
         ),
-        Some(error_desc) => println!(
-            "Name: {}\nDescription: {}",
-            error_desc.code_name, error_desc.code_description,
-        ),
+        Some(error_desc) => {
+            println!(
+                "Name: {}\nDescription: {}",
+                error_desc.code_name, error_desc.code_description,
+            );
+            if let Some(location) = error_desc.location {
+                println!("Location: {}", location)
+            }
+        }
     }
 }
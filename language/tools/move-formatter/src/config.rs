@@ -0,0 +1,53 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::path::Path;
+
+/// The name of the file at the root of a package that configures the formatter.
+pub const CONFIG_FILE_NAME: &str = "movefmt.toml";
+
+/// Formatter settings, e.g.
+///
+/// ```toml
+/// max_width = 100
+/// indent = 4
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatConfig {
+    /// The maximum width of a line. Longer lines are split at delimited lists where possible.
+    pub max_width: usize,
+    /// The number of spaces per indentation level.
+    pub indent: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            max_width: 100,
+            indent: 4,
+        }
+    }
+}
+
+impl FormatConfig {
+    /// Reads the configuration of the package at `package_root`, using the defaults if the
+    /// package has no configuration file.
+    pub fn load(package_root: &Path) -> anyhow::Result<Self> {
+        let path = package_root.join(CONFIG_FILE_NAME);
+        if path.is_file() {
+            Self::from_file(&path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read '{}'", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Invalid formatter configuration in '{}'", path.display()))
+    }
+}
@@ -0,0 +1,645 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A source code formatter for Move.
+//!
+//! The formatter works on the tokens produced by the compiler's lexer, so any file that lexes can
+//! be formatted, even if it does not parse. Comments, which the lexer skips, are recovered from
+//! the text between tokens and kept where they are.
+//!
+//! Line breaks are left to the author: the formatter keeps the lines of its input, and normalizes
+//! the indentation, the spacing between tokens and the blank lines. Lines longer than the maximum
+//! width are split at the outermost delimited list (arguments, parameters, fields, ...) around the
+//! overflow, putting each element of the list on its own line.
+
+mod config;
+
+pub use config::{FormatConfig, CONFIG_FILE_NAME};
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    diagnostics::Diagnostics,
+    parser::{
+        comments::{find_comments, verify_string, CommentKind},
+        lexer::{Lexer, Tok},
+    },
+};
+use std::collections::BTreeSet;
+
+/// Contextual keywords of the specification language that are followed by an expression, e.g.
+/// `ensures *r == 0`
+const SPEC_KEYWORDS: &[&str] = &[
+    "aborts_if",
+    "aborts_with",
+    "assume",
+    "axiom",
+    "decreases",
+    "emits",
+    "ensures",
+    "include",
+    "modifies",
+    "requires",
+    "succeeds_if",
+];
+
+/// Formats the Move source `source`, failing with the lexer's diagnostics if it contains
+/// characters or tokens that are not valid Move.
+pub fn format(source: &str, config: &FormatConfig) -> Result<String, Diagnostics> {
+    let tokens = tokenize(source)?;
+    let mut breaks = BTreeSet::new();
+    loop {
+        let layout = Printer::layout(&tokens, &breaks, config);
+        if !layout.break_long_lines(&tokens, config, &mut breaks) {
+            return Ok(layout.render());
+        }
+    }
+}
+
+//**************************************************************************************************
+// Tokens
+//**************************************************************************************************
+
+struct Token<'a> {
+    tok: Tok,
+    content: &'a str,
+    start: usize,
+    end: usize,
+    role: Role,
+    /// The index of the matching delimiter, for delimiters that are matched
+    partner: Option<usize>,
+    /// The comments and line breaks between the previous token and this one
+    trivia: Vec<Trivia<'a>>,
+}
+
+enum Trivia<'a> {
+    Newlines(usize),
+    Comment {
+        text: &'a str,
+        kind: CommentKind,
+        /// The column the comment starts at in the input
+        column: usize,
+    },
+}
+
+/// The role of a token whose spacing depends on where it appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Plain,
+    /// `<` opening type arguments or parameters
+    OpenAngle,
+    /// `>` (or `>>`) closing type arguments or parameters
+    CloseAngle,
+    /// `|` opening lambda parameters
+    OpenPipe,
+    /// `|` closing lambda parameters
+    ClosePipe,
+    /// `&`, `&mut`, `*` or `!` applied to the operand that follows
+    Prefix,
+    /// `!` of a macro call
+    Macro,
+    /// `{` of a `use` list
+    OpenUseBrace,
+    /// `}` of a `use` list
+    CloseUseBrace,
+    /// `..` between two operands
+    Range,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, Diagnostics> {
+    let file_hash = FileHash::new(source);
+    verify_string(file_hash, source)?;
+    let mut lexer = Lexer::new(source, file_hash);
+    let mut tokens = vec![];
+    let mut prev_end = 0;
+    loop {
+        lexer
+            .advance()
+            .map_err(|diag| Diagnostics::from(vec![*diag]))?;
+        let start = lexer.start_loc();
+        let content = lexer.content();
+        tokens.push(Token {
+            tok: lexer.peek(),
+            content,
+            start,
+            end: start + content.len(),
+            role: Role::Plain,
+            partner: None,
+            trivia: trivia(source, prev_end, start),
+        });
+        if lexer.peek() == Tok::EOF {
+            break;
+        }
+        prev_end = start + content.len();
+    }
+    classify(&mut tokens);
+    Ok(tokens)
+}
+
+fn trivia(source: &str, start: usize, end: usize) -> Vec<Trivia<'_>> {
+    fn newlines<'a>(trivia: &mut Vec<Trivia<'a>>, whitespace: &str) {
+        let count = whitespace.matches('\n').count();
+        if count > 0 {
+            trivia.push(Trivia::Newlines(count))
+        }
+    }
+
+    let gap = &source[start..end];
+    let mut trivia = vec![];
+    let mut pos = 0;
+    for (comment_start, comment_end, kind) in find_comments(gap) {
+        newlines(&mut trivia, &gap[pos..comment_start]);
+        let offset = start + comment_start;
+        let column = offset - source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        trivia.push(Trivia::Comment {
+            text: &gap[comment_start..comment_end],
+            kind,
+            column,
+        });
+        pos = comment_end;
+    }
+    newlines(&mut trivia, &gap[pos..]);
+    trivia
+}
+
+fn is_spec_keyword(token: &Token) -> bool {
+    token.tok == Tok::Identifier && SPEC_KEYWORDS.contains(&token.content)
+}
+
+/// Whether an operand can end with `token`, e.g. to tell a borrow `&x` from a bitwise and `a & b`
+fn ends_operand(token: &Token) -> bool {
+    match token.tok {
+        Tok::Identifier => !is_spec_keyword(token),
+        Tok::NumValue
+        | Tok::NumTypedValue
+        | Tok::ByteStringValue
+        | Tok::True
+        | Tok::False
+        | Tok::RParen
+        | Tok::RBracket
+        | Tok::RBrace => true,
+        _ => token.role == Role::CloseAngle,
+    }
+}
+
+/// Assigns roles to the tokens, and matches up the delimiters.
+fn classify(tokens: &mut [Token]) {
+    // The delimiters that are still open, as indices into `tokens`
+    let mut open: Vec<usize> = vec![];
+    for i in 0..tokens.len() {
+        let prev = i.checked_sub(1).map(|j| &tokens[j]);
+        let prev_tok = prev.map(|prev| prev.tok);
+        let follows_operand = prev.map_or(false, ends_operand);
+        let adjacent = prev.map_or(false, |prev| prev.end == tokens[i].start);
+        let role = match tokens[i].tok {
+            Tok::LParen | Tok::LBracket => {
+                open.push(i);
+                Role::Plain
+            }
+            Tok::LBrace if prev_tok == Some(Tok::ColonColon) => {
+                open.push(i);
+                Role::OpenUseBrace
+            }
+            Tok::LBrace => {
+                open.push(i);
+                Role::Plain
+            }
+            Tok::RParen | Tok::RBracket | Tok::RBrace => {
+                // Type arguments and lambda parameters are closed before any other delimiter
+                while matches!(
+                    innermost(tokens, &open, 1),
+                    Some(Role::OpenAngle | Role::OpenPipe)
+                ) {
+                    open.pop();
+                }
+                match open.pop() {
+                    Some(j) => {
+                        tokens[j].partner = Some(i);
+                        tokens[i].partner = Some(j);
+                        if tokens[j].role == Role::OpenUseBrace {
+                            Role::CloseUseBrace
+                        } else {
+                            Role::Plain
+                        }
+                    }
+                    None => Role::Plain,
+                }
+            }
+            Tok::Less if adjacent && matches!(prev_tok, Some(Tok::Identifier | Tok::Invariant)) => {
+                open.push(i);
+                Role::OpenAngle
+            }
+            Tok::Greater if innermost(tokens, &open, 1) == Some(Role::OpenAngle) => {
+                let j = open.pop().unwrap();
+                tokens[j].partner = Some(i);
+                tokens[i].partner = Some(j);
+                Role::CloseAngle
+            }
+            // `>>` closing two lists of type arguments, as in `vector<vector<u8>>`
+            Tok::GreaterGreater
+                if innermost(tokens, &open, 1) == Some(Role::OpenAngle)
+                    && innermost(tokens, &open, 2) == Some(Role::OpenAngle) =>
+            {
+                let inner = open.pop().unwrap();
+                let outer = open.pop().unwrap();
+                tokens[inner].partner = Some(i);
+                tokens[outer].partner = Some(i);
+                tokens[i].partner = Some(outer);
+                Role::CloseAngle
+            }
+            Tok::Pipe if innermost(tokens, &open, 1) == Some(Role::OpenPipe) => {
+                let j = open.pop().unwrap();
+                tokens[j].partner = Some(i);
+                tokens[i].partner = Some(j);
+                Role::ClosePipe
+            }
+            Tok::Pipe if !follows_operand => {
+                open.push(i);
+                Role::OpenPipe
+            }
+            Tok::Amp | Tok::AmpMut | Tok::Star if !follows_operand => Role::Prefix,
+            Tok::Exclaim
+                if prev.map_or(false, |prev| {
+                    prev.tok == Tok::Identifier && !is_spec_keyword(prev)
+                }) =>
+            {
+                Role::Macro
+            }
+            Tok::Exclaim => Role::Prefix,
+            Tok::PeriodPeriod if follows_operand => Role::Range,
+            _ => Role::Plain,
+        };
+        tokens[i].role = role;
+    }
+}
+
+/// The role of the `depth`-th innermost open delimiter
+fn innermost(tokens: &[Token], open: &[usize], depth: usize) -> Option<Role> {
+    let j = open.len().checked_sub(depth)?;
+    Some(tokens[open[j]].role)
+}
+
+/// Whether `prev` and `next`, on the same line, are separated by a space
+fn space_between(prev: &Token, next: &Token) -> bool {
+    preferred_space(prev, next) || would_merge(prev, next)
+}
+
+/// Whether `prev` and `next` would be lexed differently without a space between them, as in
+/// `| |` or `& &`
+fn would_merge(prev: &Token, next: &Token) -> bool {
+    let text = format!("{}{}", prev.content.trim_end(), next.content);
+    let mut lexer = Lexer::new(&text, FileHash::new(&text));
+    lexer.advance().is_err() || lexer.content() != prev.content.trim_end()
+}
+
+fn preferred_space(prev: &Token, next: &Token) -> bool {
+    use Tok::*;
+    match (prev.role, next.role) {
+        (Role::Prefix, _) => return prev.tok == AmpMut,
+        (Role::OpenAngle | Role::OpenPipe | Role::Macro | Role::OpenUseBrace | Role::Range, _)
+        | (
+            _,
+            Role::OpenAngle
+            | Role::CloseAngle
+            | Role::ClosePipe
+            | Role::Macro
+            | Role::CloseUseBrace
+            | Role::Range,
+        ) => return false,
+        _ => (),
+    }
+    match (prev.tok, next.tok) {
+        (LParen | LBracket | ColonColon | Period | AtSign | NumSign, _)
+        | (_, RParen | RBracket | Comma | Semicolon | Colon | ColonColon | Period)
+        | (LBrace, RBrace) => false,
+        (Identifier, LParen | LBracket) => is_spec_keyword(prev),
+        (Public, LParen) | (RParen | RBracket, LBracket) => false,
+        (_, LParen | LBracket) => prev.role != Role::CloseAngle,
+        _ => true,
+    }
+}
+
+/// Whether a statement or expression that ends a line with `token` continues on the next one
+fn continues(tokens: &[Token], index: usize) -> bool {
+    let token = &tokens[index];
+    match token.tok {
+        Tok::Semicolon | Tok::Comma | Tok::LParen | Tok::LBracket | Tok::LBrace | Tok::RBrace => {
+            false
+        }
+        // the end of an attribute
+        Tok::RBracket => !matches!(
+            token.partner.and_then(|j| j.checked_sub(1)),
+            Some(j) if tokens[j].tok == Tok::NumSign
+        ),
+        _ => !matches!(token.role, Role::OpenAngle | Role::OpenPipe),
+    }
+}
+
+//**************************************************************************************************
+// Layout
+//**************************************************************************************************
+
+/// The formatted lines, and where each token ended up
+struct Layout {
+    lines: Vec<String>,
+    /// The line, start column and end column of each token
+    positions: Vec<(usize, usize, usize)>,
+}
+
+struct Line {
+    text: String,
+    /// The number of delimiters open at the start of the line, or `None` for lines that do not
+    /// start with a token or comment
+    depth: Option<usize>,
+    /// The indentation level of the line
+    indent: usize,
+}
+
+/// A delimiter that is open at the current position
+struct Frame {
+    token: usize,
+    /// The indentation level of the line the delimiter is anchored to. Its contents are indented
+    /// one level more, and its closing delimiter is indented the same when it starts a line.
+    indent: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Last {
+    Token(usize),
+    Comment,
+}
+
+struct Printer<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    config: &'t FormatConfig,
+    lines: Vec<Line>,
+    current: Option<Line>,
+    frames: Vec<Frame>,
+    /// The last token or comment on the current line
+    last: Option<Last>,
+    /// The last token printed
+    prev: Option<usize>,
+    /// Whether the input has a blank line before what is printed next
+    blank_line: bool,
+    /// Whether the last thing printed is an opening delimiter
+    after_open: bool,
+    positions: Vec<(usize, usize, usize)>,
+}
+
+impl<'t, 'a> Printer<'t, 'a> {
+    /// Lays out `tokens`, starting a new line before each token in `breaks` in addition to the
+    /// line breaks of the input.
+    fn layout(
+        tokens: &'t [Token<'a>],
+        breaks: &BTreeSet<usize>,
+        config: &'t FormatConfig,
+    ) -> Layout {
+        let mut printer = Printer {
+            tokens,
+            config,
+            lines: vec![],
+            current: None,
+            frames: vec![],
+            last: None,
+            prev: None,
+            blank_line: false,
+            after_open: false,
+            positions: vec![(0, 0, 0); tokens.len()],
+        };
+        for (i, token) in tokens.iter().enumerate() {
+            for trivia in &token.trivia {
+                match trivia {
+                    Trivia::Newlines(count) => {
+                        printer.end_line();
+                        printer.blank_line |= *count > 1;
+                    }
+                    Trivia::Comment { text, kind, column } => printer.comment(text, *kind, *column),
+                }
+            }
+            if token.tok == Tok::EOF {
+                break;
+            }
+            if breaks.contains(&i) {
+                printer.end_line();
+            }
+            printer.token(i);
+        }
+        printer.end_line();
+        Layout {
+            lines: printer.lines.into_iter().map(|line| line.text).collect(),
+            positions: printer.positions,
+        }
+    }
+
+    fn end_line(&mut self) {
+        if let Some(line) = self.current.take() {
+            self.lines.push(line);
+            self.last = None;
+        }
+    }
+
+    fn start_line(&mut self, indent: usize, closes: bool) {
+        // Blank lines are kept, except at the start of the file and of a delimited list, and at
+        // its end
+        if self.blank_line && !self.lines.is_empty() && !self.after_open && !closes {
+            self.lines.push(Line {
+                text: String::new(),
+                depth: None,
+                indent: 0,
+            });
+        }
+        self.blank_line = false;
+        self.current = Some(Line {
+            text: " ".repeat(indent * self.config.indent),
+            depth: Some(self.frames.len()),
+            indent,
+        });
+    }
+
+    /// The indentation level of the contents of the innermost open delimiter
+    fn base_indent(&self) -> usize {
+        self.frames.last().map_or(0, |frame| frame.indent + 1)
+    }
+
+    /// The indentation level of the latest line that starts with at most `depth` open delimiters
+    fn anchor_indent(&self, depth: usize) -> usize {
+        self.current
+            .iter()
+            .chain(self.lines.iter().rev())
+            .find(|line| line.depth.map_or(false, |line_depth| line_depth <= depth))
+            .map_or(0, |line| line.indent)
+    }
+
+    fn comment(&mut self, text: &str, kind: CommentKind, column: usize) {
+        match &mut self.current {
+            None => self.start_line(self.base_indent(), false),
+            Some(line) => {
+                if !line.text.ends_with(['(', '[']) {
+                    line.text.push(' ')
+                }
+            }
+        }
+        let new_column = self.current.as_ref().unwrap().text.len();
+        let mut comment_lines = text.split('\n');
+        let first = comment_lines.next().unwrap().trim_end();
+        self.current.as_mut().unwrap().text.push_str(first);
+        // The lines of a block comment keep their alignment relative to its start
+        for comment_line in comment_lines {
+            let comment_line = comment_line.trim_end();
+            let whitespace = comment_line.len() - comment_line.trim_start().len();
+            let text = if new_column >= column {
+                " ".repeat(new_column - column) + comment_line
+            } else {
+                comment_line[whitespace.min(column - new_column)..].to_string()
+            };
+            self.end_line();
+            self.current = Some(Line {
+                text,
+                depth: None,
+                indent: 0,
+            });
+        }
+        self.last = Some(Last::Comment);
+        self.after_open = false;
+        if kind == CommentKind::Line {
+            self.end_line()
+        }
+    }
+
+    fn token(&mut self, index: usize) {
+        let token = &self.tokens[index];
+        let closes = token.partner.map_or(false, |partner| partner < index);
+        match (&self.current, self.last) {
+            (None, _) => {
+                let indent = if closes {
+                    let partner = token.partner.unwrap();
+                    self.frames
+                        .iter()
+                        .rev()
+                        .find(|frame| frame.token == partner)
+                        .map_or_else(|| self.base_indent(), |frame| frame.indent)
+                } else {
+                    let in_block = self
+                        .frames
+                        .last()
+                        .map_or(true, |frame| self.tokens[frame.token].tok == Tok::LBrace);
+                    let continued = in_block
+                        && token.tok != Tok::LBrace
+                        && self.prev.map_or(false, |prev| continues(self.tokens, prev));
+                    self.base_indent() + continued as usize
+                };
+                self.start_line(indent, closes)
+            }
+            (Some(_), Some(Last::Token(prev))) if !space_between(&self.tokens[prev], token) => (),
+            (Some(_), Some(Last::Comment))
+                if matches!(
+                    token.tok,
+                    Tok::RParen | Tok::RBracket | Tok::Comma | Tok::Semicolon
+                ) => {}
+            (Some(_), _) => self.current.as_mut().unwrap().text.push(' '),
+        }
+
+        let line = self.current.as_mut().unwrap();
+        let start = line.text.len();
+        // `&mut` is lexed with the space that follows it
+        line.text.push_str(token.content.trim_end());
+        self.positions[index] = (self.lines.len(), start, line.text.len());
+        self.last = Some(Last::Token(index));
+        self.prev = Some(index);
+        self.after_open = false;
+
+        if let Some(partner) = token.partner {
+            if closes {
+                while let Some(frame) = self.frames.pop() {
+                    if frame.token == partner {
+                        break;
+                    }
+                }
+            } else {
+                let indent = self.anchor_indent(self.frames.len());
+                self.frames.push(Frame {
+                    token: index,
+                    indent,
+                });
+                self.after_open = true;
+            }
+        }
+    }
+}
+
+impl Layout {
+    /// Adds line breaks to `breaks` to split the lines that are too long, returning whether any
+    /// were added.
+    fn break_long_lines(
+        &self,
+        tokens: &[Token],
+        config: &FormatConfig,
+        breaks: &mut BTreeSet<usize>,
+    ) -> bool {
+        let mut changed = false;
+        let mut line_tokens = vec![vec![]; self.lines.len()];
+        for (i, (line, _, _)) in self.positions.iter().enumerate() {
+            if tokens[i].tok != Tok::EOF {
+                line_tokens[*line].push(i);
+            }
+        }
+        for (line, indices) in line_tokens.iter().enumerate() {
+            let width = indices.last().map_or(0, |i| self.positions[*i].2);
+            if width <= config.max_width {
+                continue;
+            }
+            // The outermost non-empty delimited lists on the line
+            let mut groups = vec![];
+            let mut i = indices[0];
+            while i <= *indices.last().unwrap() {
+                match tokens[i].partner {
+                    Some(j)
+                        if j > i + 1
+                            && self.positions[j].0 == line
+                            && !matches!(tokens[i].role, Role::OpenAngle | Role::OpenPipe) =>
+                    {
+                        groups.push((i, j));
+                        i = j + 1;
+                    }
+                    _ => i += 1,
+                }
+            }
+            let group = groups
+                .iter()
+                .find(|(_, close)| self.positions[*close].2 > config.max_width)
+                .or_else(|| groups.last());
+            let (open, close) = match group {
+                Some(group) => *group,
+                None => continue,
+            };
+            changed |= breaks.insert(open + 1);
+            let mut i = open + 1;
+            while i < close {
+                match tokens[i].partner {
+                    Some(j) if j > i => i = j,
+                    _ => {
+                        let separates = match tokens[i].tok {
+                            Tok::Comma => true,
+                            Tok::Semicolon => tokens[open].tok == Tok::LBrace,
+                            _ => false,
+                        };
+                        if separates && i + 1 < close {
+                            changed |= breaks.insert(i + 1);
+                        }
+                    }
+                }
+                i += 1;
+            }
+            changed |= breaks.insert(close);
+        }
+        changed
+    }
+
+    fn render(self) -> String {
+        let mut output = String::new();
+        for line in self.lines {
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
@@ -0,0 +1,78 @@
+module 0x42::basic{
+use std::vector ;
+use 0x42::other::{Self,Thing as T};
+  friend 0x42::friendly;
+
+const MAX : u64=100;
+const BYTES: vector<u8> = b"abc";
+
+
+
+struct Coin<phantom C> has key,store{value:u64}
+struct Pair<T1:copy+drop, T2> has copy , drop { first: T1, second: T2 }
+
+public(friend) fun new<C>(value:u64):Coin<C>{
+Coin<C>{value}
+}
+
+public fun value<C>(coin:&Coin<C>):u64 { coin.value }
+
+fun arithmetic(a: u64,b: u64): (u64, u64) {
+    let sum=a+b*2-(a%3)/1;
+    let shifted = (a<<2)>>1 ^ b|a&b;
+    let cmp = a < b || a>=b && !(a == b) && a != b;
+    if(cmp){ (sum,shifted) }else{ (0 , 0) }
+}
+
+fun borrows(v: &mut vector<u64>, r: &u64): u64 {
+    let x = *r;
+    *vector::borrow_mut(v,0) = x;
+    let y = &mut x;
+    *y = *y+1;
+    let z = *&x;
+    z
+}
+
+fun nested(): vector<vector<u8>> {
+    let v = vector<vector<u8>>[b"a", x"0b"];
+    let w: vector<u64> = vector[1,2,3];
+    vector::push_back(&mut v, vector::empty<u8>());
+    v
+}
+
+fun loops(n: u64) {
+    let i = 0;
+    while (i < n) {
+        if (i == 5) break;
+        i = i + 1
+    };
+    'outer: loop {
+        if (i > 10) break 'outer else continue 'outer
+    };
+    assert!(i>10, 0);
+    let Pair { first, second: _ } = Pair { first: 1, second: 2 };
+    (first as u128);
+    abort 0
+}
+
+inline fun apply(v: &vector<u64>, f: |u64| u64): u64 {
+    f(*vector::borrow(v, 0))
+}
+
+fun uses_lambda(v: &vector<u64>): u64 {
+    apply(v, |x| x+1) + apply(v, |x| { let y = x; y * 2 })
+}
+
+fun continued(a: u64, b: u64): bool {
+    let total = a +
+    b;
+    if (a > 0 &&
+    b > 0) {
+    return true
+    };
+    total > 0
+}
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_loops() { loops(3) }
+}
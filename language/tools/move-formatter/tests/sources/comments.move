@@ -0,0 +1,29 @@
+// A file comment
+
+/// The module documentation
+module 0x42::comments {
+    /// Documentation
+    /// on two lines
+    struct S { f: u64 } // trailing comment
+
+        /**
+         * A block documentation comment,
+         *   indented by hand
+         */
+    fun f(/* no arguments */): u64 {
+        // a comment before a statement
+
+
+        let x = 1; /* inline */ let y = /* inside */ 2;
+        /* nested /* comment */ */
+        x + y
+        // a comment before the closing brace
+    }
+
+    fun g() {
+    // misindented comment
+        /* one
+           two */ f();
+    }
+}
+// A comment at the end of the file
@@ -0,0 +1,13 @@
+module 0x42::long_lines {
+    use 0x42::some_module::{first_function, second_function, third_function};
+
+    struct Config has copy, drop { width: u64, height: u64, depth: u64 }
+
+    fun make(width: u64, height: u64, depth: u64): Config { Config { width, height, depth } }
+
+    fun call(): u64 { add(multiply(1000000, 2000000), multiply(3000000, 4000000)) }
+
+    fun keep_short(): u64 { 1 }
+
+    fun unbreakable(): u64 { this_is_a_very_long_identifier_without_any_delimiters }
+}
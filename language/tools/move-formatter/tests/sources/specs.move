@@ -0,0 +1,27 @@
+module 0x42::specs {
+    struct R has key { v: u64 }
+
+    fun get(addr: address): u64 acquires R { borrow_global<R>(addr).v }
+    spec get {
+        pragma opaque;
+        aborts_if !exists<R>(addr);
+        ensures result==global<R>(addr).v;
+    }
+
+    spec module {
+        invariant forall a: address where exists<R>(a): global<R>(a).v>0;
+    }
+
+    fun sum(v: &vector<u64>): u64 {
+        let s = 0;
+        let i = 0;
+        while ({
+            spec { invariant i <= len(v); };
+            i < std::vector::length(v)
+        }) {
+            s = s + *std::vector::borrow(v, i);
+            i = i + 1;
+        };
+        s
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::testing::EXP_EXT;
+use move_formatter::{format, FormatConfig};
+use move_prover_test_utils::baseline_test::verify_or_update_baseline;
+use std::path::Path;
+
+// Formats the file, with the configuration in the `.toml` file next to it if there is one
+fn test_runner(path: &Path) -> datatest_stable::Result<()> {
+    let config_path = path.with_extension("toml");
+    let config = if config_path.is_file() {
+        FormatConfig::from_file(&config_path)?
+    } else {
+        FormatConfig::default()
+    };
+    let source = std::fs::read_to_string(path)?;
+    let formatted = match format(&source, &config) {
+        Ok(formatted) => formatted,
+        Err(_) => panic!("could not format {}", path.display()),
+    };
+    assert_eq!(
+        format(&formatted, &config).ok().as_deref(),
+        Some(formatted.as_str()),
+        "formatting is not idempotent"
+    );
+    let baseline_path = path.with_extension(EXP_EXT);
+    verify_or_update_baseline(baseline_path.as_path(), &formatted)?;
+    Ok(())
+}
+
+datatest_stable::harness!(test_runner, "tests/sources", r".*\.move");
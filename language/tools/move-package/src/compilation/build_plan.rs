@@ -3,13 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    compilation::compiled_package::CompiledPackage, resolution::resolution_graph::ResolvedGraph,
+    compilation::compiled_package::{CompiledPackage, CompilerDriver},
+    resolution::resolution_graph::ResolvedGraph,
     source_package::parsed_manifest::PackageName,
 };
 use anyhow::Result;
 use move_compiler::{
     compiled_unit::AnnotatedCompiledUnit,
-    diagnostics::{report_diagnostics_to_color_buffer, report_warnings, FilesSourceText},
+    diagnostics::{
+        report_diagnostics_to_buffer_with_format, report_diagnostics_to_color_buffer,
+        report_diagnostics_with_format, DiagnosticsFormat, FilesSourceText,
+    },
     Compiler,
 };
 use petgraph::algo::toposort;
@@ -102,32 +106,42 @@ impl BuildPlan {
         })
     }
 
-    /// Compilation results in the process exit upon warning/failure
+    /// Compilation results in the process exit upon warning/failure. Only the source files that
+    /// changed since the last build, and the files affected by them, are recompiled
     pub fn compile<W: Write>(&self, writer: &mut W) -> Result<CompiledPackage> {
-        self.compile_with_driver(writer, |compiler| compiler.build_and_report())
+        let format = self.resolution_graph.build_options.diagnostics_format;
+        self.build(
+            writer,
+            CompilerDriver::Incremental(&|files, error_diags| {
+                report_diagnostics_with_format(format, files, error_diags)
+            }),
+        )
     }
 
     /// Compilation process does not exit even if warnings/failures are encountered
     pub fn compile_no_exit<W: Write>(&self, writer: &mut W) -> Result<CompiledPackage> {
-        self.compile_with_driver(writer, |compiler| {
-            let (files, units_res) = compiler.build()?;
-            match units_res {
-                Ok((units, warning_diags)) => {
-                    report_warnings(&files, warning_diags);
-                    Ok((files, units))
-                }
-                Err(error_diags) => {
-                    assert!(!error_diags.is_empty());
-                    let diags_buf = report_diagnostics_to_color_buffer(&files, error_diags);
-                    if let Err(err) = std::io::stdout().write_all(&diags_buf) {
-                        anyhow::bail!("Cannot output compiler diagnostics: {}", err);
+        let format = self.resolution_graph.build_options.diagnostics_format;
+        self.build(
+            writer,
+            CompilerDriver::Incremental(&|files, error_diags| {
+                assert!(!error_diags.is_empty());
+                let diags_buf = match format {
+                    DiagnosticsFormat::Human => {
+                        report_diagnostics_to_color_buffer(files, error_diags)
                     }
-                    anyhow::bail!("Compilation error");
+                    DiagnosticsFormat::Json | DiagnosticsFormat::Sarif => {
+                        report_diagnostics_to_buffer_with_format(format, files, error_diags)
+                    }
+                };
+                if let Err(err) = std::io::stdout().write_all(&diags_buf) {
+                    return anyhow::anyhow!("Cannot output compiler diagnostics: {}", err);
                 }
-            }
-        })
+                anyhow::anyhow!("Compilation error")
+            }),
+        )
     }
 
+    /// Compiles all source files of the package and its dependencies with `compiler_driver`
     pub fn compile_with_driver<W: Write>(
         &self,
         writer: &mut W,
@@ -135,6 +149,14 @@ impl BuildPlan {
             Compiler,
         )
             -> anyhow::Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)>,
+    ) -> Result<CompiledPackage> {
+        self.build(writer, CompilerDriver::Custom(&mut compiler_driver))
+    }
+
+    fn build<W: Write>(
+        &self,
+        writer: &mut W,
+        compiler_driver: CompilerDriver,
     ) -> Result<CompiledPackage> {
         let root_package = &self.resolution_graph.package_table[&self.root];
         let project_root = match &self.resolution_graph.build_options.install_dir {
@@ -170,7 +192,7 @@ impl BuildPlan {
             root_package.clone(),
             transitive_dependencies,
             &self.resolution_graph,
-            &mut compiler_driver,
+            compiler_driver,
         )?;
 
         Self::clean(
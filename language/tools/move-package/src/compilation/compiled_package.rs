@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    compilation::package_layout::CompiledPackageLayout,
+    compilation::{
+        incremental_cache::{self, IncrementalCache},
+        package_layout::CompiledPackageLayout,
+    },
     resolution::resolution_graph::{Renaming, ResolvedGraph, ResolvedPackage, ResolvedTable},
     source_package::{
         layout::{SourcePackageLayout, REFERENCE_TEMPLATE_FILENAME},
@@ -28,7 +31,7 @@ use move_compiler::{
     compiled_unit::{
         self, AnnotatedCompiledUnit, CompiledUnit, NamedCompiledModule, NamedCompiledScript,
     },
-    diagnostics::FilesSourceText,
+    diagnostics::{Diagnostics, FilesSourceText},
     shared::{Flags, NamedAddressMap, NumericalAddress, PackagePaths},
     Compiler,
 };
@@ -56,6 +59,16 @@ pub struct CompiledUnitWithSource {
     pub source_path: PathBuf,
 }
 
+/// How the compiler is run over the source files of a package and its dependencies
+pub(crate) enum CompilerDriver<'a> {
+    /// Only the source files that changed since the last build, and the files affected by them,
+    /// are compiled. Errors are reported with the function, which returns the error to fail the
+    /// build with
+    Incremental(&'a dyn Fn(&FilesSourceText, Diagnostics) -> anyhow::Error),
+    /// All source files are compiled with the function
+    Custom(&'a mut dyn FnMut(Compiler) -> Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)>),
+}
+
 /// Represents meta information about a package and the information it was compiled with. Shared
 /// across both the `CompiledPackage` and `OnDiskCompiledPackage` structs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -298,25 +311,46 @@ impl OnDiskCompiledPackage {
     }
 
     fn get_compiled_units_paths(&self, package_name: Symbol) -> Result<Vec<String>> {
-        let package_dir = if self.package.compiled_package_info.package_name == package_name {
-            self.root_path.clone()
-        } else {
-            self.root_path
-                .join(CompiledPackageLayout::Dependencies.path())
-                .join(package_name.as_str())
-        };
+        let is_root_package = self.package.compiled_package_info.package_name == package_name;
         let mut compiled_unit_paths = vec![];
-        let module_path = package_dir.join(CompiledPackageLayout::CompiledModules.path());
-        if module_path.exists() {
-            compiled_unit_paths.push(module_path);
-        }
-        let script_path = package_dir.join(CompiledPackageLayout::CompiledScripts.path());
-        if script_path.exists() {
-            compiled_unit_paths.push(script_path);
+        for category in [
+            CompiledPackageLayout::CompiledModules,
+            CompiledPackageLayout::CompiledScripts,
+        ] {
+            // The units of dependencies are under a directory for each dependency, in the
+            // directory of the category
+            let category_dir = self.root_path.join(category.path());
+            let dir = if is_root_package {
+                category_dir
+            } else {
+                category_dir
+                    .join(CompiledPackageLayout::Dependencies.path())
+                    .join(package_name.as_str())
+            };
+            if dir.exists() {
+                compiled_unit_paths.push(dir);
+            }
         }
-        find_filenames(&compiled_unit_paths, |path| {
+        let dependencies_dirs = [
+            CompiledPackageLayout::CompiledModules,
+            CompiledPackageLayout::CompiledScripts,
+        ]
+        .map(|category| {
+            self.root_path
+                .join(category.path())
+                .join(CompiledPackageLayout::Dependencies.path())
+        });
+        Ok(find_filenames(&compiled_unit_paths, |path| {
             extension_equals(path, MOVE_COMPILED_EXTENSION)
+        })?
+        .into_iter()
+        .filter(|path| {
+            !is_root_package
+                || !dependencies_dirs
+                    .iter()
+                    .any(|dir| Path::new(path).starts_with(dir))
         })
+        .collect())
     }
 
     fn save_compiled_unit(
@@ -537,10 +571,7 @@ impl CompiledPackage {
             /* address mapping */ &ResolvedTable,
         )>,
         resolution_graph: &ResolvedGraph,
-        mut compiler_driver: impl FnMut(
-            Compiler,
-        )
-            -> Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)>,
+        compiler_driver: CompilerDriver,
     ) -> Result<CompiledPackage> {
         let immediate_dependencies = transitive_dependencies
             .iter()
@@ -570,29 +601,107 @@ impl CompiledPackage {
             &resolved_package,
             transitive_dependencies,
         )?;
-        let flags = if resolution_graph.build_options.test_mode {
+        let build_options = &resolution_graph.build_options;
+        let flags = if build_options.test_mode {
             Flags::testing()
         } else {
             Flags::empty()
-        };
-        // invoke the compiler
+        }
+        .set_lint(build_options.lint)
+        .set_diagnostics_format(build_options.diagnostics_format);
         let mut paths = deps_package_paths.clone();
         paths.push(sources_package_paths.clone());
+        let package_build_dir = project_root
+            .join(CompiledPackageLayout::Root.path())
+            .join(root_package_name.as_str());
 
-        let compiler = Compiler::from_package_paths(paths, vec![]).set_flags(flags);
-        let (file_map, all_compiled_units) = compiler_driver(compiler)?;
+        // invoke the compiler, on all files or only on the files that are not up to date with the
+        // last build
+        let mut previous_docs_and_abis = None;
+        let mut incremental_cache = None;
+        let all_compiled_units = match compiler_driver {
+            CompilerDriver::Incremental(report_errors) => {
+                let source_files = incremental_cache::source_files(&paths)?;
+                let doc_templates_digest =
+                    incremental_cache::doc_templates_digest(&resolved_package.package_path)?;
+                let cache = if build_options.force_recompilation {
+                    Err("recompilation forced")
+                } else {
+                    IncrementalCache::load(&package_build_dir).ok_or("no previous build")
+                };
+                if let Ok((cache, _)) = &cache {
+                    if cache.doc_templates_digest == doc_templates_digest
+                        && cache.source_files.keys().eq(source_files.keys())
+                    {
+                        previous_docs_and_abis = Some((
+                            read_artifacts(
+                                &package_build_dir.join(CompiledPackageLayout::CompiledDocs.path()),
+                                "md",
+                            )?,
+                            read_artifacts(
+                                &package_build_dir.join(CompiledPackageLayout::CompiledABIs.path()),
+                                "abi",
+                            )?,
+                        ));
+                    }
+                }
+                let package_roots = resolution_graph
+                    .package_table
+                    .iter()
+                    .map(|(name, package)| (*name, package.package_path.clone()))
+                    .collect();
+                let (units, compiled_files, recompiled) = incremental_cache::compile(
+                    w,
+                    build_options.verbose,
+                    cache,
+                    &paths,
+                    &source_files,
+                    &package_roots,
+                    &flags,
+                    report_errors,
+                )?;
+                if recompiled {
+                    previous_docs_and_abis = None;
+                }
+                incremental_cache = Some(IncrementalCache::new(
+                    &flags,
+                    &paths,
+                    doc_templates_digest,
+                    &source_files,
+                    &compiled_files,
+                    &units,
+                )?);
+                units
+            }
+            CompilerDriver::Custom(compiler_driver) => {
+                let compiler =
+                    Compiler::from_package_paths(paths.clone(), vec![]).set_flags(flags.clone());
+                let (file_map, all_compiled_units) = compiler_driver(compiler)?;
+                all_compiled_units
+                    .into_iter()
+                    .map(|annot_unit| {
+                        let source_path =
+                            PathBuf::from(file_map[&annot_unit.loc().file_hash()].0.as_str());
+                        let package_name = match &annot_unit {
+                            compiled_unit::CompiledUnitEnum::Module(m) => {
+                                m.named_module.package_name.unwrap()
+                            }
+                            compiled_unit::CompiledUnitEnum::Script(s) => {
+                                s.named_script.package_name.unwrap()
+                            }
+                        };
+                        let unit = CompiledUnitWithSource {
+                            unit: annot_unit.into_compiled_unit(),
+                            source_path,
+                        };
+                        (package_name, unit)
+                    })
+                    .collect()
+            }
+        };
         let mut root_compiled_units = vec![];
         let mut deps_compiled_units = vec![];
-        for annot_unit in all_compiled_units {
-            let source_path = PathBuf::from(file_map[&annot_unit.loc().file_hash()].0.as_str());
-            let package_name = match &annot_unit {
-                compiled_unit::CompiledUnitEnum::Module(m) => m.named_module.package_name.unwrap(),
-                compiled_unit::CompiledUnitEnum::Script(s) => s.named_script.package_name.unwrap(),
-            };
-            let unit = CompiledUnitWithSource {
-                unit: annot_unit.into_compiled_unit(),
-                source_path,
-            };
+        for (package_name, unit) in all_compiled_units {
             if package_name == root_package_name {
                 root_compiled_units.push(unit)
             } else {
@@ -600,10 +709,24 @@ impl CompiledPackage {
             }
         }
 
-        let mut compiled_docs = None;
-        let mut compiled_abis = None;
-        if resolution_graph.build_options.generate_docs
-            || resolution_graph.build_options.generate_abis
+        // reuse the docs and ABIs of the last build if none of the files changed
+        let (previous_docs, previous_abis) = previous_docs_and_abis.unwrap_or((None, None));
+        let mut compiled_docs = if build_options.generate_docs {
+            previous_docs.map(|docs| {
+                docs.into_iter()
+                    .map(|(name, contents)| (name, String::from_utf8_lossy(&contents).into_owned()))
+                    .collect()
+            })
+        } else {
+            None
+        };
+        let mut compiled_abis = if build_options.generate_abis {
+            previous_abis
+        } else {
+            None
+        };
+        if (build_options.generate_docs && compiled_docs.is_none())
+            || (build_options.generate_abis && compiled_abis.is_none())
         {
             let model = run_model_builder_with_options(
                 vec![sources_package_paths],
@@ -611,17 +734,17 @@ impl CompiledPackage {
                 ModelBuilderOptions::default(),
             )?;
 
-            if resolution_graph.build_options.generate_docs {
+            if build_options.generate_docs && compiled_docs.is_none() {
                 compiled_docs = Some(Self::build_docs(
                     resolved_package.source_package.package.name,
                     &model,
                     &resolved_package.package_path,
                     &immediate_dependencies,
-                    &resolution_graph.build_options.install_dir,
+                    &build_options.install_dir,
                 ));
             }
 
-            if resolution_graph.build_options.generate_abis {
+            if build_options.generate_abis && compiled_abis.is_none() {
                 compiled_abis = Some(Self::build_abis(
                     get_bytecode_version_from_env(),
                     &model,
@@ -643,7 +766,21 @@ impl CompiledPackage {
             compiled_abis,
         };
 
-        compiled_package.save_to_disk(project_root.join(CompiledPackageLayout::Root.path()))?;
+        let on_disk_package =
+            compiled_package.save_to_disk(project_root.join(CompiledPackageLayout::Root.path()))?;
+        // the cache of an incremental build would not describe the units compiled by a custom
+        // driver, so it is removed after such builds
+        let cache_path = on_disk_package
+            .root_path
+            .join(CompiledPackageLayout::IncrementalCache.path());
+        match incremental_cache {
+            Some(incremental_cache) => on_disk_package.save_under(
+                CompiledPackageLayout::IncrementalCache.path(),
+                serde_yaml::to_string(&incremental_cache)?.as_bytes(),
+            )?,
+            None if cache_path.exists() => std::fs::remove_file(&cache_path)?,
+            None => (),
+        }
 
         Ok(compiled_package)
     }
@@ -850,6 +987,20 @@ impl CompiledPackage {
     }
 }
 
+// Reads the files with `extension` under `dir`, by their path relative to `dir` without the
+// extension. Returns `None` if `dir` does not exist
+fn read_artifacts(dir: &Path, extension: &str) -> Result<Option<Vec<(String, Vec<u8>)>>> {
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let mut artifacts = vec![];
+    for path in find_filenames(&[dir], |path| extension_equals(path, extension))? {
+        let name = Path::new(&path).strip_prefix(dir)?.with_extension("");
+        artifacts.push((name.to_string_lossy().to_string(), std::fs::read(&path)?));
+    }
+    Ok(Some(artifacts))
+}
+
 pub(crate) fn named_address_mapping_for_compiler(
     resolution_table: &ResolvedTable,
 ) -> BTreeMap<Symbol, NumericalAddress> {
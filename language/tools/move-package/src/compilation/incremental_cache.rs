@@ -0,0 +1,1025 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Incremental compilation of a package and its dependencies. After every build, the units
+//! compiled from each source file are recorded in an `IncrementalCache` under the build directory
+//! of the package, along with the digest of the file, the interface digests of the modules the
+//! units depend on, the members of other files the file refers to, and the warnings reported for
+//! the file. The next build only recompiles the files whose source changed, then the files
+//! depending on a module whose interface changed as a result, and the files whose members gained
+//! or lost uses, as whether they are reported as never used depends on those. The files that are
+//! up to date are given to the compiler as dependencies, and their warnings are reported again.
+//!
+//! The cache is keyed on source files rather than on modules. The compiler takes whole files as
+//! targets or dependencies, so a module can only be recompiled along with the other modules of its
+//! file, and keying each module on the digest of its own source would not save any work. The
+//! digest of a file also covers what its modules share, such as address blocks, and the compiler
+//! reports warnings against files. Within a file, each unit keeps the interface digests of its own
+//! dependencies, so which units are affected by a change is still decided per module.
+
+use crate::{
+    compilation::{
+        compiled_package::{CompiledPackage, CompiledUnitWithSource, OnDiskCompiledPackage},
+        package_layout::CompiledPackageLayout,
+    },
+    source_package::{layout::SourcePackageLayout, parsed_manifest::PackageName},
+};
+use anyhow::Result;
+use colored::Colorize;
+use move_binary_format::{
+    access::{ModuleAccess, ScriptAccess},
+    file_format::Visibility,
+};
+use move_command_line_common::files::{extension_equals, find_filenames, FileHash};
+use move_compiler::{
+    compiled_unit::{CompiledUnit, CompiledUnitEnum},
+    diagnostics::{report_warnings_with_format, Diagnostics, FilesSourceText, SavedDiagnostic},
+    expansion::ast as E,
+    interface_generator::write_module_to_string,
+    shared::{
+        known_attributes::{DeprecationAttribute, KnownAttribute, SyntaxAttribute},
+        Flags, PackagePaths,
+    },
+    typing::program_uses,
+    Compiler, PASS_EXPANSION, PASS_TYPING,
+};
+use move_core_types::language_storage::ModuleId;
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// What the last build of a package compiled from each source file of the package and its
+/// dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalCache {
+    /// Whether the units were compiled in test mode
+    pub test_mode: bool,
+    /// Whether the linters were run over the units
+    pub lint: bool,
+    /// The named addresses each package was compiled with
+    pub named_addresses: BTreeMap<PackageName, BTreeMap<Symbol, String>>,
+    /// The digest of the documentation templates of the package
+    pub doc_templates_digest: String,
+    /// The source files, by path
+    pub source_files: BTreeMap<Symbol, CachedSourceFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSourceFile {
+    /// The package the file belongs to
+    pub package_name: PackageName,
+    /// The digest of the contents of the file
+    pub source_digest: String,
+    /// What the compiler found in the file
+    pub compilation: CompiledSourceFile,
+    /// The modules and scripts compiled from the file
+    pub units: Vec<CachedUnit>,
+}
+
+/// What the compiler found in a source file when compiling it, as far as deciding which other
+/// files are affected by its changes, and reporting its warnings again
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompiledSourceFile {
+    /// Whether the file declares inline or index functions
+    pub has_inlined_definitions: bool,
+    /// Whether the value of a constant of the file refers to a member of another module
+    pub has_constant_references: bool,
+    /// Whether the file declares deprecated items
+    pub has_deprecations: bool,
+    /// The structs and constants of the modules of other files that the file refers to, by the
+    /// path of the file declaring them. Whether those are reported as never used depends on it
+    pub member_uses: BTreeMap<Symbol, BTreeSet<String>>,
+    /// The warnings whose primary location is in the file
+    pub warnings: Vec<SavedDiagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedUnit {
+    pub name: Symbol,
+    pub is_module: bool,
+    /// The interface digests of the modules the unit depends on, by module id
+    pub dependencies: BTreeMap<String, String>,
+}
+
+/// A source file of the package or of one of its dependencies
+#[derive(Debug, Clone)]
+pub(crate) struct SourceFile {
+    package_name: PackageName,
+    digest: String,
+    file_hash: FileHash,
+    contents: String,
+}
+
+/// A compiled unit, along with what is needed to decide which units it affects
+struct UnitInfo {
+    path: Symbol,
+    package_name: PackageName,
+    unit: CompiledUnitWithSource,
+    /// The module id and interface digest of the unit, if it is a module
+    interface: Option<(String, String)>,
+    /// The ids of the modules the unit depends on
+    dependencies: Vec<String>,
+    /// Whether the unit is a module with friend functions
+    has_friend_functions: bool,
+    /// The ids of the modules declared as friends of the unit
+    friends: Vec<String>,
+}
+
+impl IncrementalCache {
+    /// Loads the cache of the last build of the package built under `package_build_dir`, along
+    /// with the units of that build
+    pub(crate) fn load(package_build_dir: &Path) -> Option<(Self, CompiledPackage)> {
+        let buf =
+            std::fs::read(package_build_dir.join(CompiledPackageLayout::IncrementalCache.path()))
+                .ok()?;
+        let cache = serde_yaml::from_slice::<Self>(&buf).ok()?;
+        let package = OnDiskCompiledPackage::from_path(package_build_dir)
+            .and_then(|on_disk| on_disk.into_compiled_package())
+            .ok()?;
+        Some((cache, package))
+    }
+
+    /// Records the units of a build, along with what the compiler found in each source file
+    pub(crate) fn new(
+        flags: &Flags,
+        packages: &[PackagePaths],
+        doc_templates_digest: String,
+        source_files: &BTreeMap<Symbol, SourceFile>,
+        compiled_files: &BTreeMap<Symbol, CompiledSourceFile>,
+        units: &[(PackageName, CompiledUnitWithSource)],
+    ) -> Result<Self> {
+        let units = units
+            .iter()
+            .map(|(package_name, unit)| UnitInfo::new(*package_name, unit.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let interfaces = interface_digests(&units);
+        let mut cached_files = source_files
+            .iter()
+            .map(|(path, file)| {
+                let cached = CachedSourceFile {
+                    package_name: file.package_name,
+                    source_digest: file.digest.clone(),
+                    compilation: compiled_files.get(path).cloned().unwrap_or_default(),
+                    units: vec![],
+                };
+                (*path, cached)
+            })
+            .collect::<BTreeMap<_, _>>();
+        for unit in &units {
+            let cached_file = match cached_files.get_mut(&unit.path) {
+                Some(cached_file) => cached_file,
+                None => continue,
+            };
+            cached_file.units.push(CachedUnit {
+                name: unit.unit.unit.name(),
+                is_module: unit.interface.is_some(),
+                dependencies: dependency_digests(unit, &interfaces),
+            });
+        }
+        Ok(Self {
+            test_mode: flags.is_testing(),
+            lint: flags.is_linting(),
+            named_addresses: packages
+                .iter()
+                .map(|package| (package.name.unwrap(), named_addresses(package)))
+                .collect(),
+            doc_templates_digest,
+            source_files: cached_files,
+        })
+    }
+
+    // Returns the cached units of the source file at `path`, along with the interface digests of
+    // their dependencies, or the reason they cannot be reused. The warnings of the file can only be
+    // reported again if the files they refer to are unchanged, as given by `file_hashes`
+    fn cached_units(
+        &self,
+        previous_units: &BTreeMap<(PackageName, bool, Symbol), CompiledUnitWithSource>,
+        path: Symbol,
+        file: &SourceFile,
+        named_addresses: &BTreeMap<Symbol, String>,
+        file_hashes: &BTreeSet<FileHash>,
+    ) -> std::result::Result<(Vec<CompiledUnitWithSource>, BTreeMap<String, String>), String> {
+        let cached = match self.source_files.get(&path) {
+            Some(cached) if cached.package_name == file.package_name => cached,
+            _ => return Err("new file".to_owned()),
+        };
+        if self.named_addresses.get(&file.package_name) != Some(named_addresses) {
+            return Err("named addresses changed".to_owned());
+        }
+        if cached.source_digest != file.digest {
+            return Err("source changed".to_owned());
+        }
+        for warning in &cached.compilation.warnings {
+            if warning.clone().restore().is_none() {
+                return Err("compiler diagnostics changed".to_owned());
+            }
+            if !warning
+                .labeled_locs()
+                .all(|loc| file_hashes.contains(&loc.file_hash()))
+            {
+                return Err("warnings refer to changed files".to_owned());
+            }
+        }
+        let mut units = vec![];
+        let mut dependencies = BTreeMap::new();
+        for cached_unit in &cached.units {
+            let key = (file.package_name, cached_unit.is_module, cached_unit.name);
+            let mut unit = match previous_units.get(&key) {
+                Some(unit) => unit.clone(),
+                None => return Err("compiled artifacts missing".to_owned()),
+            };
+            unit.source_path = PathBuf::from(path.as_str());
+            units.push(unit);
+            dependencies.extend(cached_unit.dependencies.clone());
+        }
+        Ok((units, dependencies))
+    }
+}
+
+impl UnitInfo {
+    fn new(package_name: PackageName, unit: CompiledUnitWithSource) -> Result<Self> {
+        let (interface, dependencies, has_friend_functions, friends) = match &unit.unit {
+            CompiledUnit::Module(named) => {
+                let (id, interface) =
+                    write_module_to_string(&BTreeMap::<ModuleId, String>::new(), &named.module)?;
+                let has_friend_functions = named
+                    .module
+                    .function_defs()
+                    .iter()
+                    .any(|fdef| fdef.visibility == Visibility::Friend);
+                (
+                    Some((id.to_string(), digest(interface.as_bytes()))),
+                    named.module.immediate_dependencies(),
+                    has_friend_functions,
+                    named.module.immediate_friends(),
+                )
+            }
+            CompiledUnit::Script(named) => {
+                let dependencies = named.script.immediate_dependencies();
+                (None, dependencies, false, vec![])
+            }
+        };
+        Ok(Self {
+            path: Symbol::from(unit.source_path.to_string_lossy().to_string()),
+            package_name,
+            unit,
+            interface,
+            dependencies: dependencies.iter().map(|id| id.to_string()).collect(),
+            has_friend_functions,
+            friends: friends.iter().map(|id| id.to_string()).collect(),
+        })
+    }
+}
+
+/// Reads the source files of `packages`
+pub(crate) fn source_files(packages: &[PackagePaths]) -> Result<BTreeMap<Symbol, SourceFile>> {
+    let mut source_files = BTreeMap::new();
+    for package in packages {
+        for path in &package.paths {
+            let contents = std::fs::read_to_string(path.as_str())?;
+            let file = SourceFile {
+                package_name: package.name.unwrap(),
+                digest: digest(contents.as_bytes()),
+                file_hash: FileHash::new(&contents),
+                contents,
+            };
+            source_files.insert(*path, file);
+        }
+    }
+    Ok(source_files)
+}
+
+/// The digest of the documentation templates of the package at `package_root`
+pub(crate) fn doc_templates_digest(package_root: &Path) -> Result<String> {
+    let templates_dir = package_root.join(SourcePackageLayout::DocTemplates.path());
+    let mut hasher = Sha256::new();
+    if templates_dir.is_dir() {
+        let mut templates = find_filenames(&[templates_dir], |path| extension_equals(path, "md"))?;
+        templates.sort();
+        for template in templates {
+            hasher.update(template.as_bytes());
+            hasher.update(digest(&std::fs::read(&template)?).as_bytes());
+        }
+    }
+    Ok(format!("{:X}", hasher.finalize()))
+}
+
+/// Compiles the units of `packages`, reusing the units in `cache` for the source files that are
+/// up to date. `cache` is either the last build or the reason it cannot be used. Errors are
+/// reported with `report_errors`, and the warnings of all source files are reported once the units
+/// are compiled. Returns the units of all source files, in dependency order, what the compiler
+/// found in each file, and whether any file was compiled.
+pub(crate) fn compile<W: Write>(
+    w: &mut W,
+    verbose: bool,
+    cache: std::result::Result<(IncrementalCache, CompiledPackage), &str>,
+    packages: &[PackagePaths],
+    source_files: &BTreeMap<Symbol, SourceFile>,
+    package_roots: &BTreeMap<PackageName, PathBuf>,
+    flags: &Flags,
+    report_errors: &dyn Fn(&FilesSourceText, Diagnostics) -> anyhow::Error,
+) -> Result<(
+    Vec<(PackageName, CompiledUnitWithSource)>,
+    BTreeMap<Symbol, CompiledSourceFile>,
+    bool,
+)> {
+    let package_named_addresses = packages
+        .iter()
+        .map(|package| (package.name.unwrap(), named_addresses(package)))
+        .collect::<BTreeMap<_, _>>();
+
+    let file_hashes = source_files
+        .values()
+        .map(|file| file.file_hash)
+        .collect::<BTreeSet<_>>();
+
+    // The units of the files that are up to date, and the interface digests of their dependencies
+    // when they were compiled
+    let mut units = vec![];
+    let mut up_to_date = BTreeMap::<Symbol, BTreeMap<String, String>>::new();
+    // The files to compile in the next round, with the reason they need to be compiled
+    let mut targets = BTreeMap::<Symbol, String>::new();
+    // The modules declared as friends in each file, as of its last compilation
+    let mut friends = BTreeMap::<Symbol, BTreeSet<String>>::new();
+    // What the compiler found in each file, as of its last compilation
+    let mut compiled_files = BTreeMap::<Symbol, CompiledSourceFile>::new();
+    // The files whose source changed since the last build, until they are compiled
+    let mut changed = BTreeSet::new();
+    // The members of other files each file refers to, as of its last compilation
+    let mut member_uses = BTreeMap::<Symbol, BTreeMap<Symbol, BTreeSet<String>>>::new();
+    match &cache {
+        Err(reason) => {
+            for path in source_files.keys() {
+                targets.insert(*path, reason.to_string());
+            }
+        }
+        Ok((cache, _))
+            if cache.test_mode != flags.is_testing() || cache.lint != flags.is_linting() =>
+        {
+            for path in source_files.keys() {
+                targets.insert(*path, "build flags changed".to_owned());
+            }
+        }
+        Ok((cache, previous)) => {
+            let previous_units = previous
+                .root_compiled_units
+                .iter()
+                .map(|unit| (previous.compiled_package_info.package_name, unit))
+                .chain(
+                    previous
+                        .deps_compiled_units
+                        .iter()
+                        .map(|(n, unit)| (*n, unit)),
+                )
+                .map(|(package_name, unit)| {
+                    let is_module = matches!(unit.unit, CompiledUnit::Module(_));
+                    ((package_name, is_module, unit.unit.name()), unit.clone())
+                })
+                .collect::<BTreeMap<_, _>>();
+            for (path, cached) in &cache.source_files {
+                member_uses.insert(*path, cached.compilation.member_uses.clone());
+                let file_friends = cached
+                    .units
+                    .iter()
+                    .filter_map(|unit| {
+                        previous_units.get(&(cached.package_name, unit.is_module, unit.name))
+                    })
+                    .filter_map(|unit| match &unit.unit {
+                        CompiledUnit::Module(named) => Some(named.module.immediate_friends()),
+                        CompiledUnit::Script(_) => None,
+                    })
+                    .flatten()
+                    .map(|id| id.to_string())
+                    .collect();
+                friends.insert(*path, file_friends);
+            }
+            for (path, file) in source_files {
+                let named_addresses = &package_named_addresses[&file.package_name];
+                match cache.cached_units(
+                    &previous_units,
+                    *path,
+                    file,
+                    named_addresses,
+                    &file_hashes,
+                ) {
+                    Ok((cached_units, dependencies)) => {
+                        for unit in cached_units {
+                            units.push(UnitInfo::new(file.package_name, unit)?);
+                        }
+                        up_to_date.insert(*path, dependencies);
+                        compiled_files.insert(*path, cache.source_files[path].compilation.clone());
+                    }
+                    Err(reason) => {
+                        targets.insert(*path, reason);
+                        changed.insert(*path);
+                    }
+                }
+            }
+
+            // The members of the files that are up to date are reported as never used depending on
+            // the uses of the other files, so the files whose members were used by removed files
+            // are recompiled
+            for (path, uses) in &member_uses {
+                if source_files.contains_key(path) {
+                    continue;
+                }
+                for declaring_path in uses.keys() {
+                    if up_to_date.remove(declaring_path).is_some() {
+                        targets.insert(*declaring_path, "uses of its members removed".to_owned());
+                    }
+                }
+            }
+
+            // The definitions of inline and index functions are compiled into the modules using
+            // them, without being part of the interface of the module declaring them. Likewise,
+            // deprecations are only reported when compiling the modules using deprecated items. So
+            // if the files that changed had some in their last compilation, every file is
+            // recompiled. What they have now is only known once they are compiled
+            let changed_definitions = cache
+                .source_files
+                .iter()
+                .filter(|(path, _)| !up_to_date.contains_key(*path))
+                .find_map(|(path, cached)| {
+                    let what = definitions_used_elsewhere(&cached.compilation)?;
+                    Some((*path, cached.package_name, what))
+                });
+            if let Some((changed, package_name, what)) = changed_definitions {
+                let reason = format!(
+                    "{} might have changed in {}",
+                    what,
+                    display_path(changed, package_name, package_roots)
+                );
+                for path in std::mem::take(&mut up_to_date).into_keys() {
+                    targets.insert(path, reason.clone());
+                }
+                units.clear();
+                compiled_files.clear();
+            }
+
+            // Calls to a 'public(package)' function make the caller a friend of the module
+            // declaring it. Since the changed files might call such functions, the modules with
+            // friend functions in the same packages are recompiled along with them
+            let changed_packages = targets
+                .keys()
+                .map(|path| source_files[path].package_name)
+                .collect::<BTreeSet<_>>();
+            let friend_declaring_files = units
+                .iter()
+                .filter(|unit| {
+                    unit.has_friend_functions && changed_packages.contains(&unit.package_name)
+                })
+                .map(|unit| unit.path)
+                .collect::<BTreeSet<_>>();
+            for path in friend_declaring_files {
+                up_to_date.remove(&path);
+                targets.insert(path, "friend functions might have new callers".to_owned());
+            }
+        }
+    }
+
+    let mut compiled = BTreeSet::new();
+    loop {
+        // Files depending on a module whose interface changed since they were compiled need to be
+        // recompiled. Modules that are about to be compiled are not known yet
+        let interfaces = interface_digests(&units);
+        let compiling = !targets.is_empty();
+        for (path, dependencies) in &up_to_date {
+            let changed =
+                dependencies
+                    .iter()
+                    .find(|(id, digest)| match interfaces.get(id.as_str()) {
+                        Some(current) => current != *digest,
+                        None => !compiling,
+                    });
+            if let Some((id, _)) = changed {
+                targets.insert(*path, format!("dependency '{}' changed", id));
+            }
+        }
+        if targets.is_empty() {
+            break;
+        }
+
+        // The values of constants are compiled into the modules declaring them, including the
+        // values of the constants of other modules they refer to. As those are not part of any
+        // interface, the files with such constants are recompiled along with any other file
+        let constant_files = up_to_date
+            .keys()
+            .filter(|path| compiled_files[*path].has_constant_references)
+            .copied()
+            .collect::<Vec<_>>();
+        for path in constant_files {
+            targets.insert(path, "constants might refer to changed modules".to_owned());
+        }
+
+        // The callers of a 'public(package)' function are only declared as friends of its module
+        // if they are compiled along with it, so the friends of recompiled modules are recompiled
+        loop {
+            let module_files = units
+                .iter()
+                .filter(|unit| up_to_date.contains_key(&unit.path))
+                .filter_map(|unit| Some((unit.interface.as_ref()?.0.as_str(), unit.path)))
+                .collect::<BTreeMap<_, _>>();
+            let friend_files = targets
+                .keys()
+                .filter_map(|path| friends.get(path))
+                .flatten()
+                .filter_map(|id| module_files.get(id.as_str()).copied())
+                .collect::<BTreeSet<_>>();
+            if friend_files.is_empty() {
+                break;
+            }
+            for path in friend_files {
+                up_to_date.remove(&path);
+                targets.insert(path, "friend of a recompiled module".to_owned());
+            }
+        }
+
+        for (path, reason) in &targets {
+            up_to_date.remove(path);
+            if verbose {
+                writeln!(
+                    w,
+                    "{} {} ({})",
+                    "CACHE MISS".bold().yellow(),
+                    display_path(*path, source_files[path].package_name, package_roots),
+                    reason
+                )?;
+            }
+        }
+        let round_targets = std::mem::take(&mut targets)
+            .into_keys()
+            .collect::<BTreeSet<_>>();
+        units.retain(|unit| !round_targets.contains(&unit.path));
+        let (compiled_units, mut round_files, round_warnings) =
+            compile_round(packages, &round_targets, flags, report_errors)?;
+        for path in &round_targets {
+            compiled_files.insert(*path, round_files.remove(path).unwrap_or_default());
+        }
+        for (path, warning) in round_warnings {
+            if let Some(file) = compiled_files.get_mut(&path) {
+                if !file.warnings.contains(&warning) {
+                    file.warnings.push(warning);
+                }
+            }
+        }
+        let mut dependencies = BTreeMap::<Symbol, BTreeMap<String, String>>::new();
+        let first_compiled = units.len();
+        for (package_name, unit) in compiled_units {
+            units.push(UnitInfo::new(package_name, unit)?);
+        }
+        for path in &round_targets {
+            friends.insert(*path, BTreeSet::new());
+        }
+        let interfaces = interface_digests(&units);
+        for unit in &units[first_compiled..] {
+            dependencies
+                .entry(unit.path)
+                .or_default()
+                .extend(dependency_digests(unit, &interfaces));
+            friends
+                .entry(unit.path)
+                .or_default()
+                .extend(unit.friends.iter().cloned());
+        }
+        for path in &round_targets {
+            up_to_date.insert(*path, dependencies.remove(path).unwrap_or_default());
+            compiled.insert(*path);
+        }
+
+        // The files whose members gained or lost uses from the files just compiled are recompiled,
+        // so that the members reported as never used are the same as in a full build
+        for path in &round_targets {
+            let new_uses = &compiled_files[path].member_uses;
+            let old_uses = member_uses
+                .insert(*path, new_uses.clone())
+                .unwrap_or_default();
+            for declaring_path in changed_member_uses(&old_uses, new_uses) {
+                if up_to_date.contains_key(&declaring_path)
+                    && !round_targets.contains(&declaring_path)
+                {
+                    let reason = format!(
+                        "uses of its members changed in {}",
+                        display_path(*path, source_files[path].package_name, package_roots)
+                    );
+                    targets.insert(declaring_path, reason);
+                }
+            }
+        }
+
+        // If the files that changed have inline functions or deprecations now, the files compiled
+        // before them are recompiled, as above
+        let changed_definitions = round_targets
+            .iter()
+            .filter(|path| changed.remove(*path))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .find_map(|path| Some((*path, definitions_used_elsewhere(&compiled_files[path])?)));
+        if let Some((changed_file, what)) = changed_definitions {
+            let package_name = source_files[&changed_file].package_name;
+            let reason = format!(
+                "{} might have changed in {}",
+                what,
+                display_path(changed_file, package_name, package_roots)
+            );
+            for path in up_to_date.keys() {
+                if !round_targets.contains(path) {
+                    targets.insert(*path, reason.clone());
+                }
+            }
+        }
+    }
+
+    if verbose {
+        for (path, file) in source_files {
+            if compiled.contains(path) {
+                continue;
+            }
+            writeln!(
+                w,
+                "{} {}",
+                "CACHE HIT".bold().green(),
+                display_path(*path, file.package_name, package_roots)
+            )?;
+        }
+    }
+
+    // The warnings of the files that are up to date are reported along with those of the files
+    // that were compiled, as they would be by a full build
+    let files = source_files
+        .iter()
+        .map(|(path, file)| (file.file_hash, (*path, file.contents.clone())))
+        .collect::<FilesSourceText>();
+    let warnings = compiled_files
+        .values()
+        .flat_map(|file| file.warnings.iter().cloned())
+        .filter_map(SavedDiagnostic::restore)
+        .collect::<Diagnostics>();
+    report_warnings_with_format(flags.diagnostics_format(), &files, warnings);
+    Ok((sort_units(units), compiled_files, !compiled.is_empty()))
+}
+
+// Compiles the `targets` files, with the other files of `packages` as dependencies. Returns the
+// compiled units, what the compiler found in each of the `targets` files, and the warnings along
+// with the file of their primary location
+fn compile_round(
+    packages: &[PackagePaths],
+    targets: &BTreeSet<Symbol>,
+    flags: &Flags,
+    report_errors: &dyn Fn(&FilesSourceText, Diagnostics) -> anyhow::Error,
+) -> Result<(
+    Vec<(PackageName, CompiledUnitWithSource)>,
+    BTreeMap<Symbol, CompiledSourceFile>,
+    Vec<(Symbol, SavedDiagnostic)>,
+)> {
+    let mut target_paths = vec![];
+    let mut dep_paths = vec![];
+    for package in packages {
+        let (package_targets, package_deps): (Vec<_>, Vec<_>) = package
+            .paths
+            .iter()
+            .copied()
+            .partition(|path| targets.contains(path));
+        for (paths, all_paths) in [
+            (package_targets, &mut target_paths),
+            (package_deps, &mut dep_paths),
+        ] {
+            if !paths.is_empty() {
+                all_paths.push(PackagePaths {
+                    name: package.name,
+                    paths,
+                    named_address_map: package.named_address_map.clone(),
+                })
+            }
+        }
+    }
+    let compiler = Compiler::from_package_paths(target_paths, dep_paths).set_flags(flags.clone());
+    let (file_map, res) = compiler.run::<PASS_EXPANSION>()?;
+    let res = res.and_then(|(_comments, stepped)| {
+        let (empty_compiler, expansion) = stepped.into_ast();
+        let mut compiled_files = compiled_source_files(&file_map, targets, &expansion);
+        // The uses of module members counted when reporting the members that are never used, by
+        // the file they are in. Only the names of the members used by the code filtered out of the
+        // compilation are known, so they stand for the members of that name in any module
+        let declared = declared_members(&file_map, &expansion);
+        let mut uses = vec![];
+        for (mident, mdef) in expansion.modules.key_cloned_iter() {
+            let file_hash = mident.loc.file_hash();
+            uses.extend(mdef.spec_uses.iter().map(|(m, n)| (file_hash, *m, n.value)));
+        }
+        let mut typing = empty_compiler
+            .at_expansion(expansion)
+            .run::<PASS_TYPING>()?;
+        for (loc, names) in typing.compilation_env().filtered_uses() {
+            for (mident, (_, members)) in &declared {
+                uses.extend(
+                    names
+                        .intersection(members)
+                        .map(|n| (loc.file_hash(), *mident, *n)),
+                );
+            }
+        }
+        let (empty_compiler, typing_ast) = typing.into_ast();
+        for (loc, module_uses) in program_uses(&typing_ast) {
+            uses.extend(
+                module_uses
+                    .into_iter()
+                    .map(|(m, n)| (loc.file_hash(), m, n)),
+            );
+        }
+        for (path, file_uses) in member_uses(&file_map, &declared, uses) {
+            if let Some(file) = compiled_files.get_mut(&path) {
+                file.member_uses = file_uses;
+            }
+        }
+        let (units, warnings) = empty_compiler.at_typing(typing_ast).build()?;
+        Ok((compiled_files, units, warnings))
+    });
+    let (compiled_files, mut compiled_units, warnings) = match res {
+        Ok(res) => res,
+        Err(error_diags) => return Err(report_errors(&file_map, error_diags)),
+    };
+    for unit in &mut compiled_units {
+        unit.build_line_table(&file_map);
+    }
+    let warnings = warnings
+        .into_vec()
+        .into_iter()
+        .map(|warning| {
+            (
+                file_map[&warning.primary_loc().file_hash()].0,
+                warning.save(),
+            )
+        })
+        .collect();
+    let units = compiled_units
+        .into_iter()
+        .map(|annot_unit| {
+            let source_path = PathBuf::from(file_map[&annot_unit.loc().file_hash()].0.as_str());
+            let package_name = match &annot_unit {
+                CompiledUnitEnum::Module(m) => m.named_module.package_name.unwrap(),
+                CompiledUnitEnum::Script(s) => s.named_script.package_name.unwrap(),
+            };
+            let unit = CompiledUnitWithSource {
+                unit: annot_unit.into_compiled_unit(),
+                source_path,
+            };
+            (package_name, unit)
+        })
+        .collect();
+    Ok((units, compiled_files, warnings))
+}
+
+// What the compiler found in each of the `targets` files, from the expansion AST of `program`
+fn compiled_source_files(
+    files: &FilesSourceText,
+    targets: &BTreeSet<Symbol>,
+    program: &E::Program,
+) -> BTreeMap<Symbol, CompiledSourceFile> {
+    let mut compiled_files = targets
+        .iter()
+        .map(|path| (*path, CompiledSourceFile::default()))
+        .collect::<BTreeMap<_, _>>();
+    for (mident, mdef) in program.modules.key_cloned_iter() {
+        let file = match compiled_files.get_mut(&files[&mdef.loc.file_hash()].0) {
+            Some(file) => file,
+            None => continue,
+        };
+        file.has_inlined_definitions |= mdef.functions.iter().any(|(_, _, f)| is_inlined(f));
+        file.has_constant_references |= mdef
+            .constants
+            .iter()
+            .any(|(_, _, c)| refers_to_other_modules(Some(&mident), &c.value));
+        file.has_deprecations |= mdef
+            .structs
+            .iter()
+            .map(|(_, _, s)| &s.attributes)
+            .chain(mdef.functions.iter().map(|(_, _, f)| &f.attributes))
+            .chain(mdef.constants.iter().map(|(_, _, c)| &c.attributes))
+            .any(is_deprecated);
+    }
+    for script in program.scripts.values() {
+        let file = match compiled_files.get_mut(&files[&script.loc.file_hash()].0) {
+            Some(file) => file,
+            None => continue,
+        };
+        file.has_inlined_definitions |= is_inlined(&script.function);
+        file.has_constant_references |= script
+            .constants
+            .iter()
+            .any(|(_, _, c)| refers_to_other_modules(None, &c.value));
+        file.has_deprecations |= std::iter::once(&script.function.attributes)
+            .chain(script.constants.iter().map(|(_, _, c)| &c.attributes))
+            .any(is_deprecated);
+    }
+    compiled_files
+}
+
+// The structs and constants of each module of `program`, along with the path of the file declaring
+// the module
+fn declared_members(
+    files: &FilesSourceText,
+    program: &E::Program,
+) -> BTreeMap<E::ModuleIdent, (Symbol, BTreeSet<Symbol>)> {
+    program
+        .modules
+        .key_cloned_iter()
+        .filter_map(|(mident, mdef)| {
+            let (path, _) = files.get(&mdef.loc.file_hash())?;
+            let members = mdef
+                .structs
+                .iter()
+                .map(|(_, s, _)| *s)
+                .chain(mdef.constants.iter().map(|(_, c, _)| *c))
+                .collect();
+            Some((mident, (*path, members)))
+        })
+        .collect()
+}
+
+// The structs and constants of the modules of other files that each file refers to, by the path of
+// the file declaring them, out of the `uses` of module members by the file they are in
+fn member_uses(
+    files: &FilesSourceText,
+    declared: &BTreeMap<E::ModuleIdent, (Symbol, BTreeSet<Symbol>)>,
+    uses: Vec<(FileHash, E::ModuleIdent, Symbol)>,
+) -> BTreeMap<Symbol, BTreeMap<Symbol, BTreeSet<String>>> {
+    let mut member_uses = BTreeMap::<Symbol, BTreeMap<Symbol, BTreeSet<String>>>::new();
+    for (file_hash, mident, name) in uses {
+        let path = match files.get(&file_hash) {
+            Some((path, _)) => *path,
+            None => continue,
+        };
+        match declared.get(&mident) {
+            Some((declaring_path, members))
+                if *declaring_path != path && members.contains(&name) =>
+            {
+                member_uses
+                    .entry(path)
+                    .or_default()
+                    .entry(*declaring_path)
+                    .or_default()
+                    .insert(format!("{}::{}", mident, name));
+            }
+            _ => (),
+        }
+    }
+    member_uses
+}
+
+// The files declaring the members whose uses by a file changed from `old` to `new`
+fn changed_member_uses<'a>(
+    old: &'a BTreeMap<Symbol, BTreeSet<String>>,
+    new: &'a BTreeMap<Symbol, BTreeSet<String>>,
+) -> impl Iterator<Item = Symbol> + 'a {
+    old.keys()
+        .chain(new.keys())
+        .filter(move |path| old.get(*path) != new.get(*path))
+        .copied()
+}
+
+// Which of the definitions of a file that changed are compiled into other files, if any
+fn definitions_used_elsewhere(file: &CompiledSourceFile) -> Option<&'static str> {
+    if file.has_inlined_definitions {
+        Some("inline functions")
+    } else if file.has_deprecations {
+        Some("deprecations")
+    } else {
+        None
+    }
+}
+
+// Returns true if the function is an inline or index function
+fn is_inlined(function: &E::Function) -> bool {
+    let syntax = E::AttributeName_::Known(KnownAttribute::Syntax(SyntaxAttribute::Syntax));
+    function.inline || function.attributes.contains_key_(&syntax)
+}
+
+fn is_deprecated(attributes: &E::Attributes) -> bool {
+    let deprecated = E::AttributeName_::Known(KnownAttribute::Deprecation(
+        DeprecationAttribute::Deprecated,
+    ));
+    attributes.contains_key_(&deprecated)
+}
+
+// Returns true if the value `e` of a constant of `module`, or of a script if it is `None`, refers
+// to a member of another module. Only the expressions supported in constants are visited
+fn refers_to_other_modules(module: Option<&E::ModuleIdent>, e: &E::Exp) -> bool {
+    use E::Exp_ as X;
+    match &e.value {
+        X::Name(access, _) => match &access.value {
+            E::ModuleAccess_::ModuleAccess(m, _) => Some(m) != module,
+            E::ModuleAccess_::Name(_) => false,
+        },
+        X::Block(seq) => seq.iter().any(|item| match &item.value {
+            E::SequenceItem_::Seq(e) => refers_to_other_modules(module, e),
+            E::SequenceItem_::Declare(_, _) | E::SequenceItem_::Bind(_, _) => false,
+        }),
+        X::UnaryExp(_, e) | X::Cast(e, _) | X::Annotate(e, _) => refers_to_other_modules(module, e),
+        X::BinopExp(e1, _, e2) => {
+            refers_to_other_modules(module, e1) || refers_to_other_modules(module, e2)
+        }
+        X::Vector(_, _, sp_es) => sp_es
+            .value
+            .iter()
+            .any(|e| refers_to_other_modules(module, e)),
+        X::ExpList(es) => es.iter().any(|e| refers_to_other_modules(module, e)),
+        _ => false,
+    }
+}
+
+// Orders the units so that modules come after the modules they depend on. Units that are already
+// in order keep their relative order
+fn sort_units(units: Vec<UnitInfo>) -> Vec<(PackageName, CompiledUnitWithSource)> {
+    fn visit(
+        idx: usize,
+        units: &[UnitInfo],
+        modules: &BTreeMap<&str, usize>,
+        visited: &mut [bool],
+        order: &mut Vec<usize>,
+    ) {
+        if visited[idx] {
+            return;
+        }
+        visited[idx] = true;
+        for dep in &units[idx].dependencies {
+            if let Some(dep_idx) = modules.get(dep.as_str()) {
+                visit(*dep_idx, units, modules, visited, order)
+            }
+        }
+        order.push(idx)
+    }
+
+    let modules = units
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, unit)| Some((unit.interface.as_ref()?.0.as_str(), idx)))
+        .collect::<BTreeMap<_, _>>();
+    let mut visited = vec![false; units.len()];
+    let mut order = vec![];
+    for idx in 0..units.len() {
+        visit(idx, &units, &modules, &mut visited, &mut order)
+    }
+    let mut units = units.into_iter().map(Some).collect::<Vec<_>>();
+    order
+        .into_iter()
+        .map(|idx| {
+            let unit = units[idx].take().unwrap();
+            (unit.package_name, unit.unit)
+        })
+        .collect()
+}
+
+// The interface digests of the modules among `units`, by module id
+fn interface_digests(units: &[UnitInfo]) -> BTreeMap<&str, &str> {
+    units
+        .iter()
+        .filter_map(|unit| {
+            let (id, digest) = unit.interface.as_ref()?;
+            Some((id.as_str(), digest.as_str()))
+        })
+        .collect()
+}
+
+// The interface digests of the dependencies of `unit`
+fn dependency_digests(
+    unit: &UnitInfo,
+    interfaces: &BTreeMap<&str, &str>,
+) -> BTreeMap<String, String> {
+    unit.dependencies
+        .iter()
+        .filter_map(|id| Some((id.clone(), interfaces.get(id.as_str())?.to_string())))
+        .collect()
+}
+
+fn named_addresses(package: &PackagePaths) -> BTreeMap<Symbol, String> {
+    package
+        .named_address_map
+        .iter()
+        .map(|(name, addr)| (*name, addr.to_string()))
+        .collect()
+}
+
+fn digest(bytes: &[u8]) -> String {
+    format!("{:X}", Sha256::digest(bytes))
+}
+
+// The path of a source file relative to the root of its package, prefixed by the package name
+fn display_path(
+    path: Symbol,
+    package_name: PackageName,
+    package_roots: &BTreeMap<PackageName, PathBuf>,
+) -> String {
+    let path = Path::new(path.as_str());
+    match package_roots
+        .get(&package_name)
+        .and_then(|root| path.strip_prefix(root).ok())
+    {
+        Some(relative) => format!("{}/{}", package_name, relative.display()),
+        None => path.display().to_string(),
+    }
+}
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 #[derive(Debug, Clone)]
 pub enum CompiledPackageLayout {
     BuildInfo,
+    IncrementalCache,
     Root,
     Dependencies,
     Sources,
@@ -21,6 +22,7 @@ impl CompiledPackageLayout {
     pub fn path(&self) -> &Path {
         let path = match self {
             Self::BuildInfo => "BuildInfo.yaml",
+            Self::IncrementalCache => "IncrementalCache.yaml",
             Self::Root => "build",
             Self::Dependencies => "dependencies",
             Self::Sources => "sources",
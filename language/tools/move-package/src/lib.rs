@@ -11,6 +11,7 @@ pub mod source_package;
 
 use anyhow::{bail, Result};
 use clap::*;
+use move_compiler::diagnostics::DiagnosticsFormat;
 use move_core_types::account_address::AccountAddress;
 use move_model::model::GlobalEnv;
 use serde::{Deserialize, Serialize};
@@ -112,6 +113,20 @@ pub struct BuildConfig {
     #[clap(name = "generate-abis", long = "abi", global = true)]
     pub generate_abis: bool,
 
+    /// Run the linters over the package and report their warnings
+    #[clap(name = "lint", long = "lint", global = true)]
+    pub lint: bool,
+
+    /// The format in which compiler diagnostics are reported
+    #[clap(
+        name = "diagnostics-format",
+        long = "diagnostics-format",
+        arg_enum,
+        default_value = "human",
+        global = true
+    )]
+    pub diagnostics_format: DiagnosticsFormat,
+
     /// Installation directory for compiled artifacts. Defaults to current directory.
     #[clap(long = "install-dir", parse(from_os_str), global = true)]
     pub install_dir: Option<PathBuf>,
@@ -134,6 +149,11 @@ pub struct BuildConfig {
     /// Skip fetching latest git dependencies
     #[clap(long = "skip-fetch-latest-git-deps", global = true)]
     pub skip_fetch_latest_git_deps: bool,
+
+    /// Report which source files are reused from the last build and which are recompiled
+    #[clap(skip)]
+    #[serde(skip)]
+    pub verbose: bool,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
@@ -0,0 +1,275 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{
+    compilation::{
+        compiled_package::CompiledPackage, incremental_cache::IncrementalCache,
+        package_layout::CompiledPackageLayout,
+    },
+    BuildConfig,
+};
+use std::{collections::BTreeMap, path::Path};
+use tempfile::tempdir;
+
+const MANIFEST: &str = r#"
+[package]
+name = "Test"
+version = "0.0.0"
+
+[addresses]
+test = "0x42"
+"#;
+
+fn write_source(root: &Path, name: &str, contents: &str) {
+    std::fs::write(root.join("sources").join(name), contents).unwrap()
+}
+
+// Builds the package at `root`, returning the bytecode of its modules and the source files that
+// were recompiled
+fn build(root: &Path, force_recompilation: bool) -> (BTreeMap<String, Vec<u8>>, Vec<String>) {
+    let mut output = Vec::new();
+    let package = BuildConfig {
+        force_recompilation,
+        verbose: true,
+        ..Default::default()
+    }
+    .compile_package(root, &mut output)
+    .unwrap();
+    let recompiled = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .filter(|line| line.contains("CACHE MISS"))
+        .map(|line| {
+            let path = line.split("Test/sources/").nth(1).unwrap();
+            path.split(' ').next().unwrap().to_owned()
+        })
+        .collect();
+    (bytecode(&package), recompiled)
+}
+
+// The number of warnings recorded in the incremental cache of the package at `root` for the source
+// file `name`
+fn cached_warnings(root: &Path, name: &str) -> usize {
+    let cache_path = root
+        .join(CompiledPackageLayout::Root.path())
+        .join("Test")
+        .join(CompiledPackageLayout::IncrementalCache.path());
+    let cache: IncrementalCache =
+        serde_yaml::from_slice(&std::fs::read(cache_path).unwrap()).unwrap();
+    let (_, file) = cache
+        .source_files
+        .iter()
+        .find(|(path, _)| path.as_str().ends_with(name))
+        .unwrap();
+    file.compilation.warnings.len()
+}
+
+fn bytecode(package: &CompiledPackage) -> BTreeMap<String, Vec<u8>> {
+    package
+        .root_compiled_units
+        .iter()
+        .map(|unit| (unit.unit.name().to_string(), unit.unit.serialize(None)))
+        .collect()
+}
+
+#[test]
+fn recompiles_changed_files_and_affected_dependents() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir(root.join("sources")).unwrap();
+    std::fs::write(root.join("Move.toml"), MANIFEST).unwrap();
+    write_source(
+        root,
+        "a.move",
+        "module test::a { public fun f(): u64 { 1 } }",
+    );
+    write_source(
+        root,
+        "b.move",
+        "module test::b { use test::a; public fun g(): u64 { a::f() + 1 } }",
+    );
+    write_source(
+        root,
+        "c.move",
+        "module test::c { public fun h(): u64 { 3 } }",
+    );
+
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["a.move", "b.move", "c.move"]);
+    assert!(root
+        .join(CompiledPackageLayout::Root.path())
+        .join("Test")
+        .join(CompiledPackageLayout::IncrementalCache.path())
+        .is_file());
+
+    // nothing changed
+    let (_, recompiled) = build(root, false);
+    assert!(recompiled.is_empty());
+
+    // the interface of `a` is the same, so `b` is up to date
+    write_source(
+        root,
+        "a.move",
+        "module test::a { public fun f(): u64 { 2 } }",
+    );
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["a.move"]);
+
+    // the interface of `a` changed, so `b` is recompiled
+    write_source(
+        root,
+        "a.move",
+        "module test::a { public fun f(): u64 { 2 } public fun f2(): u64 { 4 } }",
+    );
+    let (incremental, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["a.move", "b.move"]);
+
+    // a new dependency on a module that is up to date
+    write_source(
+        root,
+        "c.move",
+        "module test::c { use test::b; public fun h(): u64 { b::g() } }",
+    );
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["c.move"]);
+
+    let (full, recompiled) = build(root, true);
+    assert_eq!(recompiled, vec!["a.move", "b.move", "c.move"]);
+    assert_eq!(incremental["a"], full["a"]);
+    assert_eq!(incremental["b"], full["b"]);
+}
+
+#[test]
+fn recompiles_constants_referring_to_other_modules() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir(root.join("sources")).unwrap();
+    std::fs::write(root.join("Move.toml"), MANIFEST).unwrap();
+    write_source(
+        root,
+        "a.move",
+        "module test::a { const X: u64 = 1; public fun f(): u64 { X } }",
+    );
+    write_source(
+        root,
+        "b.move",
+        "module test::b { const Y: u64 = test::a::X + 1; public fun g(): u64 { Y } }",
+    );
+    write_source(
+        root,
+        "c.move",
+        "module test::c { const Z: u64 = 3; public fun h(): u64 { Z } }",
+    );
+    build(root, false);
+
+    // the value of `X` is not part of the interface of `a`, but `b` refers to it
+    write_source(
+        root,
+        "a.move",
+        "module test::a { const X: u64 = 2; public fun f(): u64 { X } }",
+    );
+    let (incremental, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["a.move", "b.move"]);
+
+    let (full, _) = build(root, true);
+    assert_eq!(incremental["b"], full["b"]);
+}
+
+#[test]
+fn recompiles_all_files_when_deprecations_change() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir(root.join("sources")).unwrap();
+    std::fs::write(root.join("Move.toml"), MANIFEST).unwrap();
+    write_source(
+        root,
+        "a.move",
+        "module test::a { public fun f(): u64 { 1 } }",
+    );
+    write_source(
+        root,
+        "b.move",
+        "module test::b { use test::a; public fun g(): u64 { a::f() } }",
+    );
+    build(root, false);
+
+    // the interface of `a` is the same, but its uses in `b` are now warned about
+    write_source(
+        root,
+        "a.move",
+        "module test::a { #[deprecated] public fun f(): u64 { 1 } }",
+    );
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["a.move", "b.move"]);
+}
+
+#[test]
+fn keeps_warnings_of_up_to_date_files() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir(root.join("sources")).unwrap();
+    std::fs::write(root.join("Move.toml"), MANIFEST).unwrap();
+    write_source(
+        root,
+        "a.move",
+        "module test::a { public fun f(x: u64): u64 { 1 } }",
+    );
+    write_source(
+        root,
+        "b.move",
+        "module test::b { public fun g(): u64 { 2 } }",
+    );
+    build(root, false);
+
+    write_source(
+        root,
+        "b.move",
+        "module test::b { public fun g(): u64 { 3 } }",
+    );
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["b.move"]);
+
+    // the unused parameter of `a` is reported again from the cache
+    assert_eq!(cached_warnings(root, "a.move"), 1);
+    assert_eq!(cached_warnings(root, "b.move"), 0);
+}
+
+#[test]
+fn recompiles_files_whose_members_gained_or_lost_uses() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir(root.join("sources")).unwrap();
+    std::fs::write(root.join("Move.toml"), MANIFEST).unwrap();
+    write_source(root, "a.move", "module test::a { struct S has drop {} }");
+    write_source(
+        root,
+        "b.move",
+        "module test::b { public fun g(_s: test::a::S) {} }",
+    );
+    build(root, false);
+    assert_eq!(cached_warnings(root, "a.move"), 0);
+
+    // `S` is reported as never used once `b` stops using it, as in a full build
+    write_source(root, "b.move", "module test::b { public fun g() {} }");
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["b.move", "a.move"]);
+    assert_eq!(cached_warnings(root, "a.move"), 1);
+
+    write_source(
+        root,
+        "b.move",
+        "module test::b { public fun g(_s: test::a::S) {} }",
+    );
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["b.move", "a.move"]);
+    assert_eq!(cached_warnings(root, "a.move"), 0);
+
+    // changes that keep the uses of `S` do not recompile `a`
+    write_source(
+        root,
+        "b.move",
+        "module test::b { public fun g(_s: test::a::S): u64 { 1 } }",
+    );
+    let (_, recompiled) = build(root, false);
+    assert_eq!(recompiled, vec!["b.move"]);
+}
@@ -50,6 +50,8 @@ pub(crate) struct FatStructType {
     pub abilities: WrappedAbilitySet,
     pub ty_args: Vec<FatType>,
     pub layout: Vec<FatType>,
+    // Only non-empty for enums, in which case `layout` is empty.
+    pub variants: Vec<Vec<FatType>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,6 +90,16 @@ impl FatStructType {
                 .iter()
                 .map(|ty| ty.subst(ty_args))
                 .collect::<PartialVMResult<_>>()?,
+            variants: self
+                .variants
+                .iter()
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .map(|ty| ty.subst(ty_args))
+                        .collect::<PartialVMResult<_>>()
+                })
+                .collect::<PartialVMResult<_>>()?,
         })
     }
 
@@ -176,6 +188,19 @@ impl TryInto<MoveStructLayout> for &FatStructType {
     type Error = PartialVMError;
 
     fn try_into(self) -> Result<MoveStructLayout, Self::Error> {
+        if !self.variants.is_empty() {
+            return Ok(MoveStructLayout::RuntimeVariants(
+                self.variants
+                    .iter()
+                    .map(|fields| {
+                        fields
+                            .iter()
+                            .map(|ty| ty.try_into())
+                            .collect::<PartialVMResult<Vec<_>>>()
+                    })
+                    .collect::<PartialVMResult<Vec<_>>>()?,
+            ));
+        }
         Ok(MoveStructLayout::new(
             self.layout
                 .iter()
@@ -199,12 +224,7 @@ impl TryInto<MoveTypeLayout> for &FatType {
             FatType::U256 => MoveTypeLayout::U256,
             FatType::Bool => MoveTypeLayout::Bool,
             FatType::Vector(v) => MoveTypeLayout::Vector(Box::new(v.as_ref().try_into()?)),
-            FatType::Struct(s) => MoveTypeLayout::Struct(MoveStructLayout::new(
-                s.layout
-                    .iter()
-                    .map(|ty| ty.try_into())
-                    .collect::<PartialVMResult<Vec<_>>>()?,
-            )),
+            FatType::Struct(s) => MoveTypeLayout::Struct(s.as_ref().try_into()?),
             FatType::Signer => MoveTypeLayout::Signer,
             FatType::Reference(_) | FatType::MutableReference(_) | FatType::TyParam(_) => {
                 return Err(PartialVMError::new(StatusCode::ABORT_TYPE_MISMATCH_ERROR))
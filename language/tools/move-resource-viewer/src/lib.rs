@@ -37,6 +37,8 @@ mod resolver;
 pub struct AnnotatedMoveStruct {
     pub abilities: AbilitySet,
     pub type_: StructTag,
+    /// The name of the variant, if the value is an enum.
+    pub variant: Option<Identifier>,
     pub value: Vec<(Identifier, AnnotatedMoveValue)>,
 }
 
@@ -171,7 +173,16 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
                 .into_iter()
                 .zip(runtime.into_iter())
                 .collect(),
-            MoveStruct::WithFields(fields) | MoveStruct::WithTypes { fields, .. } => fields,
+            MoveStruct::RuntimeVariant(variant, runtime) => self
+                .cache
+                .get_variant_field_names(&ty, variant)?
+                .1
+                .into_iter()
+                .zip(runtime.into_iter())
+                .collect(),
+            MoveStruct::WithFields(fields)
+            | MoveStruct::WithTypes { fields, .. }
+            | MoveStruct::WithVariantFields(_, _, fields) => fields,
         })
     }
 
@@ -194,14 +205,25 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
         let struct_tag = ty
             .struct_tag()
             .map_err(|e| e.finish(Location::Undefined).into_vm_status())?;
-        let field_names = self.cache.get_field_names(ty)?;
+        let (variant, field_names, field_tys) = match move_struct {
+            MoveStruct::RuntimeVariant(tag, _) => {
+                let (variant, field_names) = self.cache.get_variant_field_names(ty, *tag)?;
+                let field_tys = ty
+                    .variants
+                    .get(*tag as usize)
+                    .ok_or_else(|| anyhow!("Unexpected Variant {} of {}", tag, ty.name))?;
+                (Some(variant), field_names, field_tys)
+            }
+            _ => (None, self.cache.get_field_names(ty)?, &ty.layout),
+        };
         let mut annotated_fields = vec![];
-        for (ty, v) in ty.layout.iter().zip(move_struct.fields().iter()) {
+        for (ty, v) in field_tys.iter().zip(move_struct.fields().iter()) {
             annotated_fields.push(self.annotate_value(v, ty)?);
         }
         Ok(AnnotatedMoveStruct {
             abilities: ty.abilities.0,
             type_: struct_tag,
+            variant,
             value: field_names
                 .into_iter()
                 .zip(annotated_fields.into_iter())
@@ -305,7 +327,10 @@ fn pretty_print_struct(
     indent: u64,
 ) -> std::fmt::Result {
     pretty_print_ability_modifiers(f, value.abilities)?;
-    writeln!(f, "{} {{", value.type_)?;
+    match &value.variant {
+        Some(variant) => writeln!(f, "{}::{} {{", value.type_, variant)?,
+        None => writeln!(f, "{} {{", value.type_)?,
+    }
     for (field_name, v) in value.value.iter() {
         write_indent(f, indent + 4)?;
         write!(f, "{}: ", field_name)?;
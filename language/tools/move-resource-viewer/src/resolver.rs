@@ -130,6 +130,7 @@ impl<'a, T: MoveResolver + ?Sized> Resolver<'a, T> {
 
         match &struct_def.field_information {
             StructFieldInformation::Native => Err(anyhow!("Unexpected Native Struct")),
+            StructFieldInformation::DeclaredVariants(_) => Err(anyhow!("Unexpected Enum")),
             StructFieldInformation::Declared(defs) => Ok(defs
                 .iter()
                 .map(|field_def| module.identifier_at(field_def.name).to_owned())
@@ -137,6 +138,28 @@ impl<'a, T: MoveResolver + ?Sized> Resolver<'a, T> {
         }
     }
 
+    pub fn get_variant_field_names(
+        &self,
+        ty: &FatStructType,
+        variant: u16,
+    ) -> Result<(Identifier, Vec<Identifier>)> {
+        let module = self.get_module(&ty.address, ty.module.as_ident_str())?;
+        let struct_def_idx = find_struct_def_in_module(module.clone(), ty.name.as_ident_str())?;
+        let struct_def = module.struct_def_at(struct_def_idx);
+
+        let variant_def = struct_def
+            .variant(variant)
+            .ok_or_else(|| anyhow!("Unexpected Variant {} of {}", variant, ty.name))?;
+        Ok((
+            module.identifier_at(variant_def.name).to_owned(),
+            variant_def
+                .fields
+                .iter()
+                .map(|field_def| module.identifier_at(field_def.name).to_owned())
+                .collect(),
+        ))
+    }
+
     fn resolve_signature(
         &self,
         module: Rc<CompiledModule>,
@@ -225,6 +248,27 @@ impl<'a, T: MoveResolver + ?Sized> Resolver<'a, T> {
                     .iter()
                     .map(|field_def| self.resolve_signature(module.clone(), &field_def.signature.0))
                     .collect::<Result<_>>()?,
+                variants: vec![],
+            }),
+            StructFieldInformation::DeclaredVariants(variants) => Ok(FatStructType {
+                address,
+                module: module_name,
+                name,
+                abilities: WrappedAbilitySet(abilities),
+                ty_args,
+                layout: vec![],
+                variants: variants
+                    .iter()
+                    .map(|variant| {
+                        variant
+                            .fields
+                            .iter()
+                            .map(|field_def| {
+                                self.resolve_signature(module.clone(), &field_def.signature.0)
+                            })
+                            .collect::<Result<_>>()
+                    })
+                    .collect::<Result<_>>()?,
             }),
         }
     }
@@ -22,8 +22,10 @@ use move_vm_runtime::native_functions::NativeFunctionTable;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     collections::BTreeMap,
+    fs::OpenOptions,
     io::{Result, Write},
     marker::Send,
+    path::PathBuf,
     sync::Mutex,
 };
 
@@ -115,6 +117,15 @@ pub struct UnitTestingConfig {
     #[clap(short = 'v', long = "verbose")]
     pub report_writeset: bool,
 
+    /// Append a trace of the execution of the tests to this file, as JSON lines
+    #[clap(long = "trace")]
+    pub trace_file: Option<String>,
+
+    /// Profile the gas used by the tests, writing the folded stacks of the profile and its
+    /// reports to this directory
+    #[clap(long = "profile-gas")]
+    pub gas_profile_dir: Option<String>,
+
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
     #[cfg(feature = "evm-backend")]
@@ -148,6 +159,8 @@ impl UnitTestingConfig {
             list: false,
             named_address_values: vec![],
             report_writeset: false,
+            trace_file: None,
+            gas_profile_dir: None,
 
             #[cfg(feature = "evm-backend")]
             evm: false,
@@ -261,6 +274,18 @@ impl UnitTestingConfig {
             test_runner.filter(filter_str)
         }
 
+        if let Some(trace_file) = &self.trace_file {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(trace_file)?;
+            test_runner.trace_to(file)
+        }
+
+        if let Some(gas_profile_dir) = &self.gas_profile_dir {
+            test_runner.profile_gas_to(PathBuf::from(gas_profile_dir))
+        }
+
         let test_results = test_runner.run(&shared_writer).unwrap();
         if self.report_statistics {
             test_results.report_statistics(&shared_writer)?;
@@ -3,22 +3,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::format_module_id;
-use codespan_reporting::files::{Files, SimpleFiles};
 use colored::{control, Colorize};
 use move_binary_format::{
     access::ModuleAccess,
     errors::{ExecutionState, Location, VMError, VMResult},
 };
-use move_command_line_common::files::FileHash;
+use move_bytecode_source_map::line_table::LineTable;
 use move_compiler::{
     diagnostics::{self, Diagnostic, Diagnostics},
     unit_test::{ModuleTestPlan, TestName, TestPlan},
 };
-use move_core_types::{effects::ChangeSet, language_storage::ModuleId, vm_status::StatusType};
+use move_core_types::{
+    effects::ChangeSet,
+    language_storage::ModuleId,
+    vm_status::{StatusCode, StatusType},
+};
 use move_ir_types::location::Loc;
-use move_symbol_pool::Symbol;
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet},
     io::{Result, Write},
     sync::Mutex,
     time::Duration,
@@ -251,31 +253,11 @@ impl TestFailure {
         }
     }
 
-    fn get_line_number(
-        loc: &Loc,
-        files: &SimpleFiles<Symbol, &str>,
-        file_mapping: &HashMap<FileHash, usize>,
-    ) -> String {
-        Self::get_line_number_internal(loc, files, file_mapping)
-            .unwrap_or_else(|_| "no_source_line".to_string())
-    }
-
-    fn get_line_number_internal(
-        loc: &Loc,
-        files: &SimpleFiles<Symbol, &str>,
-        file_mapping: &HashMap<FileHash, usize>,
-    ) -> std::result::Result<String, codespan_reporting::files::Error> {
-        let id = file_mapping
-            .get(&loc.file_hash())
-            .ok_or(codespan_reporting::files::Error::FileMissing)?;
-        let start_line_index = files.line_index(*id, loc.start() as usize)?;
-        let start_line_number = files.line_number(*id, start_line_index)?;
-        let end_line_index = files.line_index(*id, loc.end() as usize)?;
-        let end_line_number = files.line_number(*id, end_line_index)?;
-        if start_line_number == end_line_number {
-            Ok(start_line_number.to_string())
-        } else {
-            Ok(format!("{}-{}", start_line_number, end_line_number))
+    fn get_line_number(loc: &Loc, line_table: &LineTable) -> String {
+        match line_table.range(*loc) {
+            Some(range) if range.start.line == range.end.line => (range.start.line + 1).to_string(),
+            Some(range) => format!("{}-{}", range.start.line + 1, range.end.line + 1),
+            None => "no_source_line".to_string(),
         }
     }
 
@@ -284,13 +266,6 @@ impl TestFailure {
         let mut buf = String::new();
         if !stack_trace.is_empty() {
             buf.push_str("stack trace\n");
-            let mut files = SimpleFiles::new();
-            let mut file_mapping = HashMap::new();
-            for (fhash, (fname, source)) in &test_plan.files {
-                let id = files.add(*fname, source.as_str());
-                file_mapping.insert(*fhash, id);
-            }
-
             for frame in stack_trace {
                 let module_id = match &frame.0 {
                     Some(v) => v,
@@ -310,17 +285,17 @@ impl TestFailure {
                 let fn_handle_idx = named_module.module.function_def_at(frame.1).function;
                 let fn_id_idx = named_module.module.function_handle_at(fn_handle_idx).name;
                 let fn_name = named_module.module.identifier_at(fn_id_idx).as_str();
-                let file_name = match test_plan.files.get(&loc.file_hash()) {
-                    Some(v) => format!("{}", v.0),
-                    None => "unknown_source".to_string(),
-                };
+                let line_table = named_module.source_map.line_table();
+                let file_name = line_table
+                    .file_path(loc.file_hash())
+                    .unwrap_or("unknown_source");
                 buf.push_str(
                     &format!(
                         "\t{}::{}({}:{})\n",
                         module_id.name(),
                         fn_name,
                         file_name,
-                        Self::get_line_number(&loc, &files, &file_mapping)
+                        Self::get_line_number(&loc, line_table)
                     )
                     .to_string(),
                 );
@@ -348,20 +323,23 @@ impl TestFailure {
         let diags = match vm_error.location() {
             Location::Module(module_id) => {
                 let diag_opt = vm_error.offsets().first().and_then(|(fdef_idx, offset)| {
-                    let function_source_map = test_plan
-                        .module_info
-                        .get(module_id)?
-                        .source_map
-                        .get_function_source_map(*fdef_idx)
-                        .ok()?;
+                    let source_map = &test_plan.module_info.get(module_id)?.source_map;
+                    let function_source_map = source_map.get_function_source_map(*fdef_idx).ok()?;
                     let loc = function_source_map.get_code_location(*offset).unwrap();
                     let msg = format!("In this function in {}", format_module_id(module_id));
+                    // the message of the assertion raising the abort, if any
+                    let assertion_message = match (vm_error.major_status(), vm_error.sub_status()) {
+                        (StatusCode::ABORTED, Some(code)) => source_map
+                            .get_assertion_source_map(code)
+                            .map(|assertion| format!("Assertion failed: {}", assertion.message)),
+                        _ => None,
+                    };
                     // TODO(tzakian) maybe migrate off of move-langs diagnostics?
                     Some(Diagnostic::new(
                         diagnostics::codes::Tests::TestFailed,
                         (loc, base_message.clone()),
                         vec![(function_source_map.definition_location, msg)],
-                        std::iter::empty::<String>(),
+                        assertion_message,
                     ))
                 });
                 match diag_opt {
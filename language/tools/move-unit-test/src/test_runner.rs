@@ -38,13 +38,21 @@ use move_stackless_bytecode_interpreter::{
     shared::bridge::{adapt_move_vm_change_set, adapt_move_vm_result},
     StacklessBytecodeInterpreter,
 };
-use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
+use move_vm_runtime::{
+    gas_profiler::{GasProfile, GasProfiler},
+    move_vm::MoveVM,
+    native_functions::NativeFunctionTable,
+    tracer::{write_trace, TraceFormat, TraceRecorder},
+};
 use move_vm_test_utils::{
     gas_schedule::{zero_cost_schedule, CostTable, Gas, GasCost, GasStatus},
     InMemoryStorage,
 };
 use rayon::prelude::*;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    cell::RefCell, collections::BTreeMap, fs::File, io::Write, marker::Send, path::PathBuf, rc::Rc,
+    sync::Mutex, time::Instant,
+};
 
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 #[cfg(feature = "evm-backend")]
@@ -70,6 +78,8 @@ pub struct SharedTestingConfig {
     check_stackless_vm: bool,
     verbose: bool,
     record_writeset: bool,
+    trace_file: Option<Mutex<File>>,
+    gas_profile: Option<Mutex<GasProfile>>,
 
     #[cfg(feature = "evm-backend")]
     evm: bool,
@@ -79,6 +89,7 @@ pub struct TestRunner {
     num_threads: usize,
     testing_config: SharedTestingConfig,
     tests: TestPlan,
+    gas_profile_dir: Option<PathBuf>,
 }
 
 /// A gas schedule where every instruction has a cost of "1". This is used to bound execution of a
@@ -186,11 +197,14 @@ impl TestRunner {
                 verbose,
                 named_address_values,
                 record_writeset,
+                trace_file: None,
+                gas_profile: None,
                 #[cfg(feature = "evm-backend")]
                 evm,
             },
             num_threads,
             tests,
+            gas_profile_dir: None,
         })
     }
 
@@ -207,10 +221,27 @@ impl TestRunner {
                     .map(|(_, test_plan)| self.testing_config.exec_module_tests(test_plan, writer))
                     .reduce(TestStatistics::new, |acc, stats| acc.combine(stats));
 
+                if let (Some(gas_profile), Some(gas_profile_dir)) =
+                    (&self.testing_config.gas_profile, &self.gas_profile_dir)
+                {
+                    gas_profile.lock().unwrap().save(gas_profile_dir)?;
+                }
+
                 Ok(TestResults::new(final_statistics, self.tests))
             })
     }
 
+    /// Appends the traces of the executions of the tests to `file`
+    pub fn trace_to(&mut self, file: File) {
+        self.testing_config.trace_file = Some(Mutex::new(file))
+    }
+
+    /// Profiles the gas used by all the tests together, saving the profile in `dir`
+    pub fn profile_gas_to(&mut self, dir: PathBuf) {
+        self.testing_config.gas_profile = Some(Mutex::new(GasProfile::new()));
+        self.gas_profile_dir = Some(dir);
+    }
+
     pub fn filter(&mut self, test_name_slice: &str) {
         for (module_id, module_test) in self.tests.module_tests.iter_mut() {
             if module_id.name().as_str().contains(test_name_slice) {
@@ -287,17 +318,41 @@ impl SharedTestingConfig {
         let extensions = extensions::new_extensions();
         let mut session =
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
-        let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
+        let gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
+        let trace_recorder = Rc::new(RefCell::new(TraceRecorder::new()));
+        if self.trace_file.is_some() {
+            session.set_tracer(trace_recorder.clone());
+        }
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
 
         let now = Instant::now();
-        let serialized_return_values_result = session.execute_function_bypass_visibility(
-            &test_plan.module_id,
-            IdentStr::new(function_name).unwrap(),
-            vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
-            &mut gas_meter,
-        );
+        let (serialized_return_values_result, gas_meter) = match &self.gas_profile {
+            None => {
+                let mut gas_meter = gas_meter;
+                let res = session.execute_function_bypass_visibility(
+                    &test_plan.module_id,
+                    IdentStr::new(function_name).unwrap(),
+                    vec![], // no ty args, at least for now
+                    serialize_values(test_info.arguments.iter()),
+                    &mut gas_meter,
+                );
+                (res, gas_meter)
+            }
+            Some(gas_profile) => {
+                let mut gas_profiler =
+                    GasProfiler::new_function(gas_meter, &test_plan.module_id, function_name);
+                let res = session.execute_function_bypass_visibility(
+                    &test_plan.module_id,
+                    IdentStr::new(function_name).unwrap(),
+                    vec![],
+                    serialize_values(test_info.arguments.iter()),
+                    &mut gas_profiler,
+                );
+                let (gas_meter, profile) = gas_profiler.finish();
+                gas_profile.lock().unwrap().merge(&profile);
+                (res, gas_meter)
+            }
+        };
         let mut return_result = serialized_return_values_result.map(|res| {
             res.return_values
                 .into_iter()
@@ -319,10 +374,19 @@ impl SharedTestingConfig {
                 .unwrap()
                 .into(),
         );
-        match session.finish_with_extensions() {
+        let result = match session.finish_with_extensions() {
             Ok((cs, _, extensions)) => (Ok(cs), Ok(extensions), return_result, test_run_info),
             Err(err) => (Err(err.clone()), Err(err), return_result, test_run_info),
+        };
+        if let Some(trace_file) = &self.trace_file {
+            write_trace(
+                &mut *trace_file.lock().unwrap(),
+                trace_recorder.borrow().events(),
+                TraceFormat::Json,
+            )
+            .expect("Unable to write the trace of a test");
         }
+        result
     }
 
     fn execute_via_stackless_vm(
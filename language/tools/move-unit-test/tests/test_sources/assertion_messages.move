@@ -0,0 +1,34 @@
+address 0x1 {
+module M {
+    #[test]
+    fun assert_with_message() {
+        let x = 1;
+        assert!(x + 1 == 3, b"x + 1 must be 3");
+    }
+
+    #[test]
+    fun assert_eq_default_message() {
+        let x = 1;
+        assert_eq!(x, 2);
+    }
+
+    #[test]
+    fun assert_ne_with_message() {
+        let x = 1;
+        assert_ne!(x, 1, b"x must not be 1");
+    }
+
+    #[test]
+    #[expected_failure(abort_code=42, location=Self)]
+    fun assert_eq_with_code() {
+        let x = 1;
+        assert_eq!(x, 2, 42);
+    }
+
+    #[test]
+    fun assert_eq_succeeds() {
+        assert_eq!(vector[1, 2], vector[1, 2], b"vectors differ");
+        assert_ne!(vector[1, 2], vector[2, 1]);
+    }
+}
+}